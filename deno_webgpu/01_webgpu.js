@@ -758,6 +758,25 @@ class InnerGPUDevice {
     this.errorScopeStack = [];
   }
 
+  /**
+   * Resolve the lost promise with the reason reported by wgpu, if any.
+   */
+  lose() {
+    if (this.isLost) {
+      return;
+    }
+    this.isLost = true;
+    const info = this.rid !== undefined
+      ? ops.op_webgpu_device_lost_info(this.rid)
+      : null;
+    this.resolveLost(
+      createGPUDeviceLostInfo(
+        info?.reason,
+        info?.message ?? "device was lost",
+      ),
+    );
+  }
+
   /** @param {any} resource */
   trackResource(resource) {
     ArrayPrototypePush(this.resources, new WeakRef(resource));
@@ -774,10 +793,7 @@ class InnerGPUDevice {
       if (err) {
         switch (err.type) {
           case "lost":
-            this.isLost = true;
-            this.resolveLost(
-              createGPUDeviceLostInfo(undefined, "device was lost"),
-            );
+            this.lose();
             break;
           case "validation":
             return PromiseReject(
@@ -898,6 +914,11 @@ class GPUDevice extends EventTarget {
 
   destroy() {
     webidl.assertBranded(this, GPUDevicePrototype);
+    const device = this[_device];
+    if (device.rid !== undefined) {
+      ops.op_webgpu_device_destroy(device.rid);
+      device.lose();
+    }
     this[_cleanup]();
   }
 
@@ -58,7 +58,10 @@ pub fn op_webgpu_create_command_encoder(
         .get::<super::WebGpuDevice>(device_rid)?;
     let device = device_resource.1;
 
-    let descriptor = wgpu_types::CommandEncoderDescriptor { label: Some(label) };
+    let descriptor = wgpu_types::CommandEncoderDescriptor {
+        label: Some(label),
+        reusable: false,
+    };
 
     gfx_put!(device => instance.device_create_command_encoder(
     device,
@@ -13,6 +13,8 @@ use std::borrow::Cow;
 use std::cell::RefCell;
 use std::collections::HashSet;
 use std::rc::Rc;
+use std::sync::Arc;
+use std::sync::Mutex;
 pub use wgpu_core;
 pub use wgpu_types;
 
@@ -105,7 +107,10 @@ impl Resource for WebGpuAdapter {
     }
 }
 
-struct WebGpuDevice(Instance, wgpu_core::id::DeviceId);
+/// Reason and message passed to the device lost callback, once it has fired.
+type DeviceLostInfo = Arc<Mutex<Option<(wgpu_types::DeviceLostReason, String)>>>;
+
+struct WebGpuDevice(Instance, wgpu_core::id::DeviceId, DeviceLostInfo);
 impl Resource for WebGpuDevice {
     fn name(&self) -> Cow<str> {
         "webGPUDevice".into()
@@ -137,6 +142,9 @@ deno_core::extension!(
         op_webgpu_request_adapter,
         op_webgpu_request_device,
         op_webgpu_request_adapter_info,
+        // Device
+        op_webgpu_device_destroy,
+        op_webgpu_device_lost_info,
         // Query Set
         op_webgpu_create_query_set,
         // buffer
@@ -659,6 +667,7 @@ pub async fn op_webgpu_request_device(
         label: Some(Cow::Owned(label)),
         features: required_features.into(),
         limits: required_limits.unwrap_or_default(),
+        memory_hints: wgpu_types::MemoryHints::default(),
     };
 
     let (device, maybe_err) = gfx_select!(adapter => instance.adapter_request_device(
@@ -675,8 +684,19 @@ pub async fn op_webgpu_request_device(
     let features = deserialize_features(&device_features);
     let limits = gfx_select!(device => instance.device_limits(device))?;
 
+    let lost_info = DeviceLostInfo::default();
+    let lost_info_ = lost_info.clone();
+    gfx_select!(device => instance.device_set_device_lost_closure(
+        device,
+        wgpu_core::device::DeviceLostClosure::from_rust(Box::new(move |reason, message| {
+            *lost_info_.lock().unwrap() = Some((reason, message));
+        }))
+    ));
+
     let instance = instance.clone();
-    let rid = state.resource_table.add(WebGpuDevice(instance, device));
+    let rid = state
+        .resource_table
+        .add(WebGpuDevice(instance, device, lost_info));
 
     Ok(GpuAdapterDevice {
         rid,
@@ -687,6 +707,44 @@ pub async fn op_webgpu_request_device(
     })
 }
 
+#[derive(Serialize)]
+pub struct GpuDeviceLostInfo {
+    reason: &'static str,
+    message: String,
+}
+
+#[op2]
+pub fn op_webgpu_device_destroy(
+    state: &mut OpState,
+    #[smi] device_rid: ResourceId,
+) -> Result<(), AnyError> {
+    let device_resource = state.resource_table.get::<WebGpuDevice>(device_rid)?;
+    let device = device_resource.1;
+    let instance = state.borrow::<Instance>();
+
+    gfx_select!(device => instance.device_destroy(device));
+
+    Ok(())
+}
+
+#[op2]
+#[serde]
+pub fn op_webgpu_device_lost_info(
+    state: &mut OpState,
+    #[smi] device_rid: ResourceId,
+) -> Result<Option<GpuDeviceLostInfo>, AnyError> {
+    let device_resource = state.resource_table.get::<WebGpuDevice>(device_rid)?;
+    let lost_info = device_resource.2.lock().unwrap().clone();
+
+    Ok(lost_info.map(|(reason, message)| GpuDeviceLostInfo {
+        reason: match reason {
+            wgpu_types::DeviceLostReason::Destroyed => "destroyed",
+            wgpu_types::DeviceLostReason::Unknown => "unknown",
+        },
+        message,
+    }))
+}
+
 #[derive(Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct GPUAdapterInfo {
@@ -116,6 +116,7 @@ pub fn op_webgpu_create_compute_pipeline(
             entry_point: Cow::from(compute.entry_point),
             // TODO(lucacasonato): support args.compute.constants
         },
+        cache: None,
     };
     let implicit_pipelines = match layout {
         GPUPipelineLayoutOrGPUAutoLayoutMode::Layout(_) => None,
@@ -389,6 +390,7 @@ pub fn op_webgpu_create_render_pipeline(
         multisample: args.multisample,
         fragment,
         multiview: None,
+        cache: None,
     };
 
     let implicit_pipelines = match args.layout {
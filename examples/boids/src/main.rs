@@ -154,6 +154,7 @@ impl wgpu_example::framework::Example for Example {
             depth_stencil: None,
             multisample: wgpu::MultisampleState::default(),
             multiview: None,
+            cache: None,
         });
 
         // create compute pipeline
@@ -163,6 +164,7 @@ impl wgpu_example::framework::Example for Example {
             layout: Some(&compute_pipeline_layout),
             module: &compute_shader,
             entry_point: "main",
+            cache: None,
         });
 
         // buffer for the three 2d triangle vertices of each instance
@@ -288,8 +290,10 @@ impl wgpu_example::framework::Example for Example {
         };
 
         // get command encoder
-        let mut command_encoder =
-            device.create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
+        let mut command_encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: None,
+            reusable: false,
+        });
 
         command_encoder.push_debug_group("compute boid movement");
         {
@@ -111,6 +111,7 @@ impl wgpu_example::framework::Example for Example {
                 depth_stencil: None,
                 multisample: wgpu::MultisampleState::default(),
                 multiview: None,
+                cache: None,
             });
 
         let pipeline_triangle_regular =
@@ -131,6 +132,7 @@ impl wgpu_example::framework::Example for Example {
                 depth_stencil: None,
                 multisample: wgpu::MultisampleState::default(),
                 multiview: None,
+                cache: None,
             });
 
         let pipeline_lines = if device
@@ -159,6 +161,7 @@ impl wgpu_example::framework::Example for Example {
                     depth_stencil: None,
                     multisample: wgpu::MultisampleState::default(),
                     multiview: None,
+                    cache: None,
                 }),
             )
         } else {
@@ -216,6 +219,7 @@ impl wgpu_example::framework::Example for Example {
                     depth_stencil: None,
                     multisample: wgpu::MultisampleState::default(),
                     multiview: None,
+                    cache: None,
                 }),
                 bind_group_layout,
             )
@@ -259,6 +263,7 @@ impl wgpu_example::framework::Example for Example {
     ) {
         let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
             label: Some("primary"),
+            reusable: false,
         });
 
         {
@@ -17,6 +17,7 @@ async fn run() {
                 label: None,
                 features: wgpu::Features::empty(),
                 limits: wgpu::Limits::downlevel_defaults(),
+                memory_hints: wgpu::MemoryHints::MemoryUsage,
             },
             None,
         )
@@ -101,18 +102,22 @@ async fn execute(
         layout: Some(&pipeline_layout),
         module: &shaders_module,
         entry_point: "patient_main",
+        cache: None,
     });
     let hasty_pipeline = device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
         label: None,
         layout: Some(&pipeline_layout),
         module: &shaders_module,
         entry_point: "hasty_main",
+        cache: None,
     });
 
     //----------------------------------------------------------
 
-    let mut command_encoder =
-        device.create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
+    let mut command_encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+        label: None,
+        reusable: false,
+    });
     {
         let mut compute_pass = command_encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
             label: None,
@@ -133,8 +138,10 @@ async fn execute(
     )
     .await;
 
-    let mut command_encoder =
-        device.create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
+    let mut command_encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+        label: None,
+        reusable: false,
+    });
     {
         let mut compute_pass = command_encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
             label: None,
@@ -168,8 +175,10 @@ async fn get_data<T: bytemuck::Pod>(
     device: &wgpu::Device,
     queue: &wgpu::Queue,
 ) {
-    let mut command_encoder =
-        device.create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
+    let mut command_encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+        label: None,
+        reusable: false,
+    });
     command_encoder.copy_buffer_to_buffer(
         storage_buffer,
         0,
@@ -32,6 +32,7 @@ async fn run() {
                 label: None,
                 features: wgpu::Features::empty(),
                 limits: wgpu::Limits::downlevel_defaults(),
+                memory_hints: wgpu::MemoryHints::MemoryUsage,
             },
             None,
         )
@@ -110,12 +111,15 @@ async fn run() {
         layout: Some(&pipeline_layout),
         module: &shader,
         entry_point: "main",
+        cache: None,
     });
 
     //----------------------------------------------------------
 
-    let mut command_encoder =
-        device.create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
+    let mut command_encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+        label: None,
+        reusable: false,
+    });
     {
         let mut compute_pass = command_encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
             label: None,
@@ -159,8 +163,10 @@ async fn get_data<T: bytemuck::Pod>(
     device: &wgpu::Device,
     queue: &wgpu::Queue,
 ) {
-    let mut command_encoder =
-        device.create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
+    let mut command_encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+        label: None,
+        reusable: false,
+    });
     command_encoder.copy_buffer_to_buffer(
         storage_buffer,
         0,
@@ -107,6 +107,7 @@ impl Example {
             depth_stencil: None,
             multisample: wgpu::MultisampleState::default(),
             multiview: None,
+            cache: None,
         });
 
         let bind_group_layout = pipeline.get_bind_group_layout(0);
@@ -215,8 +216,10 @@ impl wgpu_example::framework::Example for Example {
         device: &wgpu::Device,
         queue: &wgpu::Queue,
     ) -> Self {
-        let mut init_encoder =
-            device.create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
+        let mut init_encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: None,
+            reusable: false,
+        });
 
         // Create the texture
         let size = 1 << MIP_PASS_COUNT;
@@ -306,6 +309,7 @@ impl wgpu_example::framework::Example for Example {
             depth_stencil: None,
             multisample: wgpu::MultisampleState::default(),
             multiview: None,
+            cache: None,
         });
 
         // Create bind group
@@ -474,8 +478,10 @@ impl wgpu_example::framework::Example for Example {
         queue: &wgpu::Queue,
         _spawner: &wgpu_example::framework::Spawner,
     ) {
-        let mut encoder =
-            device.create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: None,
+            reusable: false,
+        });
         {
             let clear_color = wgpu::Color {
                 r: 0.1,
@@ -74,6 +74,7 @@ impl Example {
                 ..Default::default()
             },
             multiview: None,
+            cache: None,
         });
         let mut encoder =
             device.create_render_bundle_encoder(&wgpu::RenderBundleEncoderDescriptor {
@@ -273,8 +274,10 @@ impl wgpu_example::framework::Example for Example {
             self.rebuild_bundle = false;
         }
 
-        let mut encoder =
-            device.create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: None,
+            reusable: false,
+        });
         {
             let rpass_color_attachment = if self.sample_count == 1 {
                 wgpu::RenderPassColorAttachment {
@@ -49,9 +49,13 @@ async fn compute(local_buffer: &mut [u32], context: &WgpuContext) {
     );
     log::info!("Wrote to buffer.");
 
-    let mut command_encoder = context
-        .device
-        .create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
+    let mut command_encoder =
+        context
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                label: None,
+                reusable: false,
+            });
 
     {
         let mut compute_pass = command_encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
@@ -169,6 +173,7 @@ impl WgpuContext {
                     label: None,
                     features: wgpu::Features::empty(),
                     limits: wgpu::Limits::downlevel_defaults(),
+                    memory_hints: wgpu::MemoryHints::MemoryUsage,
                 },
                 None,
             )
@@ -242,6 +247,7 @@ impl WgpuContext {
             layout: Some(&pipeline_layout),
             module: &shader,
             entry_point: "main",
+            cache: None,
         });
 
         WgpuContext {
@@ -525,6 +525,7 @@ impl wgpu_example::framework::Example for Example {
                 }),
                 multisample: wgpu::MultisampleState::default(),
                 multiview: None,
+                cache: None,
             });
 
             Pass {
@@ -657,6 +658,7 @@ impl wgpu_example::framework::Example for Example {
                 }),
                 multisample: wgpu::MultisampleState::default(),
                 multiview: None,
+                cache: None,
             });
 
             Pass {
@@ -744,8 +746,10 @@ impl wgpu_example::framework::Example for Example {
             }
         }
 
-        let mut encoder =
-            device.create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: None,
+            reusable: false,
+        });
 
         encoder.push_debug_group("shadow passes");
         for (i, light) in self.lights.iter().enumerate() {
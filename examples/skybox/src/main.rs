@@ -219,6 +219,7 @@ impl wgpu_example::framework::Example for Skybox {
             }),
             multisample: wgpu::MultisampleState::default(),
             multiview: None,
+            cache: None,
         });
         let entity_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
             label: Some("Entity"),
@@ -250,6 +251,7 @@ impl wgpu_example::framework::Example for Skybox {
             }),
             multisample: wgpu::MultisampleState::default(),
             multiview: None,
+            cache: None,
         });
 
         let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
@@ -398,8 +400,10 @@ impl wgpu_example::framework::Example for Skybox {
         queue: &wgpu::Queue,
         _spawner: &wgpu_example::framework::Spawner,
     ) {
-        let mut encoder =
-            device.create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: None,
+            reusable: false,
+        });
 
         // update rotation
         let raw_uniforms = self.camera.to_uniform_data();
@@ -104,6 +104,7 @@ impl wgpu_example::framework::Example for Triangles {
             }),
             multisample: wgpu::MultisampleState::default(),
             multiview: None,
+            cache: None,
         });
 
         let outer_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
@@ -137,6 +138,7 @@ impl wgpu_example::framework::Example for Triangles {
             }),
             multisample: wgpu::MultisampleState::default(),
             multiview: None,
+            cache: None,
         });
 
         let stencil_buffer = device.create_texture(&wgpu::TextureDescriptor {
@@ -184,8 +186,10 @@ impl wgpu_example::framework::Example for Triangles {
         queue: &wgpu::Queue,
         _spawner: &wgpu_example::framework::Spawner,
     ) {
-        let mut encoder =
-            device.create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: None,
+            reusable: false,
+        });
         {
             let depth_view = self.stencil_buffer.create_view(&Default::default());
             let mut rpass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
@@ -339,6 +339,7 @@ impl wgpu_example::framework::Example for Example {
             depth_stencil: None,
             multisample: wgpu::MultisampleState::default(),
             multiview: None,
+            cache: None,
         });
 
         Self {
@@ -370,6 +371,7 @@ impl wgpu_example::framework::Example for Example {
     ) {
         let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
             label: Some("primary"),
+            reusable: false,
         });
 
         let mut rpass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
@@ -214,6 +214,7 @@ async fn run() {
                 label: None,
                 features,
                 limits: wgpu::Limits::downlevel_defaults(),
+                memory_hints: wgpu::MemoryHints::MemoryUsage,
             },
             None,
         )
@@ -230,8 +231,10 @@ fn submit_render_and_compute_pass_with_queries(
     device: &wgpu::Device,
     queue: &wgpu::Queue,
 ) -> Queries {
-    let mut encoder =
-        device.create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
+    let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+        label: None,
+        reusable: false,
+    });
 
     let mut queries = Queries::new(device, QueryResults::NUM_QUERIES);
     let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
@@ -286,6 +289,7 @@ fn compute_pass(
         layout: None,
         module,
         entry_point: "main_cs",
+        cache: None,
     });
     let bind_group_layout = compute_pipeline.get_bind_group_layout(0);
     let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
@@ -351,6 +355,7 @@ fn render_pass(
         depth_stencil: None,
         multisample: wgpu::MultisampleState::default(),
         multiview: None,
+        cache: None,
     });
 
     let render_target = device.create_texture(&wgpu::TextureDescriptor {
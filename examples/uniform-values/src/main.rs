@@ -113,6 +113,7 @@ impl WgpuContext {
                     label: None,
                     features: wgpu::Features::empty(),
                     limits: wgpu::Limits::downlevel_defaults(),
+                    memory_hints: wgpu::MemoryHints::MemoryUsage,
                 },
                 None,
             )
@@ -188,6 +189,7 @@ impl WgpuContext {
             depth_stencil: None,
             multisample: wgpu::MultisampleState::default(),
             multiview: None,
+            cache: None,
         });
 
         let surface_config = wgpu::SurfaceConfiguration {
@@ -291,9 +293,12 @@ async fn run(event_loop: EventLoop<()>, window: Window) {
                     struct to WGSL bytes.",
                     ),
                 );
-                let mut encoder = wgpu_context_ref
-                    .device
-                    .create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
+                let mut encoder = wgpu_context_ref.device.create_command_encoder(
+                    &wgpu::CommandEncoderDescriptor {
+                        label: None,
+                        reusable: false,
+                    },
+                );
                 {
                     let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
                         label: None,
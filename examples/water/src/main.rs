@@ -572,6 +572,7 @@ impl wgpu_example::framework::Example for Example {
             // No multisampling is used.
             multisample: wgpu::MultisampleState::default(),
             multiview: None,
+            cache: None,
         });
 
         // Same idea as the water pipeline.
@@ -606,6 +607,7 @@ impl wgpu_example::framework::Example for Example {
             }),
             multisample: wgpu::MultisampleState::default(),
             multiview: None,
+            cache: None,
         });
 
         // A render bundle to draw the terrain.
@@ -728,6 +730,7 @@ impl wgpu_example::framework::Example for Example {
         // a command buffer the GPU can understand.
         let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
             label: Some("Main Command Encoder"),
+            reusable: false,
         });
 
         // First pass: render the reflection.
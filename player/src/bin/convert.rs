@@ -0,0 +1,29 @@
+/*! Converts WebGPU traces between the RON and binary formats.
+
+Usage: `convert <input> <output>`. Either path is a directory for a RON
+trace, or a `.wgputrace` file for a binary trace. Use a `.wgputrace.gz`
+file to compress it.
+!*/
+
+#[cfg(not(target_arch = "wasm32"))]
+fn main() {
+    use std::path::PathBuf;
+
+    env_logger::init();
+
+    let mut args = std::env::args().skip(1);
+    let (input, output) = match (args.next(), args.next()) {
+        (Some(input), Some(output)) => (PathBuf::from(input), PathBuf::from(output)),
+        _ => panic!("Provide the input and output trace paths as the parameters"),
+    };
+
+    log::info!("Loading trace '{:?}'", input);
+    let (actions, trace_data) = player::load_trace(&input).unwrap();
+    log::info!("Found {} actions", actions.len());
+
+    player::save_trace(&output, actions, &trace_data).unwrap();
+    log::info!("Written trace '{:?}'", output);
+}
+
+#[cfg(target_arch = "wasm32")]
+fn main() {}
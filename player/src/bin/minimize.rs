@@ -0,0 +1,731 @@
+/*! Minimizes WebGPU traces that reproduce a bug.
+
+It repeatedly removes actions, and commands of submissions, from a trace
+while the trace keeps reproducing the bug when replayed by `play`. Removing
+the creation of a resource also removes everything using the resource.
+!*/
+
+#[cfg(not(target_arch = "wasm32"))]
+const HELP: &str = "\
+Usage: minimize [OPTIONS] <TRACE> <OUTPUT>
+
+Writes the smallest trace found that still reproduces the bug to the OUTPUT
+directory, as a RON trace. At least one condition has to be given, and all
+the given conditions have to hold for the bug to be reproduced.
+
+Conditions:
+  --panic                 The replay fails.
+  --error <TEXT>          The output of the replay contains TEXT.
+  --buffer <INDEX>=<PATH> The contents of the buffer with this index at the
+                          end of the trace are the same as the file at PATH,
+                          like one written by `play --dump`. The buffer needs
+                          the `COPY_SRC` usage.
+
+Options:
+  -h, --help              Print this help message.
+  --player <PATH>         Path to the `play` binary. By default, the one next
+                          to this binary is used. It should be built without
+                          the \"winit\" feature.
+";
+
+#[cfg(not(target_arch = "wasm32"))]
+mod minimize {
+    use wgc::{
+        command::{ComputeCommand, RenderCommand},
+        device::trace::{Action, Command},
+        id::TypedId,
+    };
+
+    use std::{
+        collections::HashSet,
+        fs,
+        path::{Path, PathBuf},
+        process,
+    };
+
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    enum Kind {
+        Buffer,
+        Texture,
+        TextureView,
+        Sampler,
+        BindGroupLayout,
+        PipelineLayout,
+        BindGroup,
+        ShaderModule,
+        ComputePipeline,
+        RenderPipeline,
+        PipelineCache,
+        RenderBundle,
+        ComputeBundle,
+        QuerySet,
+        Heap,
+        ExternalSemaphore,
+        Blas,
+        Tlas,
+    }
+
+    /// A resource of the trace, identified by its kind, index and epoch.
+    type Key = (Kind, u32, u32);
+
+    fn key(kind: Kind, id: impl TypedId) -> Key {
+        let (index, epoch, _) = id.unzip();
+        (kind, index, epoch)
+    }
+
+    /// The resources created and used by an action.
+    #[derive(Default)]
+    struct Resources {
+        created: Vec<Key>,
+        used: Vec<Key>,
+    }
+
+    fn action_resources(action: &Action) -> Resources {
+        use wgc::binding_model::BindingResource;
+
+        let mut res = Resources::default();
+        match *action {
+            Action::CreateBuffer(id, _) => res.created.push(key(Kind::Buffer, id)),
+            Action::FreeBuffer(id) | Action::DestroyBuffer(id) => {
+                res.used.push(key(Kind::Buffer, id))
+            }
+            Action::CreateTexture(id, _) | Action::GetSurfaceTexture { id, .. } => {
+                res.created.push(key(Kind::Texture, id))
+            }
+            Action::FreeTexture(id) | Action::DestroyTexture(id) => {
+                res.used.push(key(Kind::Texture, id))
+            }
+            Action::CreateTextureView { id, parent_id, .. } => {
+                res.created.push(key(Kind::TextureView, id));
+                res.used.push(key(Kind::Texture, parent_id));
+            }
+            Action::DestroyTextureView(id) => res.used.push(key(Kind::TextureView, id)),
+            Action::CreateSampler(id, _) => res.created.push(key(Kind::Sampler, id)),
+            Action::DestroySampler(id) => res.used.push(key(Kind::Sampler, id)),
+            Action::CreateBindGroupLayout(id, _) => {
+                res.created.push(key(Kind::BindGroupLayout, id))
+            }
+            Action::DestroyBindGroupLayout(id) => res.used.push(key(Kind::BindGroupLayout, id)),
+            Action::CreatePipelineLayout(id, ref desc) => {
+                res.created.push(key(Kind::PipelineLayout, id));
+                for &layout in desc.bind_group_layouts.iter() {
+                    res.used.push(key(Kind::BindGroupLayout, layout));
+                }
+            }
+            Action::DestroyPipelineLayout(id) => res.used.push(key(Kind::PipelineLayout, id)),
+            Action::CreateBindGroup(id, ref desc) => {
+                res.created.push(key(Kind::BindGroup, id));
+                res.used.push(key(Kind::BindGroupLayout, desc.layout));
+                for entry in desc.entries.iter() {
+                    match entry.resource {
+                        BindingResource::Buffer(ref binding) => {
+                            res.used.push(key(Kind::Buffer, binding.buffer_id))
+                        }
+                        BindingResource::BufferArray(ref bindings) => {
+                            for binding in bindings.iter() {
+                                res.used.push(key(Kind::Buffer, binding.buffer_id));
+                            }
+                        }
+                        BindingResource::Sampler(id) => res.used.push(key(Kind::Sampler, id)),
+                        BindingResource::SamplerArray(ref ids) => {
+                            for &id in ids.iter() {
+                                res.used.push(key(Kind::Sampler, id));
+                            }
+                        }
+                        BindingResource::TextureView(id) => {
+                            res.used.push(key(Kind::TextureView, id))
+                        }
+                        BindingResource::TextureViewArray(ref ids) => {
+                            for &id in ids.iter() {
+                                res.used.push(key(Kind::TextureView, id));
+                            }
+                        }
+                        BindingResource::AccelerationStructure(id) => {
+                            res.used.push(key(Kind::Tlas, id))
+                        }
+                    }
+                }
+            }
+            Action::DestroyBindGroup(id) => res.used.push(key(Kind::BindGroup, id)),
+            Action::CreateShaderModule { id, .. } => res.created.push(key(Kind::ShaderModule, id)),
+            Action::DestroyShaderModule(id) => res.used.push(key(Kind::ShaderModule, id)),
+            Action::CreateComputePipeline {
+                id,
+                ref desc,
+                ref implicit_context,
+            } => {
+                res.created.push(key(Kind::ComputePipeline, id));
+                res.used
+                    .extend(desc.layout.map(|id| key(Kind::PipelineLayout, id)));
+                res.used
+                    .extend(desc.cache.map(|id| key(Kind::PipelineCache, id)));
+                res.used.push(key(Kind::ShaderModule, desc.stage.module));
+                if let Some(ref context) = *implicit_context {
+                    res.created.push(key(Kind::PipelineLayout, context.root_id));
+                    for &id in context.group_ids.iter() {
+                        res.created.push(key(Kind::BindGroupLayout, id));
+                    }
+                }
+            }
+            Action::DestroyComputePipeline(id) => res.used.push(key(Kind::ComputePipeline, id)),
+            Action::CreateRenderPipeline {
+                id,
+                ref desc,
+                ref implicit_context,
+            } => {
+                res.created.push(key(Kind::RenderPipeline, id));
+                res.used
+                    .extend(desc.layout.map(|id| key(Kind::PipelineLayout, id)));
+                res.used
+                    .extend(desc.cache.map(|id| key(Kind::PipelineCache, id)));
+                res.used
+                    .push(key(Kind::ShaderModule, desc.vertex.stage.module));
+                if let Some(ref fragment) = desc.fragment {
+                    res.used
+                        .push(key(Kind::ShaderModule, fragment.stage.module));
+                }
+                if let Some(ref context) = *implicit_context {
+                    res.created.push(key(Kind::PipelineLayout, context.root_id));
+                    for &id in context.group_ids.iter() {
+                        res.created.push(key(Kind::BindGroupLayout, id));
+                    }
+                }
+            }
+            Action::DestroyRenderPipeline(id) => res.used.push(key(Kind::RenderPipeline, id)),
+            Action::CreatePipelineCache { id, .. } => {
+                res.created.push(key(Kind::PipelineCache, id))
+            }
+            Action::DestroyPipelineCache(id) => res.used.push(key(Kind::PipelineCache, id)),
+            Action::CreateRenderBundle { id, ref base, .. } => {
+                res.created.push(key(Kind::RenderBundle, id));
+                render_resources(&base.commands, &mut res.used);
+            }
+            Action::DestroyRenderBundle(id) => res.used.push(key(Kind::RenderBundle, id)),
+            Action::CreateComputeBundle { id, ref base, .. } => {
+                res.created.push(key(Kind::ComputeBundle, id));
+                compute_resources(&base.commands, &mut res.used);
+            }
+            Action::DestroyComputeBundle(id) => res.used.push(key(Kind::ComputeBundle, id)),
+            Action::CreateQuerySet { id, .. } => res.created.push(key(Kind::QuerySet, id)),
+            Action::DestroyQuerySet(id) => res.used.push(key(Kind::QuerySet, id)),
+            Action::CreateHeap(id, _) => res.created.push(key(Kind::Heap, id)),
+            Action::DestroyHeap(id) => res.used.push(key(Kind::Heap, id)),
+            Action::CreateExternalSemaphore(id, _) => {
+                res.created.push(key(Kind::ExternalSemaphore, id))
+            }
+            Action::DestroyExternalSemaphore(id) => res.used.push(key(Kind::ExternalSemaphore, id)),
+            Action::CreateBufferInHeap { id, heap, .. } => {
+                res.created.push(key(Kind::Buffer, id));
+                res.used.push(key(Kind::Heap, heap));
+            }
+            Action::CreateTextureInHeap { id, heap, .. } => {
+                res.created.push(key(Kind::Texture, id));
+                res.used.push(key(Kind::Heap, heap));
+            }
+            Action::CreateSparseBuffer(id, _) => res.created.push(key(Kind::Buffer, id)),
+            Action::CreateSparseTexture(id, _) => res.created.push(key(Kind::Texture, id)),
+            Action::CreateBlas { id, .. } => res.created.push(key(Kind::Blas, id)),
+            Action::DestroyBlas(id) => res.used.push(key(Kind::Blas, id)),
+            Action::CreateTlas { id, .. } => res.created.push(key(Kind::Tlas, id)),
+            Action::DestroyTlas(id) => res.used.push(key(Kind::Tlas, id)),
+            Action::WriteBuffer { id, .. } => res.used.push(key(Kind::Buffer, id)),
+            Action::WriteTexture { ref to, .. } => res.used.push(key(Kind::Texture, to.texture)),
+            Action::UpdateSparseBindings {
+                ref buffer_bindings,
+                ref texture_bindings,
+            } => {
+                for binding in buffer_bindings {
+                    res.used.push(key(Kind::Buffer, binding.buffer));
+                    res.used
+                        .extend(binding.memory.map(|memory| key(Kind::Heap, memory.heap)));
+                }
+                for binding in texture_bindings {
+                    res.used.push(key(Kind::Texture, binding.texture));
+                    res.used
+                        .extend(binding.memory.map(|memory| key(Kind::Heap, memory.heap)));
+                }
+            }
+            Action::Submit(_, ref commands) => {
+                for command in commands {
+                    command_resources(command, &mut res.used);
+                }
+            }
+            Action::Init { .. }
+            | Action::ConfigureSurface(..)
+            | Action::Present(_)
+            | Action::DiscardSurfaceTexture(_) => {}
+        }
+        res
+    }
+
+    fn command_resources(command: &Command, used: &mut Vec<Key>) {
+        match *command {
+            Command::CopyBufferToBuffer { src, dst, .. } => {
+                used.push(key(Kind::Buffer, src));
+                used.push(key(Kind::Buffer, dst));
+            }
+            Command::CopyBufferToTexture {
+                ref src, ref dst, ..
+            } => {
+                used.push(key(Kind::Buffer, src.buffer));
+                used.push(key(Kind::Texture, dst.texture));
+            }
+            Command::CopyTextureToBuffer {
+                ref src, ref dst, ..
+            } => {
+                used.push(key(Kind::Texture, src.texture));
+                used.push(key(Kind::Buffer, dst.buffer));
+            }
+            Command::CopyTextureToTexture {
+                ref src, ref dst, ..
+            } => {
+                used.push(key(Kind::Texture, src.texture));
+                used.push(key(Kind::Texture, dst.texture));
+            }
+            Command::ClearBuffer { dst, .. } => used.push(key(Kind::Buffer, dst)),
+            Command::ClearTexture { dst, .. } => used.push(key(Kind::Texture, dst)),
+            Command::TransitionResources {
+                ref buffer_transitions,
+                ref texture_transitions,
+            } => {
+                used.extend(
+                    buffer_transitions
+                        .iter()
+                        .map(|transition| key(Kind::Buffer, transition.buffer)),
+                );
+                used.extend(
+                    texture_transitions
+                        .iter()
+                        .map(|transition| key(Kind::Texture, transition.texture)),
+                );
+            }
+            Command::WriteTimestamp { query_set_id, .. } => {
+                used.push(key(Kind::QuerySet, query_set_id))
+            }
+            Command::ResolveQuerySet {
+                query_set_id,
+                destination,
+                ..
+            } => {
+                used.push(key(Kind::QuerySet, query_set_id));
+                used.push(key(Kind::Buffer, destination));
+            }
+            Command::PushDebugGroup(_) | Command::PopDebugGroup | Command::InsertDebugMarker(_) => {
+            }
+            Command::RunComputePass {
+                ref base,
+                ref timestamp_writes,
+            } => {
+                compute_resources(&base.commands, used);
+                used.extend(
+                    timestamp_writes
+                        .as_ref()
+                        .map(|writes| key(Kind::QuerySet, writes.query_set)),
+                );
+            }
+            Command::RunRenderPass {
+                ref base,
+                ref target_colors,
+                ref target_depth_stencil,
+                ref timestamp_writes,
+                occlusion_query_set_id,
+            } => {
+                render_resources(&base.commands, used);
+                for attachment in target_colors.iter().flatten() {
+                    used.push(key(Kind::TextureView, attachment.view));
+                    used.extend(
+                        attachment
+                            .resolve_target
+                            .map(|id| key(Kind::TextureView, id)),
+                    );
+                }
+                used.extend(
+                    target_depth_stencil
+                        .as_ref()
+                        .map(|attachment| key(Kind::TextureView, attachment.view)),
+                );
+                used.extend(
+                    timestamp_writes
+                        .as_ref()
+                        .map(|writes| key(Kind::QuerySet, writes.query_set)),
+                );
+                used.extend(occlusion_query_set_id.map(|id| key(Kind::QuerySet, id)));
+            }
+            Command::BuildAccelerationStructuresUnsafeTlas { ref blas, ref tlas } => {
+                use wgc::ray_tracing::BlasGeometries;
+
+                for entry in blas.iter() {
+                    used.push(key(Kind::Blas, entry.blas_id));
+                    match entry.geometries {
+                        BlasGeometries::TriangleGeometries(ref geometries) => {
+                            for geometry in geometries.iter() {
+                                used.push(key(Kind::Buffer, geometry.vertex_buffer));
+                                used.extend(geometry.index_buffer.map(|id| key(Kind::Buffer, id)));
+                                used.extend(
+                                    geometry.transform_buffer.map(|id| key(Kind::Buffer, id)),
+                                );
+                            }
+                        }
+                    }
+                }
+                for entry in tlas.iter() {
+                    used.push(key(Kind::Tlas, entry.tlas_id));
+                    used.push(key(Kind::Buffer, entry.instance_buffer_id));
+                }
+            }
+        }
+    }
+
+    fn compute_resources(commands: &[ComputeCommand], used: &mut Vec<Key>) {
+        for command in commands {
+            match *command {
+                ComputeCommand::SetBindGroup { bind_group_id, .. } => {
+                    used.push(key(Kind::BindGroup, bind_group_id))
+                }
+                ComputeCommand::SetPipeline(id) => used.push(key(Kind::ComputePipeline, id)),
+                ComputeCommand::DispatchIndirect { buffer_id, .. } => {
+                    used.push(key(Kind::Buffer, buffer_id))
+                }
+                ComputeCommand::WriteTimestamp { query_set_id, .. }
+                | ComputeCommand::BeginPipelineStatisticsQuery { query_set_id, .. } => {
+                    used.push(key(Kind::QuerySet, query_set_id))
+                }
+                ComputeCommand::ExecuteBundle(id) => used.push(key(Kind::ComputeBundle, id)),
+                _ => {}
+            }
+        }
+    }
+
+    fn render_resources(commands: &[RenderCommand], used: &mut Vec<Key>) {
+        for command in commands {
+            match *command {
+                RenderCommand::SetBindGroup { bind_group_id, .. } => {
+                    used.push(key(Kind::BindGroup, bind_group_id))
+                }
+                RenderCommand::SetPipeline(id) => used.push(key(Kind::RenderPipeline, id)),
+                RenderCommand::SetIndexBuffer { buffer_id, .. }
+                | RenderCommand::SetVertexBuffer { buffer_id, .. }
+                | RenderCommand::MultiDrawIndirect { buffer_id, .. } => {
+                    used.push(key(Kind::Buffer, buffer_id))
+                }
+                RenderCommand::MultiDrawIndirectCount {
+                    buffer_id,
+                    count_buffer_id,
+                    ..
+                } => {
+                    used.push(key(Kind::Buffer, buffer_id));
+                    used.push(key(Kind::Buffer, count_buffer_id));
+                }
+                RenderCommand::WriteTimestamp { query_set_id, .. }
+                | RenderCommand::BeginPipelineStatisticsQuery { query_set_id, .. } => {
+                    used.push(key(Kind::QuerySet, query_set_id))
+                }
+                RenderCommand::ExecuteBundle(id) => used.push(key(Kind::RenderBundle, id)),
+                _ => {}
+            }
+        }
+    }
+
+    /// Something that can be removed from a trace.
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    enum Item {
+        Action(usize),
+        Command { action: usize, command: usize },
+    }
+
+    /// Returns the actions without the removed items, and without whatever uses
+    /// a resource that isn't created anymore.
+    fn remove(actions: &[Action<'static>], removed: &HashSet<Item>) -> Vec<Action<'static>> {
+        let mut created = HashSet::new();
+        let mut result = Vec::new();
+        for (i, action) in actions.iter().enumerate() {
+            if removed.contains(&Item::Action(i)) {
+                continue;
+            }
+            let action = match *action {
+                Action::Submit(index, ref commands) => Action::Submit(
+                    index,
+                    commands
+                        .iter()
+                        .enumerate()
+                        .filter(|&(j, command)| {
+                            let mut used = Vec::new();
+                            command_resources(command, &mut used);
+                            !removed.contains(&Item::Command {
+                                action: i,
+                                command: j,
+                            }) && used.iter().all(|key| created.contains(key))
+                        })
+                        .map(|(_, command)| command.clone())
+                        .collect(),
+                ),
+                ref other => {
+                    let res = action_resources(other);
+                    if !res.used.iter().all(|key| created.contains(key)) {
+                        continue;
+                    }
+                    created.extend(res.created);
+                    other.clone()
+                }
+            };
+            result.push(action);
+        }
+        result
+    }
+
+    /// Number of actions and commands in a trace.
+    fn size(actions: &[Action]) -> usize {
+        actions
+            .iter()
+            .map(|action| match *action {
+                Action::Submit(_, ref commands) => 1 + commands.len(),
+                _ => 1,
+            })
+            .sum()
+    }
+
+    /// Delta-debugs the removal of `items` from the trace, keeping the removals
+    /// that reproduce the bug.
+    fn reduce(
+        actions: Vec<Action<'static>>,
+        mut items: Vec<Item>,
+        tester: &mut Tester,
+    ) -> Vec<Action<'static>> {
+        let mut removed = HashSet::new();
+        let mut current = actions.clone();
+        let mut granularity = 2;
+        while !items.is_empty() {
+            let chunk_size = (items.len() - 1) / granularity + 1;
+            let mut reduced = false;
+            for start in (0..items.len()).step_by(chunk_size) {
+                let end = (start + chunk_size).min(items.len());
+                let mut candidate_removed = removed.clone();
+                candidate_removed.extend(items[start..end].iter().copied());
+                let candidate = remove(&actions, &candidate_removed);
+                // Items that are already gone with the resources they use
+                // don't need another replay.
+                if size(&candidate) == size(&current) || tester.reproduces(&candidate) {
+                    removed = candidate_removed;
+                    items.drain(start..end);
+                    current = candidate;
+                    granularity = (granularity - 1).max(2);
+                    reduced = true;
+                    break;
+                }
+            }
+            if !reduced {
+                if chunk_size == 1 {
+                    break;
+                }
+                granularity = (granularity * 2).min(items.len());
+            }
+        }
+        log::info!("Reduced to {} actions and commands", size(&current));
+        current
+    }
+
+    /// Minimizes a trace that reproduces the bug.
+    pub fn minimize(
+        mut actions: Vec<Action<'static>>,
+        tester: &mut Tester,
+    ) -> Vec<Action<'static>> {
+        loop {
+            let initial_size = size(&actions);
+            // `Init` stays, and so do the surface actions, which only go
+            // together.
+            let items = actions
+                .iter()
+                .enumerate()
+                .filter(|&(_, action)| {
+                    !matches!(
+                        *action,
+                        Action::Init { .. }
+                            | Action::ConfigureSurface(..)
+                            | Action::GetSurfaceTexture { .. }
+                            | Action::Present(_)
+                            | Action::DiscardSurfaceTexture(_)
+                    )
+                })
+                .map(|(i, _)| Item::Action(i))
+                .collect();
+            actions = reduce(actions, items, tester);
+
+            let items = actions
+                .iter()
+                .enumerate()
+                .flat_map(|(i, action)| {
+                    let count = match *action {
+                        Action::Submit(_, ref commands) => commands.len(),
+                        _ => 0,
+                    };
+                    (0..count).map(move |j| Item::Command {
+                        action: i,
+                        command: j,
+                    })
+                })
+                .collect();
+            actions = reduce(actions, items, tester);
+
+            if size(&actions) == initial_size {
+                return actions;
+            }
+        }
+    }
+
+    /// The conditions that reproduce the bug.
+    #[derive(Debug, Default)]
+    pub struct Predicate {
+        pub panic: bool,
+        pub error: Option<String>,
+        pub buffer: Option<(u32, Vec<u8>)>,
+    }
+
+    /// Replays traces to find out whether they reproduce the bug.
+    pub struct Tester {
+        pub player: PathBuf,
+        /// Directory with the data files of the trace, where the traces to
+        /// test are written.
+        pub dir: PathBuf,
+        pub predicate: Predicate,
+        pub replays: usize,
+    }
+
+    impl Tester {
+        pub fn reproduces(&mut self, actions: &[Action<'static>]) -> bool {
+            self.replays += 1;
+            let ron = ron::ser::to_string_pretty(actions, ron::ser::PrettyConfig::default())
+                .expect("Unable to serialize the trace");
+            fs::write(self.dir.join(wgc::device::trace::FILE_NAME), ron)
+                .expect("Unable to write the trace");
+
+            let mut command = process::Command::new(&self.player);
+            let dump = self.dir.join("dump.bin");
+            if let Some((index, _)) = self.predicate.buffer {
+                let _ = fs::remove_file(&dump);
+                command.arg("--dump").arg(format!(
+                    "buffer:{}@{}={}",
+                    index,
+                    actions.len() - 1,
+                    dump.display()
+                ));
+            }
+            let output = command
+                .arg(&self.dir)
+                .output()
+                .expect("Unable to run the player");
+
+            let mut reproduces = true;
+            if self.predicate.panic {
+                reproduces &= !output.status.success();
+            }
+            if let Some(ref text) = self.predicate.error {
+                reproduces &= String::from_utf8_lossy(&output.stdout).contains(text.as_str())
+                    || String::from_utf8_lossy(&output.stderr).contains(text.as_str());
+            }
+            if let Some((_, ref expected)) = self.predicate.buffer {
+                reproduces &= fs::read(&dump).is_ok_and(|contents| contents == *expected);
+            }
+            log::debug!(
+                "Replay {} of {} actions: {}",
+                self.replays,
+                actions.len(),
+                if reproduces {
+                    "reproduced"
+                } else {
+                    "not reproduced"
+                }
+            );
+            reproduces
+        }
+    }
+
+    pub fn default_player() -> PathBuf {
+        let exe = std::env::current_exe().expect("Unable to find the current executable");
+        exe.with_file_name(format!("play{}", std::env::consts::EXE_SUFFIX))
+    }
+
+    pub fn parse_buffer(s: &str) -> Result<(u32, Vec<u8>), String> {
+        let (index, path) = s
+            .split_once('=')
+            .ok_or_else(|| format!("Invalid buffer \"{s}\", expected <INDEX>=<PATH>"))?;
+        let index = index
+            .parse()
+            .map_err(|_| format!("Invalid buffer index \"{index}\""))?;
+        let contents = fs::read(Path::new(path)).map_err(|e| format!("{path}: {e}"))?;
+        Ok((index, contents))
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn main() {
+    use minimize::{Predicate, Tester};
+    use player::TraceData;
+    use std::path::PathBuf;
+
+    let exit_with_help = || -> ! {
+        eprintln!("{HELP}");
+        std::process::exit(101);
+    };
+
+    let mut args = pico_args::Arguments::from_env();
+    if args.contains(["-h", "--help"]) {
+        exit_with_help();
+    }
+    let predicate = Predicate {
+        panic: args.contains("--panic"),
+        error: args.opt_value_from_str("--error").unwrap(),
+        buffer: args
+            .opt_value_from_fn("--buffer", minimize::parse_buffer)
+            .unwrap_or_else(|e| {
+                eprintln!("{e}");
+                exit_with_help()
+            }),
+    };
+    let player: Option<PathBuf> = args.opt_value_from_str("--player").unwrap();
+    let (input, output) = match args.finish().as_slice() {
+        [input, output] => (PathBuf::from(input), PathBuf::from(output)),
+        _ => exit_with_help(),
+    };
+    if !predicate.panic && predicate.error.is_none() && predicate.buffer.is_none() {
+        eprintln!("No condition was given");
+        exit_with_help();
+    }
+
+    env_logger::init();
+
+    log::info!("Loading trace '{:?}'", input);
+    let (actions, trace_data) = player::load_trace(&input).unwrap();
+    log::info!("Found {} actions", actions.len());
+
+    // Candidates are written next to a copy of the data files.
+    let dir = std::env::temp_dir().join(format!("wgpu-minimize-{}", std::process::id()));
+    player::save_trace(&dir, actions, &trace_data).unwrap();
+    let (actions, _) = player::load_trace(&dir).unwrap();
+
+    let mut tester = Tester {
+        player: player.unwrap_or_else(minimize::default_player),
+        dir,
+        predicate,
+        replays: 0,
+    };
+    if !tester.reproduces(&actions) {
+        let _ = std::fs::remove_dir_all(&tester.dir);
+        eprintln!("The trace doesn't reproduce the bug");
+        std::process::exit(1);
+    }
+    let initial_count = actions.len();
+    let actions = minimize::minimize(actions, &mut tester);
+    println!(
+        "Minimized from {} to {} actions in {} replays",
+        initial_count,
+        actions.len(),
+        tester.replays
+    );
+
+    player::save_trace(&output, actions, &TraceData::Directory(tester.dir.clone())).unwrap();
+    let _ = std::fs::remove_dir_all(&tester.dir);
+    log::info!("Written trace '{:?}'", output);
+}
+
+#[cfg(target_arch = "wasm32")]
+fn main() {}
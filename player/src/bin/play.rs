@@ -1,35 +1,438 @@
 /*! This is a player for WebGPU traces.
 !*/
 
+#[cfg(not(target_arch = "wasm32"))]
+const HELP: &str = "\
+Usage: play [OPTIONS] <TRACE>
+
+Replays a trace, which is either a directory with a RON trace or a binary
+trace file.
+
+Options:
+  -h, --help              Print this help message.
+  -b, --backend <NAME>    Replay on this backend instead of the recorded one:
+                          vulkan, metal, dx12, dx11, gl or cpu.
+  -u, --until <FRAME>     Stop after this frame, counting from 1. Frames end
+                          with a `Present` action, or with a `Submit` action in
+                          traces that don't present.
+  -s, --step              Wait for the space key before every frame. Requires
+                          the \"winit\" feature.
+  -l, --loop <COUNT>      Keep replaying the last COUNT frames, for profiling.
+                          The frames must free all the resources they create.
+  -d, --dump <KIND>:<INDEX>@<ACTION>=<PATH>
+                          Write the contents of the buffer or texture with this
+                          index to PATH, right after the action with this number
+                          (the `Init` action being 0). KIND is `buffer` or
+                          `texture`. Textures are written as tightly packed rows
+                          of their first mip level. Can be repeated.
+";
+
+#[cfg(not(target_arch = "wasm32"))]
+mod replay {
+    use player::{GlobalPlay as _, IdentityPassThroughFactory, TraceData};
+    use wgc::{
+        device::trace::Action,
+        gfx_select,
+        id::{self, TypedId as _},
+    };
+
+    use std::{collections::HashMap, path::PathBuf, str::FromStr};
+
+    type Global = wgc::global::Global<IdentityPassThroughFactory>;
+
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    pub enum DumpKind {
+        Buffer,
+        Texture,
+    }
+
+    /// A resource to write to a file at some point of the trace.
+    #[derive(Debug)]
+    pub struct Dump {
+        pub kind: DumpKind,
+        pub index: u32,
+        pub action: usize,
+        pub path: PathBuf,
+    }
+
+    impl FromStr for Dump {
+        type Err = String;
+
+        fn from_str(s: &str) -> Result<Self, String> {
+            let error = || format!("Invalid dump \"{s}\", expected <KIND>:<INDEX>@<ACTION>=<PATH>");
+            let (resource, path) = s.split_once('=').ok_or_else(error)?;
+            let (resource, action) = resource.split_once('@').ok_or_else(error)?;
+            let (kind, index) = resource.split_once(':').ok_or_else(error)?;
+            Ok(Self {
+                kind: match kind {
+                    "buffer" => DumpKind::Buffer,
+                    "texture" => DumpKind::Texture,
+                    _ => return Err(error()),
+                },
+                index: index.parse().map_err(|_| error())?,
+                action: action.parse().map_err(|_| error())?,
+                path: PathBuf::from(path),
+            })
+        }
+    }
+
+    pub fn parse_backend(name: &str) -> Result<wgt::Backend, String> {
+        Ok(match name.to_lowercase().as_str() {
+            "vulkan" => wgt::Backend::Vulkan,
+            "metal" => wgt::Backend::Metal,
+            "dx12" => wgt::Backend::Dx12,
+            "dx11" => wgt::Backend::Dx11,
+            "gl" => wgt::Backend::Gl,
+            "cpu" => wgt::Backend::Cpu,
+            _ => return Err(format!("Unknown backend \"{name}\"")),
+        })
+    }
+
+    /// Whether an action ends a frame.
+    pub fn ends_frame(action: &Action, presents: bool) -> bool {
+        match *action {
+            Action::Present(_) => true,
+            Action::Submit(..) => !presents,
+            _ => false,
+        }
+    }
+
+    /// Replays the actions of a trace, after `Init`.
+    pub struct Replay {
+        pub global: Global,
+        pub device: id::DeviceId,
+        trace_data: TraceData,
+        /// Remaining actions, in reverse order.
+        actions: Vec<Action<'static>>,
+        /// Number of the next action of the trace.
+        action_index: usize,
+        /// Frames replayed in a loop once the trace is done.
+        loop_actions: Vec<Action<'static>>,
+        loop_index: usize,
+        dumps: Vec<Dump>,
+        buffers: HashMap<u32, (id::BufferId, wgc::resource::BufferDescriptor<'static>)>,
+        textures: HashMap<u32, (id::TextureId, wgc::resource::TextureDescriptor<'static>)>,
+        /// Index of the next buffer created to read back a dump, past all the
+        /// buffers of the trace.
+        dump_buffer_index: u32,
+        command_buffer_id_manager: wgc::identity::IdentityManager,
+    }
+
+    impl Replay {
+        pub fn new(
+            global: Global,
+            device: id::DeviceId,
+            trace_data: TraceData,
+            mut actions: Vec<Action<'static>>,
+            until: Option<usize>,
+            loop_frames: Option<usize>,
+            dumps: Vec<Dump>,
+        ) -> Self {
+            let presents = actions
+                .iter()
+                .any(|action| matches!(*action, Action::Present(_)));
+            let frame_ends: Vec<usize> = actions
+                .iter()
+                .enumerate()
+                .filter(|&(_, action)| ends_frame(action, presents))
+                .map(|(i, _)| i)
+                .collect();
+            if let Some(&end) = until.and_then(|frame| frame_ends.get(frame.max(1) - 1)) {
+                actions.truncate(end + 1);
+            }
+            let frame_ends = &frame_ends[..frame_ends.partition_point(|&end| end < actions.len())];
+
+            let loop_actions = match loop_frames {
+                Some(count) if count > 0 && !frame_ends.is_empty() => {
+                    let start = frame_ends
+                        .len()
+                        .checked_sub(count + 1)
+                        .map_or(0, |i| frame_ends[i] + 1);
+                    actions[start..=frame_ends[frame_ends.len() - 1]].to_vec()
+                }
+                _ => Vec::new(),
+            };
+
+            let dump_buffer_index = actions
+                .iter()
+                .filter_map(|action| match *action {
+                    Action::CreateBuffer(id, _) => Some(id.unzip().0 + 1),
+                    _ => None,
+                })
+                .max()
+                .unwrap_or(0);
+
+            actions.reverse(); // allows us to pop from the top
+            Self {
+                global,
+                device,
+                trace_data,
+                actions,
+                action_index: 1,
+                loop_actions,
+                loop_index: 0,
+                dumps,
+                buffers: HashMap::new(),
+                textures: HashMap::new(),
+                dump_buffer_index,
+                command_buffer_id_manager: wgc::identity::IdentityManager::default(),
+            }
+        }
+
+        /// Returns the next action, or `None` once the trace is done and there
+        /// are no frames to loop over.
+        pub fn next_action(&mut self) -> Option<Action<'static>> {
+            if let Some(action) = self.actions.pop() {
+                return Some(action);
+            }
+            if self.loop_actions.is_empty() {
+                return None;
+            }
+            if self.loop_index == 0 {
+                log::info!("Looping over the last frames");
+            }
+            let action = self.loop_actions[self.loop_index].clone();
+            self.loop_index = (self.loop_index + 1) % self.loop_actions.len();
+            Some(action)
+        }
+
+        /// Executes an action other than the surface ones.
+        pub fn execute(&mut self, action: Action<'static>) {
+            log::debug!("Executing action {}", self.action_index);
+            match action {
+                Action::CreateBuffer(id, ref desc) => {
+                    self.buffers.insert(id.unzip().0, (id, desc.clone()));
+                }
+                Action::CreateTexture(id, ref desc) => {
+                    self.textures.insert(id.unzip().0, (id, desc.clone()));
+                }
+                _ => {}
+            }
+            let device = self.device;
+            let global = &self.global;
+            gfx_select!(device => global.process(device, action, &self.trace_data, &mut self.command_buffer_id_manager));
+            self.finish_action();
+        }
+
+        /// Writes the dumps that were requested after the current action.
+        pub fn finish_action(&mut self) {
+            // Looped frames don't count as actions of the trace.
+            if self.actions.is_empty() && self.loop_index != 0 {
+                return;
+            }
+            let dumps = std::mem::take(&mut self.dumps);
+            for dump in dumps.iter() {
+                if dump.action == self.action_index {
+                    if let Err(e) = self.dump(dump) {
+                        log::error!("Unable to dump {:?}: {}", dump, e);
+                    }
+                }
+            }
+            self.dumps = dumps;
+            self.action_index += 1;
+        }
+
+        fn dump(&mut self, dump: &Dump) -> Result<(), String> {
+            use wgt::BufferUsages;
+
+            let device = self.device;
+            let backend = device.backend();
+            // Size of the read back buffer, and the padded and unpadded size of
+            // texture rows.
+            let (size, rows) = match dump.kind {
+                DumpKind::Buffer => {
+                    let (_, desc) = self
+                        .buffers
+                        .get(&dump.index)
+                        .ok_or("no such buffer was created")?;
+                    (desc.size, None)
+                }
+                DumpKind::Texture => {
+                    let (_, desc) = self
+                        .textures
+                        .get(&dump.index)
+                        .ok_or("no such texture was created")?;
+                    let block_size = desc
+                        .format
+                        .block_size(None)
+                        .ok_or("the texture format can't be copied")?;
+                    let (block_width, block_height) = desc.format.block_dimensions();
+                    let size = desc.size.physical_size(desc.format);
+                    let unpadded = size.width / block_width * block_size;
+                    let padded = wgt::math::align_to(unpadded, wgt::COPY_BYTES_PER_ROW_ALIGNMENT);
+                    let height = size.height / block_height;
+                    let count = height as u64 * desc.size.depth_or_array_layers as u64;
+                    (padded as u64 * count, Some((padded, unpadded, height)))
+                }
+            };
+
+            let buffer = id::TypedId::zip(self.dump_buffer_index, 1, backend);
+            self.dump_buffer_index += 1;
+            let global = &self.global;
+            let (_, error) = gfx_select!(device => global.device_create_buffer(
+                device,
+                &wgc::resource::BufferDescriptor {
+                    label: Some("dump".into()),
+                    size,
+                    usage: BufferUsages::MAP_READ | BufferUsages::COPY_DST,
+                    mapped_at_creation: false,
+                },
+                buffer
+            ));
+            if let Some(e) = error {
+                return Err(e.to_string());
+            }
+            let result = self.read_back(dump, buffer, size, rows);
+            let global = &self.global;
+            gfx_select!(device => global.buffer_drop(buffer, false));
+            result
+        }
+
+        fn read_back(
+            &mut self,
+            dump: &Dump,
+            buffer: id::BufferId,
+            size: wgt::BufferAddress,
+            rows: Option<(u32, u32, u32)>,
+        ) -> Result<(), String> {
+            let device = self.device;
+            let global = &self.global;
+            let (encoder, error) = gfx_select!(device => global.device_create_command_encoder(
+                device,
+                &wgt::CommandEncoderDescriptor {
+                label: None,
+                reusable: false,
+            },
+                self.command_buffer_id_manager.alloc(device.backend())
+            ));
+            if let Some(e) = error {
+                return Err(e.to_string());
+            }
+            match (dump.kind, rows) {
+                (DumpKind::Texture, Some((padded, _, height))) => {
+                    let &(texture, ref desc) = &self.textures[&dump.index];
+                    gfx_select!(device => global.command_encoder_copy_texture_to_buffer(
+                        encoder,
+                        &wgc::command::ImageCopyTexture {
+                            texture,
+                            mip_level: 0,
+                            origin: wgt::Origin3d::ZERO,
+                            aspect: wgt::TextureAspect::All,
+                        },
+                        &wgc::command::ImageCopyBuffer {
+                            buffer,
+                            layout: wgt::ImageDataLayout {
+                                offset: 0,
+                                bytes_per_row: Some(padded),
+                                rows_per_image: Some(height),
+                            },
+                        },
+                        &desc.size
+                    ))
+                    .map_err(|e| e.to_string())?;
+                }
+                _ => {
+                    let source = self.buffers[&dump.index].0;
+                    gfx_select!(device => global.command_encoder_copy_buffer_to_buffer(
+                        encoder, source, 0, buffer, 0, size
+                    ))
+                    .map_err(|e| e.to_string())?;
+                }
+            }
+            let (command_buffer, error) = gfx_select!(device => global.command_encoder_finish(
+                encoder,
+                &wgt::CommandBufferDescriptor { label: None }
+            ));
+            if let Some(e) = error {
+                return Err(e.to_string());
+            }
+            gfx_select!(device => global.queue_submit(device, &[command_buffer]))
+                .map_err(|e| e.to_string())?;
+
+            gfx_select!(device => global.buffer_map_async(
+                buffer,
+                0..size,
+                wgc::resource::BufferMapOperation {
+                    host: wgc::device::HostMap::Read,
+                    callback: wgc::resource::BufferMapCallback::from_rust(Box::new(|_| {})),
+                }
+            ))
+            .map_err(|e| e.to_string())?;
+            gfx_select!(device => global.device_poll(device, wgt::Maintain::Wait))
+                .map_err(|e| e.to_string())?;
+            let (ptr, size) =
+                gfx_select!(device => global.buffer_get_mapped_range(buffer, 0, None))
+                    .map_err(|e| e.to_string())?;
+            let contents = unsafe { std::slice::from_raw_parts(ptr, size as usize) };
+            let data = match rows {
+                Some((padded, unpadded, _)) => contents
+                    .chunks(padded as usize)
+                    .flat_map(|row| &row[..unpadded as usize])
+                    .copied()
+                    .collect(),
+                None => contents.to_vec(),
+            };
+            gfx_select!(device => global.buffer_unmap(buffer)).map_err(|e| e.to_string())?;
+
+            std::fs::write(&dump.path, data).map_err(|e| e.to_string())?;
+            log::info!("Dumped {:?} {} into {:?}", dump.kind, dump.index, dump.path);
+            Ok(())
+        }
+    }
+}
+
 #[cfg(not(target_arch = "wasm32"))]
 fn main() {
-    use player::{GlobalPlay as _, IdentityPassThroughFactory};
+    use player::IdentityPassThroughFactory;
+    use replay::{Dump, Replay};
     use wgc::{device::trace, gfx_select};
 
-    use std::{
-        fs,
-        path::{Path, PathBuf},
-    };
+    use std::path::PathBuf;
 
     #[cfg(feature = "winit")]
     use raw_window_handle::{HasRawDisplayHandle, HasRawWindowHandle};
     #[cfg(feature = "winit")]
     use winit::{event_loop::EventLoop, window::WindowBuilder};
 
-    env_logger::init();
-
-    //TODO: setting for the backend bits
-    //TODO: setting for the target frame, or controls
+    let exit_with_help = || -> ! {
+        eprintln!("{HELP}");
+        std::process::exit(101);
+    };
 
-    let dir = match std::env::args().nth(1) {
-        Some(arg) if Path::new(&arg).is_dir() => PathBuf::from(arg),
-        _ => panic!("Provide the dir path as the parameter"),
+    let mut args = pico_args::Arguments::from_env();
+    if args.contains(["-h", "--help"]) {
+        exit_with_help();
+    }
+    let backend_override = args
+        .opt_value_from_fn(["-b", "--backend"], replay::parse_backend)
+        .unwrap_or_else(|e| {
+            eprintln!("{e}");
+            exit_with_help()
+        });
+    let until: Option<usize> = args.opt_value_from_str(["-u", "--until"]).unwrap();
+    let step = args.contains(["-s", "--step"]);
+    let loop_frames: Option<usize> = args.opt_value_from_str(["-l", "--loop"]).unwrap();
+    let dumps: Vec<Dump> = args.values_from_str(["-d", "--dump"]).unwrap_or_else(|e| {
+        eprintln!("{e}");
+        exit_with_help()
+    });
+    let path = match args.finish().as_slice() {
+        [path] if !path.to_string_lossy().starts_with('-') => PathBuf::from(path),
+        [] => exit_with_help(),
+        remaining => {
+            eprintln!("Unexpected argument(s): {remaining:?}");
+            exit_with_help()
+        }
     };
+    #[cfg(not(feature = "winit"))]
+    if step {
+        eprintln!("Stepping through frames requires the \"winit\" feature");
+    }
 
-    log::info!("Loading trace '{:?}'", dir);
-    let file = fs::File::open(dir.join(trace::FILE_NAME)).unwrap();
-    let mut actions: Vec<trace::Action> = ron::de::from_reader(file).unwrap();
-    actions.reverse(); // allows us to pop from the top
+    env_logger::init();
+
+    log::info!("Loading trace '{:?}'", path);
+    let (mut actions, trace_data) = player::load_trace(&path).unwrap();
     log::info!("Found {} actions", actions.len());
 
     #[cfg(feature = "winit")]
@@ -49,7 +452,6 @@ fn main() {
         IdentityPassThroughFactory,
         wgt::InstanceDescriptor::default(),
     );
-    let mut command_buffer_id_manager = wgc::identity::IdentityManager::default();
 
     #[cfg(feature = "winit")]
     let surface = global.instance_create_surface(
@@ -58,8 +460,9 @@ fn main() {
         wgc::id::TypedId::zip(0, 1, wgt::Backend::Empty),
     );
 
-    let device = match actions.pop() {
-        Some(trace::Action::Init { desc, backend }) => {
+    let device = match actions.first() {
+        Some(&trace::Action::Init { ref desc, backend }) => {
+            let backend = backend_override.unwrap_or(backend);
             log::info!("Initializing the device for backend: {:?}", backend);
             let adapter = global
                 .request_adapter(
@@ -83,7 +486,7 @@ fn main() {
             let id = wgc::id::TypedId::zip(1, 0, backend);
             let (_, error) = gfx_select!(adapter => global.adapter_request_device(
                 adapter,
-                &desc,
+                desc,
                 None,
                 id
             ));
@@ -94,16 +497,29 @@ fn main() {
         }
         _ => panic!("Expected Action::Init"),
     };
+    actions.remove(0);
+
+    let mut replay = Replay::new(
+        global,
+        device,
+        trace_data,
+        actions,
+        until,
+        loop_frames,
+        dumps,
+    );
 
     log::info!("Executing actions");
     #[cfg(not(feature = "winit"))]
     {
+        let global = &replay.global;
         gfx_select!(device => global.device_start_capture(device));
 
-        while let Some(action) = actions.pop() {
-            gfx_select!(device => global.process(device, action, &dir, &mut command_buffer_id_manager));
+        while let Some(action) = replay.next_action() {
+            replay.execute(action);
         }
 
+        let global = &replay.global;
         gfx_select!(device => global.device_stop_capture(device));
         gfx_select!(device => global.device_poll(device, wgt::Maintain::Wait)).unwrap();
     }
@@ -117,18 +533,21 @@ fn main() {
         let mut resize_config = None;
         let mut frame_count = 0;
         let mut done = false;
+        // Whether the next frame can be replayed, when stepping through frames.
+        let mut advance = !step;
         event_loop.run(move |event, _, control_flow| {
             *control_flow = ControlFlow::Poll;
             match event {
                 Event::MainEventsCleared => {
                     window.request_redraw();
                 }
-                Event::RedrawRequested(_) if resize_config.is_none() => loop {
-                    match actions.pop() {
+                Event::RedrawRequested(_) if resize_config.is_none() && advance => loop {
+                    match replay.next_action() {
                         Some(trace::Action::ConfigureSurface(_device_id, config)) => {
                             log::info!("Configuring the surface");
                             let current_size: (u32, u32) = window.inner_size().into();
                             let size = (config.width, config.height);
+                            replay.finish_action();
                             if current_size != size {
                                 window.set_inner_size(winit::dpi::PhysicalSize::new(
                                     config.width,
@@ -137,6 +556,7 @@ fn main() {
                                 resize_config = Some(config);
                                 break;
                             } else {
+                                let global = &replay.global;
                                 let error = gfx_select!(device => global.surface_configure(surface, device, &config));
                                 if let Some(e) = error {
                                     panic!("{:?}", e);
@@ -146,16 +566,21 @@ fn main() {
                         Some(trace::Action::Present(id)) => {
                             frame_count += 1;
                             log::debug!("Presenting frame {}", frame_count);
+                            let global = &replay.global;
                             gfx_select!(device => global.surface_present(id)).unwrap();
+                            replay.finish_action();
+                            advance = !step;
                             break;
                         }
                         Some(trace::Action::DiscardSurfaceTexture(id)) => {
                             log::debug!("Discarding frame {}", frame_count);
+                            let global = &replay.global;
                             gfx_select!(device => global.surface_texture_discard(id)).unwrap();
+                            replay.finish_action();
                             break;
                         }
                         Some(action) => {
-                            gfx_select!(device => global.process(device, action, &dir, &mut command_buffer_id_manager));
+                            replay.execute(action);
                         }
                         None => {
                             if !done {
@@ -169,12 +594,24 @@ fn main() {
                 Event::WindowEvent { event, .. } => match event {
                     WindowEvent::Resized(_) => {
                         if let Some(config) = resize_config.take() {
+                            let global = &replay.global;
                             let error = gfx_select!(device => global.surface_configure(surface, device, &config));
                             if let Some(e) = error {
                                 panic!("{:?}", e);
                             }
                         }
                     }
+                    WindowEvent::KeyboardInput {
+                        input:
+                            KeyboardInput {
+                                virtual_keycode: Some(VirtualKeyCode::Space),
+                                state: ElementState::Pressed,
+                                ..
+                            },
+                        ..
+                    } => {
+                        advance = true;
+                    }
                     WindowEvent::KeyboardInput {
                         input:
                             KeyboardInput {
@@ -191,6 +628,7 @@ fn main() {
                 },
                 Event::LoopDestroyed => {
                     log::info!("Closing");
+                    let global = &replay.global;
                     gfx_select!(device => global.device_poll(device, wgt::Maintain::Wait)).unwrap();
                 }
                 _ => {}
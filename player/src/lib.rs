@@ -10,7 +10,14 @@
 
 use wgc::device::trace;
 
-use std::{borrow::Cow, fmt::Debug, fs, marker::PhantomData, path::Path};
+use std::{
+    borrow::Cow,
+    collections::HashMap,
+    fmt::Debug,
+    fs,
+    marker::PhantomData,
+    path::{Path, PathBuf},
+};
 
 #[derive(Debug)]
 pub struct IdentityPassThrough<I>(PhantomData<I>);
@@ -42,6 +49,72 @@ impl wgc::identity::GlobalIdentityHandlerFactory for IdentityPassThroughFactory
     }
 }
 
+/// The data files that the actions of a trace refer to, like buffer contents
+/// and shader sources.
+#[derive(Debug)]
+pub enum TraceData {
+    /// Files in the directory of a RON trace.
+    Directory(PathBuf),
+    /// Files stored in a binary trace.
+    Binary(HashMap<String, Vec<u8>>),
+}
+
+impl TraceData {
+    pub fn read(&self, name: &str) -> Vec<u8> {
+        match *self {
+            Self::Directory(ref dir) => fs::read(dir.join(name)).unwrap(),
+            Self::Binary(ref data) => data[name].clone(),
+        }
+    }
+}
+
+/// Loads a trace, which is either a directory with a RON trace or a binary
+/// trace file.
+pub fn load_trace(path: &Path) -> std::io::Result<(Vec<trace::Action<'static>>, TraceData)> {
+    if path.is_dir() {
+        let file = fs::File::open(path.join(trace::FILE_NAME))?;
+        let actions = ron::de::from_reader(file)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+        Ok((actions, TraceData::Directory(path.to_path_buf())))
+    } else {
+        let trace = trace::BinaryTrace::load(path)?;
+        Ok((trace.actions, TraceData::Binary(trace.data)))
+    }
+}
+
+/// Writes a trace in the format picked by the extension of `path`, along with
+/// the data files that its actions refer to.
+pub fn save_trace(
+    path: &Path,
+    actions: Vec<trace::Action<'static>>,
+    trace_data: &TraceData,
+) -> std::io::Result<()> {
+    use trace::Action;
+
+    let format = trace::TraceFormat::from_path(path);
+    if format == trace::TraceFormat::Ron {
+        fs::create_dir_all(path)?;
+    }
+    let mut trace = trace::Trace::new(path, format)?;
+    for mut action in actions {
+        match action {
+            Action::CreateShaderModule { ref mut data, .. }
+            | Action::WriteBuffer { ref mut data, .. }
+            | Action::WriteTexture { ref mut data, .. } => {
+                let kind = Path::new(data.as_str())
+                    .extension()
+                    .and_then(|kind| kind.to_str())
+                    .unwrap_or("bin")
+                    .to_string();
+                *data = trace.make_binary(&kind, &trace_data.read(data));
+            }
+            _ => {}
+        }
+        trace.add(action);
+    }
+    Ok(())
+}
+
 pub trait GlobalPlay {
     fn encode_commands<A: wgc::hal_api::HalApi>(
         &self,
@@ -52,7 +125,7 @@ pub trait GlobalPlay {
         &self,
         device: wgc::id::DeviceId,
         action: trace::Action,
-        dir: &Path,
+        trace_data: &TraceData,
         comb_manager: &mut wgc::identity::IdentityManager,
     );
 }
@@ -94,6 +167,16 @@ impl GlobalPlay for wgc::global::Global<IdentityPassThroughFactory> {
                 } => self
                     .command_encoder_clear_texture::<A>(encoder, dst, &subresource_range)
                     .unwrap(),
+                trace::Command::TransitionResources {
+                    buffer_transitions,
+                    texture_transitions,
+                } => self
+                    .command_encoder_transition_resources::<A>(
+                        encoder,
+                        &buffer_transitions,
+                        &texture_transitions,
+                    )
+                    .unwrap(),
                 trace::Command::WriteTimestamp {
                     query_set_id,
                     query_index,
@@ -153,6 +236,11 @@ impl GlobalPlay for wgc::global::Global<IdentityPassThroughFactory> {
                     )
                     .unwrap();
                 }
+                trace::Command::BuildAccelerationStructuresUnsafeTlas { blas, tlas } => self
+                    .command_encoder_build_acceleration_structures_unsafe_tlas::<A>(
+                        encoder, &blas, &tlas,
+                    )
+                    .unwrap(),
             }
         }
         let (cmd_buf, error) = self
@@ -167,7 +255,7 @@ impl GlobalPlay for wgc::global::Global<IdentityPassThroughFactory> {
         &self,
         device: wgc::id::DeviceId,
         action: trace::Action,
-        dir: &Path,
+        trace_data: &TraceData,
         comb_manager: &mut wgc::identity::IdentityManager,
     ) {
         use wgc::device::trace::Action;
@@ -232,6 +320,66 @@ impl GlobalPlay for wgc::global::Global<IdentityPassThroughFactory> {
             Action::DestroySampler(id) => {
                 self.sampler_drop::<A>(id);
             }
+            Action::CreateHeap(id, desc) => {
+                self.device_maintain_ids::<A>(device).unwrap();
+                let (_, error) = self.device_create_heap::<A>(device, &desc, id);
+                if let Some(e) = error {
+                    panic!("{e}");
+                }
+            }
+            Action::DestroyHeap(id) => {
+                self.heap_drop::<A>(id);
+            }
+            Action::CreateExternalSemaphore(id, desc) => {
+                self.device_maintain_ids::<A>(device).unwrap();
+                let (_, error) = self.device_create_external_semaphore::<A>(device, &desc, id);
+                if let Some(e) = error {
+                    panic!("{e}");
+                }
+            }
+            Action::DestroyExternalSemaphore(id) => {
+                self.external_semaphore_drop::<A>(id);
+            }
+            Action::CreateBufferInHeap {
+                id,
+                desc,
+                heap,
+                offset,
+            } => {
+                self.device_maintain_ids::<A>(device).unwrap();
+                let (_, error) =
+                    self.device_create_buffer_in_heap::<A>(device, &desc, heap, offset, id);
+                if let Some(e) = error {
+                    panic!("{e}");
+                }
+            }
+            Action::CreateTextureInHeap {
+                id,
+                desc,
+                heap,
+                offset,
+            } => {
+                self.device_maintain_ids::<A>(device).unwrap();
+                let (_, error) =
+                    self.device_create_texture_in_heap::<A>(device, &desc, heap, offset, id);
+                if let Some(e) = error {
+                    panic!("{e}");
+                }
+            }
+            Action::CreateSparseBuffer(id, desc) => {
+                self.device_maintain_ids::<A>(device).unwrap();
+                let (_, error) = self.device_create_sparse_buffer::<A>(device, &desc, id);
+                if let Some(e) = error {
+                    panic!("{e}");
+                }
+            }
+            Action::CreateSparseTexture(id, desc) => {
+                self.device_maintain_ids::<A>(device).unwrap();
+                let (_, error) = self.device_create_sparse_texture::<A>(device, &desc, id);
+                if let Some(e) = error {
+                    panic!("{e}");
+                }
+            }
             Action::GetSurfaceTexture { id, parent_id } => {
                 self.device_maintain_ids::<A>(device).unwrap();
                 self.surface_get_current_texture::<A>(parent_id, id)
@@ -270,7 +418,7 @@ impl GlobalPlay for wgc::global::Global<IdentityPassThroughFactory> {
             }
             Action::CreateShaderModule { id, desc, data } => {
                 log::info!("Creating shader from {}", data);
-                let code = fs::read_to_string(dir.join(&data)).unwrap();
+                let code = String::from_utf8(trace_data.read(&data)).unwrap();
                 let source = if data.ends_with(".wgsl") {
                     wgc::pipeline::ShaderModuleSource::Wgsl(Cow::Owned(code.clone()))
                 } else if data.ends_with(".ron") {
@@ -331,6 +479,16 @@ impl GlobalPlay for wgc::global::Global<IdentityPassThroughFactory> {
             Action::DestroyRenderPipeline(id) => {
                 self.render_pipeline_drop::<A>(id);
             }
+            Action::CreatePipelineCache { id, desc } => {
+                let (_, error) =
+                    unsafe { self.device_create_pipeline_cache::<A>(device, &desc, id) };
+                if let Some(e) = error {
+                    panic!("{e}");
+                }
+            }
+            Action::DestroyPipelineCache(id) => {
+                self.pipeline_cache_drop::<A>(id);
+            }
             Action::CreateRenderBundle { id, desc, base } => {
                 let bundle =
                     wgc::command::RenderBundleEncoder::new(&desc, device, Some(base)).unwrap();
@@ -346,6 +504,20 @@ impl GlobalPlay for wgc::global::Global<IdentityPassThroughFactory> {
             Action::DestroyRenderBundle(id) => {
                 self.render_bundle_drop::<A>(id);
             }
+            Action::CreateComputeBundle { id, desc, base } => {
+                let bundle = wgc::command::ComputeBundleEncoder::new(&desc, device, Some(base));
+                let (_, error) = self.compute_bundle_encoder_finish::<A>(
+                    bundle,
+                    &wgt::ComputeBundleDescriptor { label: desc.label },
+                    id,
+                );
+                if let Some(e) = error {
+                    panic!("{e}");
+                }
+            }
+            Action::DestroyComputeBundle(id) => {
+                self.compute_bundle_drop::<A>(id);
+            }
             Action::CreateQuerySet { id, desc } => {
                 self.device_maintain_ids::<A>(device).unwrap();
                 let (_, error) = self.device_create_query_set::<A>(device, &desc, id);
@@ -356,13 +528,33 @@ impl GlobalPlay for wgc::global::Global<IdentityPassThroughFactory> {
             Action::DestroyQuerySet(id) => {
                 self.query_set_drop::<A>(id);
             }
+            Action::CreateBlas { id, desc, sizes } => {
+                self.device_maintain_ids::<A>(device).unwrap();
+                let (_, _, error) = self.device_create_blas::<A>(device, &desc, sizes, id);
+                if let Some(e) = error {
+                    panic!("{e}");
+                }
+            }
+            Action::DestroyBlas(id) => {
+                self.blas_drop::<A>(id);
+            }
+            Action::CreateTlas { id, desc } => {
+                self.device_maintain_ids::<A>(device).unwrap();
+                let (_, error) = self.device_create_tlas::<A>(device, &desc, id);
+                if let Some(e) = error {
+                    panic!("{e}");
+                }
+            }
+            Action::DestroyTlas(id) => {
+                self.tlas_drop::<A>(id);
+            }
             Action::WriteBuffer {
                 id,
                 data,
                 range,
                 queued,
             } => {
-                let bin = std::fs::read(dir.join(data)).unwrap();
+                let bin = trace_data.read(&data);
                 let size = (range.end - range.start) as usize;
                 if queued {
                     self.queue_write_buffer::<A>(device, id, range.start, &bin)
@@ -379,17 +571,27 @@ impl GlobalPlay for wgc::global::Global<IdentityPassThroughFactory> {
                 layout,
                 size,
             } => {
-                let bin = std::fs::read(dir.join(data)).unwrap();
+                let bin = trace_data.read(&data);
                 self.queue_write_texture::<A>(device, &to, &bin, &layout, &size)
                     .unwrap();
             }
+            Action::UpdateSparseBindings {
+                buffer_bindings,
+                texture_bindings,
+            } => {
+                self.queue_update_sparse_bindings::<A>(device, &buffer_bindings, &texture_bindings)
+                    .unwrap();
+            }
             Action::Submit(_index, ref commands) if commands.is_empty() => {
                 self.queue_submit::<A>(device, &[]).unwrap();
             }
             Action::Submit(_index, commands) => {
                 let (encoder, error) = self.device_create_command_encoder::<A>(
                     device,
-                    &wgt::CommandEncoderDescriptor { label: None },
+                    &wgt::CommandEncoderDescriptor {
+                        label: None,
+                        reusable: false,
+                    },
                     comb_manager.alloc(device.backend()),
                 );
                 if let Some(e) = error {
@@ -5,18 +5,22 @@
  *  Test requirements:
  *    - all IDs have the backend `Empty`
  *    - all expected buffers have `MAP_READ` usage
- *    - last action is `Submit`
- *    - no swapchain use
+ *    - surfaces are configured before their textures are acquired
+ *
+ *  Surfaces are replaced by offscreen textures, and the presented frames
+ *  can be compared against PNG images.
 !*/
 #![cfg(not(target_arch = "wasm32"))]
 
-use player::{GlobalPlay, IdentityPassThroughFactory};
+use player::{GlobalPlay, IdentityPassThroughFactory, TraceData};
 use std::{
+    collections::HashMap,
     fs::{read_to_string, File},
     io::{Read, Seek, SeekFrom},
     path::{Path, PathBuf},
     slice,
 };
+use wgc::device::trace::Action;
 
 #[derive(serde::Deserialize)]
 struct RawId {
@@ -47,13 +51,37 @@ struct Expectation {
     buffer: RawId,
     offset: wgt::BufferAddress,
     data: ExpectedData,
+    /// Index of the submission after which the expectation is checked,
+    /// instead of the end of the test.
+    #[serde(default)]
+    submission: Option<u64>,
+}
+
+#[derive(serde::Deserialize)]
+struct FrameExpectation {
+    name: String,
+    /// Index of the presented frame, counting from 0.
+    frame: usize,
+    /// PNG image with the expected contents of the frame.
+    image: String,
+    /// Largest difference allowed in each channel.
+    #[serde(default)]
+    tolerance: u8,
 }
 
 #[derive(serde::Deserialize)]
 struct Test<'a> {
     features: wgt::Features,
     expectations: Vec<Expectation>,
-    actions: Vec<wgc::device::trace::Action<'a>>,
+    #[serde(default)]
+    frames: Vec<FrameExpectation>,
+    actions: Vec<Action<'a>>,
+}
+
+/// A surface replaced by offscreen textures.
+struct Surface {
+    config: wgt::SurfaceConfiguration<Vec<wgt::TextureFormat>>,
+    texture: Option<wgc::id::TextureId>,
 }
 
 fn map_callback(status: Result<(), wgc::resource::BufferAccessError>) {
@@ -70,6 +98,7 @@ impl Test<'_> {
             wgt::Backend::Dx12 => "Dx12",
             wgt::Backend::Dx11 => "Dx11",
             wgt::Backend::Gl => "Gl",
+            wgt::Backend::Cpu => "Cpu",
             _ => unreachable!(),
         };
         let string = read_to_string(path).unwrap().replace("Empty", backend_name);
@@ -91,6 +120,7 @@ impl Test<'_> {
                 label: None,
                 features: self.features,
                 limits: wgt::Limits::default(),
+                memory_hints: wgt::MemoryHints::default(),
             },
             None,
             device
@@ -99,13 +129,96 @@ impl Test<'_> {
             panic!("{:?}", e);
         }
 
-        let mut command_buffer_id_manager = wgc::identity::IdentityManager::default();
+        let mut runner = Runner {
+            dir,
+            global,
+            device,
+            command_buffer_id_manager: wgc::identity::IdentityManager::default(),
+            // Frames are read back into buffers past the ones of the test.
+            readback_buffer_index: self
+                .actions
+                .iter()
+                .filter_map(|action| match *action {
+                    Action::CreateBuffer(id, _) => Some(wgc::id::TypedId::unzip(id).0 + 1),
+                    _ => None,
+                })
+                .max()
+                .unwrap_or(0),
+        };
+        let trace_data = TraceData::Directory(dir.to_path_buf());
+        let mut surfaces = HashMap::new();
+        let mut frame = 0;
         println!("\t\t\tRunning...");
         for action in self.actions {
-            wgc::gfx_select!(device => global.process(device, action, dir, &mut command_buffer_id_manager));
+            match action {
+                Action::ConfigureSurface(id, config) => {
+                    surfaces.insert(
+                        id,
+                        Surface {
+                            config,
+                            texture: None,
+                        },
+                    );
+                }
+                Action::GetSurfaceTexture { id, parent_id } => {
+                    let surface = surfaces
+                        .get_mut(&parent_id)
+                        .expect("Surface is not configured");
+                    runner.create_surface_texture(id, &surface.config);
+                    surface.texture = Some(id);
+                }
+                Action::Present(id) => {
+                    let surface = surfaces.get_mut(&id).unwrap();
+                    let texture = surface.texture.take().expect("No texture to present");
+                    for expect in self.frames.iter().filter(|expect| expect.frame == frame) {
+                        runner.check_frame(expect, texture, &surface.config);
+                    }
+                    wgc::gfx_select!(device => global.texture_drop(texture, false));
+                    frame += 1;
+                }
+                Action::DiscardSurfaceTexture(id) => {
+                    if let Some(texture) = surfaces.get_mut(&id).unwrap().texture.take() {
+                        wgc::gfx_select!(device => global.texture_drop(texture, false));
+                    }
+                }
+                Action::Submit(index, _) => {
+                    wgc::gfx_select!(device => global.process(device, action, &trace_data, &mut runner.command_buffer_id_manager));
+                    runner.check_expectations(
+                        self.expectations
+                            .iter()
+                            .filter(|expect| expect.submission == Some(index)),
+                    );
+                }
+                _ => {
+                    wgc::gfx_select!(device => global.process(device, action, &trace_data, &mut runner.command_buffer_id_manager));
+                }
+            }
         }
+        runner.check_expectations(
+            self.expectations
+                .iter()
+                .filter(|expect| expect.submission.is_none()),
+        );
+
+        wgc::gfx_select!(device => global.clear_backend(()));
+    }
+}
+
+struct Runner<'a> {
+    dir: &'a Path,
+    global: &'a wgc::global::Global<IdentityPassThroughFactory>,
+    device: wgc::id::DeviceId,
+    command_buffer_id_manager: wgc::identity::IdentityManager,
+    readback_buffer_index: u32,
+}
+
+impl Runner<'_> {
+    fn check_expectations<'e>(&self, expectations: impl Iterator<Item = &'e Expectation> + Clone) {
+        let global = self.global;
+        let device = self.device;
+        let backend = device.backend();
         println!("\t\t\tMapping...");
-        for expect in &self.expectations {
+        for expect in expectations.clone() {
             let buffer = wgc::id::TypedId::zip(expect.buffer.index, expect.buffer.epoch, backend);
             wgc::gfx_select!(device => global.buffer_map_async(
                 buffer,
@@ -123,7 +236,7 @@ impl Test<'_> {
         println!("\t\t\tWaiting...");
         wgc::gfx_select!(device => global.device_poll(device, wgt::Maintain::Wait)).unwrap();
 
-        for expect in self.expectations {
+        for expect in expectations {
             println!("\t\t\tChecking {}", expect.name);
             let buffer = wgc::id::TypedId::zip(expect.buffer.index, expect.buffer.epoch, backend);
             let (ptr, size) =
@@ -131,17 +244,17 @@ impl Test<'_> {
                     .unwrap();
             let contents = unsafe { slice::from_raw_parts(ptr, size as usize) };
             let expected_data = match expect.data {
-                ExpectedData::Raw(vec) => vec,
-                ExpectedData::File(name, size) => {
+                ExpectedData::Raw(ref vec) => vec.clone(),
+                ExpectedData::File(ref name, size) => {
                     let mut bin = vec![0; size];
-                    let mut file = File::open(dir.join(name)).unwrap();
+                    let mut file = File::open(self.dir.join(name)).unwrap();
                     file.seek(SeekFrom::Start(expect.offset)).unwrap();
                     file.read_exact(&mut bin[..]).unwrap();
 
                     bin
                 }
-                ExpectedData::U64(vec) => vec
-                    .into_iter()
+                ExpectedData::U64(ref vec) => vec
+                    .iter()
                     .flat_map(|u| u.to_ne_bytes().to_vec())
                     .collect::<Vec<u8>>(),
             };
@@ -153,9 +266,165 @@ impl Test<'_> {
                     contents, expected_data
                 );
             }
+            wgc::gfx_select!(device => global.buffer_unmap(buffer)).unwrap();
         }
+    }
 
-        wgc::gfx_select!(device => global.clear_backend(()));
+    /// Creates an offscreen texture standing for the current texture of a
+    /// surface.
+    fn create_surface_texture(
+        &self,
+        id: wgc::id::TextureId,
+        config: &wgt::SurfaceConfiguration<Vec<wgt::TextureFormat>>,
+    ) {
+        let global = self.global;
+        let device = self.device;
+        wgc::gfx_select!(device => global.device_maintain_ids(device)).unwrap();
+        let (_, error) = wgc::gfx_select!(device => global.device_create_texture(
+            device,
+            &wgc::resource::TextureDescriptor {
+                label: Some("surface".into()),
+                size: wgt::Extent3d {
+                    width: config.width,
+                    height: config.height,
+                    depth_or_array_layers: 1,
+                },
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: wgt::TextureDimension::D2,
+                format: config.format,
+                usage: config.usage | wgt::TextureUsages::COPY_SRC,
+                view_formats: config.view_formats.clone(),
+            },
+            id
+        ));
+        if let Some(e) = error {
+            panic!("{e}");
+        }
+    }
+
+    fn check_frame(
+        &mut self,
+        expect: &FrameExpectation,
+        texture: wgc::id::TextureId,
+        config: &wgt::SurfaceConfiguration<Vec<wgt::TextureFormat>>,
+    ) {
+        println!("\t\t\tChecking {}", expect.name);
+        let global = self.global;
+        let device = self.device;
+        let backend = device.backend();
+        let swizzle = match config.format {
+            wgt::TextureFormat::Rgba8Unorm | wgt::TextureFormat::Rgba8UnormSrgb => false,
+            wgt::TextureFormat::Bgra8Unorm | wgt::TextureFormat::Bgra8UnormSrgb => true,
+            other => panic!("Unsupported surface format {other:?}"),
+        };
+        let row_size = config.width * 4;
+        let bytes_per_row = wgt::math::align_to(row_size, wgt::COPY_BYTES_PER_ROW_ALIGNMENT);
+        let size = bytes_per_row as wgt::BufferAddress * config.height as wgt::BufferAddress;
+
+        let buffer = wgc::id::TypedId::zip(self.readback_buffer_index, 1, backend);
+        self.readback_buffer_index += 1;
+        let (_, error) = wgc::gfx_select!(device => global.device_create_buffer(
+            device,
+            &wgc::resource::BufferDescriptor {
+                label: Some("frame".into()),
+                size,
+                usage: wgt::BufferUsages::MAP_READ | wgt::BufferUsages::COPY_DST,
+                mapped_at_creation: false,
+            },
+            buffer
+        ));
+        if let Some(e) = error {
+            panic!("{e}");
+        }
+        let (encoder, error) = wgc::gfx_select!(device => global.device_create_command_encoder(
+            device,
+            &wgt::CommandEncoderDescriptor {
+                label: None,
+                reusable: false,
+            },
+            self.command_buffer_id_manager.alloc(backend)
+        ));
+        if let Some(e) = error {
+            panic!("{e}");
+        }
+        let command_buffer = wgc::gfx_select!(device => global.encode_commands(
+            encoder,
+            vec![wgc::device::trace::Command::CopyTextureToBuffer {
+                src: wgc::command::ImageCopyTexture {
+                    texture,
+                    mip_level: 0,
+                    origin: wgt::Origin3d::ZERO,
+                    aspect: wgt::TextureAspect::All,
+                },
+                dst: wgc::command::ImageCopyBuffer {
+                    buffer,
+                    layout: wgt::ImageDataLayout {
+                        offset: 0,
+                        bytes_per_row: Some(bytes_per_row),
+                        rows_per_image: None,
+                    },
+                },
+                size: wgt::Extent3d {
+                    width: config.width,
+                    height: config.height,
+                    depth_or_array_layers: 1,
+                },
+            }]
+        ));
+        wgc::gfx_select!(device => global.queue_submit(device, &[command_buffer])).unwrap();
+        wgc::gfx_select!(device => global.buffer_map_async(
+            buffer,
+            0..size,
+            wgc::resource::BufferMapOperation {
+                host: wgc::device::HostMap::Read,
+                callback: wgc::resource::BufferMapCallback::from_rust(Box::new(map_callback)),
+            }
+        ))
+        .unwrap();
+        wgc::gfx_select!(device => global.device_poll(device, wgt::Maintain::Wait)).unwrap();
+        let (ptr, size) =
+            wgc::gfx_select!(device => global.buffer_get_mapped_range(buffer, 0, None)).unwrap();
+        let contents = unsafe { slice::from_raw_parts(ptr, size as usize) };
+        let mut frame = Vec::with_capacity((row_size * config.height) as usize);
+        for row in contents.chunks(bytes_per_row as usize) {
+            for pixel in row[..row_size as usize].chunks(4) {
+                if swizzle {
+                    frame.extend([pixel[2], pixel[1], pixel[0], pixel[3]]);
+                } else {
+                    frame.extend_from_slice(pixel);
+                }
+            }
+        }
+        wgc::gfx_select!(device => global.buffer_unmap(buffer)).unwrap();
+        wgc::gfx_select!(device => global.buffer_drop(buffer, false));
+
+        let decoder = png::Decoder::new(File::open(self.dir.join(&expect.image)).unwrap());
+        let mut reader = decoder.read_info().unwrap();
+        let mut expected_data = vec![0; reader.output_buffer_size()];
+        let info = reader.next_frame(&mut expected_data).unwrap();
+        assert_eq!(
+            (info.width, info.height),
+            (config.width, config.height),
+            "Image size doesn't match the surface"
+        );
+        assert_eq!(
+            (info.color_type, info.bit_depth),
+            (png::ColorType::Rgba, png::BitDepth::Eight),
+            "Only 8-bit RGBA images are supported"
+        );
+
+        let differences = frame
+            .iter()
+            .zip(&expected_data)
+            .filter(|&(&actual, &expected)| actual.abs_diff(expected) > expect.tolerance)
+            .count();
+        if differences != 0 {
+            panic!(
+                "Test expectation is not met!\n{differences} channels of frame {} differ from {} by more than {}",
+                expect.frame, expect.image, expect.tolerance
+            );
+        }
     }
 }
 
@@ -171,6 +440,7 @@ const BACKENDS: &[wgt::Backend] = &[
     wgt::Backend::Dx12,
     wgt::Backend::Dx11,
     wgt::Backend::Gl,
+    wgt::Backend::Cpu,
 ];
 
 impl Corpus {
@@ -353,6 +353,7 @@ fn copy_via_compute(
         layout: Some(&pll),
         module: &sm,
         entry_point: "copy_texture_to_buffer",
+        cache: None,
     });
 
     {
@@ -3,7 +3,7 @@
 
 use std::panic::{catch_unwind, AssertUnwindSafe};
 
-use wgpu::{Adapter, Device, DownlevelFlags, Instance, Queue, Surface};
+use wgpu::{Adapter, Device, DownlevelFlags, Instance, InstanceFlags, Queue, Surface};
 use wgt::{Backends, DeviceDescriptor, DownlevelCapabilities, Features, Limits};
 
 pub mod image;
@@ -24,6 +24,7 @@ async fn initialize_device(
                 label: None,
                 features,
                 limits,
+                memory_hints: wgpu::MemoryHints::MemoryUsage,
             },
             None,
         )
@@ -250,6 +251,9 @@ pub struct TestParameters {
     pub required_downlevel_properties: DownlevelCapabilities,
     pub required_limits: Limits,
 
+    /// Instance flags enabled on top of the debugging ones.
+    pub instance_flags: InstanceFlags,
+
     /// Conditions under which this test should be skipped.
     pub skips: Vec<FailureCase>,
 
@@ -263,6 +267,7 @@ impl Default for TestParameters {
             required_features: Features::empty(),
             required_downlevel_properties: lowest_downlevel_properties(),
             required_limits: Limits::downlevel_webgl2_defaults(),
+            instance_flags: InstanceFlags::empty(),
             skips: Vec::new(),
             failures: Vec::new(),
         }
@@ -305,6 +310,12 @@ impl TestParameters {
         self
     }
 
+    /// Enable instance flags, like extra validation, for the test.
+    pub fn instance_flags(mut self, instance_flags: InstanceFlags) -> Self {
+        self.instance_flags |= instance_flags;
+        self
+    }
+
     /// Mark the test as always failing, but not to be skipped.
     pub fn expect_fail(mut self, when: FailureCase) -> Self {
         self.failures.push(when);
@@ -327,7 +338,7 @@ pub fn initialize_test(parameters: TestParameters, test_function: impl FnOnce(Te
 
     let _test_guard = isolation::OneTestPerProcessGuard::new();
 
-    let (adapter, _surface_guard) = initialize_adapter();
+    let (adapter, _surface_guard) = initialize_adapter_with_flags(parameters.instance_flags);
 
     let adapter_info = adapter.get_info();
 
@@ -450,7 +461,15 @@ pub fn initialize_test(parameters: TestParameters, test_function: impl FnOnce(Te
 }
 
 pub fn initialize_adapter() -> (Adapter, Option<SurfaceGuard>) {
-    let instance = initialize_instance();
+    initialize_adapter_with_flags(InstanceFlags::empty())
+}
+
+/// Initialize an adapter from an instance with `instance_flags` enabled on
+/// top of the debugging ones.
+pub fn initialize_adapter_with_flags(
+    instance_flags: InstanceFlags,
+) -> (Adapter, Option<SurfaceGuard>) {
+    let instance = initialize_instance_with_flags(instance_flags);
     let surface_guard: Option<SurfaceGuard>;
     let compatible_surface;
 
@@ -512,12 +531,16 @@ pub fn initialize_adapter() -> (Adapter, Option<SurfaceGuard>) {
 }
 
 pub fn initialize_instance() -> Instance {
+    initialize_instance_with_flags(InstanceFlags::empty())
+}
+
+pub fn initialize_instance_with_flags(instance_flags: InstanceFlags) -> Instance {
     let backends = wgpu::util::backend_bits_from_env().unwrap_or_else(Backends::all);
     let dx12_shader_compiler = wgpu::util::dx12_shader_compiler_from_env().unwrap_or_default();
     let gles_minor_version = wgpu::util::gles_minor_version_from_env().unwrap_or_default();
     Instance::new(wgpu::InstanceDescriptor {
         backends,
-        flags: wgpu::InstanceFlags::debugging().with_env(),
+        flags: wgpu::InstanceFlags::debugging().with_env() | instance_flags,
         dx12_shader_compiler,
         gles_minor_version,
     })
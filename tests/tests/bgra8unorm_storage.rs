@@ -98,10 +98,13 @@ fn bgra8unorm_storage() {
             layout: Some(&pl),
             entry_point: "main",
             module: &module,
+            cache: None,
         });
 
-        let mut encoder =
-            device.create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: None,
+            reusable: false,
+        });
 
         {
             let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
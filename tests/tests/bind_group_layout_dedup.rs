@@ -87,6 +87,7 @@ fn bind_group_layout_deduplication() {
             depth_stencil: None,
             multiview: None,
             multisample: wgpu::MultisampleState::default(),
+            cache: None,
         };
 
         let pipeline = ctx.device.create_render_pipeline(&desc);
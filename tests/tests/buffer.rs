@@ -134,7 +134,10 @@ fn test_map_offset() {
 
         let mut encoder = ctx
             .device
-            .create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                label: None,
+                reusable: false,
+            });
 
         encoder.copy_buffer_to_buffer(&write_buf, 0, &read_buf, 0, 256);
 
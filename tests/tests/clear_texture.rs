@@ -326,6 +326,25 @@ fn clear_texture_tests(ctx: &TestingContext, formats: &[wgpu::TextureFormat]) {
     }
 }
 
+fn clear_volume_texture_tests(ctx: &TestingContext, formats: &[wgpu::TextureFormat]) {
+    for &format in formats {
+        // Small slices are cleared several at a time, large slices one by one
+        // in multiple row chunks.
+        for (width, height, depth) in [(64, 64, 64), (512, 512, 3), (256, 4, 100)] {
+            single_texture_clear_test(
+                ctx,
+                format,
+                wgpu::Extent3d {
+                    width,
+                    height,
+                    depth_or_array_layers: depth,
+                },
+                wgpu::TextureDimension::D3,
+            );
+        }
+    }
+}
+
 #[test]
 #[wasm_bindgen_test]
 fn clear_texture_uncompressed_gles_compat() {
@@ -431,3 +450,27 @@ fn clear_texture_etc2() {
         },
     )
 }
+
+#[test]
+#[wasm_bindgen_test]
+fn clear_texture_volume() {
+    initialize_test(
+        TestParameters::default()
+            .skip(FailureCase::webgl2())
+            .features(wgpu::Features::CLEAR_TEXTURE)
+            .limits(wgpu::Limits {
+                max_texture_dimension_3d: 512,
+                ..wgpu::Limits::downlevel_webgl2_defaults()
+            }),
+        |ctx| {
+            clear_volume_texture_tests(
+                &ctx,
+                &[
+                    wgpu::TextureFormat::R8Unorm,
+                    wgpu::TextureFormat::Rgba8Unorm,
+                    wgpu::TextureFormat::Rgba32Float,
+                ],
+            );
+        },
+    )
+}
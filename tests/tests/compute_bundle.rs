@@ -0,0 +1,136 @@
+use wgpu_test::{fail, initialize_test, TestParameters, TestingContext};
+
+const SHADER: &str = "
+@group(0) @binding(0)
+var<storage, read_write> output: array<u32>;
+
+@compute @workgroup_size(1)
+fn main(@builtin(workgroup_id) id: vec3<u32>) {
+    output[id.x] = id.x + 1u;
+}
+";
+
+struct Resources {
+    pipeline: wgpu::ComputePipeline,
+    bind_group: wgpu::BindGroup,
+    output: wgpu::Buffer,
+}
+
+impl Resources {
+    fn new(ctx: &TestingContext) -> Self {
+        let module = ctx
+            .device
+            .create_shader_module(wgpu::ShaderModuleDescriptor {
+                label: None,
+                source: wgpu::ShaderSource::Wgsl(SHADER.into()),
+            });
+        let pipeline = ctx
+            .device
+            .create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+                label: None,
+                layout: None,
+                module: &module,
+                entry_point: "main",
+                cache: None,
+            });
+        let output = ctx.device.create_buffer(&wgpu::BufferDescriptor {
+            label: None,
+            size: 16,
+            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC,
+            mapped_at_creation: false,
+        });
+        let bind_group = ctx.device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: None,
+            layout: &pipeline.get_bind_group_layout(0),
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: output.as_entire_binding(),
+            }],
+        });
+        Self {
+            pipeline,
+            bind_group,
+            output,
+        }
+    }
+
+    /// Execute `bundles` in a single compute pass and return the output values.
+    fn run(&self, ctx: &TestingContext, bundles: &[&wgpu::ComputeBundle]) -> [u32; 4] {
+        let readback = ctx.device.create_buffer(&wgpu::BufferDescriptor {
+            label: None,
+            size: 16,
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+        let mut encoder = ctx
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+        {
+            let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor::default());
+            pass.execute_bundles(bundles.iter().copied());
+        }
+        encoder.copy_buffer_to_buffer(&self.output, 0, &readback, 0, 16);
+        ctx.queue.submit(Some(encoder.finish()));
+
+        let slice = readback.slice(..);
+        slice.map_async(wgpu::MapMode::Read, |_| ());
+        ctx.device.poll(wgpu::Maintain::Wait);
+        let data = slice.get_mapped_range();
+        *bytemuck::from_bytes(&data)
+    }
+}
+
+fn parameters() -> TestParameters {
+    TestParameters::default()
+        .downlevel_flags(wgpu::DownlevelFlags::COMPUTE_SHADERS)
+        .limits(wgpu::Limits::downlevel_defaults())
+}
+
+fn create_bundle_encoder(ctx: &TestingContext) -> wgpu::ComputeBundleEncoder<'_> {
+    ctx.device
+        .create_compute_bundle_encoder(&wgpu::ComputeBundleEncoderDescriptor {
+            label: Some("bundle"),
+        })
+}
+
+#[test]
+fn compute_bundle_dispatches() {
+    initialize_test(parameters(), |ctx| {
+        let resources = Resources::new(&ctx);
+
+        let mut encoder = create_bundle_encoder(&ctx);
+        encoder.set_pipeline(&resources.pipeline);
+        encoder.set_bind_group(0, &resources.bind_group, &[]);
+        encoder.dispatch_workgroups(4, 1, 1);
+        let bundle = encoder.finish(&wgpu::ComputeBundleDescriptor { label: None });
+
+        assert_eq!(resources.run(&ctx, &[&bundle]), [1, 2, 3, 4]);
+    })
+}
+
+#[test]
+fn compute_bundle_state_does_not_leak_into_pass() {
+    initialize_test(parameters(), |ctx| {
+        let resources = Resources::new(&ctx);
+
+        let mut encoder = create_bundle_encoder(&ctx);
+        encoder.set_pipeline(&resources.pipeline);
+        encoder.set_bind_group(0, &resources.bind_group, &[]);
+        encoder.dispatch_workgroups(1, 1, 1);
+        let bundle = encoder.finish(&wgpu::ComputeBundleDescriptor { label: None });
+
+        // The pipeline and bind group set inside the bundle are reset once it has executed,
+        // so the dispatch after it has no pipeline to run.
+        fail(&ctx.device, || {
+            let mut encoder = ctx
+                .device
+                .create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+            {
+                let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor::default());
+                pass.execute_bundles(Some(&bundle));
+                pass.dispatch_workgroups(1, 1, 1);
+            }
+            encoder.finish()
+        });
+    })
+}
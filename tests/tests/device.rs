@@ -436,6 +436,7 @@ fn device_destroy_then_more() {
                         multisample: wgpu::MultisampleState::default(),
                         fragment: None,
                         multiview: None,
+                        cache: None,
                     });
             });
 
@@ -447,6 +448,7 @@ fn device_destroy_then_more() {
                         layout: None,
                         module: &shader_module,
                         entry_point: "",
+                        cache: None,
                     });
             });
 
@@ -464,3 +466,42 @@ fn device_destroy_then_more() {
         },
     )
 }
+
+#[test]
+fn device_lost_callback_on_destroy() {
+    initialize_test(TestParameters::default(), |ctx| {
+        let (sender, receiver) = std::sync::mpsc::channel();
+        ctx.device.set_device_lost_callback(move |reason, message| {
+            sender.send((reason, message)).unwrap();
+        });
+
+        ctx.device.destroy();
+        // Destroying again must not report the loss a second time.
+        ctx.device.destroy();
+
+        let (reason, _message) = receiver
+            .try_recv()
+            .expect("device lost callback was not called");
+        assert_eq!(reason, wgpu::DeviceLostReason::Destroyed);
+        assert!(receiver.try_recv().is_err());
+    })
+}
+
+#[test]
+fn device_lost_callback_on_drop() {
+    initialize_test(TestParameters::default(), |ctx| {
+        let (device, _) =
+            pollster::block_on(ctx.adapter.request_device(&Default::default(), None)).unwrap();
+
+        let (sender, receiver) = std::sync::mpsc::channel();
+        device.set_device_lost_callback(move |reason, message| {
+            sender.send((reason, message)).unwrap();
+        });
+        drop(device);
+
+        let (reason, _message) = receiver
+            .try_recv()
+            .expect("device lost callback was not called");
+        assert_eq!(reason, wgpu::DeviceLostReason::Unknown);
+    })
+}
@@ -0,0 +1,55 @@
+use wgpu_test::{fail, initialize_test, valid, TestParameters};
+
+fn create_external_semaphore(device: &wgpu::Device) -> wgpu::ExternalSemaphore {
+    device.create_external_semaphore(&wgpu::ExternalSemaphoreDescriptor {
+        label: Some("external semaphore test"),
+    })
+}
+
+#[test]
+fn external_semaphore_requires_feature() {
+    initialize_test(TestParameters::default(), |ctx| {
+        fail(&ctx.device, || create_external_semaphore(&ctx.device));
+    })
+}
+
+#[test]
+fn external_semaphore_signal_then_wait() {
+    initialize_test(
+        TestParameters::default().features(wgpu::Features::EXTERNAL_SEMAPHORES),
+        |ctx| {
+            let semaphore = valid(&ctx.device, || create_external_semaphore(&ctx.device));
+            let buffer = ctx.device.create_buffer(&wgpu::BufferDescriptor {
+                label: None,
+                size: 16,
+                usage: wgpu::BufferUsages::COPY_DST,
+                mapped_at_creation: false,
+            });
+
+            let mut encoder = ctx
+                .device
+                .create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+            encoder.clear_buffer(&buffer, 0, None);
+            ctx.queue
+                .submit_with_sync(Some(encoder.finish()), &[], &[(&semaphore, 1)]);
+
+            // The second submission can only start once the first one has
+            // signaled the semaphore.
+            let mut encoder = ctx
+                .device
+                .create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+            encoder.clear_buffer(&buffer, 0, None);
+            let index = ctx.queue.submit_with_sync(
+                Some(encoder.finish()),
+                &[(&semaphore, 1)],
+                &[(&semaphore, 2)],
+            );
+
+            // Dropping the semaphore defers its destruction until the
+            // submissions using it are done.
+            drop(semaphore);
+            ctx.device
+                .poll(wgpu::Maintain::WaitForSubmissionIndex(index));
+        },
+    );
+}
@@ -0,0 +1,282 @@
+use wgpu::util::DeviceExt;
+use wgpu_test::{fail, initialize_test, valid, TestParameters, TestingContext};
+
+const SIZE: u32 = 4;
+
+const SHADER: &str = "
+@vertex
+fn vs_main(@location(0) position: vec2<f32>) -> @builtin(position) vec4<f32> {
+    return vec4<f32>(position, 0.0, 1.0);
+}
+
+@fragment
+fn fs_main() -> @location(0) vec4<f32> {
+    return vec4<f32>(1.0);
+}
+";
+
+const FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::R8Unorm;
+
+/// A triangle covering the whole target.
+const VERTICES: [[f32; 2]; 3] = [[-1.0, -1.0], [3.0, -1.0], [-1.0, 3.0]];
+
+fn parameters() -> TestParameters {
+    TestParameters::default().instance_flags(wgpu::InstanceFlags::INDEX_RANGE_VALIDATION)
+}
+
+struct Scene {
+    pipeline: wgpu::RenderPipeline,
+    vertices: wgpu::Buffer,
+    indices: wgpu::Buffer,
+    texture: wgpu::Texture,
+    view: wgpu::TextureView,
+}
+
+impl Scene {
+    fn new(ctx: &TestingContext, indices: &[u16]) -> Self {
+        let module = ctx
+            .device
+            .create_shader_module(wgpu::ShaderModuleDescriptor {
+                label: None,
+                source: wgpu::ShaderSource::Wgsl(SHADER.into()),
+            });
+        let pipeline = ctx
+            .device
+            .create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+                label: None,
+                layout: None,
+                vertex: wgpu::VertexState {
+                    module: &module,
+                    entry_point: "vs_main",
+                    buffers: &[wgpu::VertexBufferLayout {
+                        array_stride: 8,
+                        step_mode: wgpu::VertexStepMode::Vertex,
+                        attributes: &wgpu::vertex_attr_array![0 => Float32x2],
+                    }],
+                },
+                primitive: wgpu::PrimitiveState::default(),
+                depth_stencil: None,
+                multisample: wgpu::MultisampleState::default(),
+                fragment: Some(wgpu::FragmentState {
+                    module: &module,
+                    entry_point: "fs_main",
+                    targets: &[Some(FORMAT.into())],
+                }),
+                multiview: None,
+                cache: None,
+            });
+        let vertices = ctx
+            .device
+            .create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: None,
+                contents: &VERTICES
+                    .iter()
+                    .flatten()
+                    .flat_map(|f| f.to_ne_bytes())
+                    .collect::<Vec<u8>>(),
+                usage: wgpu::BufferUsages::VERTEX,
+            });
+        let indices = ctx
+            .device
+            .create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: None,
+                contents: &indices
+                    .iter()
+                    .flat_map(|i| i.to_ne_bytes())
+                    .collect::<Vec<u8>>(),
+                usage: wgpu::BufferUsages::INDEX | wgpu::BufferUsages::COPY_DST,
+            });
+        let texture = ctx.device.create_texture(&wgpu::TextureDescriptor {
+            label: None,
+            size: wgpu::Extent3d {
+                width: SIZE,
+                height: SIZE,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: FORMAT,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+            view_formats: &[],
+        });
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        Self {
+            pipeline,
+            vertices,
+            indices,
+            texture,
+            view,
+        }
+    }
+
+    /// Record a render pass running `draw` into `encoder`.
+    fn record<'a>(
+        &'a self,
+        encoder: &'a mut wgpu::CommandEncoder,
+        draw: impl FnOnce(&mut wgpu::RenderPass<'a>),
+    ) {
+        let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: None,
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view: &self.view,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+                    store: wgpu::StoreOp::Store,
+                },
+            })],
+            depth_stencil_attachment: None,
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        });
+        pass.set_pipeline(&self.pipeline);
+        pass.set_vertex_buffer(0, self.vertices.slice(..));
+        pass.set_index_buffer(self.indices.slice(..), wgpu::IndexFormat::Uint16);
+        draw(&mut pass);
+    }
+
+    /// Record a render pass running `draw` into a new encoder.
+    fn encode(
+        &self,
+        ctx: &TestingContext,
+        draw: impl FnOnce(&mut wgpu::RenderPass<'_>),
+    ) -> wgpu::CommandEncoder {
+        let mut encoder = ctx
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+        self.record(&mut encoder, draw);
+        encoder
+    }
+
+    /// Submit `encoder`, and return the first texel of the target.
+    fn first_texel(&self, ctx: &TestingContext, mut encoder: wgpu::CommandEncoder) -> u8 {
+        let readback = ctx.device.create_buffer(&wgpu::BufferDescriptor {
+            label: None,
+            size: (wgpu::COPY_BYTES_PER_ROW_ALIGNMENT * SIZE) as u64,
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+        encoder.copy_texture_to_buffer(
+            self.texture.as_image_copy(),
+            wgpu::ImageCopyBuffer {
+                buffer: &readback,
+                layout: wgpu::ImageDataLayout {
+                    offset: 0,
+                    bytes_per_row: Some(wgpu::COPY_BYTES_PER_ROW_ALIGNMENT),
+                    rows_per_image: None,
+                },
+            },
+            wgpu::Extent3d {
+                width: SIZE,
+                height: SIZE,
+                depth_or_array_layers: 1,
+            },
+        );
+        ctx.queue.submit(Some(encoder.finish()));
+
+        let slice = readback.slice(..);
+        slice.map_async(wgpu::MapMode::Read, |_| ());
+        ctx.device.poll(wgpu::Maintain::Wait);
+        let data = slice.get_mapped_range();
+        data[0]
+    }
+}
+
+#[test]
+fn draw_indexed_within_vertex_buffer() {
+    initialize_test(parameters(), |ctx| {
+        let scene = Scene::new(&ctx, &[0, 1, 2, 1, 2, 3]);
+        let encoder = valid(&ctx.device, || {
+            scene.encode(&ctx, |pass| {
+                pass.draw_indexed(0..3, 0, 0..1);
+                // Index 3 is brought back in range by the base vertex.
+                pass.draw_indexed(3..6, -1, 0..1);
+            })
+        });
+        assert_eq!(scene.first_texel(&ctx, encoder), 255);
+    })
+}
+
+#[test]
+fn draw_indexed_beyond_vertex_buffer() {
+    initialize_test(parameters(), |ctx| {
+        let scene = Scene::new(&ctx, &[0, 1, 2, 1, 2, 3]);
+        fail(&ctx.device, || {
+            scene.encode(&ctx, |pass| pass.draw_indexed(3..6, 0, 0..1));
+        });
+        fail(&ctx.device, || {
+            scene.encode(&ctx, |pass| pass.draw_indexed(0..3, 1, 0..1));
+        });
+        fail(&ctx.device, || {
+            scene.encode(&ctx, |pass| pass.draw_indexed(0..3, -1, 0..1));
+        });
+    })
+}
+
+#[test]
+fn draw_indexed_after_write_buffer() {
+    initialize_test(parameters(), |ctx| {
+        let scene = Scene::new(&ctx, &[0, 1, 2, 0]);
+        valid(&ctx.device, || {
+            scene.encode(&ctx, |pass| pass.draw_indexed(0..3, 0, 0..1));
+        });
+
+        let indices: [u16; 2] = [5, 0];
+        ctx.queue.write_buffer(
+            &scene.indices,
+            4,
+            &indices
+                .iter()
+                .flat_map(|i| i.to_ne_bytes())
+                .collect::<Vec<u8>>(),
+        );
+        fail(&ctx.device, || {
+            scene.encode(&ctx, |pass| pass.draw_indexed(0..3, 0, 0..1));
+        });
+    })
+}
+
+#[test]
+fn draw_indexed_indirect_clamped() {
+    let parameters = parameters().downlevel_flags(
+        wgpu::DownlevelFlags::INDIRECT_EXECUTION | wgpu::DownlevelFlags::COMPUTE_SHADERS,
+    );
+    initialize_test(parameters, |ctx| {
+        let scene = Scene::new(&ctx, &[0, 1, 2, 0, 1, 7]);
+        let args = [
+            wgpu::util::DrawIndexedIndirect {
+                vertex_count: 3,
+                instance_count: 1,
+                base_index: 0,
+                vertex_offset: 0,
+                base_instance: 0,
+            },
+            wgpu::util::DrawIndexedIndirect {
+                vertex_count: 3,
+                instance_count: 1,
+                base_index: 3,
+                vertex_offset: 0,
+                base_instance: 0,
+            },
+        ];
+        let indirect = ctx
+            .device
+            .create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: None,
+                contents: &[args[0].as_bytes(), args[1].as_bytes()].concat(),
+                usage: wgpu::BufferUsages::INDIRECT,
+            });
+
+        for (offset, expected) in [(0, 255), (20, 0)] {
+            let mut encoder = ctx
+                .device
+                .create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+            scene.record(&mut encoder, |pass| {
+                pass.draw_indexed_indirect(&indirect, offset)
+            });
+            // The second draw fetches vertex 7, so it is turned into an empty draw.
+            assert_eq!(scene.first_texel(&ctx, encoder), expected);
+        }
+    })
+}
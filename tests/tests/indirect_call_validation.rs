@@ -0,0 +1,349 @@
+use wgpu::util::DeviceExt;
+use wgpu_test::{initialize_test, TestParameters, TestingContext};
+
+const SIZE: u32 = 4;
+
+const RENDER_SHADER: &str = "
+@vertex
+fn vs_main(@location(0) position: vec2<f32>) -> @builtin(position) vec4<f32> {
+    return vec4<f32>(position, 0.0, 1.0);
+}
+
+@fragment
+fn fs_main() -> @location(0) vec4<f32> {
+    return vec4<f32>(1.0);
+}
+";
+
+const COMPUTE_SHADER: &str = "
+@group(0) @binding(0)
+var<storage, read_write> buffer: array<atomic<u32>>;
+
+@compute @workgroup_size(1)
+fn count_workgroups() {
+    atomicAdd(&buffer[0], 1u);
+}
+
+@compute @workgroup_size(1)
+fn write_args() {
+    atomicStore(&buffer[0], 3u);
+    atomicStore(&buffer[1], 1u);
+    atomicStore(&buffer[2], 1u);
+}
+";
+
+const FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::R8Unorm;
+
+/// A triangle covering the whole target.
+const VERTICES: [[f32; 2]; 3] = [[-1.0, -1.0], [3.0, -1.0], [-1.0, 3.0]];
+
+fn parameters() -> TestParameters {
+    TestParameters::default()
+        .limits(wgpu::Limits::downlevel_defaults())
+        .instance_flags(wgpu::InstanceFlags::VALIDATION_INDIRECT_CALL)
+        .downlevel_flags(
+            wgpu::DownlevelFlags::INDIRECT_EXECUTION | wgpu::DownlevelFlags::COMPUTE_SHADERS,
+        )
+}
+
+fn bytes<T: Copy>(values: &[T], to_bytes: impl Fn(T) -> [u8; 4]) -> Vec<u8> {
+    values.iter().flat_map(|&value| to_bytes(value)).collect()
+}
+
+fn read_u32(ctx: &TestingContext, buffer: &wgpu::Buffer) -> u32 {
+    let readback = ctx.device.create_buffer(&wgpu::BufferDescriptor {
+        label: None,
+        size: 4,
+        usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+        mapped_at_creation: false,
+    });
+    let mut encoder = ctx
+        .device
+        .create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+    encoder.copy_buffer_to_buffer(buffer, 0, &readback, 0, 4);
+    ctx.queue.submit(Some(encoder.finish()));
+
+    let slice = readback.slice(..);
+    slice.map_async(wgpu::MapMode::Read, |_| ());
+    ctx.device.poll(wgpu::Maintain::Wait);
+    let data = slice.get_mapped_range();
+    u32::from_ne_bytes([data[0], data[1], data[2], data[3]])
+}
+
+/// Draw a triangle covering the target with `draw`, and return the first
+/// texel of the target.
+fn draw_first_texel(
+    ctx: &TestingContext,
+    draw: impl for<'a> FnOnce(&mut wgpu::RenderPass<'a>, &'a wgpu::Buffer),
+    args: &[u8],
+) -> u8 {
+    let module = ctx
+        .device
+        .create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: None,
+            source: wgpu::ShaderSource::Wgsl(RENDER_SHADER.into()),
+        });
+    let pipeline = ctx
+        .device
+        .create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: None,
+            layout: None,
+            vertex: wgpu::VertexState {
+                module: &module,
+                entry_point: "vs_main",
+                buffers: &[wgpu::VertexBufferLayout {
+                    array_stride: 8,
+                    step_mode: wgpu::VertexStepMode::Vertex,
+                    attributes: &wgpu::vertex_attr_array![0 => Float32x2],
+                }],
+            },
+            primitive: wgpu::PrimitiveState::default(),
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState::default(),
+            fragment: Some(wgpu::FragmentState {
+                module: &module,
+                entry_point: "fs_main",
+                targets: &[Some(FORMAT.into())],
+            }),
+            multiview: None,
+            cache: None,
+        });
+    let vertices = ctx
+        .device
+        .create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: None,
+            contents: &bytes(
+                &VERTICES.iter().flatten().copied().collect::<Vec<f32>>(),
+                f32::to_ne_bytes,
+            ),
+            usage: wgpu::BufferUsages::VERTEX,
+        });
+    let indices = ctx
+        .device
+        .create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: None,
+            contents: &bytes(&[0u32, 1, 2], u32::to_ne_bytes),
+            usage: wgpu::BufferUsages::INDEX,
+        });
+    let indirect = ctx
+        .device
+        .create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: None,
+            contents: args,
+            usage: wgpu::BufferUsages::INDIRECT,
+        });
+    let texture = ctx.device.create_texture(&wgpu::TextureDescriptor {
+        label: None,
+        size: wgpu::Extent3d {
+            width: SIZE,
+            height: SIZE,
+            depth_or_array_layers: 1,
+        },
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format: FORMAT,
+        usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+        view_formats: &[],
+    });
+    let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+    let readback = ctx.device.create_buffer(&wgpu::BufferDescriptor {
+        label: None,
+        size: (wgpu::COPY_BYTES_PER_ROW_ALIGNMENT * SIZE) as u64,
+        usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+        mapped_at_creation: false,
+    });
+
+    let mut encoder = ctx
+        .device
+        .create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+    {
+        let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: None,
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view: &view,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+                    store: wgpu::StoreOp::Store,
+                },
+            })],
+            depth_stencil_attachment: None,
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        });
+        pass.set_pipeline(&pipeline);
+        pass.set_vertex_buffer(0, vertices.slice(..));
+        pass.set_index_buffer(indices.slice(..), wgpu::IndexFormat::Uint32);
+        draw(&mut pass, &indirect);
+    }
+    encoder.copy_texture_to_buffer(
+        texture.as_image_copy(),
+        wgpu::ImageCopyBuffer {
+            buffer: &readback,
+            layout: wgpu::ImageDataLayout {
+                offset: 0,
+                bytes_per_row: Some(wgpu::COPY_BYTES_PER_ROW_ALIGNMENT),
+                rows_per_image: None,
+            },
+        },
+        wgpu::Extent3d {
+            width: SIZE,
+            height: SIZE,
+            depth_or_array_layers: 1,
+        },
+    );
+    ctx.queue.submit(Some(encoder.finish()));
+
+    let slice = readback.slice(..);
+    slice.map_async(wgpu::MapMode::Read, |_| ());
+    ctx.device.poll(wgpu::Maintain::Wait);
+    let data = slice.get_mapped_range();
+    data[0]
+}
+
+#[test]
+fn draw_indirect_validated() {
+    initialize_test(parameters(), |ctx| {
+        let draw = |vertex_count, first_vertex, first_instance| {
+            let args = wgpu::util::DrawIndirect {
+                vertex_count,
+                instance_count: 1,
+                base_vertex: first_vertex,
+                base_instance: first_instance,
+            };
+            draw_first_texel(
+                &ctx,
+                |pass, indirect| pass.draw_indirect(indirect, 0),
+                args.as_bytes(),
+            )
+        };
+        assert_eq!(draw(3, 0, 0), 255);
+        // Beyond the vertex buffer.
+        assert_eq!(draw(4, 0, 0), 0);
+        assert_eq!(draw(3, 1, 0), 0);
+        // `INDIRECT_FIRST_INSTANCE` is not enabled.
+        assert_eq!(draw(3, 0, 1), 0);
+    })
+}
+
+#[test]
+fn draw_indexed_indirect_validated() {
+    initialize_test(parameters(), |ctx| {
+        let draw = |index_count, first_index| {
+            let args = wgpu::util::DrawIndexedIndirect {
+                vertex_count: index_count,
+                instance_count: 1,
+                base_index: first_index,
+                vertex_offset: 0,
+                base_instance: 0,
+            };
+            draw_first_texel(
+                &ctx,
+                |pass, indirect| pass.draw_indexed_indirect(indirect, 0),
+                args.as_bytes(),
+            )
+        };
+        assert_eq!(draw(3, 0), 255);
+        // Beyond the index buffer.
+        assert_eq!(draw(4, 0), 0);
+        assert_eq!(draw(3, 1), 0);
+    })
+}
+
+#[test]
+fn dispatch_indirect_validated() {
+    initialize_test(parameters(), |ctx| {
+        let module = ctx
+            .device
+            .create_shader_module(wgpu::ShaderModuleDescriptor {
+                label: None,
+                source: wgpu::ShaderSource::Wgsl(COMPUTE_SHADER.into()),
+            });
+        let pipeline = |entry_point| {
+            ctx.device
+                .create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+                    label: None,
+                    layout: None,
+                    module: &module,
+                    entry_point,
+                    cache: None,
+                })
+        };
+        let count_pipeline = pipeline("count_workgroups");
+        let write_pipeline = pipeline("write_args");
+        let bind_group = |pipeline: &wgpu::ComputePipeline, buffer: &wgpu::Buffer| {
+            ctx.device.create_bind_group(&wgpu::BindGroupDescriptor {
+                label: None,
+                layout: &pipeline.get_bind_group_layout(0),
+                entries: &[wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: buffer.as_entire_binding(),
+                }],
+            })
+        };
+
+        let limit = ctx.device.limits().max_compute_workgroups_per_dimension;
+        for (args, expected) in [([2, 1, 1], 2), ([2, limit + 1, 1], 0), ([0, 1, 1], 0)] {
+            let indirect = ctx
+                .device
+                .create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                    label: None,
+                    contents: &bytes(&args, u32::to_ne_bytes),
+                    usage: wgpu::BufferUsages::INDIRECT,
+                });
+            let counter = ctx.device.create_buffer(&wgpu::BufferDescriptor {
+                label: None,
+                size: 4,
+                usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC,
+                mapped_at_creation: false,
+            });
+            let counter_bind_group = bind_group(&count_pipeline, &counter);
+
+            let mut encoder = ctx
+                .device
+                .create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+            {
+                let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor::default());
+                pass.set_pipeline(&count_pipeline);
+                pass.set_bind_group(0, &counter_bind_group, &[]);
+                pass.dispatch_workgroups_indirect(&indirect, 0);
+            }
+            ctx.queue.submit(Some(encoder.finish()));
+            assert_eq!(read_u32(&ctx, &counter), expected, "{args:?}");
+        }
+
+        // The arguments are written by the previous dispatch of the pass.
+        let indirect = ctx.device.create_buffer(&wgpu::BufferDescriptor {
+            label: None,
+            size: 12,
+            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::INDIRECT,
+            mapped_at_creation: false,
+        });
+        let counter = ctx.device.create_buffer(&wgpu::BufferDescriptor {
+            label: None,
+            size: 4,
+            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC,
+            mapped_at_creation: false,
+        });
+        let write_bind_group = bind_group(&write_pipeline, &indirect);
+        let counter_bind_group = bind_group(&count_pipeline, &counter);
+
+        let mut encoder = ctx
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+        {
+            let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor::default());
+            pass.set_pipeline(&write_pipeline);
+            pass.set_bind_group(0, &write_bind_group, &[]);
+            pass.dispatch_workgroups(1, 1, 1);
+            pass.set_pipeline(&count_pipeline);
+            pass.set_bind_group(0, &counter_bind_group, &[]);
+            pass.dispatch_workgroups_indirect(&indirect, 0);
+            // The bind group is set again after the validation.
+            pass.dispatch_workgroups_indirect(&indirect, 0);
+        }
+        ctx.queue.submit(Some(encoder.finish()));
+        assert_eq!(read_u32(&ctx, &counter), 6);
+    })
+}
@@ -0,0 +1,45 @@
+use wgpu_test::{initialize_test, TestParameters};
+
+#[test]
+fn memory_report_counts_resources() {
+    initialize_test(TestParameters::default(), |ctx| {
+        let before = ctx.device.memory_report();
+
+        let buffer = ctx.device.create_buffer(&wgpu::BufferDescriptor {
+            label: None,
+            size: 256,
+            usage: wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let texture = ctx.device.create_texture(&wgpu::TextureDescriptor {
+            label: None,
+            size: wgpu::Extent3d {
+                width: 16,
+                height: 16,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::Rgba8Unorm,
+            usage: wgpu::TextureUsages::TEXTURE_BINDING,
+            view_formats: &[],
+        });
+
+        let report = ctx.device.memory_report();
+        assert_eq!(report.buffers.count, before.buffers.count + 1);
+        assert_eq!(report.buffers.bytes, before.buffers.bytes + 256);
+        assert_eq!(report.textures.count, before.textures.count + 1);
+        assert_eq!(report.textures.bytes, before.textures.bytes + 16 * 16 * 4);
+        for memory_type in &report.memory_types {
+            assert!(memory_type.used <= memory_type.allocated);
+            assert!((memory_type.heap_index as usize) < report.heaps.len());
+        }
+
+        buffer.destroy();
+        texture.destroy();
+        let report = ctx.device.memory_report();
+        assert_eq!(report.buffers.count, before.buffers.count);
+        assert_eq!(report.textures.count, before.textures.count);
+    })
+}
@@ -49,6 +49,7 @@ fn occlusion_query() {
                 }),
                 multisample: wgpu::MultisampleState::default(),
                 multiview: None,
+                cache: None,
             });
 
         // Create occlusion query set
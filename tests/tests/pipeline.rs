@@ -29,6 +29,7 @@ fn pipeline_default_layout_bad_module() {
                     layout: None,
                     module: &module,
                     entry_point: "doesn't exist",
+                    cache: None,
                 });
 
             pipeline.get_bind_group_layout(0);
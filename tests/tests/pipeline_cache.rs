@@ -0,0 +1,89 @@
+use wgpu_test::{fail, initialize_test, valid, TestParameters};
+
+const SHADER: &str = "
+@compute @workgroup_size(1)
+fn main() {}
+";
+
+fn create_pipeline(
+    device: &wgpu::Device,
+    module: &wgpu::ShaderModule,
+    cache: &wgpu::PipelineCache,
+) -> wgpu::ComputePipeline {
+    device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+        label: Some("pipeline cache test"),
+        layout: None,
+        module,
+        entry_point: "main",
+        cache: Some(cache),
+    })
+}
+
+#[test]
+fn pipeline_cache_round_trip() {
+    initialize_test(
+        TestParameters::default().features(wgpu::Features::PIPELINE_CACHE),
+        |ctx| {
+            let module = ctx
+                .device
+                .create_shader_module(wgpu::ShaderModuleDescriptor {
+                    label: None,
+                    source: wgpu::ShaderSource::Wgsl(SHADER.into()),
+                });
+
+            let cache = unsafe {
+                ctx.device
+                    .create_pipeline_cache(&wgpu::PipelineCacheDescriptor {
+                        label: Some("empty cache"),
+                        data: None,
+                        fallback: false,
+                    })
+            };
+            let _pipeline = create_pipeline(&ctx.device, &module, &cache);
+            let data = cache.get_data().expect("cache data should be retrievable");
+
+            // Data produced on this device must be accepted as-is.
+            let cache = valid(&ctx.device, || unsafe {
+                ctx.device
+                    .create_pipeline_cache(&wgpu::PipelineCacheDescriptor {
+                        label: Some("restored cache"),
+                        data: Some(&data),
+                        fallback: false,
+                    })
+            });
+            let _pipeline = valid(&ctx.device, || {
+                create_pipeline(&ctx.device, &module, &cache)
+            });
+        },
+    );
+}
+
+#[test]
+fn pipeline_cache_rejects_foreign_data() {
+    initialize_test(
+        TestParameters::default().features(wgpu::Features::PIPELINE_CACHE),
+        |ctx| {
+            let garbage = vec![0xAB; 256];
+
+            fail(&ctx.device, || unsafe {
+                ctx.device
+                    .create_pipeline_cache(&wgpu::PipelineCacheDescriptor {
+                        label: None,
+                        data: Some(&garbage),
+                        fallback: false,
+                    })
+            });
+
+            // With fallback enabled, unusable data just results in an empty cache.
+            let cache = valid(&ctx.device, || unsafe {
+                ctx.device
+                    .create_pipeline_cache(&wgpu::PipelineCacheDescriptor {
+                        label: None,
+                        data: Some(&garbage),
+                        fallback: true,
+                    })
+            });
+            assert!(cache.get_data().is_some());
+        },
+    );
+}
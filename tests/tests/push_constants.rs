@@ -0,0 +1,339 @@
+use wgpu_test::{fail, initialize_test, TestParameters, TestingContext};
+
+const SHADER_ONE: &str = "
+var<push_constant> value: f32;
+
+@group(0) @binding(0)
+var<storage, read_write> output: array<u32>;
+
+@compute @workgroup_size(1)
+fn main() {
+    output[0] = u32(value);
+}
+";
+
+const SHADER_TWO: &str = "
+struct Values {
+    first: f32,
+    second: f32,
+}
+
+var<push_constant> values: Values;
+
+@group(0) @binding(0)
+var<storage, read_write> output: array<u32>;
+
+@compute @workgroup_size(1)
+fn main() {
+    output[0] = u32(values.first + values.second);
+}
+";
+
+const RENDER_SHADER: &str = "
+var<push_constant> value: f32;
+
+@vertex
+fn vs_main(@builtin(vertex_index) index: u32) -> @builtin(position) vec4<f32> {
+    let uv = vec2<f32>(f32(index & 1u), f32(index >> 1u));
+    return vec4<f32>(uv * 4.0 - 1.0, 0.0, 1.0);
+}
+
+@fragment
+fn fs_main() -> @location(0) u32 {
+    return u32(value);
+}
+";
+
+fn parameters() -> TestParameters {
+    TestParameters::default()
+        .features(wgpu::Features::PUSH_CONSTANTS)
+        .downlevel_flags(wgpu::DownlevelFlags::COMPUTE_SHADERS)
+        .limits(wgpu::Limits {
+            max_push_constant_size: 8,
+            ..wgpu::Limits::downlevel_defaults()
+        })
+}
+
+struct Pipelines {
+    /// Reads one push constant word.
+    one: wgpu::ComputePipeline,
+    /// Reads two push constant words, with a different pipeline layout.
+    two: wgpu::ComputePipeline,
+    output: wgpu::Buffer,
+    bind_group: wgpu::BindGroup,
+}
+
+impl Pipelines {
+    fn new(ctx: &TestingContext) -> Self {
+        let bind_group_layout =
+            ctx.device
+                .create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                    label: None,
+                    entries: &[wgpu::BindGroupLayoutEntry {
+                        binding: 0,
+                        visibility: wgpu::ShaderStages::COMPUTE,
+                        ty: wgpu::BindingType::Buffer {
+                            ty: wgpu::BufferBindingType::Storage { read_only: false },
+                            has_dynamic_offset: false,
+                            min_binding_size: None,
+                        },
+                        count: None,
+                    }],
+                });
+        let pipeline = |source: &str, size: u32| {
+            let layout = ctx
+                .device
+                .create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                    label: None,
+                    bind_group_layouts: &[&bind_group_layout],
+                    push_constant_ranges: &[wgpu::PushConstantRange {
+                        stages: wgpu::ShaderStages::COMPUTE,
+                        range: 0..size,
+                    }],
+                });
+            let module = ctx
+                .device
+                .create_shader_module(wgpu::ShaderModuleDescriptor {
+                    label: None,
+                    source: wgpu::ShaderSource::Wgsl(source.into()),
+                });
+            ctx.device
+                .create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+                    label: None,
+                    layout: Some(&layout),
+                    module: &module,
+                    entry_point: "main",
+                    cache: None,
+                })
+        };
+        let output = ctx.device.create_buffer(&wgpu::BufferDescriptor {
+            label: None,
+            size: 4,
+            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC,
+            mapped_at_creation: false,
+        });
+        let bind_group = ctx.device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: None,
+            layout: &bind_group_layout,
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: output.as_entire_binding(),
+            }],
+        });
+        Self {
+            one: pipeline(SHADER_ONE, 4),
+            two: pipeline(SHADER_TWO, 8),
+            output,
+            bind_group,
+        }
+    }
+
+    /// Run a compute pass recorded by `record`, and return the output value.
+    fn run(
+        &self,
+        ctx: &TestingContext,
+        record: impl for<'a> FnOnce(&mut wgpu::ComputePass<'a>, &'a Self),
+    ) -> u32 {
+        let readback = ctx.device.create_buffer(&wgpu::BufferDescriptor {
+            label: None,
+            size: 4,
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+        let mut encoder = ctx
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+        {
+            let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor::default());
+            pass.set_bind_group(0, &self.bind_group, &[]);
+            record(&mut pass, self);
+        }
+        encoder.copy_buffer_to_buffer(&self.output, 0, &readback, 0, 4);
+        ctx.queue.submit(Some(encoder.finish()));
+
+        let slice = readback.slice(..);
+        slice.map_async(wgpu::MapMode::Read, |_| ());
+        ctx.device.poll(wgpu::Maintain::Wait);
+        let data = slice.get_mapped_range();
+        u32::from_ne_bytes([data[0], data[1], data[2], data[3]])
+    }
+}
+
+#[test]
+fn push_constants_before_pipeline() {
+    initialize_test(parameters(), |ctx| {
+        let pipelines = Pipelines::new(&ctx);
+        let value = pipelines.run(&ctx, |pass, pipelines| {
+            pass.set_push_constants(0, &42f32.to_ne_bytes());
+            pass.set_pipeline(&pipelines.one);
+            pass.dispatch_workgroups(1, 1, 1);
+        });
+        assert_eq!(value, 42);
+    })
+}
+
+#[test]
+fn push_constants_kept_across_layouts() {
+    initialize_test(parameters(), |ctx| {
+        let pipelines = Pipelines::new(&ctx);
+        let value = pipelines.run(&ctx, |pass, pipelines| {
+            pass.set_pipeline(&pipelines.one);
+            pass.set_push_constants(0, &7f32.to_ne_bytes());
+            // The second word was never set, so it is zero.
+            pass.set_pipeline(&pipelines.two);
+            pass.dispatch_workgroups(1, 1, 1);
+        });
+        assert_eq!(value, 7);
+    })
+}
+
+#[test]
+fn push_constants_before_pipeline_out_of_range() {
+    initialize_test(parameters(), |ctx| {
+        let pipelines = Pipelines::new(&ctx);
+        fail(&ctx.device, || {
+            pipelines.run(&ctx, |pass, pipelines| {
+                pass.set_push_constants(4, &1f32.to_ne_bytes());
+                pass.set_pipeline(&pipelines.one);
+                pass.dispatch_workgroups(1, 1, 1);
+            });
+        });
+    })
+}
+
+#[test]
+fn push_constants_offset_overflow() {
+    initialize_test(parameters(), |ctx| {
+        let pipelines = Pipelines::new(&ctx);
+        fail(&ctx.device, || {
+            pipelines.run(&ctx, |pass, pipelines| {
+                pass.set_pipeline(&pipelines.one);
+                pass.set_push_constants(u32::MAX - 3, &[0; 8]);
+                pass.dispatch_workgroups(1, 1, 1);
+            });
+        });
+    })
+}
+
+/// Draw a full screen triangle into a single texel render pass recorded by
+/// `record`, and return the texel.
+fn render(
+    ctx: &TestingContext,
+    record: impl for<'a> FnOnce(&mut wgpu::RenderPass<'a>, &'a wgpu::RenderPipeline),
+) -> u32 {
+    let layout = ctx
+        .device
+        .create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: None,
+            bind_group_layouts: &[],
+            push_constant_ranges: &[wgpu::PushConstantRange {
+                stages: wgpu::ShaderStages::FRAGMENT,
+                range: 0..4,
+            }],
+        });
+    let module = ctx
+        .device
+        .create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: None,
+            source: wgpu::ShaderSource::Wgsl(RENDER_SHADER.into()),
+        });
+    let pipeline = ctx
+        .device
+        .create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: None,
+            layout: Some(&layout),
+            vertex: wgpu::VertexState {
+                module: &module,
+                entry_point: "vs_main",
+                buffers: &[],
+            },
+            primitive: wgpu::PrimitiveState::default(),
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState::default(),
+            fragment: Some(wgpu::FragmentState {
+                module: &module,
+                entry_point: "fs_main",
+                targets: &[Some(wgpu::TextureFormat::R32Uint.into())],
+            }),
+            multiview: None,
+            cache: None,
+        });
+    let texture = ctx.device.create_texture(&wgpu::TextureDescriptor {
+        label: None,
+        size: wgpu::Extent3d::default(),
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format: wgpu::TextureFormat::R32Uint,
+        usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+        view_formats: &[],
+    });
+    let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+    let readback = ctx.device.create_buffer(&wgpu::BufferDescriptor {
+        label: None,
+        size: 4,
+        usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+        mapped_at_creation: false,
+    });
+
+    let mut encoder = ctx
+        .device
+        .create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+    {
+        let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: None,
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view: &view,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+                    store: wgpu::StoreOp::Store,
+                },
+            })],
+            depth_stencil_attachment: None,
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        });
+        record(&mut pass, &pipeline);
+    }
+    encoder.copy_texture_to_buffer(
+        texture.as_image_copy(),
+        wgpu::ImageCopyBuffer {
+            buffer: &readback,
+            layout: wgpu::ImageDataLayout::default(),
+        },
+        wgpu::Extent3d::default(),
+    );
+    ctx.queue.submit(Some(encoder.finish()));
+
+    let slice = readback.slice(..);
+    slice.map_async(wgpu::MapMode::Read, |_| ());
+    ctx.device.poll(wgpu::Maintain::Wait);
+    let data = slice.get_mapped_range();
+    u32::from_ne_bytes([data[0], data[1], data[2], data[3]])
+}
+
+#[test]
+fn render_push_constants_before_pipeline() {
+    initialize_test(parameters(), |ctx| {
+        let value = render(&ctx, |pass, pipeline| {
+            pass.set_push_constants(wgpu::ShaderStages::FRAGMENT, 0, &42f32.to_ne_bytes());
+            pass.set_pipeline(pipeline);
+            pass.draw(0..3, 0..1);
+        });
+        assert_eq!(value, 42);
+    })
+}
+
+#[test]
+fn render_push_constants_offset_overflow() {
+    initialize_test(parameters(), |ctx| {
+        fail(&ctx.device, || {
+            render(&ctx, |pass, pipeline| {
+                pass.set_push_constants(wgpu::ShaderStages::FRAGMENT, u32::MAX - 3, &[0; 8]);
+                pass.set_pipeline(pipeline);
+                pass.draw(0..3, 0..1);
+            });
+        });
+    })
+}
@@ -1,4 +1,4 @@
-use wgpu_test::{initialize_test, FailureCase, TestParameters};
+use wgpu_test::{initialize_test, FailureCase, TestParameters, TestingContext};
 
 #[test]
 fn drop_failed_timestamp_query_set() {
@@ -24,3 +24,166 @@ fn drop_failed_timestamp_query_set() {
         assert!(pollster::block_on(ctx.device.pop_error_scope()).is_some());
     });
 }
+
+/// Query set, resolve destination and read back buffer of the resolve tests.
+struct OcclusionQueries {
+    query_set: wgpu::QuerySet,
+    query_buffer: wgpu::Buffer,
+    mapping_buffer: wgpu::Buffer,
+}
+
+impl OcclusionQueries {
+    fn new(ctx: &TestingContext, count: u32) -> Self {
+        let query_set = ctx.device.create_query_set(&wgpu::QuerySetDescriptor {
+            label: Some("Query set"),
+            ty: wgpu::QueryType::Occlusion,
+            count,
+        });
+
+        // Fill the destination with garbage, so that we can tell if the
+        // unwritten queries were resolved at all.
+        let size = std::mem::size_of::<u64>() as u64 * count as u64;
+        let query_buffer = ctx.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Query buffer"),
+            size,
+            usage: wgpu::BufferUsages::QUERY_RESOLVE
+                | wgpu::BufferUsages::COPY_SRC
+                | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        ctx.queue
+            .write_buffer(&query_buffer, 0, &vec![0xFF; size as usize]);
+
+        let mapping_buffer = ctx.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Mapping buffer"),
+            size,
+            usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        Self {
+            query_set,
+            query_buffer,
+            mapping_buffer,
+        }
+    }
+
+    /// Records a render pass writing the query at `index`. Nothing is drawn,
+    /// so its result is zero.
+    fn write(&self, ctx: &TestingContext, encoder: &mut wgpu::CommandEncoder, index: u32) {
+        let texture = ctx.device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("Color texture"),
+            size: wgpu::Extent3d {
+                width: 4,
+                height: 4,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::Rgba8Unorm,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+            view_formats: &[],
+        });
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+
+        let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("Render pass"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view: &view,
+                resolve_target: None,
+                ops: wgpu::Operations::default(),
+            })],
+            depth_stencil_attachment: None,
+            timestamp_writes: None,
+            occlusion_query_set: Some(&self.query_set),
+        });
+        render_pass.begin_occlusion_query(index);
+        render_pass.end_occlusion_query();
+    }
+
+    /// Records the resolve of all the queries and their copy to the mapping
+    /// buffer.
+    fn resolve(&self, encoder: &mut wgpu::CommandEncoder) {
+        let size = self.query_buffer.size();
+        encoder.resolve_query_set(
+            &self.query_set,
+            0..(size / std::mem::size_of::<u64>() as u64) as u32,
+            &self.query_buffer,
+            0,
+        );
+        encoder.copy_buffer_to_buffer(&self.query_buffer, 0, &self.mapping_buffer, 0, size);
+    }
+
+    fn read(&self, ctx: &TestingContext) -> Vec<u64> {
+        self.mapping_buffer
+            .slice(..)
+            .map_async(wgpu::MapMode::Read, |_| ());
+        ctx.device.poll(wgpu::Maintain::Wait);
+        let view = self.mapping_buffer.slice(..).get_mapped_range();
+        bytemuck::cast_slice(&view).to_vec()
+    }
+}
+
+#[test]
+fn resolve_unwritten_queries_as_zero() {
+    initialize_test(TestParameters::default(), |ctx| {
+        let queries = OcclusionQueries::new(&ctx, 4);
+
+        let mut encoder = ctx
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+        // Only query 1 gets written.
+        queries.write(&ctx, &mut encoder, 1);
+        queries.resolve(&mut encoder);
+        ctx.queue.submit(Some(encoder.finish()));
+
+        assert_eq!(queries.read(&ctx), [0; 4]);
+    });
+}
+
+#[test]
+fn resolve_queries_of_dropped_encoder_as_zero() {
+    initialize_test(TestParameters::default(), |ctx| {
+        let queries = OcclusionQueries::new(&ctx, 2);
+
+        let mut write_encoder = ctx
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+        queries.write(&ctx, &mut write_encoder, 0);
+
+        let mut resolve_encoder = ctx
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+        queries.resolve(&mut resolve_encoder);
+
+        // The query is never written, since its encoder is never submitted.
+        drop(write_encoder);
+        ctx.queue.submit(Some(resolve_encoder.finish()));
+
+        assert_eq!(queries.read(&ctx), [0; 2]);
+    });
+}
+
+#[test]
+fn resolve_queries_before_their_write_is_submitted() {
+    initialize_test(TestParameters::default(), |ctx| {
+        let queries = OcclusionQueries::new(&ctx, 2);
+
+        let mut write_encoder = ctx
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+        queries.write(&ctx, &mut write_encoder, 0);
+
+        let mut resolve_encoder = ctx
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+        queries.resolve(&mut resolve_encoder);
+
+        // The query is only written after it was resolved.
+        ctx.queue
+            .submit([resolve_encoder.finish(), write_encoder.finish()]);
+
+        assert_eq!(queries.read(&ctx), [0; 2]);
+    });
+}
@@ -0,0 +1,264 @@
+use wgpu::util::{BufferInitDescriptor, DeviceExt, TlasInstance};
+use wgpu_test::{fail, initialize_test, valid, TestParameters, TestingContext};
+
+const SHADER: &str = "
+@group(0) @binding(0)
+var acc_struct: acceleration_structure;
+@group(0) @binding(1)
+var<storage, read_write> output: array<u32, 2>;
+
+fn trace(origin: vec3<f32>) -> u32 {
+    var rq: ray_query;
+    rayQueryInitialize(&rq, acc_struct, RayDesc(0u, 0xFFu, 0.1, 100.0, origin, vec3<f32>(0.0, 0.0, -1.0)));
+    rayQueryProceed(&rq);
+    return rayQueryGetCommittedIntersection(&rq).kind;
+}
+
+@compute @workgroup_size(1)
+fn main() {
+    output[0] = trace(vec3<f32>(0.25, 0.25, 1.0));
+    output[1] = trace(vec3<f32>(2.0, 2.0, 1.0));
+}
+";
+
+const IDENTITY: [f32; 12] = [
+    1.0, 0.0, 0.0, 0.0, //
+    0.0, 1.0, 0.0, 0.0, //
+    0.0, 0.0, 1.0, 0.0, //
+];
+
+fn parameters() -> TestParameters {
+    TestParameters::default()
+        .features(wgpu::Features::RAY_TRACING_ACCELERATION_STRUCTURE | wgpu::Features::RAY_QUERY)
+}
+
+fn triangle_size() -> wgpu::BlasTriangleGeometrySizeDescriptor {
+    wgpu::BlasTriangleGeometrySizeDescriptor {
+        vertex_format: wgpu::VertexFormat::Float32x3,
+        vertex_count: 3,
+        index_format: None,
+        index_count: None,
+        flags: wgpu::AccelerationStructureGeometryFlags::OPAQUE,
+    }
+}
+
+/// A unit triangle in the z = 0 plane, and its bottom level acceleration structure.
+fn create_triangle_blas(ctx: &TestingContext) -> (wgpu::Buffer, wgpu::Blas) {
+    let vertices: [f32; 9] = [0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0, 0.0];
+    let vertex_buffer = ctx.device.create_buffer_init(&BufferInitDescriptor {
+        label: Some("vertices"),
+        contents: bytemuck::cast_slice(&vertices),
+        usage: wgpu::BufferUsages::BLAS_INPUT,
+    });
+    let blas = ctx.device.create_blas(
+        &wgpu::CreateBlasDescriptor {
+            label: Some("triangle"),
+            flags: wgpu::AccelerationStructureFlags::PREFER_FAST_TRACE,
+            update_mode: wgpu::AccelerationStructureUpdateMode::Build,
+        },
+        wgpu::BlasGeometrySizeDescriptors::Triangles {
+            desc: vec![triangle_size()],
+        },
+    );
+    (vertex_buffer, blas)
+}
+
+fn create_tlas(ctx: &TestingContext, max_instances: u32) -> wgpu::Tlas {
+    ctx.device.create_tlas(&wgpu::CreateTlasDescriptor {
+        label: Some("scene"),
+        max_instances,
+        flags: wgpu::AccelerationStructureFlags::PREFER_FAST_TRACE,
+        update_mode: wgpu::AccelerationStructureUpdateMode::Build,
+    })
+}
+
+#[test]
+fn build_acceleration_structures_validation() {
+    initialize_test(parameters(), |ctx| {
+        let (vertex_buffer, blas) = create_triangle_blas(&ctx);
+        let tlas = create_tlas(&ctx, 1);
+        let size = triangle_size();
+        let instance = TlasInstance::new(blas.handle().unwrap(), IDENTITY, 0, 0xff);
+        let instance_buffer = ctx.device.create_buffer_init(&BufferInitDescriptor {
+            label: Some("instances"),
+            contents: instance.as_bytes(),
+            usage: wgpu::BufferUsages::TLAS_INPUT,
+        });
+
+        let triangle = wgpu::BlasTriangleGeometry {
+            size: &size,
+            vertex_buffer: &vertex_buffer,
+            first_vertex: 0,
+            vertex_stride: 12,
+            index_buffer: None,
+            index_buffer_offset: None,
+            transform_buffer: None,
+            transform_buffer_offset: None,
+        };
+        let build = |blas_entries: &[wgpu::BlasBuildEntry],
+                     tlas_entries: &[wgpu::TlasBuildEntry]| {
+            let mut encoder = ctx
+                .device
+                .create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+            encoder.build_acceleration_structures_unsafe_tlas(blas_entries, tlas_entries);
+            encoder.finish()
+        };
+
+        // The number of geometries has to match the one at creation.
+        fail(&ctx.device, || {
+            build(
+                &[wgpu::BlasBuildEntry {
+                    blas: &blas,
+                    geometry: wgpu::BlasGeometries::TriangleGeometries(vec![]),
+                }],
+                &[],
+            )
+        });
+
+        // The vertex stride reads past the end of the vertex buffer.
+        fail(&ctx.device, || {
+            build(
+                &[wgpu::BlasBuildEntry {
+                    blas: &blas,
+                    geometry: wgpu::BlasGeometries::TriangleGeometries(vec![
+                        wgpu::BlasTriangleGeometry {
+                            vertex_stride: 16,
+                            ..triangle.clone()
+                        },
+                    ]),
+                }],
+                &[],
+            )
+        });
+
+        // More instances than the top level acceleration structure was created for.
+        fail(&ctx.device, || {
+            build(
+                &[],
+                &[wgpu::TlasBuildEntry {
+                    tlas: &tlas,
+                    instance_buffer: &instance_buffer,
+                    instance_count: 2,
+                }],
+            )
+        });
+
+        let command_buffer = valid(&ctx.device, || {
+            build(
+                &[wgpu::BlasBuildEntry {
+                    blas: &blas,
+                    geometry: wgpu::BlasGeometries::TriangleGeometries(vec![triangle.clone()]),
+                }],
+                &[wgpu::TlasBuildEntry {
+                    tlas: &tlas,
+                    instance_buffer: &instance_buffer,
+                    instance_count: 1,
+                }],
+            )
+        });
+        ctx.queue.submit(Some(command_buffer));
+        ctx.device.poll(wgpu::Maintain::Wait);
+    });
+}
+
+#[test]
+fn ray_query_hits_triangle() {
+    initialize_test(parameters(), |ctx| {
+        let (vertex_buffer, blas) = create_triangle_blas(&ctx);
+        let tlas = create_tlas(&ctx, 1);
+        let size = triangle_size();
+        let instance = TlasInstance::new(blas.handle().unwrap(), IDENTITY, 0, 0xff);
+        let instance_buffer = ctx.device.create_buffer_init(&BufferInitDescriptor {
+            label: Some("instances"),
+            contents: instance.as_bytes(),
+            usage: wgpu::BufferUsages::TLAS_INPUT,
+        });
+
+        let output_buffer = ctx.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("output"),
+            size: 8,
+            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC,
+            mapped_at_creation: false,
+        });
+        let mapping_buffer = ctx.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("mapping"),
+            size: 8,
+            usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let module = ctx
+            .device
+            .create_shader_module(wgpu::ShaderModuleDescriptor {
+                label: None,
+                source: wgpu::ShaderSource::Wgsl(SHADER.into()),
+            });
+        let pipeline = ctx
+            .device
+            .create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+                label: Some("ray query"),
+                layout: None,
+                module: &module,
+                entry_point: "main",
+                cache: None,
+            });
+        let bind_group = ctx.device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: None,
+            layout: &pipeline.get_bind_group_layout(0),
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::AccelerationStructure(&tlas),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: output_buffer.as_entire_binding(),
+                },
+            ],
+        });
+
+        let mut encoder = ctx
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+        encoder.build_acceleration_structures_unsafe_tlas(
+            &[wgpu::BlasBuildEntry {
+                blas: &blas,
+                geometry: wgpu::BlasGeometries::TriangleGeometries(vec![
+                    wgpu::BlasTriangleGeometry {
+                        size: &size,
+                        vertex_buffer: &vertex_buffer,
+                        first_vertex: 0,
+                        vertex_stride: 12,
+                        index_buffer: None,
+                        index_buffer_offset: None,
+                        transform_buffer: None,
+                        transform_buffer_offset: None,
+                    },
+                ]),
+            }],
+            &[wgpu::TlasBuildEntry {
+                tlas: &tlas,
+                instance_buffer: &instance_buffer,
+                instance_count: 1,
+            }],
+        );
+        {
+            let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor::default());
+            pass.set_pipeline(&pipeline);
+            pass.set_bind_group(0, &bind_group, &[]);
+            pass.dispatch_workgroups(1, 1, 1);
+        }
+        encoder.copy_buffer_to_buffer(&output_buffer, 0, &mapping_buffer, 0, 8);
+        ctx.queue.submit(Some(encoder.finish()));
+
+        mapping_buffer
+            .slice(..)
+            .map_async(wgpu::MapMode::Read, |_| ());
+        ctx.device.poll(wgpu::Maintain::Wait);
+        let data = mapping_buffer.slice(..).get_mapped_range();
+        let kinds: &[u32] = bytemuck::cast_slice(&data);
+
+        // RAY_QUERY_INTERSECTION_TRIANGLE, then RAY_QUERY_INTERSECTION_NONE.
+        assert_eq!(kinds, [1, 0]);
+    });
+}
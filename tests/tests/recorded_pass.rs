@@ -0,0 +1,170 @@
+use std::sync::Arc;
+
+use wgpu_test::{fail, initialize_test, TestParameters, TestingContext};
+
+const SHADER: &str = "
+@group(0) @binding(0)
+var<storage, read_write> output: array<u32>;
+
+@compute @workgroup_size(1)
+fn main(@builtin(workgroup_id) id: vec3<u32>) {
+    output[id.x] = id.x + 1u;
+}
+";
+
+struct Resources {
+    pipeline: Arc<wgpu::ComputePipeline>,
+    bind_group: Arc<wgpu::BindGroup>,
+    output: Arc<wgpu::Buffer>,
+}
+
+impl Resources {
+    fn new(ctx: &TestingContext) -> Self {
+        let module = ctx
+            .device
+            .create_shader_module(wgpu::ShaderModuleDescriptor {
+                label: None,
+                source: wgpu::ShaderSource::Wgsl(SHADER.into()),
+            });
+        let pipeline = ctx
+            .device
+            .create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+                label: None,
+                layout: None,
+                module: &module,
+                entry_point: "main",
+                cache: None,
+            });
+        let output = ctx.device.create_buffer(&wgpu::BufferDescriptor {
+            label: None,
+            size: 16,
+            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC,
+            mapped_at_creation: false,
+        });
+        let bind_group = ctx.device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: None,
+            layout: &pipeline.get_bind_group_layout(0),
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: output.as_entire_binding(),
+            }],
+        });
+        Self {
+            pipeline: Arc::new(pipeline),
+            bind_group: Arc::new(bind_group),
+            output: Arc::new(output),
+        }
+    }
+
+    /// Replay `recorded` into a fresh encoder and return the output values.
+    fn run(&self, ctx: &TestingContext, recorded: &wgpu::RecordedComputePass) -> [u32; 4] {
+        let readback = ctx.device.create_buffer(&wgpu::BufferDescriptor {
+            label: None,
+            size: 16,
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+        let mut encoder = ctx
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+        encoder.replay_compute_pass(&wgpu::ComputePassDescriptor::default(), recorded);
+        encoder.copy_buffer_to_buffer(&self.output, 0, &readback, 0, 16);
+        ctx.queue.submit(Some(encoder.finish()));
+
+        let slice = readback.slice(..);
+        slice.map_async(wgpu::MapMode::Read, |_| ());
+        ctx.device.poll(wgpu::Maintain::Wait);
+        let data = slice.get_mapped_range();
+        *bytemuck::from_bytes(&data)
+    }
+}
+
+fn parameters() -> TestParameters {
+    TestParameters::default()
+        .downlevel_flags(wgpu::DownlevelFlags::COMPUTE_SHADERS)
+        .limits(wgpu::Limits::downlevel_defaults())
+}
+
+#[test]
+fn recorded_compute_pass_on_other_thread() {
+    initialize_test(parameters(), |ctx| {
+        let resources = Resources::new(&ctx);
+
+        let (pipeline, bind_group) = (resources.pipeline.clone(), resources.bind_group.clone());
+        let recorded = std::thread::spawn(move || {
+            let mut recorded = wgpu::RecordedComputePass::new();
+            recorded.push_debug_group("recorded");
+            recorded.set_pipeline(&pipeline);
+            recorded.set_bind_group(0, &bind_group, &[]);
+            recorded.dispatch_workgroups(4, 1, 1);
+            recorded.pop_debug_group();
+            recorded
+        })
+        .join()
+        .unwrap();
+
+        assert_eq!(resources.run(&ctx, &recorded), [1, 2, 3, 4]);
+    })
+}
+
+#[test]
+fn recorded_compute_pass_is_validated_on_replay() {
+    initialize_test(parameters(), |ctx| {
+        let resources = Resources::new(&ctx);
+
+        // Dispatching without a bind group is only caught once the pass is replayed.
+        let mut recorded = wgpu::RecordedComputePass::new();
+        recorded.set_pipeline(&resources.pipeline);
+        recorded.dispatch_workgroups(1, 1, 1);
+
+        fail(&ctx.device, || resources.run(&ctx, &recorded));
+    })
+}
+
+#[test]
+fn recorded_render_pass_replays_every_command() {
+    initialize_test(TestParameters::default(), |ctx| {
+        let texture = ctx.device.create_texture(&wgpu::TextureDescriptor {
+            label: None,
+            size: wgpu::Extent3d {
+                width: 4,
+                height: 4,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::Rgba8Unorm,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+            view_formats: &[],
+        });
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+
+        let mut recorded = wgpu::RecordedRenderPass::new();
+        recorded.insert_debug_marker("marker");
+        recorded.set_viewport(0.0, 0.0, 2.0, 2.0, 0.0, 1.0);
+        recorded.set_scissor_rect(0, 0, 2, 2);
+        recorded.set_blend_constant(wgpu::Color::WHITE);
+        recorded.set_stencil_reference(1);
+        assert!(!recorded.is_empty());
+
+        let mut encoder = ctx
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+        encoder.replay_render_pass(
+            &wgpu::RenderPassDescriptor {
+                label: None,
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: &view,
+                    resolve_target: None,
+                    ops: wgpu::Operations::default(),
+                })],
+                depth_stencil_attachment: None,
+                timestamp_writes: None,
+                occlusion_query_set: None,
+            },
+            &recorded,
+        );
+        ctx.queue.submit(Some(encoder.finish()));
+    })
+}
@@ -80,6 +80,7 @@ fn pass_reset_vertex_buffer() {
                     })],
                 }),
                 multiview: None,
+                cache: None,
             });
 
         let single_pipeline = ctx
@@ -109,6 +110,7 @@ fn pass_reset_vertex_buffer() {
                     })],
                 }),
                 multiview: None,
+                cache: None,
             });
 
         let view = ctx
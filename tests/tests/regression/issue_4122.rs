@@ -26,6 +26,7 @@ fn fill_test(ctx: &TestingContext, range: Range<u64>, size: u64) -> bool {
         .device
         .create_command_encoder(&wgpu::CommandEncoderDescriptor {
             label: Some("encoder"),
+            reusable: false,
         });
 
     encoder.clear_buffer(
@@ -0,0 +1,188 @@
+use wgpu::util::DeviceExt;
+use wgpu_test::{initialize_test, TestParameters, TestingContext};
+
+const SIZE: u32 = 4;
+
+const SHADER: &str = "
+@vertex
+fn vs_main(@builtin(vertex_index) index: u32) -> @builtin(position) vec4<f32> {
+    let uv = vec2<f32>(f32((index << 1u) & 2u), f32(index & 2u));
+    return vec4<f32>(uv * 4.0 - 1.0, 0.0, 1.0);
+}
+
+@fragment
+fn fs_main() -> @location(0) vec4<f32> {
+    return vec4<f32>(1.0);
+}
+";
+
+const FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::R8Unorm;
+
+fn create_pipeline(ctx: &TestingContext) -> wgpu::RenderPipeline {
+    let module = ctx
+        .device
+        .create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: None,
+            source: wgpu::ShaderSource::Wgsl(SHADER.into()),
+        });
+    ctx.device
+        .create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: None,
+            layout: None,
+            vertex: wgpu::VertexState {
+                module: &module,
+                entry_point: "vs_main",
+                buffers: &[],
+            },
+            primitive: wgpu::PrimitiveState::default(),
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState::default(),
+            fragment: Some(wgpu::FragmentState {
+                module: &module,
+                entry_point: "fs_main",
+                targets: &[Some(FORMAT.into())],
+            }),
+            multiview: None,
+            cache: None,
+        })
+}
+
+fn create_bundle_encoder(ctx: &TestingContext) -> wgpu::RenderBundleEncoder<'_> {
+    ctx.device
+        .create_render_bundle_encoder(&wgpu::RenderBundleEncoderDescriptor {
+            label: Some("bundle"),
+            color_formats: &[Some(FORMAT)],
+            depth_stencil: None,
+            sample_count: 1,
+            multiview: None,
+        })
+}
+
+/// Execute `bundle` in a render pass cleared to black, and return the first
+/// texel of the target.
+fn execute_bundle(ctx: &TestingContext, bundle: &wgpu::RenderBundle) -> u8 {
+    let texture = ctx.device.create_texture(&wgpu::TextureDescriptor {
+        label: None,
+        size: wgpu::Extent3d {
+            width: SIZE,
+            height: SIZE,
+            depth_or_array_layers: 1,
+        },
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format: FORMAT,
+        usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+        view_formats: &[],
+    });
+    let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+    let readback = ctx.device.create_buffer(&wgpu::BufferDescriptor {
+        label: None,
+        size: (wgpu::COPY_BYTES_PER_ROW_ALIGNMENT * SIZE) as u64,
+        usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+        mapped_at_creation: false,
+    });
+
+    let mut encoder = ctx
+        .device
+        .create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+    {
+        let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: None,
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view: &view,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+                    store: wgpu::StoreOp::Store,
+                },
+            })],
+            depth_stencil_attachment: None,
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        });
+        pass.execute_bundles(Some(bundle));
+    }
+    encoder.copy_texture_to_buffer(
+        texture.as_image_copy(),
+        wgpu::ImageCopyBuffer {
+            buffer: &readback,
+            layout: wgpu::ImageDataLayout {
+                offset: 0,
+                bytes_per_row: Some(wgpu::COPY_BYTES_PER_ROW_ALIGNMENT),
+                rows_per_image: None,
+            },
+        },
+        wgpu::Extent3d {
+            width: SIZE,
+            height: SIZE,
+            depth_or_array_layers: 1,
+        },
+    );
+    ctx.queue.submit(Some(encoder.finish()));
+
+    let slice = readback.slice(..);
+    slice.map_async(wgpu::MapMode::Read, |_| ());
+    ctx.device.poll(wgpu::Maintain::Wait);
+    let data = slice.get_mapped_range();
+    data[0]
+}
+
+#[test]
+fn bundle_debug_markers() {
+    initialize_test(TestParameters::default(), |ctx| {
+        let pipeline = create_pipeline(&ctx);
+
+        let mut encoder = create_bundle_encoder(&ctx);
+        encoder.push_debug_group("outer");
+        encoder.set_pipeline(&pipeline);
+        encoder.insert_debug_marker("draw");
+        encoder.push_debug_group("inner");
+        encoder.draw(0..3, 0..1);
+        encoder.pop_debug_group();
+        encoder.pop_debug_group();
+        let bundle = encoder.finish(&wgpu::RenderBundleDescriptor::default());
+
+        assert_eq!(execute_bundle(&ctx, &bundle), 255);
+    })
+}
+
+#[test]
+fn bundle_multi_draw_indirect() {
+    let parameters = TestParameters::default()
+        .features(wgpu::Features::MULTI_DRAW_INDIRECT)
+        .downlevel_flags(wgpu::DownlevelFlags::INDIRECT_EXECUTION);
+    initialize_test(parameters, |ctx| {
+        let pipeline = create_pipeline(&ctx);
+        // The first draw is empty, so the texel is only written if the
+        // second one is issued as well.
+        let args = [
+            wgpu::util::DrawIndirect {
+                vertex_count: 0,
+                instance_count: 1,
+                base_vertex: 0,
+                base_instance: 0,
+            },
+            wgpu::util::DrawIndirect {
+                vertex_count: 3,
+                instance_count: 1,
+                base_vertex: 0,
+                base_instance: 0,
+            },
+        ];
+        let indirect = ctx
+            .device
+            .create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: None,
+                contents: &[args[0].as_bytes(), args[1].as_bytes()].concat(),
+                usage: wgpu::BufferUsages::INDIRECT,
+            });
+
+        let mut encoder = create_bundle_encoder(&ctx);
+        encoder.set_pipeline(&pipeline);
+        encoder.multi_draw_indirect(&indirect, 0, 2);
+        let bundle = encoder.finish(&wgpu::RenderBundleDescriptor::default());
+
+        assert_eq!(execute_bundle(&ctx, &bundle), 255);
+    })
+}
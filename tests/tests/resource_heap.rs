@@ -0,0 +1,173 @@
+use wgpu_test::{fail, initialize_test, valid, TestParameters};
+
+const HEAP_SIZE: wgpu::BufferAddress = 1 << 20;
+
+const SHADER: &str = "
+@group(0) @binding(0) var<storage, read> src: array<u32>;
+@group(0) @binding(1) var<storage, read_write> dst: array<u32>;
+
+@compute @workgroup_size(1)
+fn main() {
+    dst[0] = src[0];
+}
+";
+
+fn create_heap(device: &wgpu::Device) -> wgpu::Heap {
+    device.create_heap(&wgpu::HeapDescriptor {
+        label: Some("resource heap test"),
+        size: HEAP_SIZE,
+    })
+}
+
+fn storage_buffer_desc(usage: wgpu::BufferUsages) -> wgpu::BufferDescriptor<'static> {
+    wgpu::BufferDescriptor {
+        label: None,
+        size: 256,
+        usage: wgpu::BufferUsages::STORAGE | usage,
+        mapped_at_creation: false,
+    }
+}
+
+#[test]
+fn resource_heap_requires_feature() {
+    initialize_test(TestParameters::default(), |ctx| {
+        fail(&ctx.device, || create_heap(&ctx.device));
+    })
+}
+
+#[test]
+fn resource_heap_placement_validation() {
+    initialize_test(
+        TestParameters::default().features(wgpu::Features::RESOURCE_HEAPS),
+        |ctx| {
+            let heap = valid(&ctx.device, || create_heap(&ctx.device));
+
+            // Placed buffers can't be mapped.
+            fail(&ctx.device, || {
+                ctx.device.create_buffer_in_heap(
+                    &storage_buffer_desc(wgpu::BufferUsages::MAP_READ),
+                    &heap,
+                    0,
+                )
+            });
+            // The buffer has to fit in the heap.
+            fail(&ctx.device, || {
+                ctx.device.create_buffer_in_heap(
+                    &storage_buffer_desc(wgpu::BufferUsages::empty()),
+                    &heap,
+                    HEAP_SIZE,
+                )
+            });
+        },
+    );
+}
+
+#[test]
+fn resource_heap_aliases_in_one_pass() {
+    initialize_test(
+        TestParameters::default().features(wgpu::Features::RESOURCE_HEAPS),
+        |ctx| {
+            let heap = create_heap(&ctx.device);
+            let src = ctx.device.create_buffer_in_heap(
+                &storage_buffer_desc(wgpu::BufferUsages::empty()),
+                &heap,
+                0,
+            );
+            let dst = ctx.device.create_buffer_in_heap(
+                &storage_buffer_desc(wgpu::BufferUsages::empty()),
+                &heap,
+                0,
+            );
+
+            let module = ctx
+                .device
+                .create_shader_module(wgpu::ShaderModuleDescriptor {
+                    label: None,
+                    source: wgpu::ShaderSource::Wgsl(SHADER.into()),
+                });
+            let pipeline = ctx
+                .device
+                .create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+                    label: None,
+                    layout: None,
+                    module: &module,
+                    entry_point: "main",
+                    cache: None,
+                });
+            let bind_group = ctx.device.create_bind_group(&wgpu::BindGroupDescriptor {
+                label: None,
+                layout: &pipeline.get_bind_group_layout(0),
+                entries: &[
+                    wgpu::BindGroupEntry {
+                        binding: 0,
+                        resource: src.as_entire_binding(),
+                    },
+                    wgpu::BindGroupEntry {
+                        binding: 1,
+                        resource: dst.as_entire_binding(),
+                    },
+                ],
+            });
+
+            // Both aliases of the same memory are used by one dispatch.
+            fail(&ctx.device, || {
+                let mut encoder = ctx
+                    .device
+                    .create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+                {
+                    let mut pass = encoder.begin_compute_pass(&Default::default());
+                    pass.set_pipeline(&pipeline);
+                    pass.set_bind_group(0, &bind_group, &[]);
+                    pass.dispatch_workgroups(1, 1, 1);
+                }
+                encoder.finish()
+            });
+        },
+    );
+}
+
+#[test]
+fn resource_heap_aliases_in_sequence() {
+    initialize_test(
+        TestParameters::default().features(wgpu::Features::RESOURCE_HEAPS),
+        |ctx| {
+            let heap = create_heap(&ctx.device);
+            let first = ctx.device.create_buffer_in_heap(
+                &storage_buffer_desc(wgpu::BufferUsages::COPY_SRC | wgpu::BufferUsages::COPY_DST),
+                &heap,
+                0,
+            );
+            let second = ctx.device.create_buffer_in_heap(
+                &storage_buffer_desc(wgpu::BufferUsages::COPY_SRC),
+                &heap,
+                0,
+            );
+            let readback = ctx.device.create_buffer(&wgpu::BufferDescriptor {
+                label: None,
+                size: 256,
+                usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+                mapped_at_creation: false,
+            });
+
+            ctx.queue.write_buffer(&first, 0, &[0xFF; 256]);
+            let mut encoder = ctx
+                .device
+                .create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+            encoder.copy_buffer_to_buffer(&first, 0, &readback, 0, 256);
+            ctx.queue.submit(Some(encoder.finish()));
+
+            // The memory still holds the contents of `first`, but `second` was
+            // never initialized, so it has to read back as zero.
+            let mut encoder = ctx
+                .device
+                .create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+            encoder.copy_buffer_to_buffer(&second, 0, &readback, 0, 256);
+            ctx.queue.submit(Some(encoder.finish()));
+
+            let slice = readback.slice(..);
+            slice.map_async(wgpu::MapMode::Read, |_| ());
+            ctx.device.poll(wgpu::Maintain::Wait);
+            assert!(slice.get_mapped_range().iter().all(|&byte| byte == 0));
+        },
+    );
+}
@@ -0,0 +1,97 @@
+use wgpu_test::{fail, initialize_test, TestParameters};
+
+const SHADER: &str = "
+@group(0) @binding(0)
+var<storage, read_write> counter: u32;
+
+@compute @workgroup_size(1)
+fn main() {
+    counter += 1u;
+}
+";
+
+#[test]
+fn reusable_command_buffer_submitted_twice() {
+    initialize_test(
+        TestParameters::default()
+            .features(wgpu::Features::REUSABLE_COMMAND_BUFFERS)
+            .downlevel_flags(wgpu::DownlevelFlags::COMPUTE_SHADERS)
+            .limits(wgpu::Limits::downlevel_defaults()),
+        |ctx| {
+            let module = ctx
+                .device
+                .create_shader_module(wgpu::ShaderModuleDescriptor {
+                    label: None,
+                    source: wgpu::ShaderSource::Wgsl(SHADER.into()),
+                });
+            let pipeline = ctx
+                .device
+                .create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+                    label: None,
+                    layout: None,
+                    module: &module,
+                    entry_point: "main",
+                    cache: None,
+                });
+            let counter = ctx.device.create_buffer(&wgpu::BufferDescriptor {
+                label: None,
+                size: 4,
+                usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC,
+                mapped_at_creation: false,
+            });
+            let readback = ctx.device.create_buffer(&wgpu::BufferDescriptor {
+                label: None,
+                size: 4,
+                usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+                mapped_at_creation: false,
+            });
+            let bind_group = ctx.device.create_bind_group(&wgpu::BindGroupDescriptor {
+                label: None,
+                layout: &pipeline.get_bind_group_layout(0),
+                entries: &[wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: counter.as_entire_binding(),
+                }],
+            });
+
+            let mut encoder = ctx
+                .device
+                .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                    label: None,
+                    reusable: true,
+                });
+            {
+                let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor::default());
+                pass.set_pipeline(&pipeline);
+                pass.set_bind_group(0, &bind_group, &[]);
+                pass.dispatch_workgroups(1, 1, 1);
+            }
+            encoder.copy_buffer_to_buffer(&counter, 0, &readback, 0, 4);
+            let command_buffer = encoder.finish();
+
+            ctx.queue.submit_reusable([&command_buffer]);
+            ctx.queue.submit_reusable([&command_buffer]);
+            // Dropping it while the submissions may still be executing is fine.
+            drop(command_buffer);
+
+            let slice = readback.slice(..);
+            slice.map_async(wgpu::MapMode::Read, |_| ());
+            ctx.device.poll(wgpu::Maintain::Wait);
+            let data = slice.get_mapped_range();
+            assert_eq!(*bytemuck::from_bytes::<u32>(&data), 2);
+        },
+    )
+}
+
+#[test]
+fn reusable_command_buffer_requires_feature() {
+    initialize_test(TestParameters::default(), |ctx| {
+        fail(&ctx.device, || {
+            ctx.device
+                .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                    label: None,
+                    reusable: true,
+                })
+        });
+    })
+}
@@ -12,26 +12,40 @@ mod buffer;
 mod buffer_copy;
 mod buffer_usages;
 mod clear_texture;
+mod compute_bundle;
 mod create_surface_error;
 mod device;
 mod encoder;
 mod example_wgsl;
+mod external_semaphore;
 mod external_texture;
+mod index_range_validation;
+mod indirect_call_validation;
 mod instance;
+mod memory_report;
 mod occlusion_query;
 mod partially_bounded_arrays;
 mod pipeline;
+mod pipeline_cache;
 mod poll;
+mod push_constants;
 mod query_set;
 mod queue_transfer;
+mod ray_tracing;
+mod recorded_pass;
+mod render_bundle;
 mod resource_descriptor_accessor;
 mod resource_error;
+mod resource_heap;
+mod reusable_command_buffer;
 mod scissor_tests;
 mod shader;
 mod shader_primitive_index;
 mod shader_view_format;
+mod sparse_binding;
 mod texture_bounds;
 mod transfer;
+mod transition_resources;
 mod vertex_indices;
 mod write_texture;
 mod zero_init_texture_after_discard;
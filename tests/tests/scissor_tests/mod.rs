@@ -55,13 +55,17 @@ fn scissor_test_impl(ctx: &TestingContext, scissor_rect: Rect, expected_data: [u
                 })],
             }),
             multiview: None,
+            cache: None,
         });
 
     let readback_buffer = image::ReadbackBuffers::new(&ctx.device, &texture);
     {
         let mut encoder = ctx
             .device
-            .create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                label: None,
+                reusable: false,
+            });
         {
             let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
                 label: Some("Renderpass"),
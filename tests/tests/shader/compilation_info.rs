@@ -0,0 +1,63 @@
+use wasm_bindgen_test::*;
+
+use wgpu_test::{fail, initialize_test, valid, TestParameters};
+
+const VALID_SHADER: &str = "
+@compute @workgroup_size(1)
+fn main() {}
+";
+
+// The error is the unknown identifier on the third line.
+const INVALID_SHADER: &str = "
+@compute @workgroup_size(1)
+fn main() { let x = undefined_value; }
+";
+
+#[test]
+#[wasm_bindgen_test]
+fn compilation_info_valid_shader() {
+    initialize_test(TestParameters::default(), |ctx| {
+        let shader = valid(&ctx.device, || {
+            ctx.device
+                .create_shader_module(wgpu::ShaderModuleDescriptor {
+                    label: None,
+                    source: wgpu::ShaderSource::Wgsl(VALID_SHADER.into()),
+                })
+        });
+
+        let info = pollster::block_on(shader.get_compilation_info());
+        assert!(
+            info.messages
+                .iter()
+                .all(|message| message.message_type != wgpu::CompilationMessageType::Error),
+            "{info:?}"
+        );
+    })
+}
+
+#[test]
+#[wasm_bindgen_test]
+fn compilation_info_invalid_shader() {
+    initialize_test(TestParameters::default(), |ctx| {
+        let shader = fail(&ctx.device, || {
+            ctx.device
+                .create_shader_module(wgpu::ShaderModuleDescriptor {
+                    label: None,
+                    source: wgpu::ShaderSource::Wgsl(INVALID_SHADER.into()),
+                })
+        });
+
+        let info = pollster::block_on(shader.get_compilation_info());
+        let error = info
+            .messages
+            .iter()
+            .find(|message| message.message_type == wgpu::CompilationMessageType::Error)
+            .unwrap_or_else(|| panic!("expected an error message: {info:?}"));
+
+        let location = error.location.expect("error should have a location");
+        assert_eq!(location.line_number, 3);
+        let offset = location.offset as usize;
+        let length = location.length as usize;
+        assert_eq!(&INVALID_SHADER[offset..offset + length], "undefined_value");
+    })
+}
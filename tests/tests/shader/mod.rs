@@ -15,6 +15,7 @@ use wgpu::{
 
 use wgpu_test::TestingContext;
 
+mod compilation_info;
 mod numeric_builtins;
 mod struct_layout;
 mod zero_init_workgroup_mem;
@@ -297,6 +298,7 @@ fn shader_input_output_test(
                 layout: Some(&pll),
                 module: &sm,
                 entry_point: "cs_main",
+                cache: None,
             });
 
         // -- Initializing data --
@@ -322,7 +324,10 @@ fn shader_input_output_test(
 
         let mut encoder = ctx
             .device
-            .create_command_encoder(&CommandEncoderDescriptor { label: None });
+            .create_command_encoder(&CommandEncoderDescriptor {
+                label: None,
+                reusable: false,
+            });
 
         let mut cpass = encoder.begin_compute_pass(&ComputePassDescriptor {
             label: Some(&format!("cpass {test_name}")),
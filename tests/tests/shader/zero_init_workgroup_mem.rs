@@ -109,6 +109,7 @@ fn zero_init_workgroup_mem_impl(ctx: TestingContext) {
             layout: Some(&pll),
             module: &sm,
             entry_point: "read",
+            cache: None,
         });
 
     let pipeline_write = ctx
@@ -118,6 +119,7 @@ fn zero_init_workgroup_mem_impl(ctx: TestingContext) {
             layout: None,
             module: &sm,
             entry_point: "write",
+            cache: None,
         });
 
     // -- Initializing data --
@@ -148,6 +148,7 @@ fn pulling_common(
                 })],
             }),
             multiview: None,
+            cache: None,
         });
 
     let width = 2;
@@ -109,6 +109,7 @@ fn reinterpret(
             depth_stencil: None,
             multisample: wgpu::MultisampleState::default(),
             multiview: None,
+            cache: None,
         });
     let bind_group = ctx.device.create_bind_group(&wgpu::BindGroupDescriptor {
         layout: &pipeline.get_bind_group_layout(0),
@@ -160,7 +161,10 @@ fn reinterpret(
 
     let mut encoder = ctx
         .device
-        .create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
+        .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: None,
+            reusable: false,
+        });
     encoder.copy_texture_to_buffer(
         wgpu::ImageCopyTexture {
             texture: &target_tex,
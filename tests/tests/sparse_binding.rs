@@ -0,0 +1,181 @@
+use wgpu_test::{fail, initialize_test, valid, TestParameters, TestingContext};
+
+const PAGE: wgpu::BufferAddress = wgpu::SPARSE_PAGE_SIZE;
+
+fn create_heap(device: &wgpu::Device, pages: wgpu::BufferAddress) -> wgpu::Heap {
+    device.create_heap(&wgpu::HeapDescriptor {
+        label: Some("sparse binding test"),
+        size: pages * PAGE,
+    })
+}
+
+fn sparse_buffer_desc(
+    pages: wgpu::BufferAddress,
+    usage: wgpu::BufferUsages,
+) -> wgpu::BufferDescriptor<'static> {
+    wgpu::BufferDescriptor {
+        label: None,
+        size: pages * PAGE,
+        usage: wgpu::BufferUsages::COPY_SRC | wgpu::BufferUsages::COPY_DST | usage,
+        mapped_at_creation: false,
+    }
+}
+
+fn bind<'a>(
+    buffer: &'a wgpu::Buffer,
+    pages: std::ops::Range<wgpu::BufferAddress>,
+    memory: Option<(&'a wgpu::Heap, wgpu::BufferAddress)>,
+) -> wgpu::SparseBufferBinding<'a> {
+    wgpu::SparseBufferBinding {
+        buffer,
+        offset: pages.start * PAGE,
+        size: (pages.end - pages.start) * PAGE,
+        memory: memory.map(|(heap, page)| wgpu::SparseMemory {
+            heap,
+            offset: page * PAGE,
+        }),
+    }
+}
+
+fn read_back(ctx: &TestingContext, buffer: &wgpu::Buffer, size: u64) -> Vec<u8> {
+    let readback = ctx.device.create_buffer(&wgpu::BufferDescriptor {
+        label: None,
+        size,
+        usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+        mapped_at_creation: false,
+    });
+    let mut encoder = ctx
+        .device
+        .create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+    encoder.copy_buffer_to_buffer(buffer, 0, &readback, 0, size);
+    ctx.queue.submit(Some(encoder.finish()));
+
+    let slice = readback.slice(..);
+    slice.map_async(wgpu::MapMode::Read, |_| ());
+    ctx.device.poll(wgpu::Maintain::Wait);
+    let data = slice.get_mapped_range().to_vec();
+    data
+}
+
+#[test]
+fn sparse_binding_requires_feature() {
+    initialize_test(TestParameters::default(), |ctx| {
+        fail(&ctx.device, || {
+            ctx.device
+                .create_sparse_buffer(&sparse_buffer_desc(1, wgpu::BufferUsages::empty()))
+        });
+    })
+}
+
+#[test]
+fn sparse_binding_validation() {
+    initialize_test(
+        TestParameters::default()
+            .features(wgpu::Features::SPARSE_BINDING | wgpu::Features::RESOURCE_HEAPS),
+        |ctx| {
+            // Sparse buffers can't be mapped.
+            fail(&ctx.device, || {
+                ctx.device
+                    .create_sparse_buffer(&sparse_buffer_desc(1, wgpu::BufferUsages::MAP_READ))
+            });
+
+            let buffer = ctx
+                .device
+                .create_sparse_buffer(&sparse_buffer_desc(2, wgpu::BufferUsages::empty()));
+            let heap = create_heap(&ctx.device, 1);
+
+            // Ranges start on page boundaries.
+            fail(&ctx.device, || {
+                ctx.queue.update_sparse_bindings(
+                    &[wgpu::SparseBufferBinding {
+                        offset: 256,
+                        ..bind(&buffer, 0..1, Some((&heap, 0)))
+                    }],
+                    &[],
+                )
+            });
+            // Two pages don't fit in the heap.
+            fail(&ctx.device, || {
+                ctx.queue
+                    .update_sparse_bindings(&[bind(&buffer, 0..2, Some((&heap, 0)))], &[])
+            });
+            // Only sparse buffers can be bound.
+            let dense = ctx
+                .device
+                .create_buffer(&sparse_buffer_desc(1, wgpu::BufferUsages::empty()));
+            fail(&ctx.device, || {
+                ctx.queue
+                    .update_sparse_bindings(&[bind(&dense, 0..1, Some((&heap, 0)))], &[])
+            });
+        },
+    );
+}
+
+#[test]
+fn sparse_buffer_partially_bound() {
+    initialize_test(
+        TestParameters::default()
+            .features(wgpu::Features::SPARSE_BINDING | wgpu::Features::RESOURCE_HEAPS),
+        |ctx| {
+            if ctx
+                .device
+                .features()
+                .contains(wgpu::Features::SPARSE_RESIDENCY)
+            {
+                return;
+            }
+            let buffer = ctx
+                .device
+                .create_sparse_buffer(&sparse_buffer_desc(2, wgpu::BufferUsages::empty()));
+            let heap = create_heap(&ctx.device, 1);
+            let readback = ctx.device.create_buffer(&wgpu::BufferDescriptor {
+                label: None,
+                size: 4,
+                usage: wgpu::BufferUsages::COPY_DST,
+                mapped_at_creation: false,
+            });
+
+            ctx.queue
+                .update_sparse_bindings(&[bind(&buffer, 0..1, Some((&heap, 0)))], &[]);
+
+            // Without residency, the second page can't be left unbound.
+            fail(&ctx.device, || {
+                let mut encoder = ctx
+                    .device
+                    .create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+                encoder.copy_buffer_to_buffer(&buffer, 0, &readback, 0, 4);
+                ctx.queue.submit(Some(encoder.finish()));
+            });
+        },
+    );
+}
+
+#[test]
+fn sparse_buffer_rebinding() {
+    initialize_test(
+        TestParameters::default()
+            .features(wgpu::Features::SPARSE_BINDING | wgpu::Features::RESOURCE_HEAPS),
+        |ctx| {
+            let buffer = ctx
+                .device
+                .create_sparse_buffer(&sparse_buffer_desc(1, wgpu::BufferUsages::empty()));
+            let heap = create_heap(&ctx.device, 2);
+
+            valid(&ctx.device, || {
+                ctx.queue
+                    .update_sparse_bindings(&[bind(&buffer, 0..1, Some((&heap, 0)))], &[])
+            });
+            ctx.queue.write_buffer(&buffer, 0, &[0xFF; 256]);
+            assert!(read_back(&ctx, &buffer, 256)
+                .iter()
+                .all(|&byte| byte == 0xFF));
+
+            // The page moves to memory it was never bound to, so it reads as zero.
+            valid(&ctx.device, || {
+                ctx.queue
+                    .update_sparse_bindings(&[bind(&buffer, 0..1, Some((&heap, 1)))], &[])
+            });
+            assert!(read_back(&ctx, &buffer, 256).iter().all(|&byte| byte == 0));
+        },
+    );
+}
@@ -0,0 +1,186 @@
+use wgpu_test::{fail, initialize_test, TestParameters};
+
+#[test]
+fn transition_resources_before_copy() {
+    initialize_test(TestParameters::default(), |ctx| {
+        let src = ctx.device.create_buffer(&wgpu::BufferDescriptor {
+            label: None,
+            size: 16,
+            usage: wgpu::BufferUsages::COPY_SRC | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        ctx.queue.write_buffer(
+            &src,
+            0,
+            &[1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16],
+        );
+        let dst = ctx.device.create_buffer(&wgpu::BufferDescriptor {
+            label: None,
+            size: 16,
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+        let texture = ctx.device.create_texture(&wgpu::TextureDescriptor {
+            label: None,
+            size: wgpu::Extent3d {
+                width: 4,
+                height: 1,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::Rgba8Unorm,
+            usage: wgpu::TextureUsages::COPY_SRC | wgpu::TextureUsages::COPY_DST,
+            view_formats: &[],
+        });
+
+        let mut encoder = ctx
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+        encoder.transition_resources(
+            &[
+                wgpu::BufferTransition {
+                    buffer: &src,
+                    state: wgpu::BufferUsages::COPY_SRC,
+                },
+                wgpu::BufferTransition {
+                    buffer: &dst,
+                    state: wgpu::BufferUsages::COPY_DST,
+                },
+            ],
+            &[wgpu::TextureTransition {
+                texture: &texture,
+                subresource_range: wgpu::ImageSubresourceRange::default(),
+                state: wgpu::TextureUsages::COPY_DST,
+            }],
+        );
+        let layout = wgpu::ImageDataLayout {
+            offset: 0,
+            bytes_per_row: None,
+            rows_per_image: None,
+        };
+        let extent = wgpu::Extent3d {
+            width: 4,
+            height: 1,
+            depth_or_array_layers: 1,
+        };
+        // Round trip through the texture, so both of its transitions are exercised.
+        encoder.copy_buffer_to_texture(
+            wgpu::ImageCopyBuffer {
+                buffer: &src,
+                layout,
+            },
+            texture.as_image_copy(),
+            extent,
+        );
+        encoder.transition_resources(
+            &[],
+            &[wgpu::TextureTransition {
+                texture: &texture,
+                subresource_range: wgpu::ImageSubresourceRange::default(),
+                state: wgpu::TextureUsages::COPY_SRC,
+            }],
+        );
+        encoder.copy_texture_to_buffer(
+            texture.as_image_copy(),
+            wgpu::ImageCopyBuffer {
+                buffer: &dst,
+                layout,
+            },
+            extent,
+        );
+        ctx.queue.submit(Some(encoder.finish()));
+
+        let slice = dst.slice(..);
+        slice.map_async(wgpu::MapMode::Read, |_| ());
+        ctx.device.poll(wgpu::Maintain::Wait);
+        assert_eq!(
+            &*slice.get_mapped_range(),
+            &[1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16]
+        );
+    })
+}
+
+#[test]
+fn transition_resources_validation() {
+    initialize_test(TestParameters::default(), |ctx| {
+        let buffer = ctx.device.create_buffer(&wgpu::BufferDescriptor {
+            label: None,
+            size: 16,
+            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::UNIFORM,
+            mapped_at_creation: false,
+        });
+        let texture = ctx.device.create_texture(&wgpu::TextureDescriptor {
+            label: None,
+            size: wgpu::Extent3d {
+                width: 4,
+                height: 4,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::Rgba8Unorm,
+            usage: wgpu::TextureUsages::TEXTURE_BINDING,
+            view_formats: &[],
+        });
+
+        let transition_buffer = |state| {
+            let mut encoder = ctx
+                .device
+                .create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+            encoder.transition_resources(
+                &[wgpu::BufferTransition {
+                    buffer: &buffer,
+                    state,
+                }],
+                &[],
+            );
+            encoder.finish()
+        };
+        let transition_texture = |subresource_range, state| {
+            let mut encoder = ctx
+                .device
+                .create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+            encoder.transition_resources(
+                &[],
+                &[wgpu::TextureTransition {
+                    texture: &texture,
+                    subresource_range,
+                    state,
+                }],
+            );
+            encoder.finish()
+        };
+
+        transition_buffer(wgpu::BufferUsages::UNIFORM);
+        // Storage may be written to, so it can't be combined with anything else.
+        fail(&ctx.device, || {
+            transition_buffer(wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::UNIFORM)
+        });
+        fail(&ctx.device, || {
+            transition_buffer(wgpu::BufferUsages::COPY_SRC)
+        });
+
+        transition_texture(
+            wgpu::ImageSubresourceRange::default(),
+            wgpu::TextureUsages::TEXTURE_BINDING,
+        );
+        fail(&ctx.device, || {
+            transition_texture(
+                wgpu::ImageSubresourceRange::default(),
+                wgpu::TextureUsages::COPY_DST,
+            )
+        });
+        fail(&ctx.device, || {
+            transition_texture(
+                wgpu::ImageSubresourceRange {
+                    base_mip_level: 1,
+                    ..Default::default()
+                },
+                wgpu::TextureUsages::TEXTURE_BINDING,
+            )
+        });
+    })
+}
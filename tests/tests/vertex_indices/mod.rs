@@ -79,6 +79,7 @@ fn pulling_common(
                 })],
             }),
             multiview: None,
+            cache: None,
         });
 
     let dummy = ctx
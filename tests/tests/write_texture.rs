@@ -60,7 +60,10 @@ fn write_texture_subset_2d() {
 
         let mut encoder = ctx
             .device
-            .create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                label: None,
+                reusable: false,
+            });
 
         encoder.copy_texture_to_buffer(
             wgpu::ImageCopyTexture {
@@ -156,7 +159,10 @@ fn write_texture_subset_3d() {
 
         let mut encoder = ctx
             .device
-            .create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                label: None,
+                reusable: false,
+            });
 
         encoder.copy_texture_to_buffer(
             wgpu::ImageCopyTexture {
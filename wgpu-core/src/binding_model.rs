@@ -2,7 +2,9 @@ use crate::{
     device::{DeviceError, MissingDownlevelFlags, MissingFeatures, SHADER_STAGE_COUNT},
     error::{ErrorFormatter, PrettyError},
     hal_api::HalApi,
-    id::{BindGroupLayoutId, BufferId, DeviceId, SamplerId, TextureId, TextureViewId, Valid},
+    id::{
+        BindGroupLayoutId, BufferId, DeviceId, SamplerId, TextureId, TextureViewId, TlasId, Valid,
+    },
     init_tracker::{BufferInitTrackerAction, TextureInitTrackerAction},
     resource::Resource,
     track::{BindGroupStates, UsageConflict},
@@ -76,6 +78,8 @@ pub enum CreateBindGroupError {
     InvalidTexture(TextureId),
     #[error("Sampler {0:?} is invalid")]
     InvalidSampler(SamplerId),
+    #[error("Top level acceleration structure {0:?} is invalid")]
+    InvalidTlas(TlasId),
     #[error(
         "Binding count declared with at most {expected} items, but {actual} items were provided"
     )]
@@ -336,6 +340,8 @@ impl BindingTypeMaxCountValidator {
             wgt::BindingType::StorageTexture { .. } => {
                 self.storage_textures.add(binding.visibility, count);
             }
+            // There is no limit for acceleration structures yet.
+            wgt::BindingType::AccelerationStructure => {}
         }
     }
 
@@ -608,6 +614,12 @@ pub enum PushConstantUploadError {
     },
     #[error("Provided push constant offset {0} does not respect `PUSH_CONSTANT_ALIGNMENT`")]
     Unaligned(u32),
+    #[error("Provided push constant of {size} bytes at offset {offset} exceeds the maximum push constant size of {max_size}")]
+    ExceedsMaxSize {
+        offset: u32,
+        size: u32,
+        max_size: u32,
+    },
 }
 
 /// Describes a pipeline layout.
@@ -753,6 +765,7 @@ pub enum BindingResource<'a> {
     SamplerArray(Cow<'a, [SamplerId]>),
     TextureView(TextureViewId),
     TextureViewArray(Cow<'a, [TextureViewId]>),
+    AccelerationStructure(TlasId),
 }
 
 #[derive(Clone, Debug, Error)]
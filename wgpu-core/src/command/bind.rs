@@ -1,6 +1,7 @@
 use crate::{
     binding_model::{
         BindGroup, BindGroupLayouts, LateMinBufferBindingSizeMismatch, PipelineLayout,
+        PushConstantUploadError,
     },
     device::SHADER_STAGE_COUNT,
     hal_api::HalApi,
@@ -12,6 +13,8 @@ use crate::{
 
 use arrayvec::ArrayVec;
 
+use std::ops::Range;
+
 type BindGroupMask = u8;
 
 mod compat {
@@ -292,6 +295,15 @@ impl Binder {
             .map(move |index| payloads[index].group_id.as_ref().unwrap().value)
     }
 
+    /// Return the groups that are compatible with the current pipeline layout,
+    /// with their index.
+    pub(super) fn list_active_entries(&self) -> impl Iterator<Item = (usize, &EntryPayload)> + '_ {
+        let payloads = &self.payloads;
+        self.manager
+            .list_active()
+            .map(move |index| (index, &payloads[index]))
+    }
+
     pub(super) fn invalid_mask<A: hal::Api>(
         &self,
         bind_group_layouts: &BindGroupLayouts<A>,
@@ -324,6 +336,112 @@ impl Binder {
     }
 }
 
+/// Shadow copy of the push constants set in a pass.
+///
+/// Push constants may be set before the pipeline that uses them, the same
+/// way bind groups are assigned to the [`Binder`] before the pipeline layout
+/// is known. The values are kept here and sent again for every range of each
+/// new pipeline layout.
+#[derive(Debug, Default)]
+pub(super) struct PushConstantState {
+    /// Values written so far, in words from offset 0.
+    data: Vec<u32>,
+    /// Writes made while no pipeline layout was bound, to be validated
+    /// against the next one.
+    unvalidated: Vec<(wgt::ShaderStages, Range<u32>)>,
+}
+
+impl PushConstantState {
+    pub(super) fn reset(&mut self) {
+        self.data.clear();
+        self.unvalidated.clear();
+    }
+
+    /// Store `values` at byte `offset`.
+    ///
+    /// The write is validated against `pipeline_layout` if there is one, and
+    /// against the next layout passed to [`Self::change_pipeline_layout`]
+    /// otherwise. Either way, it has to fit in `max_push_constant_size`
+    /// before anything gets stored.
+    pub(super) fn set<A: hal::Api>(
+        &mut self,
+        pipeline_layout: Option<&PipelineLayout<A>>,
+        max_push_constant_size: u32,
+        stages: wgt::ShaderStages,
+        offset: u32,
+        values: &[u32],
+    ) -> Result<(), PushConstantUploadError> {
+        let size = values.len() as u32 * wgt::PUSH_CONSTANT_ALIGNMENT;
+        let end_offset = match offset.checked_add(size) {
+            Some(end_offset) if end_offset <= max_push_constant_size => end_offset,
+            _ => {
+                return Err(PushConstantUploadError::ExceedsMaxSize {
+                    offset,
+                    size,
+                    max_size: max_push_constant_size,
+                })
+            }
+        };
+        match pipeline_layout {
+            Some(pipeline_layout) => {
+                pipeline_layout.validate_push_constant_ranges(stages, offset, end_offset)?
+            }
+            None if offset % wgt::PUSH_CONSTANT_ALIGNMENT != 0 => {
+                return Err(PushConstantUploadError::Unaligned(offset));
+            }
+            None => self.unvalidated.push((stages, offset..end_offset)),
+        }
+
+        let start = (offset / wgt::PUSH_CONSTANT_ALIGNMENT) as usize;
+        let end = start + values.len();
+        if self.data.len() < end {
+            self.data.resize(end, 0);
+        }
+        self.data[start..end].copy_from_slice(values);
+        Ok(())
+    }
+
+    /// Validate the writes made before `pipeline_layout` was bound, and send
+    /// the current values of all its ranges through `set_push_constants`.
+    ///
+    /// Values that were never written are zero.
+    pub(super) fn change_pipeline_layout<A: hal::Api>(
+        &mut self,
+        pipeline_layout: &PipelineLayout<A>,
+        set_push_constants: impl FnMut(wgt::ShaderStages, u32, &[u32]),
+    ) -> Result<(), PushConstantUploadError> {
+        for (stages, range) in self.unvalidated.drain(..) {
+            pipeline_layout.validate_push_constant_ranges(stages, range.start, range.end)?;
+        }
+
+        let end = pipeline_layout
+            .push_constant_ranges
+            .iter()
+            .map(|range| (range.range.end / wgt::PUSH_CONSTANT_ALIGNMENT) as usize)
+            .max()
+            .unwrap_or(0);
+        if self.data.len() < end {
+            self.data.resize(end, 0);
+        }
+        self.emit(pipeline_layout, set_push_constants);
+        Ok(())
+    }
+
+    /// Send the current values of all the ranges of `pipeline_layout`, which
+    /// must have been passed to [`Self::change_pipeline_layout`] last.
+    pub(super) fn emit<A: hal::Api>(
+        &self,
+        pipeline_layout: &PipelineLayout<A>,
+        mut set_push_constants: impl FnMut(wgt::ShaderStages, u32, &[u32]),
+    ) {
+        for range in compute_nonoverlapping_ranges(&pipeline_layout.push_constant_ranges) {
+            let start = (range.range.start / wgt::PUSH_CONSTANT_ALIGNMENT) as usize;
+            let end = (range.range.end / wgt::PUSH_CONSTANT_ALIGNMENT) as usize;
+            set_push_constants(range.stages, range.range.start, &self.data[start..end]);
+        }
+    }
+}
+
 struct PushConstantChange {
     stages: wgt::ShaderStages,
     offset: u32,
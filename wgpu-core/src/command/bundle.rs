@@ -81,12 +81,13 @@ index format changes.
 use crate::{
     binding_model::{self, buffer_binding_type_alignment},
     command::{
-        BasePass, BindGroupStateChange, ColorAttachmentError, DrawError, MapPassErr,
-        PassErrorScope, RenderCommand, RenderCommandError, StateChange,
+        end_pipeline_statistics_query, BasePass, BindGroupStateChange, ColorAttachmentError,
+        DrawError, MapPassErr, PassErrorScope, QueryResetMap, QueryUseError, RenderCommand,
+        RenderCommandError, SimplifiedQueryType, StateChange,
     },
     conv,
     device::{
-        AttachmentData, Device, DeviceError, MissingDownlevelFlags,
+        AttachmentData, Device, DeviceError, MissingDownlevelFlags, MissingFeatures,
         RenderPassCompatibilityCheckType, RenderPassContext, SHADER_STAGE_COUNT,
     },
     error::{ErrorFormatter, PrettyError},
@@ -94,7 +95,11 @@ use crate::{
     hub::{Hub, Token},
     id,
     identity::GlobalIdentityHandlerFactory,
-    init_tracker::{BufferInitTrackerAction, MemoryInitKind, TextureInitTrackerAction},
+    index_validation,
+    init_tracker::{
+        BufferInitTrackerAction, MemoryInitKind, QuerySetInitTrackerAction,
+        TextureInitTrackerAction,
+    },
     pipeline::{self, PipelineFlags},
     resource::{self, Resource},
     storage::Storage,
@@ -103,7 +108,7 @@ use crate::{
     Label, LabelHelpers, LifeGuard, Stored,
 };
 use arrayvec::ArrayVec;
-use std::{borrow::Cow, mem, num::NonZeroU32, ops::Range};
+use std::{borrow::Cow, mem, num::NonZeroU32, ops::Range, str};
 use thiserror::Error;
 
 use hal::CommandEncoder as _;
@@ -285,6 +290,9 @@ impl RenderBundleEncoder {
 
         let base = self.base.as_ref();
         let mut next_dynamic_offset = 0;
+        let mut debug_scope_depth = 0u32;
+        let mut active_query = None::<(id::QuerySetId, u32)>;
+        let mut used_queries = QueryResetMap::<A>::new();
 
         for &command in base.commands {
             match command {
@@ -370,7 +378,10 @@ impl RenderBundleEncoder {
                         .map_pass_err(scope)?;
 
                     self.context
-                        .check_compatible(&pipeline.pass_context, RenderPassCompatibilityCheckType::RenderPipeline)
+                        .check_compatible(
+                            &pipeline.pass_context,
+                            RenderPassCompatibilityCheckType::RenderPipeline,
+                        )
                         .map_err(RenderCommandError::IncompatiblePipelineTargets)
                         .map_pass_err(scope)?;
 
@@ -510,7 +521,7 @@ impl RenderBundleEncoder {
                     index_count,
                     instance_count,
                     first_index,
-                    base_vertex: _,
+                    base_vertex,
                     first_instance,
                 } => {
                     let scope = PassErrorScope::Draw {
@@ -524,7 +535,6 @@ impl RenderBundleEncoder {
                         Some(ref index) => index,
                         None => return Err(DrawError::MissingIndexBuffer).map_pass_err(scope),
                     };
-                    //TODO: validate that base_vertex + max_index() is within the provided range
                     let vertex_limits = state.vertex_limits(pipeline);
                     let index_limit = index.limit();
                     let last_index = first_index + index_count;
@@ -544,6 +554,27 @@ impl RenderBundleEncoder {
                         })
                         .map_pass_err(scope);
                     }
+                    if let Ok(buffer) = buffer_guard.get(index.buffer) {
+                        if let Some(ref contents) = buffer.index_contents {
+                            let indices = index_validation::index_byte_range(
+                                index.format,
+                                index.range.start,
+                                first_index,
+                                index_count,
+                            );
+                            contents
+                                .lock()
+                                .validate_draw(
+                                    index.format,
+                                    indices,
+                                    pipeline.strip_index_format.is_some(),
+                                    base_vertex,
+                                    vertex_limits.vertex_limit,
+                                    vertex_limits.vertex_limit_slot,
+                                )
+                                .map_pass_err(scope)?;
+                        }
+                    }
                     commands.extend(state.flush_index());
                     commands.extend(state.flush_vertices());
                     commands.extend(state.flush_binds(used_bind_groups, base.dynamic_offsets));
@@ -552,14 +583,19 @@ impl RenderBundleEncoder {
                 RenderCommand::MultiDrawIndirect {
                     buffer_id,
                     offset,
-                    count: None,
-                    indexed: false,
+                    count,
+                    indexed,
                 } => {
                     let scope = PassErrorScope::Draw {
-                        indexed: false,
+                        indexed,
                         indirect: true,
                         pipeline: state.pipeline_id(),
                     };
+                    if count.is_some() {
+                        device
+                            .require_features(wgt::Features::MULTI_DRAW_INDIRECT)
+                            .map_pass_err(scope)?;
+                    }
                     device
                         .require_downlevel_flags(wgt::DownlevelFlags::INDIRECT_EXECUTION)
                         .map_pass_err(scope)?;
@@ -577,27 +613,50 @@ impl RenderBundleEncoder {
                     check_buffer_usage(buffer.usage, wgt::BufferUsages::INDIRECT)
                         .map_pass_err(scope)?;
 
+                    let stride = indirect_stride(indexed);
+                    let end_offset = offset + stride * count.map_or(1, |c| c.get()) as u64;
+                    if end_offset > buffer.size {
+                        return Err(RenderBundleErrorInner::IndirectBufferOverrun {
+                            count,
+                            offset,
+                            end_offset,
+                            buffer_size: buffer.size,
+                        })
+                        .map_pass_err(scope);
+                    }
                     buffer_memory_init_actions.extend(buffer.initialization_status.create_action(
                         buffer_id,
-                        offset..(offset + mem::size_of::<wgt::DrawIndirectArgs>() as u64),
+                        offset..end_offset,
                         MemoryInitKind::NeedsInitializedMemory,
                     ));
 
+                    if indexed {
+                        let index = match state.index {
+                            Some(ref mut index) => index,
+                            None => return Err(DrawError::MissingIndexBuffer).map_pass_err(scope),
+                        };
+                        commands.extend(index.flush());
+                    }
                     commands.extend(state.flush_vertices());
                     commands.extend(state.flush_binds(used_bind_groups, base.dynamic_offsets));
                     commands.push(command);
                 }
-                RenderCommand::MultiDrawIndirect {
+                RenderCommand::MultiDrawIndirectCount {
                     buffer_id,
                     offset,
-                    count: None,
-                    indexed: true,
+                    count_buffer_id,
+                    count_buffer_offset,
+                    max_count,
+                    indexed,
                 } => {
                     let scope = PassErrorScope::Draw {
-                        indexed: true,
+                        indexed,
                         indirect: true,
                         pipeline: state.pipeline_id(),
                     };
+                    device
+                        .require_features(wgt::Features::MULTI_DRAW_INDIRECT_COUNT)
+                        .map_pass_err(scope)?;
                     device
                         .require_downlevel_flags(wgt::DownlevelFlags::INDIRECT_EXECUTION)
                         .map_pass_err(scope)?;
@@ -615,46 +674,176 @@ impl RenderBundleEncoder {
                     check_buffer_usage(buffer.usage, wgt::BufferUsages::INDIRECT)
                         .map_pass_err(scope)?;
 
+                    let end_offset = offset + indirect_stride(indexed) * max_count as u64;
+                    if end_offset > buffer.size {
+                        return Err(RenderBundleErrorInner::IndirectBufferOverrun {
+                            count: None,
+                            offset,
+                            end_offset,
+                            buffer_size: buffer.size,
+                        })
+                        .map_pass_err(scope);
+                    }
                     buffer_memory_init_actions.extend(buffer.initialization_status.create_action(
                         buffer_id,
-                        offset..(offset + mem::size_of::<wgt::DrawIndirectArgs>() as u64),
+                        offset..end_offset,
                         MemoryInitKind::NeedsInitializedMemory,
                     ));
 
-                    let index = match state.index {
-                        Some(ref mut index) => index,
-                        None => return Err(DrawError::MissingIndexBuffer).map_pass_err(scope),
-                    };
+                    let count_buffer: &resource::Buffer<A> = state
+                        .trackers
+                        .buffers
+                        .merge_single(&*buffer_guard, count_buffer_id, hal::BufferUses::INDIRECT)
+                        .map_pass_err(scope)?;
+                    self.check_valid_to_use(count_buffer.device_id.value)
+                        .map_pass_err(scope)?;
+                    check_buffer_usage(count_buffer.usage, wgt::BufferUsages::INDIRECT)
+                        .map_pass_err(scope)?;
 
-                    commands.extend(index.flush());
+                    let end_count_offset = count_buffer_offset + 4;
+                    if end_count_offset > count_buffer.size {
+                        return Err(RenderBundleErrorInner::IndirectCountBufferOverrun {
+                            begin_count_offset: count_buffer_offset,
+                            end_count_offset,
+                            count_buffer_size: count_buffer.size,
+                        })
+                        .map_pass_err(scope);
+                    }
+                    buffer_memory_init_actions.extend(
+                        count_buffer.initialization_status.create_action(
+                            count_buffer_id,
+                            count_buffer_offset..end_count_offset,
+                            MemoryInitKind::NeedsInitializedMemory,
+                        ),
+                    );
+
+                    if indexed {
+                        let index = match state.index {
+                            Some(ref mut index) => index,
+                            None => return Err(DrawError::MissingIndexBuffer).map_pass_err(scope),
+                        };
+                        commands.extend(index.flush());
+                    }
                     commands.extend(state.flush_vertices());
                     commands.extend(state.flush_binds(used_bind_groups, base.dynamic_offsets));
                     commands.push(command);
                 }
-                RenderCommand::MultiDrawIndirect { .. }
-                | RenderCommand::MultiDrawIndirectCount { .. } => unimplemented!(),
-                RenderCommand::PushDebugGroup { color: _, len: _ } => unimplemented!(),
-                RenderCommand::InsertDebugMarker { color: _, len: _ } => unimplemented!(),
-                RenderCommand::PopDebugGroup => unimplemented!(),
-                RenderCommand::WriteTimestamp { .. } // Must check the TIMESTAMP_QUERY_INSIDE_PASSES feature
-                | RenderCommand::BeginOcclusionQuery { .. }
-                | RenderCommand::EndOcclusionQuery
-                | RenderCommand::BeginPipelineStatisticsQuery { .. }
-                | RenderCommand::EndPipelineStatisticsQuery => unimplemented!(),
+                RenderCommand::PushDebugGroup { color: _, len: _ } => {
+                    debug_scope_depth += 1;
+                    commands.push(command);
+                }
+                RenderCommand::InsertDebugMarker { color: _, len: _ } => {
+                    commands.push(command);
+                }
+                RenderCommand::PopDebugGroup => {
+                    let scope = PassErrorScope::PopDebugGroup;
+                    if debug_scope_depth == 0 {
+                        return Err(RenderBundleErrorInner::InvalidPopDebugGroup)
+                            .map_pass_err(scope);
+                    }
+                    debug_scope_depth -= 1;
+                    commands.push(command);
+                }
+                RenderCommand::WriteTimestamp {
+                    query_set_id,
+                    query_index,
+                } => {
+                    let scope = PassErrorScope::WriteTimestamp;
+                    device
+                        .require_features(wgt::Features::TIMESTAMP_QUERY_INSIDE_PASSES)
+                        .map_pass_err(scope)?;
+
+                    let query_set: &resource::QuerySet<A> = state
+                        .trackers
+                        .query_sets
+                        .add_single(&*query_set_guard, query_set_id)
+                        .ok_or(RenderCommandError::InvalidQuerySet(query_set_id))
+                        .map_pass_err(scope)?;
+                    self.check_valid_to_use(query_set.device_id.value)
+                        .map_pass_err(scope)?;
+
+                    query_set
+                        .validate_query(
+                            query_set_id,
+                            SimplifiedQueryType::Timestamp,
+                            query_index,
+                            Some(&mut used_queries),
+                        )
+                        .map_pass_err(scope)?;
+                    commands.push(command);
+                }
+                RenderCommand::BeginPipelineStatisticsQuery {
+                    query_set_id,
+                    query_index,
+                } => {
+                    let scope = PassErrorScope::BeginPipelineStatisticsQuery;
+
+                    let query_set: &resource::QuerySet<A> = state
+                        .trackers
+                        .query_sets
+                        .add_single(&*query_set_guard, query_set_id)
+                        .ok_or(RenderCommandError::InvalidQuerySet(query_set_id))
+                        .map_pass_err(scope)?;
+                    self.check_valid_to_use(query_set.device_id.value)
+                        .map_pass_err(scope)?;
+
+                    query_set
+                        .validate_query(
+                            query_set_id,
+                            SimplifiedQueryType::PipelineStatistics,
+                            query_index,
+                            Some(&mut used_queries),
+                        )
+                        .map_pass_err(scope)?;
+                    if let Some((_, active_query_index)) = active_query {
+                        return Err(QueryUseError::AlreadyStarted {
+                            active_query_index,
+                            new_query_index: query_index,
+                        })
+                        .map_pass_err(scope);
+                    }
+                    active_query = Some((query_set_id, query_index));
+                    commands.push(command);
+                }
+                RenderCommand::EndPipelineStatisticsQuery => {
+                    let scope = PassErrorScope::EndPipelineStatisticsQuery;
+                    if active_query.take().is_none() {
+                        return Err(QueryUseError::AlreadyStopped).map_pass_err(scope);
+                    }
+                    commands.push(command);
+                }
                 RenderCommand::ExecuteBundle(_)
                 | RenderCommand::SetBlendConstant(_)
                 | RenderCommand::SetStencilReference(_)
                 | RenderCommand::SetViewport { .. }
-                | RenderCommand::SetScissor(_) => unreachable!("not supported by a render bundle"),
+                | RenderCommand::SetScissor(_)
+                | RenderCommand::BeginOcclusionQuery { .. }
+                | RenderCommand::EndOcclusionQuery => {
+                    unreachable!("not supported by a render bundle")
+                }
             }
         }
 
+        if debug_scope_depth != 0 {
+            return Err(RenderBundleErrorInner::MissingPopDebugGroup {
+                count: debug_scope_depth,
+            })
+            .map_pass_err(PassErrorScope::Bundle);
+        }
+        if let Some((query_set_id, query_index)) = active_query {
+            return Err(RenderBundleErrorInner::UnendedPipelineStatisticsQuery {
+                query_set_id,
+                query_index,
+            })
+            .map_pass_err(PassErrorScope::Bundle);
+        }
+
         Ok(RenderBundle {
             base: BasePass {
                 label: desc.label.as_ref().map(|cow| cow.to_string()),
                 commands,
                 dynamic_offsets: state.flat_dynamic_offsets,
-                string_data: Vec::new(),
+                string_data: base.string_data.to_vec(),
                 push_constant_data: Vec::new(),
             },
             is_depth_read_only: self.is_depth_read_only,
@@ -714,8 +903,8 @@ pub enum CreateRenderBundleError {
 pub enum ExecutionError {
     #[error("Buffer {0:?} is destroyed")]
     DestroyedBuffer(id::BufferId),
-    #[error("Using {0} in a render bundle is not implemented")]
-    Unimplemented(&'static str),
+    #[error(transparent)]
+    Query(#[from] QueryUseError),
 }
 impl PrettyError for ExecutionError {
     fn fmt_pretty(&self, fmt: &mut ErrorFormatter) {
@@ -724,7 +913,7 @@ impl PrettyError for ExecutionError {
             Self::DestroyedBuffer(id) => {
                 fmt.buffer_label(&id);
             }
-            Self::Unimplemented(_reason) => {}
+            Self::Query(_) => {}
         };
     }
 }
@@ -774,7 +963,8 @@ impl<A: HalApi> RenderBundle<A> {
     ///
     /// Note that the function isn't expected to fail, generally.
     /// All the validation has already been done by this point.
-    /// The only failure condition is if some of the used buffers are destroyed.
+    /// The only failure conditions are if some of the used buffers are destroyed,
+    /// or if the queries of the bundle conflict with the ones of the render pass.
     pub(super) unsafe fn execute(
         &self,
         raw: &mut A::CommandEncoder,
@@ -785,8 +975,13 @@ impl<A: HalApi> RenderBundle<A> {
         bind_group_guard: &Storage<crate::binding_model::BindGroup<A>, id::BindGroupId>,
         pipeline_guard: &Storage<crate::pipeline::RenderPipeline<A>, id::RenderPipelineId>,
         buffer_guard: &Storage<crate::resource::Buffer<A>, id::BufferId>,
+        query_set_guard: &Storage<resource::QuerySet<A>, id::QuerySetId>,
+        pending_query_resets: &mut QueryResetMap<A>,
+        query_init_actions: &mut Vec<QuerySetInitTrackerAction>,
+        active_query: &mut Option<(id::QuerySetId, u32)>,
     ) -> Result<(), ExecutionError> {
         let mut offsets = self.base.dynamic_offsets.as_slice();
+        let mut string_offset = 0;
         let mut pipeline_layout_id = None::<id::Valid<id::PipelineLayoutId>>;
         if let Some(ref label) = self.base.label {
             unsafe { raw.begin_debug_marker(label) };
@@ -917,8 +1112,8 @@ impl<A: HalApi> RenderBundle<A> {
                 RenderCommand::MultiDrawIndirect {
                     buffer_id,
                     offset,
-                    count: None,
-                    indexed: false,
+                    count,
+                    indexed,
                 } => {
                     let buffer = buffer_guard
                         .get(buffer_id)
@@ -926,13 +1121,19 @@ impl<A: HalApi> RenderBundle<A> {
                         .raw
                         .as_ref()
                         .ok_or(ExecutionError::DestroyedBuffer(buffer_id))?;
-                    unsafe { raw.draw_indirect(buffer, offset, 1) };
+                    let count = count.map_or(1, |c| c.get());
+                    match indexed {
+                        false => unsafe { raw.draw_indirect(buffer, offset, count) },
+                        true => unsafe { raw.draw_indexed_indirect(buffer, offset, count) },
+                    }
                 }
-                RenderCommand::MultiDrawIndirect {
+                RenderCommand::MultiDrawIndirectCount {
                     buffer_id,
                     offset,
-                    count: None,
-                    indexed: true,
+                    count_buffer_id,
+                    count_buffer_offset,
+                    max_count,
+                    indexed,
                 } => {
                     let buffer = buffer_guard
                         .get(buffer_id)
@@ -940,29 +1141,93 @@ impl<A: HalApi> RenderBundle<A> {
                         .raw
                         .as_ref()
                         .ok_or(ExecutionError::DestroyedBuffer(buffer_id))?;
-                    unsafe { raw.draw_indexed_indirect(buffer, offset, 1) };
+                    let count_buffer = buffer_guard
+                        .get(count_buffer_id)
+                        .unwrap()
+                        .raw
+                        .as_ref()
+                        .ok_or(ExecutionError::DestroyedBuffer(count_buffer_id))?;
+                    match indexed {
+                        false => unsafe {
+                            raw.draw_indirect_count(
+                                buffer,
+                                offset,
+                                count_buffer,
+                                count_buffer_offset,
+                                max_count,
+                            )
+                        },
+                        true => unsafe {
+                            raw.draw_indexed_indirect_count(
+                                buffer,
+                                offset,
+                                count_buffer,
+                                count_buffer_offset,
+                                max_count,
+                            )
+                        },
+                    }
                 }
-                RenderCommand::MultiDrawIndirect { .. }
-                | RenderCommand::MultiDrawIndirectCount { .. } => {
-                    return Err(ExecutionError::Unimplemented("multi-draw-indirect"))
+                RenderCommand::PushDebugGroup { color: _, len } => {
+                    let label =
+                        str::from_utf8(&self.base.string_data[string_offset..string_offset + len])
+                            .unwrap();
+                    string_offset += len;
+                    unsafe { raw.begin_debug_marker(label) };
                 }
-                RenderCommand::PushDebugGroup { .. }
-                | RenderCommand::InsertDebugMarker { .. }
-                | RenderCommand::PopDebugGroup => {
-                    return Err(ExecutionError::Unimplemented("debug-markers"))
+                RenderCommand::InsertDebugMarker { color: _, len } => {
+                    let label =
+                        str::from_utf8(&self.base.string_data[string_offset..string_offset + len])
+                            .unwrap();
+                    string_offset += len;
+                    unsafe { raw.insert_debug_marker(label) };
                 }
-                RenderCommand::WriteTimestamp { .. }
-                | RenderCommand::BeginOcclusionQuery { .. }
-                | RenderCommand::EndOcclusionQuery
-                | RenderCommand::BeginPipelineStatisticsQuery { .. }
-                | RenderCommand::EndPipelineStatisticsQuery => {
-                    return Err(ExecutionError::Unimplemented("queries"))
+                RenderCommand::PopDebugGroup => {
+                    unsafe { raw.end_debug_marker() };
+                }
+                RenderCommand::WriteTimestamp {
+                    query_set_id,
+                    query_index,
+                } => {
+                    // The query set was checked by `RenderBundleEncoder::finish`, but
+                    // the query may have been used by the pass already.
+                    query_set_guard
+                        .get(query_set_id)
+                        .unwrap()
+                        .validate_and_write_timestamp(
+                            raw,
+                            query_set_id,
+                            query_index,
+                            Some(&mut *pending_query_resets),
+                            query_init_actions,
+                        )?;
+                }
+                RenderCommand::BeginPipelineStatisticsQuery {
+                    query_set_id,
+                    query_index,
+                } => {
+                    query_set_guard
+                        .get(query_set_id)
+                        .unwrap()
+                        .validate_and_begin_pipeline_statistics_query(
+                            raw,
+                            query_set_id,
+                            query_index,
+                            Some(&mut *pending_query_resets),
+                            query_init_actions,
+                            active_query,
+                        )?;
+                }
+                RenderCommand::EndPipelineStatisticsQuery => {
+                    end_pipeline_statistics_query(raw, query_set_guard, active_query)?;
                 }
                 RenderCommand::ExecuteBundle(_)
                 | RenderCommand::SetBlendConstant(_)
                 | RenderCommand::SetStencilReference(_)
                 | RenderCommand::SetViewport { .. }
-                | RenderCommand::SetScissor(_) => unreachable!(),
+                | RenderCommand::SetScissor(_)
+                | RenderCommand::BeginOcclusionQuery { .. }
+                | RenderCommand::EndOcclusionQuery => unreachable!(),
             }
         }
 
@@ -982,6 +1247,15 @@ impl<A: HalApi> Resource for RenderBundle<A> {
     }
 }
 
+/// Return the size of the arguments of an indirect draw.
+fn indirect_stride(indexed: bool) -> wgt::BufferAddress {
+    let size = match indexed {
+        false => mem::size_of::<wgt::DrawIndirectArgs>(),
+        true => mem::size_of::<wgt::DrawIndexedIndirectArgs>(),
+    };
+    size as wgt::BufferAddress
+}
+
 /// A render bundle's current index buffer state.
 ///
 /// [`RenderBundleEncoder::finish`] records the currently set index buffer here,
@@ -1109,6 +1383,10 @@ struct PipelineState {
     /// by vertex buffer slot number.
     steps: Vec<pipeline::VertexStep>,
 
+    /// The index format of strip topologies, which skip the primitive
+    /// restart value.
+    strip_index_format: Option<wgt::IndexFormat>,
+
     /// Ranges of push constants this pipeline uses, copied from the pipeline
     /// layout.
     push_constant_ranges: ArrayVec<wgt::PushConstantRange, { SHADER_STAGE_COUNT }>,
@@ -1127,6 +1405,7 @@ impl PipelineState {
             id: pipeline_id,
             layout_id: pipeline.layout_id.value,
             steps: pipeline.vertex_steps.to_vec(),
+            strip_index_format: pipeline.strip_index_format,
             push_constant_ranges: layout.push_constant_ranges.iter().cloned().collect(),
             used_bind_groups: layout.bind_group_layout_ids.len(),
         }
@@ -1409,7 +1688,36 @@ pub(super) enum RenderBundleErrorInner {
     #[error(transparent)]
     Draw(#[from] DrawError),
     #[error(transparent)]
+    MissingFeatures(#[from] MissingFeatures),
+    #[error(transparent)]
     MissingDownlevelFlags(#[from] MissingDownlevelFlags),
+    #[error("Indirect draw uses bytes {offset}..{end_offset} {} which overruns indirect buffer of size {buffer_size}",
+        count.map_or_else(String::new, |v| format!("(using count {v})")))]
+    IndirectBufferOverrun {
+        count: Option<NonZeroU32>,
+        offset: u64,
+        end_offset: u64,
+        buffer_size: u64,
+    },
+    #[error("Indirect draw uses bytes {begin_count_offset}..{end_count_offset} which overruns indirect buffer of size {count_buffer_size}")]
+    IndirectCountBufferOverrun {
+        begin_count_offset: u64,
+        end_count_offset: u64,
+        count_buffer_size: u64,
+    },
+    #[error("Cannot pop debug group, because number of pushed debug groups is zero")]
+    InvalidPopDebugGroup,
+    #[error("{count} debug group(s) pushed in the bundle were not popped")]
+    MissingPopDebugGroup { count: u32 },
+    #[error(transparent)]
+    QueryUse(#[from] QueryUseError),
+    #[error(
+        "Pipeline statistics query {query_index} of {query_set_id:?} was not ended in the bundle"
+    )]
+    UnendedPipelineStatisticsQuery {
+        query_set_id: id::QuerySetId,
+        query_index: u32,
+    },
 }
 
 impl<T> From<T> for RenderBundleErrorInner
@@ -1460,7 +1768,7 @@ where
 pub mod bundle_ffi {
     use super::{RenderBundleEncoder, RenderCommand};
     use crate::{id, RawString};
-    use std::{convert::TryInto, slice};
+    use std::{convert::TryInto, ffi, num::NonZeroU32, slice};
     use wgt::{BufferAddress, BufferSize, DynamicOffset, IndexFormat};
 
     /// # Safety
@@ -1641,21 +1949,101 @@ pub mod bundle_ffi {
         });
     }
 
+    #[no_mangle]
+    pub extern "C" fn wgpu_render_bundle_multi_draw_indirect(
+        bundle: &mut RenderBundleEncoder,
+        buffer_id: id::BufferId,
+        offset: BufferAddress,
+        count: u32,
+    ) {
+        bundle.base.commands.push(RenderCommand::MultiDrawIndirect {
+            buffer_id,
+            offset,
+            count: NonZeroU32::new(count),
+            indexed: false,
+        });
+    }
+
+    #[no_mangle]
+    pub extern "C" fn wgpu_render_bundle_multi_draw_indexed_indirect(
+        bundle: &mut RenderBundleEncoder,
+        buffer_id: id::BufferId,
+        offset: BufferAddress,
+        count: u32,
+    ) {
+        bundle.base.commands.push(RenderCommand::MultiDrawIndirect {
+            buffer_id,
+            offset,
+            count: NonZeroU32::new(count),
+            indexed: true,
+        });
+    }
+
+    #[no_mangle]
+    pub extern "C" fn wgpu_render_bundle_multi_draw_indirect_count(
+        bundle: &mut RenderBundleEncoder,
+        buffer_id: id::BufferId,
+        offset: BufferAddress,
+        count_buffer_id: id::BufferId,
+        count_buffer_offset: BufferAddress,
+        max_count: u32,
+    ) {
+        bundle
+            .base
+            .commands
+            .push(RenderCommand::MultiDrawIndirectCount {
+                buffer_id,
+                offset,
+                count_buffer_id,
+                count_buffer_offset,
+                max_count,
+                indexed: false,
+            });
+    }
+
+    #[no_mangle]
+    pub extern "C" fn wgpu_render_bundle_multi_draw_indexed_indirect_count(
+        bundle: &mut RenderBundleEncoder,
+        buffer_id: id::BufferId,
+        offset: BufferAddress,
+        count_buffer_id: id::BufferId,
+        count_buffer_offset: BufferAddress,
+        max_count: u32,
+    ) {
+        bundle
+            .base
+            .commands
+            .push(RenderCommand::MultiDrawIndirectCount {
+                buffer_id,
+                offset,
+                count_buffer_id,
+                count_buffer_offset,
+                max_count,
+                indexed: true,
+            });
+    }
+
     /// # Safety
     ///
     /// This function is unsafe as there is no guarantee that the given `label`
     /// is a valid null-terminated string.
     #[no_mangle]
     pub unsafe extern "C" fn wgpu_render_bundle_push_debug_group(
-        _bundle: &mut RenderBundleEncoder,
-        _label: RawString,
+        bundle: &mut RenderBundleEncoder,
+        label: RawString,
     ) {
-        //TODO
+        let bytes = unsafe { ffi::CStr::from_ptr(label) }.to_bytes();
+        bundle.base.string_data.extend_from_slice(bytes);
+
+        bundle.base.commands.push(RenderCommand::PushDebugGroup {
+            color: 0,
+            len: bytes.len(),
+        });
     }
 
     #[no_mangle]
-    pub extern "C" fn wgpu_render_bundle_pop_debug_group(_bundle: &mut RenderBundleEncoder) {
-        //TODO
+    pub extern "C" fn wgpu_render_bundle_pop_debug_group(bundle: &mut RenderBundleEncoder) {
+        bundle.base.commands.push(RenderCommand::PopDebugGroup);
     }
 
     /// # Safety
@@ -1664,9 +2052,52 @@ pub mod bundle_ffi {
     /// is a valid null-terminated string.
     #[no_mangle]
     pub unsafe extern "C" fn wgpu_render_bundle_insert_debug_marker(
-        _bundle: &mut RenderBundleEncoder,
-        _label: RawString,
+        bundle: &mut RenderBundleEncoder,
+        label: RawString,
     ) {
-        //TODO
+        let bytes = unsafe { ffi::CStr::from_ptr(label) }.to_bytes();
+        bundle.base.string_data.extend_from_slice(bytes);
+
+        bundle.base.commands.push(RenderCommand::InsertDebugMarker {
+            color: 0,
+            len: bytes.len(),
+        });
+    }
+
+    #[no_mangle]
+    pub extern "C" fn wgpu_render_bundle_write_timestamp(
+        bundle: &mut RenderBundleEncoder,
+        query_set_id: id::QuerySetId,
+        query_index: u32,
+    ) {
+        bundle.base.commands.push(RenderCommand::WriteTimestamp {
+            query_set_id,
+            query_index,
+        });
+    }
+
+    #[no_mangle]
+    pub extern "C" fn wgpu_render_bundle_begin_pipeline_statistics_query(
+        bundle: &mut RenderBundleEncoder,
+        query_set_id: id::QuerySetId,
+        query_index: u32,
+    ) {
+        bundle
+            .base
+            .commands
+            .push(RenderCommand::BeginPipelineStatisticsQuery {
+                query_set_id,
+                query_index,
+            });
+    }
+
+    #[no_mangle]
+    pub extern "C" fn wgpu_render_bundle_end_pipeline_statistics_query(
+        bundle: &mut RenderBundleEncoder,
+    ) {
+        bundle
+            .base
+            .commands
+            .push(RenderCommand::EndPipelineStatisticsQuery);
     }
 }
@@ -144,6 +144,7 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
             ));
         // actual hal barrier & operation
         let dst_barrier = dst_pending.map(|pending| pending.into_hal(dst_buffer));
+        dst_buffer.invalidate_index_contents();
         let cmd_buf_raw = cmd_buf.encoder.open();
         unsafe {
             cmd_buf_raw.transition_buffers(dst_barrier.into_iter());
@@ -357,15 +358,58 @@ fn clear_texture_via_buffer_copies<A: hal::Api>(
             texture_desc.size
         );
 
-        let z_range = 0..(if texture_desc.dimension == wgt::TextureDimension::D3 {
+        let is_volume = texture_desc.dimension == wgt::TextureDimension::D3;
+        let depth = if is_volume {
             mip_size.depth_or_array_layers
         } else {
             1
-        });
+        };
+
+        // For volume textures, try to cover as many depth slices as possible
+        // with a single copy. This is only possible if at least one full slice
+        // fits into the zero buffer, otherwise we go slice by slice below.
+        let bytes_per_image = bytes_per_row as u64 * (mip_size.height / block_height) as u64;
+        let max_slices_per_copy = if is_volume {
+            (crate::device::ZERO_BUFFER_SIZE / bytes_per_image) as u32
+        } else {
+            0
+        };
 
         for array_layer in range.layer_range.clone() {
-            // TODO: Only doing one layer at a time for volume textures right now.
-            for z in z_range.clone() {
+            if max_slices_per_copy > 0 {
+                let mut num_slices_left = depth;
+                while num_slices_left > 0 {
+                    let num_slices = num_slices_left.min(max_slices_per_copy);
+
+                    zero_buffer_copy_regions.push(hal::BufferTextureCopy {
+                        buffer_layout: wgt::ImageDataLayout {
+                            offset: 0,
+                            bytes_per_row: Some(bytes_per_row),
+                            rows_per_image: Some(mip_size.height / block_height),
+                        },
+                        texture_base: hal::TextureCopyBase {
+                            mip_level,
+                            array_layer,
+                            origin: wgt::Origin3d {
+                                x: 0,
+                                y: 0,
+                                z: depth - num_slices_left,
+                            },
+                            aspect: hal::FormatAspects::COLOR,
+                        },
+                        size: hal::CopyExtent {
+                            width: mip_size.width,
+                            height: mip_size.height,
+                            depth: num_slices,
+                        },
+                    });
+
+                    num_slices_left -= num_slices;
+                }
+                continue;
+            }
+
+            for z in 0..depth {
                 // May need multiple copies for each subresource! However, we
                 // assume that we never need to split a row.
                 let mut num_rows_left = mip_size.height;
@@ -391,7 +435,7 @@ fn clear_texture_via_buffer_copies<A: hal::Api>(
                         size: hal::CopyExtent {
                             width: mip_size.width, // full row
                             height: num_rows,
-                            depth: 1, // Only single slice of volume texture at a time right now
+                            depth: 1, // Slice doesn't fit into the zero buffer as a whole
                         },
                     });
 
@@ -1,16 +1,17 @@
 use crate::{
     binding_model::{
-        BindError, BindGroup, BindGroupLayouts, LateMinBufferBindingSizeMismatch,
+        BindError, BindGroup, BindGroupLayouts, LateMinBufferBindingSizeMismatch, PipelineLayout,
         PushConstantUploadError,
     },
     command::{
-        bind::Binder,
+        bind::{Binder, PushConstantState},
         end_pipeline_statistics_query,
         memory_init::{fixup_discarded_surfaces, SurfacesInDiscardState},
         BasePass, BasePassRef, BindGroupStateChange, CommandBuffer, CommandEncoderError,
-        CommandEncoderStatus, MapPassErr, PassErrorScope, QueryUseError, StateChange,
+        CommandEncoderStatus, ComputeBundle, MapPassErr, PassErrorScope, QueryUseError,
+        StateChange,
     },
-    device::{MissingDownlevelFlags, MissingFeatures},
+    device::{DeviceError, MissingDownlevelFlags, MissingFeatures},
     error::{ErrorFormatter, PrettyError},
     global::Global,
     hal_api::HalApi,
@@ -18,8 +19,10 @@ use crate::{
     id,
     id::DeviceId,
     identity::GlobalIdentityHandlerFactory,
-    init_tracker::MemoryInitKind,
-    pipeline,
+    indirect_validation::IndirectDispatches,
+    init_tracker::{MemoryInitKind, QuerySetInitTrackerAction},
+    pipeline::ComputePipeline,
+    ray_tracing::{TlasAction, TlasActionKind},
     resource::{self, Buffer, Texture},
     storage::Storage,
     track::{Tracker, UsageConflict, UsageScope},
@@ -27,6 +30,7 @@ use crate::{
     Label,
 };
 
+use arrayvec::ArrayVec;
 use hal::CommandEncoder as _;
 #[cfg(any(feature = "serial-pass", feature = "replay"))]
 use serde::Deserialize;
@@ -95,6 +99,7 @@ pub enum ComputeCommand {
         query_index: u32,
     },
     EndPipelineStatisticsQuery,
+    ExecuteBundle(id::ComputeBundleId),
 }
 
 #[cfg_attr(feature = "serial-pass", derive(serde::Deserialize, serde::Serialize))]
@@ -196,10 +201,14 @@ pub enum ComputePassErrorInner {
     InvalidBindGroup(id::BindGroupId),
     #[error("Device {0:?} is invalid")]
     InvalidDevice(DeviceId),
+    #[error(transparent)]
+    Device(#[from] DeviceError),
     #[error("Bind group index {index} is greater than the device's requested `max_bind_group` limit {max}")]
     BindGroupIndexOutOfRange { index: u32, max: u32 },
     #[error("Compute pipeline {0:?} is invalid")]
     InvalidPipeline(id::ComputePipelineId),
+    #[error("Compute bundle {0:?} is invalid")]
+    InvalidComputeBundle(id::ComputeBundleId),
     #[error("QuerySet {0:?} is invalid")]
     InvalidQuerySet(id::QuerySetId),
     #[error("Indirect buffer {0:?} is invalid or destroyed")]
@@ -282,6 +291,7 @@ where
 struct State<A: HalApi> {
     binder: Binder,
     pipeline: Option<id::ComputePipelineId>,
+    push_constants: PushConstantState,
     scope: UsageScope<A>,
     debug_scope_depth: u32,
 }
@@ -303,8 +313,6 @@ impl<A: HalApi> State<A> {
         Ok(())
     }
 
-    // `extra_buffer` is there to represent the indirect buffer that is also
-    // part of the usage scope.
     fn flush_states(
         &mut self,
         raw_encoder: &mut A::CommandEncoder,
@@ -314,37 +322,107 @@ impl<A: HalApi> State<A> {
         texture_guard: &Storage<Texture<A>, id::TextureId>,
         indirect_buffer: Option<id::Valid<id::BufferId>>,
     ) -> Result<(), UsageConflict> {
-        for id in self.binder.list_active() {
-            unsafe {
-                self.scope
-                    .merge_bind_group(texture_guard, &bind_group_guard[id].used)?
-            };
-            // Note: stateless trackers are not merged: the lifetime reference
-            // is held to the bind group itself.
-        }
+        let bind_groups = self
+            .binder
+            .list_active()
+            .collect::<ArrayVec<_, { hal::MAX_BIND_GROUPS }>>();
+        flush_dispatch_states(
+            raw_encoder,
+            &mut self.scope,
+            base_trackers,
+            &bind_groups,
+            bind_group_guard,
+            buffer_guard,
+            texture_guard,
+            indirect_buffer,
+        )
+    }
+
+    /// Unset the pipeline, bind groups and push constants after a bundle
+    /// was executed.
+    fn reset_bundle(&mut self) {
+        self.binder.reset();
+        self.push_constants.reset();
+        self.pipeline = None;
+    }
 
-        for id in self.binder.list_active() {
+    /// Set the pipeline, bind groups and push constants again, after an
+    /// internal dispatch disturbed them.
+    fn rebind(
+        &self,
+        raw_encoder: &mut A::CommandEncoder,
+        pipeline_guard: &Storage<ComputePipeline<A>, id::ComputePipelineId>,
+        pipeline_layout_guard: &Storage<PipelineLayout<A>, id::PipelineLayoutId>,
+        bind_group_guard: &Storage<BindGroup<A>, id::BindGroupId>,
+    ) {
+        let pipeline = &pipeline_guard[id::Valid(self.pipeline.unwrap())];
+        let pipeline_layout = &pipeline_layout_guard[pipeline.layout_id.value];
+        unsafe {
+            raw_encoder.set_compute_pipeline(&pipeline.raw);
+        }
+        for (index, e) in self.binder.list_active_entries() {
+            let raw_bg = &bind_group_guard[e.group_id.as_ref().unwrap().value].raw;
             unsafe {
-                base_trackers.set_and_remove_from_usage_scope_sparse(
-                    texture_guard,
-                    &mut self.scope,
-                    &bind_group_guard[id].used,
-                )
+                raw_encoder.set_bind_group(
+                    &pipeline_layout.raw,
+                    index as u32,
+                    raw_bg,
+                    &e.dynamic_offsets,
+                );
             }
         }
+        self.push_constants
+            .emit(pipeline_layout, |stages, offset, data| unsafe {
+                raw_encoder.set_push_constants(&pipeline_layout.raw, stages, offset, data);
+            });
+    }
+}
+
+/// Merge the resources used by a dispatch, with `bind_groups` bound, into
+/// `scope`, move them to `base_trackers` and record the barriers needed
+/// before the dispatch.
+///
+/// `indirect_buffer` is there to represent the indirect buffer that is also
+/// part of the usage scope.
+#[allow(clippy::too_many_arguments)]
+pub(super) fn flush_dispatch_states<A: HalApi>(
+    raw_encoder: &mut A::CommandEncoder,
+    scope: &mut UsageScope<A>,
+    base_trackers: &mut Tracker<A>,
+    bind_groups: &[id::Valid<id::BindGroupId>],
+    bind_group_guard: &Storage<BindGroup<A>, id::BindGroupId>,
+    buffer_guard: &Storage<Buffer<A>, id::BufferId>,
+    texture_guard: &Storage<Texture<A>, id::TextureId>,
+    indirect_buffer: Option<id::Valid<id::BufferId>>,
+) -> Result<(), UsageConflict> {
+    for &id in bind_groups {
+        unsafe { scope.merge_bind_group(texture_guard, &bind_group_guard[id].used)? };
+        // Note: stateless trackers are not merged: the lifetime reference
+        // is held to the bind group itself.
+    }
+    scope.check_aliasing(buffer_guard, texture_guard)?;
 
-        // Add the state of the indirect buffer if it hasn't been hit before.
+    for &id in bind_groups {
         unsafe {
-            base_trackers
-                .buffers
-                .set_and_remove_from_usage_scope_sparse(&mut self.scope.buffers, indirect_buffer);
+            base_trackers.set_and_remove_from_usage_scope_sparse(
+                texture_guard,
+                scope,
+                &bind_group_guard[id].used,
+            )
         }
+    }
 
-        log::trace!("Encoding dispatch barriers");
-
-        CommandBuffer::drain_barriers(raw_encoder, base_trackers, buffer_guard, texture_guard);
-        Ok(())
+    // Add the state of the indirect buffer if it hasn't been hit before.
+    unsafe {
+        base_trackers
+            .buffers
+            .set_and_remove_from_usage_scope_sparse(&mut scope.buffers, indirect_buffer);
     }
+
+    log::trace!("Encoding dispatch barriers");
+
+    CommandBuffer::drain_barriers(raw_encoder, base_trackers, buffer_guard, texture_guard);
+    Ok(())
 }
 
 // Common routines between render/compute
@@ -408,7 +486,7 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
             });
         }
 
-        let (_, mut token) = hub.render_bundles.read(&mut token);
+        let (compute_bundle_guard, mut token) = hub.compute_bundles.read(&mut token);
         let (pipeline_layout_guard, mut token) = hub.pipeline_layouts.read(&mut token);
         let (bind_group_guard, mut token) = hub.bind_groups.read(&mut token);
         let (pipeline_guard, mut token) = hub.compute_pipelines.read(&mut token);
@@ -420,6 +498,7 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
         let mut state = State {
             binder: Binder::new(),
             pipeline: None,
+            push_constants: PushConstantState::default(),
             scope: UsageScope::new(&*buffer_guard, &*texture_guard),
             debug_scope_depth: 0,
         };
@@ -437,22 +516,18 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
                 .map_pass_err(init_scope)?;
 
             // Unlike in render passes we can't delay resetting the query sets since
-            // there is no auxillary pass.
-            let range = if let (Some(index_a), Some(index_b)) =
-                (tw.beginning_of_pass_write_index, tw.end_of_pass_write_index)
+            // there is no auxillary pass. Only the written queries are reset, the
+            // ones in between keep the results of earlier submissions.
+            for index in [tw.beginning_of_pass_write_index, tw.end_of_pass_write_index]
+                .into_iter()
+                .flatten()
             {
-                Some(index_a.min(index_b)..index_a.max(index_b) + 1)
-            } else {
-                tw.beginning_of_pass_write_index
-                    .or(tw.end_of_pass_write_index)
-                    .map(|i| i..i + 1)
-            };
-            // Range should always be Some, both values being None should lead to a validation error.
-            // But no point in erroring over that nuance here!
-            if let Some(range) = range {
                 unsafe {
-                    raw.reset_queries(&query_set.raw, range);
+                    raw.reset_queries(&query_set.raw, index..index + 1);
                 }
+                cmd_buf
+                    .query_init_actions
+                    .push(QuerySetInitTrackerAction::write(tw.query_set, index));
             }
 
             Some(hal::ComputePassTimestampWrites {
@@ -473,9 +548,35 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
             Some(&*pipeline_guard),
             None,
             None,
+            Some(&*compute_bundle_guard),
             Some(&*query_set_guard),
         );
 
+        // Indirect dispatches are validated within the pass, see
+        // `indirect_validation`. This includes the ones of executed bundles.
+        if device.indirect_validation.is_some()
+            && device
+                .instance_flags
+                .contains(wgt::InstanceFlags::VALIDATION_INDIRECT_CALL)
+        {
+            let count = base
+                .commands
+                .iter()
+                .map(|command| match *command {
+                    ComputeCommand::DispatchIndirect { .. } => 1,
+                    ComputeCommand::ExecuteBundle(bundle_id) => compute_bundle_guard
+                        .get(bundle_id)
+                        .map_or(0, |bundle| bundle.indirect_dispatch_count),
+                    _ => 0,
+                })
+                .sum::<usize>();
+            if count != 0 {
+                let dispatches =
+                    IndirectDispatches::new(device, count as u64).map_pass_err(init_scope)?;
+                cmd_buf.indirect_dispatches = Some(dispatches);
+            }
+        }
+
         let hal_desc = hal::ComputePassDescriptor {
             label: base.label,
             timestamp_writes,
@@ -535,6 +636,17 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
                         ),
                     );
 
+                    cmd_buf.tlas_actions.extend(
+                        bind_group
+                            .used
+                            .acceleration_structures
+                            .used()
+                            .map(|id| TlasAction {
+                                id: id.0,
+                                kind: TlasActionKind::Use,
+                            }),
+                    );
+
                     for action in bind_group.used_texture_ranges.iter() {
                         pending_discard_init_fixups.extend(
                             cmd_buf
@@ -571,7 +683,7 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
 
                     state.pipeline = Some(pipeline_id);
 
-                    let pipeline: &pipeline::ComputePipeline<A> = cmd_buf
+                    let pipeline: &ComputePipeline<A> = cmd_buf
                         .trackers
                         .compute_pipelines
                         .add_single(&*pipeline_guard, pipeline_id)
@@ -606,26 +718,21 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
                             }
                         }
 
-                        // Clear push constant ranges
-                        let non_overlapping = super::bind::compute_nonoverlapping_ranges(
-                            &pipeline_layout.push_constant_ranges,
-                        );
-                        for range in non_overlapping {
-                            let offset = range.range.start;
-                            let size_bytes = range.range.end - offset;
-                            super::push_constant_clear(
-                                offset,
-                                size_bytes,
-                                |clear_offset, clear_data| unsafe {
+                        // Send the push constants again
+                        state
+                            .push_constants
+                            .change_pipeline_layout(
+                                pipeline_layout,
+                                |stages, offset, data| unsafe {
                                     raw.set_push_constants(
                                         &pipeline_layout.raw,
-                                        wgt::ShaderStages::COMPUTE,
-                                        clear_offset,
-                                        clear_data,
+                                        stages,
+                                        offset,
+                                        data,
                                     );
                                 },
-                            );
-                        }
+                            )
+                            .map_pass_err(PassErrorScope::SetPushConstant)?;
                     }
                 }
                 ComputeCommand::SetPushConstant {
@@ -635,37 +742,37 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
                 } => {
                     let scope = PassErrorScope::SetPushConstant;
 
-                    let end_offset_bytes = offset + size_bytes;
                     let values_end_offset =
                         (values_offset + size_bytes / wgt::PUSH_CONSTANT_ALIGNMENT) as usize;
                     let data_slice =
                         &base.push_constant_data[(values_offset as usize)..values_end_offset];
 
-                    let pipeline_layout_id = state
+                    // Without a pipeline layout, the values are sent when one is bound.
+                    let pipeline_layout = state
                         .binder
                         .pipeline_layout_id
-                        //TODO: don't error here, lazily update the push constants
-                        .ok_or(ComputePassErrorInner::Dispatch(
-                            DispatchError::MissingPipeline,
-                        ))
-                        .map_pass_err(scope)?;
-                    let pipeline_layout = &pipeline_layout_guard[pipeline_layout_id];
+                        .map(|id| &pipeline_layout_guard[id]);
 
-                    pipeline_layout
-                        .validate_push_constant_ranges(
+                    state
+                        .push_constants
+                        .set(
+                            pipeline_layout,
+                            cmd_buf.limits.max_push_constant_size,
                             wgt::ShaderStages::COMPUTE,
                             offset,
-                            end_offset_bytes,
+                            data_slice,
                         )
                         .map_pass_err(scope)?;
 
-                    unsafe {
-                        raw.set_push_constants(
-                            &pipeline_layout.raw,
-                            wgt::ShaderStages::COMPUTE,
-                            offset,
-                            data_slice,
-                        );
+                    if let Some(pipeline_layout) = pipeline_layout {
+                        unsafe {
+                            raw.set_push_constants(
+                                &pipeline_layout.raw,
+                                wgt::ShaderStages::COMPUTE,
+                                offset,
+                                data_slice,
+                            );
+                        }
                     }
                 }
                 ComputeCommand::Dispatch(groups) => {
@@ -721,10 +828,16 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
                         .require_downlevel_flags(wgt::DownlevelFlags::INDIRECT_EXECUTION)
                         .map_pass_err(scope)?;
 
+                    // The arguments are read by the validation dispatch as well.
+                    let validate = cmd_buf.indirect_dispatches.is_some() && offset % 4 == 0;
+                    let usage = match validate {
+                        true => hal::BufferUses::INDIRECT | hal::BufferUses::STORAGE_READ,
+                        false => hal::BufferUses::INDIRECT,
+                    };
                     let indirect_buffer: &Buffer<A> = state
                         .scope
                         .buffers
-                        .merge_single(&*buffer_guard, buffer_id, hal::BufferUses::INDIRECT)
+                        .merge_single(&*buffer_guard, buffer_id, usage)
                         .map_pass_err(scope)?;
                     check_buffer_usage(indirect_buffer.usage, wgt::BufferUsages::INDIRECT)
                         .map_pass_err(scope)?;
@@ -765,6 +878,21 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
                             Some(id::Valid(buffer_id)),
                         )
                         .map_pass_err(scope)?;
+                    let (buf_raw, offset) = match cmd_buf.indirect_dispatches {
+                        Some(ref mut dispatches) if validate => {
+                            let output_offset = dispatches
+                                .validate(device, raw, buf_raw, offset)
+                                .map_pass_err(scope)?;
+                            state.rebind(
+                                raw,
+                                &*pipeline_guard,
+                                &*pipeline_layout_guard,
+                                &*bind_group_guard,
+                            );
+                            (dispatches.buffer(), output_offset)
+                        }
+                        _ => (buf_raw, offset),
+                    };
                     unsafe {
                         raw.dispatch_indirect(buf_raw, offset);
                     }
@@ -816,7 +944,13 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
                         .map_pass_err(scope)?;
 
                     query_set
-                        .validate_and_write_timestamp(raw, query_set_id, query_index, None)
+                        .validate_and_write_timestamp(
+                            raw,
+                            query_set_id,
+                            query_index,
+                            None,
+                            &mut cmd_buf.query_init_actions,
+                        )
                         .map_pass_err(scope)?;
                 }
                 ComputeCommand::BeginPipelineStatisticsQuery {
@@ -838,6 +972,7 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
                             query_set_id,
                             query_index,
                             None,
+                            &mut cmd_buf.query_init_actions,
                             &mut active_query,
                         )
                         .map_pass_err(scope)?;
@@ -848,6 +983,68 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
                     end_pipeline_statistics_query(raw, &*query_set_guard, &mut active_query)
                         .map_pass_err(scope)?;
                 }
+                ComputeCommand::ExecuteBundle(bundle_id) => {
+                    let scope = PassErrorScope::ExecuteBundle;
+
+                    let bundle: &ComputeBundle<A> = cmd_buf
+                        .trackers
+                        .compute_bundles
+                        .add_single(&*compute_bundle_guard, bundle_id)
+                        .ok_or(ComputePassErrorInner::InvalidComputeBundle(bundle_id))
+                        .map_pass_err(scope)?;
+
+                    if bundle.device_id.value != cmd_buf.device_id.value {
+                        return Err(DeviceError::WrongDevice).map_pass_err(scope);
+                    }
+
+                    cmd_buf.buffer_memory_init_actions.extend(
+                        bundle
+                            .buffer_memory_init_actions
+                            .iter()
+                            .filter_map(|action| match buffer_guard.get(action.id) {
+                                Ok(buffer) => buffer.initialization_status.check_action(action),
+                                Err(_) => None,
+                            }),
+                    );
+                    for bind_group_id in bundle.used.bind_groups.used() {
+                        cmd_buf.tlas_actions.extend(
+                            bind_group_guard[bind_group_id]
+                                .used
+                                .acceleration_structures
+                                .used()
+                                .map(|id| TlasAction {
+                                    id: id.0,
+                                    kind: TlasActionKind::Use,
+                                }),
+                        );
+                    }
+                    for action in bundle.texture_memory_init_actions.iter() {
+                        pending_discard_init_fixups.extend(
+                            cmd_buf
+                                .texture_memory_actions
+                                .register_init_action(action, &texture_guard),
+                        );
+                    }
+
+                    unsafe {
+                        bundle.execute(
+                            raw,
+                            device,
+                            &mut state.scope,
+                            &mut intermediate_trackers,
+                            cmd_buf.indirect_dispatches.as_mut(),
+                            &*pipeline_layout_guard,
+                            &*bind_group_guard,
+                            &*pipeline_guard,
+                            &*buffer_guard,
+                            &*texture_guard,
+                        )
+                    }
+                    .map_pass_err(scope)?;
+
+                    cmd_buf.trackers.add_from_compute_bundle(&bundle.used);
+                    state.reset_bundle();
+                }
             }
         }
 
@@ -879,6 +1076,11 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
             &*buffer_guard,
             &*texture_guard,
         );
+        if let Some(dispatches) = cmd_buf.indirect_dispatches.take() {
+            dispatches
+                .encode(&device.raw, transit, &mut cmd_buf.temp_resources)
+                .map_pass_err(init_scope)?;
+        }
         // Close the command buffer, and swap it with the previous.
         cmd_buf.encoder.close_and_swap();
 
@@ -1076,4 +1278,25 @@ pub mod compute_ffi {
             .commands
             .push(ComputeCommand::EndPipelineStatisticsQuery);
     }
+
+    /// # Safety
+    ///
+    /// This function is unsafe as there is no guarantee that the given pointer is
+    /// valid for `compute_bundle_ids_length` elements.
+    #[no_mangle]
+    pub unsafe extern "C" fn wgpu_compute_pass_execute_bundles(
+        pass: &mut ComputePass,
+        compute_bundle_ids: *const id::ComputeBundleId,
+        compute_bundle_ids_length: usize,
+    ) {
+        for &bundle_id in
+            unsafe { slice::from_raw_parts(compute_bundle_ids, compute_bundle_ids_length) }
+        {
+            pass.base
+                .commands
+                .push(ComputeCommand::ExecuteBundle(bundle_id));
+        }
+        pass.current_pipeline.reset();
+        pass.current_bind_groups.reset();
+    }
 }
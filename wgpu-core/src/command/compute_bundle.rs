@@ -0,0 +1,917 @@
+/*! Compute Bundles
+
+A compute bundle is a prerecorded sequence of dispatches that can be replayed
+in a compute pass with a single call, the same way a [render bundle] is
+replayed in a render pass. The commands are validated once, when the bundle is
+finished, and replaying the bundle only has to encode them.
+
+Only pipeline changes, bind group changes and dispatches can be recorded in a
+compute bundle. Compute bundles have no WebGPU equivalent.
+
+## Compute Bundle Isolation
+
+Like render bundles, compute bundles are isolated from the passes that execute
+them: a dispatch in a bundle only uses the pipeline and bind groups set within
+the bundle, and after executing a bundle the pipeline, bind groups and push
+constants of the compute pass are unset.
+
+Setting a pipeline in a bundle initializes any push constant storage it could
+access to zero.
+
+## Compute Bundle Lifecycle
+
+To create a compute bundle:
+
+1) Create a [`ComputeBundleEncoder`] by calling
+   [`Global::device_create_compute_bundle_encoder`][Gdccbe].
+
+2) Record commands in the `ComputeBundleEncoder` using functions from the
+   [`compute_bundle_ffi`] module.
+
+3) Call [`Global::compute_bundle_encoder_finish`][Gcbef], which validates
+   the command stream and returns a `ComputeBundleId`.
+
+4) Then, any number of times, call [`wgpu_compute_pass_execute_bundles`][wcpeb]
+   to execute the bundle as part of some compute pass.
+
+## Execution
+
+Unlike a render pass, where all the resources of the pass form a single usage
+scope, every dispatch of a compute pass is its own usage scope. The resources
+used by the dispatches of a bundle can therefore not be merged up front:
+[`ComputeBundle::execute`] builds the usage scope of each dispatch from the
+bind groups it uses and records the barriers it needs, exactly like
+`command_encoder_run_compute_pass` does.
+
+[render bundle]: crate::command::RenderBundle
+[Gdccbe]: crate::global::Global::device_create_compute_bundle_encoder
+[Gcbef]: crate::global::Global::compute_bundle_encoder_finish
+[wcpeb]: crate::command::compute_ffi::wgpu_compute_pass_execute_bundles
+!*/
+
+use crate::{
+    binding_model::{BindGroup, BindGroupLayouts, PipelineLayout},
+    command::{
+        bind::Binder, compute::flush_dispatch_states, BasePass, BindGroupStateChange,
+        ComputeCommand, ComputePassErrorInner, DispatchError, MapPassErr, PassErrorScope,
+        StateChange,
+    },
+    device::{Device, DeviceError, SHADER_STAGE_COUNT},
+    error::{ErrorFormatter, PrettyError},
+    hal_api::HalApi,
+    hub::{Hub, Token},
+    id,
+    identity::GlobalIdentityHandlerFactory,
+    indirect_validation::IndirectDispatches,
+    init_tracker::{BufferInitTrackerAction, MemoryInitKind, TextureInitTrackerAction},
+    pipeline::ComputePipeline,
+    resource::{Buffer, Resource, Texture},
+    storage::Storage,
+    track::{ComputeBundleScope, Tracker, UsageScope},
+    validation::check_buffer_usage,
+    Label, LabelHelpers, LifeGuard, Stored,
+};
+use arrayvec::ArrayVec;
+use std::{mem, ops::Range};
+use thiserror::Error;
+
+use hal::CommandEncoder as _;
+
+/// Describes a [`ComputeBundleEncoder`].
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "trace", derive(serde::Serialize))]
+#[cfg_attr(feature = "replay", derive(serde::Deserialize))]
+pub struct ComputeBundleEncoderDescriptor<'a> {
+    /// Debug label of the compute bundle encoder.
+    ///
+    /// This will show up in graphics debuggers for easy identification.
+    pub label: Label<'a>,
+}
+
+#[derive(Debug)]
+#[cfg_attr(feature = "serial-pass", derive(serde::Deserialize, serde::Serialize))]
+pub struct ComputeBundleEncoder {
+    base: BasePass<ComputeCommand>,
+    parent_id: id::DeviceId,
+
+    // Resource binding dedupe state.
+    #[cfg_attr(feature = "serial-pass", serde(skip))]
+    current_bind_groups: BindGroupStateChange,
+    #[cfg_attr(feature = "serial-pass", serde(skip))]
+    current_pipeline: StateChange<id::ComputePipelineId>,
+}
+
+impl ComputeBundleEncoder {
+    pub fn new(
+        desc: &ComputeBundleEncoderDescriptor,
+        parent_id: id::DeviceId,
+        base: Option<BasePass<ComputeCommand>>,
+    ) -> Self {
+        Self {
+            base: base.unwrap_or_else(|| BasePass::new(&desc.label)),
+            parent_id,
+            current_bind_groups: BindGroupStateChange::new(),
+            current_pipeline: StateChange::new(),
+        }
+    }
+
+    #[cfg(feature = "trace")]
+    pub(crate) fn to_base_pass(&self) -> BasePass<ComputeCommand> {
+        BasePass::from_ref(self.base.as_ref())
+    }
+
+    pub fn parent(&self) -> id::DeviceId {
+        self.parent_id
+    }
+
+    /// Convert this encoder's commands into a [`ComputeBundle`].
+    ///
+    /// Every command is validated here, so that executing the bundle only
+    /// has to track the usage of its resources. Bind groups are only set
+    /// right before the dispatches that use them, once a pipeline is known,
+    /// and redundant changes are dropped.
+    pub(crate) fn finish<A: HalApi, G: GlobalIdentityHandlerFactory>(
+        self,
+        desc: &ComputeBundleDescriptor,
+        device: &Device<A>,
+        hub: &Hub<A, G>,
+        token: &mut Token<Device<A>>,
+    ) -> Result<ComputeBundle<A>, ComputeBundleError> {
+        let (pipeline_layout_guard, mut token) = hub.pipeline_layouts.read(token);
+        let (bind_group_layout_guard, mut token) = hub.bind_group_layouts.read(&mut token);
+        let (bind_group_guard, mut token) = hub.bind_groups.read(&mut token);
+        let (pipeline_guard, mut token) = hub.compute_pipelines.read(&mut token);
+        let (buffer_guard, _) = hub.buffers.read(&mut token);
+
+        let mut state = State {
+            trackers: ComputeBundleScope::new(&*buffer_guard, &*bind_group_guard, &*pipeline_guard),
+            binder: Binder::new(),
+            pipeline: None,
+            bind: (0..hal::MAX_BIND_GROUPS).map(|_| None).collect(),
+            flat_dynamic_offsets: Vec::new(),
+        };
+        let mut commands = Vec::new();
+        let mut buffer_memory_init_actions = Vec::new();
+        let mut texture_memory_init_actions = Vec::new();
+        let mut indirect_dispatch_count = 0;
+
+        let base = self.base.as_ref();
+        let mut next_dynamic_offset = 0;
+
+        for &command in base.commands {
+            match command {
+                ComputeCommand::SetBindGroup {
+                    index,
+                    num_dynamic_offsets,
+                    bind_group_id,
+                } => {
+                    let scope = PassErrorScope::SetBindGroup(bind_group_id);
+
+                    let bind_group: &BindGroup<A> = state
+                        .trackers
+                        .bind_groups
+                        .add_single(&*bind_group_guard, bind_group_id)
+                        .ok_or(ComputePassErrorInner::InvalidBindGroup(bind_group_id))
+                        .map_pass_err(scope)?;
+                    self.check_valid_to_use(bind_group.device_id.value)
+                        .map_pass_err(scope)?;
+
+                    let max_bind_groups = device.limits.max_bind_groups;
+                    if index >= max_bind_groups {
+                        return Err(ComputePassErrorInner::BindGroupIndexOutOfRange {
+                            index,
+                            max: max_bind_groups,
+                        })
+                        .map_pass_err(scope);
+                    }
+
+                    // Identify the next `num_dynamic_offsets` entries from `base.dynamic_offsets`.
+                    let offsets_range =
+                        next_dynamic_offset..next_dynamic_offset + num_dynamic_offsets as usize;
+                    next_dynamic_offset = offsets_range.end;
+                    let offsets = &base.dynamic_offsets[offsets_range.clone()];
+
+                    bind_group
+                        .validate_dynamic_bindings(index, offsets, &device.limits)
+                        .map_pass_err(scope)?;
+
+                    buffer_memory_init_actions.extend_from_slice(&bind_group.used_buffer_ranges);
+                    texture_memory_init_actions.extend_from_slice(&bind_group.used_texture_ranges);
+
+                    state.binder.assign_group(
+                        index as usize,
+                        id::Valid(bind_group_id),
+                        bind_group,
+                        offsets,
+                    );
+                    state.set_bind_group(index, bind_group_id, bind_group.layout_id, offsets_range);
+                }
+                ComputeCommand::SetPipeline(pipeline_id) => {
+                    let scope = PassErrorScope::SetPipelineCompute(pipeline_id);
+
+                    let pipeline: &ComputePipeline<A> = state
+                        .trackers
+                        .compute_pipelines
+                        .add_single(&*pipeline_guard, pipeline_id)
+                        .ok_or(ComputePassErrorInner::InvalidPipeline(pipeline_id))
+                        .map_pass_err(scope)?;
+                    self.check_valid_to_use(pipeline.device_id.value)
+                        .map_pass_err(scope)?;
+
+                    let layout = &pipeline_layout_guard[pipeline.layout_id.value];
+                    let pipeline_state = PipelineState::new(pipeline_id, layout);
+
+                    state.binder.change_pipeline_layout(
+                        &*pipeline_layout_guard,
+                        pipeline.layout_id.value,
+                        &pipeline.late_sized_buffer_groups,
+                    );
+
+                    commands.push(command);
+
+                    state.invalidate_bind_groups(&pipeline_state, layout);
+                    state.pipeline = Some(pipeline_state);
+                }
+                ComputeCommand::Dispatch(groups) => {
+                    let scope = PassErrorScope::Dispatch {
+                        indirect: false,
+                        pipeline: state.pipeline_id(),
+                    };
+
+                    let used_bind_groups = state
+                        .is_ready(&*bind_group_layout_guard)
+                        .map_pass_err(scope)?;
+
+                    let groups_size_limit = device.limits.max_compute_workgroups_per_dimension;
+                    if groups.iter().any(|&count| count > groups_size_limit) {
+                        return Err(DispatchError::InvalidGroupSize {
+                            current: groups,
+                            limit: groups_size_limit,
+                        })
+                        .map_pass_err(scope);
+                    }
+
+                    commands.extend(state.flush_binds(used_bind_groups, base.dynamic_offsets));
+                    commands.push(command);
+                }
+                ComputeCommand::DispatchIndirect { buffer_id, offset } => {
+                    let scope = PassErrorScope::Dispatch {
+                        indirect: true,
+                        pipeline: state.pipeline_id(),
+                    };
+
+                    let used_bind_groups = state
+                        .is_ready(&*bind_group_layout_guard)
+                        .map_pass_err(scope)?;
+
+                    device
+                        .require_downlevel_flags(wgt::DownlevelFlags::INDIRECT_EXECUTION)
+                        .map_pass_err(scope)?;
+
+                    let buffer: &Buffer<A> = state
+                        .trackers
+                        .buffers
+                        .merge_single(&*buffer_guard, buffer_id, hal::BufferUses::INDIRECT)
+                        .map_pass_err(scope)?;
+                    self.check_valid_to_use(buffer.device_id.value)
+                        .map_pass_err(scope)?;
+                    check_buffer_usage(buffer.usage, wgt::BufferUsages::INDIRECT)
+                        .map_pass_err(scope)?;
+
+                    let end_offset = offset + mem::size_of::<wgt::DispatchIndirectArgs>() as u64;
+                    if end_offset > buffer.size {
+                        return Err(ComputePassErrorInner::IndirectBufferOverrun {
+                            offset,
+                            end_offset,
+                            buffer_size: buffer.size,
+                        })
+                        .map_pass_err(scope);
+                    }
+
+                    buffer_memory_init_actions.extend(buffer.initialization_status.create_action(
+                        buffer_id,
+                        offset..end_offset,
+                        MemoryInitKind::NeedsInitializedMemory,
+                    ));
+
+                    commands.extend(state.flush_binds(used_bind_groups, base.dynamic_offsets));
+                    commands.push(command);
+                    indirect_dispatch_count += 1;
+                }
+                ComputeCommand::SetPushConstant { .. }
+                | ComputeCommand::PushDebugGroup { .. }
+                | ComputeCommand::PopDebugGroup
+                | ComputeCommand::InsertDebugMarker { .. }
+                | ComputeCommand::WriteTimestamp { .. }
+                | ComputeCommand::BeginPipelineStatisticsQuery { .. }
+                | ComputeCommand::EndPipelineStatisticsQuery
+                | ComputeCommand::ExecuteBundle(_) => {
+                    unreachable!("not supported by a compute bundle")
+                }
+            }
+        }
+
+        Ok(ComputeBundle {
+            base: BasePass {
+                label: desc.label.as_ref().map(|cow| cow.to_string()),
+                commands,
+                dynamic_offsets: state.flat_dynamic_offsets,
+                string_data: Vec::new(),
+                push_constant_data: Vec::new(),
+            },
+            device_id: Stored {
+                value: id::Valid(self.parent_id),
+                ref_count: device.life_guard.add_ref(),
+            },
+            used: state.trackers,
+            buffer_memory_init_actions,
+            texture_memory_init_actions,
+            indirect_dispatch_count,
+            life_guard: LifeGuard::new(desc.label.borrow_or_default()),
+        })
+    }
+
+    fn check_valid_to_use(
+        &self,
+        device_id: id::Valid<id::DeviceId>,
+    ) -> Result<(), ComputeBundleErrorInner> {
+        if device_id.0 != self.parent_id {
+            return Err(ComputeBundleErrorInner::NotValidToUse);
+        }
+
+        Ok(())
+    }
+}
+
+pub type ComputeBundleDescriptor<'a> = wgt::ComputeBundleDescriptor<Label<'a>>;
+
+pub struct ComputeBundle<A: HalApi> {
+    // Normalized command stream. Bind groups are only set after a pipeline,
+    // and before the dispatches that use them.
+    base: BasePass<ComputeCommand>,
+    pub(crate) device_id: Stored<id::DeviceId>,
+    pub(crate) used: ComputeBundleScope<A>,
+    pub(super) buffer_memory_init_actions: Vec<BufferInitTrackerAction>,
+    pub(super) texture_memory_init_actions: Vec<TextureInitTrackerAction>,
+    /// The number of indirect dispatches, each of which may need a slot in
+    /// the [`IndirectDispatches`] of the pass.
+    pub(super) indirect_dispatch_count: usize,
+    pub(crate) life_guard: LifeGuard,
+}
+
+#[cfg(any(
+    not(target_arch = "wasm32"),
+    all(
+        feature = "fragile-send-sync-non-atomic-wasm",
+        not(target_feature = "atomics")
+    )
+))]
+unsafe impl<A: HalApi> Send for ComputeBundle<A> {}
+#[cfg(any(
+    not(target_arch = "wasm32"),
+    all(
+        feature = "fragile-send-sync-non-atomic-wasm",
+        not(target_feature = "atomics")
+    )
+))]
+unsafe impl<A: HalApi> Sync for ComputeBundle<A> {}
+
+impl<A: HalApi> ComputeBundle<A> {
+    /// Encode the contents into a native command buffer.
+    ///
+    /// The commands were validated in [`ComputeBundleEncoder::finish`], but
+    /// every dispatch is still its own usage scope: the resources it uses are
+    /// merged into `scope`, moved to `base_trackers` and the barriers are
+    /// recorded, like in `command_encoder_run_compute_pass`.
+    ///
+    /// The function fails if some of the used resources conflict with each
+    /// other or are destroyed.
+    #[allow(clippy::too_many_arguments)]
+    pub(super) unsafe fn execute(
+        &self,
+        raw: &mut A::CommandEncoder,
+        device: &Device<A>,
+        scope: &mut UsageScope<A>,
+        base_trackers: &mut Tracker<A>,
+        mut indirect_dispatches: Option<&mut IndirectDispatches<A>>,
+        pipeline_layout_guard: &Storage<PipelineLayout<A>, id::PipelineLayoutId>,
+        bind_group_guard: &Storage<BindGroup<A>, id::BindGroupId>,
+        pipeline_guard: &Storage<ComputePipeline<A>, id::ComputePipelineId>,
+        buffer_guard: &Storage<Buffer<A>, id::BufferId>,
+        texture_guard: &Storage<Texture<A>, id::TextureId>,
+    ) -> Result<(), ComputePassErrorInner> {
+        let mut next_dynamic_offset = 0;
+        let mut pipeline = None::<&ComputePipeline<A>>;
+        // The bind group set at each index, with the range of its dynamic
+        // offsets in `self.base.dynamic_offsets`.
+        let mut bind: [Option<(id::Valid<id::BindGroupId>, Range<usize>)>; hal::MAX_BIND_GROUPS] =
+            Default::default();
+        if let Some(ref label) = self.base.label {
+            unsafe { raw.begin_debug_marker(label) };
+        }
+
+        for command in self.base.commands.iter() {
+            match *command {
+                ComputeCommand::SetBindGroup {
+                    index,
+                    num_dynamic_offsets,
+                    bind_group_id,
+                } => {
+                    let offsets =
+                        next_dynamic_offset..next_dynamic_offset + num_dynamic_offsets as usize;
+                    next_dynamic_offset = offsets.end;
+                    let bind_group = bind_group_guard.get(bind_group_id).unwrap();
+                    let pipeline_layout = &pipeline_layout_guard[pipeline.unwrap().layout_id.value];
+                    unsafe {
+                        raw.set_bind_group(
+                            &pipeline_layout.raw,
+                            index,
+                            &bind_group.raw,
+                            &self.base.dynamic_offsets[offsets.clone()],
+                        )
+                    };
+                    bind[index as usize] = Some((id::Valid(bind_group_id), offsets));
+                }
+                ComputeCommand::SetPipeline(pipeline_id) => {
+                    let compute_pipeline = pipeline_guard.get(pipeline_id).unwrap();
+                    unsafe { raw.set_compute_pipeline(&compute_pipeline.raw) };
+                    self.clear_push_constants(
+                        raw,
+                        &pipeline_layout_guard[compute_pipeline.layout_id.value],
+                    );
+                    pipeline = Some(compute_pipeline);
+                }
+                ComputeCommand::Dispatch(groups) => {
+                    let pipeline_layout = &pipeline_layout_guard[pipeline.unwrap().layout_id.value];
+                    let bind_groups = Self::used_bind_groups(&bind, pipeline_layout);
+                    flush_dispatch_states(
+                        raw,
+                        scope,
+                        base_trackers,
+                        &bind_groups,
+                        bind_group_guard,
+                        buffer_guard,
+                        texture_guard,
+                        None,
+                    )?;
+                    unsafe { raw.dispatch(groups) };
+                }
+                ComputeCommand::DispatchIndirect { buffer_id, offset } => {
+                    let compute_pipeline = pipeline.unwrap();
+                    let pipeline_layout = &pipeline_layout_guard[compute_pipeline.layout_id.value];
+                    let bind_groups = Self::used_bind_groups(&bind, pipeline_layout);
+
+                    // The arguments are read by the validation dispatch as well.
+                    let validate = indirect_dispatches.is_some() && offset % 4 == 0;
+                    let usage = match validate {
+                        true => hal::BufferUses::INDIRECT | hal::BufferUses::STORAGE_READ,
+                        false => hal::BufferUses::INDIRECT,
+                    };
+                    let buffer = scope.buffers.merge_single(buffer_guard, buffer_id, usage)?;
+                    let buf_raw = buffer
+                        .raw
+                        .as_ref()
+                        .ok_or(ComputePassErrorInner::InvalidIndirectBuffer(buffer_id))?;
+
+                    flush_dispatch_states(
+                        raw,
+                        scope,
+                        base_trackers,
+                        &bind_groups,
+                        bind_group_guard,
+                        buffer_guard,
+                        texture_guard,
+                        Some(id::Valid(buffer_id)),
+                    )?;
+                    let (buf_raw, offset) = match indirect_dispatches {
+                        Some(ref mut dispatches) if validate => {
+                            let output_offset =
+                                dispatches.validate(device, raw, buf_raw, offset)?;
+
+                            // Set the state of the bundle again.
+                            unsafe { raw.set_compute_pipeline(&compute_pipeline.raw) };
+                            for (index, &(bind_group_id, ref offsets)) in bind
+                                [..pipeline_layout.bind_group_layout_ids.len()]
+                                .iter()
+                                .enumerate()
+                                .filter_map(|(index, entry)| Some((index, entry.as_ref()?)))
+                            {
+                                unsafe {
+                                    raw.set_bind_group(
+                                        &pipeline_layout.raw,
+                                        index as u32,
+                                        &bind_group_guard[bind_group_id].raw,
+                                        &self.base.dynamic_offsets[offsets.clone()],
+                                    )
+                                };
+                            }
+                            self.clear_push_constants(raw, pipeline_layout);
+
+                            (dispatches.buffer(), output_offset)
+                        }
+                        _ => (buf_raw, offset),
+                    };
+                    unsafe { raw.dispatch_indirect(buf_raw, offset) };
+                }
+                ComputeCommand::SetPushConstant { .. }
+                | ComputeCommand::PushDebugGroup { .. }
+                | ComputeCommand::PopDebugGroup
+                | ComputeCommand::InsertDebugMarker { .. }
+                | ComputeCommand::WriteTimestamp { .. }
+                | ComputeCommand::BeginPipelineStatisticsQuery { .. }
+                | ComputeCommand::EndPipelineStatisticsQuery
+                | ComputeCommand::ExecuteBundle(_) => {
+                    unreachable!("not supported by a compute bundle")
+                }
+            }
+        }
+
+        if self.base.label.is_some() {
+            unsafe { raw.end_debug_marker() };
+        }
+
+        Ok(())
+    }
+
+    /// Return the bind groups used by a dispatch with `pipeline_layout`.
+    fn used_bind_groups(
+        bind: &[Option<(id::Valid<id::BindGroupId>, Range<usize>)>],
+        pipeline_layout: &PipelineLayout<A>,
+    ) -> ArrayVec<id::Valid<id::BindGroupId>, { hal::MAX_BIND_GROUPS }> {
+        bind[..pipeline_layout.bind_group_layout_ids.len()]
+            .iter()
+            .flatten()
+            .map(|&(bind_group_id, _)| bind_group_id)
+            .collect()
+    }
+
+    /// Zero the push constant ranges of `pipeline_layout`.
+    fn clear_push_constants(
+        &self,
+        raw: &mut A::CommandEncoder,
+        pipeline_layout: &PipelineLayout<A>,
+    ) {
+        for range in
+            super::bind::compute_nonoverlapping_ranges(&pipeline_layout.push_constant_ranges)
+        {
+            super::push_constant_clear(
+                range.range.start,
+                range.range.end - range.range.start,
+                |clear_offset, clear_data| unsafe {
+                    raw.set_push_constants(
+                        &pipeline_layout.raw,
+                        range.stages,
+                        clear_offset,
+                        clear_data,
+                    );
+                },
+            );
+        }
+    }
+}
+
+impl<A: HalApi> Resource for ComputeBundle<A> {
+    const TYPE: &'static str = "ComputeBundle";
+
+    fn life_guard(&self) -> &LifeGuard {
+        &self.life_guard
+    }
+}
+
+/// A bind group that has been set at a particular index during compute
+/// bundle encoding.
+#[derive(Debug)]
+struct BindState {
+    /// The id of the bind group set at this index.
+    bind_group_id: id::BindGroupId,
+
+    /// The layout of `group`.
+    layout_id: id::Valid<id::BindGroupLayoutId>,
+
+    /// The range of dynamic offsets for this bind group, in the original
+    /// command stream's `BassPass::dynamic_offsets` array.
+    dynamic_offsets: Range<usize>,
+
+    /// True if this index's contents have been changed since the last time we
+    /// generated a `SetBindGroup` command.
+    is_dirty: bool,
+}
+
+/// The bundle's current pipeline, and the information needed to know which
+/// bind groups must be set again when it changes.
+struct PipelineState {
+    /// The pipeline's id.
+    id: id::ComputePipelineId,
+
+    /// Ranges of push constants this pipeline uses, copied from the pipeline
+    /// layout.
+    push_constant_ranges: ArrayVec<wgt::PushConstantRange, { SHADER_STAGE_COUNT }>,
+
+    /// The number of bind groups this pipeline uses.
+    used_bind_groups: usize,
+}
+
+impl PipelineState {
+    fn new<A: HalApi>(pipeline_id: id::ComputePipelineId, layout: &PipelineLayout<A>) -> Self {
+        Self {
+            id: pipeline_id,
+            push_constant_ranges: layout.push_constant_ranges.iter().cloned().collect(),
+            used_bind_groups: layout.bind_group_layout_ids.len(),
+        }
+    }
+}
+
+/// State for validating and cleaning up compute bundle command streams.
+///
+/// The [`Binder`] validates the bind groups of each dispatch against the
+/// current pipeline, the same way a compute pass does, while `bind` records
+/// the `SetBindGroup` commands to emit before the next dispatch.
+struct State<A: HalApi> {
+    /// Resources used by this bundle. This will become [`ComputeBundle::used`].
+    trackers: ComputeBundleScope<A>,
+
+    binder: Binder,
+
+    /// The currently set pipeline, if any.
+    pipeline: Option<PipelineState>,
+
+    /// The bind group set at each index, if any.
+    bind: ArrayVec<Option<BindState>, { hal::MAX_BIND_GROUPS }>,
+
+    /// Dynamic offset values used by the cleaned-up command sequence.
+    ///
+    /// This becomes the final [`ComputeBundle`]'s [`BasePass`]'s
+    /// [`dynamic_offsets`] list.
+    ///
+    /// [`dynamic_offsets`]: BasePass::dynamic_offsets
+    flat_dynamic_offsets: Vec<wgt::DynamicOffset>,
+}
+
+impl<A: HalApi> State<A> {
+    /// Return the id of the current pipeline, if any.
+    fn pipeline_id(&self) -> Option<id::ComputePipelineId> {
+        self.pipeline.as_ref().map(|p| p.id)
+    }
+
+    /// Check that a dispatch can be made, and return the number of bind
+    /// groups it uses.
+    fn is_ready(&self, bind_group_layouts: &BindGroupLayouts<A>) -> Result<usize, DispatchError> {
+        let pipeline = self
+            .pipeline
+            .as_ref()
+            .ok_or(DispatchError::MissingPipeline)?;
+        let bind_mask = self.binder.invalid_mask(bind_group_layouts);
+        if bind_mask != 0 {
+            return Err(DispatchError::IncompatibleBindGroup {
+                index: bind_mask.trailing_zeros(),
+            });
+        }
+        self.binder.check_late_buffer_bindings()?;
+
+        Ok(pipeline.used_bind_groups)
+    }
+
+    /// Mark all non-empty bind group table entries from `index` onwards as dirty.
+    fn invalidate_bind_group_from(&mut self, index: usize) {
+        for contents in self.bind[index..].iter_mut().flatten() {
+            contents.is_dirty = true;
+        }
+    }
+
+    fn set_bind_group(
+        &mut self,
+        slot: u32,
+        bind_group_id: id::BindGroupId,
+        layout_id: id::Valid<id::BindGroupLayoutId>,
+        dynamic_offsets: Range<usize>,
+    ) {
+        // If this call wouldn't actually change this index's state, we can
+        // return early.  (If there are dynamic offsets, the range will always
+        // be different.)
+        if dynamic_offsets.is_empty() {
+            if let Some(ref contents) = self.bind[slot as usize] {
+                if contents.bind_group_id == bind_group_id {
+                    return;
+                }
+            }
+        }
+
+        // Record the index's new state.
+        self.bind[slot as usize] = Some(BindState {
+            bind_group_id,
+            layout_id,
+            dynamic_offsets,
+            is_dirty: true,
+        });
+
+        // Once we've changed the bind group at a particular index, all
+        // subsequent indices need to be rewritten.
+        self.invalidate_bind_group_from(slot as usize + 1);
+    }
+
+    /// Determine which bind group slots need to be re-set after a pipeline
+    /// change, following the same rules as [`RenderBundleEncoder::finish`].
+    ///
+    /// [`RenderBundleEncoder::finish`]: crate::command::RenderBundleEncoder
+    fn invalidate_bind_groups(&mut self, new: &PipelineState, layout: &PipelineLayout<A>) {
+        match self.pipeline {
+            None => {
+                // Establishing entirely new pipeline state.
+                self.invalidate_bind_group_from(0);
+            }
+            Some(ref old) => {
+                if old.id == new.id {
+                    return;
+                }
+
+                // Any push constant change invalidates all groups.
+                if old.push_constant_ranges != new.push_constant_ranges {
+                    self.invalidate_bind_group_from(0);
+                } else {
+                    let first_changed = self
+                        .bind
+                        .iter()
+                        .zip(&layout.bind_group_layout_ids)
+                        .position(|(entry, &layout_id)| match *entry {
+                            Some(ref contents) => contents.layout_id != layout_id,
+                            None => false,
+                        });
+                    if let Some(slot) = first_changed {
+                        self.invalidate_bind_group_from(slot);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Generate `SetBindGroup` commands for any bind groups that need to be updated.
+    fn flush_binds(
+        &mut self,
+        used_bind_groups: usize,
+        dynamic_offsets: &[wgt::DynamicOffset],
+    ) -> impl Iterator<Item = ComputeCommand> + '_ {
+        // Append each dirty bind group's dynamic offsets to `flat_dynamic_offsets`.
+        for contents in self.bind[..used_bind_groups].iter().flatten() {
+            if contents.is_dirty {
+                self.flat_dynamic_offsets
+                    .extend_from_slice(&dynamic_offsets[contents.dynamic_offsets.clone()]);
+            }
+        }
+
+        // Then, generate `SetBindGroup` commands to update the dirty bind
+        // groups. After this, all bind groups are clean.
+        self.bind[..used_bind_groups]
+            .iter_mut()
+            .enumerate()
+            .flat_map(|(i, entry)| {
+                if let Some(ref mut contents) = *entry {
+                    if contents.is_dirty {
+                        contents.is_dirty = false;
+                        let offsets = &contents.dynamic_offsets;
+                        return Some(ComputeCommand::SetBindGroup {
+                            index: i.try_into().unwrap(),
+                            bind_group_id: contents.bind_group_id,
+                            num_dynamic_offsets: (offsets.end - offsets.start) as u8,
+                        });
+                    }
+                }
+                None
+            })
+    }
+}
+
+/// Error encountered when finishing recording a compute bundle.
+#[derive(Clone, Debug, Error)]
+pub(super) enum ComputeBundleErrorInner {
+    #[error("Resource is not valid to use with this compute bundle because the resource and the bundle come from different devices")]
+    NotValidToUse,
+    #[error(transparent)]
+    ComputePass(ComputePassErrorInner),
+}
+
+impl<T> From<T> for ComputeBundleErrorInner
+where
+    T: Into<ComputePassErrorInner>,
+{
+    fn from(t: T) -> Self {
+        Self::ComputePass(t.into())
+    }
+}
+
+/// Error encountered when finishing recording a compute bundle.
+#[derive(Clone, Debug, Error)]
+#[error("{scope}")]
+pub struct ComputeBundleError {
+    pub scope: PassErrorScope,
+    #[source]
+    inner: ComputeBundleErrorInner,
+}
+
+impl ComputeBundleError {
+    pub(crate) const INVALID_DEVICE: Self = ComputeBundleError {
+        scope: PassErrorScope::Bundle,
+        inner: ComputeBundleErrorInner::ComputePass(ComputePassErrorInner::Device(
+            DeviceError::Invalid,
+        )),
+    };
+}
+impl PrettyError for ComputeBundleError {
+    fn fmt_pretty(&self, fmt: &mut ErrorFormatter) {
+        // This error is wrapper for the inner error,
+        // but the scope has useful labels
+        fmt.error(self);
+        self.scope.fmt_pretty(fmt);
+    }
+}
+
+impl<T, E> MapPassErr<T, ComputeBundleError> for Result<T, E>
+where
+    E: Into<ComputeBundleErrorInner>,
+{
+    fn map_pass_err(self, scope: PassErrorScope) -> Result<T, ComputeBundleError> {
+        self.map_err(|inner| ComputeBundleError {
+            scope,
+            inner: inner.into(),
+        })
+    }
+}
+
+pub mod compute_bundle_ffi {
+    use super::{ComputeBundleEncoder, ComputeCommand};
+    use crate::id;
+    use std::convert::TryInto;
+    use wgt::{BufferAddress, DynamicOffset};
+
+    /// # Safety
+    ///
+    /// This function is unsafe as there is no guarantee that the given pointer is
+    /// valid for `offset_length` elements.
+    #[no_mangle]
+    pub unsafe extern "C" fn wgpu_compute_bundle_set_bind_group(
+        bundle: &mut ComputeBundleEncoder,
+        index: u32,
+        bind_group_id: id::BindGroupId,
+        offsets: *const DynamicOffset,
+        offset_length: usize,
+    ) {
+        let redundant = unsafe {
+            bundle.current_bind_groups.set_and_check_redundant(
+                bind_group_id,
+                index,
+                &mut bundle.base.dynamic_offsets,
+                offsets,
+                offset_length,
+            )
+        };
+
+        if redundant {
+            return;
+        }
+
+        bundle.base.commands.push(ComputeCommand::SetBindGroup {
+            index,
+            num_dynamic_offsets: offset_length.try_into().unwrap(),
+            bind_group_id,
+        });
+    }
+
+    #[no_mangle]
+    pub extern "C" fn wgpu_compute_bundle_set_pipeline(
+        bundle: &mut ComputeBundleEncoder,
+        pipeline_id: id::ComputePipelineId,
+    ) {
+        if bundle.current_pipeline.set_and_check_redundant(pipeline_id) {
+            return;
+        }
+
+        bundle
+            .base
+            .commands
+            .push(ComputeCommand::SetPipeline(pipeline_id));
+    }
+
+    #[no_mangle]
+    pub extern "C" fn wgpu_compute_bundle_dispatch_workgroups(
+        bundle: &mut ComputeBundleEncoder,
+        groups_x: u32,
+        groups_y: u32,
+        groups_z: u32,
+    ) {
+        bundle
+            .base
+            .commands
+            .push(ComputeCommand::Dispatch([groups_x, groups_y, groups_z]));
+    }
+
+    #[no_mangle]
+    pub extern "C" fn wgpu_compute_bundle_dispatch_workgroups_indirect(
+        bundle: &mut ComputeBundleEncoder,
+        buffer_id: id::BufferId,
+        offset: BufferAddress,
+    ) {
+        bundle
+            .base
+            .commands
+            .push(ComputeCommand::DispatchIndirect { buffer_id, offset });
+    }
+}
@@ -45,6 +45,12 @@ pub enum DrawError {
     },
     #[error("Index {last_index} extends beyond limit {index_limit}. Did you bind the correct index buffer?")]
     IndexBeyondLimit { last_index: u32, index_limit: u32 },
+    #[error("Index buffer refers to vertex {vertex} (including the base vertex), which is beyond limit {vertex_limit} imposed by the buffer in slot {slot}. Did you bind the correct `Vertex` step-rate vertex buffer?")]
+    IndexedVertexBeyondLimit {
+        vertex: i64,
+        vertex_limit: u32,
+        slot: u32,
+    },
     #[error(
         "Pipeline index format ({pipeline:?}) and buffer index format ({buffer:?}) do not match"
     )]
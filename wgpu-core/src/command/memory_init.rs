@@ -1,4 +1,4 @@
-use std::{collections::hash_map::Entry, ops::Range, vec::Drain};
+use std::{collections::hash_map::Entry, ops::Range};
 
 use hal::CommandEncoder;
 
@@ -13,7 +13,7 @@ use crate::{
     FastHashMap,
 };
 
-use super::{clear::clear_texture, BakedCommands, DestroyedBufferError, DestroyedTextureError};
+use super::{clear::clear_texture, DestroyedBufferError, DestroyedTextureError};
 
 /// Surface that was discarded by `StoreOp::Discard` of a preceding renderpass.
 /// Any read access to this surface needs to be preceded by a texture initialization.
@@ -38,8 +38,8 @@ pub(crate) struct CommandBufferTextureMemoryActions {
 }
 
 impl CommandBufferTextureMemoryActions {
-    pub(crate) fn drain_init_actions(&mut self) -> Drain<TextureInitTrackerAction> {
-        self.init_actions.drain(..)
+    pub(crate) fn init_actions(&self) -> &[TextureInitTrackerAction] {
+        &self.init_actions
     }
 
     pub(crate) fn discard(&mut self, discard: TextureSurfaceDiscard) {
@@ -158,181 +158,179 @@ pub(crate) fn fixup_discarded_surfaces<
     }
 }
 
-impl<A: HalApi> BakedCommands<A> {
-    // inserts all buffer initializations that are going to be needed for
-    // executing the commands and updates resource init states accordingly
-    pub(crate) fn initialize_buffer_memory(
-        &mut self,
-        device_tracker: &mut Tracker<A>,
-        buffer_guard: &mut Storage<Buffer<A>, id::BufferId>,
-    ) -> Result<(), DestroyedBufferError> {
-        // Gather init ranges for each buffer so we can collapse them.
-        // It is not possible to do this at an earlier point since previously
-        // executed command buffer change the resource init state.
-        let mut uninitialized_ranges_per_buffer = FastHashMap::default();
-        for buffer_use in self.buffer_memory_init_actions.drain(..) {
-            let buffer = buffer_guard
-                .get_mut(buffer_use.id)
-                .map_err(|_| DestroyedBufferError(buffer_use.id))?;
+// inserts all buffer initializations that are going to be needed for
+// executing the commands and updates resource init states accordingly
+pub(crate) fn initialize_buffer_memory<A: HalApi>(
+    encoder: &mut A::CommandEncoder,
+    init_actions: &[BufferInitTrackerAction],
+    device_tracker: &mut Tracker<A>,
+    buffer_guard: &mut Storage<Buffer<A>, id::BufferId>,
+) -> Result<(), DestroyedBufferError> {
+    // Gather init ranges for each buffer so we can collapse them.
+    // It is not possible to do this at an earlier point since previously
+    // executed command buffer change the resource init state.
+    let mut uninitialized_ranges_per_buffer = FastHashMap::default();
+    for buffer_use in init_actions {
+        let buffer = buffer_guard
+            .get_mut(buffer_use.id)
+            .map_err(|_| DestroyedBufferError(buffer_use.id))?;
 
-            // align the end to 4
-            let end_remainder = buffer_use.range.end % wgt::COPY_BUFFER_ALIGNMENT;
-            let end = if end_remainder == 0 {
-                buffer_use.range.end
-            } else {
-                buffer_use.range.end + wgt::COPY_BUFFER_ALIGNMENT - end_remainder
-            };
-            let uninitialized_ranges = buffer
-                .initialization_status
-                .drain(buffer_use.range.start..end);
+        // align the end to 4
+        let end_remainder = buffer_use.range.end % wgt::COPY_BUFFER_ALIGNMENT;
+        let end = if end_remainder == 0 {
+            buffer_use.range.end
+        } else {
+            buffer_use.range.end + wgt::COPY_BUFFER_ALIGNMENT - end_remainder
+        };
+        let uninitialized_ranges = buffer
+            .initialization_status
+            .drain(buffer_use.range.start..end);
 
-            match buffer_use.kind {
-                MemoryInitKind::ImplicitlyInitialized => {}
-                MemoryInitKind::NeedsInitializedMemory => {
-                    match uninitialized_ranges_per_buffer.entry(buffer_use.id) {
-                        Entry::Vacant(e) => {
-                            e.insert(
-                                uninitialized_ranges.collect::<Vec<Range<wgt::BufferAddress>>>(),
-                            );
-                        }
-                        Entry::Occupied(mut e) => {
-                            e.get_mut().extend(uninitialized_ranges);
-                        }
+        match buffer_use.kind {
+            MemoryInitKind::ImplicitlyInitialized => {}
+            MemoryInitKind::NeedsInitializedMemory => {
+                match uninitialized_ranges_per_buffer.entry(buffer_use.id) {
+                    Entry::Vacant(e) => {
+                        e.insert(uninitialized_ranges.collect::<Vec<Range<wgt::BufferAddress>>>());
+                    }
+                    Entry::Occupied(mut e) => {
+                        e.get_mut().extend(uninitialized_ranges);
                     }
                 }
             }
         }
+    }
 
-        for (buffer_id, mut ranges) in uninitialized_ranges_per_buffer {
-            // Collapse touching ranges.
-            ranges.sort_by_key(|r| r.start);
-            for i in (1..ranges.len()).rev() {
-                // The memory init tracker made sure of this!
-                assert!(ranges[i - 1].end <= ranges[i].start);
-                if ranges[i].start == ranges[i - 1].end {
-                    ranges[i - 1].end = ranges[i].end;
-                    ranges.swap_remove(i); // Ordering not important at this point
-                }
+    for (buffer_id, mut ranges) in uninitialized_ranges_per_buffer {
+        // Collapse touching ranges.
+        ranges.sort_by_key(|r| r.start);
+        for i in (1..ranges.len()).rev() {
+            // The memory init tracker made sure of this!
+            assert!(ranges[i - 1].end <= ranges[i].start);
+            if ranges[i].start == ranges[i - 1].end {
+                ranges[i - 1].end = ranges[i].end;
+                ranges.swap_remove(i); // Ordering not important at this point
             }
+        }
 
-            // Don't do use_replace since the buffer may already no longer have
-            // a ref_count.
-            //
-            // However, we *know* that it is currently in use, so the tracker
-            // must already know about it.
-            let transition = device_tracker
-                .buffers
-                .set_single(buffer_guard, buffer_id, hal::BufferUses::COPY_DST)
-                .unwrap()
-                .1;
+        // Don't do use_replace since the buffer may already no longer have
+        // a ref_count.
+        //
+        // However, we *know* that it is currently in use, so the tracker
+        // must already know about it.
+        let transition = device_tracker
+            .buffers
+            .set_single(buffer_guard, buffer_id, hal::BufferUses::COPY_DST)
+            .unwrap()
+            .1;
 
-            let buffer = buffer_guard
-                .get_mut(buffer_id)
-                .map_err(|_| DestroyedBufferError(buffer_id))?;
-            let raw_buf = buffer.raw.as_ref().ok_or(DestroyedBufferError(buffer_id))?;
+        let buffer = buffer_guard
+            .get_mut(buffer_id)
+            .map_err(|_| DestroyedBufferError(buffer_id))?;
+        let raw_buf = buffer.raw.as_ref().ok_or(DestroyedBufferError(buffer_id))?;
 
-            unsafe {
-                self.encoder.transition_buffers(
-                    transition
-                        .map(|pending| pending.into_hal(buffer))
-                        .into_iter(),
-                );
-            }
+        unsafe {
+            encoder.transition_buffers(
+                transition
+                    .map(|pending| pending.into_hal(buffer))
+                    .into_iter(),
+            );
+        }
 
-            for range in ranges.iter() {
-                assert!(
-                    range.start % wgt::COPY_BUFFER_ALIGNMENT == 0,
-                    "Buffer {:?} has an uninitialized range with a start \
+        for range in ranges.iter() {
+            assert!(
+                range.start % wgt::COPY_BUFFER_ALIGNMENT == 0,
+                "Buffer {:?} has an uninitialized range with a start \
                          not aligned to 4 (start was {})",
-                    raw_buf,
-                    range.start
-                );
-                assert!(
-                    range.end % wgt::COPY_BUFFER_ALIGNMENT == 0,
-                    "Buffer {:?} has an uninitialized range with an end \
+                raw_buf,
+                range.start
+            );
+            assert!(
+                range.end % wgt::COPY_BUFFER_ALIGNMENT == 0,
+                "Buffer {:?} has an uninitialized range with an end \
                          not aligned to 4 (end was {})",
-                    raw_buf,
-                    range.end
-                );
+                raw_buf,
+                range.end
+            );
 
-                unsafe {
-                    self.encoder.clear_buffer(raw_buf, range.clone());
-                }
+            unsafe {
+                encoder.clear_buffer(raw_buf, range.clone());
             }
         }
-        Ok(())
     }
+    Ok(())
+}
 
-    // inserts all texture initializations that are going to be needed for
-    // executing the commands and updates resource init states accordingly any
-    // textures that are left discarded by this command buffer will be marked as
-    // uninitialized
-    pub(crate) fn initialize_texture_memory(
-        &mut self,
-        device_tracker: &mut Tracker<A>,
-        texture_guard: &mut Storage<Texture<A>, TextureId>,
-        device: &Device<A>,
-    ) -> Result<(), DestroyedTextureError> {
-        let mut ranges: Vec<TextureInitRange> = Vec::new();
-        for texture_use in self.texture_memory_actions.drain_init_actions() {
-            let texture = texture_guard
-                .get_mut(texture_use.id)
-                .map_err(|_| DestroyedTextureError(texture_use.id))?;
+// inserts all texture initializations that are going to be needed for
+// executing the commands and updates resource init states accordingly any
+// textures that are left discarded by this command buffer will be marked as
+// uninitialized
+pub(crate) fn initialize_texture_memory<A: HalApi>(
+    encoder: &mut A::CommandEncoder,
+    memory_actions: &CommandBufferTextureMemoryActions,
+    device_tracker: &mut Tracker<A>,
+    texture_guard: &mut Storage<Texture<A>, TextureId>,
+    device: &Device<A>,
+) -> Result<(), DestroyedTextureError> {
+    let mut ranges: Vec<TextureInitRange> = Vec::new();
+    for texture_use in memory_actions.init_actions() {
+        let texture = texture_guard
+            .get_mut(texture_use.id)
+            .map_err(|_| DestroyedTextureError(texture_use.id))?;
 
-            let use_range = texture_use.range;
-            let affected_mip_trackers = texture
-                .initialization_status
-                .mips
-                .iter_mut()
-                .enumerate()
-                .skip(use_range.mip_range.start as usize)
-                .take((use_range.mip_range.end - use_range.mip_range.start) as usize);
+        let use_range = &texture_use.range;
+        let affected_mip_trackers = texture
+            .initialization_status
+            .mips
+            .iter_mut()
+            .enumerate()
+            .skip(use_range.mip_range.start as usize)
+            .take((use_range.mip_range.end - use_range.mip_range.start) as usize);
 
-            match texture_use.kind {
-                MemoryInitKind::ImplicitlyInitialized => {
-                    for (_, mip_tracker) in affected_mip_trackers {
-                        mip_tracker.drain(use_range.layer_range.clone());
-                    }
+        match texture_use.kind {
+            MemoryInitKind::ImplicitlyInitialized => {
+                for (_, mip_tracker) in affected_mip_trackers {
+                    mip_tracker.drain(use_range.layer_range.clone());
                 }
-                MemoryInitKind::NeedsInitializedMemory => {
-                    for (mip_level, mip_tracker) in affected_mip_trackers {
-                        for layer_range in mip_tracker.drain(use_range.layer_range.clone()) {
-                            ranges.push(TextureInitRange {
-                                mip_range: (mip_level as u32)..(mip_level as u32 + 1),
-                                layer_range,
-                            });
-                        }
+            }
+            MemoryInitKind::NeedsInitializedMemory => {
+                for (mip_level, mip_tracker) in affected_mip_trackers {
+                    for layer_range in mip_tracker.drain(use_range.layer_range.clone()) {
+                        ranges.push(TextureInitRange {
+                            mip_range: (mip_level as u32)..(mip_level as u32 + 1),
+                            layer_range,
+                        });
                     }
                 }
             }
-
-            // TODO: Could we attempt some range collapsing here?
-            for range in ranges.drain(..) {
-                clear_texture(
-                    texture_guard,
-                    id::Valid(texture_use.id),
-                    range,
-                    &mut self.encoder,
-                    &mut device_tracker.textures,
-                    &device.alignments,
-                    &device.zero_buffer,
-                )
-                .unwrap();
-            }
         }
 
-        // Now that all buffers/textures have the proper init state for before
-        // cmdbuf start, we discard init states for textures it left discarded
-        // after its execution.
-        for surface_discard in self.texture_memory_actions.discards.iter() {
-            let texture = texture_guard
-                .get_mut(surface_discard.texture)
-                .map_err(|_| DestroyedTextureError(surface_discard.texture))?;
-            texture
-                .initialization_status
-                .discard(surface_discard.mip_level, surface_discard.layer);
+        // TODO: Could we attempt some range collapsing here?
+        for range in ranges.drain(..) {
+            clear_texture(
+                texture_guard,
+                id::Valid(texture_use.id),
+                range,
+                encoder,
+                &mut device_tracker.textures,
+                &device.alignments,
+                &device.zero_buffer,
+            )
+            .unwrap();
         }
+    }
 
-        Ok(())
+    // Now that all buffers/textures have the proper init state for before
+    // cmdbuf start, we discard init states for textures it left discarded
+    // after its execution.
+    for surface_discard in memory_actions.discards.iter() {
+        let texture = texture_guard
+            .get_mut(surface_discard.texture)
+            .map_err(|_| DestroyedTextureError(surface_discard.texture))?;
+        texture
+            .initialization_status
+            .discard(surface_discard.mip_level, surface_discard.layer);
     }
+
+    Ok(())
 }
@@ -2,33 +2,43 @@ mod bind;
 mod bundle;
 mod clear;
 mod compute;
+mod compute_bundle;
 mod draw;
 mod memory_init;
 mod query;
+mod ray_tracing;
 mod render;
 mod transfer;
+mod transition_resources;
 
 use std::slice;
 
-pub(crate) use self::clear::clear_texture;
 pub use self::{
-    bundle::*, clear::ClearError, compute::*, draw::*, query::*, render::*, transfer::*,
+    bundle::*, clear::ClearError, compute::*, compute_bundle::*, draw::*, query::*, render::*,
+    transfer::*, transition_resources::TransitionResourcesError,
 };
+pub(crate) use self::{clear::clear_texture, query::encode_query_resolves};
 
 use self::memory_init::CommandBufferTextureMemoryActions;
 
 use crate::error::{ErrorFormatter, PrettyError};
-use crate::init_tracker::BufferInitTrackerAction;
+use crate::init_tracker::{BufferInitTrackerAction, QuerySetInitTrackerAction};
 use crate::track::{Tracker, UsageScope};
 use crate::{
+    device::{
+        queue::{QueueSubmitError, TempResource},
+        Device, DeviceError, MissingFeatures,
+    },
     global::Global,
     hal_api::HalApi,
     hub::Token,
     id,
     identity::GlobalIdentityHandlerFactory,
+    indirect_validation::{IndirectDispatches, IndirectDraws},
+    ray_tracing::TlasAction,
     resource::{Buffer, Texture},
     storage::Storage,
-    Label, Stored,
+    Label, Stored, SubmissionIndex,
 };
 
 use hal::CommandEncoder as _;
@@ -97,9 +107,7 @@ impl<A: hal::Api> CommandEncoder<A> {
 pub struct BakedCommands<A: HalApi> {
     pub(crate) encoder: A::CommandEncoder,
     pub(crate) list: Vec<A::CommandBuffer>,
-    pub(crate) trackers: Tracker<A>,
-    buffer_memory_init_actions: Vec<BufferInitTrackerAction>,
-    texture_memory_actions: CommandBufferTextureMemoryActions,
+    pub(crate) temp_resources: Vec<TempResource<A>>,
 }
 
 pub(crate) struct DestroyedBufferError(pub id::BufferId);
@@ -112,9 +120,29 @@ pub struct CommandBuffer<A: HalApi> {
     pub(crate) trackers: Tracker<A>,
     buffer_memory_init_actions: Vec<BufferInitTrackerAction>,
     texture_memory_actions: CommandBufferTextureMemoryActions,
+    /// Query writes and resolves, in order. Resolves are encoded at submit.
+    pub(crate) query_init_actions: Vec<QuerySetInitTrackerAction>,
     pub(crate) pending_query_resets: QueryResetMap<A>,
+    /// Acceleration structures built by this command buffer, in order.
+    pub(crate) blas_builds: Vec<id::BlasId>,
+    /// Builds and uses of top level acceleration structures, in order.
+    pub(crate) tlas_actions: Vec<TlasAction>,
+    /// Internal resources, like scratch buffers, to be freed once the command
+    /// buffer is done executing.
+    temp_resources: Vec<TempResource<A>>,
+    /// Indirect draws of the render pass being recorded, to be validated
+    /// before it.
+    indirect_draws: Option<IndirectDraws<A>>,
+    /// Indirect dispatches of the compute pass being recorded.
+    indirect_dispatches: Option<IndirectDispatches<A>>,
     limits: wgt::Limits,
     support_clear_texture: bool,
+    /// Whether this command buffer stays registered after being submitted,
+    /// so that it can be submitted again.
+    pub(crate) reusable: bool,
+    /// The index of the last submission of this command buffer, if it is
+    /// reusable and has been submitted.
+    pub(crate) last_submission_index: Option<SubmissionIndex>,
     #[cfg(feature = "trace")]
     pub(crate) commands: Option<Vec<TraceCommand>>,
 }
@@ -128,6 +156,7 @@ impl<A: HalApi> CommandBuffer<A> {
         features: wgt::Features,
         #[cfg(feature = "trace")] enable_tracing: bool,
         label: &Label,
+        reusable: bool,
     ) -> Self {
         CommandBuffer {
             encoder: CommandEncoder {
@@ -141,9 +170,17 @@ impl<A: HalApi> CommandBuffer<A> {
             trackers: Tracker::new(),
             buffer_memory_init_actions: Default::default(),
             texture_memory_actions: Default::default(),
+            query_init_actions: Vec::new(),
             pending_query_resets: QueryResetMap::new(),
+            blas_builds: Vec::new(),
+            tlas_actions: Vec::new(),
+            temp_resources: Vec::new(),
+            indirect_draws: None,
+            indirect_dispatches: None,
             limits,
             support_clear_texture: features.contains(wgt::Features::CLEAR_TEXTURE),
+            reusable,
+            last_submission_index: None,
             #[cfg(feature = "trace")]
             commands: if enable_tracing {
                 Some(Vec::new())
@@ -232,14 +269,71 @@ impl<A: HalApi> CommandBuffer<A> {
     }
 
     pub(crate) fn into_baked(self) -> BakedCommands<A> {
+        let mut temp_resources = self.temp_resources;
+        // Left behind by a pass that failed.
+        temp_resources.extend(self.indirect_draws.map(|draws| draws.into_temp_resource()));
+        if let Some(dispatches) = self.indirect_dispatches {
+            temp_resources.extend(dispatches.into_temp_resources());
+        }
         BakedCommands {
             encoder: self.encoder.raw,
             list: self.encoder.list,
-            trackers: self.trackers,
-            buffer_memory_init_actions: self.buffer_memory_init_actions,
-            texture_memory_actions: self.texture_memory_actions,
+            temp_resources,
         }
     }
+
+    /// The raw command buffers holding the recorded commands.
+    pub(crate) fn raw_command_buffers(&self) -> &[A::CommandBuffer] {
+        &self.encoder.list
+    }
+
+    /// Encode the memory initialization and resource transitions needed
+    /// before this command buffer can be executed.
+    ///
+    /// They are encoded into `transit` if given, or else into this command
+    /// buffer's own encoder, which must not be used for anything afterwards.
+    pub(crate) fn encode_transit(
+        &mut self,
+        transit: Option<&mut A::CommandEncoder>,
+        device_tracker: &mut Tracker<A>,
+        buffer_guard: &mut Storage<Buffer<A>, id::BufferId>,
+        texture_guard: &mut Storage<Texture<A>, id::TextureId>,
+        device: &Device<A>,
+    ) -> Result<A::CommandBuffer, QueueSubmitError> {
+        let raw = match transit {
+            Some(raw) => raw,
+            None => &mut self.encoder.raw,
+        };
+        unsafe {
+            raw.begin_encoding(Some("(wgpu internal) Transit"))
+                .map_err(DeviceError::from)?
+        };
+        memory_init::initialize_buffer_memory(
+            raw,
+            &self.buffer_memory_init_actions,
+            device_tracker,
+            buffer_guard,
+        )
+        .map_err(|err| QueueSubmitError::DestroyedBuffer(err.0))?;
+        memory_init::initialize_texture_memory(
+            raw,
+            &self.texture_memory_actions,
+            device_tracker,
+            texture_guard,
+            device,
+        )
+        .map_err(|err| QueueSubmitError::DestroyedTexture(err.0))?;
+        //Note: stateless trackers are not merged:
+        // device already knows these resources exist.
+        Self::insert_barriers_from_tracker(
+            raw,
+            device_tracker,
+            &self.trackers,
+            buffer_guard,
+            texture_guard,
+        );
+        Ok(unsafe { raw.end_encoding().unwrap() })
+    }
 }
 
 impl<A: HalApi> crate::resource::Resource for CommandBuffer<A> {
@@ -275,7 +369,7 @@ pub struct BasePassRef<'a, C> {
 /// [`SetBindGroup`]: RenderCommand::SetBindGroup
 /// [`InsertDebugMarker`]: RenderCommand::InsertDebugMarker
 #[doc(hidden)]
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 #[cfg_attr(
     any(feature = "serial-pass", feature = "trace"),
     derive(serde::Serialize)
@@ -342,6 +436,15 @@ impl<C: Clone> BasePass<C> {
     }
 }
 
+#[derive(Clone, Debug, Error)]
+#[non_exhaustive]
+pub enum CreateCommandEncoderError {
+    #[error(transparent)]
+    Device(#[from] DeviceError),
+    #[error(transparent)]
+    MissingFeatures(#[from] MissingFeatures),
+}
+
 #[derive(Clone, Debug, Error)]
 #[non_exhaustive]
 pub enum CommandEncoderError {
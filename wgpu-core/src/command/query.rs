@@ -4,13 +4,14 @@ use hal::CommandEncoder as _;
 use crate::device::trace::Command as TraceCommand;
 use crate::{
     command::{CommandBuffer, CommandEncoderError},
+    device::{queue::QueueSubmitError, DeviceError},
     global::Global,
     hal_api::HalApi,
     hub::Token,
     id::{self, Id, TypedId},
     identity::GlobalIdentityHandlerFactory,
-    init_tracker::MemoryInitKind,
-    resource::QuerySet,
+    init_tracker::{MemoryInitKind, QueryInitKind, QueryResolve, QuerySetInitTrackerAction},
+    resource::{Buffer, QuerySet},
     storage::Storage,
     Epoch, FastHashMap, Index,
 };
@@ -180,7 +181,7 @@ pub enum ResolveError {
 }
 
 impl<A: HalApi> QuerySet<A> {
-    fn validate_query(
+    pub(super) fn validate_query(
         &self,
         query_set_id: id::QuerySetId,
         query_type: SimplifiedQueryType,
@@ -220,6 +221,7 @@ impl<A: HalApi> QuerySet<A> {
         query_set_id: id::QuerySetId,
         query_index: u32,
         reset_state: Option<&mut QueryResetMap<A>>,
+        init_actions: &mut Vec<QuerySetInitTrackerAction>,
     ) -> Result<(), QueryUseError> {
         let needs_reset = reset_state.is_none();
         let query_set = self.validate_query(
@@ -236,6 +238,7 @@ impl<A: HalApi> QuerySet<A> {
             }
             raw_encoder.write_timestamp(query_set, query_index);
         }
+        init_actions.push(QuerySetInitTrackerAction::write(query_set_id, query_index));
 
         Ok(())
     }
@@ -246,6 +249,7 @@ impl<A: HalApi> QuerySet<A> {
         query_set_id: id::QuerySetId,
         query_index: u32,
         reset_state: Option<&mut QueryResetMap<A>>,
+        init_actions: &mut Vec<QuerySetInitTrackerAction>,
         active_query: &mut Option<(id::QuerySetId, u32)>,
     ) -> Result<(), QueryUseError> {
         let needs_reset = reset_state.is_none();
@@ -270,6 +274,7 @@ impl<A: HalApi> QuerySet<A> {
             }
             raw_encoder.begin_query(query_set, query_index);
         }
+        init_actions.push(QuerySetInitTrackerAction::write(query_set_id, query_index));
 
         Ok(())
     }
@@ -280,6 +285,7 @@ impl<A: HalApi> QuerySet<A> {
         query_set_id: id::QuerySetId,
         query_index: u32,
         reset_state: Option<&mut QueryResetMap<A>>,
+        init_actions: &mut Vec<QuerySetInitTrackerAction>,
         active_query: &mut Option<(id::QuerySetId, u32)>,
     ) -> Result<(), QueryUseError> {
         let needs_reset = reset_state.is_none();
@@ -304,6 +310,7 @@ impl<A: HalApi> QuerySet<A> {
             }
             raw_encoder.begin_query(query_set, query_index);
         }
+        init_actions.push(QuerySetInitTrackerAction::write(query_set_id, query_index));
 
         Ok(())
     }
@@ -373,7 +380,13 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
             .add_single(&*query_set_guard, query_set_id)
             .ok_or(QueryError::InvalidQuerySet(query_set_id))?;
 
-        query_set.validate_and_write_timestamp(raw_encoder, query_set_id, query_index, None)?;
+        query_set.validate_and_write_timestamp(
+            raw_encoder,
+            query_set_id,
+            query_index,
+            None,
+            &mut cmd_buf.query_init_actions,
+        )?;
 
         Ok(())
     }
@@ -462,7 +475,6 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
             .into());
         }
 
-        // TODO(https://github.com/gfx-rs/wgpu/issues/3993): Need to track initialization state.
         cmd_buf
             .buffer_memory_init_actions
             .extend(dst_buffer.initialization_status.create_action(
@@ -471,17 +483,84 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
                 MemoryInitKind::ImplicitlyInitialized,
             ));
 
+        dst_buffer.invalidate_index_contents();
         unsafe {
             raw_encoder.transition_buffers(dst_barrier.into_iter());
-            raw_encoder.copy_query_results(
-                &query_set.raw,
-                start_query..end_query,
-                dst_buffer.raw.as_ref().unwrap(),
-                destination_offset,
-                wgt::BufferSize::new_unchecked(stride as u64),
-            );
         }
 
+        // Whether the queries have been written is only known at submit, so
+        // the resolve gets a command buffer of its own, encoded then and
+        // executed between the commands recorded before and after it.
+        cmd_buf.encoder.close();
+        cmd_buf.query_init_actions.push(QuerySetInitTrackerAction {
+            id: query_set_id,
+            range: start_query..end_query,
+            kind: QueryInitKind::Resolve(QueryResolve {
+                buffer: destination,
+                offset: destination_offset,
+                stride,
+                position: cmd_buf.encoder.list.len(),
+            }),
+        });
+
         Ok(())
     }
 }
+
+/// Applies the query init actions of a command buffer to the query sets in
+/// recording order, and encodes its query resolves.
+///
+/// Queries that were never written have no defined result (and waiting for
+/// them may never finish), so they are resolved as zeros. Every resolve is
+/// returned along with the index of the raw command buffer of the command
+/// buffer's list it has to be executed before.
+pub(crate) fn encode_query_resolves<A: HalApi>(
+    encoder: &mut A::CommandEncoder,
+    init_actions: &[QuerySetInitTrackerAction],
+    query_set_guard: &Storage<QuerySet<A>, id::QuerySetId>,
+    buffer_guard: &Storage<Buffer<A>, id::BufferId>,
+) -> Result<Vec<(usize, A::CommandBuffer)>, QueueSubmitError> {
+    let mut resolves = Vec::new();
+    for action in init_actions {
+        let query_set = &query_set_guard[id::Valid(action.id)];
+        let mut status = query_set.initialization_status.lock();
+        let resolve = match action.kind {
+            QueryInitKind::Write => {
+                status.mark_written(action.range.clone());
+                continue;
+            }
+            QueryInitKind::Resolve(ref resolve) => resolve,
+        };
+        let dst_raw = buffer_guard
+            .get(resolve.buffer)
+            .ok()
+            .and_then(|buffer| buffer.raw.as_ref())
+            .ok_or(QueueSubmitError::DestroyedBuffer(resolve.buffer))?;
+        let stride = resolve.stride as BufferAddress;
+
+        unsafe {
+            encoder
+                .begin_encoding(Some("(wgpu internal) Resolve queries"))
+                .map_err(DeviceError::from)?;
+            for (range, written) in status.runs(action.range.clone()) {
+                let offset =
+                    resolve.offset + (range.start - action.range.start) as BufferAddress * stride;
+                if written {
+                    encoder.copy_query_results(
+                        &query_set.raw,
+                        range,
+                        dst_raw,
+                        offset,
+                        wgt::BufferSize::new_unchecked(stride),
+                    );
+                } else {
+                    let size = (range.end - range.start) as BufferAddress * stride;
+                    encoder.clear_buffer(dst_raw, offset..offset + size);
+                }
+            }
+            let raw = encoder.end_encoding().map_err(DeviceError::from)?;
+            resolves.push((resolve.position, raw));
+        }
+    }
+    Ok(resolves)
+}
@@ -0,0 +1,447 @@
+#[cfg(feature = "trace")]
+use crate::device::trace::Command as TraceCommand;
+use crate::{
+    command::CommandBuffer,
+    device::{queue::TempResource, DeviceError},
+    global::Global,
+    hal_api::HalApi,
+    hub::Token,
+    id::{BufferId, CommandEncoderId},
+    identity::GlobalIdentityHandlerFactory,
+    init_tracker::MemoryInitKind,
+    ray_tracing::{
+        validate_triangle_geometry, BlasBuildEntry, BlasGeometries,
+        BuildAccelerationStructureError, TlasAction, TlasActionKind, TlasBuildEntry,
+        SCRATCH_BUFFER_ALIGNMENT,
+    },
+    resource::{Buffer, CreateBufferError},
+    storage::Storage,
+};
+
+use hal::{CommandEncoder as _, Device as _};
+use wgt::{math::align_to, BufferAddress, BufferUsages};
+
+use std::ops::Range;
+
+/// Size of the 3x4 `f32` matrix read from a transform buffer.
+const TRANSFORM_SIZE: BufferAddress = 48;
+/// Alignment required of offsets into a transform buffer.
+const TRANSFORM_ALIGNMENT: BufferAddress = 16;
+
+/// A validated build of a single acceleration structure, waiting for the
+/// scratch buffer to be allocated.
+struct PendingBuild<'a, A: HalApi> {
+    raw: &'a A::AccelerationStructure,
+    flags: wgt::AccelerationStructureFlags,
+    entries: hal::AccelerationStructureEntries<'a, A>,
+    mode: hal::AccelerationStructureBuildMode,
+    scratch_offset: BufferAddress,
+}
+
+impl<'a, A: HalApi> PendingBuild<'a, A> {
+    /// Pick the build mode and reserve a range of the scratch buffer, which
+    /// is `scratch_size` bytes large so far.
+    fn new(
+        raw: &'a A::AccelerationStructure,
+        flags: wgt::AccelerationStructureFlags,
+        update_mode: wgt::AccelerationStructureUpdateMode,
+        built: bool,
+        size_info: &hal::AccelerationStructureBuildSizes,
+        entries: hal::AccelerationStructureEntries<'a, A>,
+        scratch_size: &mut BufferAddress,
+    ) -> Self {
+        let update = update_mode == wgt::AccelerationStructureUpdateMode::PreferUpdate
+            && flags.contains(wgt::AccelerationStructureFlags::ALLOW_UPDATE)
+            && built;
+        let (mode, size) = if update {
+            (
+                hal::AccelerationStructureBuildMode::Update,
+                size_info.update_scratch_size,
+            )
+        } else {
+            (
+                hal::AccelerationStructureBuildMode::Build,
+                size_info.build_scratch_size,
+            )
+        };
+
+        let scratch_offset = *scratch_size;
+        *scratch_size += align_to(size, SCRATCH_BUFFER_ALIGNMENT);
+
+        Self {
+            raw,
+            flags,
+            entries,
+            mode,
+            scratch_offset,
+        }
+    }
+
+    fn to_hal<'b>(
+        &'b self,
+        scratch_buffer: &'b A::Buffer,
+    ) -> hal::BuildAccelerationStructureDescriptor<'b, A> {
+        hal::BuildAccelerationStructureDescriptor {
+            entries: &self.entries,
+            mode: self.mode,
+            flags: self.flags,
+            source_acceleration_structure: None,
+            destination_acceleration_structure: self.raw,
+            scratch_buffer,
+            scratch_buffer_offset: self.scratch_offset,
+        }
+    }
+}
+
+/// Track `id` as an acceleration structure build input, checking its usage
+/// flags, the range read from it, and that the range is initialized.
+fn use_input_buffer<'a, A: HalApi>(
+    cmd_buf: &mut CommandBuffer<A>,
+    buffer_guard: &'a Storage<Buffer<A>, BufferId>,
+    barriers: &mut Vec<hal::BufferBarrier<'a, A>>,
+    id: BufferId,
+    range: Range<BufferAddress>,
+    top_level: bool,
+) -> Result<&'a Buffer<A>, BuildAccelerationStructureError> {
+    let (usage, state) = if top_level {
+        (
+            BufferUsages::TLAS_INPUT,
+            hal::BufferUses::TOP_LEVEL_ACCELERATION_STRUCTURE_INPUT,
+        )
+    } else {
+        (
+            BufferUsages::BLAS_INPUT,
+            hal::BufferUses::BOTTOM_LEVEL_ACCELERATION_STRUCTURE_INPUT,
+        )
+    };
+
+    let (buffer, transition) = cmd_buf
+        .trackers
+        .buffers
+        .set_single(buffer_guard, id, state)
+        .ok_or(BuildAccelerationStructureError::InvalidBuffer(id))?;
+    if buffer.raw.is_none() {
+        return Err(BuildAccelerationStructureError::InvalidBuffer(id));
+    }
+    if !buffer.usage.contains(usage) {
+        return Err(if top_level {
+            BuildAccelerationStructureError::MissingTlasInputUsageFlag(id)
+        } else {
+            BuildAccelerationStructureError::MissingBlasInputUsageFlag(id)
+        });
+    }
+    if range.end > buffer.size {
+        return Err(BuildAccelerationStructureError::BufferOverrun {
+            buffer: id,
+            start: range.start,
+            end: range.end,
+            buffer_size: buffer.size,
+        });
+    }
+    barriers.extend(transition.map(|pending| pending.into_hal(buffer)));
+
+    cmd_buf
+        .buffer_memory_init_actions
+        .extend(buffer.initialization_status.create_action(
+            id,
+            range,
+            MemoryInitKind::NeedsInitializedMemory,
+        ));
+
+    Ok(buffer)
+}
+
+fn check_alignment(
+    buffer: BufferId,
+    offset: BufferAddress,
+    alignment: BufferAddress,
+) -> Result<(), BuildAccelerationStructureError> {
+    if offset % alignment != 0 {
+        return Err(BuildAccelerationStructureError::UnalignedOffset {
+            buffer,
+            offset,
+            alignment,
+        });
+    }
+    Ok(())
+}
+
+impl<G: GlobalIdentityHandlerFactory> Global<G> {
+    /// Build bottom level acceleration structures, then top level ones.
+    ///
+    /// The contents of the instance buffers are not validated: every
+    /// instance has to reference a [`Blas`] that is alive and built by the
+    /// time the command buffer executes.
+    ///
+    /// [`Blas`]: crate::resource::Blas
+    pub fn command_encoder_build_acceleration_structures_unsafe_tlas<A: HalApi>(
+        &self,
+        command_encoder_id: CommandEncoderId,
+        blas: &[BlasBuildEntry],
+        tlas: &[TlasBuildEntry],
+    ) -> Result<(), BuildAccelerationStructureError> {
+        profiling::scope!("CommandEncoder::build_acceleration_structures_unsafe_tlas");
+
+        let hub = A::hub(self);
+        let mut token = Token::root();
+
+        let (device_guard, mut token) = hub.devices.read(&mut token);
+        let (mut cmd_buf_guard, mut token) = hub.command_buffers.write(&mut token);
+        let cmd_buf = CommandBuffer::get_encoder_mut(&mut *cmd_buf_guard, command_encoder_id)?;
+        let (buffer_guard, mut token) = hub.buffers.read(&mut token);
+        let (blas_guard, mut token) = hub.blas_s.read(&mut token);
+        let (tlas_guard, _) = hub.tlas_s.read(&mut token);
+
+        let device = &device_guard[cmd_buf.device_id.value];
+        if !device.is_valid() {
+            return Err(DeviceError::Lost.into());
+        }
+        device.require_features(wgt::Features::RAY_TRACING_ACCELERATION_STRUCTURE)?;
+
+        #[cfg(feature = "trace")]
+        if let Some(ref mut list) = cmd_buf.commands {
+            list.push(TraceCommand::BuildAccelerationStructuresUnsafeTlas {
+                blas: blas.to_vec(),
+                tlas: tlas.to_vec(),
+            });
+        }
+
+        let mut barriers = Vec::new();
+        let mut scratch_size = 0;
+
+        // Bottom level: validate every geometry and gather the hal entries.
+        let mut blas_ids = Vec::with_capacity(blas.len());
+        let mut blas_builds = Vec::with_capacity(blas.len());
+        for entry in blas {
+            let blas_id = entry.blas_id;
+            if blas_ids.contains(&blas_id) {
+                return Err(BuildAccelerationStructureError::DuplicateBlasBuild(blas_id));
+            }
+            let blas_res = cmd_buf
+                .trackers
+                .blas_s
+                .add_single(&*blas_guard, blas_id)
+                .ok_or(BuildAccelerationStructureError::InvalidBlas(blas_id))?;
+
+            let wgt::BlasGeometrySizeDescriptors::Triangles {
+                desc: ref created_sizes,
+            } = blas_res.sizes;
+            let BlasGeometries::TriangleGeometries(ref geometries) = entry.geometries;
+            if geometries.len() != created_sizes.len() {
+                return Err(BuildAccelerationStructureError::GeometryCountMismatch {
+                    blas: blas_id,
+                    expected: created_sizes.len(),
+                    actual: geometries.len(),
+                });
+            }
+
+            let mut triangles = Vec::with_capacity(geometries.len());
+            for (index, (geometry, created)) in geometries.iter().zip(created_sizes).enumerate() {
+                validate_triangle_geometry(blas_id, index, created, geometry)?;
+                let size = &geometry.size;
+
+                let vertex_start = geometry.first_vertex as BufferAddress * geometry.vertex_stride;
+                let vertex_end = match size.vertex_count {
+                    0 => vertex_start,
+                    count => {
+                        vertex_start
+                            + (count as BufferAddress - 1) * geometry.vertex_stride
+                            + size.vertex_format.size()
+                    }
+                };
+                let vertex_buffer = use_input_buffer(
+                    cmd_buf,
+                    &buffer_guard,
+                    &mut barriers,
+                    geometry.vertex_buffer,
+                    vertex_start..vertex_end,
+                    false,
+                )?;
+
+                let indices = match (geometry.index_buffer, size.index_format) {
+                    (Some(index_buffer_id), Some(format)) => {
+                        let offset = geometry.index_buffer_offset.unwrap_or(0);
+                        let count = size.index_count.unwrap_or(0);
+                        let index_size: BufferAddress = match format {
+                            wgt::IndexFormat::Uint16 => 2,
+                            wgt::IndexFormat::Uint32 => 4,
+                        };
+                        check_alignment(index_buffer_id, offset, index_size)?;
+                        let index_buffer = use_input_buffer(
+                            cmd_buf,
+                            &buffer_guard,
+                            &mut barriers,
+                            index_buffer_id,
+                            offset..offset + count as BufferAddress * index_size,
+                            false,
+                        )?;
+                        Some(hal::AccelerationStructureTriangleIndices {
+                            format,
+                            buffer: index_buffer.raw.as_ref(),
+                            offset: offset as u32,
+                            count,
+                        })
+                    }
+                    _ => None,
+                };
+
+                let transform = match geometry.transform_buffer {
+                    Some(transform_buffer_id) => {
+                        let offset = geometry.transform_buffer_offset.unwrap_or(0);
+                        check_alignment(transform_buffer_id, offset, TRANSFORM_ALIGNMENT)?;
+                        let transform_buffer = use_input_buffer(
+                            cmd_buf,
+                            &buffer_guard,
+                            &mut barriers,
+                            transform_buffer_id,
+                            offset..offset + TRANSFORM_SIZE,
+                            false,
+                        )?;
+                        Some(hal::AccelerationStructureTriangleTransform {
+                            buffer: transform_buffer.raw.as_ref().unwrap(),
+                            offset: offset as u32,
+                        })
+                    }
+                    None => None,
+                };
+
+                triangles.push(hal::AccelerationStructureTriangles {
+                    vertex_buffer: vertex_buffer.raw.as_ref(),
+                    vertex_format: size.vertex_format,
+                    first_vertex: geometry.first_vertex,
+                    vertex_count: size.vertex_count,
+                    vertex_stride: geometry.vertex_stride,
+                    indices,
+                    transform,
+                    flags: size.flags,
+                });
+            }
+
+            blas_ids.push(blas_id);
+            blas_builds.push(PendingBuild::new(
+                &blas_res.raw,
+                blas_res.flags,
+                blas_res.update_mode,
+                blas_res.built_index.is_some(),
+                &blas_res.size_info,
+                hal::AccelerationStructureEntries::Triangles(triangles),
+                &mut scratch_size,
+            ));
+        }
+
+        // Top level: only the instance buffer range can be checked.
+        let mut tlas_ids = Vec::with_capacity(tlas.len());
+        let mut tlas_builds = Vec::with_capacity(tlas.len());
+        for entry in tlas {
+            let tlas_id = entry.tlas_id;
+            if tlas_ids.contains(&tlas_id) {
+                return Err(BuildAccelerationStructureError::DuplicateTlasBuild(tlas_id));
+            }
+            let tlas_res = cmd_buf
+                .trackers
+                .tlas_s
+                .add_single(&*tlas_guard, tlas_id)
+                .ok_or(BuildAccelerationStructureError::InvalidTlas(tlas_id))?;
+
+            if entry.instance_count > tlas_res.max_instance_count {
+                return Err(BuildAccelerationStructureError::TooManyInstances {
+                    tlas: tlas_id,
+                    count: entry.instance_count,
+                    max: tlas_res.max_instance_count,
+                });
+            }
+
+            let instance_buffer = use_input_buffer(
+                cmd_buf,
+                &buffer_guard,
+                &mut barriers,
+                entry.instance_buffer_id,
+                0..entry.instance_count as BufferAddress * wgt::TLAS_INSTANCE_SIZE,
+                true,
+            )?;
+
+            tlas_ids.push(tlas_id);
+            tlas_builds.push(PendingBuild::new(
+                &tlas_res.raw,
+                tlas_res.flags,
+                tlas_res.update_mode,
+                tlas_res.built_index.is_some(),
+                &tlas_res.size_info,
+                hal::AccelerationStructureEntries::Instances(hal::AccelerationStructureInstances {
+                    buffer: instance_buffer.raw.as_ref(),
+                    offset: 0,
+                    count: entry.instance_count,
+                }),
+                &mut scratch_size,
+            ));
+        }
+
+        if blas_builds.is_empty() && tlas_builds.is_empty() {
+            return Ok(());
+        }
+
+        let scratch_buffer = unsafe {
+            device.raw.create_buffer(&hal::BufferDescriptor {
+                label: Some("(wgpu internal) acceleration structure scratch buffer"),
+                size: scratch_size.max(SCRATCH_BUFFER_ALIGNMENT),
+                usage: hal::BufferUses::ACCELERATION_STRUCTURE_SCRATCH,
+                memory_flags: hal::MemoryFlags::empty(),
+            })
+        }
+        .map_err(|err| {
+            BuildAccelerationStructureError::ScratchBuffer(CreateBufferError::Device(err.into()))
+        })?;
+
+        let cmd_buf_raw = cmd_buf.encoder.open();
+        unsafe {
+            cmd_buf_raw.transition_buffers(barriers.into_iter());
+            cmd_buf_raw.place_acceleration_structure_barrier(hal::AccelerationStructureBarrier {
+                usage: hal::AccelerationStructureUses::all()
+                    ..hal::AccelerationStructureUses::BUILD_INPUT
+                        | hal::AccelerationStructureUses::BUILD_OUTPUT,
+            });
+            if !blas_builds.is_empty() {
+                cmd_buf_raw.build_acceleration_structures(
+                    blas_builds.len() as u32,
+                    blas_builds
+                        .iter()
+                        .map(|build| build.to_hal(&scratch_buffer)),
+                );
+            }
+            if !tlas_builds.is_empty() {
+                if !blas_builds.is_empty() {
+                    cmd_buf_raw.place_acceleration_structure_barrier(
+                        hal::AccelerationStructureBarrier {
+                            usage: hal::AccelerationStructureUses::BUILD_OUTPUT
+                                ..hal::AccelerationStructureUses::BUILD_INPUT,
+                        },
+                    );
+                }
+                cmd_buf_raw.build_acceleration_structures(
+                    tlas_builds.len() as u32,
+                    tlas_builds
+                        .iter()
+                        .map(|build| build.to_hal(&scratch_buffer)),
+                );
+            }
+            cmd_buf_raw.place_acceleration_structure_barrier(hal::AccelerationStructureBarrier {
+                usage: hal::AccelerationStructureUses::BUILD_OUTPUT
+                    ..hal::AccelerationStructureUses::BUILD_INPUT
+                        | hal::AccelerationStructureUses::SHADER_INPUT,
+            });
+        }
+
+        cmd_buf.blas_builds.extend(blas_ids);
+        cmd_buf
+            .tlas_actions
+            .extend(tlas_ids.into_iter().map(|id| TlasAction {
+                id,
+                kind: TlasActionKind::Build,
+            }));
+        cmd_buf
+            .temp_resources
+            .push(TempResource::Buffer(scratch_buffer));
+
+        Ok(())
+    }
+}
@@ -2,7 +2,7 @@ use crate::{
     binding_model::{BindError, BindGroupLayouts},
     command::{
         self,
-        bind::Binder,
+        bind::{Binder, PushConstantState},
         end_occlusion_query, end_pipeline_statistics_query,
         memory_init::{fixup_discarded_surfaces, SurfacesInDiscardState},
         BasePass, BasePassRef, BindGroupStateChange, CommandBuffer, CommandEncoderError,
@@ -19,8 +19,13 @@ use crate::{
     hub::Token,
     id,
     identity::GlobalIdentityHandlerFactory,
-    init_tracker::{MemoryInitKind, TextureInitRange, TextureInitTrackerAction},
+    index_validation,
+    indirect_validation::{self, IndirectDraw, IndirectDrawIndices, IndirectDraws},
+    init_tracker::{
+        MemoryInitKind, QuerySetInitTrackerAction, TextureInitRange, TextureInitTrackerAction,
+    },
     pipeline::{self, PipelineFlags},
+    ray_tracing::{TlasAction, TlasActionKind},
     resource::{Buffer, QuerySet, Texture, TextureView, TextureViewNotRenderableReason},
     storage::Storage,
     track::{TextureSelector, UsageConflict, UsageScope},
@@ -412,6 +417,7 @@ impl VertexState {
 struct State {
     pipeline_flags: PipelineFlags,
     binder: Binder,
+    push_constants: PushConstantState,
     blend_constant: OptionalState,
     stencil_reference: u32,
     pipeline: Option<id::RenderPipelineId>,
@@ -470,9 +476,50 @@ impl State {
         Ok(())
     }
 
+    /// Describe an indirect draw to be validated, if `device` validates it.
+    fn indirect_draw<A: HalApi>(
+        &self,
+        device: &Device<A>,
+        indexed: bool,
+        indirect_buffer: id::BufferId,
+        indirect_offset: BufferAddress,
+        count: u32,
+    ) -> Option<IndirectDraw> {
+        device.indirect_validation.as_ref()?;
+        let flags = device.instance_flags;
+        if !indirect_validation::validates_draws(flags, indexed) || indirect_offset % 4 != 0 {
+            return None;
+        }
+        let indices = match indexed {
+            false => None,
+            true => {
+                let (buffer, ref range) = *self.index.bound_buffer_view.as_ref()?;
+                Some(IndirectDrawIndices {
+                    buffer: buffer.0,
+                    range: range.clone(),
+                    format: self.index.format?,
+                    restart: self.index.pipeline_format.is_some(),
+                    scan: flags.contains(wgt::InstanceFlags::INDEX_RANGE_VALIDATION),
+                })
+            }
+        };
+        Some(IndirectDraw {
+            indirect_buffer,
+            indirect_offset,
+            count,
+            indices,
+            vertex_limit: self.vertex.vertex_limit,
+            instance_limit: self.vertex.instance_limit,
+            first_instance: device
+                .features
+                .contains(wgt::Features::INDIRECT_FIRST_INSTANCE),
+        })
+    }
+
     /// Reset the `RenderBundle`-related states.
     fn reset_bundle(&mut self) {
         self.binder.reset();
+        self.push_constants.reset();
         self.pipeline = None;
         self.index.reset();
         self.vertex.reset();
@@ -1154,11 +1201,17 @@ impl<'a, A: HalApi> RenderPassInfo<'a, A> {
                 cmd_buf
                     .pending_query_resets
                     .use_query_set(tw.query_set, query_set, index);
+                cmd_buf
+                    .query_init_actions
+                    .push(QuerySetInitTrackerAction::write(tw.query_set, index));
             }
             if let Some(index) = tw.end_of_pass_write_index {
                 cmd_buf
                     .pending_query_resets
                     .use_query_set(tw.query_set, query_set, index);
+                cmd_buf
+                    .query_init_actions
+                    .push(QuerySetInitTrackerAction::write(tw.query_set, index));
             }
 
             Some(hal::RenderPassTimestampWrites {
@@ -1213,6 +1266,7 @@ impl<'a, A: HalApi> RenderPassInfo<'a, A> {
     fn finish(
         mut self,
         raw: &mut A::CommandEncoder,
+        buffer_guard: &Storage<Buffer<A>, id::BufferId>,
         texture_guard: &Storage<Texture<A>, id::TextureId>,
     ) -> Result<(UsageScope<A>, SurfacesInDiscardState), RenderPassErrorInner> {
         profiling::scope!("RenderPassInfo::finish");
@@ -1242,6 +1296,9 @@ impl<'a, A: HalApi> RenderPassInfo<'a, A> {
             };
         }
 
+        self.usage_scope
+            .check_aliasing(buffer_guard, texture_guard)?;
+
         // If either only stencil or depth was discarded, we put in a special
         // clear pass to keep the init status of the aspects in sync. We do this
         // so we don't need to track init state for depth/stencil aspects
@@ -1403,14 +1460,46 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
                 None,
                 Some(&*render_pipeline_guard),
                 Some(&*bundle_guard),
+                None,
                 Some(&*query_set_guard),
             );
 
+            // Indirect draws are issued from a buffer of validated arguments,
+            // see `indirect_validation`.
+            if device.indirect_validation.is_some() {
+                let validated_size =
+                    |indexed, count: u32| match indirect_validation::validates_draws(
+                        device.instance_flags,
+                        indexed,
+                    ) {
+                        true => count as u64 * indirect_validation::draw_args_size(indexed),
+                        false => 0,
+                    };
+                let size = base
+                    .commands
+                    .iter()
+                    .map(|command| match *command {
+                        RenderCommand::MultiDrawIndirect { indexed, count, .. } => {
+                            validated_size(indexed, count.map_or(1, |c| c.get()))
+                        }
+                        RenderCommand::MultiDrawIndirectCount {
+                            indexed, max_count, ..
+                        } => validated_size(indexed, max_count),
+                        _ => 0,
+                    })
+                    .sum::<u64>();
+                if size != 0 {
+                    let draws = IndirectDraws::new(&device.raw, size).map_pass_err(init_scope)?;
+                    cmd_buf.indirect_draws = Some(draws);
+                }
+            }
+
             let raw = &mut cmd_buf.encoder.raw;
 
             let mut state = State {
                 pipeline_flags: PipelineFlags::empty(),
                 binder: Binder::new(),
+                push_constants: PushConstantState::default(),
                 blend_constant: OptionalState::Unused,
                 stencil_reference: 0,
                 pipeline: None,
@@ -1481,6 +1570,17 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
                                 }
                             }),
                         );
+                        cmd_buf.tlas_actions.extend(
+                            bind_group
+                                .used
+                                .acceleration_structures
+                                .used()
+                                .map(|id| TlasAction {
+                                    id: id.0,
+                                    kind: TlasActionKind::Use,
+                                }),
+                        );
+
                         for action in bind_group.used_texture_ranges.iter() {
                             info.pending_discard_init_fixups.extend(
                                 cmd_buf
@@ -1589,26 +1689,22 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
                                 }
                             }
 
-                            // Clear push constant ranges
-                            let non_overlapping = super::bind::compute_nonoverlapping_ranges(
-                                &pipeline_layout.push_constant_ranges,
-                            );
-                            for range in non_overlapping {
-                                let offset = range.range.start;
-                                let size_bytes = range.range.end - offset;
-                                super::push_constant_clear(
-                                    offset,
-                                    size_bytes,
-                                    |clear_offset, clear_data| unsafe {
+                            // Send the push constants again
+                            state
+                                .push_constants
+                                .change_pipeline_layout(
+                                    pipeline_layout,
+                                    |stages, offset, data| unsafe {
                                         raw.set_push_constants(
                                             &pipeline_layout.raw,
-                                            range.stages,
-                                            clear_offset,
-                                            clear_data,
+                                            stages,
+                                            offset,
+                                            data,
                                         );
                                     },
-                                );
-                            }
+                                )
+                                .map_err(RenderCommandError::from)
+                                .map_pass_err(PassErrorScope::SetPushConstant)?;
                         }
 
                         state.index.pipeline_format = pipeline.strip_index_format;
@@ -1823,26 +1919,38 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
                             .ok_or(RenderPassErrorInner::InvalidValuesOffset)
                             .map_pass_err(scope)?;
 
-                        let end_offset_bytes = offset + size_bytes;
                         let values_end_offset =
                             (values_offset + size_bytes / wgt::PUSH_CONSTANT_ALIGNMENT) as usize;
                         let data_slice =
                             &base.push_constant_data[(values_offset as usize)..values_end_offset];
 
-                        let pipeline_layout_id = state
+                        // Without a pipeline layout, the values are sent when one is bound.
+                        let pipeline_layout = state
                             .binder
                             .pipeline_layout_id
-                            .ok_or(DrawError::MissingPipeline)
-                            .map_pass_err(scope)?;
-                        let pipeline_layout = &pipeline_layout_guard[pipeline_layout_id];
+                            .map(|id| &pipeline_layout_guard[id]);
 
-                        pipeline_layout
-                            .validate_push_constant_ranges(stages, offset, end_offset_bytes)
+                        state
+                            .push_constants
+                            .set(
+                                pipeline_layout,
+                                device.limits.max_push_constant_size,
+                                stages,
+                                offset,
+                                data_slice,
+                            )
                             .map_err(RenderCommandError::from)
                             .map_pass_err(scope)?;
 
-                        unsafe {
-                            raw.set_push_constants(&pipeline_layout.raw, stages, offset, data_slice)
+                        if let Some(pipeline_layout) = pipeline_layout {
+                            unsafe {
+                                raw.set_push_constants(
+                                    &pipeline_layout.raw,
+                                    stages,
+                                    offset,
+                                    data_slice,
+                                )
+                            }
                         }
                     }
                     RenderCommand::SetScissor(ref rect) => {
@@ -1929,8 +2037,6 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
                             .is_ready::<A>(indexed, &*bind_group_layout_guard)
                             .map_pass_err(scope)?;
 
-                        //TODO: validate that base_vertex + max_index() is
-                        // within the provided range
                         let last_index = first_index + index_count;
                         let index_limit = state.index.limit;
                         if last_index > index_limit {
@@ -1951,6 +2057,30 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
                             .map_pass_err(scope);
                         }
 
+                        if let (&Some((index_buffer, ref range)), Some(format)) =
+                            (&state.index.bound_buffer_view, state.index.format)
+                        {
+                            if let Some(ref contents) = buffer_guard[index_buffer].index_contents {
+                                let indices = index_validation::index_byte_range(
+                                    format,
+                                    range.start,
+                                    first_index,
+                                    index_count,
+                                );
+                                contents
+                                    .lock()
+                                    .validate_draw(
+                                        format,
+                                        indices,
+                                        state.index.pipeline_format.is_some(),
+                                        base_vertex,
+                                        state.vertex.vertex_limit,
+                                        state.vertex.vertex_limit_slot,
+                                    )
+                                    .map_pass_err(scope)?;
+                            }
+                        }
+
                         unsafe {
                             raw.draw_indexed(
                                 first_index,
@@ -2026,6 +2156,20 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
                             ),
                         );
 
+                        let validated = cmd_buf.indirect_draws.as_mut().zip(state.indirect_draw(
+                            device,
+                            indexed,
+                            buffer_id,
+                            offset,
+                            actual_count,
+                        ));
+                        let (indirect_raw, offset) = match validated {
+                            Some((draws, draw)) => {
+                                let output_offset = draws.push(draw);
+                                (draws.output(), output_offset)
+                            }
+                            None => (indirect_raw, offset),
+                        };
                         match indexed {
                             false => unsafe {
                                 raw.draw_indirect(indirect_raw, offset, actual_count);
@@ -2132,6 +2276,16 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
                             ),
                         );
 
+                        let validated = cmd_buf.indirect_draws.as_mut().zip(
+                            state.indirect_draw(device, indexed, buffer_id, offset, max_count),
+                        );
+                        let (indirect_raw, offset) = match validated {
+                            Some((draws, draw)) => {
+                                let output_offset = draws.push(draw);
+                                (draws.output(), output_offset)
+                            }
+                            None => (indirect_raw, offset),
+                        };
                         match indexed {
                             false => unsafe {
                                 raw.draw_indirect_count(
@@ -2212,6 +2366,7 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
                                 query_set_id,
                                 query_index,
                                 Some(&mut cmd_buf.pending_query_resets),
+                                &mut cmd_buf.query_init_actions,
                             )
                             .map_pass_err(scope)?;
                     }
@@ -2236,6 +2391,7 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
                                 query_set_id,
                                 query_index,
                                 Some(&mut cmd_buf.pending_query_resets),
+                                &mut cmd_buf.query_init_actions,
                                 &mut active_query,
                             )
                             .map_pass_err(scope)?;
@@ -2267,6 +2423,7 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
                                 query_set_id,
                                 query_index,
                                 Some(&mut cmd_buf.pending_query_resets),
+                                &mut cmd_buf.query_init_actions,
                                 &mut active_query,
                             )
                             .map_pass_err(scope)?;
@@ -2323,6 +2480,18 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
                                     Err(_) => None,
                                 }),
                         );
+                        for bind_group_id in bundle.used.bind_groups.used() {
+                            cmd_buf.tlas_actions.extend(
+                                bind_group_guard[bind_group_id]
+                                    .used
+                                    .acceleration_structures
+                                    .used()
+                                    .map(|id| TlasAction {
+                                        id: id.0,
+                                        kind: TlasActionKind::Use,
+                                    }),
+                            );
+                        }
                         for action in bundle.texture_memory_init_actions.iter() {
                             info.pending_discard_init_fixups.extend(
                                 cmd_buf
@@ -2338,15 +2507,17 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
                                 &*bind_group_guard,
                                 &*render_pipeline_guard,
                                 &*buffer_guard,
+                                &*query_set_guard,
+                                &mut cmd_buf.pending_query_resets,
+                                &mut cmd_buf.query_init_actions,
+                                &mut active_query,
                             )
                         }
                         .map_err(|e| match e {
                             ExecutionError::DestroyedBuffer(id) => {
-                                RenderCommandError::DestroyedBuffer(id)
-                            }
-                            ExecutionError::Unimplemented(what) => {
-                                RenderCommandError::Unimplemented(what)
+                                RenderCommandError::DestroyedBuffer(id).into()
                             }
+                            ExecutionError::Query(e) => RenderPassErrorInner::QueryUse(e),
                         })
                         .map_pass_err(scope)?;
 
@@ -2365,8 +2536,9 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
             }
 
             log::trace!("Merging renderpass into cmd_buf {:?}", encoder_id);
-            let (trackers, pending_discard_init_fixups) =
-                info.finish(raw, &*texture_guard).map_pass_err(init_scope)?;
+            let (trackers, pending_discard_init_fixups) = info
+                .finish(raw, &*buffer_guard, &*texture_guard)
+                .map_pass_err(init_scope)?;
 
             cmd_buf.encoder.close();
             (trackers, pending_discard_init_fixups)
@@ -2381,6 +2553,18 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
         {
             let transit = cmd_buf.encoder.open();
 
+            if let Some(draws) = cmd_buf.indirect_draws.take() {
+                draws
+                    .encode(
+                        &device_guard[cmd_buf.device_id.value],
+                        transit,
+                        &buffer_guard,
+                        &mut cmd_buf.trackers.buffers,
+                        &mut cmd_buf.temp_resources,
+                    )
+                    .map_pass_err(init_scope)?;
+            }
+
             fixup_discarded_surfaces(
                 pending_discard_init_fixups.into_iter(),
                 transit,
@@ -692,6 +692,7 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
             dst_offset: destination_offset,
             size: wgt::BufferSize::new(size).unwrap(),
         };
+        dst_buffer.invalidate_index_contents();
         let cmd_buf_raw = cmd_buf.encoder.open();
         unsafe {
             cmd_buf_raw.transition_buffers(src_barrier.into_iter().chain(dst_barrier));
@@ -998,6 +999,7 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
                 size: hal_copy_size,
             }
         });
+        dst_buffer.invalidate_index_contents();
         let cmd_buf_raw = cmd_buf.encoder.open();
         unsafe {
             cmd_buf_raw.transition_buffers(dst_barrier.into_iter());
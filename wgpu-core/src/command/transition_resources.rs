@@ -0,0 +1,192 @@
+#[cfg(feature = "trace")]
+use crate::device::trace::Command as TraceCommand;
+use crate::{
+    command::{CommandBuffer, CommandEncoderError},
+    conv,
+    global::Global,
+    hal_api::HalApi,
+    hub::Token,
+    id::{BufferId, CommandEncoderId, DeviceId, TextureId},
+    identity::GlobalIdentityHandlerFactory,
+    track::{invalid_resource_state, TextureSelector},
+    validation::{
+        check_buffer_usage, check_texture_usage, MissingBufferUsageError, MissingTextureUsageError,
+    },
+};
+
+use hal::CommandEncoder as _;
+use thiserror::Error;
+use wgt::{BufferTransition, BufferUsages, TextureTransition, TextureUsages};
+
+/// Error encountered while attempting to transition resources.
+#[derive(Clone, Debug, Error)]
+#[non_exhaustive]
+pub enum TransitionResourcesError {
+    #[error(transparent)]
+    Encoder(#[from] CommandEncoderError),
+    #[error("Device {0:?} is invalid")]
+    InvalidDevice(DeviceId),
+    #[error("Buffer {0:?} is invalid or destroyed")]
+    InvalidBuffer(BufferId),
+    #[error("Texture {0:?} is invalid or destroyed")]
+    InvalidTexture(TextureId),
+    #[error(transparent)]
+    MissingBufferUsage(#[from] MissingBufferUsageError),
+    #[error(transparent)]
+    MissingTextureUsage(#[from] MissingTextureUsageError),
+    #[error("Buffer {0:?} can't be transitioned to {1:?}, only read-only usages can be combined")]
+    InvalidBufferState(BufferId, BufferUsages),
+    #[error("Texture {0:?} can't be transitioned to {1:?}, only read-only usages can be combined")]
+    InvalidTextureState(TextureId, TextureUsages),
+    #[error("Texture {texture:?} lacks the aspects that were specified in the image subresource range. Texture with format {texture_format:?}, specified was {subresource_range_aspects:?}")]
+    MissingTextureAspect {
+        texture: TextureId,
+        texture_format: wgt::TextureFormat,
+        subresource_range_aspects: wgt::TextureAspect,
+    },
+    #[error("Image subresource range {subresource_range:?} is outside of the subresources of texture {texture:?}")]
+    InvalidSubresourceRange {
+        texture: TextureId,
+        subresource_range: wgt::ImageSubresourceRange,
+    },
+}
+
+/// The state a buffer is put in when it is about to be used with `usage`.
+///
+/// Storage usages are assumed to be written to.
+fn map_buffer_state(usage: BufferUsages) -> hal::BufferUses {
+    let mut state = conv::map_buffer_usage(usage);
+    state.remove(hal::BufferUses::STORAGE_READ);
+    state
+}
+
+/// The state a texture is put in when it is about to be used with `usage`.
+///
+/// Storage and depth-stencil usages are assumed to be written to.
+fn map_texture_state(usage: TextureUsages, format: wgt::TextureFormat) -> hal::TextureUses {
+    let mut state = conv::map_texture_usage(usage, format.into());
+    state.remove(hal::TextureUses::STORAGE_READ | hal::TextureUses::DEPTH_STENCIL_READ);
+    state
+}
+
+impl<G: GlobalIdentityHandlerFactory> Global<G> {
+    /// Move resources into the states they are going to be used in next.
+    ///
+    /// The barriers are recorded right away, instead of right before the
+    /// commands that use the resources, so that backends can overlap them
+    /// with unrelated work.
+    pub fn command_encoder_transition_resources<A: HalApi>(
+        &self,
+        command_encoder_id: CommandEncoderId,
+        buffer_transitions: &[BufferTransition<BufferId>],
+        texture_transitions: &[TextureTransition<TextureId>],
+    ) -> Result<(), TransitionResourcesError> {
+        profiling::scope!("CommandEncoder::transition_resources");
+
+        let hub = A::hub(self);
+        let mut token = Token::root();
+
+        let (device_guard, mut token) = hub.devices.read(&mut token);
+        let (mut cmd_buf_guard, mut token) = hub.command_buffers.write(&mut token);
+        let cmd_buf = CommandBuffer::get_encoder_mut(&mut *cmd_buf_guard, command_encoder_id)?;
+        let (buffer_guard, mut token) = hub.buffers.read(&mut token);
+        let (texture_guard, _) = hub.textures.read(&mut token);
+
+        let device = &device_guard[cmd_buf.device_id.value];
+        if !device.is_valid() {
+            return Err(TransitionResourcesError::InvalidDevice(
+                cmd_buf.device_id.value.0,
+            ));
+        }
+
+        #[cfg(feature = "trace")]
+        if let Some(ref mut list) = cmd_buf.commands {
+            list.push(TraceCommand::TransitionResources {
+                buffer_transitions: buffer_transitions.to_vec(),
+                texture_transitions: texture_transitions.to_vec(),
+            });
+        }
+
+        let mut buffer_barriers = Vec::with_capacity(buffer_transitions.len());
+        for transition in buffer_transitions {
+            let id = transition.buffer;
+            let buffer = buffer_guard
+                .get(id)
+                .map_err(|_| TransitionResourcesError::InvalidBuffer(id))?;
+            if buffer.raw.is_none() {
+                return Err(TransitionResourcesError::InvalidBuffer(id));
+            }
+            check_buffer_usage(buffer.usage, transition.state)?;
+            let state = map_buffer_state(transition.state);
+            if state.is_empty() || invalid_resource_state(state) {
+                return Err(TransitionResourcesError::InvalidBufferState(
+                    id,
+                    transition.state,
+                ));
+            }
+
+            let (buffer, pending) = cmd_buf
+                .trackers
+                .buffers
+                .set_single(&*buffer_guard, id, state)
+                .ok_or(TransitionResourcesError::InvalidBuffer(id))?;
+            buffer_barriers.extend(pending.map(|pending| pending.into_hal(buffer)));
+        }
+
+        let mut texture_barriers = Vec::new();
+        for transition in texture_transitions {
+            let id = transition.texture;
+            let texture = texture_guard
+                .get(id)
+                .map_err(|_| TransitionResourcesError::InvalidTexture(id))?;
+            if texture.inner.as_raw().is_none() {
+                return Err(TransitionResourcesError::InvalidTexture(id));
+            }
+            check_texture_usage(texture.desc.usage, transition.state)?;
+            let state = map_texture_state(transition.state, texture.desc.format);
+            if state.is_empty() || invalid_resource_state(state) {
+                return Err(TransitionResourcesError::InvalidTextureState(
+                    id,
+                    transition.state,
+                ));
+            }
+
+            let range = &transition.subresource_range;
+            if hal::FormatAspects::new(texture.desc.format, range.aspect).is_empty() {
+                return Err(TransitionResourcesError::MissingTextureAspect {
+                    texture: id,
+                    texture_format: texture.desc.format,
+                    subresource_range_aspects: range.aspect,
+                });
+            }
+            let selector = TextureSelector {
+                mips: range.mip_range(texture.full_range.mips.end),
+                layers: range.layer_range(texture.full_range.layers.end),
+            };
+            if selector.mips.is_empty()
+                || selector.layers.is_empty()
+                || selector.mips.end > texture.full_range.mips.end
+                || selector.layers.end > texture.full_range.layers.end
+            {
+                return Err(TransitionResourcesError::InvalidSubresourceRange {
+                    texture: id,
+                    subresource_range: *range,
+                });
+            }
+
+            let pending = cmd_buf
+                .trackers
+                .textures
+                .set_single(texture, id, selector, state)
+                .ok_or(TransitionResourcesError::InvalidTexture(id))?;
+            texture_barriers.extend(pending.map(|pending| pending.into_hal(texture)));
+        }
+
+        let cmd_buf_raw = cmd_buf.encoder.open();
+        unsafe {
+            cmd_buf_raw.transition_buffers(buffer_barriers.into_iter());
+            cmd_buf_raw.transition_textures(texture_barriers.into_iter());
+        }
+        Ok(())
+    }
+}
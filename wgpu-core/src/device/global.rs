@@ -26,7 +26,10 @@ use wgt::{BufferAddress, TextureFormat};
 
 use std::{borrow::Cow, iter, mem, ops::Range, ptr};
 
-use super::{BufferMapPendingClosure, ImplicitPipelineIds, InvalidDevice, UserClosures};
+use super::{
+    resource::ResourceMemory, BufferMapPendingClosure, DeviceLostClosure, ImplicitPipelineIds,
+    InvalidDevice, UserClosures,
+};
 
 impl<G: GlobalIdentityHandlerFactory> Global<G> {
     pub fn adapter_is_surface_supported<A: HalApi>(
@@ -140,6 +143,52 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
         Ok(device.downlevel.clone())
     }
 
+    pub fn device_memory_report<A: HalApi>(
+        &self,
+        device_id: DeviceId,
+    ) -> Result<wgt::MemoryReport, InvalidDevice> {
+        let hub = A::hub(self);
+        let mut token = Token::root();
+        let (device_guard, mut token) = hub.devices.read(&mut token);
+        let device = device_guard.get(device_id).map_err(|_| InvalidDevice)?;
+        if !device.valid {
+            return Err(InvalidDevice);
+        }
+
+        let hal::MemoryReport {
+            heaps,
+            memory_types,
+        } = device.raw.memory_report();
+        let mut report = wgt::MemoryReport {
+            heaps,
+            memory_types,
+            ..Default::default()
+        };
+
+        let (buffer_guard, mut token) = hub.buffers.read(&mut token);
+        for (_, buffer) in buffer_guard.iter(A::VARIANT) {
+            if buffer.device_id.value.0 == device_id && buffer.raw.is_some() {
+                report.buffers.count += 1;
+                report.buffers.bytes += buffer.size;
+            }
+        }
+        let (texture_guard, _) = hub.textures.read(&mut token);
+        for (_, texture) in texture_guard.iter(A::VARIANT) {
+            // Surface textures are owned by the presentation engine.
+            if texture.device_id.value.0 == device_id
+                && matches!(
+                    texture.inner,
+                    resource::TextureInner::Native { raw: Some(_) }
+                )
+            {
+                report.textures.count += 1;
+                report.textures.bytes += texture.content_size();
+            }
+        }
+
+        Ok(report)
+    }
+
     pub fn device_create_buffer<A: HalApi>(
         &self,
         device_id: DeviceId,
@@ -179,10 +228,11 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
                     .add(trace::Action::CreateBuffer(fid.id(), desc));
             }
 
-            let mut buffer = match device.create_buffer(device_id, desc, false) {
-                Ok(buffer) => buffer,
-                Err(e) => break e,
-            };
+            let mut buffer =
+                match device.create_buffer(device_id, desc, false, ResourceMemory::Dedicated) {
+                    Ok(buffer) => buffer,
+                    Err(e) => break e,
+                };
             let ref_count = buffer.life_guard.add_ref();
 
             let buffer_use = if !desc.mapped_at_creation {
@@ -221,7 +271,12 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
                     usage: wgt::BufferUsages::MAP_WRITE | wgt::BufferUsages::COPY_SRC,
                     mapped_at_creation: false,
                 };
-                let mut stage = match device.create_buffer(device_id, &stage_desc, true) {
+                let mut stage = match device.create_buffer(
+                    device_id,
+                    &stage_desc,
+                    true,
+                    ResourceMemory::Dedicated,
+                ) {
                     Ok(stage) => stage,
                     Err(e) => {
                         let raw = buffer.raw.unwrap();
@@ -616,10 +671,11 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
             }
 
             let adapter = &adapter_guard[device.adapter_id.value];
-            let texture = match device.create_texture(device_id, adapter, desc) {
-                Ok(texture) => texture,
-                Err(error) => break error,
-            };
+            let texture =
+                match device.create_texture(device_id, adapter, desc, ResourceMemory::Dedicated) {
+                    Ok(texture) => texture,
+                    Err(error) => break error,
+                };
             let ref_count = texture.life_guard.add_ref();
 
             let id = fid.assign(texture, &mut token);
@@ -891,6 +947,372 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
         }
     }
 
+    pub fn device_create_heap<A: HalApi>(
+        &self,
+        device_id: DeviceId,
+        desc: &resource::HeapDescriptor,
+        id_in: Input<G, id::HeapId>,
+    ) -> (id::HeapId, Option<resource::CreateHeapError>) {
+        profiling::scope!("Device::create_heap");
+
+        let hub = A::hub(self);
+        let mut token = Token::root();
+        let fid = hub.heaps.prepare(id_in);
+
+        let (device_guard, mut token) = hub.devices.read(&mut token);
+        let error = loop {
+            let device = match device_guard.get(device_id) {
+                Ok(device) => device,
+                Err(_) => break DeviceError::Invalid.into(),
+            };
+            if !device.valid {
+                break DeviceError::Lost.into();
+            }
+
+            #[cfg(feature = "trace")]
+            if let Some(ref trace) = device.trace {
+                trace
+                    .lock()
+                    .add(trace::Action::CreateHeap(fid.id(), desc.clone()));
+            }
+
+            let heap = match device.create_heap(device_id, desc) {
+                Ok(heap) => heap,
+                Err(e) => break e,
+            };
+            let id = fid.assign(heap, &mut token);
+
+            log::trace!("Device::create_heap -> {:?}", id.0);
+
+            return (id.0, None);
+        };
+
+        let id = fid.assign_error(desc.label.borrow_or_default(), &mut token);
+        (id, Some(error))
+    }
+
+    pub fn heap_label<A: HalApi>(&self, id: id::HeapId) -> String {
+        A::hub(self).heaps.label_for_resource(id)
+    }
+
+    /// Drop the user's handle to a heap.
+    ///
+    /// The heap's memory is freed once all the resources placed in it are
+    /// destroyed as well.
+    pub fn heap_drop<A: HalApi>(&self, heap_id: id::HeapId) {
+        profiling::scope!("Heap::drop");
+        log::trace!("Heap::drop {heap_id:?}");
+
+        let hub = A::hub(self);
+        let mut token = Token::root();
+        let (device_id, ref_count) = {
+            let (mut heap_guard, _) = hub.heaps.write(&mut token);
+            match heap_guard.get_mut(heap_id) {
+                Ok(heap) => (
+                    heap.device_id.value,
+                    heap.life_guard.ref_count.take().unwrap(),
+                ),
+                Err(InvalidId) => {
+                    hub.heaps.unregister_locked(heap_id, &mut *heap_guard);
+                    return;
+                }
+            }
+        };
+
+        let (device_guard, mut token) = hub.devices.read(&mut token);
+        device_guard[device_id]
+            .lock_life(&mut token)
+            .suspected_resources
+            .heaps
+            .push(Stored {
+                value: id::Valid(heap_id),
+                ref_count,
+            });
+    }
+
+    /// Create a buffer at `offset` in the memory of `heap_id`.
+    ///
+    /// The buffer aliases any other resource placed at an overlapping range
+    /// of the heap.
+    pub fn device_create_buffer_in_heap<A: HalApi>(
+        &self,
+        device_id: DeviceId,
+        desc: &resource::BufferDescriptor,
+        heap_id: id::HeapId,
+        offset: BufferAddress,
+        id_in: Input<G, id::BufferId>,
+    ) -> (id::BufferId, Option<resource::CreateBufferError>) {
+        profiling::scope!("Device::create_buffer_in_heap");
+
+        let hub = A::hub(self);
+        let mut token = Token::root();
+        let fid = hub.buffers.prepare(id_in);
+
+        let (device_guard, mut token) = hub.devices.read(&mut token);
+        let error = loop {
+            let device = match device_guard.get(device_id) {
+                Ok(device) => device,
+                Err(_) => break DeviceError::Invalid.into(),
+            };
+            if !device.valid {
+                break DeviceError::Lost.into();
+            }
+            if let Err(e) = device.require_features(wgt::Features::RESOURCE_HEAPS) {
+                break resource::PlacementError::from(e).into();
+            }
+            if desc.mapped_at_creation
+                || desc
+                    .usage
+                    .intersects(wgt::BufferUsages::MAP_READ | wgt::BufferUsages::MAP_WRITE)
+            {
+                break resource::PlacementError::Mappable.into();
+            }
+
+            #[cfg(feature = "trace")]
+            if let Some(ref trace) = device.trace {
+                trace.lock().add(trace::Action::CreateBufferInHeap {
+                    id: fid.id(),
+                    desc: desc.clone(),
+                    heap: heap_id,
+                    offset,
+                });
+            }
+
+            let buffer = {
+                let (heap_guard, _) = hub.heaps.read(&mut token);
+                let heap = match heap_guard.get(heap_id) {
+                    Ok(heap) if heap.device_id.value.0 == device_id => heap,
+                    _ => break resource::PlacementError::InvalidHeap(heap_id).into(),
+                };
+                let placement = super::resource::HeapPlacementRequest {
+                    heap_id: id::Valid(heap_id),
+                    heap,
+                    offset,
+                };
+                match device.create_buffer(
+                    device_id,
+                    desc,
+                    false,
+                    ResourceMemory::Placed(placement),
+                ) {
+                    Ok(buffer) => buffer,
+                    Err(e) => break e,
+                }
+            };
+            let ref_count = buffer.life_guard.add_ref();
+
+            let id = fid.assign(buffer, &mut token);
+            log::trace!("Device::create_buffer_in_heap -> {:?}", id.0);
+
+            device
+                .trackers
+                .lock()
+                .buffers
+                .insert_single(id, ref_count, hal::BufferUses::empty());
+
+            return (id.0, None);
+        };
+
+        let id = fid.assign_error(desc.label.borrow_or_default(), &mut token);
+        (id, Some(error))
+    }
+
+    /// Create a texture at `offset` in the memory of `heap_id`.
+    ///
+    /// The texture aliases any other resource placed at an overlapping range
+    /// of the heap.
+    pub fn device_create_texture_in_heap<A: HalApi>(
+        &self,
+        device_id: DeviceId,
+        desc: &resource::TextureDescriptor,
+        heap_id: id::HeapId,
+        offset: BufferAddress,
+        id_in: Input<G, id::TextureId>,
+    ) -> (id::TextureId, Option<resource::CreateTextureError>) {
+        profiling::scope!("Device::create_texture_in_heap");
+
+        let hub = A::hub(self);
+        let mut token = Token::root();
+        let fid = hub.textures.prepare(id_in);
+
+        let (adapter_guard, mut token) = hub.adapters.read(&mut token);
+        let (device_guard, mut token) = hub.devices.read(&mut token);
+        let error = loop {
+            let device = match device_guard.get(device_id) {
+                Ok(device) => device,
+                Err(_) => break DeviceError::Invalid.into(),
+            };
+            if !device.valid {
+                break DeviceError::Lost.into();
+            }
+            if let Err(e) = device.require_features(wgt::Features::RESOURCE_HEAPS) {
+                break resource::PlacementError::from(e).into();
+            }
+
+            #[cfg(feature = "trace")]
+            if let Some(ref trace) = device.trace {
+                trace.lock().add(trace::Action::CreateTextureInHeap {
+                    id: fid.id(),
+                    desc: desc.clone(),
+                    heap: heap_id,
+                    offset,
+                });
+            }
+
+            let adapter = &adapter_guard[device.adapter_id.value];
+            let texture = {
+                let (heap_guard, _) = hub.heaps.read(&mut token);
+                let heap = match heap_guard.get(heap_id) {
+                    Ok(heap) if heap.device_id.value.0 == device_id => heap,
+                    _ => break resource::PlacementError::InvalidHeap(heap_id).into(),
+                };
+                let placement = super::resource::HeapPlacementRequest {
+                    heap_id: id::Valid(heap_id),
+                    heap,
+                    offset,
+                };
+                match device.create_texture(
+                    device_id,
+                    adapter,
+                    desc,
+                    ResourceMemory::Placed(placement),
+                ) {
+                    Ok(texture) => texture,
+                    Err(error) => break error,
+                }
+            };
+            let ref_count = texture.life_guard.add_ref();
+
+            let id = fid.assign(texture, &mut token);
+            log::trace!("Device::create_texture_in_heap -> {:?}", id.0);
+
+            device.trackers.lock().textures.insert_single(
+                id.0,
+                ref_count,
+                hal::TextureUses::UNINITIALIZED,
+            );
+
+            return (id.0, None);
+        };
+
+        let id = fid.assign_error(desc.label.borrow_or_default(), &mut token);
+        (id, Some(error))
+    }
+
+    /// Create a buffer without memory, see [`wgt::Features::SPARSE_BINDING`].
+    ///
+    /// Memory is bound to its pages with
+    /// [`queue_update_sparse_bindings`](Self::queue_update_sparse_bindings).
+    pub fn device_create_sparse_buffer<A: HalApi>(
+        &self,
+        device_id: DeviceId,
+        desc: &resource::BufferDescriptor,
+        id_in: Input<G, id::BufferId>,
+    ) -> (id::BufferId, Option<resource::CreateBufferError>) {
+        profiling::scope!("Device::create_sparse_buffer");
+
+        let hub = A::hub(self);
+        let mut token = Token::root();
+        let fid = hub.buffers.prepare(id_in);
+
+        let (device_guard, mut token) = hub.devices.read(&mut token);
+        let error = loop {
+            let device = match device_guard.get(device_id) {
+                Ok(device) => device,
+                Err(_) => break DeviceError::Invalid.into(),
+            };
+            if !device.valid {
+                break DeviceError::Lost.into();
+            }
+
+            #[cfg(feature = "trace")]
+            if let Some(ref trace) = device.trace {
+                trace
+                    .lock()
+                    .add(trace::Action::CreateSparseBuffer(fid.id(), desc.clone()));
+            }
+
+            let buffer = match device.create_buffer(device_id, desc, false, ResourceMemory::Sparse)
+            {
+                Ok(buffer) => buffer,
+                Err(e) => break e,
+            };
+            let ref_count = buffer.life_guard.add_ref();
+
+            let id = fid.assign(buffer, &mut token);
+            log::trace!("Device::create_sparse_buffer -> {:?}", id.0);
+
+            device
+                .trackers
+                .lock()
+                .buffers
+                .insert_single(id, ref_count, hal::BufferUses::empty());
+
+            return (id.0, None);
+        };
+
+        let id = fid.assign_error(desc.label.borrow_or_default(), &mut token);
+        (id, Some(error))
+    }
+
+    /// Create a texture without memory, see [`wgt::Features::SPARSE_RESIDENCY`].
+    ///
+    /// Memory is bound to its tiles with
+    /// [`queue_update_sparse_bindings`](Self::queue_update_sparse_bindings).
+    pub fn device_create_sparse_texture<A: HalApi>(
+        &self,
+        device_id: DeviceId,
+        desc: &resource::TextureDescriptor,
+        id_in: Input<G, id::TextureId>,
+    ) -> (id::TextureId, Option<resource::CreateTextureError>) {
+        profiling::scope!("Device::create_sparse_texture");
+
+        let hub = A::hub(self);
+        let mut token = Token::root();
+        let fid = hub.textures.prepare(id_in);
+
+        let (adapter_guard, mut token) = hub.adapters.read(&mut token);
+        let (device_guard, mut token) = hub.devices.read(&mut token);
+        let error = loop {
+            let device = match device_guard.get(device_id) {
+                Ok(device) => device,
+                Err(_) => break DeviceError::Invalid.into(),
+            };
+            if !device.valid {
+                break DeviceError::Lost.into();
+            }
+
+            #[cfg(feature = "trace")]
+            if let Some(ref trace) = device.trace {
+                trace
+                    .lock()
+                    .add(trace::Action::CreateSparseTexture(fid.id(), desc.clone()));
+            }
+
+            let adapter = &adapter_guard[device.adapter_id.value];
+            let texture =
+                match device.create_texture(device_id, adapter, desc, ResourceMemory::Sparse) {
+                    Ok(texture) => texture,
+                    Err(error) => break error,
+                };
+            let ref_count = texture.life_guard.add_ref();
+
+            let id = fid.assign(texture, &mut token);
+            log::trace!("Device::create_sparse_texture -> {:?}", id.0);
+
+            device.trackers.lock().textures.insert_single(
+                id.0,
+                ref_count,
+                hal::TextureUses::UNINITIALIZED,
+            );
+
+            return (id.0, None);
+        };
+
+        let id = fid.assign_error(desc.label.borrow_or_default(), &mut token);
+        (id, Some(error))
+    }
+
     pub fn texture_create_view<A: HalApi>(
         &self,
         texture_id: id::TextureId,
@@ -1560,7 +1982,10 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
         device_id: DeviceId,
         desc: &wgt::CommandEncoderDescriptor<Label>,
         id_in: Input<G, id::CommandEncoderId>,
-    ) -> (id::CommandEncoderId, Option<DeviceError>) {
+    ) -> (
+        id::CommandEncoderId,
+        Option<command::CreateCommandEncoderError>,
+    ) {
         profiling::scope!("Device::create_command_encoder");
 
         let hub = A::hub(self);
@@ -1571,23 +1996,39 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
         let error = loop {
             let device = match device_guard.get(device_id) {
                 Ok(device) => device,
-                Err(_) => break DeviceError::Invalid,
+                Err(_) => break DeviceError::Invalid.into(),
             };
             if !device.valid {
-                break DeviceError::Lost;
+                break DeviceError::Lost.into();
+            }
+            if desc.reusable {
+                if let Err(e) = device.require_features(wgt::Features::REUSABLE_COMMAND_BUFFERS) {
+                    break e.into();
+                }
             }
 
             let dev_stored = Stored {
                 value: id::Valid(device_id),
                 ref_count: device.life_guard.add_ref(),
             };
-            let encoder = match device
-                .command_allocator
-                .lock()
-                .acquire_encoder(&device.raw, &device.queue)
-            {
+            let encoder = if desc.reusable {
+                // The encoder of a reusable command buffer is never reset, so it
+                // is not taken from the pool shared with one-shot command buffers.
+                let hal_desc = hal::CommandEncoderDescriptor {
+                    label: None,
+                    queue: &device.queue,
+                    reusable: true,
+                };
+                unsafe { device.raw.create_command_encoder(&hal_desc) }
+            } else {
+                device
+                    .command_allocator
+                    .lock()
+                    .acquire_encoder(&device.raw, &device.queue)
+            };
+            let encoder = match encoder {
                 Ok(raw) => raw,
-                Err(_) => break DeviceError::OutOfMemory,
+                Err(_) => break DeviceError::OutOfMemory.into(),
             };
             let command_buffer = command::CommandBuffer::new(
                 encoder,
@@ -1598,6 +2039,7 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
                 #[cfg(feature = "trace")]
                 device.trace.is_some(),
                 &desc.label,
+                desc.reusable,
             );
 
             let id = fid.assign(command_buffer, &mut token);
@@ -1629,7 +2071,13 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
         if let Some(cmdbuf) = cmdbuf {
             let device = &mut device_guard[cmdbuf.device_id.value];
             device.untrack::<G>(hub, &cmdbuf.trackers, &mut token);
-            device.destroy_command_buffer(cmdbuf);
+            match cmdbuf.last_submission_index {
+                // A submitted reusable command buffer may still be executing.
+                Some(index) => device
+                    .lock_life(&mut token)
+                    .schedule_command_buffer_destruction(cmdbuf.into_baked(), index),
+                None => device.destroy_command_buffer(cmdbuf),
+            }
         }
     }
 
@@ -1639,71 +2087,172 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
         self.command_encoder_drop::<A>(command_buffer_id)
     }
 
-    pub fn device_create_render_bundle_encoder(
+    pub fn device_create_render_bundle_encoder(
+        &self,
+        device_id: DeviceId,
+        desc: &command::RenderBundleEncoderDescriptor,
+    ) -> (
+        id::RenderBundleEncoderId,
+        Option<command::CreateRenderBundleError>,
+    ) {
+        profiling::scope!("Device::create_render_bundle_encoder");
+        log::trace!("Device::device_create_render_bundle_encoder");
+        let (encoder, error) = match command::RenderBundleEncoder::new(desc, device_id, None) {
+            Ok(encoder) => (encoder, None),
+            Err(e) => (command::RenderBundleEncoder::dummy(device_id), Some(e)),
+        };
+        (Box::into_raw(Box::new(encoder)), error)
+    }
+
+    pub fn render_bundle_encoder_finish<A: HalApi>(
+        &self,
+        bundle_encoder: command::RenderBundleEncoder,
+        desc: &command::RenderBundleDescriptor,
+        id_in: Input<G, id::RenderBundleId>,
+    ) -> (id::RenderBundleId, Option<command::RenderBundleError>) {
+        profiling::scope!("RenderBundleEncoder::finish");
+
+        let hub = A::hub(self);
+        let mut token = Token::root();
+        let fid = hub.render_bundles.prepare(id_in);
+
+        let (device_guard, mut token) = hub.devices.read(&mut token);
+        let error = loop {
+            let device = match device_guard.get(bundle_encoder.parent()) {
+                Ok(device) => device,
+                Err(_) => break command::RenderBundleError::INVALID_DEVICE,
+            };
+            if !device.valid {
+                break command::RenderBundleError::INVALID_DEVICE;
+            }
+
+            #[cfg(feature = "trace")]
+            if let Some(ref trace) = device.trace {
+                trace.lock().add(trace::Action::CreateRenderBundle {
+                    id: fid.id(),
+                    desc: trace::new_render_bundle_encoder_descriptor(
+                        desc.label.clone(),
+                        &bundle_encoder.context,
+                        bundle_encoder.is_depth_read_only,
+                        bundle_encoder.is_stencil_read_only,
+                    ),
+                    base: bundle_encoder.to_base_pass(),
+                });
+            }
+
+            let render_bundle = match bundle_encoder.finish(desc, device, hub, &mut token) {
+                Ok(bundle) => bundle,
+                Err(e) => break e,
+            };
+
+            log::debug!("Render bundle");
+            let ref_count = render_bundle.life_guard.add_ref();
+            let id = fid.assign(render_bundle, &mut token);
+
+            device.trackers.lock().bundles.insert_single(id, ref_count);
+
+            log::trace!("RenderBundleEncoder::finish -> {:?}", id.0);
+
+            return (id.0, None);
+        };
+
+        let id = fid.assign_error(desc.label.borrow_or_default(), &mut token);
+        (id, Some(error))
+    }
+
+    pub fn render_bundle_label<A: HalApi>(&self, id: id::RenderBundleId) -> String {
+        A::hub(self).render_bundles.label_for_resource(id)
+    }
+
+    pub fn render_bundle_drop<A: HalApi>(&self, render_bundle_id: id::RenderBundleId) {
+        profiling::scope!("RenderBundle::drop");
+        log::trace!("RenderBundle::drop {:?}", render_bundle_id);
+        let hub = A::hub(self);
+        let mut token = Token::root();
+
+        let (device_guard, mut token) = hub.devices.read(&mut token);
+        let device_id = {
+            let (mut bundle_guard, _) = hub.render_bundles.write(&mut token);
+            match bundle_guard.get_mut(render_bundle_id) {
+                Ok(bundle) => {
+                    bundle.life_guard.ref_count.take();
+                    bundle.device_id.value
+                }
+                Err(InvalidId) => {
+                    hub.render_bundles
+                        .unregister_locked(render_bundle_id, &mut *bundle_guard);
+                    return;
+                }
+            }
+        };
+
+        device_guard[device_id]
+            .lock_life(&mut token)
+            .suspected_resources
+            .render_bundles
+            .push(id::Valid(render_bundle_id));
+    }
+
+    pub fn device_create_compute_bundle_encoder(
         &self,
         device_id: DeviceId,
-        desc: &command::RenderBundleEncoderDescriptor,
-    ) -> (
-        id::RenderBundleEncoderId,
-        Option<command::CreateRenderBundleError>,
-    ) {
-        profiling::scope!("Device::create_render_bundle_encoder");
-        log::trace!("Device::device_create_render_bundle_encoder");
-        let (encoder, error) = match command::RenderBundleEncoder::new(desc, device_id, None) {
-            Ok(encoder) => (encoder, None),
-            Err(e) => (command::RenderBundleEncoder::dummy(device_id), Some(e)),
-        };
-        (Box::into_raw(Box::new(encoder)), error)
+        desc: &command::ComputeBundleEncoderDescriptor,
+    ) -> id::ComputeBundleEncoderId {
+        profiling::scope!("Device::create_compute_bundle_encoder");
+        log::trace!("Device::device_create_compute_bundle_encoder");
+        let encoder = command::ComputeBundleEncoder::new(desc, device_id, None);
+        Box::into_raw(Box::new(encoder))
     }
 
-    pub fn render_bundle_encoder_finish<A: HalApi>(
+    pub fn compute_bundle_encoder_finish<A: HalApi>(
         &self,
-        bundle_encoder: command::RenderBundleEncoder,
-        desc: &command::RenderBundleDescriptor,
-        id_in: Input<G, id::RenderBundleId>,
-    ) -> (id::RenderBundleId, Option<command::RenderBundleError>) {
-        profiling::scope!("RenderBundleEncoder::finish");
+        bundle_encoder: command::ComputeBundleEncoder,
+        desc: &command::ComputeBundleDescriptor,
+        id_in: Input<G, id::ComputeBundleId>,
+    ) -> (id::ComputeBundleId, Option<command::ComputeBundleError>) {
+        profiling::scope!("ComputeBundleEncoder::finish");
 
         let hub = A::hub(self);
         let mut token = Token::root();
-        let fid = hub.render_bundles.prepare(id_in);
+        let fid = hub.compute_bundles.prepare(id_in);
 
         let (device_guard, mut token) = hub.devices.read(&mut token);
         let error = loop {
             let device = match device_guard.get(bundle_encoder.parent()) {
                 Ok(device) => device,
-                Err(_) => break command::RenderBundleError::INVALID_DEVICE,
+                Err(_) => break command::ComputeBundleError::INVALID_DEVICE,
             };
             if !device.valid {
-                break command::RenderBundleError::INVALID_DEVICE;
+                break command::ComputeBundleError::INVALID_DEVICE;
             }
 
             #[cfg(feature = "trace")]
             if let Some(ref trace) = device.trace {
-                trace.lock().add(trace::Action::CreateRenderBundle {
+                trace.lock().add(trace::Action::CreateComputeBundle {
                     id: fid.id(),
-                    desc: trace::new_render_bundle_encoder_descriptor(
-                        desc.label.clone(),
-                        &bundle_encoder.context,
-                        bundle_encoder.is_depth_read_only,
-                        bundle_encoder.is_stencil_read_only,
-                    ),
+                    desc: command::ComputeBundleEncoderDescriptor {
+                        label: desc.label.clone(),
+                    },
                     base: bundle_encoder.to_base_pass(),
                 });
             }
 
-            let render_bundle = match bundle_encoder.finish(desc, device, hub, &mut token) {
+            let compute_bundle = match bundle_encoder.finish(desc, device, hub, &mut token) {
                 Ok(bundle) => bundle,
                 Err(e) => break e,
             };
 
-            log::debug!("Render bundle");
-            let ref_count = render_bundle.life_guard.add_ref();
-            let id = fid.assign(render_bundle, &mut token);
+            log::debug!("Compute bundle");
+            let ref_count = compute_bundle.life_guard.add_ref();
+            let id = fid.assign(compute_bundle, &mut token);
 
-            device.trackers.lock().bundles.insert_single(id, ref_count);
+            device
+                .trackers
+                .lock()
+                .compute_bundles
+                .insert_single(id, ref_count);
 
-            log::trace!("RenderBundleEncoder::finish -> {:?}", id.0);
+            log::trace!("ComputeBundleEncoder::finish -> {:?}", id.0);
 
             return (id.0, None);
         };
@@ -1712,27 +2261,27 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
         (id, Some(error))
     }
 
-    pub fn render_bundle_label<A: HalApi>(&self, id: id::RenderBundleId) -> String {
-        A::hub(self).render_bundles.label_for_resource(id)
+    pub fn compute_bundle_label<A: HalApi>(&self, id: id::ComputeBundleId) -> String {
+        A::hub(self).compute_bundles.label_for_resource(id)
     }
 
-    pub fn render_bundle_drop<A: HalApi>(&self, render_bundle_id: id::RenderBundleId) {
-        profiling::scope!("RenderBundle::drop");
-        log::trace!("RenderBundle::drop {:?}", render_bundle_id);
+    pub fn compute_bundle_drop<A: HalApi>(&self, compute_bundle_id: id::ComputeBundleId) {
+        profiling::scope!("ComputeBundle::drop");
+        log::trace!("ComputeBundle::drop {:?}", compute_bundle_id);
         let hub = A::hub(self);
         let mut token = Token::root();
 
         let (device_guard, mut token) = hub.devices.read(&mut token);
         let device_id = {
-            let (mut bundle_guard, _) = hub.render_bundles.write(&mut token);
-            match bundle_guard.get_mut(render_bundle_id) {
+            let (mut bundle_guard, _) = hub.compute_bundles.write(&mut token);
+            match bundle_guard.get_mut(compute_bundle_id) {
                 Ok(bundle) => {
                     bundle.life_guard.ref_count.take();
                     bundle.device_id.value
                 }
                 Err(InvalidId) => {
-                    hub.render_bundles
-                        .unregister_locked(render_bundle_id, &mut *bundle_guard);
+                    hub.compute_bundles
+                        .unregister_locked(compute_bundle_id, &mut *bundle_guard);
                     return;
                 }
             }
@@ -1741,8 +2290,8 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
         device_guard[device_id]
             .lock_life(&mut token)
             .suspected_resources
-            .render_bundles
-            .push(id::Valid(render_bundle_id));
+            .compute_bundles
+            .push(id::Valid(compute_bundle_id));
     }
 
     pub fn device_create_query_set<A: HalApi>(
@@ -1834,6 +2383,150 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
         A::hub(self).query_sets.label_for_resource(id)
     }
 
+    pub fn device_create_external_semaphore<A: HalApi>(
+        &self,
+        device_id: DeviceId,
+        desc: &resource::ExternalSemaphoreDescriptor,
+        id_in: Input<G, id::ExternalSemaphoreId>,
+    ) -> (
+        id::ExternalSemaphoreId,
+        Option<resource::CreateExternalSemaphoreError>,
+    ) {
+        profiling::scope!("Device::create_external_semaphore");
+
+        let hub = A::hub(self);
+        let mut token = Token::root();
+        let fid = hub.external_semaphores.prepare(id_in);
+
+        let (device_guard, mut token) = hub.devices.read(&mut token);
+        let error = loop {
+            let device = match device_guard.get(device_id) {
+                Ok(device) => device,
+                Err(_) => break DeviceError::Invalid.into(),
+            };
+            if !device.valid {
+                break DeviceError::Lost.into();
+            }
+
+            #[cfg(feature = "trace")]
+            if let Some(ref trace) = device.trace {
+                trace.lock().add(trace::Action::CreateExternalSemaphore(
+                    fid.id(),
+                    desc.clone(),
+                ));
+            }
+
+            let semaphore = match device.create_external_semaphore(device_id, desc) {
+                Ok(semaphore) => semaphore,
+                Err(e) => break e,
+            };
+            let id = fid.assign(semaphore, &mut token);
+
+            log::trace!("Device::create_external_semaphore -> {:?}", id.0);
+
+            return (id.0, None);
+        };
+
+        let id = fid.assign_error(desc.label.borrow_or_default(), &mut token);
+        (id, Some(error))
+    }
+
+    /// # Safety
+    ///
+    /// - `hal_semaphore` must be created from `device_id` corresponding raw handle.
+    /// - `hal_semaphore` must be a timeline semaphore
+    pub unsafe fn create_external_semaphore_from_hal<A: HalApi>(
+        &self,
+        hal_semaphore: A::ExternalSemaphore,
+        device_id: DeviceId,
+        desc: &resource::ExternalSemaphoreDescriptor,
+        id_in: Input<G, id::ExternalSemaphoreId>,
+    ) -> (
+        id::ExternalSemaphoreId,
+        Option<resource::CreateExternalSemaphoreError>,
+    ) {
+        profiling::scope!("Device::create_external_semaphore_from_hal");
+
+        let hub = A::hub(self);
+        let mut token = Token::root();
+        let fid = hub.external_semaphores.prepare(id_in);
+
+        let (device_guard, mut token) = hub.devices.read(&mut token);
+        let error = loop {
+            let device = match device_guard.get(device_id) {
+                Ok(device) => device,
+                Err(_) => break DeviceError::Invalid.into(),
+            };
+            if !device.valid {
+                break DeviceError::Lost.into();
+            }
+            if let Err(e) = device.require_features(wgt::Features::EXTERNAL_SEMAPHORES) {
+                break e.into();
+            }
+
+            // NB: The semaphore is signaled from outside of wgpu, a replay
+            // gets one of its own that nothing else signals.
+            #[cfg(feature = "trace")]
+            if let Some(ref trace) = device.trace {
+                trace.lock().add(trace::Action::CreateExternalSemaphore(
+                    fid.id(),
+                    desc.clone(),
+                ));
+            }
+
+            let semaphore =
+                device.create_external_semaphore_from_hal(hal_semaphore, device_id, desc);
+            let id = fid.assign(semaphore, &mut token);
+
+            log::trace!("Device::create_external_semaphore_from_hal -> {:?}", id.0);
+
+            return (id.0, None);
+        };
+
+        let id = fid.assign_error(desc.label.borrow_or_default(), &mut token);
+        (id, Some(error))
+    }
+
+    pub fn external_semaphore_label<A: HalApi>(&self, id: id::ExternalSemaphoreId) -> String {
+        A::hub(self).external_semaphores.label_for_resource(id)
+    }
+
+    /// Drop the user's handle to an external semaphore.
+    ///
+    /// The semaphore is destroyed once the submissions waiting on or
+    /// signaling it are done.
+    pub fn external_semaphore_drop<A: HalApi>(
+        &self,
+        external_semaphore_id: id::ExternalSemaphoreId,
+    ) {
+        profiling::scope!("ExternalSemaphore::drop");
+        log::trace!("ExternalSemaphore::drop {external_semaphore_id:?}");
+
+        let hub = A::hub(self);
+        let mut token = Token::root();
+        let device_id = {
+            let (mut semaphore_guard, _) = hub.external_semaphores.write(&mut token);
+            match semaphore_guard.get_mut(external_semaphore_id) {
+                Ok(semaphore) => {
+                    semaphore.life_guard.ref_count.take();
+                    semaphore.device_id.value
+                }
+                Err(InvalidId) => {
+                    hub.external_semaphores
+                        .unregister_locked(external_semaphore_id, &mut *semaphore_guard);
+                    return;
+                }
+            }
+        };
+
+        let (device_guard, mut token) = hub.devices.read(&mut token);
+        device_guard[device_id]
+            .lock_life(&mut token)
+            .suspected_resources
+            .external_semaphores
+            .push(id::Valid(external_semaphore_id));
+    }
+
     pub fn device_create_render_pipeline<A: HalApi>(
         &self,
         device_id: DeviceId,
@@ -2176,6 +2869,120 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
             .push(layout_id);
     }
 
+    /// # Safety
+    /// The `data` argument of `desc` must have been returned by
+    /// [Self::pipeline_cache_get_data] for the same adapter.
+    pub unsafe fn device_create_pipeline_cache<A: HalApi>(
+        &self,
+        device_id: DeviceId,
+        desc: &pipeline::PipelineCacheDescriptor<'_>,
+        id_in: Input<G, id::PipelineCacheId>,
+    ) -> (
+        id::PipelineCacheId,
+        Option<pipeline::CreatePipelineCacheError>,
+    ) {
+        profiling::scope!("Device::create_pipeline_cache");
+
+        let hub = A::hub(self);
+        let mut token = Token::root();
+        let fid = hub.pipeline_caches.prepare(id_in);
+
+        let (adapter_guard, mut token) = hub.adapters.read(&mut token);
+        let (device_guard, mut token) = hub.devices.read(&mut token);
+        let error = loop {
+            let device = match device_guard.get(device_id) {
+                Ok(device) => device,
+                Err(_) => break DeviceError::Invalid.into(),
+            };
+            if !device.valid {
+                break DeviceError::Lost.into();
+            }
+
+            let adapter = &adapter_guard[device.adapter_id.value];
+            #[cfg(feature = "trace")]
+            if let Some(ref trace) = device.trace {
+                // The cache contents only affect how fast pipelines are created, so
+                // don't bloat the trace with them.
+                trace.lock().add(trace::Action::CreatePipelineCache {
+                    id: fid.id(),
+                    desc: pipeline::PipelineCacheDescriptor {
+                        label: desc.label.clone(),
+                        data: None,
+                        fallback: desc.fallback,
+                    },
+                });
+            }
+
+            let cache = match unsafe { device.create_pipeline_cache(device_id, adapter, desc) } {
+                Ok(cache) => cache,
+                Err(e) => break e,
+            };
+            let id = fid.assign(cache, &mut token);
+
+            log::trace!("Device::create_pipeline_cache -> {:?}", id.0);
+
+            return (id.0, None);
+        };
+
+        let id = fid.assign_error(desc.label.borrow_or_default(), &mut token);
+        (id, Some(error))
+    }
+
+    /// Serialize the contents of a pipeline cache, prefixed with a header identifying
+    /// the adapter it can be used with.
+    ///
+    /// Returns `None` if the cache is invalid or the backend doesn't support
+    /// retrieving cache data.
+    pub fn pipeline_cache_get_data<A: HalApi>(&self, id: id::PipelineCacheId) -> Option<Vec<u8>> {
+        use crate::pipeline_cache;
+
+        profiling::scope!("PipelineCache::get_data");
+        let hub = A::hub(self);
+        let mut token = Token::root();
+
+        let (adapter_guard, mut token) = hub.adapters.read(&mut token);
+        let (device_guard, mut token) = hub.devices.read(&mut token);
+        let (cache_guard, _) = hub.pipeline_caches.read(&mut token);
+        let cache = cache_guard.get(id).ok()?;
+        let device = &device_guard[cache.device_id.value];
+        if !device.valid {
+            return None;
+        }
+        let adapter = &adapter_guard[device.adapter_id.value];
+
+        let validation_key = device.raw.pipeline_cache_validation_key()?;
+        let data = unsafe { device.raw.pipeline_cache_get_data(&cache.raw) }?;
+        Some(pipeline_cache::add_cache_header(
+            &data,
+            &adapter.raw.info,
+            validation_key,
+        ))
+    }
+
+    pub fn pipeline_cache_drop<A: HalApi>(&self, pipeline_cache_id: id::PipelineCacheId) {
+        profiling::scope!("PipelineCache::drop");
+        log::trace!("PipelineCache::drop {:?}", pipeline_cache_id);
+
+        let hub = A::hub(self);
+        let mut token = Token::root();
+        let (device_guard, mut token) = hub.devices.read(&mut token);
+        let (cache, _) = hub
+            .pipeline_caches
+            .unregister(pipeline_cache_id, &mut token);
+        if let Some(cache) = cache {
+            let device = &device_guard[cache.device_id.value];
+            #[cfg(feature = "trace")]
+            if let Some(ref trace) = device.trace {
+                trace
+                    .lock()
+                    .add(trace::Action::DestroyPipelineCache(pipeline_cache_id));
+            }
+            unsafe {
+                device.raw.destroy_pipeline_cache(cache.raw);
+            }
+        }
+    }
+
     pub fn surface_configure<A: HalApi>(
         &self,
         surface_id: SurfaceId,
@@ -2489,10 +3296,18 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
             let hub = A::hub(self);
             let mut token = Token::root();
             let (device_guard, mut token) = hub.devices.read(&mut token);
-            device_guard
+            let result = device_guard
                 .get(device_id)
                 .map_err(|_| DeviceError::Invalid)?
-                .maintain(hub, maintain, &mut token)?
+                .maintain(hub, maintain, &mut token);
+            drop(device_guard);
+
+            // A lost device can't do any more work, so resolve its lost
+            // callback before reporting the error.
+            if let Err(WaitIdleError::Device(DeviceError::Lost)) = result {
+                self.device_lose::<A>(device_id, None);
+            }
+            result?
         };
 
         closures.fire();
@@ -2580,6 +3395,11 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
             all_queue_empty =
                 self.poll_devices::<hal::api::Gles>(force_wait, &mut closures)? && all_queue_empty;
         }
+        #[cfg(feature = "cpu")]
+        {
+            all_queue_empty =
+                self.poll_devices::<hal::api::Cpu>(force_wait, &mut closures)? && all_queue_empty;
+        }
 
         closures.fire();
 
@@ -2618,6 +3438,34 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
         }
     }
 
+    pub fn device_set_device_lost_closure<A: HalApi>(
+        &self,
+        device_id: DeviceId,
+        device_lost_closure: DeviceLostClosure,
+    ) {
+        log::trace!("Device::set_device_lost_closure {device_id:?}");
+
+        let hub = A::hub(self);
+        let mut token = Token::root();
+
+        let (mut device_guard, _) = hub.devices.write(&mut token);
+        let device = match device_guard.get_mut(device_id) {
+            Ok(device) => device,
+            Err(_) => return,
+        };
+        if device.valid {
+            *device.device_lost_closure.get_mut() = Some(device_lost_closure);
+            return;
+        }
+        drop(device_guard);
+
+        // The device has already been lost, so there's nothing to wait for.
+        device_lost_closure.call(
+            wgt::DeviceLostReason::Unknown,
+            "Device was already lost".to_string(),
+        );
+    }
+
     pub fn device_drop<A: HalApi>(&self, device_id: DeviceId) {
         profiling::scope!("Device::drop");
         log::trace!("Device::drop {device_id:?}");
@@ -2629,9 +3477,24 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
         // stands for the user's reference to the device. We'll take care of
         // cleaning up the device when we're polled, once its queue submissions
         // have completed and it is no longer needed by other resources.
-        let (mut device_guard, _) = hub.devices.write(&mut token);
-        if let Ok(device) = device_guard.get_mut(device_id) {
-            device.life_guard.ref_count.take().unwrap();
+        let device_lost_closure = {
+            let (mut device_guard, _) = hub.devices.write(&mut token);
+            match device_guard.get_mut(device_id) {
+                Ok(device) => {
+                    device.life_guard.ref_count.take().unwrap();
+                    device.device_lost_closure.get_mut().take()
+                }
+                Err(_) => None,
+            }
+        };
+
+        // Nobody can observe the device anymore, so this is the last chance
+        // to tell the user that it's gone.
+        if let Some(closure) = device_lost_closure {
+            closure.call(
+                wgt::DeviceLostReason::Unknown,
+                "Device was dropped".to_string(),
+            );
         }
     }
 
@@ -2641,8 +3504,13 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
         let hub = A::hub(self);
         let mut token = Token::root();
 
-        let (mut device_guard, _) = hub.devices.write(&mut token);
-        if let Ok(device) = device_guard.get_mut(device_id) {
+        let invocation = {
+            let (mut device_guard, _) = hub.devices.write(&mut token);
+            let device = match device_guard.get_mut(device_id) {
+                Ok(device) => device,
+                Err(_) => return,
+            };
+
             // Follow the steps at
             // https://gpuweb.github.io/gpuweb/#dom-gpudevice-destroy.
 
@@ -2660,10 +3528,11 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
             // TODO: implement this delay.
 
             // Finish by losing the device.
+            device.lose(wgt::DeviceLostReason::Destroyed, "Device was destroyed")
+        };
 
-            // TODO: associate this "destroyed" reason more tightly with
-            // the GPUDeviceLostReason defined in webgpu.idl.
-            device.lose(Some("destroyed"));
+        if let Some(invocation) = invocation {
+            invocation.call();
         }
     }
 
@@ -2673,9 +3542,20 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
         let hub = A::hub(self);
         let mut token = Token::root();
 
-        let (mut device_guard, _) = hub.devices.write(&mut token);
-        if let Ok(device) = device_guard.get_mut(device_id) {
-            device.lose(reason);
+        let invocation = {
+            let (mut device_guard, _) = hub.devices.write(&mut token);
+            match device_guard.get_mut(device_id) {
+                // Only the first loss is reported.
+                Ok(device) if device.valid => device.lose(
+                    wgt::DeviceLostReason::Unknown,
+                    reason.unwrap_or("Device was lost"),
+                ),
+                _ => None,
+            }
+        };
+
+        if let Some(invocation) = invocation {
+            invocation.call();
         }
     }
 
@@ -2922,6 +3802,11 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
                         queued: true,
                     });
                 }
+                if let Some(ref mut contents) = buffer.index_contents {
+                    contents.get_mut().write(0, unsafe {
+                        std::slice::from_raw_parts(ptr.as_ptr(), buffer.size as usize)
+                    });
+                }
                 let _ = ptr;
                 if needs_flush {
                     unsafe {
@@ -2983,6 +3868,14 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
                             queued: false,
                         });
                     }
+                    if let Some(ref mut contents) = buffer.index_contents {
+                        contents.get_mut().write(range.start, unsafe {
+                            std::slice::from_raw_parts(
+                                ptr.as_ptr(),
+                                (range.end - range.start) as usize,
+                            )
+                        });
+                    }
                     let _ = (ptr, range);
                 }
                 unsafe {
@@ -1,6 +1,7 @@
 #[cfg(feature = "trace")]
 use crate::device::trace;
 use crate::{
+    command::BakedCommands,
     device::{
         queue::{EncoderInFlight, SubmittedWorkDoneClosure, TempResource},
         DeviceError,
@@ -10,7 +11,7 @@ use crate::{
     id,
     identity::GlobalIdentityHandlerFactory,
     resource,
-    track::{BindGroupStates, RenderBundleScope, Tracker},
+    track::{BindGroupStates, ComputeBundleScope, RenderBundleScope, Tracker},
     RefCount, Stored, SubmissionIndex,
 };
 use smallvec::SmallVec;
@@ -34,7 +35,12 @@ pub(super) struct SuspectedResources {
     pub(super) bind_group_layouts: Vec<id::Valid<id::BindGroupLayoutId>>,
     pub(super) pipeline_layouts: Vec<Stored<id::PipelineLayoutId>>,
     pub(super) render_bundles: Vec<id::Valid<id::RenderBundleId>>,
+    pub(super) compute_bundles: Vec<id::Valid<id::ComputeBundleId>>,
     pub(super) query_sets: Vec<id::Valid<id::QuerySetId>>,
+    pub(super) blas_s: Vec<id::Valid<id::BlasId>>,
+    pub(super) tlas_s: Vec<id::Valid<id::TlasId>>,
+    pub(super) heaps: Vec<Stored<id::HeapId>>,
+    pub(super) external_semaphores: Vec<id::Valid<id::ExternalSemaphoreId>>,
 }
 
 impl SuspectedResources {
@@ -49,7 +55,12 @@ impl SuspectedResources {
         self.bind_group_layouts.clear();
         self.pipeline_layouts.clear();
         self.render_bundles.clear();
+        self.compute_bundles.clear();
         self.query_sets.clear();
+        self.blas_s.clear();
+        self.tlas_s.clear();
+        self.heaps.clear();
+        self.external_semaphores.clear();
     }
 
     pub(super) fn extend(&mut self, other: &Self) {
@@ -67,7 +78,14 @@ impl SuspectedResources {
         self.pipeline_layouts
             .extend_from_slice(&other.pipeline_layouts);
         self.render_bundles.extend_from_slice(&other.render_bundles);
+        self.compute_bundles
+            .extend_from_slice(&other.compute_bundles);
         self.query_sets.extend_from_slice(&other.query_sets);
+        self.blas_s.extend_from_slice(&other.blas_s);
+        self.tlas_s.extend_from_slice(&other.tlas_s);
+        self.heaps.extend_from_slice(&other.heaps);
+        self.external_semaphores
+            .extend_from_slice(&other.external_semaphores);
     }
 
     pub(super) fn add_render_bundle_scope<A: HalApi>(&mut self, trackers: &RenderBundleScope<A>) {
@@ -79,11 +97,19 @@ impl SuspectedResources {
         self.query_sets.extend(trackers.query_sets.used());
     }
 
+    pub(super) fn add_compute_bundle_scope<A: HalApi>(&mut self, trackers: &ComputeBundleScope<A>) {
+        self.buffers.extend(trackers.buffers.used());
+        self.bind_groups.extend(trackers.bind_groups.used());
+        self.compute_pipelines
+            .extend(trackers.compute_pipelines.used());
+    }
+
     pub(super) fn add_bind_group_states<A: HalApi>(&mut self, trackers: &BindGroupStates<A>) {
         self.buffers.extend(trackers.buffers.used());
         self.textures.extend(trackers.textures.used());
         self.texture_views.extend(trackers.views.used());
         self.samplers.extend(trackers.samplers.used());
+        self.tlas_s.extend(trackers.acceleration_structures.used());
     }
 }
 
@@ -100,6 +126,12 @@ struct NonReferencedResources<A: hal::Api> {
     bind_group_layouts: Vec<A::BindGroupLayout>,
     pipeline_layouts: Vec<A::PipelineLayout>,
     query_sets: Vec<A::QuerySet>,
+    acceleration_structures: Vec<A::AccelerationStructure>,
+    /// Heaps are destroyed after the buffers and textures placed in them.
+    heaps: Vec<A::Heap>,
+    external_semaphores: Vec<A::ExternalSemaphore>,
+    /// Encoders of dropped reusable command buffers.
+    command_encoders: Vec<EncoderInFlight<A>>,
 }
 
 impl<A: hal::Api> NonReferencedResources<A> {
@@ -115,6 +147,10 @@ impl<A: hal::Api> NonReferencedResources<A> {
             bind_group_layouts: Vec::new(),
             pipeline_layouts: Vec::new(),
             query_sets: Vec::new(),
+            acceleration_structures: Vec::new(),
+            heaps: Vec::new(),
+            external_semaphores: Vec::new(),
+            command_encoders: Vec::new(),
         }
     }
 
@@ -127,6 +163,11 @@ impl<A: hal::Api> NonReferencedResources<A> {
         self.compute_pipes.extend(other.compute_pipes);
         self.render_pipes.extend(other.render_pipes);
         self.query_sets.extend(other.query_sets);
+        self.acceleration_structures
+            .extend(other.acceleration_structures);
+        self.heaps.extend(other.heaps);
+        self.external_semaphores.extend(other.external_semaphores);
+        self.command_encoders.extend(other.command_encoders);
         assert!(other.bind_group_layouts.is_empty());
         assert!(other.pipeline_layouts.is_empty());
     }
@@ -192,6 +233,33 @@ impl<A: hal::Api> NonReferencedResources<A> {
                 unsafe { device.destroy_query_set(raw) };
             }
         }
+        if !self.acceleration_structures.is_empty() {
+            profiling::scope!("destroy_acceleration_structures");
+            for raw in self.acceleration_structures.drain(..) {
+                unsafe { device.destroy_acceleration_structure(raw) };
+            }
+        }
+        if !self.heaps.is_empty() {
+            profiling::scope!("destroy_heaps");
+            for raw in self.heaps.drain(..) {
+                unsafe { device.destroy_heap(raw) };
+            }
+        }
+        if !self.external_semaphores.is_empty() {
+            profiling::scope!("destroy_external_semaphores");
+            for raw in self.external_semaphores.drain(..) {
+                unsafe { device.destroy_external_semaphore(raw) };
+            }
+        }
+        if !self.command_encoders.is_empty() {
+            profiling::scope!("destroy_command_encoders");
+            for encoder in self.command_encoders.drain(..) {
+                unsafe {
+                    let raw = encoder.land();
+                    device.destroy_command_encoder(raw);
+                }
+            }
+        }
     }
 }
 
@@ -349,6 +417,7 @@ impl<A: hal::Api> LifetimeTracker<A> {
                     last_resources.textures.push(raw);
                     last_resources.texture_views.extend(views);
                 }
+                TempResource::BindGroup(raw) => last_resources.bind_groups.push(raw),
             }
         }
 
@@ -452,6 +521,7 @@ impl<A: hal::Api> LifetimeTracker<A> {
                 resources.texture_views.extend(views);
                 resources.textures.push(raw);
             }
+            TempResource::BindGroup(raw) => resources.bind_groups.push(raw),
         }
     }
 
@@ -470,6 +540,27 @@ impl<A: hal::Api> LifetimeTracker<A> {
 }
 
 impl<A: HalApi> LifetimeTracker<A> {
+    /// Destroy a dropped reusable command buffer once its last submission
+    /// has completed.
+    pub fn schedule_command_buffer_destruction(
+        &mut self,
+        baked: BakedCommands<A>,
+        last_submit_index: SubmissionIndex,
+    ) {
+        for temp_resource in baked.temp_resources {
+            self.schedule_resource_destruction(temp_resource, last_submit_index);
+        }
+        let resources = self
+            .active
+            .iter_mut()
+            .find(|a| a.index == last_submit_index)
+            .map_or(&mut self.free_resources, |a| &mut a.last_resources);
+        resources.command_encoders.push(EncoderInFlight {
+            raw: baked.encoder,
+            cmd_buffers: baked.list,
+        });
+    }
+
     /// Identify resources to free, according to `trackers` and `self.suspected_resources`.
     ///
     /// Given `trackers`, the [`Tracker`] belonging to same [`Device`] as
@@ -537,6 +628,25 @@ impl<A: HalApi> LifetimeTracker<A> {
             }
         }
 
+        if !self.suspected_resources.compute_bundles.is_empty() {
+            let (mut guard, _) = hub.compute_bundles.write(token);
+            let mut trackers = trackers.lock();
+
+            while let Some(id) = self.suspected_resources.compute_bundles.pop() {
+                if trackers.compute_bundles.remove_abandoned(id) {
+                    log::debug!("Compute bundle {:?} will be destroyed", id);
+                    #[cfg(feature = "trace")]
+                    if let Some(t) = trace {
+                        t.lock().add(trace::Action::DestroyComputeBundle(id.0));
+                    }
+
+                    if let Some(res) = hub.compute_bundles.unregister_locked(id.0, &mut *guard) {
+                        self.suspected_resources.add_compute_bundle_scope(&res.used);
+                    }
+                }
+            }
+        }
+
         if !self.suspected_resources.bind_groups.is_empty() {
             let (mut guard, _) = hub.bind_groups.write(token);
             let mut trackers = trackers.lock();
@@ -609,6 +719,14 @@ impl<A: HalApi> LifetimeTracker<A> {
                     }
 
                     if let Some(res) = hub.textures.unregister_locked(id.0, &mut *guard) {
+                        self.suspected_resources
+                            .heaps
+                            .extend(res.heap.map(|placement| placement.heap_id));
+                        self.suspected_resources.heaps.extend(
+                            res.sparse
+                                .into_iter()
+                                .flat_map(resource::SparsePages::into_heaps),
+                        );
                         let submit_index = res.life_guard.life_count();
                         let raw = match res.inner {
                             resource::TextureInner::Native { raw: Some(raw) } => raw,
@@ -671,6 +789,14 @@ impl<A: HalApi> LifetimeTracker<A> {
                     }
 
                     if let Some(res) = hub.buffers.unregister_locked(id.0, &mut *guard) {
+                        self.suspected_resources
+                            .heaps
+                            .extend(res.heap.map(|placement| placement.heap_id));
+                        self.suspected_resources.heaps.extend(
+                            res.sparse
+                                .into_iter()
+                                .flat_map(resource::SparsePages::into_heaps),
+                        );
                         let submit_index = res.life_guard.life_count();
                         if let resource::BufferMapState::Init { stage_buffer, .. } = res.map_state {
                             self.free_resources.buffers.push(stage_buffer);
@@ -816,6 +942,108 @@ impl<A: HalApi> LifetimeTracker<A> {
                 }
             }
         }
+
+        if !self.suspected_resources.tlas_s.is_empty() {
+            let (mut guard, _) = hub.tlas_s.write(token);
+            let mut trackers = trackers.lock();
+
+            for id in self.suspected_resources.tlas_s.drain(..) {
+                if trackers.tlas_s.remove_abandoned(id) {
+                    log::debug!("Tlas {:?} will be destroyed", id);
+                    #[cfg(feature = "trace")]
+                    if let Some(t) = trace {
+                        t.lock().add(trace::Action::DestroyTlas(id.0));
+                    }
+
+                    if let Some(res) = hub.tlas_s.unregister_locked(id.0, &mut *guard) {
+                        let submit_index = res.life_guard.life_count();
+                        self.active
+                            .iter_mut()
+                            .find(|a| a.index == submit_index)
+                            .map_or(&mut self.free_resources, |a| &mut a.last_resources)
+                            .acceleration_structures
+                            .push(res.raw);
+                    }
+                }
+            }
+        }
+
+        if !self.suspected_resources.blas_s.is_empty() {
+            let (mut guard, _) = hub.blas_s.write(token);
+            let mut trackers = trackers.lock();
+
+            for id in self.suspected_resources.blas_s.drain(..) {
+                if trackers.blas_s.remove_abandoned(id) {
+                    log::debug!("Blas {:?} will be destroyed", id);
+                    #[cfg(feature = "trace")]
+                    if let Some(t) = trace {
+                        t.lock().add(trace::Action::DestroyBlas(id.0));
+                    }
+
+                    if let Some(res) = hub.blas_s.unregister_locked(id.0, &mut *guard) {
+                        let submit_index = res.life_guard.life_count();
+                        self.active
+                            .iter_mut()
+                            .find(|a| a.index == submit_index)
+                            .map_or(&mut self.free_resources, |a| &mut a.last_resources)
+                            .acceleration_structures
+                            .push(res.raw);
+                    }
+                }
+            }
+        }
+
+        if !self.suspected_resources.heaps.is_empty() {
+            let (mut guard, _) = hub.heaps.write(token);
+
+            for Stored {
+                value: id,
+                ref_count,
+            } in self.suspected_resources.heaps.drain(..)
+            {
+                //Note: this has to happen after all the suspected buffers and textures are destroyed
+                if ref_count.load() == 1 {
+                    log::debug!("Heap {:?} will be destroyed", id);
+                    #[cfg(feature = "trace")]
+                    if let Some(t) = trace {
+                        t.lock().add(trace::Action::DestroyHeap(id.0));
+                    }
+
+                    if let Some(res) = hub.heaps.unregister_locked(id.0, &mut *guard) {
+                        // The resources placed in the heap may still be in use
+                        // by any of the active submissions.
+                        self.active
+                            .last_mut()
+                            .map_or(&mut self.free_resources, |a| &mut a.last_resources)
+                            .heaps
+                            .push(res.raw);
+                    }
+                }
+            }
+        }
+
+        if !self.suspected_resources.external_semaphores.is_empty() {
+            let (mut guard, _) = hub.external_semaphores.write(token);
+
+            // Nothing but the user's handle refers to external semaphores.
+            for id in self.suspected_resources.external_semaphores.drain(..) {
+                log::debug!("External semaphore {:?} will be destroyed", id);
+                #[cfg(feature = "trace")]
+                if let Some(t) = trace {
+                    t.lock().add(trace::Action::DestroyExternalSemaphore(id.0));
+                }
+
+                if let Some(res) = hub.external_semaphores.unregister_locked(id.0, &mut *guard) {
+                    let submit_index = res.life_guard.life_count();
+                    self.active
+                        .iter_mut()
+                        .find(|a| a.index == submit_index)
+                        .map_or(&mut self.free_resources, |a| &mut a.last_resources)
+                        .external_semaphores
+                        .push(res.raw);
+                }
+            }
+        }
     }
 
     /// Determine which buffers are ready to map, and which must wait for the
@@ -13,13 +13,19 @@ use arrayvec::ArrayVec;
 use hal::Device as _;
 use smallvec::SmallVec;
 use thiserror::Error;
-use wgt::{BufferAddress, TextureFormat};
+use wgt::{BufferAddress, DeviceLostReason, TextureFormat};
 
-use std::{iter, num::NonZeroU32, ptr};
+use std::{
+    ffi::{c_char, CString},
+    iter,
+    num::NonZeroU32,
+    ptr,
+};
 
 pub mod global;
 mod life;
 pub mod queue;
+mod ray_tracing;
 pub mod resource;
 #[cfg(any(feature = "trace", feature = "replay"))]
 pub mod trace;
@@ -192,6 +198,102 @@ impl UserClosures {
     }
 }
 
+#[cfg(any(
+    not(target_arch = "wasm32"),
+    all(
+        feature = "fragile-send-sync-non-atomic-wasm",
+        not(target_feature = "atomics")
+    )
+))]
+pub type DeviceLostCallback = Box<dyn FnOnce(DeviceLostReason, String) + Send + 'static>;
+#[cfg(not(any(
+    not(target_arch = "wasm32"),
+    all(
+        feature = "fragile-send-sync-non-atomic-wasm",
+        not(target_feature = "atomics")
+    )
+)))]
+pub type DeviceLostCallback = Box<dyn FnOnce(DeviceLostReason, String) + 'static>;
+
+#[repr(C)]
+pub struct DeviceLostClosureC {
+    pub callback: unsafe extern "C" fn(user_data: *mut u8, reason: u8, message: *const c_char),
+    pub user_data: *mut u8,
+}
+
+#[cfg(any(
+    not(target_arch = "wasm32"),
+    all(
+        feature = "fragile-send-sync-non-atomic-wasm",
+        not(target_feature = "atomics")
+    )
+))]
+unsafe impl Send for DeviceLostClosureC {}
+
+/// Callback invoked once when a device is lost, see [`Global::device_set_device_lost_closure`].
+///
+/// [`Global::device_set_device_lost_closure`]: crate::global::Global::device_set_device_lost_closure
+pub struct DeviceLostClosure {
+    // We wrap this so creating the enum in the C variant can be unsafe,
+    // allowing our call function to be safe.
+    inner: DeviceLostClosureInner,
+}
+
+enum DeviceLostClosureInner {
+    Rust { callback: DeviceLostCallback },
+    C { inner: DeviceLostClosureC },
+}
+
+impl DeviceLostClosure {
+    pub fn from_rust(callback: DeviceLostCallback) -> Self {
+        Self {
+            inner: DeviceLostClosureInner::Rust { callback },
+        }
+    }
+
+    /// # Safety
+    ///
+    /// - The callback pointer must be valid to call with the provided `user_data`
+    ///   pointer.
+    ///
+    /// - Both pointers must point to `'static` data, as the callback may happen at
+    ///   an unspecified time.
+    ///
+    /// - The `message` pointer passed to the callback is only valid for the
+    ///   duration of the call.
+    pub unsafe fn from_c(inner: DeviceLostClosureC) -> Self {
+        Self {
+            inner: DeviceLostClosureInner::C { inner },
+        }
+    }
+
+    pub(crate) fn call(self, reason: DeviceLostReason, message: String) {
+        match self.inner {
+            DeviceLostClosureInner::Rust { callback } => callback(reason, message),
+            // SAFETY: the contract of the call to from_c says that this unsafe is sound.
+            DeviceLostClosureInner::C { inner } => unsafe {
+                // Interior nul bytes can't be represented, so drop the message
+                // rather than fail to notify the user at all.
+                let message = CString::new(message).unwrap_or_default();
+                (inner.callback)(inner.user_data, reason as u8, message.as_ptr())
+            },
+        }
+    }
+}
+
+/// A device lost callback that is ready to be called, along with its arguments.
+pub(crate) struct DeviceLostInvocation {
+    closure: DeviceLostClosure,
+    reason: DeviceLostReason,
+    message: String,
+}
+
+impl DeviceLostInvocation {
+    fn call(self) {
+        self.closure.call(self.reason, self.message);
+    }
+}
+
 fn map_buffer<A: hal::Api>(
     raw: &A::Device,
     buffer: &mut Buffer<A>,
@@ -266,7 +368,11 @@ impl<A: hal::Api> CommandAllocator<A> {
         match self.free_encoders.pop() {
             Some(encoder) => Ok(encoder),
             None => unsafe {
-                let hal_desc = hal::CommandEncoderDescriptor { label: None, queue };
+                let hal_desc = hal::CommandEncoderDescriptor {
+                    label: None,
+                    queue,
+                    reusable: false,
+                };
                 device.create_command_encoder(&hal_desc)
             },
         }
@@ -2,20 +2,29 @@
 use crate::device::trace::Action;
 use crate::{
     command::{
-        extract_texture_selector, validate_linear_texture_data, validate_texture_copy_range,
-        ClearError, CommandBuffer, CopySide, ImageCopyTexture, TransferError,
+        encode_query_resolves, extract_texture_selector, validate_linear_texture_data,
+        validate_texture_copy_range, ClearError, CopySide, ImageCopyTexture, TransferError,
     },
     conv,
-    device::{DeviceError, WaitIdleError},
+    device::{DeviceError, MissingFeatures, WaitIdleError},
     get_lowest_common_denom,
     global::Global,
     hal_api::HalApi,
     hub::Token,
     id,
     identity::{GlobalIdentityHandlerFactory, Input},
-    init_tracker::{has_copy_partial_init_tracker_coverage, TextureInitRange},
-    resource::{BufferAccessError, BufferMapState, StagingBuffer, TextureInner},
-    track, FastHashSet, SubmissionIndex,
+    init_tracker::{
+        has_copy_partial_init_tracker_coverage, BufferInitTracker, TextureInitRange,
+        TextureInitTracker,
+    },
+    ray_tracing::TlasActionKind,
+    resource::{
+        sparse_page_base, sparse_tiles, Buffer, BufferAccessError, BufferMapState, Heap,
+        HeapResource, StagingBuffer, Texture, TextureInner,
+    },
+    storage::Storage,
+    track::{self, Tracker},
+    FastHashSet, Stored, SubmissionIndex,
 };
 
 use hal::{CommandEncoder as _, Device as _, Queue as _};
@@ -129,12 +138,14 @@ pub struct WrappedSubmissionIndex {
 pub enum TempResource<A: hal::Api> {
     Buffer(A::Buffer),
     Texture(A::Texture, SmallVec<[A::TextureView; 1]>),
+    BindGroup(A::BindGroup),
 }
 
 /// A queue execution for a particular command encoder.
+#[derive(Debug)]
 pub(super) struct EncoderInFlight<A: hal::Api> {
-    raw: A::CommandEncoder,
-    cmd_buffers: Vec<A::CommandBuffer>,
+    pub(super) raw: A::CommandEncoder,
+    pub(super) cmd_buffers: Vec<A::CommandBuffer>,
 }
 
 impl<A: hal::Api> EncoderInFlight<A> {
@@ -203,6 +214,9 @@ impl<A: hal::Api> PendingWrites<A> {
                     }
                     device.destroy_texture(texture);
                 },
+                TempResource::BindGroup(bind_group) => unsafe {
+                    device.destroy_bind_group(bind_group);
+                },
             }
         }
     }
@@ -272,8 +286,8 @@ impl<A: hal::Api> PendingWrites<A> {
     }
 }
 
-fn prepare_staging_buffer<A: HalApi>(
-    device: &mut A::Device,
+pub(crate) fn prepare_staging_buffer<A: HalApi>(
+    device: &A::Device,
     size: wgt::BufferAddress,
 ) -> Result<(StagingBuffer<A>, *mut u8), DeviceError> {
     profiling::scope!("prepare_staging_buffer");
@@ -296,8 +310,80 @@ fn prepare_staging_buffer<A: HalApi>(
     Ok((staging_buffer, mapping.ptr.as_ptr()))
 }
 
+/// Make the placed buffers and textures among `buffers` and `textures`
+/// resident in their heaps, in order.
+///
+/// The resources they evict lose their contents, so their initialization
+/// status is reset. If `trackers` is given, a texture that evicts anything
+/// also starts over from [`hal::TextureUses::UNINITIALIZED`], since the
+/// layout it was left in is gone as well.
+fn make_resident<A: HalApi>(
+    heap_guard: &mut Storage<Heap<A>, id::HeapId>,
+    buffer_guard: &mut Storage<Buffer<A>, id::BufferId>,
+    texture_guard: &mut Storage<Texture<A>, id::TextureId>,
+    mut trackers: Option<&mut Tracker<A>>,
+    buffers: impl Iterator<Item = id::BufferId>,
+    textures: impl Iterator<Item = id::TextureId>,
+) {
+    let placed_buffers = buffers.filter_map(|id| {
+        let placement = buffer_guard.get(id).ok()?.heap.as_ref()?;
+        Some((
+            placement.heap_id.value,
+            placement.range.clone(),
+            HeapResource::Buffer(id),
+        ))
+    });
+    let placed_textures = textures.filter_map(|id| {
+        let placement = texture_guard.get(id).ok()?.heap.as_ref()?;
+        Some((
+            placement.heap_id.value,
+            placement.range.clone(),
+            HeapResource::Texture(id),
+        ))
+    });
+    let placed = placed_buffers.chain(placed_textures).collect::<Vec<_>>();
+
+    for (heap_id, range, resource) in placed {
+        let evicted = heap_guard[heap_id].make_resident(resource, &range);
+        if evicted.is_empty() {
+            continue;
+        }
+
+        // Evicted resources may have been dropped since they were last used.
+        for evicted in evicted {
+            match evicted {
+                HeapResource::Buffer(id) if buffer_guard.contains(id) => {
+                    if let Ok(buffer) = buffer_guard.get_mut(id) {
+                        log::trace!("Buffer {:?} was evicted from its heap", id);
+                        buffer.initialization_status = BufferInitTracker::new(buffer.size);
+                    }
+                }
+                HeapResource::Texture(id) if texture_guard.contains(id) => {
+                    if let Ok(texture) = texture_guard.get_mut(id) {
+                        log::trace!("Texture {:?} was evicted from its heap", id);
+                        texture.initialization_status = TextureInitTracker::new(
+                            texture.desc.mip_level_count,
+                            texture.desc.array_layer_count(),
+                        );
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        if let (HeapResource::Texture(id), Some(trackers)) = (resource, trackers.as_deref_mut()) {
+            let id = id::Valid(id);
+            let ref_count = unsafe { trackers.textures.get_ref_count(id) }.clone();
+            trackers.textures.remove(id);
+            trackers
+                .textures
+                .insert_single(id.0, ref_count, hal::TextureUses::UNINITIALIZED);
+        }
+    }
+}
+
 impl<A: hal::Api> StagingBuffer<A> {
-    unsafe fn flush(&self, device: &A::Device) -> Result<(), DeviceError> {
+    pub(crate) unsafe fn flush(&self, device: &A::Device) -> Result<(), DeviceError> {
         if !self.is_coherent {
             unsafe { device.flush_mapped_ranges(&self.raw, iter::once(0..self.size)) };
         }
@@ -340,6 +426,125 @@ pub enum QueueSubmitError {
     SurfaceUnconfigured,
     #[error("GPU got stuck :(")]
     StuckGpu,
+    #[error("Top level acceleration structure {0:?} is used before it was built")]
+    UnbuiltTlas(id::TlasId),
+    #[error("Sparse buffer {0:?} is used while some of its pages are unbound")]
+    UnboundSparseBuffer(id::BufferId),
+    #[error(transparent)]
+    MissingFeatures(#[from] MissingFeatures),
+    #[error("External semaphore {0:?} is invalid")]
+    InvalidExternalSemaphore(id::ExternalSemaphoreId),
+}
+
+#[derive(Clone, Debug, Error)]
+#[non_exhaustive]
+pub enum SparseBindingError {
+    #[error(transparent)]
+    Queue(#[from] DeviceError),
+    #[error(transparent)]
+    Submit(#[from] QueueSubmitError),
+    #[error(transparent)]
+    MissingFeatures(#[from] MissingFeatures),
+    #[error("Buffer {0:?} is invalid or destroyed")]
+    InvalidBuffer(id::BufferId),
+    #[error("Texture {0:?} is invalid or destroyed")]
+    InvalidTexture(id::TextureId),
+    #[error("Heap {0:?} is invalid")]
+    InvalidHeap(id::HeapId),
+    #[error("Buffer {0:?} is not sparse")]
+    NotSparseBuffer(id::BufferId),
+    #[error("Texture {0:?} is not sparse")]
+    NotSparseTexture(id::TextureId),
+    #[error("Offset {0} must be a multiple of the sparse page size")]
+    UnalignedOffset(wgt::BufferAddress),
+    #[error("Size {0} must be a multiple of the sparse page size")]
+    UnalignedSize(wgt::BufferAddress),
+    #[error(
+        "{size} bytes at offset {offset} are past the end of the buffer ({buffer_size} bytes)"
+    )]
+    BufferOutOfBounds {
+        offset: wgt::BufferAddress,
+        size: wgt::BufferAddress,
+        buffer_size: wgt::BufferAddress,
+    },
+    #[error("Mip level {mip_level} is past the end of texture {texture:?}")]
+    InvalidMipLevel {
+        texture: id::TextureId,
+        mip_level: u32,
+    },
+    #[error("Region at {origin:?} of size {size:?} is not made of whole {tile_width}x{tile_height} tiles")]
+    UnalignedRegion {
+        origin: wgt::Origin3d,
+        size: wgt::Extent3d,
+        tile_width: u32,
+        tile_height: u32,
+    },
+    #[error(
+        "Region at {origin:?} of size {size:?} is past the end of the mip level ({mip_size:?})"
+    )]
+    RegionOutOfBounds {
+        origin: wgt::Origin3d,
+        size: wgt::Extent3d,
+        mip_size: wgt::Extent3d,
+    },
+    #[error("{size} bytes at offset {offset} are past the end of the heap ({heap_size} bytes)")]
+    HeapOutOfBounds {
+        offset: wgt::BufferAddress,
+        size: wgt::BufferAddress,
+        heap_size: wgt::BufferAddress,
+    },
+}
+
+/// Check that `page_count` pages of `memory` are in the heap it refers to.
+fn validate_sparse_memory<'a, A: HalApi>(
+    heap_guard: &'a Storage<Heap<A>, id::HeapId>,
+    device_id: id::DeviceId,
+    memory: &Option<wgt::SparseMemory<id::HeapId>>,
+    page_count: u64,
+) -> Result<Option<&'a Heap<A>>, SparseBindingError> {
+    let memory = match *memory {
+        Some(ref memory) => memory,
+        None => return Ok(None),
+    };
+    let heap = match heap_guard.get(memory.heap) {
+        Ok(heap) if heap.device_id.value.0 == device_id => heap,
+        _ => return Err(SparseBindingError::InvalidHeap(memory.heap)),
+    };
+    if memory.offset % wgt::SPARSE_PAGE_SIZE != 0 {
+        return Err(SparseBindingError::UnalignedOffset(memory.offset));
+    }
+    let size = page_count * wgt::SPARSE_PAGE_SIZE;
+    if memory.offset + size > heap.size {
+        return Err(SparseBindingError::HeapOutOfBounds {
+            offset: memory.offset,
+            size,
+            heap_size: heap.size,
+        });
+    }
+    Ok(Some(heap))
+}
+
+/// Bind the pages of `pages` in `range` to consecutive pages of `memory`, or
+/// unbind them.
+///
+/// The heaps the pages were bound to until now are added to `replaced`.
+fn bind_sparse_pages(
+    pages: &mut [Option<Stored<id::HeapId>>],
+    range: std::ops::Range<usize>,
+    memory: Option<(id::HeapId, &crate::LifeGuard)>,
+    replaced: &mut Vec<Stored<id::HeapId>>,
+) {
+    for page in pages[range].iter_mut() {
+        let new = memory.map(|(heap_id, life_guard)| Stored {
+            value: id::Valid(heap_id),
+            ref_count: life_guard.add_ref(),
+        });
+        if let Some(old) = mem::replace(page, new) {
+            if replaced.iter().all(|heap| heap.value != old.value) {
+                replaced.push(old);
+            }
+        }
+    }
 }
 
 //TODO: move out common parts of write_xxx.
@@ -384,8 +589,7 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
         // Platform validation requires that the staging buffer always be
         // freed, even if an error occurs. All paths from here must call
         // `device.pending_writes.consume`.
-        let (staging_buffer, staging_buffer_ptr) =
-            prepare_staging_buffer(&mut device.raw, data_size)?;
+        let (staging_buffer, staging_buffer_ptr) = prepare_staging_buffer(&device.raw, data_size)?;
 
         if let Err(flush_error) = unsafe {
             profiling::scope!("copy");
@@ -403,6 +607,7 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
             &staging_buffer,
             buffer_id,
             buffer_offset,
+            Some(data),
         );
 
         device.pending_writes.consume(staging_buffer);
@@ -425,7 +630,7 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
             .map_err(|_| DeviceError::Invalid)?;
 
         let (staging_buffer, staging_buffer_ptr) =
-            prepare_staging_buffer(&mut device.raw, buffer_size.get())?;
+            prepare_staging_buffer(&device.raw, buffer_size.get())?;
 
         let fid = hub.staging_buffers.prepare(id_in);
         let id = fid.assign(staging_buffer, device_token);
@@ -471,6 +676,7 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
             &staging_buffer,
             buffer_id,
             buffer_offset,
+            None,
         );
 
         device.pending_writes.consume(staging_buffer);
@@ -502,7 +708,7 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
 
     fn queue_validate_write_buffer_impl<A: HalApi>(
         &self,
-        buffer: &crate::resource::Buffer<A>,
+        buffer: &Buffer<A>,
         buffer_id: id::BufferId,
         buffer_offset: u64,
         buffer_size: u64,
@@ -539,6 +745,7 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
         staging_buffer: &StagingBuffer<A>,
         buffer_id: id::BufferId,
         buffer_offset: u64,
+        data: Option<&[u8]>,
     ) -> Result<(), QueueWriteError> {
         let hub = A::hub(self);
 
@@ -590,6 +797,14 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
             let dst = buffer_guard.get_mut(buffer_id).unwrap();
             dst.initialization_status
                 .drain(buffer_offset..(buffer_offset + src_buffer_size));
+
+            if let Some(ref mut contents) = dst.index_contents {
+                match data {
+                    Some(data) => contents.get_mut().write(buffer_offset, data),
+                    // The contents of the staging buffer are only known to the user.
+                    None => contents.get_mut().invalidate(),
+                }
+            }
         }
 
         Ok(())
@@ -778,8 +993,7 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
         // Platform validation requires that the staging buffer always be
         // freed, even if an error occurs. All paths from here must call
         // `device.pending_writes.consume`.
-        let (staging_buffer, staging_buffer_ptr) =
-            prepare_staging_buffer(&mut device.raw, stage_size)?;
+        let (staging_buffer, staging_buffer_ptr) = prepare_staging_buffer(&device.raw, stage_size)?;
 
         if stage_bytes_per_row == bytes_per_row {
             profiling::scope!("copy aligned");
@@ -1060,6 +1274,25 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
         &self,
         queue_id: id::QueueId,
         command_buffer_ids: &[id::CommandBufferId],
+    ) -> Result<WrappedSubmissionIndex, QueueSubmitError> {
+        self.queue_submit_with_sync::<A>(queue_id, command_buffer_ids, &[], &[])
+    }
+
+    /// Submit command buffers like [`Global::queue_submit`], synchronized
+    /// with external semaphores.
+    ///
+    /// The submission executes once each semaphore in `waits` reaches at least
+    /// the value it's paired with, and each semaphore in `signals` is set to
+    /// its value once the submission completes.
+    ///
+    /// Traces record the submission without its waits and signals, since
+    /// nothing outside of a replay would signal them.
+    pub fn queue_submit_with_sync<A: HalApi>(
+        &self,
+        queue_id: id::QueueId,
+        command_buffer_ids: &[id::CommandBufferId],
+        waits: &[(id::ExternalSemaphoreId, u64)],
+        signals: &[(id::ExternalSemaphoreId, u64)],
     ) -> Result<WrappedSubmissionIndex, QueueSubmitError> {
         profiling::scope!("Queue::submit");
         log::trace!("Queue::submit {queue_id:?}");
@@ -1072,12 +1305,43 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
             let device = device_guard
                 .get_mut(queue_id)
                 .map_err(|_| DeviceError::Invalid)?;
+            if !waits.is_empty() || !signals.is_empty() {
+                device.require_features(wgt::Features::EXTERNAL_SEMAPHORES)?;
+                let (semaphore_guard, _) = hub.external_semaphores.read(&mut token);
+                for &(id, _) in waits.iter().chain(signals) {
+                    match semaphore_guard.get(id) {
+                        Ok(semaphore) if semaphore.device_id.value.0 == queue_id => {}
+                        Ok(_) => return Err(DeviceError::WrongDevice.into()),
+                        Err(_) => return Err(QueueSubmitError::InvalidExternalSemaphore(id)),
+                    }
+                }
+            }
             device.temp_suspected.clear();
             device.active_submission_index += 1;
             let submit_index = device.active_submission_index;
             let mut active_executions = Vec::new();
+            // For every entry of `active_executions`, the reusable command
+            // buffer whose commands go between its transit and present ones,
+            // with the positions of its query resolves among them.
+            let mut reused_command_buffers = Vec::new();
+            let mut submit_temp_resources = Vec::new();
             let mut used_surface_textures = track::TextureUsageScope::new();
 
+            // The pending writes are executed before the command buffers.
+            if device.features.contains(wgt::Features::RESOURCE_HEAPS) {
+                let (mut buffer_guard, mut token) = hub.buffers.write(&mut token);
+                let (mut texture_guard, mut token) = hub.textures.write(&mut token);
+                let (mut heap_guard, _) = hub.heaps.write(&mut token);
+                make_resident(
+                    &mut heap_guard,
+                    &mut buffer_guard,
+                    &mut texture_guard,
+                    None,
+                    device.pending_writes.dst_buffers.iter().copied(),
+                    device.pending_writes.dst_textures.iter().copied(),
+                );
+            }
+
             {
                 let (mut command_buffer_guard, mut token) = hub.command_buffers.write(&mut token);
 
@@ -1085,6 +1349,7 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
                     profiling::scope!("prepare");
 
                     let (render_bundle_guard, mut token) = hub.render_bundles.read(&mut token);
+                    let (compute_bundle_guard, mut token) = hub.compute_bundles.read(&mut token);
                     let (_, mut token) = hub.pipeline_layouts.read(&mut token);
                     let (bind_group_guard, mut token) = hub.bind_groups.read(&mut token);
                     let (compute_pipe_guard, mut token) = hub.compute_pipelines.read(&mut token);
@@ -1093,7 +1358,10 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
                     let (mut texture_guard, mut token) = hub.textures.write(&mut token);
                     let (texture_view_guard, mut token) = hub.texture_views.read(&mut token);
                     let (sampler_guard, mut token) = hub.samplers.read(&mut token);
-                    let (query_set_guard, _) = hub.query_sets.read(&mut token);
+                    let (query_set_guard, mut token) = hub.query_sets.read(&mut token);
+                    let (mut blas_guard, mut token) = hub.blas_s.write(&mut token);
+                    let (mut tlas_guard, mut token) = hub.tlas_s.write(&mut token);
+                    let (mut heap_guard, _) = hub.heaps.write(&mut token);
 
                     //Note: locking the trackers has to be done after the storages
                     let mut trackers = device.trackers.lock();
@@ -1108,13 +1376,15 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
                         // it, so make sure to set_size on it.
                         used_surface_textures.set_size(texture_guard.len());
 
-                        #[allow(unused_mut)]
-                        let mut cmdbuf = match hub
-                            .command_buffers
-                            .unregister_locked(cmb_id, &mut *command_buffer_guard)
-                        {
-                            Some(cmdbuf) => cmdbuf,
-                            None => continue,
+                        // Reusable command buffers stay registered until they
+                        // are dropped, everything else is consumed here.
+                        let cmdbuf = match command_buffer_guard.get_mut(cmb_id) {
+                            Ok(cmdbuf) => cmdbuf,
+                            Err(_) => {
+                                hub.command_buffers
+                                    .unregister_locked(cmb_id, &mut *command_buffer_guard);
+                                continue;
+                            }
                         };
 
                         if cmdbuf.device_id.value.0 != queue_id {
@@ -1123,13 +1393,23 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
 
                         #[cfg(feature = "trace")]
                         if let Some(ref trace) = device.trace {
-                            trace.lock().add(Action::Submit(
-                                submit_index,
-                                cmdbuf.commands.take().unwrap(),
-                            ));
+                            let commands = if cmdbuf.reusable {
+                                cmdbuf.commands.clone()
+                            } else {
+                                cmdbuf.commands.take()
+                            };
+                            trace
+                                .lock()
+                                .add(Action::Submit(submit_index, commands.unwrap()));
                         }
                         if !cmdbuf.is_finished() {
-                            device.destroy_command_buffer(cmdbuf);
+                            if !cmdbuf.reusable {
+                                let cmdbuf = hub
+                                    .command_buffers
+                                    .unregister_locked(cmb_id, &mut *command_buffer_guard)
+                                    .unwrap();
+                                device.destroy_command_buffer(cmdbuf);
+                            }
                             continue;
                         }
 
@@ -1145,6 +1425,11 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
                                     return Err(QueueSubmitError::DestroyedBuffer(id.0));
                                 }
                             };
+                            if let Some(ref sparse) = buffer.sparse {
+                                if !sparse.residency && !sparse.is_fully_bound() {
+                                    return Err(QueueSubmitError::UnboundSparseBuffer(id.0));
+                                }
+                            }
                             if !buffer.life_guard.use_at(submit_index) {
                                 if let BufferMapState::Active { .. } = buffer.map_state {
                                     log::warn!("Dropped buffer has a pending mapping.");
@@ -1210,6 +1495,9 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
                             for sub_id in bg.used.samplers.used() {
                                 sampler_guard[sub_id].life_guard.use_at(submit_index);
                             }
+                            for sub_id in bg.used.acceleration_structures.used() {
+                                tlas_guard[sub_id].life_guard.use_at(submit_index);
+                            }
                         }
                         // assert!(cmdbuf.trackers.samplers.is_empty());
                         for id in cmdbuf.trackers.compute_pipelines.used() {
@@ -1242,42 +1530,142 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
                                 query_set_guard[sub_id].life_guard.use_at(submit_index);
                             }
                         }
+                        // The bind groups and pipelines of compute bundles are
+                        // tracked by the command buffer directly.
+                        for id in cmdbuf.trackers.compute_bundles.used() {
+                            if !compute_bundle_guard[id].life_guard.use_at(submit_index) {
+                                device.temp_suspected.compute_bundles.push(id);
+                            }
+                        }
+                        for id in cmdbuf.trackers.blas_s.used() {
+                            if !blas_guard[id].life_guard.use_at(submit_index) {
+                                device.temp_suspected.blas_s.push(id);
+                            }
+                        }
+                        for id in cmdbuf.trackers.tlas_s.used() {
+                            if !tlas_guard[id].life_guard.use_at(submit_index) {
+                                device.temp_suspected.tlas_s.push(id);
+                            }
+                        }
 
-                        let mut baked = cmdbuf.into_baked();
-                        // execute resource transitions
-                        unsafe {
-                            baked
-                                .encoder
-                                .begin_encoding(Some("(wgpu internal) Transit"))
-                                .map_err(DeviceError::from)?
+                        // Acceleration structures built by this command buffer
+                        // can be used by it, and by any later submission.
+                        for &id in cmdbuf.blas_builds.iter() {
+                            blas_guard[id::Valid(id)].built_index = Some(submit_index);
+                        }
+                        let mut unbuilt_tlas = None;
+                        for action in cmdbuf.tlas_actions.iter() {
+                            let tlas = &mut tlas_guard[id::Valid(action.id)];
+                            match action.kind {
+                                TlasActionKind::Build => tlas.built_index = Some(submit_index),
+                                TlasActionKind::Use => {
+                                    if tlas.built_index.is_none() {
+                                        unbuilt_tlas = Some(action.id);
+                                        break;
+                                    }
+                                }
+                            }
+                        }
+                        if let Some(id) = unbuilt_tlas {
+                            if !cmdbuf.reusable {
+                                let cmdbuf = hub
+                                    .command_buffers
+                                    .unregister_locked(cmb_id, &mut *command_buffer_guard)
+                                    .unwrap();
+                                device.destroy_command_buffer(cmdbuf);
+                            }
+                            return Err(QueueSubmitError::UnbuiltTlas(id));
+                        }
+
+                        // Resources aliasing the ones used by the command buffer
+                        // lose their contents.
+                        if device.features.contains(wgt::Features::RESOURCE_HEAPS) {
+                            make_resident(
+                                &mut heap_guard,
+                                &mut buffer_guard,
+                                &mut texture_guard,
+                                Some(&mut *trackers),
+                                cmdbuf.trackers.buffers.used().map(|id| id.0),
+                                cmdbuf.trackers.textures.used().map(|id| id.0),
+                            );
+                        }
+
+                        // Reusable command buffers resolve their queries again on
+                        // every submission.
+                        let query_init_actions = if cmdbuf.reusable {
+                            cmdbuf.query_init_actions.clone()
+                        } else {
+                            mem::take(&mut cmdbuf.query_init_actions)
                         };
+
+                        // execute resource transitions
                         log::trace!("Stitching command buffer {:?} before submission", cmb_id);
-                        baked
-                            .initialize_buffer_memory(&mut *trackers, &mut *buffer_guard)
-                            .map_err(|err| QueueSubmitError::DestroyedBuffer(err.0))?;
-                        baked
-                            .initialize_texture_memory(&mut *trackers, &mut *texture_guard, device)
-                            .map_err(|err| QueueSubmitError::DestroyedTexture(err.0))?;
-                        //Note: stateless trackers are not merged:
-                        // device already knows these resources exist.
-                        CommandBuffer::insert_barriers_from_tracker(
-                            &mut baked.encoder,
-                            &mut *trackers,
-                            &baked.trackers,
-                            &*buffer_guard,
-                            &*texture_guard,
-                        );
+                        let (mut encoder, mut cmd_buffers, reused) = if cmdbuf.reusable {
+                            // The recorded commands are kept for the next submission,
+                            // so the transitions get an encoder of their own.
+                            let mut encoder = device
+                                .command_allocator
+                                .lock()
+                                .acquire_encoder(&device.raw, &device.queue)
+                                .map_err(DeviceError::from)?;
+                            let transit = cmdbuf.encode_transit(
+                                Some(&mut encoder),
+                                &mut trackers,
+                                &mut buffer_guard,
+                                &mut texture_guard,
+                                device,
+                            )?;
+                            cmdbuf.last_submission_index = Some(submit_index);
+                            (encoder, vec![transit], Some(cmb_id))
+                        } else {
+                            let transit = cmdbuf.encode_transit(
+                                None,
+                                &mut trackers,
+                                &mut buffer_guard,
+                                &mut texture_guard,
+                                device,
+                            )?;
+                            let mut baked = hub
+                                .command_buffers
+                                .unregister_locked(cmb_id, &mut *command_buffer_guard)
+                                .unwrap()
+                                .into_baked();
+                            submit_temp_resources.append(&mut baked.temp_resources);
+                            baked.list.insert(0, transit);
+                            (baked.encoder, baked.list, None)
+                        };
 
-                        let transit = unsafe { baked.encoder.end_encoding().unwrap() };
-                        baked.list.insert(0, transit);
+                        // The queries written by the previous command buffers are
+                        // known by now, so the query resolves can be encoded.
+                        let resolves = encode_query_resolves(
+                            &mut encoder,
+                            &query_init_actions,
+                            &query_set_guard,
+                            &buffer_guard,
+                        )?;
+                        let reused = match reused {
+                            // The resolves go after the transit commands, and get
+                            // stitched between the recorded ones at submission.
+                            Some(id) => {
+                                let (positions, raws): (Vec<_>, Vec<_>) =
+                                    resolves.into_iter().unzip();
+                                cmd_buffers.extend(raws);
+                                Some((id, positions))
+                            }
+                            None => {
+                                for (position, raw) in resolves.into_iter().rev() {
+                                    cmd_buffers.insert(1 + position, raw);
+                                }
+                                None
+                            }
+                        };
 
                         // Transition surface textures into `Present` state.
                         // Note: we could technically do it after all of the command buffers,
                         // but here we have a command encoder by hand, so it's easier to use it.
                         if !used_surface_textures.is_empty() {
                             unsafe {
-                                baked
-                                    .encoder
+                                encoder
                                     .begin_encoding(Some("(wgpu internal) Present"))
                                     .map_err(DeviceError::from)?
                             };
@@ -1289,18 +1677,19 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
                                 pending.into_hal(tex)
                             });
                             let present = unsafe {
-                                baked.encoder.transition_textures(texture_barriers);
-                                baked.encoder.end_encoding().unwrap()
+                                encoder.transition_textures(texture_barriers);
+                                encoder.end_encoding().unwrap()
                             };
-                            baked.list.push(present);
+                            cmd_buffers.push(present);
                             used_surface_textures = track::TextureUsageScope::new();
                         }
 
                         // done
                         active_executions.push(EncoderInFlight {
-                            raw: baked.encoder,
-                            cmd_buffers: baked.list,
+                            raw: encoder,
+                            cmd_buffers,
                         });
+                        reused_command_buffers.push(reused);
                     }
 
                     log::trace!("Device after submission {}", submit_index);
@@ -1322,9 +1711,18 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
                     //
                     // 2) It's doing the extra locking unconditionally. Maybe we
                     //    can only do so if any surfaces are being written to?
-                    let (_, mut token) = hub.buffers.read(&mut token); // skip token
+                    let (buffer_guard, mut token) = hub.buffers.read(&mut token);
                     let (mut texture_guard, _) = hub.textures.write(&mut token);
 
+                    for &id in pending_writes.dst_buffers.iter() {
+                        let sparse = buffer_guard.get(id).ok().and_then(|b| b.sparse.as_ref());
+                        if let Some(sparse) = sparse {
+                            if !sparse.residency && !sparse.is_fully_bound() {
+                                return Err(QueueSubmitError::UnboundSparseBuffer(id));
+                            }
+                        }
+                    }
+
                     used_surface_textures.set_size(texture_guard.len());
 
                     for &id in pending_writes.dst_textures.iter() {
@@ -1379,12 +1777,54 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
                     .chain(
                         active_executions
                             .iter()
-                            .flat_map(|pool_execution| pool_execution.cmd_buffers.iter()),
+                            .zip(reused_command_buffers.iter())
+                            .flat_map(|(pool_execution, reused)| {
+                                let (transit, rest) =
+                                    pool_execution.cmd_buffers.split_first().unwrap();
+                                let mut refs = vec![transit];
+                                match *reused {
+                                    Some((id, ref positions)) => {
+                                        let recorded = command_buffer_guard
+                                            .get(id)
+                                            .unwrap()
+                                            .raw_command_buffers();
+                                        let (resolves, rest) = rest.split_at(positions.len());
+                                        let mut next = 0;
+                                        for (&position, resolve) in positions.iter().zip(resolves) {
+                                            refs.extend(&recorded[next..position]);
+                                            refs.push(resolve);
+                                            next = position;
+                                        }
+                                        refs.extend(&recorded[next..]);
+                                        refs.extend(rest);
+                                    }
+                                    None => refs.extend(rest),
+                                }
+                                refs
+                            }),
                     )
                     .collect::<Vec<_>>();
+
+                let (semaphore_guard, _) = hub.external_semaphores.read(&mut token);
+                let hal_semaphores = |sync: &[(id::ExternalSemaphoreId, u64)]| {
+                    sync.iter()
+                        .map(|&(id, value)| {
+                            let semaphore = &semaphore_guard[id::Valid(id)];
+                            semaphore.life_guard.use_at(submit_index);
+                            (&semaphore.raw, value)
+                        })
+                        .collect::<Vec<_>>()
+                };
+                let hal_waits = hal_semaphores(waits);
+                let hal_signals = hal_semaphores(signals);
                 unsafe {
                     queue
-                        .submit(&refs, Some((fence, submit_index)))
+                        .submit_with_sync(
+                            &refs,
+                            &hal_waits,
+                            &hal_signals,
+                            Some((fence, submit_index)),
+                        )
                         .map_err(DeviceError::from)?;
                 }
             }
@@ -1402,7 +1842,9 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
             let mut pending_write_resources = mem::take(&mut device.pending_writes.temp_resources);
             device.lock_life(&mut token).track_submission(
                 submit_index,
-                pending_write_resources.drain(..),
+                pending_write_resources
+                    .drain(..)
+                    .chain(submit_temp_resources),
                 active_executions,
             );
 
@@ -1433,6 +1875,350 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
         })
     }
 
+    /// Bind heap memory to pages of sparse buffers and tiles of sparse
+    /// textures, or unbind it.
+    ///
+    /// The bindings change after all the work submitted so far, including
+    /// the pending writes, and before any work submitted later.
+    pub fn queue_update_sparse_bindings<A: HalApi>(
+        &self,
+        queue_id: id::QueueId,
+        buffer_bindings: &[wgt::SparseBufferBinding<id::BufferId, id::HeapId>],
+        texture_bindings: &[wgt::SparseTextureBinding<id::TextureId, id::HeapId>],
+    ) -> Result<(), SparseBindingError> {
+        profiling::scope!("Queue::update_sparse_bindings");
+        log::trace!("Queue::update_sparse_bindings {queue_id:?}");
+
+        // The writes queued so far have to see the old bindings.
+        self.queue_submit::<A>(queue_id, &[])?;
+
+        let hub = A::hub(self);
+        let mut token = Token::root();
+        let (mut device_guard, mut token) = hub.devices.write(&mut token);
+        let device = device_guard
+            .get_mut(queue_id)
+            .map_err(|_| DeviceError::Invalid)?;
+        device.require_features(wgt::Features::SPARSE_BINDING)?;
+
+        #[cfg(feature = "trace")]
+        if let Some(ref trace) = device.trace {
+            trace.lock().add(Action::UpdateSparseBindings {
+                buffer_bindings: buffer_bindings.to_vec(),
+                texture_bindings: texture_bindings.to_vec(),
+            });
+        }
+
+        let replaced_heaps = {
+            let (mut buffer_guard, mut token) = hub.buffers.write(&mut token);
+            let (mut texture_guard, mut token) = hub.textures.write(&mut token);
+            let (heap_guard, _) = hub.heaps.read(&mut token);
+
+            // Validate everything before binding anything.
+            let mut hal_buffer_bindings = Vec::with_capacity(buffer_bindings.len());
+            let mut buffer_pages = Vec::with_capacity(buffer_bindings.len());
+            for binding in buffer_bindings {
+                let buffer = buffer_guard
+                    .get(binding.buffer)
+                    .map_err(|_| SparseBindingError::InvalidBuffer(binding.buffer))?;
+                if buffer.device_id.value.0 != queue_id {
+                    return Err(DeviceError::WrongDevice.into());
+                }
+                let raw = buffer
+                    .raw
+                    .as_ref()
+                    .ok_or(SparseBindingError::InvalidBuffer(binding.buffer))?;
+                if buffer.sparse.is_none() {
+                    return Err(SparseBindingError::NotSparseBuffer(binding.buffer));
+                }
+                if binding.offset % wgt::SPARSE_PAGE_SIZE != 0 {
+                    return Err(SparseBindingError::UnalignedOffset(binding.offset));
+                }
+                let end = binding.offset + binding.size;
+                if end > buffer.size {
+                    return Err(SparseBindingError::BufferOutOfBounds {
+                        offset: binding.offset,
+                        size: binding.size,
+                        buffer_size: buffer.size,
+                    });
+                }
+                // The last page may be partially used by the buffer.
+                if binding.size % wgt::SPARSE_PAGE_SIZE != 0 && end != buffer.size {
+                    return Err(SparseBindingError::UnalignedSize(binding.size));
+                }
+
+                let pages = binding.offset / wgt::SPARSE_PAGE_SIZE
+                    ..wgt::math::align_to(end, wgt::SPARSE_PAGE_SIZE) / wgt::SPARSE_PAGE_SIZE;
+                let heap = validate_sparse_memory(
+                    &heap_guard,
+                    queue_id,
+                    &binding.memory,
+                    pages.end - pages.start,
+                )?;
+                hal_buffer_bindings.push(hal::SparseBufferBinding {
+                    buffer: raw,
+                    range: pages.start * wgt::SPARSE_PAGE_SIZE..pages.end * wgt::SPARSE_PAGE_SIZE,
+                    memory: heap.zip(binding.memory.as_ref()).map(|(heap, memory)| {
+                        hal::SparseMemory {
+                            heap: &heap.raw,
+                            offset: memory.offset,
+                        }
+                    }),
+                });
+                buffer_pages.push(pages.start as usize..pages.end as usize);
+            }
+
+            let mut hal_texture_bindings = Vec::with_capacity(texture_bindings.len());
+            for binding in texture_bindings {
+                let texture = texture_guard
+                    .get(binding.texture)
+                    .map_err(|_| SparseBindingError::InvalidTexture(binding.texture))?;
+                if texture.device_id.value.0 != queue_id {
+                    return Err(DeviceError::WrongDevice.into());
+                }
+                let raw = texture
+                    .inner
+                    .as_raw()
+                    .ok_or(SparseBindingError::InvalidTexture(binding.texture))?;
+                if texture.sparse.is_none() {
+                    return Err(SparseBindingError::NotSparseTexture(binding.texture));
+                }
+                let mip_size = texture.desc.mip_level_size(binding.mip_level).ok_or(
+                    SparseBindingError::InvalidMipLevel {
+                        texture: binding.texture,
+                        mip_level: binding.mip_level,
+                    },
+                )?;
+                let (tile_width, tile_height) = texture.desc.format.sparse_tile_extent().unwrap();
+                if binding.origin.x % tile_width != 0
+                    || binding.origin.y % tile_height != 0
+                    || binding.size.width % tile_width != 0
+                    || binding.size.height % tile_height != 0
+                {
+                    return Err(SparseBindingError::UnalignedRegion {
+                        origin: binding.origin,
+                        size: binding.size,
+                        tile_width,
+                        tile_height,
+                    });
+                }
+                if binding.origin.x + binding.size.width > mip_size.width
+                    || binding.origin.y + binding.size.height > mip_size.height
+                    || binding.origin.z + binding.size.depth_or_array_layers
+                        > mip_size.depth_or_array_layers
+                {
+                    return Err(SparseBindingError::RegionOutOfBounds {
+                        origin: binding.origin,
+                        size: binding.size,
+                        mip_size,
+                    });
+                }
+
+                let layer_tiles = u64::from(binding.size.width / tile_width)
+                    * u64::from(binding.size.height / tile_height);
+                let heap = validate_sparse_memory(
+                    &heap_guard,
+                    queue_id,
+                    &binding.memory,
+                    layer_tiles * u64::from(binding.size.depth_or_array_layers),
+                )?;
+                for layer in 0..binding.size.depth_or_array_layers {
+                    hal_texture_bindings.push(hal::SparseTextureBinding {
+                        texture: raw,
+                        mip_level: binding.mip_level,
+                        array_layer: binding.origin.z + layer,
+                        origin: wgt::Origin3d {
+                            z: 0,
+                            ..binding.origin
+                        },
+                        size: hal::CopyExtent {
+                            width: binding.size.width,
+                            height: binding.size.height,
+                            depth: 1,
+                        },
+                        memory: heap.zip(binding.memory.as_ref()).map(|(heap, memory)| {
+                            hal::SparseMemory {
+                                heap: &heap.raw,
+                                offset: memory.offset
+                                    + u64::from(layer) * layer_tiles * wgt::SPARSE_PAGE_SIZE,
+                            }
+                        }),
+                    });
+                }
+            }
+
+            // The bindings are updated like a submission, so that the
+            // resources and heaps they use outlive them.
+            device.active_submission_index += 1;
+            let submit_index = device.active_submission_index;
+            unsafe {
+                device
+                    .queue
+                    .bind_sparse(
+                        &hal_buffer_bindings,
+                        &hal_texture_bindings,
+                        Some((&mut device.fence, submit_index)),
+                    )
+                    .map_err(DeviceError::from)?;
+            }
+
+            let mut replaced_heaps = Vec::new();
+            let heap_memory = |memory: &Option<wgt::SparseMemory<id::HeapId>>| {
+                memory
+                    .as_ref()
+                    .map(|memory| (memory.heap, &heap_guard[id::Valid(memory.heap)].life_guard))
+            };
+
+            for (binding, pages) in buffer_bindings.iter().zip(buffer_pages) {
+                let buffer = buffer_guard.get_mut(binding.buffer).unwrap();
+                buffer.life_guard.use_at(submit_index);
+                // Bound memory has to be cleared before use, and unbound
+                // memory may be bound again later on.
+                let end = (pages.end as u64 * wgt::SPARSE_PAGE_SIZE).min(buffer.size);
+                buffer
+                    .initialization_status
+                    .discard_range(pages.start as u64 * wgt::SPARSE_PAGE_SIZE..end);
+                bind_sparse_pages(
+                    &mut buffer.sparse.as_mut().unwrap().pages,
+                    pages,
+                    heap_memory(&binding.memory),
+                    &mut replaced_heaps,
+                );
+            }
+
+            // Tiles bound in subresources that are already initialized are
+            // cleared right away. Otherwise, the whole subresource is cleared
+            // before its first use.
+            let mut cleared_tiles = Vec::new();
+            for binding in texture_bindings {
+                let texture = texture_guard.get_mut(binding.texture).unwrap();
+                texture.life_guard.use_at(submit_index);
+                let (tile_width, tile_height) = texture.desc.format.sparse_tile_extent().unwrap();
+                let (columns, _) = sparse_tiles(&texture.desc, binding.mip_level);
+                let first_column = binding.origin.x / tile_width;
+                let first_row = binding.origin.y / tile_height;
+                let row_tiles = binding.size.width / tile_width;
+
+                for layer in binding.origin.z..binding.origin.z + binding.size.depth_or_array_layers
+                {
+                    let base = sparse_page_base(&texture.desc, binding.mip_level, layer);
+                    for row in first_row..first_row + binding.size.height / tile_height {
+                        let start = base + (row * columns + first_column) as usize;
+                        bind_sparse_pages(
+                            &mut texture.sparse.as_mut().unwrap().pages,
+                            start..start + row_tiles as usize,
+                            heap_memory(&binding.memory),
+                            &mut replaced_heaps,
+                        );
+                    }
+
+                    let initialized = texture.initialization_status.mips
+                        [binding.mip_level as usize]
+                        .check(layer..layer + 1)
+                        .is_none();
+                    let subresource = (binding.texture, binding.mip_level, layer);
+                    if binding.memory.is_some()
+                        && initialized
+                        && !cleared_tiles.contains(&subresource)
+                    {
+                        cleared_tiles.push(subresource);
+                    }
+                }
+            }
+
+            if !cleared_tiles.is_empty() {
+                let mut trackers = device.trackers.lock();
+                let encoder = device.pending_writes.activate();
+                for &(texture_id, mip_level, layer) in cleared_tiles.iter() {
+                    let texture = texture_guard.get(texture_id).unwrap();
+                    let format = texture.desc.format;
+                    let (tile_width, tile_height) = format.sparse_tile_extent().unwrap();
+                    let (block_width, block_height) = format.block_dimensions();
+                    let bytes_per_row = tile_width / block_width * format.block_size(None).unwrap();
+
+                    let transition = trackers
+                        .textures
+                        .set_single(
+                            texture,
+                            texture_id,
+                            track::TextureSelector {
+                                mips: mip_level..mip_level + 1,
+                                layers: layer..layer + 1,
+                            },
+                            hal::TextureUses::COPY_DST,
+                        )
+                        .unwrap();
+                    let regions = texture_bindings
+                        .iter()
+                        .filter(|other| {
+                            other.texture == texture_id
+                                && other.mip_level == mip_level
+                                && other.memory.is_some()
+                                && (other.origin.z
+                                    ..other.origin.z + other.size.depth_or_array_layers)
+                                    .contains(&layer)
+                        })
+                        .flat_map(|other| {
+                            let columns = other.size.width / tile_width;
+                            let rows = other.size.height / tile_height;
+                            (0..rows).flat_map(move |row| {
+                                (0..columns).map(move |column| hal::BufferTextureCopy {
+                                    buffer_layout: wgt::ImageDataLayout {
+                                        offset: 0,
+                                        bytes_per_row: Some(bytes_per_row),
+                                        rows_per_image: Some(tile_height / block_height),
+                                    },
+                                    texture_base: hal::TextureCopyBase {
+                                        mip_level,
+                                        array_layer: layer,
+                                        origin: wgt::Origin3d {
+                                            x: other.origin.x + column * tile_width,
+                                            y: other.origin.y + row * tile_height,
+                                            z: 0,
+                                        },
+                                        aspect: hal::FormatAspects::COLOR,
+                                    },
+                                    size: hal::CopyExtent {
+                                        width: tile_width,
+                                        height: tile_height,
+                                        depth: 1,
+                                    },
+                                })
+                            })
+                        });
+                    unsafe {
+                        encoder.transition_textures(
+                            transition.map(|pending| pending.into_hal(texture)),
+                        );
+                        encoder.copy_buffer_to_texture(
+                            &device.zero_buffer,
+                            texture.inner.as_raw().unwrap(),
+                            regions,
+                        );
+                    }
+                    texture
+                        .life_guard
+                        .use_at(device.active_submission_index + 1);
+                }
+                device
+                    .pending_writes
+                    .dst_textures
+                    .extend(cleared_tiles.iter().map(|&(texture_id, ..)| texture_id));
+            }
+
+            replaced_heaps
+        };
+
+        let mut life_tracker = device.lock_life(&mut token);
+        life_tracker.track_submission(device.active_submission_index, iter::empty(), Vec::new());
+        // Heaps only held alive by the pages they were bound to can go now.
+        life_tracker
+            .suspected_resources
+            .heaps
+            .extend(replaced_heaps);
+
+        Ok(())
+    }
+
     pub fn queue_get_timestamp_period<A: HalApi>(
         &self,
         queue_id: id::QueueId,
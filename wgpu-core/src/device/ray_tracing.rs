@@ -0,0 +1,313 @@
+#[cfg(feature = "trace")]
+use crate::device::trace;
+use crate::{
+    device::{Device, DeviceError},
+    global::Global,
+    hal_api::HalApi,
+    hub::Token,
+    id::{self, BlasId, TlasId},
+    identity::{GlobalIdentityHandlerFactory, Input},
+    ray_tracing::{
+        validate_blas_sizes, BlasDescriptor, CreateBlasError, CreateTlasError, TlasDescriptor,
+    },
+    resource, LabelHelpers as _, LifeGuard, Stored,
+};
+
+use hal::Device as _;
+
+impl<A: HalApi> Device<A> {
+    fn create_blas(
+        &self,
+        self_id: id::DeviceId,
+        desc: &BlasDescriptor,
+        sizes: wgt::BlasGeometrySizeDescriptors,
+    ) -> Result<resource::Blas<A>, CreateBlasError> {
+        self.require_features(wgt::Features::RAY_TRACING_ACCELERATION_STRUCTURE)?;
+        validate_blas_sizes(&sizes)?;
+
+        let wgt::BlasGeometrySizeDescriptors::Triangles {
+            desc: ref triangles,
+        } = sizes;
+        let entries = hal::AccelerationStructureEntries::Triangles(
+            triangles
+                .iter()
+                .map(|size| hal::AccelerationStructureTriangles {
+                    vertex_buffer: None,
+                    vertex_format: size.vertex_format,
+                    first_vertex: 0,
+                    vertex_count: size.vertex_count,
+                    vertex_stride: 0,
+                    indices: size.index_format.map(|format| {
+                        hal::AccelerationStructureTriangleIndices {
+                            format,
+                            buffer: None,
+                            offset: 0,
+                            count: size.index_count.unwrap_or(0),
+                        }
+                    }),
+                    transform: None,
+                    flags: size.flags,
+                })
+                .collect(),
+        );
+
+        let size_info = unsafe {
+            self.raw.get_acceleration_structure_build_sizes(
+                &hal::GetAccelerationStructureBuildSizesDescriptor {
+                    entries: &entries,
+                    flags: desc.flags,
+                },
+            )
+        };
+
+        let raw = unsafe {
+            self.raw
+                .create_acceleration_structure(&hal::AccelerationStructureDescriptor {
+                    label: desc.label.borrow_option(),
+                    size: size_info.acceleration_structure_size,
+                    format: hal::AccelerationStructureFormat::BottomLevel,
+                })
+        }
+        .map_err(DeviceError::from)?;
+        let handle = unsafe { self.raw.get_acceleration_structure_device_address(&raw) };
+
+        Ok(resource::Blas {
+            raw,
+            device_id: Stored {
+                value: id::Valid(self_id),
+                ref_count: self.life_guard.add_ref(),
+            },
+            life_guard: LifeGuard::new(desc.label.borrow_or_default()),
+            size_info,
+            sizes,
+            flags: desc.flags,
+            update_mode: desc.update_mode,
+            built_index: None,
+            handle,
+        })
+    }
+
+    fn create_tlas(
+        &self,
+        self_id: id::DeviceId,
+        desc: &TlasDescriptor,
+    ) -> Result<resource::Tlas<A>, CreateTlasError> {
+        self.require_features(wgt::Features::RAY_TRACING_ACCELERATION_STRUCTURE)?;
+
+        let entries =
+            hal::AccelerationStructureEntries::Instances(hal::AccelerationStructureInstances {
+                buffer: None,
+                offset: 0,
+                count: desc.max_instances,
+            });
+
+        let size_info = unsafe {
+            self.raw.get_acceleration_structure_build_sizes(
+                &hal::GetAccelerationStructureBuildSizesDescriptor {
+                    entries: &entries,
+                    flags: desc.flags,
+                },
+            )
+        };
+
+        let raw = unsafe {
+            self.raw
+                .create_acceleration_structure(&hal::AccelerationStructureDescriptor {
+                    label: desc.label.borrow_option(),
+                    size: size_info.acceleration_structure_size,
+                    format: hal::AccelerationStructureFormat::TopLevel,
+                })
+        }
+        .map_err(DeviceError::from)?;
+
+        Ok(resource::Tlas {
+            raw,
+            device_id: Stored {
+                value: id::Valid(self_id),
+                ref_count: self.life_guard.add_ref(),
+            },
+            life_guard: LifeGuard::new(desc.label.borrow_or_default()),
+            size_info,
+            max_instance_count: desc.max_instances,
+            flags: desc.flags,
+            update_mode: desc.update_mode,
+            built_index: None,
+        })
+    }
+}
+
+impl<G: GlobalIdentityHandlerFactory> Global<G> {
+    /// Create a bottom level acceleration structure able to hold the
+    /// geometries described by `sizes`.
+    ///
+    /// Also returns the handle instances in a top level acceleration
+    /// structure use to reference it.
+    pub fn device_create_blas<A: HalApi>(
+        &self,
+        device_id: id::DeviceId,
+        desc: &BlasDescriptor,
+        sizes: wgt::BlasGeometrySizeDescriptors,
+        id_in: Input<G, BlasId>,
+    ) -> (BlasId, Option<u64>, Option<CreateBlasError>) {
+        profiling::scope!("Device::create_blas");
+
+        let hub = A::hub(self);
+        let mut token = Token::root();
+        let fid = hub.blas_s.prepare(id_in);
+
+        let (device_guard, mut token) = hub.devices.read(&mut token);
+        let error = loop {
+            let device = match device_guard.get(device_id) {
+                Ok(device) => device,
+                Err(_) => break DeviceError::Invalid.into(),
+            };
+            if !device.valid {
+                break DeviceError::Lost.into();
+            }
+
+            #[cfg(feature = "trace")]
+            if let Some(ref trace) = device.trace {
+                trace.lock().add(trace::Action::CreateBlas {
+                    id: fid.id(),
+                    desc: desc.clone(),
+                    sizes: sizes.clone(),
+                });
+            }
+
+            let blas = match device.create_blas(device_id, desc, sizes) {
+                Ok(blas) => blas,
+                Err(err) => break err,
+            };
+            let handle = blas.handle;
+
+            let ref_count = blas.life_guard.add_ref();
+            let id = fid.assign(blas, &mut token);
+            log::trace!("Device::create_blas -> {:?}", id.0);
+
+            device.trackers.lock().blas_s.insert_single(id, ref_count);
+
+            return (id.0, Some(handle), None);
+        };
+
+        let id = fid.assign_error(desc.label.borrow_or_default(), &mut token);
+        (id, None, Some(error))
+    }
+
+    pub fn device_create_tlas<A: HalApi>(
+        &self,
+        device_id: id::DeviceId,
+        desc: &TlasDescriptor,
+        id_in: Input<G, TlasId>,
+    ) -> (TlasId, Option<CreateTlasError>) {
+        profiling::scope!("Device::create_tlas");
+
+        let hub = A::hub(self);
+        let mut token = Token::root();
+        let fid = hub.tlas_s.prepare(id_in);
+
+        let (device_guard, mut token) = hub.devices.read(&mut token);
+        let error = loop {
+            let device = match device_guard.get(device_id) {
+                Ok(device) => device,
+                Err(_) => break DeviceError::Invalid.into(),
+            };
+            if !device.valid {
+                break DeviceError::Lost.into();
+            }
+
+            #[cfg(feature = "trace")]
+            if let Some(ref trace) = device.trace {
+                trace.lock().add(trace::Action::CreateTlas {
+                    id: fid.id(),
+                    desc: desc.clone(),
+                });
+            }
+
+            let tlas = match device.create_tlas(device_id, desc) {
+                Ok(tlas) => tlas,
+                Err(err) => break err,
+            };
+
+            let ref_count = tlas.life_guard.add_ref();
+            let id = fid.assign(tlas, &mut token);
+            log::trace!("Device::create_tlas -> {:?}", id.0);
+
+            device.trackers.lock().tlas_s.insert_single(id, ref_count);
+
+            return (id.0, None);
+        };
+
+        let id = fid.assign_error(desc.label.borrow_or_default(), &mut token);
+        (id, Some(error))
+    }
+
+    pub fn blas_drop<A: HalApi>(&self, blas_id: BlasId) {
+        profiling::scope!("Blas::drop");
+        log::trace!("Blas::drop {blas_id:?}");
+
+        let hub = A::hub(self);
+        let mut token = Token::root();
+
+        let device_id = {
+            let (mut blas_guard, _) = hub.blas_s.write(&mut token);
+            match blas_guard.get_mut(blas_id) {
+                Ok(blas) => {
+                    blas.life_guard.ref_count.take();
+                    blas.device_id.value
+                }
+                Err(_) => {
+                    hub.blas_s.unregister_locked(blas_id, &mut *blas_guard);
+                    return;
+                }
+            }
+        };
+
+        let (device_guard, mut token) = hub.devices.read(&mut token);
+        let device = &device_guard[device_id];
+
+        device
+            .lock_life(&mut token)
+            .suspected_resources
+            .blas_s
+            .push(id::Valid(blas_id));
+    }
+
+    pub fn tlas_drop<A: HalApi>(&self, tlas_id: TlasId) {
+        profiling::scope!("Tlas::drop");
+        log::trace!("Tlas::drop {tlas_id:?}");
+
+        let hub = A::hub(self);
+        let mut token = Token::root();
+
+        let device_id = {
+            let (mut tlas_guard, _) = hub.tlas_s.write(&mut token);
+            match tlas_guard.get_mut(tlas_id) {
+                Ok(tlas) => {
+                    tlas.life_guard.ref_count.take();
+                    tlas.device_id.value
+                }
+                Err(_) => {
+                    hub.tlas_s.unregister_locked(tlas_id, &mut *tlas_guard);
+                    return;
+                }
+            }
+        };
+
+        let (device_guard, mut token) = hub.devices.read(&mut token);
+        let device = &device_guard[device_id];
+
+        device
+            .lock_life(&mut token)
+            .suspected_resources
+            .tlas_s
+            .push(id::Valid(tlas_id));
+    }
+
+    pub fn blas_label<A: HalApi>(&self, id: BlasId) -> String {
+        A::hub(self).blas_s.label_for_resource(id)
+    }
+
+    pub fn tlas_label<A: HalApi>(&self, id: TlasId) -> String {
+        A::hub(self).tlas_s.label_for_resource(id)
+    }
+}
@@ -15,9 +15,11 @@ use crate::{
     hub::{Hub, Token},
     id,
     identity::GlobalIdentityHandlerFactory,
+    index_validation::IndexContents,
+    indirect_validation::IndirectValidation,
     init_tracker::{
-        BufferInitTracker, BufferInitTrackerAction, MemoryInitKind, TextureInitRange,
-        TextureInitTracker, TextureInitTrackerAction,
+        BufferInitTracker, BufferInitTrackerAction, MemoryInitKind, QuerySetInitTracker,
+        TextureInitRange, TextureInitTracker, TextureInitTrackerAction,
     },
     instance::Adapter,
     pipeline,
@@ -38,8 +40,8 @@ use wgt::{TextureFormat, TextureSampleType, TextureViewDimension};
 use std::{borrow::Cow, iter, num::NonZeroU32};
 
 use super::{
-    life, queue, DeviceDescriptor, DeviceError, ImplicitPipelineContext, UserClosures, EP_FAILURE,
-    IMPLICIT_FAILURE, ZERO_BUFFER_SIZE,
+    life, queue, DeviceDescriptor, DeviceError, DeviceLostClosure, DeviceLostInvocation,
+    ImplicitPipelineContext, UserClosures, EP_FAILURE, IMPLICIT_FAILURE, ZERO_BUFFER_SIZE,
 };
 
 /// Structure describing a logical device. Some members are internally mutable,
@@ -88,6 +90,11 @@ pub struct Device<A: HalApi> {
     /// using ref-counted references for internal access.
     pub(crate) valid: bool,
 
+    /// Closure to be called on "lose the device". It is taken out by
+    /// [`Device::lose`], or when the user drops the device, so that it is
+    /// invoked at most once.
+    pub(crate) device_lost_closure: Mutex<Option<DeviceLostClosure>>,
+
     /// All live resources allocated with this [`Device`].
     ///
     /// Has to be locked temporarily only (locked last)
@@ -101,6 +108,11 @@ pub struct Device<A: HalApi> {
     pub(crate) limits: wgt::Limits,
     pub(crate) features: wgt::Features,
     pub(crate) downlevel: wgt::DownlevelCapabilities,
+    pub(crate) instance_flags: wgt::InstanceFlags,
+    /// Pipelines validating indirect calls, if
+    /// [`wgt::InstanceFlags::VALIDATION_INDIRECT_CALL`] or
+    /// [`wgt::InstanceFlags::INDEX_RANGE_VALIDATION`] is set and supported.
+    pub(crate) indirect_validation: Option<IndirectValidation<A>>,
     // TODO: move this behind another mutex. This would allow several methods to
     // switch to borrow Device immutably, such as `write_buffer`, `write_texture`,
     // and `buffer_unmap`.
@@ -143,9 +155,9 @@ impl<A: HalApi> Device<A> {
     pub(crate) fn new(
         open: hal::OpenDevice<A>,
         adapter_id: Stored<id::AdapterId>,
-        alignments: hal::Alignments,
-        downlevel: wgt::DownlevelCapabilities,
+        caps: &hal::Capabilities,
         desc: &DeviceDescriptor,
+        instance_flags: wgt::InstanceFlags,
         trace_path: Option<&std::path::Path>,
     ) -> Result<Self, CreateDeviceError> {
         #[cfg(not(feature = "trace"))]
@@ -193,6 +205,15 @@ impl<A: HalApi> Device<A> {
                 }));
         }
 
+        let indirect_validation = if instance_flags.intersects(
+            wgt::InstanceFlags::VALIDATION_INDIRECT_CALL
+                | wgt::InstanceFlags::INDEX_RANGE_VALIDATION,
+        ) {
+            IndirectValidation::new(&open.device, caps)
+        } else {
+            None
+        };
+
         let life_guard = LifeGuard::new("<device>");
         let ref_count = life_guard.add_ref();
         Ok(Self {
@@ -206,27 +227,32 @@ impl<A: HalApi> Device<A> {
             active_submission_index: 0,
             fence,
             valid: true,
+            device_lost_closure: Mutex::new(None),
             trackers: Mutex::new(Tracker::new()),
             life_tracker: Mutex::new(life::LifetimeTracker::new()),
             temp_suspected: life::SuspectedResources::default(),
             #[cfg(feature = "trace")]
-            trace: trace_path.and_then(|path| match trace::Trace::new(path) {
-                Ok(mut trace) => {
-                    trace.add(trace::Action::Init {
-                        desc: desc.clone(),
-                        backend: A::VARIANT,
-                    });
-                    Some(Mutex::new(trace))
-                }
-                Err(e) => {
-                    log::error!("Unable to start a trace in '{:?}': {:?}", path, e);
-                    None
+            trace: trace_path.and_then(|path| {
+                match trace::Trace::new(path, trace::TraceFormat::from_path(path)) {
+                    Ok(mut trace) => {
+                        trace.add(trace::Action::Init {
+                            desc: desc.clone(),
+                            backend: A::VARIANT,
+                        });
+                        Some(Mutex::new(trace))
+                    }
+                    Err(e) => {
+                        log::error!("Unable to start a trace in '{:?}': {:?}", path, e);
+                        None
+                    }
                 }
             }),
-            alignments,
+            alignments: caps.alignments.clone(),
             limits: desc.limits.clone(),
             features: desc.features,
-            downlevel,
+            downlevel: caps.downlevel.clone(),
+            instance_flags,
+            indirect_validation,
             pending_writes,
         })
     }
@@ -335,7 +361,9 @@ impl<A: HalApi> Device<A> {
             let (buffer_guard, mut token) = hub.buffers.read(&mut token);
             let (texture_guard, mut token) = hub.textures.read(&mut token);
             let (texture_view_guard, mut token) = hub.texture_views.read(&mut token);
-            let (sampler_guard, _) = hub.samplers.read(&mut token);
+            let (sampler_guard, mut token) = hub.samplers.read(&mut token);
+            let (blas_guard, mut token) = hub.blas_s.read(&mut token);
+            let (tlas_guard, _) = hub.tlas_s.read(&mut token);
 
             for id in trackers.buffers.used() {
                 if buffer_guard[id].life_guard.ref_count.is_none() {
@@ -377,6 +405,16 @@ impl<A: HalApi> Device<A> {
                     self.temp_suspected.query_sets.push(id);
                 }
             }
+            for id in trackers.blas_s.used() {
+                if blas_guard[id].life_guard.ref_count.is_none() {
+                    self.temp_suspected.blas_s.push(id);
+                }
+            }
+            for id in trackers.tlas_s.used() {
+                if tlas_guard[id].life_guard.ref_count.is_none() {
+                    self.temp_suspected.tlas_s.push(id);
+                }
+            }
         }
 
         self.lock_life(token)
@@ -391,6 +429,7 @@ impl<A: HalApi> Device<A> {
         self_id: id::DeviceId,
         desc: &resource::BufferDescriptor,
         transient: bool,
+        memory: ResourceMemory<A>,
     ) -> Result<Buffer<A>, resource::CreateBufferError> {
         debug_assert_eq!(self_id.backend(), A::VARIANT);
 
@@ -446,6 +485,28 @@ impl<A: HalApi> Device<A> {
             usage |= hal::BufferUses::COPY_DST;
         }
 
+        let validate_index_range = self
+            .instance_flags
+            .contains(wgt::InstanceFlags::INDEX_RANGE_VALIDATION);
+        if validate_index_range
+            && desc
+                .usage
+                .intersects(wgt::BufferUsages::INDEX | wgt::BufferUsages::INDIRECT)
+        {
+            // Indexed indirect draws are validated from a copy of the arguments
+            // and the indices.
+            usage |= hal::BufferUses::COPY_SRC;
+        }
+        if self
+            .instance_flags
+            .contains(wgt::InstanceFlags::VALIDATION_INDIRECT_CALL)
+            && desc.usage.contains(wgt::BufferUsages::INDIRECT)
+        {
+            // Indirect draws are validated from a copy of the arguments, and
+            // dispatches by binding them as a storage buffer.
+            usage |= hal::BufferUses::COPY_SRC | hal::BufferUses::STORAGE_READ;
+        }
+
         let actual_size = if desc.size == 0 {
             wgt::COPY_BUFFER_ALIGNMENT
         } else if desc.usage.contains(wgt::BufferUsages::VERTEX) {
@@ -456,7 +517,7 @@ impl<A: HalApi> Device<A> {
             desc.size
         };
         let clear_remainder = actual_size % wgt::COPY_BUFFER_ALIGNMENT;
-        let aligned_size = if clear_remainder != 0 {
+        let mut aligned_size = if clear_remainder != 0 {
             actual_size + wgt::COPY_BUFFER_ALIGNMENT - clear_remainder
         } else {
             actual_size
@@ -465,13 +526,62 @@ impl<A: HalApi> Device<A> {
         let mut memory_flags = hal::MemoryFlags::empty();
         memory_flags.set(hal::MemoryFlags::TRANSIENT, transient);
 
+        let sparse = if let ResourceMemory::Sparse = memory {
+            self.require_features(wgt::Features::SPARSE_BINDING)
+                .map_err(resource::SparseResourceError::from)?;
+            if desc.mapped_at_creation
+                || desc
+                    .usage
+                    .intersects(wgt::BufferUsages::MAP_READ | wgt::BufferUsages::MAP_WRITE)
+            {
+                return Err(resource::SparseResourceError::Mappable.into());
+            }
+            let residency = self.features.contains(wgt::Features::SPARSE_RESIDENCY);
+            memory_flags |= hal::MemoryFlags::SPARSE_BINDING;
+            memory_flags.set(hal::MemoryFlags::SPARSE_RESIDENCY, residency);
+
+            // The last page is bound as a whole, so it has to be part of the buffer.
+            aligned_size = wgt::math::align_to(aligned_size, wgt::SPARSE_PAGE_SIZE);
+            let page_count = aligned_size / wgt::SPARSE_PAGE_SIZE;
+            Some(resource::SparsePages::new(residency, page_count as usize))
+        } else {
+            None
+        };
+
         let hal_desc = hal::BufferDescriptor {
             label: desc.label.borrow_option(),
             size: aligned_size,
             usage,
             memory_flags,
         };
-        let buffer = unsafe { self.raw.create_buffer(&hal_desc) }.map_err(DeviceError::from)?;
+        let (buffer, heap) = match memory {
+            ResourceMemory::Placed(placement) => {
+                let placed = unsafe {
+                    self.raw
+                        .create_buffer_in_heap(&hal_desc, &placement.heap.raw, placement.offset)
+                }
+                .map_err(|e| placement.map_err::<resource::CreateBufferError>(e))?;
+                (placed.raw, Some(placement.finish(placed.size)))
+            }
+            ResourceMemory::Dedicated | ResourceMemory::Sparse => (
+                unsafe { self.raw.create_buffer(&hal_desc) }.map_err(DeviceError::from)?,
+                None,
+            ),
+        };
+
+        let index_contents =
+            if validate_index_range && desc.usage.contains(wgt::BufferUsages::INDEX) {
+                // Shaders may write to storage buffers at any time.
+                Some(Mutex::new(
+                    if desc.usage.contains(wgt::BufferUsages::STORAGE) {
+                        IndexContents::unknown()
+                    } else {
+                        IndexContents::new(desc.size)
+                    },
+                ))
+            } else {
+                None
+            };
 
         Ok(Buffer {
             raw: Some(buffer),
@@ -485,6 +595,9 @@ impl<A: HalApi> Device<A> {
             sync_mapped_writes: None,
             map_state: resource::BufferMapState::Idle,
             life_guard: LifeGuard::new(desc.label.borrow_or_default()),
+            index_contents,
+            heap,
+            sparse,
         })
     }
 
@@ -520,6 +633,8 @@ impl<A: HalApi> Device<A> {
             },
             life_guard: LifeGuard::new(desc.label.borrow_or_default()),
             clear_mode,
+            heap: None,
+            sparse: None,
         }
     }
 
@@ -543,6 +658,9 @@ impl<A: HalApi> Device<A> {
             sync_mapped_writes: None,
             map_state: resource::BufferMapState::Idle,
             life_guard: LifeGuard::new(desc.label.borrow_or_default()),
+            index_contents: None,
+            heap: None,
+            sparse: None,
         }
     }
 
@@ -551,6 +669,7 @@ impl<A: HalApi> Device<A> {
         self_id: id::DeviceId,
         adapter: &Adapter<A>,
         desc: &resource::TextureDescriptor,
+        memory: ResourceMemory<A>,
     ) -> Result<resource::Texture<A>, resource::CreateTextureError> {
         use resource::{CreateTextureError, TextureDimensionError};
 
@@ -671,6 +790,38 @@ impl<A: HalApi> Device<A> {
             });
         }
 
+        let sparse = if let ResourceMemory::Sparse = memory {
+            use resource::SparseResourceError as Sre;
+
+            self.require_features(wgt::Features::SPARSE_RESIDENCY)
+                .map_err(Sre::from)?;
+            if desc.dimension != wgt::TextureDimension::D2 || desc.sample_count != 1 {
+                return Err(Sre::InvalidDimension.into());
+            }
+            let (tile_width, tile_height) = desc
+                .format
+                .sparse_tile_extent()
+                .ok_or(Sre::UnsupportedFormat(desc.format))?;
+            // Mip levels are bound tile by tile, without a tail of partial tiles.
+            for mip_level in 0..desc.mip_level_count {
+                let size = desc.size.mip_level_size(mip_level, desc.dimension);
+                if size.width % tile_width != 0 || size.height % tile_height != 0 {
+                    return Err(Sre::PartialTiles {
+                        mip_level,
+                        width: size.width,
+                        height: size.height,
+                        tile_width,
+                        tile_height,
+                    }
+                    .into());
+                }
+            }
+            let page_count = resource::sparse_page_base(desc, desc.mip_level_count, 0);
+            Some(resource::SparsePages::new(true, page_count))
+        } else {
+            None
+        };
+
         let missing_allowed_usages = desc.usage - format_features.allowed_usages;
         if !missing_allowed_usages.is_empty() {
             // detect downlevel incompatibilities
@@ -719,8 +870,19 @@ impl<A: HalApi> Device<A> {
                 } else {
                     hal::TextureUses::COPY_DST
                 }
+            }
+            // Newly bound tiles of sparse textures are cleared with copies.
+            | if sparse.is_some() {
+                hal::TextureUses::COPY_DST
+            } else {
+                hal::TextureUses::empty()
             };
 
+        let mut memory_flags = hal::MemoryFlags::empty();
+        if sparse.is_some() {
+            memory_flags |= hal::MemoryFlags::SPARSE_BINDING | hal::MemoryFlags::SPARSE_RESIDENCY;
+        }
+
         let hal_desc = hal::TextureDescriptor {
             label: desc.label.borrow_option(),
             size: desc.size,
@@ -729,14 +891,30 @@ impl<A: HalApi> Device<A> {
             dimension: desc.dimension,
             format: desc.format,
             usage: hal_usage,
-            memory_flags: hal::MemoryFlags::empty(),
+            memory_flags,
             view_formats: hal_view_formats,
         };
 
-        let raw_texture = unsafe {
-            self.raw
-                .create_texture(&hal_desc)
-                .map_err(DeviceError::from)?
+        let (raw_texture, heap) = match memory {
+            ResourceMemory::Placed(placement) => {
+                let placed = unsafe {
+                    self.raw.create_texture_in_heap(
+                        &hal_desc,
+                        &placement.heap.raw,
+                        placement.offset,
+                    )
+                }
+                .map_err(|e| placement.map_err::<CreateTextureError>(e))?;
+                (placed.raw, Some(placement.finish(placed.size)))
+            }
+            ResourceMemory::Dedicated | ResourceMemory::Sparse => (
+                unsafe {
+                    self.raw
+                        .create_texture(&hal_desc)
+                        .map_err(DeviceError::from)?
+                },
+                None,
+            ),
         };
 
         let clear_mode = if hal_usage
@@ -792,6 +970,8 @@ impl<A: HalApi> Device<A> {
             clear_mode,
         );
         texture.hal_usage = hal_usage;
+        texture.heap = heap;
+        texture.sparse = sparse;
         Ok(texture)
     }
 
@@ -1301,6 +1481,10 @@ impl<A: HalApi> Device<A> {
             Caps::DUAL_SOURCE_BLENDING,
             self.features.contains(wgt::Features::DUAL_SOURCE_BLENDING),
         );
+        caps.set(
+            Caps::RAY_QUERY,
+            self.features.contains(wgt::Features::RAY_QUERY),
+        );
 
         let info = naga::valid::Validator::new(naga::valid::ValidationFlags::all(), caps)
             .validate(&module)
@@ -1574,6 +1758,10 @@ impl<A: HalApi> Device<A> {
                         },
                     )
                 }
+                Bt::AccelerationStructure => {
+                    required_features |= wgt::Features::RAY_QUERY;
+                    (None, WritableStorage::No)
+                }
             };
 
             // Validate the count parameter
@@ -1882,7 +2070,8 @@ impl<A: HalApi> Device<A> {
         let (buffer_guard, mut token) = hub.buffers.read(token);
         let (texture_guard, mut token) = hub.textures.read(&mut token); //skip token
         let (texture_view_guard, mut token) = hub.texture_views.read(&mut token);
-        let (sampler_guard, _) = hub.samplers.read(&mut token);
+        let (sampler_guard, mut token) = hub.samplers.read(&mut token);
+        let (tlas_guard, _) = hub.tlas_s.read(&mut token);
 
         let mut used_buffer_ranges = Vec::new();
         let mut used_texture_ranges = Vec::new();
@@ -1890,6 +2079,7 @@ impl<A: HalApi> Device<A> {
         let mut hal_buffers = Vec::new();
         let mut hal_samplers = Vec::new();
         let mut hal_textures = Vec::new();
+        let mut hal_acceleration_structures = Vec::new();
         for entry in desc.entries.iter() {
             let binding = entry.binding;
             // Find the corresponding declaration in the layout
@@ -2066,6 +2256,31 @@ impl<A: HalApi> Device<A> {
 
                     (res_index, num_bindings)
                 }
+                Br::AccelerationStructure(id) => {
+                    match decl.ty {
+                        wgt::BindingType::AccelerationStructure => {}
+                        _ => {
+                            return Err(Error::WrongBindingType {
+                                binding,
+                                actual: decl.ty,
+                                expected: "AccelerationStructure",
+                            })
+                        }
+                    }
+
+                    let tlas = used
+                        .acceleration_structures
+                        .add_single(&*tlas_guard, id)
+                        .ok_or(Error::InvalidTlas(id))?;
+
+                    if tlas.device_id.value.0 != self_id {
+                        return Err(DeviceError::WrongDevice.into());
+                    }
+
+                    let res_index = hal_acceleration_structures.len();
+                    hal_acceleration_structures.push(&tlas.raw);
+                    (res_index, 1)
+                }
             };
 
             hal_entries.push(hal::BindGroupEntry {
@@ -2093,6 +2308,7 @@ impl<A: HalApi> Device<A> {
             buffers: &hal_buffers,
             samplers: &hal_samplers,
             textures: &hal_textures,
+            acceleration_structures: &hal_acceleration_structures,
         };
         let raw = unsafe {
             self.raw
@@ -2481,7 +2697,8 @@ impl<A: HalApi> Device<A> {
         let mut shader_binding_sizes = FastHashMap::default();
 
         let io = validation::StageIo::default();
-        let (shader_module_guard, _) = hub.shader_modules.read(&mut token);
+        let (shader_module_guard, mut token) = hub.shader_modules.read(&mut token);
+        let (pipeline_cache_guard, _) = hub.pipeline_caches.read(&mut token);
 
         let shader_module = shader_module_guard
             .get(desc.stage.module)
@@ -2491,6 +2708,19 @@ impl<A: HalApi> Device<A> {
             return Err(DeviceError::WrongDevice.into());
         }
 
+        let cache = match desc.cache {
+            Some(cache_id) => {
+                let cache = pipeline_cache_guard
+                    .get(cache_id)
+                    .map_err(|_| pipeline::CreateComputePipelineError::InvalidCache)?;
+                if cache.device_id.value.0 != self_id {
+                    return Err(DeviceError::WrongDevice.into());
+                }
+                Some(&cache.raw)
+            }
+            None => None,
+        };
+
         {
             let flag = wgt::ShaderStages::COMPUTE;
             let provided_layouts = match desc.layout {
@@ -2548,6 +2778,7 @@ impl<A: HalApi> Device<A> {
                 entry_point: desc.stage.entry_point.as_ref(),
                 module: &shader_module.raw,
             },
+            cache,
         };
 
         let raw =
@@ -2868,7 +3099,8 @@ impl<A: HalApi> Device<A> {
             sc
         };
 
-        let (shader_module_guard, _) = hub.shader_modules.read(&mut token);
+        let (shader_module_guard, mut token) = hub.shader_modules.read(&mut token);
+        let (pipeline_cache_guard, _) = hub.pipeline_caches.read(&mut token);
 
         let vertex_stage = {
             let stage = &desc.vertex.stage;
@@ -3068,6 +3300,19 @@ impl<A: HalApi> Device<A> {
             }
         }
 
+        let cache = match desc.cache {
+            Some(cache_id) => {
+                let cache = pipeline_cache_guard
+                    .get(cache_id)
+                    .map_err(|_| pipeline::CreateRenderPipelineError::InvalidCache)?;
+                if cache.device_id.value.0 != self_id {
+                    return Err(DeviceError::WrongDevice.into());
+                }
+                Some(&cache.raw)
+            }
+            None => None,
+        };
+
         let late_sized_buffer_groups =
             Device::make_late_sized_buffer_groups(&shader_binding_sizes, layout, &*bgl_guard);
 
@@ -3082,6 +3327,7 @@ impl<A: HalApi> Device<A> {
             fragment_stage,
             color_targets,
             multiview: desc.multiview,
+            cache,
         };
         let raw =
             unsafe { self.raw.create_render_pipeline(&pipeline_desc) }.map_err(
@@ -3154,6 +3400,68 @@ impl<A: HalApi> Device<A> {
         Ok(pipeline)
     }
 
+    /// # Safety
+    /// The `data` field on `desc` must have previously been returned from
+    /// [`crate::global::Global::pipeline_cache_get_data`].
+    pub(super) unsafe fn create_pipeline_cache(
+        &self,
+        self_id: id::DeviceId,
+        adapter: &Adapter<A>,
+        desc: &pipeline::PipelineCacheDescriptor,
+    ) -> Result<pipeline::PipelineCache<A>, pipeline::CreatePipelineCacheError> {
+        use crate::pipeline_cache;
+
+        self.require_features(wgt::Features::PIPELINE_CACHE)?;
+
+        let data = match desc.data {
+            Some(ref data) => {
+                let validation_key = self.raw.pipeline_cache_validation_key().ok_or_else(|| {
+                    pipeline::CreatePipelineCacheError::Internal(
+                        "backend does not support pipeline caches".to_string(),
+                    )
+                })?;
+                match pipeline_cache::validate_pipeline_cache(
+                    data,
+                    &adapter.raw.info,
+                    validation_key,
+                ) {
+                    Ok(data) => Some(data),
+                    Err(err) if desc.fallback => {
+                        if err.was_avoidable() {
+                            log::warn!("Pipeline cache data was unusable, starting empty: {err}");
+                        } else {
+                            log::info!("Pipeline cache data was not for this device: {err}");
+                        }
+                        None
+                    }
+                    Err(err) => return Err(err.into()),
+                }
+            }
+            None => None,
+        };
+
+        let cache_desc = hal::PipelineCacheDescriptor {
+            label: desc.label.borrow_option(),
+            data,
+        };
+        let raw =
+            unsafe { self.raw.create_pipeline_cache(&cache_desc) }.map_err(|err| match err {
+                hal::PipelineCacheError::Device(error) => {
+                    pipeline::CreatePipelineCacheError::Device(error.into())
+                }
+            })?;
+
+        Ok(pipeline::PipelineCache {
+            raw,
+            device_id: Stored {
+                value: id::Valid(self_id),
+                ref_count: self.life_guard.add_ref(),
+            },
+            #[cfg(debug_assertions)]
+            label: desc.label.borrow_or_default().to_string(),
+        })
+    }
+
     pub(super) fn describe_format_features(
         &self,
         adapter: &Adapter<A>,
@@ -3203,6 +3511,69 @@ impl<A: HalApi> Device<A> {
         Ok(())
     }
 
+    pub(super) fn create_heap(
+        &self,
+        self_id: id::DeviceId,
+        desc: &resource::HeapDescriptor,
+    ) -> Result<resource::Heap<A>, resource::CreateHeapError> {
+        self.require_features(wgt::Features::RESOURCE_HEAPS)?;
+
+        if desc.size == 0 {
+            return Err(resource::CreateHeapError::ZeroSize);
+        }
+
+        let hal_desc = hal::HeapDescriptor {
+            label: desc.label.borrow_option(),
+            size: desc.size,
+        };
+        let raw = unsafe { self.raw.create_heap(&hal_desc) }.map_err(DeviceError::from)?;
+
+        Ok(resource::Heap {
+            raw,
+            device_id: Stored {
+                value: id::Valid(self_id),
+                ref_count: self.life_guard.add_ref(),
+            },
+            size: desc.size,
+            life_guard: LifeGuard::new(desc.label.borrow_or_default()),
+            residents: Vec::new(),
+        })
+    }
+
+    pub(super) fn create_external_semaphore(
+        &self,
+        self_id: id::DeviceId,
+        desc: &resource::ExternalSemaphoreDescriptor,
+    ) -> Result<resource::ExternalSemaphore<A>, resource::CreateExternalSemaphoreError> {
+        self.require_features(wgt::Features::EXTERNAL_SEMAPHORES)?;
+
+        let hal_desc = hal::ExternalSemaphoreDescriptor {
+            label: desc.label.borrow_option(),
+        };
+        let raw =
+            unsafe { self.raw.create_external_semaphore(&hal_desc) }.map_err(DeviceError::from)?;
+
+        Ok(self.create_external_semaphore_from_hal(raw, self_id, desc))
+    }
+
+    pub(super) fn create_external_semaphore_from_hal(
+        &self,
+        hal_semaphore: A::ExternalSemaphore,
+        self_id: id::DeviceId,
+        desc: &resource::ExternalSemaphoreDescriptor,
+    ) -> resource::ExternalSemaphore<A> {
+        debug_assert_eq!(self_id.backend(), A::VARIANT);
+
+        resource::ExternalSemaphore {
+            raw: hal_semaphore,
+            device_id: Stored {
+                value: id::Valid(self_id),
+                ref_count: self.life_guard.add_ref(),
+            },
+            life_guard: LifeGuard::new(desc.label.borrow_or_default()),
+        }
+    }
+
     pub(super) fn create_query_set(
         &self,
         self_id: id::DeviceId,
@@ -3240,20 +3611,35 @@ impl<A: HalApi> Device<A> {
             },
             life_guard: LifeGuard::new(""),
             desc: desc.map_label(|_| ()),
+            initialization_status: Mutex::new(QuerySetInitTracker::new(desc.count)),
         })
     }
 
-    pub(crate) fn lose(&mut self, _reason: Option<&str>) {
+    pub(crate) fn lose(
+        &mut self,
+        reason: wgt::DeviceLostReason,
+        message: &str,
+    ) -> Option<DeviceLostInvocation> {
         // Follow the steps at https://gpuweb.github.io/gpuweb/#lose-the-device.
 
         // Mark the device explicitly as invalid. This is checked in various
         // places to prevent new work from being submitted.
         self.valid = false;
 
-        // The following steps remain in "lose the device":
         // 1) Resolve the GPUDevice device.lost promise.
 
-        // TODO: triggger this passively or actively, and supply the reason.
+        // The closure is taken out so it fires at most once. It's returned
+        // rather than called here, as the caller is most likely holding locks
+        // that the user callback may want to take.
+        let invocation =
+            self.device_lost_closure
+                .get_mut()
+                .take()
+                .map(|closure| DeviceLostInvocation {
+                    closure,
+                    reason,
+                    message: message.to_string(),
+                });
 
         // 2) Complete any outstanding mapAsync() steps.
         // 3) Complete any outstanding onSubmittedWorkDone() steps.
@@ -3262,6 +3648,8 @@ impl<A: HalApi> Device<A> {
         // since that will prevent any new work from being added to the queues.
         // Future calls to poll_devices will continue to check the work queues
         // until they are cleared, and then drop the device.
+
+        invocation
     }
 }
 
@@ -3282,6 +3670,20 @@ impl<A: HalApi> Device<A> {
         unsafe {
             self.raw.destroy_command_encoder(baked.encoder);
         }
+        for temp in baked.temp_resources {
+            match temp {
+                queue::TempResource::Buffer(raw) => unsafe { self.raw.destroy_buffer(raw) },
+                queue::TempResource::Texture(raw, views) => unsafe {
+                    for view in views {
+                        self.raw.destroy_texture_view(view);
+                    }
+                    self.raw.destroy_texture(raw);
+                },
+                queue::TempResource::BindGroup(raw) => unsafe {
+                    self.raw.destroy_bind_group(raw);
+                },
+            }
+        }
     }
 
     /// Wait for idle and remove resources that we can, before we die.
@@ -3303,6 +3705,9 @@ impl<A: HalApi> Device<A> {
     pub(crate) fn dispose(self) {
         self.pending_writes.dispose(&self.raw);
         self.command_allocator.into_inner().dispose(&self.raw);
+        if let Some(indirect_validation) = self.indirect_validation {
+            indirect_validation.dispose(&self.raw);
+        }
         unsafe {
             self.raw.destroy_buffer(self.zero_buffer);
             self.raw.destroy_fence(self.fence);
@@ -3318,3 +3723,52 @@ impl<A: HalApi> crate::resource::Resource for Device<A> {
         &self.life_guard
     }
 }
+
+/// The memory to create a buffer or texture in.
+pub(super) enum ResourceMemory<'a, A: HalApi> {
+    /// Memory allocated for the resource alone.
+    Dedicated,
+    Placed(HeapPlacementRequest<'a, A>),
+    /// No memory, it is bound to the pages of the resource later on.
+    Sparse,
+}
+
+/// A request to create a buffer or texture at `offset` in `heap`.
+pub(super) struct HeapPlacementRequest<'a, A: HalApi> {
+    pub heap_id: id::Valid<id::HeapId>,
+    pub heap: &'a resource::Heap<A>,
+    pub offset: wgt::BufferAddress,
+}
+
+impl<A: HalApi> HeapPlacementRequest<'_, A> {
+    fn map_err<E>(&self, error: hal::HeapPlacementError) -> E
+    where
+        E: From<DeviceError> + From<resource::PlacementError>,
+    {
+        use resource::PlacementError as Pe;
+
+        match error {
+            hal::HeapPlacementError::Misaligned(alignment) => E::from(Pe::Misaligned {
+                offset: self.offset,
+                alignment,
+            }),
+            hal::HeapPlacementError::OutOfBounds(size) => E::from(Pe::OutOfBounds {
+                offset: self.offset,
+                size,
+                heap_size: self.heap.size,
+            }),
+            hal::HeapPlacementError::IncompatibleMemory => E::from(Pe::IncompatibleMemory),
+            hal::HeapPlacementError::Device(error) => E::from(DeviceError::from(error)),
+        }
+    }
+
+    fn finish(self, size: wgt::BufferAddress) -> resource::HeapPlacement {
+        resource::HeapPlacement {
+            heap_id: Stored {
+                value: self.heap_id,
+                ref_count: self.heap.life_guard.add_ref(),
+            },
+            range: self.offset..self.offset + size,
+        }
+    }
+}
@@ -1,7 +1,9 @@
 use crate::id;
 use std::ops::Range;
 #[cfg(feature = "trace")]
-use std::{borrow::Cow, io::Write as _};
+use std::{borrow::Cow, io::Write};
+#[cfg(feature = "replay")]
+use std::{collections::HashMap, io::Read};
 
 //TODO: consider a readable Id that doesn't include the backend
 
@@ -9,6 +11,59 @@ type FileName = String;
 
 pub const FILE_NAME: &str = "trace.ron";
 
+/// Extension of binary trace files.
+pub const BINARY_EXTENSION: &str = "wgputrace";
+
+/// Identifies binary traces, followed by the version and the flags as
+/// little endian `u32`s.
+#[cfg(any(feature = "trace", feature = "replay"))]
+const BINARY_MAGIC: [u8; 8] = *b"WGPUTRCE";
+#[cfg(any(feature = "trace", feature = "replay"))]
+const BINARY_VERSION: u32 = 1;
+/// Everything after the header is deflate compressed.
+#[cfg(any(feature = "trace", feature = "replay"))]
+const BINARY_FLAG_COMPRESSED: u32 = 1;
+
+/// Record tags of binary traces. Every tag is followed by a little endian
+/// `u64` length and as many bytes of payload.
+#[cfg(any(feature = "trace", feature = "replay"))]
+const RECORD_ACTION: u8 = 0;
+/// A data file, named by a `u32` length prefixed string at the start of the
+/// payload. Identical data files are only stored once.
+#[cfg(any(feature = "trace", feature = "replay"))]
+const RECORD_DATA: u8 = 1;
+
+/// How a trace is stored.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TraceFormat {
+    /// A directory with a `trace.ron` file listing the actions, and a file
+    /// for every piece of data they refer to.
+    Ron,
+    /// A single file of length prefixed `bincode` records, holding both the
+    /// actions and their data.
+    Binary { compressed: bool },
+}
+
+impl TraceFormat {
+    /// Picks the format of a trace from its path.
+    ///
+    /// Paths ending in `.wgputrace` are binary traces, and `.wgputrace.gz`
+    /// compressed binary traces. Anything else is a directory for a RON trace.
+    pub fn from_path(path: &std::path::Path) -> Self {
+        let name = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .unwrap_or("");
+        if name.ends_with(&format!(".{BINARY_EXTENSION}")) {
+            Self::Binary { compressed: false }
+        } else if name.ends_with(&format!(".{BINARY_EXTENSION}.gz")) {
+            Self::Binary { compressed: true }
+        } else {
+            Self::Ron
+        }
+    }
+}
+
 #[cfg(feature = "trace")]
 pub(crate) fn new_render_bundle_encoder_descriptor<'a>(
     label: crate::Label<'a>,
@@ -32,7 +87,7 @@ pub(crate) fn new_render_bundle_encoder_descriptor<'a>(
 }
 
 #[allow(clippy::large_enum_variant)]
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 #[cfg_attr(feature = "trace", derive(serde::Serialize))]
 #[cfg_attr(feature = "replay", derive(serde::Deserialize))]
 pub enum Action<'a> {
@@ -58,6 +113,27 @@ pub enum Action<'a> {
     DestroyTextureView(id::TextureViewId),
     CreateSampler(id::SamplerId, crate::resource::SamplerDescriptor<'a>),
     DestroySampler(id::SamplerId),
+    CreateHeap(id::HeapId, crate::resource::HeapDescriptor<'a>),
+    DestroyHeap(id::HeapId),
+    CreateExternalSemaphore(
+        id::ExternalSemaphoreId,
+        crate::resource::ExternalSemaphoreDescriptor<'a>,
+    ),
+    DestroyExternalSemaphore(id::ExternalSemaphoreId),
+    CreateBufferInHeap {
+        id: id::BufferId,
+        desc: crate::resource::BufferDescriptor<'a>,
+        heap: id::HeapId,
+        offset: wgt::BufferAddress,
+    },
+    CreateTextureInHeap {
+        id: id::TextureId,
+        desc: crate::resource::TextureDescriptor<'a>,
+        heap: id::HeapId,
+        offset: wgt::BufferAddress,
+    },
+    CreateSparseBuffer(id::BufferId, crate::resource::BufferDescriptor<'a>),
+    CreateSparseTexture(id::TextureId, crate::resource::TextureDescriptor<'a>),
     GetSurfaceTexture {
         id: id::TextureId,
         parent_id: id::SurfaceId,
@@ -99,17 +175,39 @@ pub enum Action<'a> {
         implicit_context: Option<super::ImplicitPipelineContext>,
     },
     DestroyRenderPipeline(id::RenderPipelineId),
+    CreatePipelineCache {
+        id: id::PipelineCacheId,
+        desc: crate::pipeline::PipelineCacheDescriptor<'a>,
+    },
+    DestroyPipelineCache(id::PipelineCacheId),
     CreateRenderBundle {
         id: id::RenderBundleId,
         desc: crate::command::RenderBundleEncoderDescriptor<'a>,
         base: crate::command::BasePass<crate::command::RenderCommand>,
     },
     DestroyRenderBundle(id::RenderBundleId),
+    CreateComputeBundle {
+        id: id::ComputeBundleId,
+        desc: crate::command::ComputeBundleEncoderDescriptor<'a>,
+        base: crate::command::BasePass<crate::command::ComputeCommand>,
+    },
+    DestroyComputeBundle(id::ComputeBundleId),
     CreateQuerySet {
         id: id::QuerySetId,
         desc: crate::resource::QuerySetDescriptor<'a>,
     },
     DestroyQuerySet(id::QuerySetId),
+    CreateBlas {
+        id: id::BlasId,
+        desc: crate::ray_tracing::BlasDescriptor<'a>,
+        sizes: wgt::BlasGeometrySizeDescriptors,
+    },
+    DestroyBlas(id::BlasId),
+    CreateTlas {
+        id: id::TlasId,
+        desc: crate::ray_tracing::TlasDescriptor<'a>,
+    },
+    DestroyTlas(id::TlasId),
     WriteBuffer {
         id: id::BufferId,
         data: FileName,
@@ -122,10 +220,14 @@ pub enum Action<'a> {
         layout: wgt::ImageDataLayout,
         size: wgt::Extent3d,
     },
+    UpdateSparseBindings {
+        buffer_bindings: Vec<wgt::SparseBufferBinding<id::BufferId, id::HeapId>>,
+        texture_bindings: Vec<wgt::SparseTextureBinding<id::TextureId, id::HeapId>>,
+    },
     Submit(crate::SubmissionIndex, Vec<Command>),
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 #[cfg_attr(feature = "trace", derive(serde::Serialize))]
 #[cfg_attr(feature = "replay", derive(serde::Deserialize))]
 pub enum Command {
@@ -160,6 +262,10 @@ pub enum Command {
         dst: id::TextureId,
         subresource_range: wgt::ImageSubresourceRange,
     },
+    TransitionResources {
+        buffer_transitions: Vec<wgt::BufferTransition<id::BufferId>>,
+        texture_transitions: Vec<wgt::TextureTransition<id::TextureId>>,
+    },
     WriteTimestamp {
         query_set_id: id::QuerySetId,
         query_index: u32,
@@ -185,53 +291,309 @@ pub enum Command {
         timestamp_writes: Option<crate::command::RenderPassTimestampWrites>,
         occlusion_query_set_id: Option<id::QuerySetId>,
     },
+    BuildAccelerationStructuresUnsafeTlas {
+        blas: Vec<crate::ray_tracing::BlasBuildEntry>,
+        tlas: Vec<crate::ray_tracing::TlasBuildEntry>,
+    },
+}
+
+#[cfg(feature = "trace")]
+enum Output {
+    Ron {
+        file: std::fs::File,
+        config: ron::ser::PrettyConfig,
+    },
+    Binary {
+        writer: Box<dyn Write + Send>,
+        /// Names of the data files written so far, by kind and contents.
+        data_names: crate::FastHashMap<(String, DataHash), String>,
+    },
+}
+
+/// Hash of the contents of a data file, used to store identical files once.
+#[cfg(feature = "trace")]
+type DataHash = (usize, u64, u64);
+
+#[cfg(feature = "trace")]
+fn hash_data(data: &[u8]) -> DataHash {
+    use std::hash::{Hash as _, Hasher as _};
+
+    let hash = |seed: u8| {
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        seed.hash(&mut hasher);
+        data.hash(&mut hasher);
+        hasher.finish()
+    };
+    (data.len(), hash(0), hash(1))
+}
+
+#[cfg(feature = "trace")]
+fn write_record(writer: &mut dyn Write, tag: u8, parts: &[&[u8]]) -> std::io::Result<()> {
+    let len: usize = parts.iter().map(|part| part.len()).sum();
+    writer.write_all(&[tag])?;
+    writer.write_all(&(len as u64).to_le_bytes())?;
+    for part in parts {
+        writer.write_all(part)?;
+    }
+    Ok(())
+}
+
+#[cfg(feature = "trace")]
+impl std::fmt::Debug for Output {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match *self {
+            Self::Ron { .. } => f.write_str("Ron"),
+            Self::Binary { .. } => f.write_str("Binary"),
+        }
+    }
 }
 
 #[cfg(feature = "trace")]
 #[derive(Debug)]
 pub struct Trace {
     path: std::path::PathBuf,
-    file: std::fs::File,
-    config: ron::ser::PrettyConfig,
+    output: Output,
     binary_id: usize,
 }
 
 #[cfg(feature = "trace")]
 impl Trace {
-    pub fn new(path: &std::path::Path) -> Result<Self, std::io::Error> {
-        log::info!("Tracing into '{:?}'", path);
-        let mut file = std::fs::File::create(path.join(FILE_NAME))?;
-        file.write_all(b"[\n")?;
+    /// Starts a trace at `path`, which is a directory for RON traces and a
+    /// file for binary traces.
+    pub fn new(path: &std::path::Path, format: TraceFormat) -> Result<Self, std::io::Error> {
+        log::info!("Tracing into '{:?}' as {:?}", path, format);
+        let output = match format {
+            TraceFormat::Ron => {
+                let mut file = std::fs::File::create(path.join(FILE_NAME))?;
+                file.write_all(b"[\n")?;
+                Output::Ron {
+                    file,
+                    config: ron::ser::PrettyConfig::default(),
+                }
+            }
+            TraceFormat::Binary { compressed } => {
+                let mut file = std::io::BufWriter::new(std::fs::File::create(path)?);
+                let flags = if compressed {
+                    BINARY_FLAG_COMPRESSED
+                } else {
+                    0
+                };
+                file.write_all(&BINARY_MAGIC)?;
+                file.write_all(&BINARY_VERSION.to_le_bytes())?;
+                file.write_all(&flags.to_le_bytes())?;
+                let writer: Box<dyn Write + Send> = if compressed {
+                    Box::new(flate2::write::DeflateEncoder::new(
+                        file,
+                        flate2::Compression::fast(),
+                    ))
+                } else {
+                    Box::new(file)
+                };
+                Output::Binary {
+                    writer,
+                    data_names: Default::default(),
+                }
+            }
+        };
         Ok(Self {
             path: path.to_path_buf(),
-            file,
-            config: ron::ser::PrettyConfig::default(),
+            output,
             binary_id: 0,
         })
     }
 
+    /// Stores a data file referred to by an action, and returns its name.
     pub fn make_binary(&mut self, kind: &str, data: &[u8]) -> String {
-        self.binary_id += 1;
-        let name = format!("data{}.{}", self.binary_id, kind);
-        let _ = std::fs::write(self.path.join(&name), data);
-        name
-    }
-
-    pub(crate) fn add(&mut self, action: Action) {
-        match ron::ser::to_string_pretty(&action, self.config.clone()) {
-            Ok(string) => {
-                let _ = writeln!(self.file, "{},", string);
+        match self.output {
+            Output::Ron { .. } => {
+                self.binary_id += 1;
+                let name = format!("data{}.{}", self.binary_id, kind);
+                let _ = std::fs::write(self.path.join(&name), data);
+                name
             }
-            Err(e) => {
-                log::warn!("RON serialization failure: {:?}", e);
+            Output::Binary {
+                ref mut writer,
+                ref mut data_names,
+            } => {
+                let key = (kind.to_string(), hash_data(data));
+                if let Some(name) = data_names.get(&key) {
+                    return name.clone();
+                }
+                self.binary_id += 1;
+                let name = format!("data{}.{}", self.binary_id, kind);
+                let name_len = (name.len() as u32).to_le_bytes();
+                if let Err(e) =
+                    write_record(writer, RECORD_DATA, &[&name_len, name.as_bytes(), data])
+                {
+                    log::warn!("Trace write failure: {:?}", e);
+                }
+                data_names.insert(key, name.clone());
+                name
             }
         }
     }
+
+    pub fn add(&mut self, action: Action) {
+        match self.output {
+            Output::Ron {
+                ref mut file,
+                ref config,
+            } => match ron::ser::to_string_pretty(&action, config.clone()) {
+                Ok(string) => {
+                    let _ = writeln!(file, "{},", string);
+                }
+                Err(e) => {
+                    log::warn!("RON serialization failure: {:?}", e);
+                }
+            },
+            Output::Binary { ref mut writer, .. } => match bincode::serialize(&action) {
+                Ok(bytes) => {
+                    if let Err(e) = write_record(writer, RECORD_ACTION, &[&bytes]) {
+                        log::warn!("Trace write failure: {:?}", e);
+                    }
+                }
+                Err(e) => {
+                    log::warn!("Binary serialization failure: {:?}", e);
+                }
+            },
+        }
+    }
 }
 
 #[cfg(feature = "trace")]
 impl Drop for Trace {
     fn drop(&mut self) {
-        let _ = self.file.write_all(b"]");
+        match self.output {
+            Output::Ron { ref mut file, .. } => {
+                let _ = file.write_all(b"]");
+            }
+            Output::Binary { ref mut writer, .. } => {
+                let _ = writer.flush();
+            }
+        }
+    }
+}
+
+/// Contents of a binary trace.
+#[cfg(feature = "replay")]
+#[derive(Debug)]
+pub struct BinaryTrace {
+    pub actions: Vec<Action<'static>>,
+    /// Data files referred to by the actions, by name.
+    pub data: HashMap<String, Vec<u8>>,
+}
+
+#[cfg(feature = "replay")]
+impl BinaryTrace {
+    /// Reads a binary trace file.
+    pub fn load(path: &std::path::Path) -> Result<Self, std::io::Error> {
+        use std::io::{Error, ErrorKind};
+
+        let invalid = |message| Error::new(ErrorKind::InvalidData, message);
+        let mut file = std::io::BufReader::new(std::fs::File::open(path)?);
+        let mut header = [0; 16];
+        file.read_exact(&mut header)?;
+        if header[..8] != BINARY_MAGIC {
+            return Err(invalid("not a binary trace".to_string()));
+        }
+        let version = u32::from_le_bytes(header[8..12].try_into().unwrap());
+        if version != BINARY_VERSION {
+            return Err(invalid(format!("unsupported trace version {version}")));
+        }
+        let flags = u32::from_le_bytes(header[12..16].try_into().unwrap());
+        let mut reader: Box<dyn Read> = if flags & BINARY_FLAG_COMPRESSED != 0 {
+            Box::new(flate2::read::DeflateDecoder::new(file))
+        } else {
+            Box::new(file)
+        };
+
+        let mut trace = Self {
+            actions: Vec::new(),
+            data: HashMap::new(),
+        };
+        loop {
+            let mut tag = [0; 1];
+            match reader.read_exact(&mut tag) {
+                Ok(()) => {}
+                Err(e) if e.kind() == ErrorKind::UnexpectedEof => break,
+                Err(e) => return Err(e),
+            }
+            let mut len = [0; 8];
+            reader.read_exact(&mut len)?;
+            let len = usize::try_from(u64::from_le_bytes(len))
+                .map_err(|_| invalid("record too large".to_string()))?;
+            let mut payload = vec![0; len];
+            reader.read_exact(&mut payload)?;
+            match tag[0] {
+                RECORD_ACTION => {
+                    let action =
+                        bincode::deserialize(&payload).map_err(|e| invalid(e.to_string()))?;
+                    trace.actions.push(action);
+                }
+                RECORD_DATA => {
+                    let name_len = payload
+                        .get(..4)
+                        .map(|bytes| u32::from_le_bytes(bytes.try_into().unwrap()) as usize)
+                        .filter(|&name_len| 4 + name_len <= payload.len())
+                        .ok_or_else(|| invalid("truncated data record".to_string()))?;
+                    let name = String::from_utf8(payload[4..4 + name_len].to_vec())
+                        .map_err(|e| invalid(e.to_string()))?;
+                    payload.drain(..4 + name_len);
+                    trace.data.insert(name, payload);
+                }
+                other => return Err(invalid(format!("unknown record {other}"))),
+            }
+        }
+        Ok(trace)
+    }
+}
+
+#[cfg(all(feature = "trace", feature = "replay"))]
+#[test]
+fn binary_round_trip() {
+    for (extension, compressed) in [("wgputrace", false), ("wgputrace.gz", true)] {
+        let path = std::env::temp_dir().join(format!(
+            "wgpu-core-binary-round-trip-{}.{extension}",
+            std::process::id()
+        ));
+        let format = TraceFormat::from_path(&path);
+        assert_eq!(format, TraceFormat::Binary { compressed });
+
+        let buffer = id::TypedId::zip(1, 1, wgt::Backend::Empty);
+        let actions = {
+            let mut trace = Trace::new(&path, format).unwrap();
+            let first = trace.make_binary("bin", &[1, 2, 3, 4]);
+            let second = trace.make_binary("bin", &[1, 2, 3, 4]);
+            assert_eq!(first, second);
+            let actions = [
+                Action::CreateBuffer(
+                    buffer,
+                    crate::resource::BufferDescriptor {
+                        label: Some(Cow::Borrowed("buffer")),
+                        size: 4,
+                        usage: wgt::BufferUsages::COPY_DST,
+                        mapped_at_creation: false,
+                    },
+                ),
+                Action::WriteBuffer {
+                    id: buffer,
+                    data: first,
+                    range: 0..4,
+                    queued: true,
+                },
+                Action::DestroyBuffer(buffer),
+            ];
+            let debug = format!("{actions:?}");
+            for action in actions {
+                trace.add(action);
+            }
+            debug
+        };
+
+        let trace = BinaryTrace::load(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+        assert_eq!(format!("{:?}", trace.actions), actions);
+        assert_eq!(trace.data.len(), 1);
+        assert_eq!(trace.data["data1.bin"], [1, 2, 3, 4]);
     }
 }
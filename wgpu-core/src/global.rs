@@ -21,6 +21,8 @@ pub struct GlobalReport {
     pub dx11: Option<HubReport>,
     #[cfg(feature = "gles")]
     pub gl: Option<HubReport>,
+    #[cfg(feature = "cpu")]
+    pub cpu: Option<HubReport>,
 }
 
 pub struct Global<G: GlobalIdentityHandlerFactory> {
@@ -114,6 +116,12 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
             } else {
                 None
             },
+            #[cfg(feature = "cpu")]
+            cpu: if self.instance.cpu.is_some() {
+                Some(self.hubs.cpu.generate_report())
+            } else {
+                None
+            },
         }
     }
 }
@@ -145,6 +153,10 @@ impl<G: GlobalIdentityHandlerFactory> Drop for Global<G> {
         {
             self.hubs.gl.clear(&mut surface_guard, true);
         }
+        #[cfg(feature = "cpu")]
+        {
+            self.hubs.cpu.clear(&mut surface_guard, true);
+        }
 
         // destroy surfaces
         for element in surface_guard.map.drain(..) {
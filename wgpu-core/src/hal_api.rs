@@ -155,3 +155,27 @@ impl HalApi for hal::api::Gles {
         surface.gl.as_mut()
     }
 }
+
+#[cfg(feature = "cpu")]
+impl HalApi for hal::api::Cpu {
+    const VARIANT: Backend = Backend::Cpu;
+    fn create_instance_from_hal(name: &str, hal_instance: Self::Instance) -> Instance {
+        Instance {
+            name: name.to_owned(),
+            cpu: Some(hal_instance),
+            ..Default::default()
+        }
+    }
+    fn instance_as_hal(instance: &Instance) -> Option<&Self::Instance> {
+        instance.cpu.as_ref()
+    }
+    fn hub<G: GlobalIdentityHandlerFactory>(global: &Global<G>) -> &Hub<Self, G> {
+        &global.hubs.cpu
+    }
+    fn get_surface(surface: &Surface) -> Option<&HalSurface<Self>> {
+        surface.cpu.as_ref()
+    }
+    fn get_surface_mut(surface: &mut Surface) -> Option<&mut HalSurface<Self>> {
+        surface.cpu.as_mut()
+    }
+}
@@ -157,15 +157,18 @@ flagged as errors as well.
 
 use crate::{
     binding_model::{BindGroup, BindGroupLayout, PipelineLayout},
-    command::{CommandBuffer, RenderBundle},
+    command::{CommandBuffer, ComputeBundle, RenderBundle},
     device::Device,
     hal_api::HalApi,
     id,
     identity::GlobalIdentityHandlerFactory,
     instance::{Adapter, HalSurface, Instance, Surface},
-    pipeline::{ComputePipeline, RenderPipeline, ShaderModule},
+    pipeline::{ComputePipeline, PipelineCache, RenderPipeline, ShaderModule},
     registry::Registry,
-    resource::{Buffer, QuerySet, Sampler, StagingBuffer, Texture, TextureClearMode, TextureView},
+    resource::{
+        Blas, Buffer, ExternalSemaphore, Heap, QuerySet, Sampler, StagingBuffer, Texture,
+        TextureClearMode, TextureView, Tlas,
+    },
     storage::{Element, Storage, StorageReport},
 };
 
@@ -195,18 +198,24 @@ use std::{fmt::Debug, marker::PhantomData};
 /// - [`Device`]
 /// - [`CommandBuffer`]
 /// - [`RenderBundle`]
+/// - [`ComputeBundle`]
 /// - [`PipelineLayout`]
 /// - [`BindGroupLayout`]
 /// - [`BindGroup`]
 /// - [`ComputePipeline`]
 /// - [`RenderPipeline`]
 /// - [`ShaderModule`]
+/// - [`PipelineCache`]
 /// - [`Buffer`]
 /// - [`StagingBuffer`]
 /// - [`Texture`]
 /// - [`TextureView`]
 /// - [`Sampler`]
 /// - [`QuerySet`]
+/// - [`Blas`]
+/// - [`Tlas`]
+/// - [`Heap`]
+/// - [`ExternalSemaphore`]
 ///
 /// That is, you may only acquire a new lock on a `Hub` field if it
 /// appears in the list after all the other fields you're already
@@ -239,9 +248,13 @@ impl<A: HalApi> Access<CommandBuffer<A>> for Root {}
 impl<A: HalApi> Access<CommandBuffer<A>> for Device<A> {}
 impl<A: HalApi> Access<RenderBundle<A>> for Device<A> {}
 impl<A: HalApi> Access<RenderBundle<A>> for CommandBuffer<A> {}
+impl<A: HalApi> Access<ComputeBundle<A>> for Device<A> {}
+impl<A: HalApi> Access<ComputeBundle<A>> for CommandBuffer<A> {}
+impl<A: HalApi> Access<ComputeBundle<A>> for RenderBundle<A> {}
 impl<A: HalApi> Access<PipelineLayout<A>> for Root {}
 impl<A: HalApi> Access<PipelineLayout<A>> for Device<A> {}
 impl<A: HalApi> Access<PipelineLayout<A>> for RenderBundle<A> {}
+impl<A: HalApi> Access<PipelineLayout<A>> for ComputeBundle<A> {}
 impl<A: HalApi> Access<BindGroupLayout<A>> for Root {}
 impl<A: HalApi> Access<BindGroupLayout<A>> for Device<A> {}
 impl<A: HalApi> Access<BindGroupLayout<A>> for PipelineLayout<A> {}
@@ -258,6 +271,9 @@ impl<A: HalApi> Access<RenderPipeline<A>> for BindGroup<A> {}
 impl<A: HalApi> Access<RenderPipeline<A>> for ComputePipeline<A> {}
 impl<A: HalApi> Access<ShaderModule<A>> for Device<A> {}
 impl<A: HalApi> Access<ShaderModule<A>> for BindGroupLayout<A> {}
+impl<A: HalApi> Access<PipelineCache<A>> for Root {}
+impl<A: HalApi> Access<PipelineCache<A>> for Device<A> {}
+impl<A: HalApi> Access<PipelineCache<A>> for ShaderModule<A> {}
 impl<A: HalApi> Access<Buffer<A>> for Root {}
 impl<A: HalApi> Access<Buffer<A>> for Device<A> {}
 impl<A: HalApi> Access<Buffer<A>> for BindGroupLayout<A> {}
@@ -282,6 +298,26 @@ impl<A: HalApi> Access<QuerySet<A>> for CommandBuffer<A> {}
 impl<A: HalApi> Access<QuerySet<A>> for RenderPipeline<A> {}
 impl<A: HalApi> Access<QuerySet<A>> for ComputePipeline<A> {}
 impl<A: HalApi> Access<QuerySet<A>> for Sampler<A> {}
+impl<A: HalApi> Access<Blas<A>> for Root {}
+impl<A: HalApi> Access<Blas<A>> for Device<A> {}
+impl<A: HalApi> Access<Blas<A>> for CommandBuffer<A> {}
+impl<A: HalApi> Access<Blas<A>> for Buffer<A> {}
+impl<A: HalApi> Access<Blas<A>> for Sampler<A> {}
+impl<A: HalApi> Access<Blas<A>> for QuerySet<A> {}
+impl<A: HalApi> Access<Tlas<A>> for Root {}
+impl<A: HalApi> Access<Tlas<A>> for Device<A> {}
+impl<A: HalApi> Access<Tlas<A>> for CommandBuffer<A> {}
+impl<A: HalApi> Access<Tlas<A>> for Buffer<A> {}
+impl<A: HalApi> Access<Tlas<A>> for Sampler<A> {}
+impl<A: HalApi> Access<Tlas<A>> for QuerySet<A> {}
+impl<A: HalApi> Access<Tlas<A>> for Blas<A> {}
+impl<A: HalApi> Access<Heap<A>> for Root {}
+impl<A: HalApi> Access<Heap<A>> for Device<A> {}
+impl<A: HalApi> Access<Heap<A>> for Texture<A> {}
+impl<A: HalApi> Access<Heap<A>> for Tlas<A> {}
+impl<A: HalApi> Access<ExternalSemaphore<A>> for Root {}
+impl<A: HalApi> Access<ExternalSemaphore<A>> for Device<A> {}
+impl<A: HalApi> Access<ExternalSemaphore<A>> for CommandBuffer<A> {}
 
 #[cfg(any(debug_assertions, feature = "strict_asserts"))]
 thread_local! {
@@ -367,13 +403,19 @@ pub struct HubReport {
     pub bind_groups: StorageReport,
     pub command_buffers: StorageReport,
     pub render_bundles: StorageReport,
+    pub compute_bundles: StorageReport,
     pub render_pipelines: StorageReport,
     pub compute_pipelines: StorageReport,
+    pub pipeline_caches: StorageReport,
     pub query_sets: StorageReport,
     pub buffers: StorageReport,
     pub textures: StorageReport,
     pub texture_views: StorageReport,
     pub samplers: StorageReport,
+    pub blas_s: StorageReport,
+    pub tlas_s: StorageReport,
+    pub heaps: StorageReport,
+    pub external_semaphores: StorageReport,
 }
 
 impl HubReport {
@@ -457,14 +499,20 @@ pub struct Hub<A: HalApi, F: GlobalIdentityHandlerFactory> {
     pub bind_groups: Registry<BindGroup<A>, id::BindGroupId, F>,
     pub command_buffers: Registry<CommandBuffer<A>, id::CommandBufferId, F>,
     pub render_bundles: Registry<RenderBundle<A>, id::RenderBundleId, F>,
+    pub compute_bundles: Registry<ComputeBundle<A>, id::ComputeBundleId, F>,
     pub render_pipelines: Registry<RenderPipeline<A>, id::RenderPipelineId, F>,
     pub compute_pipelines: Registry<ComputePipeline<A>, id::ComputePipelineId, F>,
+    pub pipeline_caches: Registry<PipelineCache<A>, id::PipelineCacheId, F>,
     pub query_sets: Registry<QuerySet<A>, id::QuerySetId, F>,
     pub buffers: Registry<Buffer<A>, id::BufferId, F>,
     pub staging_buffers: Registry<StagingBuffer<A>, id::StagingBufferId, F>,
     pub textures: Registry<Texture<A>, id::TextureId, F>,
     pub texture_views: Registry<TextureView<A>, id::TextureViewId, F>,
     pub samplers: Registry<Sampler<A>, id::SamplerId, F>,
+    pub blas_s: Registry<Blas<A>, id::BlasId, F>,
+    pub tlas_s: Registry<Tlas<A>, id::TlasId, F>,
+    pub heaps: Registry<Heap<A>, id::HeapId, F>,
+    pub external_semaphores: Registry<ExternalSemaphore<A>, id::ExternalSemaphoreId, F>,
 }
 
 impl<A: HalApi, F: GlobalIdentityHandlerFactory> Hub<A, F> {
@@ -478,14 +526,20 @@ impl<A: HalApi, F: GlobalIdentityHandlerFactory> Hub<A, F> {
             bind_groups: Registry::new(A::VARIANT, factory),
             command_buffers: Registry::new(A::VARIANT, factory),
             render_bundles: Registry::new(A::VARIANT, factory),
+            compute_bundles: Registry::new(A::VARIANT, factory),
             render_pipelines: Registry::new(A::VARIANT, factory),
             compute_pipelines: Registry::new(A::VARIANT, factory),
+            pipeline_caches: Registry::new(A::VARIANT, factory),
             query_sets: Registry::new(A::VARIANT, factory),
             buffers: Registry::new(A::VARIANT, factory),
             staging_buffers: Registry::new(A::VARIANT, factory),
             textures: Registry::new(A::VARIANT, factory),
             texture_views: Registry::new(A::VARIANT, factory),
             samplers: Registry::new(A::VARIANT, factory),
+            blas_s: Registry::new(A::VARIANT, factory),
+            tlas_s: Registry::new(A::VARIANT, factory),
+            heaps: Registry::new(A::VARIANT, factory),
+            external_semaphores: Registry::new(A::VARIANT, factory),
         }
     }
 
@@ -557,6 +611,23 @@ impl<A: HalApi, F: GlobalIdentityHandlerFactory> Hub<A, F> {
                 devices[buffer.device_id.value].destroy_buffer(buffer);
             }
         }
+        // the resources placed in heaps are gone now
+        for element in self.heaps.data.write().map.drain(..) {
+            if let Element::Occupied(heap, _) = element {
+                let device = &devices[heap.device_id.value];
+                unsafe {
+                    device.raw.destroy_heap(heap.raw);
+                }
+            }
+        }
+        for element in self.external_semaphores.data.write().map.drain(..) {
+            if let Element::Occupied(semaphore, _) = element {
+                let device = &devices[semaphore.device_id.value];
+                unsafe {
+                    device.raw.destroy_external_semaphore(semaphore.raw);
+                }
+            }
+        }
         for element in self.bind_groups.data.write().map.drain(..) {
             if let Element::Occupied(bind_group, _) = element {
                 let device = &devices[bind_group.device_id.value];
@@ -608,6 +679,14 @@ impl<A: HalApi, F: GlobalIdentityHandlerFactory> Hub<A, F> {
                 }
             }
         }
+        for element in self.pipeline_caches.data.write().map.drain(..) {
+            if let Element::Occupied(cache, _) = element {
+                let device = &devices[cache.device_id.value];
+                unsafe {
+                    device.raw.destroy_pipeline_cache(cache.raw);
+                }
+            }
+        }
 
         for element in surface_guard.map.iter_mut() {
             if let Element::Occupied(ref mut surface, _epoch) = *element {
@@ -639,6 +718,23 @@ impl<A: HalApi, F: GlobalIdentityHandlerFactory> Hub<A, F> {
             }
         }
 
+        for element in self.tlas_s.data.write().map.drain(..) {
+            if let Element::Occupied(tlas, _) = element {
+                let device = &devices[tlas.device_id.value];
+                unsafe {
+                    device.raw.destroy_acceleration_structure(tlas.raw);
+                }
+            }
+        }
+        for element in self.blas_s.data.write().map.drain(..) {
+            if let Element::Occupied(blas, _) = element {
+                let device = &devices[blas.device_id.value];
+                unsafe {
+                    device.raw.destroy_acceleration_structure(blas.raw);
+                }
+            }
+        }
+
         for element in devices.map.drain(..) {
             if let Element::Occupied(device, _) = element {
                 device.dispose();
@@ -675,13 +771,19 @@ impl<A: HalApi, F: GlobalIdentityHandlerFactory> Hub<A, F> {
             bind_groups: self.bind_groups.data.read().generate_report(),
             command_buffers: self.command_buffers.data.read().generate_report(),
             render_bundles: self.render_bundles.data.read().generate_report(),
+            compute_bundles: self.compute_bundles.data.read().generate_report(),
             render_pipelines: self.render_pipelines.data.read().generate_report(),
             compute_pipelines: self.compute_pipelines.data.read().generate_report(),
+            pipeline_caches: self.pipeline_caches.data.read().generate_report(),
             query_sets: self.query_sets.data.read().generate_report(),
             buffers: self.buffers.data.read().generate_report(),
             textures: self.textures.data.read().generate_report(),
             texture_views: self.texture_views.data.read().generate_report(),
             samplers: self.samplers.data.read().generate_report(),
+            blas_s: self.blas_s.data.read().generate_report(),
+            tlas_s: self.tlas_s.data.read().generate_report(),
+            heaps: self.heaps.data.read().generate_report(),
+            external_semaphores: self.external_semaphores.data.read().generate_report(),
         }
     }
 }
@@ -697,12 +799,15 @@ pub struct Hubs<F: GlobalIdentityHandlerFactory> {
     pub(crate) dx11: Hub<hal::api::Dx11, F>,
     #[cfg(feature = "gles")]
     pub(crate) gl: Hub<hal::api::Gles, F>,
+    #[cfg(feature = "cpu")]
+    pub(crate) cpu: Hub<hal::api::Cpu, F>,
     #[cfg(all(
         not(all(feature = "vulkan", not(target_arch = "wasm32"))),
         not(all(feature = "metal", any(target_os = "macos", target_os = "ios"))),
         not(all(feature = "dx12", windows)),
         not(all(feature = "dx11", windows)),
         not(feature = "gles"),
+        not(feature = "cpu"),
     ))]
     pub(crate) empty: Hub<hal::api::Empty, F>,
 }
@@ -720,12 +825,15 @@ impl<F: GlobalIdentityHandlerFactory> Hubs<F> {
             dx11: Hub::new(factory),
             #[cfg(feature = "gles")]
             gl: Hub::new(factory),
+            #[cfg(feature = "cpu")]
+            cpu: Hub::new(factory),
             #[cfg(all(
                 not(all(feature = "vulkan", not(target_arch = "wasm32"))),
                 not(all(feature = "metal", any(target_os = "macos", target_os = "ios"))),
                 not(all(feature = "dx12", windows)),
                 not(all(feature = "dx11", windows)),
                 not(feature = "gles"),
+                not(feature = "cpu"),
             ))]
             empty: Hub::new(factory),
         }
@@ -113,6 +113,7 @@ impl<T> Id<T> {
             3 => Backend::Dx12,
             4 => Backend::Dx11,
             5 => Backend::Gl,
+            7 => Backend::Cpu,
             _ => unreachable!(),
         }
     }
@@ -218,6 +219,8 @@ pub type StagingBufferId = Id<crate::resource::StagingBuffer<Dummy>>;
 pub type TextureViewId = Id<crate::resource::TextureView<Dummy>>;
 pub type TextureId = Id<crate::resource::Texture<Dummy>>;
 pub type SamplerId = Id<crate::resource::Sampler<Dummy>>;
+pub type HeapId = Id<crate::resource::Heap<Dummy>>;
+pub type ExternalSemaphoreId = Id<crate::resource::ExternalSemaphore<Dummy>>;
 // Binding model
 pub type BindGroupLayoutId = Id<crate::binding_model::BindGroupLayout<Dummy>>;
 pub type PipelineLayoutId = Id<crate::binding_model::PipelineLayout<Dummy>>;
@@ -226,6 +229,7 @@ pub type BindGroupId = Id<crate::binding_model::BindGroup<Dummy>>;
 pub type ShaderModuleId = Id<crate::pipeline::ShaderModule<Dummy>>;
 pub type RenderPipelineId = Id<crate::pipeline::RenderPipeline<Dummy>>;
 pub type ComputePipelineId = Id<crate::pipeline::ComputePipeline<Dummy>>;
+pub type PipelineCacheId = Id<crate::pipeline::PipelineCache<Dummy>>;
 // Command
 pub type CommandEncoderId = CommandBufferId;
 pub type CommandBufferId = Id<crate::command::CommandBuffer<Dummy>>;
@@ -233,7 +237,12 @@ pub type RenderPassEncoderId = *mut crate::command::RenderPass;
 pub type ComputePassEncoderId = *mut crate::command::ComputePass;
 pub type RenderBundleEncoderId = *mut crate::command::RenderBundleEncoder;
 pub type RenderBundleId = Id<crate::command::RenderBundle<Dummy>>;
+pub type ComputeBundleEncoderId = *mut crate::command::ComputeBundleEncoder;
+pub type ComputeBundleId = Id<crate::command::ComputeBundle<Dummy>>;
 pub type QuerySetId = Id<crate::resource::QuerySet<Dummy>>;
+// Ray tracing
+pub type BlasId = Id<crate::resource::Blas<Dummy>>;
+pub type TlasId = Id<crate::resource::Tlas<Dummy>>;
 
 #[test]
 fn test_id_backend() {
@@ -244,6 +253,7 @@ fn test_id_backend() {
         Backend::Dx12,
         Backend::Dx11,
         Backend::Gl,
+        Backend::Cpu,
     ] {
         let id: Id<()> = Id::zip(1, 0, b);
         let (_id, _epoch, backend) = id.unzip();
@@ -264,6 +274,7 @@ fn test_id() {
         Backend::Dx12,
         Backend::Dx11,
         Backend::Gl,
+        Backend::Cpu,
     ];
     for &i in &indexes {
         for &e in &epochs {
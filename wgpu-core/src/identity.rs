@@ -152,14 +152,20 @@ pub trait GlobalIdentityHandlerFactory:
     + IdentityHandlerFactory<id::BindGroupId>
     + IdentityHandlerFactory<id::CommandBufferId>
     + IdentityHandlerFactory<id::RenderBundleId>
+    + IdentityHandlerFactory<id::ComputeBundleId>
     + IdentityHandlerFactory<id::RenderPipelineId>
     + IdentityHandlerFactory<id::ComputePipelineId>
+    + IdentityHandlerFactory<id::PipelineCacheId>
     + IdentityHandlerFactory<id::QuerySetId>
     + IdentityHandlerFactory<id::BufferId>
     + IdentityHandlerFactory<id::StagingBufferId>
     + IdentityHandlerFactory<id::TextureId>
     + IdentityHandlerFactory<id::TextureViewId>
     + IdentityHandlerFactory<id::SamplerId>
+    + IdentityHandlerFactory<id::BlasId>
+    + IdentityHandlerFactory<id::TlasId>
+    + IdentityHandlerFactory<id::HeapId>
+    + IdentityHandlerFactory<id::ExternalSemaphoreId>
     + IdentityHandlerFactory<id::SurfaceId>
 {
     fn ids_are_generated_in_wgpu() -> bool;
@@ -0,0 +1,218 @@
+/*! Optional validation of the vertices fetched by indexed draws.
+
+WebGPU only requires the range of indices used by a draw to be within the
+bound index buffer: the vertices those indices refer to are not validated,
+and out-of-bounds vertex fetches are left to the robustness guarantees of
+the backend. When [`wgt::InstanceFlags::INDEX_RANGE_VALIDATION`] is set,
+the device validates them as well:
+
+- The contents of index buffers are shadowed on the CPU by [`IndexContents`],
+  which caches the lowest and highest index of every range drawn from. A
+  `draw_indexed` call is rejected if one of its indices, offset by the base
+  vertex, is beyond the bound vertex buffers. Buffers that may be written by
+  the GPU lose their shadow, and their draws are not validated.
+
+- The arguments of indexed indirect draws recorded in render passes are only
+  known to the GPU. They are validated by the compute passes of
+  [`indirect_validation`](crate::indirect_validation), which also scan the
+  indices of each draw.
+
+Indirect draws recorded in render bundles are not validated.
+ */
+
+use crate::{command::DrawError, FastHashMap};
+
+use wgt::{BufferAddress, IndexFormat};
+
+use std::ops::Range;
+
+/// Return the size of an index of `format`, in bytes.
+pub(crate) fn index_size(format: IndexFormat) -> u64 {
+    match format {
+        IndexFormat::Uint16 => 2,
+        IndexFormat::Uint32 => 4,
+    }
+}
+
+/// Return the byte range of `index_count` indices starting at `first_index`,
+/// in an index buffer bound at `offset`.
+pub(crate) fn index_byte_range(
+    format: IndexFormat,
+    offset: BufferAddress,
+    first_index: u32,
+    index_count: u32,
+) -> Range<BufferAddress> {
+    let start = offset + first_index as BufferAddress * index_size(format);
+    start..start + index_count as BufferAddress * index_size(format)
+}
+
+/// Index format, byte range and whether the restart value is skipped.
+type IndexRangeKey = (IndexFormat, Range<BufferAddress>, bool);
+
+/// CPU copy of the contents of an index buffer.
+#[derive(Debug)]
+pub(crate) struct IndexContents {
+    /// The contents of the buffer, or `None` if they are unknown.
+    data: Option<Vec<u8>>,
+    /// Lowest and highest index of the ranges drawn from so far.
+    bounds: FastHashMap<IndexRangeKey, Option<(u32, u32)>>,
+}
+
+impl IndexContents {
+    /// Shadow a zero initialized buffer of `size` bytes.
+    pub(crate) fn new(size: BufferAddress) -> Self {
+        Self {
+            data: Some(vec![0; size as usize]),
+            bounds: FastHashMap::default(),
+        }
+    }
+
+    /// Shadow a buffer whose contents are not known.
+    pub(crate) fn unknown() -> Self {
+        Self {
+            data: None,
+            bounds: FastHashMap::default(),
+        }
+    }
+
+    /// Record a CPU write of `data` at `offset`.
+    pub(crate) fn write(&mut self, offset: BufferAddress, data: &[u8]) {
+        if let Some(ref mut contents) = self.data {
+            let end = offset + data.len() as BufferAddress;
+            contents[offset as usize..end as usize].copy_from_slice(data);
+            self.bounds
+                .retain(|&(_, ref range, _), _| range.end <= offset || range.start >= end);
+        }
+    }
+
+    /// Forget the contents, because the GPU may write to the buffer.
+    pub(crate) fn invalidate(&mut self) {
+        self.data = None;
+        self.bounds.clear();
+    }
+
+    /// Return the lowest and highest index in `range`, or `None` if the range
+    /// only contains restart values.
+    ///
+    /// Return `None` if the contents are unknown.
+    fn bounds(
+        &mut self,
+        format: IndexFormat,
+        range: Range<BufferAddress>,
+        restart: bool,
+    ) -> Option<Option<(u32, u32)>> {
+        let data = self.data.as_ref()?;
+        let bounds = self
+            .bounds
+            .entry((format, range.clone(), restart))
+            .or_insert_with(|| {
+                let bytes = &data[range.start as usize..range.end as usize];
+                let indices: Box<dyn Iterator<Item = u32>> = match format {
+                    IndexFormat::Uint16 => Box::new(
+                        bytes
+                            .chunks_exact(2)
+                            .map(|b| u16::from_ne_bytes([b[0], b[1]]) as u32)
+                            .filter(move |&i| !restart || i != u16::MAX as u32),
+                    ),
+                    IndexFormat::Uint32 => Box::new(
+                        bytes
+                            .chunks_exact(4)
+                            .map(|b| u32::from_ne_bytes([b[0], b[1], b[2], b[3]]))
+                            .filter(move |&i| !restart || i != u32::MAX),
+                    ),
+                };
+                indices.fold(None, |bounds, i| match bounds {
+                    Some((min, max)) => Some((i.min(min), i.max(max))),
+                    None => Some((i, i)),
+                })
+            });
+        Some(*bounds)
+    }
+
+    /// Check that the indices of a draw, offset by `base_vertex`, are within
+    /// `vertex_limit`.
+    ///
+    /// `range` is the byte range of the indices used by the draw.
+    pub(crate) fn validate_draw(
+        &mut self,
+        format: IndexFormat,
+        range: Range<BufferAddress>,
+        restart: bool,
+        base_vertex: i32,
+        vertex_limit: u32,
+        slot: u32,
+    ) -> Result<(), DrawError> {
+        if vertex_limit == u32::MAX {
+            return Ok(());
+        }
+        let (min, max) = match self.bounds(format, range, restart) {
+            Some(Some(bounds)) => bounds,
+            _ => return Ok(()),
+        };
+        let first_vertex = min as i64 + base_vertex as i64;
+        let last_vertex = max as i64 + base_vertex as i64;
+        let vertex = if first_vertex < 0 {
+            first_vertex
+        } else if last_vertex >= vertex_limit as i64 {
+            last_vertex
+        } else {
+            return Ok(());
+        };
+        Err(DrawError::IndexedVertexBeyondLimit {
+            vertex,
+            vertex_limit,
+            slot,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{index_byte_range, IndexContents};
+    use crate::command::DrawError;
+    use wgt::IndexFormat;
+
+    fn indices(values: &[u16]) -> Vec<u8> {
+        values.iter().flat_map(|i| i.to_ne_bytes()).collect()
+    }
+
+    #[test]
+    fn validate_draw() {
+        let mut contents = IndexContents::new(8);
+        contents.write(0, &indices(&[0, 1, 2, 0xFFFF]));
+        let range = index_byte_range(IndexFormat::Uint16, 0, 0, 4);
+
+        assert_eq!(
+            contents.validate_draw(IndexFormat::Uint16, range.clone(), false, 0, 3, 0),
+            Err(DrawError::IndexedVertexBeyondLimit {
+                vertex: 0xFFFF,
+                vertex_limit: 3,
+                slot: 0
+            })
+        );
+        assert_eq!(
+            contents.validate_draw(IndexFormat::Uint16, range.clone(), true, 0, 3, 0),
+            Ok(())
+        );
+        assert_eq!(
+            contents.validate_draw(IndexFormat::Uint16, range.clone(), true, -1, 3, 1),
+            Err(DrawError::IndexedVertexBeyondLimit {
+                vertex: -1,
+                vertex_limit: 3,
+                slot: 1
+            })
+        );
+
+        // Overwriting the indices drops the cached bounds.
+        contents.write(2, &indices(&[5]));
+        assert!(contents
+            .validate_draw(IndexFormat::Uint16, range.clone(), true, 0, 3, 0)
+            .is_err());
+
+        contents.invalidate();
+        assert_eq!(
+            contents.validate_draw(IndexFormat::Uint16, range, true, 0, 3, 0),
+            Ok(())
+        );
+    }
+}
@@ -0,0 +1,923 @@
+/*! Validation of the arguments of indirect calls on the GPU.
+
+The arguments of indirect draws and dispatches are only known to the GPU, so
+they can't be validated when the calls are recorded. When
+[`wgt::InstanceFlags::VALIDATION_INDIRECT_CALL`] or
+[`wgt::InstanceFlags::INDEX_RANGE_VALIDATION`] is set, internal compute
+dispatches of [`IndirectValidation`] copy the arguments to a scratch buffer,
+turning the calls that would be rejected on the CPU into empty ones, and the
+calls are issued from there:
+
+- The indirect draws of a render pass are collected in [`IndirectDraws`], and
+  validated together right before the pass. Their arguments can't be written
+  during the pass, since storage writes and indirect reads of a buffer are
+  exclusive within a render pass. The indices of indexed draws are scanned as
+  well if [`wgt::InstanceFlags::INDEX_RANGE_VALIDATION`] is set, see
+  [`index_validation`](crate::index_validation).
+
+- The arguments of a dispatch may have been written by the previous dispatch
+  of the same compute pass, so [`IndirectDispatches`] validates each of them
+  right before it, within the pass. This disturbs the pipeline, bind groups
+  and push constants of the pass, which have to be set again.
+
+Indirect draws recorded in render bundles are not validated.
+ */
+
+use crate::{
+    device::{queue::TempResource, Device, DeviceError},
+    hal_api::HalApi,
+    id::{self, BufferId},
+    index_validation,
+    resource::Buffer,
+    storage::Storage,
+    track::BufferTracker,
+    FastHashMap,
+};
+
+use hal::{CommandEncoder as _, Device as _};
+use wgt::{BufferAddress, IndexFormat};
+
+use std::{iter, mem, ops::Range};
+
+/// Number of words at the start of the draw validation input, holding the number of draws.
+const HEADER_WORDS: usize = 4;
+/// Number of words describing each draw in the draw validation input.
+const RECORD_WORDS: usize = 11;
+const WORKGROUP_SIZE: u32 = 64;
+/// Size of the arguments of an indirect dispatch.
+const DISPATCH_ARGS_SIZE: BufferAddress =
+    mem::size_of::<wgt::DispatchIndirectArgs>() as BufferAddress;
+/// Size of the slot of a dispatch in [`IndirectDispatches`], before alignment.
+///
+/// A slot holds the validated arguments, followed at word 4 by the word
+/// offset of the arguments in the bound source range, and by the workgroup
+/// count limit.
+const DISPATCH_SLOT_SIZE: BufferAddress = 32;
+
+/// Validation shader.
+///
+/// For draws, `sources` starts with the number of draws, followed by a record
+/// of [`RECORD_WORDS`] words for each of them, and by the data copied from the
+/// indirect and index buffers. Records are made of:
+///
+/// 0. the word offset of the draw arguments in `sources`,
+/// 1. the number of draws,
+/// 2. the word offset of the validated arguments in `results`,
+/// 3. the number of words of the arguments of a draw, 5 for indexed draws,
+/// 4. the byte offset of the bound index buffer range in `sources`,
+/// 5. the number of indices in the bound range,
+/// 6. the size of an index in bytes, or 0 if the indices are not scanned,
+/// 7. whether the primitive restart value is skipped,
+/// 8. the vertex limit,
+/// 9. the instance limit,
+/// 10. whether the first instance may be non-zero.
+///
+/// For dispatches, `sources` is the range of the indirect buffer holding the
+/// arguments, and `results` is a slot, see [`DISPATCH_SLOT_SIZE`].
+#[cfg_attr(not(feature = "wgsl"), allow(dead_code))]
+const SHADER: &str = "
+@group(0) @binding(0)
+var<storage, read> sources: array<u32>;
+@group(0) @binding(1)
+var<storage, read_write> results: array<u32>;
+
+fn read_index(indices: u32, index_size: u32, i: u32) -> u32 {
+    if index_size == 4u {
+        return sources[(indices >> 2u) + i];
+    }
+    let byte = indices + i * 2u;
+    return (sources[byte >> 2u] >> ((byte & 2u) * 8u)) & 0xffffu;
+}
+
+fn instances_valid(record: u32, instance_count: u32, first_instance: u32) -> bool {
+    if first_instance != 0u && sources[record + 10u] == 0u {
+        return false;
+    }
+    let instance_limit = sources[record + 9u];
+    return instance_count <= instance_limit && first_instance <= instance_limit - instance_count;
+}
+
+fn draw_valid(record: u32, args: u32) -> bool {
+    let vertex_count = sources[args];
+    let first_vertex = sources[args + 2u];
+
+    let vertex_limit = sources[record + 8u];
+    if vertex_count > vertex_limit || first_vertex > vertex_limit - vertex_count {
+        return false;
+    }
+    return instances_valid(record, sources[args + 1u], sources[args + 3u]);
+}
+
+fn indexed_draw_valid(record: u32, args: u32) -> bool {
+    let index_count = sources[args];
+    let instance_count = sources[args + 1u];
+    let first_index = sources[args + 2u];
+    let base_vertex = sources[args + 3u];
+    let first_instance = sources[args + 4u];
+
+    let index_limit = sources[record + 5u];
+    if index_count > index_limit || first_index > index_limit - index_count {
+        return false;
+    }
+    if !instances_valid(record, instance_count, first_instance) {
+        return false;
+    }
+    let index_size = sources[record + 6u];
+    let vertex_limit = sources[record + 8u];
+    if index_size == 0u || vertex_limit == 0xffffffffu || instance_count == 0u {
+        return true;
+    }
+
+    let indices = sources[record + 4u];
+    let restart = sources[record + 7u] != 0u;
+    let restart_value = select(0xffffffffu, 0xffffu, index_size == 2u);
+    let negative = bitcast<i32>(base_vertex) < 0;
+    let shift = select(base_vertex, 0u - base_vertex, negative);
+    for (var i = first_index; i < first_index + index_count; i += 1u) {
+        let value = read_index(indices, index_size, i);
+        if restart && value == restart_value {
+            continue;
+        }
+        if negative {
+            if value < shift || value - shift >= vertex_limit {
+                return false;
+            }
+        } else if value >= vertex_limit || shift >= vertex_limit - value {
+            return false;
+        }
+    }
+    return true;
+}
+
+@compute @workgroup_size(64)
+fn validate_draws(@builtin(global_invocation_id) id: vec3<u32>) {
+    if id.x >= sources[0] {
+        return;
+    }
+    let record = 4u + id.x * 11u;
+    let args = sources[record];
+    let count = sources[record + 1u];
+    let output = sources[record + 2u];
+    let words = sources[record + 3u];
+    for (var draw = 0u; draw < count; draw += 1u) {
+        let src = args + draw * words;
+        let dst = output + draw * words;
+        var valid = false;
+        if words == 5u {
+            valid = indexed_draw_valid(record, src);
+        } else {
+            valid = draw_valid(record, src);
+        }
+        results[dst] = select(0u, sources[src], valid);
+        results[dst + 1u] = select(0u, sources[src + 1u], valid);
+        for (var i = 2u; i < words; i += 1u) {
+            results[dst + i] = sources[src + i];
+        }
+    }
+}
+
+@compute @workgroup_size(1)
+fn validate_dispatch() {
+    let src = results[4];
+    let limit = results[5];
+    let x = sources[src];
+    let y = sources[src + 1u];
+    let z = sources[src + 2u];
+    let valid = x <= limit && y <= limit && z <= limit;
+    results[0] = select(0u, x, valid);
+    results[1] = select(0u, y, valid);
+    results[2] = select(0u, z, valid);
+}
+";
+
+/// Return whether the indirect draws of render passes are validated, with
+/// the given instance flags.
+pub(crate) fn validates_draws(flags: wgt::InstanceFlags, indexed: bool) -> bool {
+    flags.contains(wgt::InstanceFlags::VALIDATION_INDIRECT_CALL)
+        || (indexed && flags.contains(wgt::InstanceFlags::INDEX_RANGE_VALIDATION))
+}
+
+/// Return the size of the arguments of an indirect draw.
+pub(crate) fn draw_args_size(indexed: bool) -> BufferAddress {
+    match indexed {
+        false => mem::size_of::<wgt::DrawIndirectArgs>() as BufferAddress,
+        true => mem::size_of::<wgt::DrawIndexedIndirectArgs>() as BufferAddress,
+    }
+}
+
+fn create_bind_group<A: HalApi>(
+    device: &A::Device,
+    layout: &A::BindGroupLayout,
+    sources: hal::BufferBinding<'_, A>,
+    results: hal::BufferBinding<'_, A>,
+) -> Result<A::BindGroup, DeviceError> {
+    unsafe {
+        device.create_bind_group(&hal::BindGroupDescriptor {
+            label: Some("(wgpu internal) indirect validation"),
+            layout,
+            buffers: &[sources, results],
+            samplers: &[],
+            textures: &[],
+            entries: &[
+                hal::BindGroupEntry {
+                    binding: 0,
+                    resource_index: 0,
+                    count: 1,
+                },
+                hal::BindGroupEntry {
+                    binding: 1,
+                    resource_index: 1,
+                    count: 1,
+                },
+            ],
+            acceleration_structures: &[],
+        })
+    }
+    .map_err(DeviceError::from)
+}
+
+/// Device-wide objects used to validate indirect calls.
+///
+/// The validation is not bound by the limits requested for the device, only
+/// by the capabilities of the adapter.
+#[derive(Debug)]
+pub(crate) struct IndirectValidation<A: hal::Api> {
+    max_storage_buffer_binding_size: u32,
+    min_storage_buffer_offset_alignment: u32,
+    bind_group_layout: A::BindGroupLayout,
+    pipeline_layout: A::PipelineLayout,
+    draw_pipeline: A::ComputePipeline,
+    dispatch_pipeline: A::ComputePipeline,
+}
+
+impl<A: hal::Api> IndirectValidation<A> {
+    /// Create the validation pipelines, or return `None` if they aren't supported.
+    pub(crate) fn new(device: &A::Device, caps: &hal::Capabilities) -> Option<Self> {
+        let required =
+            wgt::DownlevelFlags::COMPUTE_SHADERS | wgt::DownlevelFlags::INDIRECT_EXECUTION;
+        if !caps.downlevel.flags.contains(required) {
+            log::warn!(
+                "Indirect calls are not validated, the device is missing {:?}",
+                required - caps.downlevel.flags
+            );
+            return None;
+        }
+        Self::create(device, &caps.limits)
+    }
+
+    #[cfg(not(feature = "wgsl"))]
+    fn create(_device: &A::Device, _limits: &wgt::Limits) -> Option<Self> {
+        log::warn!("Indirect calls are not validated, the 'wgsl' feature is disabled");
+        None
+    }
+
+    #[cfg(feature = "wgsl")]
+    fn create(device: &A::Device, limits: &wgt::Limits) -> Option<Self> {
+        let module = naga::front::wgsl::parse_str(SHADER).unwrap();
+        let info = naga::valid::Validator::new(
+            naga::valid::ValidationFlags::all(),
+            naga::valid::Capabilities::empty(),
+        )
+        .validate(&module)
+        .unwrap();
+
+        let storage_entry = |binding, read_only| wgt::BindGroupLayoutEntry {
+            binding,
+            visibility: wgt::ShaderStages::COMPUTE,
+            ty: wgt::BindingType::Buffer {
+                ty: wgt::BufferBindingType::Storage { read_only },
+                has_dynamic_offset: false,
+                min_binding_size: None,
+            },
+            count: None,
+        };
+        let bind_group_layout = unsafe {
+            device.create_bind_group_layout(&hal::BindGroupLayoutDescriptor {
+                label: Some("(wgpu internal) indirect validation"),
+                flags: hal::BindGroupLayoutFlags::empty(),
+                entries: &[storage_entry(0, true), storage_entry(1, false)],
+            })
+        }
+        .map_err(|err| log::error!("Failed to create indirect validation layout: {err}"))
+        .ok()?;
+        let pipeline_layout = match unsafe {
+            device.create_pipeline_layout(&hal::PipelineLayoutDescriptor {
+                label: Some("(wgpu internal) indirect validation"),
+                flags: hal::PipelineLayoutFlags::empty(),
+                bind_group_layouts: &[&bind_group_layout],
+                push_constant_ranges: &[],
+            })
+        } {
+            Ok(layout) => layout,
+            Err(err) => {
+                log::error!("Failed to create indirect validation layout: {err}");
+                unsafe { device.destroy_bind_group_layout(bind_group_layout) };
+                return None;
+            }
+        };
+
+        let shader = hal::NagaShader {
+            module: std::borrow::Cow::Owned(module),
+            info,
+        };
+        let pipelines = unsafe {
+            device
+                .create_shader_module(
+                    &hal::ShaderModuleDescriptor {
+                        label: Some("(wgpu internal) indirect validation"),
+                        runtime_checks: false,
+                    },
+                    hal::ShaderInput::Naga(shader),
+                )
+                .map_err(|err| err.to_string())
+                .and_then(|module| {
+                    let create_pipeline = |entry_point| {
+                        device
+                            .create_compute_pipeline(&hal::ComputePipelineDescriptor {
+                                label: Some("(wgpu internal) indirect validation"),
+                                layout: &pipeline_layout,
+                                stage: hal::ProgrammableStage {
+                                    module: &module,
+                                    entry_point,
+                                },
+                                cache: None,
+                            })
+                            .map_err(|err| err.to_string())
+                    };
+                    let pipelines = create_pipeline("validate_draws").and_then(|draw_pipeline| {
+                        match create_pipeline("validate_dispatch") {
+                            Ok(dispatch_pipeline) => Ok((draw_pipeline, dispatch_pipeline)),
+                            Err(err) => {
+                                device.destroy_compute_pipeline(draw_pipeline);
+                                Err(err)
+                            }
+                        }
+                    });
+                    device.destroy_shader_module(module);
+                    pipelines
+                })
+        };
+        match pipelines {
+            Ok((draw_pipeline, dispatch_pipeline)) => Some(Self {
+                max_storage_buffer_binding_size: limits.max_storage_buffer_binding_size,
+                min_storage_buffer_offset_alignment: limits.min_storage_buffer_offset_alignment,
+                bind_group_layout,
+                pipeline_layout,
+                draw_pipeline,
+                dispatch_pipeline,
+            }),
+            Err(err) => {
+                log::error!("Failed to create indirect validation pipeline: {err}");
+                unsafe {
+                    device.destroy_pipeline_layout(pipeline_layout);
+                    device.destroy_bind_group_layout(bind_group_layout);
+                }
+                None
+            }
+        }
+    }
+
+    pub(crate) fn dispose(self, device: &A::Device) {
+        unsafe {
+            device.destroy_compute_pipeline(self.draw_pipeline);
+            device.destroy_compute_pipeline(self.dispatch_pipeline);
+            device.destroy_pipeline_layout(self.pipeline_layout);
+            device.destroy_bind_group_layout(self.bind_group_layout);
+        }
+    }
+}
+
+/// The index buffer bound for an indexed indirect draw.
+#[derive(Debug)]
+pub(crate) struct IndirectDrawIndices {
+    pub buffer: BufferId,
+    /// Byte range of the bound index buffer.
+    pub range: Range<BufferAddress>,
+    pub format: IndexFormat,
+    /// Whether the primitive restart value is skipped.
+    pub restart: bool,
+    /// Whether the indices are scanned, to validate the vertices they refer to.
+    pub scan: bool,
+}
+
+/// An indirect draw, recorded in a render pass.
+#[derive(Debug)]
+pub(crate) struct IndirectDraw {
+    pub indirect_buffer: BufferId,
+    pub indirect_offset: BufferAddress,
+    /// Number of draws, for multi-draw-indirect.
+    pub count: u32,
+    /// The bound index buffer, for indexed draws.
+    pub indices: Option<IndirectDrawIndices>,
+    pub vertex_limit: u32,
+    pub instance_limit: u32,
+    /// Whether the first instance may be non-zero.
+    pub first_instance: bool,
+}
+
+impl IndirectDraw {
+    fn args_size(&self) -> BufferAddress {
+        self.count as BufferAddress * draw_args_size(self.indices.is_some())
+    }
+}
+
+/// Indirect draws of a render pass, waiting to be validated.
+///
+/// The draws are issued from [`output`], which gets the validated arguments
+/// when [`encode`] is called before the pass.
+///
+/// [`output`]: IndirectDraws::output
+/// [`encode`]: IndirectDraws::encode
+#[derive(Debug)]
+pub(crate) struct IndirectDraws<A: hal::Api> {
+    output: A::Buffer,
+    output_size: BufferAddress,
+    draws: Vec<(IndirectDraw, BufferAddress)>,
+}
+
+impl<A: HalApi> IndirectDraws<A> {
+    /// Prepare for draws with `size` bytes of arguments.
+    pub(crate) fn new(device: &A::Device, size: BufferAddress) -> Result<Self, DeviceError> {
+        let output = unsafe {
+            device.create_buffer(&hal::BufferDescriptor {
+                label: Some("(wgpu internal) validated indirect draws"),
+                size,
+                usage: hal::BufferUses::STORAGE_READ_WRITE
+                    | hal::BufferUses::INDIRECT
+                    | hal::BufferUses::COPY_DST,
+                memory_flags: hal::MemoryFlags::empty(),
+            })
+        }?;
+        Ok(Self {
+            output,
+            output_size: size,
+            draws: Vec::new(),
+        })
+    }
+
+    pub(crate) fn output(&self) -> &A::Buffer {
+        &self.output
+    }
+
+    /// Record `draw`, and return the offset of its validated arguments in
+    /// [`IndirectDraws::output`].
+    pub(crate) fn push(&mut self, draw: IndirectDraw) -> BufferAddress {
+        let offset = self
+            .draws
+            .last()
+            .map_or(0, |&(ref last, offset)| offset + last.args_size());
+        debug_assert!(offset + draw.args_size() <= self.output_size);
+        self.draws.push((draw, offset));
+        offset
+    }
+
+    /// Release the output buffer, for instance when the pass failed.
+    pub(crate) fn into_temp_resource(self) -> TempResource<A> {
+        TempResource::Buffer(self.output)
+    }
+
+    /// Record the validation of the draws into `encoder`, which must be
+    /// executed before the render pass.
+    ///
+    /// The indirect and index buffers are transitioned in `trackers`, and the
+    /// internal resources are pushed to `temp_resources`.
+    pub(crate) fn encode(
+        self,
+        device: &Device<A>,
+        encoder: &mut A::CommandEncoder,
+        buffer_guard: &Storage<Buffer<A>, BufferId>,
+        trackers: &mut BufferTracker<A>,
+        temp_resources: &mut Vec<TempResource<A>>,
+    ) -> Result<(), DeviceError> {
+        let IndirectDraws {
+            output,
+            output_size: _,
+            draws,
+        } = self;
+        let validation = device
+            .indirect_validation
+            .as_ref()
+            .expect("Indirect draws are recorded without validation");
+        let device = &device.raw;
+
+        // Lay out the records, followed by the data they refer to.
+        let mut records = vec![0u32; HEADER_WORDS + draws.len() * RECORD_WORDS];
+        records[0] = draws.len() as u32;
+        let mut size = (records.len() * mem::size_of::<u32>()) as BufferAddress;
+        let mut copies = Vec::new();
+        let mut index_copies = FastHashMap::default();
+        for (i, &(ref draw, output_offset)) in draws.iter().enumerate() {
+            let args_offset = size;
+            let args_size = draw.args_size();
+            copies.push((
+                draw.indirect_buffer,
+                draw.indirect_offset,
+                args_offset,
+                args_size,
+            ));
+            size += args_size;
+
+            let mut index_offset = 0;
+            let mut index_limit = 0;
+            let mut index_size = 0;
+            if let Some(ref indices) = draw.indices {
+                index_limit = (indices.range.end - indices.range.start)
+                    / index_validation::index_size(indices.format);
+                if indices.scan {
+                    let start = indices.range.start & !3;
+                    let end = (indices.range.end + 3) & !3;
+                    index_offset = *index_copies
+                        .entry((indices.buffer, start, end))
+                        .or_insert_with(|| {
+                            let offset = size;
+                            copies.push((indices.buffer, start, offset, end - start));
+                            size += end - start;
+                            offset
+                        })
+                        + indices.range.start
+                        - start;
+                    index_size = index_validation::index_size(indices.format);
+                }
+            }
+
+            let record = &mut records[HEADER_WORDS + i * RECORD_WORDS..][..RECORD_WORDS];
+            record.copy_from_slice(&[
+                (args_offset / 4) as u32,
+                draw.count,
+                (output_offset / 4) as u32,
+                (draw_args_size(draw.indices.is_some()) / 4) as u32,
+                index_offset as u32,
+                index_limit as u32,
+                index_size as u32,
+                matches!(draw.indices, Some(ref indices) if indices.restart) as u32,
+                draw.vertex_limit,
+                draw.instance_limit,
+                draw.first_instance as u32,
+            ]);
+        }
+
+        let mut barriers = Vec::new();
+        for &(buffer_id, ..) in copies.iter() {
+            if let Some((buffer, transition)) =
+                trackers.set_single(buffer_guard, buffer_id, hal::BufferUses::COPY_SRC)
+            {
+                barriers.extend(transition.map(|pending| pending.into_hal(buffer)));
+            }
+        }
+        let raw = |buffer_id| {
+            buffer_guard[id::Valid(buffer_id)]
+                .raw
+                .as_ref()
+                .expect("Buffer is destroyed")
+        };
+
+        if size > validation.max_storage_buffer_binding_size as BufferAddress {
+            log::warn!(
+                "Validating the indirect draws of a render pass needs {size} bytes, \
+                which is over the storage buffer binding size limit. They are not validated."
+            );
+            unsafe {
+                encoder.transition_buffers(barriers.into_iter().chain(iter::once(
+                    hal::BufferBarrier {
+                        buffer: &output,
+                        usage: hal::BufferUses::empty()..hal::BufferUses::COPY_DST,
+                    },
+                )));
+                for &(ref draw, output_offset) in draws.iter() {
+                    if let Some(size) = wgt::BufferSize::new(draw.args_size()) {
+                        let region = hal::BufferCopy {
+                            src_offset: draw.indirect_offset,
+                            dst_offset: output_offset,
+                            size,
+                        };
+                        encoder.copy_buffer_to_buffer(
+                            raw(draw.indirect_buffer),
+                            &output,
+                            iter::once(region),
+                        );
+                    }
+                }
+                encoder.transition_buffers(iter::once(hal::BufferBarrier {
+                    buffer: &output,
+                    usage: hal::BufferUses::COPY_DST..hal::BufferUses::INDIRECT,
+                }));
+            }
+            temp_resources.push(TempResource::Buffer(output));
+            return Ok(());
+        }
+
+        let input = match unsafe {
+            device.create_buffer(&hal::BufferDescriptor {
+                label: Some("(wgpu internal) indirect validation input"),
+                size,
+                usage: hal::BufferUses::COPY_DST | hal::BufferUses::STORAGE_READ,
+                memory_flags: hal::MemoryFlags::TRANSIENT,
+            })
+        } {
+            Ok(input) => input,
+            Err(err) => {
+                temp_resources.push(TempResource::Buffer(output));
+                return Err(err.into());
+            }
+        };
+        let records: Vec<u8> = records.iter().flat_map(|word| word.to_ne_bytes()).collect();
+        let (staging_buffer, staging_ptr) =
+            match crate::device::queue::prepare_staging_buffer::<A>(device, records.len() as u64) {
+                Ok(staging) => staging,
+                Err(err) => {
+                    temp_resources.push(TempResource::Buffer(output));
+                    temp_resources.push(TempResource::Buffer(input));
+                    return Err(err);
+                }
+            };
+        let flushed = unsafe {
+            std::ptr::copy_nonoverlapping(records.as_ptr(), staging_ptr, records.len());
+            staging_buffer.flush(device)
+        };
+        let bind_group = flushed.and_then(|()| {
+            create_bind_group::<A>(
+                device,
+                &validation.bind_group_layout,
+                hal::BufferBinding {
+                    buffer: &input,
+                    offset: 0,
+                    size: None,
+                },
+                hal::BufferBinding {
+                    buffer: &output,
+                    offset: 0,
+                    size: None,
+                },
+            )
+        });
+        let bind_group = match bind_group {
+            Ok(bind_group) => bind_group,
+            Err(err) => {
+                temp_resources.push(TempResource::Buffer(output));
+                temp_resources.push(TempResource::Buffer(staging_buffer.raw));
+                temp_resources.push(TempResource::Buffer(input));
+                return Err(err);
+            }
+        };
+
+        unsafe {
+            encoder.transition_buffers(barriers.into_iter().chain([
+                hal::BufferBarrier {
+                    buffer: &staging_buffer.raw,
+                    usage: hal::BufferUses::MAP_WRITE..hal::BufferUses::COPY_SRC,
+                },
+                hal::BufferBarrier {
+                    buffer: &input,
+                    usage: hal::BufferUses::empty()..hal::BufferUses::COPY_DST,
+                },
+            ]));
+            if let Some(size) = wgt::BufferSize::new(records.len() as u64) {
+                let region = hal::BufferCopy {
+                    src_offset: 0,
+                    dst_offset: 0,
+                    size,
+                };
+                encoder.copy_buffer_to_buffer(&staging_buffer.raw, &input, iter::once(region));
+            }
+            for &(buffer_id, src_offset, dst_offset, size) in copies.iter() {
+                if let Some(size) = wgt::BufferSize::new(size) {
+                    let region = hal::BufferCopy {
+                        src_offset,
+                        dst_offset,
+                        size,
+                    };
+                    encoder.copy_buffer_to_buffer(raw(buffer_id), &input, iter::once(region));
+                }
+            }
+            encoder.transition_buffers(
+                [
+                    hal::BufferBarrier {
+                        buffer: &input,
+                        usage: hal::BufferUses::COPY_DST..hal::BufferUses::STORAGE_READ,
+                    },
+                    hal::BufferBarrier {
+                        buffer: &output,
+                        usage: hal::BufferUses::empty()..hal::BufferUses::STORAGE_READ_WRITE,
+                    },
+                ]
+                .into_iter(),
+            );
+
+            encoder.begin_compute_pass(&hal::ComputePassDescriptor {
+                label: Some("(wgpu internal) indirect validation"),
+                timestamp_writes: None,
+            });
+            encoder.set_compute_pipeline(&validation.draw_pipeline);
+            encoder.set_bind_group(&validation.pipeline_layout, 0, &bind_group, &[]);
+            let workgroups = (draws.len() as u32 + WORKGROUP_SIZE - 1) / WORKGROUP_SIZE;
+            encoder.dispatch([workgroups, 1, 1]);
+            encoder.end_compute_pass();
+
+            encoder.transition_buffers(iter::once(hal::BufferBarrier {
+                buffer: &output,
+                usage: hal::BufferUses::STORAGE_READ_WRITE..hal::BufferUses::INDIRECT,
+            }));
+        }
+
+        temp_resources.push(TempResource::Buffer(output));
+        temp_resources.push(TempResource::Buffer(staging_buffer.raw));
+        temp_resources.push(TempResource::Buffer(input));
+        temp_resources.push(TempResource::BindGroup(bind_group));
+        Ok(())
+    }
+}
+
+/// Indirect dispatches of a compute pass.
+///
+/// Each dispatch gets a slot in an internal buffer, which is filled with the
+/// validated arguments right before the dispatch, see [`validate`]. The slots
+/// also hold parameters of the validation, which are uploaded when [`encode`]
+/// is called before the pass.
+///
+/// [`validate`]: IndirectDispatches::validate
+/// [`encode`]: IndirectDispatches::encode
+#[derive(Debug)]
+pub(crate) struct IndirectDispatches<A: hal::Api> {
+    buffer: A::Buffer,
+    slot_size: BufferAddress,
+    /// Word offset of the arguments in the bound source range, and workgroup
+    /// count limit, of each slot.
+    params: Vec<[u32; 2]>,
+    bind_groups: Vec<A::BindGroup>,
+}
+
+impl<A: HalApi> IndirectDispatches<A> {
+    /// Prepare for at most `count` dispatches.
+    pub(crate) fn new(device: &Device<A>, count: u64) -> Result<Self, DeviceError> {
+        let validation = device
+            .indirect_validation
+            .as_ref()
+            .expect("Indirect dispatches are recorded without validation");
+        let alignment = validation.min_storage_buffer_offset_alignment as BufferAddress;
+        let slot_size = (DISPATCH_SLOT_SIZE + alignment - 1) / alignment * alignment;
+        let buffer = unsafe {
+            device.raw.create_buffer(&hal::BufferDescriptor {
+                label: Some("(wgpu internal) validated indirect dispatches"),
+                size: count * slot_size,
+                usage: hal::BufferUses::STORAGE_READ_WRITE
+                    | hal::BufferUses::INDIRECT
+                    | hal::BufferUses::COPY_DST,
+                memory_flags: hal::MemoryFlags::empty(),
+            })
+        }?;
+        Ok(Self {
+            buffer,
+            slot_size,
+            params: Vec::new(),
+            bind_groups: Vec::new(),
+        })
+    }
+
+    /// The buffer to dispatch from.
+    pub(crate) fn buffer(&self) -> &A::Buffer {
+        &self.buffer
+    }
+
+    /// Record the validation of the arguments at `offset` in `indirect_buffer`
+    /// into `encoder`, and return the offset of the validated arguments in
+    /// [`IndirectDispatches::buffer`].
+    ///
+    /// `indirect_buffer` must be in the `STORAGE_READ` state. The current
+    /// pipeline, bind groups and push constants of the compute pass are
+    /// disturbed.
+    pub(crate) fn validate(
+        &mut self,
+        device: &Device<A>,
+        encoder: &mut A::CommandEncoder,
+        indirect_buffer: &A::Buffer,
+        offset: BufferAddress,
+    ) -> Result<BufferAddress, DeviceError> {
+        let validation = device
+            .indirect_validation
+            .as_ref()
+            .expect("Indirect dispatches are recorded without validation");
+        let alignment = validation.min_storage_buffer_offset_alignment as BufferAddress;
+        let source_offset = offset - offset % alignment;
+        let slot = self.params.len() as BufferAddress;
+        let slot_offset = slot * self.slot_size;
+
+        let bind_group = create_bind_group::<A>(
+            &device.raw,
+            &validation.bind_group_layout,
+            hal::BufferBinding {
+                buffer: indirect_buffer,
+                offset: source_offset,
+                size: wgt::BufferSize::new(offset + DISPATCH_ARGS_SIZE - source_offset),
+            },
+            hal::BufferBinding {
+                buffer: &self.buffer,
+                offset: slot_offset,
+                size: wgt::BufferSize::new(DISPATCH_SLOT_SIZE),
+            },
+        )?;
+        self.params.push([
+            ((offset - source_offset) / 4) as u32,
+            device.limits.max_compute_workgroups_per_dimension,
+        ]);
+
+        unsafe {
+            if slot != 0 {
+                encoder.transition_buffers(iter::once(hal::BufferBarrier {
+                    buffer: &self.buffer,
+                    usage: hal::BufferUses::INDIRECT..hal::BufferUses::STORAGE_READ_WRITE,
+                }));
+            }
+            encoder.set_compute_pipeline(&validation.dispatch_pipeline);
+            encoder.set_bind_group(&validation.pipeline_layout, 0, &bind_group, &[]);
+            encoder.dispatch([1, 1, 1]);
+            encoder.transition_buffers(iter::once(hal::BufferBarrier {
+                buffer: &self.buffer,
+                usage: hal::BufferUses::STORAGE_READ_WRITE..hal::BufferUses::INDIRECT,
+            }));
+        }
+        self.bind_groups.push(bind_group);
+        Ok(slot_offset)
+    }
+
+    /// Release the internal resources, for instance when the pass failed.
+    pub(crate) fn into_temp_resources(self) -> impl Iterator<Item = TempResource<A>> {
+        iter::once(TempResource::Buffer(self.buffer))
+            .chain(self.bind_groups.into_iter().map(TempResource::BindGroup))
+    }
+
+    /// Record the upload of the validation parameters into `encoder`, which
+    /// must be executed before the compute pass.
+    ///
+    /// The internal resources are pushed to `temp_resources`.
+    pub(crate) fn encode(
+        self,
+        device: &A::Device,
+        encoder: &mut A::CommandEncoder,
+        temp_resources: &mut Vec<TempResource<A>>,
+    ) -> Result<(), DeviceError> {
+        let slot_words = (self.slot_size / 4) as usize;
+        let mut words = vec![0u32; self.params.len() * slot_words];
+        for (slot, params) in words.chunks_exact_mut(slot_words).zip(self.params.iter()) {
+            slot[4..6].copy_from_slice(params);
+        }
+        let data: Vec<u8> = words.iter().flat_map(|word| word.to_ne_bytes()).collect();
+
+        let staging = crate::device::queue::prepare_staging_buffer::<A>(device, data.len() as u64)
+            .and_then(|(staging_buffer, staging_ptr)| {
+                let flushed = unsafe {
+                    std::ptr::copy_nonoverlapping(data.as_ptr(), staging_ptr, data.len());
+                    staging_buffer.flush(device)
+                };
+                match flushed {
+                    Ok(()) => Ok(staging_buffer),
+                    Err(err) => {
+                        temp_resources.push(TempResource::Buffer(staging_buffer.raw));
+                        Err(err)
+                    }
+                }
+            });
+        let staging_buffer = match staging {
+            Ok(staging_buffer) => staging_buffer,
+            Err(err) => {
+                temp_resources.extend(self.into_temp_resources());
+                return Err(err);
+            }
+        };
+
+        unsafe {
+            encoder.transition_buffers(
+                [
+                    hal::BufferBarrier {
+                        buffer: &staging_buffer.raw,
+                        usage: hal::BufferUses::MAP_WRITE..hal::BufferUses::COPY_SRC,
+                    },
+                    hal::BufferBarrier {
+                        buffer: &self.buffer,
+                        usage: hal::BufferUses::empty()..hal::BufferUses::COPY_DST,
+                    },
+                ]
+                .into_iter(),
+            );
+            if let Some(size) = wgt::BufferSize::new(data.len() as u64) {
+                let region = hal::BufferCopy {
+                    src_offset: 0,
+                    dst_offset: 0,
+                    size,
+                };
+                encoder.copy_buffer_to_buffer(
+                    &staging_buffer.raw,
+                    &self.buffer,
+                    iter::once(region),
+                );
+            }
+            encoder.transition_buffers(iter::once(hal::BufferBarrier {
+                buffer: &self.buffer,
+                usage: hal::BufferUses::COPY_DST..hal::BufferUses::STORAGE_READ_WRITE,
+            }));
+        }
+
+        temp_resources.push(TempResource::Buffer(staging_buffer.raw));
+        temp_resources.extend(self.into_temp_resources());
+        Ok(())
+    }
+}
@@ -12,6 +12,9 @@ The granularity is different for buffers and textures:
 - Texture: Mip-level per layer. That is, a 2D surface is either
   completely initialized or not, subrects are not tracked.
 
+- Query set: Per query. Unwritten queries are resolved as zeros, so
+  resolves are only encoded at queue submit.
+
 Every use of a buffer/texture generates a InitTrackerAction which are
 recorded and later resolved at queue submit by merging them with the
 current state and each other in execution order.
@@ -35,9 +38,13 @@ use smallvec::SmallVec;
 use std::{fmt, iter, ops::Range};
 
 mod buffer;
+mod query_set;
 mod texture;
 
 pub(crate) use buffer::{BufferInitTracker, BufferInitTrackerAction};
+pub(crate) use query_set::{
+    QueryInitKind, QueryResolve, QuerySetInitTracker, QuerySetInitTrackerAction,
+};
 pub(crate) use texture::{
     has_copy_partial_init_tracker_coverage, TextureInitRange, TextureInitTracker,
     TextureInitTrackerAction,
@@ -198,6 +205,27 @@ where
             next_index: index,
         }
     }
+
+    // Makes a range uninitialized, merging it with the uninitialized ranges it
+    // overlaps or touches.
+    pub(crate) fn discard_range(&mut self, range: Range<Idx>) {
+        if range.start >= range.end {
+            return;
+        }
+        let first = self
+            .uninitialized_ranges
+            .partition_point(|r| r.end < range.start);
+        let last = self
+            .uninitialized_ranges
+            .partition_point(|r| r.start <= range.end);
+        let mut merged = range;
+        if first < last {
+            merged.start = merged.start.min(self.uninitialized_ranges[first].start);
+            merged.end = merged.end.max(self.uninitialized_ranges[last - 1].end);
+        }
+        self.uninitialized_ranges.drain(first..last);
+        self.uninitialized_ranges.insert(first, merged);
+    }
 }
 
 impl InitTracker<u32> {
@@ -377,4 +405,49 @@ mod test {
         assert_eq!(tracker.uninitialized_ranges.len(), 1);
         assert_eq!(tracker.uninitialized_ranges[0], 0..10);
     }
+
+    #[test]
+    fn discard_range_merges_ranges() {
+        let mut tracker = Tracker::new(20);
+        tracker.drain(0..20);
+        tracker.discard_range(2..4);
+        tracker.discard_range(8..10);
+        tracker.discard_range(14..16);
+        assert_eq!(
+            tracker.uninitialized_ranges.as_slice(),
+            &[2..4, 8..10, 14..16]
+        );
+
+        // Touching and overlapping ranges are merged.
+        tracker.discard_range(4..9);
+        tracker.discard_range(12..15);
+        assert_eq!(tracker.uninitialized_ranges.as_slice(), &[2..10, 12..16]);
+
+        tracker.discard_range(0..20);
+        assert_eq!(tracker.uninitialized_ranges.as_slice(), &[0..20]);
+    }
+
+    #[test]
+    fn query_set_runs() {
+        let mut tracker = Tracker::new(20);
+        assert_eq!(tracker.runs(2..5).into_vec(), vec![(2..5, false)]);
+
+        tracker.mark_written(4..8);
+        tracker.mark_written(12..14);
+        assert_eq!(
+            tracker.runs(0..20).into_vec(),
+            vec![
+                (0..4, false),
+                (4..8, true),
+                (8..12, false),
+                (12..14, true),
+                (14..20, false)
+            ]
+        );
+        assert_eq!(
+            tracker.runs(5..13).into_vec(),
+            vec![(5..8, true), (8..12, false), (12..13, true)]
+        );
+        assert_eq!(tracker.runs(12..14).into_vec(), vec![(12..14, true)]);
+    }
 }
@@ -0,0 +1,83 @@
+use super::InitTracker;
+use crate::id::{BufferId, QuerySetId};
+use smallvec::SmallVec;
+use std::ops::Range;
+
+/// A use of queries by a command buffer, applied to the query set's tracker
+/// at submit, in recording order.
+#[derive(Debug, Clone)]
+pub(crate) struct QuerySetInitTrackerAction {
+    pub id: QuerySetId,
+    pub range: Range<u32>,
+    pub kind: QueryInitKind,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) enum QueryInitKind {
+    /// The queries get written.
+    Write,
+    /// The queries get resolved. Whether they have been written is only known
+    /// at submit, so that is when the resolve gets encoded.
+    Resolve(QueryResolve),
+}
+
+/// A query resolve whose commands are encoded at submit.
+#[derive(Debug, Clone)]
+pub(crate) struct QueryResolve {
+    pub buffer: BufferId,
+    pub offset: wgt::BufferAddress,
+    pub stride: u32,
+    /// Index of the raw command buffer of the command buffer's list the
+    /// resolve has to be executed before.
+    pub position: usize,
+}
+
+impl QuerySetInitTrackerAction {
+    pub(crate) fn write(id: QuerySetId, query_index: u32) -> Self {
+        Self {
+            id,
+            range: query_index..query_index + 1,
+            kind: QueryInitKind::Write,
+        }
+    }
+}
+
+/// Tracks which queries of a query set have been written by submitted
+/// commands.
+///
+/// Unwritten queries can't be initialized ahead of time, so the tracker is
+/// consulted when a query resolve gets submitted, to resolve them as zeros.
+pub(crate) type QuerySetInitTracker = InitTracker<u32>;
+
+impl QuerySetInitTracker {
+    /// Marks the queries in `range` as written.
+    pub(crate) fn mark_written(&mut self, range: Range<u32>) {
+        self.drain(range);
+    }
+
+    /// Splits `range` into consecutive runs of queries, each flagged with
+    /// whether it has been written before.
+    pub(crate) fn runs(&self, range: Range<u32>) -> SmallVec<[(Range<u32>, bool); 1]> {
+        let mut runs = SmallVec::new();
+        let mut cursor = range.start;
+        let index = self
+            .uninitialized_ranges
+            .partition_point(|r| r.end <= range.start);
+        for uninitialized in self.uninitialized_ranges[index..].iter() {
+            if uninitialized.start >= range.end {
+                break;
+            }
+            let start = uninitialized.start.max(range.start);
+            let end = uninitialized.end.min(range.end);
+            if cursor < start {
+                runs.push((cursor..start, true));
+            }
+            runs.push((start..end, false));
+            cursor = end;
+        }
+        if cursor < range.end {
+            runs.push((cursor..range.end, true));
+        }
+        runs
+    }
+}
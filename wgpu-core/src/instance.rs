@@ -57,6 +57,7 @@ fn downlevel_default_limits_less_than_default_limits() {
 pub struct Instance {
     #[allow(dead_code)]
     pub name: String,
+    pub flags: wgt::InstanceFlags,
     #[cfg(all(feature = "vulkan", not(target_arch = "wasm32")))]
     pub vulkan: Option<HalInstance<hal::api::Vulkan>>,
     #[cfg(all(feature = "metal", any(target_os = "macos", target_os = "ios")))]
@@ -67,6 +68,8 @@ pub struct Instance {
     pub dx11: Option<HalInstance<hal::api::Dx11>>,
     #[cfg(feature = "gles")]
     pub gl: Option<HalInstance<hal::api::Gles>>,
+    #[cfg(feature = "cpu")]
+    pub cpu: Option<HalInstance<hal::api::Cpu>>,
 }
 
 impl Instance {
@@ -101,6 +104,7 @@ impl Instance {
 
         Self {
             name: name.to_string(),
+            flags: instance_desc.flags,
             #[cfg(all(feature = "vulkan", not(target_arch = "wasm32")))]
             vulkan: init(hal::api::Vulkan, &instance_desc),
             #[cfg(all(feature = "metal", any(target_os = "macos", target_os = "ios")))]
@@ -111,6 +115,8 @@ impl Instance {
             dx11: init(hal::api::Dx11, &instance_desc),
             #[cfg(feature = "gles")]
             gl: init(hal::api::Gles, &instance_desc),
+            #[cfg(feature = "cpu")]
+            cpu: init(hal::api::Cpu, &instance_desc),
         }
     }
 
@@ -136,6 +142,8 @@ impl Instance {
         destroy(hal::api::Dx11, &self.dx11, surface.dx11);
         #[cfg(feature = "gles")]
         destroy(hal::api::Gles, &self.gl, surface.gl);
+        #[cfg(feature = "cpu")]
+        destroy(hal::api::Cpu, &self.cpu, surface.cpu);
     }
 }
 
@@ -151,6 +159,8 @@ pub struct Surface {
     pub dx11: Option<HalSurface<hal::api::Dx11>>,
     #[cfg(feature = "gles")]
     pub gl: Option<HalSurface<hal::api::Gles>>,
+    #[cfg(feature = "cpu")]
+    pub cpu: Option<HalSurface<hal::api::Cpu>>,
 }
 
 impl crate::resource::Resource for Surface {
@@ -295,6 +305,7 @@ impl<A: HalApi> Adapter<A> {
         self_id: AdapterId,
         open: hal::OpenDevice<A>,
         desc: &DeviceDescriptor,
+        instance_flags: wgt::InstanceFlags,
         trace_path: Option<&std::path::Path>,
     ) -> Result<Device<A>, RequestDeviceError> {
         log::trace!("Adapter::create_device");
@@ -306,9 +317,9 @@ impl<A: HalApi> Adapter<A> {
                 value: Valid(self_id),
                 ref_count: self.life_guard.add_ref(),
             },
-            caps.alignments.clone(),
-            caps.downlevel.clone(),
+            caps,
             desc,
+            instance_flags,
             trace_path,
         )
         .or(Err(RequestDeviceError::OutOfMemory))
@@ -318,6 +329,7 @@ impl<A: HalApi> Adapter<A> {
         &self,
         self_id: AdapterId,
         desc: &DeviceDescriptor,
+        instance_flags: wgt::InstanceFlags,
         trace_path: Option<&std::path::Path>,
     ) -> Result<Device<A>, RequestDeviceError> {
         // Verify all features were exposed by the adapter
@@ -360,15 +372,18 @@ impl<A: HalApi> Adapter<A> {
             return Err(RequestDeviceError::LimitsExceeded(failed));
         }
 
-        let open = unsafe { self.raw.adapter.open(desc.features, &desc.limits) }.map_err(
-            |err| match err {
-                hal::DeviceError::Lost => RequestDeviceError::DeviceLost,
-                hal::DeviceError::OutOfMemory => RequestDeviceError::OutOfMemory,
-                hal::DeviceError::ResourceCreationFailed => RequestDeviceError::Internal,
-            },
-        )?;
+        let open = unsafe {
+            self.raw
+                .adapter
+                .open(desc.features, &desc.limits, &desc.memory_hints)
+        }
+        .map_err(|err| match err {
+            hal::DeviceError::Lost => RequestDeviceError::DeviceLost,
+            hal::DeviceError::OutOfMemory => RequestDeviceError::OutOfMemory,
+            hal::DeviceError::ResourceCreationFailed => RequestDeviceError::Internal,
+        })?;
 
-        self.create_device_from_hal(self_id, open, desc, trace_path)
+        self.create_device_from_hal(self_id, open, desc, instance_flags, trace_path)
     }
 }
 
@@ -494,6 +509,8 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
             dx11: init::<hal::api::Dx11>(&self.instance.dx11, display_handle, window_handle),
             #[cfg(feature = "gles")]
             gl: init::<hal::api::Gles>(&self.instance.gl, display_handle, window_handle),
+            #[cfg(feature = "cpu")]
+            cpu: init::<hal::api::Cpu>(&self.instance.cpu, display_handle, window_handle),
         };
 
         let mut token = Token::root();
@@ -526,6 +543,8 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
             vulkan: None,
             #[cfg(feature = "gles")]
             gl: None,
+            #[cfg(feature = "cpu")]
+            cpu: None,
         };
 
         let mut token = Token::root();
@@ -557,6 +576,8 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
                     })
                 })
                 .transpose()?,
+            #[cfg(feature = "cpu")]
+            cpu: None,
         };
 
         let mut token = Token::root();
@@ -588,6 +609,8 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
                     })
                 })
                 .transpose()?,
+            #[cfg(feature = "cpu")]
+            cpu: None,
         };
 
         let mut token = Token::root();
@@ -616,6 +639,8 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
             dx11: None,
             #[cfg(feature = "gles")]
             gl: None,
+            #[cfg(feature = "cpu")]
+            cpu: None,
         };
 
         let mut token = Token::root();
@@ -644,6 +669,8 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
             dx11: None,
             #[cfg(feature = "gles")]
             gl: None,
+            #[cfg(feature = "cpu")]
+            cpu: None,
         };
 
         let mut token = Token::root();
@@ -672,6 +699,8 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
             dx11: None,
             #[cfg(feature = "gles")]
             gl: None,
+            #[cfg(feature = "cpu")]
+            cpu: None,
         };
 
         let mut token = Token::root();
@@ -708,6 +737,8 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
                 Backend::Dx11 => unconfigure(self, surface.dx11.as_mut().unwrap(), &present),
                 #[cfg(feature = "gles")]
                 Backend::Gl => unconfigure(self, surface.gl.as_mut().unwrap(), &present),
+                #[cfg(feature = "cpu")]
+                Backend::Cpu => unconfigure(self, surface.cpu.as_mut().unwrap(), &present),
                 _ => unreachable!(),
             }
         }
@@ -773,6 +804,8 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
         self.enumerate(hal::api::Dx11, &self.instance.dx11, &inputs, &mut adapters);
         #[cfg(feature = "gles")]
         self.enumerate(hal::api::Gles, &self.instance.gl, &inputs, &mut adapters);
+        #[cfg(feature = "cpu")]
+        self.enumerate(hal::api::Cpu, &self.instance.cpu, &inputs, &mut adapters);
 
         adapters
     }
@@ -900,6 +933,15 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
             desc.force_fallback_adapter,
             &mut device_types,
         );
+        #[cfg(feature = "cpu")]
+        let (id_cpu, adapters_cpu) = gather(
+            hal::api::Cpu,
+            self.instance.cpu.as_ref(),
+            &inputs,
+            compatible_surface,
+            desc.force_fallback_adapter,
+            &mut device_types,
+        );
 
         // need to free the token to be used by `select`
         drop(surface_guard);
@@ -975,6 +1017,10 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
         if let Some(id) = self.select(&mut selected, id_gl, adapters_gl) {
             return Ok(id);
         }
+        #[cfg(feature = "cpu")]
+        if let Some(id) = self.select(&mut selected, id_cpu, adapters_cpu) {
+            return Ok(id);
+        }
         let _ = selected;
 
         log::warn!("Some adapters are present, but enumerating them failed!");
@@ -1005,6 +1051,8 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
             Backend::Dx11 => fid.assign(Adapter::new(hal_adapter), &mut token).0,
             #[cfg(feature = "gles")]
             Backend::Gl => fid.assign(Adapter::new(hal_adapter), &mut token).0,
+            #[cfg(feature = "cpu")]
+            Backend::Cpu => fid.assign(Adapter::new(hal_adapter), &mut token).0,
             _ => unreachable!(),
         }
     }
@@ -1127,10 +1175,11 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
                 Ok(adapter) => adapter,
                 Err(_) => break RequestDeviceError::InvalidAdapter,
             };
-            let device = match adapter.create_device(adapter_id, desc, trace_path) {
-                Ok(device) => device,
-                Err(e) => break e,
-            };
+            let device =
+                match adapter.create_device(adapter_id, desc, self.instance.flags, trace_path) {
+                    Ok(device) => device,
+                    Err(e) => break e,
+                };
             let id = fid.assign(device, &mut token);
             return (id.0, None);
         };
@@ -1163,11 +1212,16 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
                 Ok(adapter) => adapter,
                 Err(_) => break RequestDeviceError::InvalidAdapter,
             };
-            let device =
-                match adapter.create_device_from_hal(adapter_id, hal_device, desc, trace_path) {
-                    Ok(device) => device,
-                    Err(e) => break e,
-                };
+            let device = match adapter.create_device_from_hal(
+                adapter_id,
+                hal_device,
+                desc,
+                self.instance.flags,
+                trace_path,
+            ) {
+                Ok(device) => device,
+                Err(e) => break e,
+            };
             let id = fid.assign(device, &mut token);
             return (id.0, None);
         };
@@ -1190,6 +1244,7 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
 /// - metal  = "metal" or "mtl"
 /// - gles   = "opengl" or "gles" or "gl"
 /// - webgpu = "webgpu"
+/// - cpu    = "cpu"
 pub fn parse_backends_from_comma_list(string: &str) -> Backends {
     let mut backends = Backends::empty();
     for backend in string.to_lowercase().split(',') {
@@ -1200,6 +1255,7 @@ pub fn parse_backends_from_comma_list(string: &str) -> Backends {
             "metal" | "mtl" => Backends::METAL,
             "opengl" | "gles" | "gl" => Backends::GL,
             "webgpu" => Backends::BROWSER_WEBGPU,
+            "cpu" => Backends::CPU,
             b => {
                 log::warn!("unknown backend string '{}'", b);
                 continue;
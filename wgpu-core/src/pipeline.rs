@@ -2,7 +2,8 @@ use crate::{
     binding_model::{CreateBindGroupLayoutError, CreatePipelineLayoutError},
     command::ColorAttachmentError,
     device::{DeviceError, MissingDownlevelFlags, MissingFeatures, RenderPassContext},
-    id::{DeviceId, PipelineLayoutId, ShaderModuleId},
+    id::{DeviceId, PipelineCacheId, PipelineLayoutId, ShaderModuleId},
+    pipeline_cache::PipelineCacheValidationError,
     resource::Resource,
     validation, Label, LifeGuard, Stored,
 };
@@ -152,6 +153,95 @@ impl CreateShaderModuleError {
             _ => None,
         }
     }
+
+    /// Describe the error as a list of [`wgt::CompilationMessage`]s.
+    ///
+    /// The first message is the error itself, located at its primary span where
+    /// naga provides one. Every other labelled span of the error follows as an
+    /// [`Info`] message, so tooling can point at all the involved source ranges.
+    ///
+    /// [`Info`]: wgt::CompilationMessageType::Info
+    pub fn compilation_info(&self) -> wgt::CompilationInfo {
+        let mut messages = Vec::new();
+        match *self {
+            #[cfg(feature = "wgsl")]
+            CreateShaderModuleError::Parsing(ref err) => {
+                messages.push(wgt::CompilationMessage {
+                    message: err.inner.message().to_string(),
+                    message_type: wgt::CompilationMessageType::Error,
+                    location: err.inner.location(&err.source).map(map_source_location),
+                });
+                for (span, label) in err.inner.labels() {
+                    push_span_info(&mut messages, &err.source, span, label);
+                }
+            }
+            CreateShaderModuleError::Validation(ref err) => {
+                // Validation errors nest the actual problem in their sources,
+                // so the top level message alone isn't much help.
+                let mut message = err.inner.as_inner().to_string();
+                let mut source = err.inner.as_inner().source();
+                while let Some(inner) = source {
+                    message = format!("{message}: {inner}");
+                    source = inner.source();
+                }
+                messages.push(wgt::CompilationMessage {
+                    message,
+                    message_type: wgt::CompilationMessageType::Error,
+                    location: err
+                        .inner
+                        .spans()
+                        .next()
+                        .and_then(|&(span, _)| span_location(&err.source, span)),
+                });
+                for &(span, ref label) in err.inner.spans() {
+                    push_span_info(&mut messages, &err.source, span, label);
+                }
+            }
+            _ => messages.push(wgt::CompilationMessage {
+                message: self.to_string(),
+                message_type: wgt::CompilationMessageType::Error,
+                location: None,
+            }),
+        }
+        wgt::CompilationInfo { messages }
+    }
+}
+
+fn map_source_location(location: naga::SourceLocation) -> wgt::SourceLocation {
+    wgt::SourceLocation {
+        line_number: location.line_number,
+        line_position: location.line_position,
+        offset: location.offset,
+        length: location.length,
+    }
+}
+
+/// Locate `span` in `source`, if it refers to it at all.
+///
+/// Modules passed in as naga IR are validated without their source text, in
+/// which case the spans can't be resolved.
+fn span_location(source: &str, span: naga::Span) -> Option<wgt::SourceLocation> {
+    let range = span.to_range()?;
+    source.get(range)?;
+    Some(map_source_location(span.location(source)))
+}
+
+fn push_span_info(
+    messages: &mut Vec<wgt::CompilationMessage>,
+    source: &str,
+    span: naga::Span,
+    label: &str,
+) {
+    if label.is_empty() {
+        return;
+    }
+    if let Some(location) = span_location(source, span) {
+        messages.push(wgt::CompilationMessage {
+            message: label.to_string(),
+            message_type: wgt::CompilationMessageType::Info,
+            location: Some(location),
+        });
+    }
 }
 
 /// Describes a programmable pipeline stage.
@@ -192,6 +282,9 @@ pub struct ComputePipelineDescriptor<'a> {
     pub layout: Option<PipelineLayoutId>,
     /// The compiled compute stage and its entry point.
     pub stage: ProgrammableStageDescriptor<'a>,
+    /// The pipeline cache to use when creating this pipeline.
+    #[cfg_attr(any(feature = "replay", feature = "trace"), serde(default))]
+    pub cache: Option<PipelineCacheId>,
 }
 
 #[derive(Clone, Debug, Error)]
@@ -201,6 +294,8 @@ pub enum CreateComputePipelineError {
     Device(#[from] DeviceError),
     #[error("Pipeline layout is invalid")]
     InvalidLayout,
+    #[error("Pipeline cache is invalid")]
+    InvalidCache,
     #[error("Unable to derive an implicit layout")]
     Implicit(#[from] ImplicitLayoutError),
     #[error("Error matching shader requirements against the pipeline")]
@@ -228,6 +323,56 @@ impl<A: hal::Api> Resource for ComputePipeline<A> {
     }
 }
 
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "trace", derive(serde::Serialize))]
+#[cfg_attr(feature = "replay", derive(serde::Deserialize))]
+pub struct PipelineCacheDescriptor<'a> {
+    pub label: Label<'a>,
+    /// Data previously returned by [`Global::pipeline_cache_get_data`], if any.
+    ///
+    /// [`Global::pipeline_cache_get_data`]: crate::global::Global::pipeline_cache_get_data
+    pub data: Option<Cow<'a, [u8]>>,
+    /// Create an empty cache instead of failing if `data` can't be used with this device,
+    /// for example because it was produced by a different driver version.
+    pub fallback: bool,
+}
+
+#[derive(Clone, Debug, Error)]
+#[non_exhaustive]
+pub enum CreatePipelineCacheError {
+    #[error(transparent)]
+    Device(#[from] DeviceError),
+    #[error("Pipeline cache validation failed")]
+    Validation(#[from] PipelineCacheValidationError),
+    #[error(transparent)]
+    MissingFeatures(#[from] MissingFeatures),
+    #[error("Internal error: {0}")]
+    Internal(String),
+}
+
+#[derive(Debug)]
+pub struct PipelineCache<A: hal::Api> {
+    pub(crate) raw: A::PipelineCache,
+    pub(crate) device_id: Stored<DeviceId>,
+    #[cfg(debug_assertions)]
+    pub(crate) label: String,
+}
+
+impl<A: hal::Api> Resource for PipelineCache<A> {
+    const TYPE: &'static str = "PipelineCache";
+
+    fn life_guard(&self) -> &LifeGuard {
+        unreachable!()
+    }
+
+    fn label(&self) -> &str {
+        #[cfg(debug_assertions)]
+        return &self.label;
+        #[cfg(not(debug_assertions))]
+        return "";
+    }
+}
+
 /// Describes how the vertex buffer is interpreted.
 #[derive(Clone, Debug)]
 #[cfg_attr(feature = "trace", derive(serde::Serialize))]
@@ -288,6 +433,9 @@ pub struct RenderPipelineDescriptor<'a> {
     /// If the pipeline will be used with a multiview render pass, this indicates how many array
     /// layers the attachments will have.
     pub multiview: Option<NonZeroU32>,
+    /// The pipeline cache to use when creating this pipeline.
+    #[cfg_attr(any(feature = "replay", feature = "trace"), serde(default))]
+    pub cache: Option<PipelineCacheId>,
 }
 
 #[derive(Clone, Debug, Error)]
@@ -334,6 +482,8 @@ pub enum CreateRenderPipelineError {
     Device(#[from] DeviceError),
     #[error("Pipeline layout is invalid")]
     InvalidLayout,
+    #[error("Pipeline cache is invalid")]
+    InvalidCache,
     #[error("Unable to derive an implicit layout")]
     Implicit(#[from] ImplicitLayoutError),
     #[error("Color state [{0}] is invalid")]
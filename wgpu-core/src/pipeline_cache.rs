@@ -0,0 +1,280 @@
+//! Serialization format for pipeline cache data.
+//!
+//! Drivers are not required to gracefully handle pipeline cache data that was
+//! produced by a different device, driver version or build of the driver, and
+//! some of them will happily crash when given such data. To avoid that, the
+//! data handed out by [`PipelineCache::get_data`] is prefixed with a header
+//! identifying the adapter and driver it was produced on, which is validated
+//! before anything is passed on to the backend.
+//!
+//! The header is laid out as follows, with all integers in little endian:
+//!
+//! | Offset | Size | Contents                                  |
+//! |--------|------|-------------------------------------------|
+//! | 0      | 8    | Magic number, `b"WGPUPLCH"`               |
+//! | 8      | 4    | Header version                            |
+//! | 12     | 4    | Cache ABI (pointer width of the producer) |
+//! | 16     | 1    | [`wgt::Backend`]                          |
+//! | 17     | 1    | [`wgt::DeviceType`]                       |
+//! | 18     | 6    | Reserved, must be zero                    |
+//! | 24     | 4    | Adapter vendor id                         |
+//! | 28     | 4    | Adapter device id                         |
+//! | 32     | 16   | Backend validation key (driver UUID)      |
+//! | 48     | 8    | Size of the data following the header     |
+//! | 56     | 8    | FNV-1a hash of the data                   |
+//!
+//! [`PipelineCache::get_data`]: crate::global::Global::pipeline_cache_get_data
+
+use std::mem;
+
+use thiserror::Error;
+use wgt::AdapterInfo;
+
+pub const HEADER_LENGTH: usize = 64;
+
+const MAGIC: [u8; 8] = *b"WGPUPLCH";
+const HEADER_VERSION: u32 = 1;
+const ABI: u32 = mem::size_of::<*const ()>() as u32;
+
+#[derive(Clone, Debug, Error)]
+#[non_exhaustive]
+pub enum PipelineCacheValidationError {
+    #[error("The pipeline cache data was truncated")]
+    Truncated,
+    #[error("The pipeline cache data was longer than recorded")]
+    Extended,
+    #[error("The pipeline cache data was corrupted (e.g. the hash didn't match)")]
+    Corrupted,
+    #[error("The pipeline cache data was out of date and so cannot be safely used")]
+    Outdated,
+    #[error("The pipeline cache data was created for a different device or driver")]
+    WrongDevice,
+    #[error("The pipeline cache data was created for a future version of wgpu")]
+    Unsupported,
+}
+
+impl PipelineCacheValidationError {
+    /// Could the error have been avoided?
+    ///
+    /// Data from a different device or driver version is expected, for example after
+    /// a driver update, while truncated or corrupted data points at a bug in how the
+    /// data was stored.
+    pub fn was_avoidable(&self) -> bool {
+        match *self {
+            Self::WrongDevice | Self::Unsupported => false,
+            Self::Truncated | Self::Outdated | Self::Corrupted | Self::Extended => true,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct PipelineCacheHeader {
+    backend: u8,
+    device_type: u8,
+    vendor: u32,
+    device: u32,
+    validation_key: [u8; 16],
+    data_size: u64,
+    data_hash: u64,
+}
+
+impl PipelineCacheHeader {
+    fn new(adapter: &AdapterInfo, validation_key: [u8; 16], data: &[u8]) -> Self {
+        Self {
+            backend: adapter.backend as u8,
+            device_type: adapter.device_type as u8,
+            vendor: adapter.vendor,
+            device: adapter.device,
+            validation_key,
+            data_size: data.len() as u64,
+            data_hash: hash(data),
+        }
+    }
+
+    fn write(&self, out: &mut [u8; HEADER_LENGTH]) {
+        out[0..8].copy_from_slice(&MAGIC);
+        out[8..12].copy_from_slice(&HEADER_VERSION.to_le_bytes());
+        out[12..16].copy_from_slice(&ABI.to_le_bytes());
+        out[16] = self.backend;
+        out[17] = self.device_type;
+        out[18..24].fill(0);
+        out[24..28].copy_from_slice(&self.vendor.to_le_bytes());
+        out[28..32].copy_from_slice(&self.device.to_le_bytes());
+        out[32..48].copy_from_slice(&self.validation_key);
+        out[48..56].copy_from_slice(&self.data_size.to_le_bytes());
+        out[56..64].copy_from_slice(&self.data_hash.to_le_bytes());
+    }
+
+    fn read(data: &[u8; HEADER_LENGTH]) -> Result<Self, PipelineCacheValidationError> {
+        fn u32_at(data: &[u8], offset: usize) -> u32 {
+            u32::from_le_bytes(data[offset..offset + 4].try_into().unwrap())
+        }
+        fn u64_at(data: &[u8], offset: usize) -> u64 {
+            u64::from_le_bytes(data[offset..offset + 8].try_into().unwrap())
+        }
+
+        if data[0..8] != MAGIC {
+            return Err(PipelineCacheValidationError::Corrupted);
+        }
+        let version = u32_at(data, 8);
+        if version > HEADER_VERSION {
+            return Err(PipelineCacheValidationError::Unsupported);
+        }
+        if version < HEADER_VERSION {
+            return Err(PipelineCacheValidationError::Outdated);
+        }
+        if u32_at(data, 12) != ABI {
+            return Err(PipelineCacheValidationError::WrongDevice);
+        }
+        if data[18..24].iter().any(|&b| b != 0) {
+            return Err(PipelineCacheValidationError::Corrupted);
+        }
+
+        Ok(Self {
+            backend: data[16],
+            device_type: data[17],
+            vendor: u32_at(data, 24),
+            device: u32_at(data, 28),
+            validation_key: data[32..48].try_into().unwrap(),
+            data_size: u64_at(data, 48),
+            data_hash: u64_at(data, 56),
+        })
+    }
+}
+
+/// Check that `cache_data` was produced by [`add_cache_header`] for the same adapter
+/// and backend validation key, returning the backend data following the header.
+pub fn validate_pipeline_cache<'d>(
+    cache_data: &'d [u8],
+    adapter: &AdapterInfo,
+    validation_key: [u8; 16],
+) -> Result<&'d [u8], PipelineCacheValidationError> {
+    if cache_data.len() < HEADER_LENGTH {
+        return Err(PipelineCacheValidationError::Truncated);
+    }
+    let (header, remaining_data) = cache_data.split_at(HEADER_LENGTH);
+    let header = PipelineCacheHeader::read(header.try_into().unwrap())?;
+
+    let expected = PipelineCacheHeader::new(adapter, validation_key, &[]);
+    if header.backend != expected.backend
+        || header.device_type != expected.device_type
+        || header.vendor != expected.vendor
+        || header.device != expected.device
+        || header.validation_key != expected.validation_key
+    {
+        return Err(PipelineCacheValidationError::WrongDevice);
+    }
+
+    let data_size = remaining_data.len() as u64;
+    if data_size < header.data_size {
+        return Err(PipelineCacheValidationError::Truncated);
+    }
+    if data_size > header.data_size {
+        return Err(PipelineCacheValidationError::Extended);
+    }
+    if hash(remaining_data) != header.data_hash {
+        return Err(PipelineCacheValidationError::Corrupted);
+    }
+    Ok(remaining_data)
+}
+
+/// Prefix the backend cache `data` with a header describing `adapter`.
+pub fn add_cache_header(data: &[u8], adapter: &AdapterInfo, validation_key: [u8; 16]) -> Vec<u8> {
+    let mut header = [0; HEADER_LENGTH];
+    PipelineCacheHeader::new(adapter, validation_key, data).write(&mut header);
+
+    let mut result = Vec::with_capacity(HEADER_LENGTH + data.len());
+    result.extend_from_slice(&header);
+    result.extend_from_slice(data);
+    result
+}
+
+/// 64-bit FNV-1a, which is plenty to detect accidental corruption.
+fn hash(data: &[u8]) -> u64 {
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+    data.iter().fold(OFFSET_BASIS, |hash, &byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(PRIME)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn adapter() -> AdapterInfo {
+        AdapterInfo {
+            name: "Test".into(),
+            vendor: 0x10de,
+            device: 0x2684,
+            device_type: wgt::DeviceType::DiscreteGpu,
+            driver: String::new(),
+            driver_info: String::new(),
+            backend: wgt::Backend::Vulkan,
+        }
+    }
+
+    const KEY: [u8; 16] = *b"0123456789abcdef";
+
+    #[test]
+    fn round_trip() {
+        let data = add_cache_header(b"driver data", &adapter(), KEY);
+        assert_eq!(data.len(), HEADER_LENGTH + 11);
+        let inner = validate_pipeline_cache(&data, &adapter(), KEY).unwrap();
+        assert_eq!(inner, b"driver data");
+    }
+
+    #[test]
+    fn wrong_device() {
+        let data = add_cache_header(b"driver data", &adapter(), KEY);
+
+        let mut other = adapter();
+        other.device += 1;
+        assert!(matches!(
+            validate_pipeline_cache(&data, &other, KEY),
+            Err(PipelineCacheValidationError::WrongDevice)
+        ));
+
+        let mut other_key = KEY;
+        other_key[15] ^= 1;
+        assert!(matches!(
+            validate_pipeline_cache(&data, &adapter(), other_key),
+            Err(PipelineCacheValidationError::WrongDevice)
+        ));
+    }
+
+    #[test]
+    fn damaged_data() {
+        let data = add_cache_header(b"driver data", &adapter(), KEY);
+
+        assert!(matches!(
+            validate_pipeline_cache(&data[..HEADER_LENGTH - 1], &adapter(), KEY),
+            Err(PipelineCacheValidationError::Truncated)
+        ));
+        assert!(matches!(
+            validate_pipeline_cache(&data[..data.len() - 1], &adapter(), KEY),
+            Err(PipelineCacheValidationError::Truncated)
+        ));
+
+        let mut extended = data.clone();
+        extended.push(0);
+        assert!(matches!(
+            validate_pipeline_cache(&extended, &adapter(), KEY),
+            Err(PipelineCacheValidationError::Extended)
+        ));
+
+        let mut corrupted = data.clone();
+        *corrupted.last_mut().unwrap() ^= 0xFF;
+        assert!(matches!(
+            validate_pipeline_cache(&corrupted, &adapter(), KEY),
+            Err(PipelineCacheValidationError::Corrupted)
+        ));
+
+        let mut future = data;
+        future[8] = 2;
+        assert!(matches!(
+            validate_pipeline_cache(&future, &adapter(), KEY),
+            Err(PipelineCacheValidationError::Unsupported)
+        ));
+    }
+}
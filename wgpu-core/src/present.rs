@@ -222,6 +222,8 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
                         clear_views,
                         is_color: true,
                     },
+                    heap: None,
+                    sparse: None,
                 };
 
                 let ref_count = texture.life_guard.add_ref();
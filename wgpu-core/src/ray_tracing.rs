@@ -0,0 +1,358 @@
+/*! Ray tracing acceleration structures.
+
+Acceleration structures come in two levels:
+
+- A bottom level acceleration structure ([`Blas`]) contains geometry, which
+  is described up front by [`wgt::BlasGeometrySizeDescriptors`] when the
+  structure is created. Every build has to stay within those bounds.
+
+- A top level acceleration structure ([`Tlas`]) contains instances of bottom
+  level acceleration structures. Instances are read from a buffer filled by
+  the user, in the layout of `VkAccelerationStructureInstanceKHR`. The
+  contents of that buffer are not validated, which is why the build entry
+  point is named `..._unsafe_tlas`.
+
+A `Tlas` may only be bound once it has been built by a command buffer that
+was submitted before, or together with, the command buffer using it.
+
+[`Blas`]: crate::resource::Blas
+[`Tlas`]: crate::resource::Tlas
+*/
+
+use crate::{
+    command::CommandEncoderError,
+    device::{DeviceError, MissingFeatures},
+    id::{BlasId, BufferId, TlasId},
+    resource::CreateBufferError,
+    Label,
+};
+
+use thiserror::Error;
+use wgt::{BufferAddress, VertexFormat};
+
+pub type BlasDescriptor<'a> = wgt::CreateBlasDescriptor<Label<'a>>;
+pub type TlasDescriptor<'a> = wgt::CreateTlasDescriptor<Label<'a>>;
+
+/// Alignment of the ranges of the scratch buffer used by each build.
+///
+/// This is the largest value `minAccelerationStructureScratchOffsetAlignment`
+/// is allowed to have in Vulkan.
+pub(crate) const SCRATCH_BUFFER_ALIGNMENT: BufferAddress = 256;
+
+#[derive(Clone, Debug, Error)]
+#[non_exhaustive]
+pub enum CreateBlasError {
+    #[error(transparent)]
+    Device(#[from] DeviceError),
+    #[error(transparent)]
+    MissingFeatures(#[from] MissingFeatures),
+    #[error("Vertex format {0:?} is not supported for acceleration structure geometry")]
+    UnsupportedVertexFormat(VertexFormat),
+    #[error("Geometry {0} specifies only one of an index format and an index count")]
+    MismatchedIndexData(usize),
+    #[error("Index count {count} of geometry {index} is not a multiple of 3")]
+    UnalignedIndexCount { index: usize, count: u32 },
+}
+
+#[derive(Clone, Debug, Error)]
+#[non_exhaustive]
+pub enum CreateTlasError {
+    #[error(transparent)]
+    Device(#[from] DeviceError),
+    #[error(transparent)]
+    MissingFeatures(#[from] MissingFeatures),
+}
+
+/// Error encountered while attempting to build acceleration structures.
+#[derive(Clone, Debug, Error)]
+#[non_exhaustive]
+pub enum BuildAccelerationStructureError {
+    #[error(transparent)]
+    Encoder(#[from] CommandEncoderError),
+    #[error(transparent)]
+    Device(#[from] DeviceError),
+    #[error(transparent)]
+    MissingFeatures(#[from] MissingFeatures),
+    #[error("Failed to create the scratch buffer")]
+    ScratchBuffer(#[source] CreateBufferError),
+    #[error("Buffer {0:?} is invalid or destroyed")]
+    InvalidBuffer(BufferId),
+    #[error("Buffer {0:?} is missing the `BLAS_INPUT` usage flag")]
+    MissingBlasInputUsageFlag(BufferId),
+    #[error("Buffer {0:?} is missing the `TLAS_INPUT` usage flag")]
+    MissingTlasInputUsageFlag(BufferId),
+    #[error("Buffer {buffer:?} of size {buffer_size} is too small, reading {start}..{end}")]
+    BufferOverrun {
+        buffer: BufferId,
+        start: BufferAddress,
+        end: BufferAddress,
+        buffer_size: BufferAddress,
+    },
+    #[error("Offset {offset} into buffer {buffer:?} is not aligned to {alignment}")]
+    UnalignedOffset {
+        buffer: BufferId,
+        offset: BufferAddress,
+        alignment: BufferAddress,
+    },
+    #[error("Bottom level acceleration structure {0:?} is invalid or destroyed")]
+    InvalidBlas(BlasId),
+    #[error("Top level acceleration structure {0:?} is invalid or destroyed")]
+    InvalidTlas(TlasId),
+    #[error("Bottom level acceleration structure {0:?} is built more than once by the same call")]
+    DuplicateBlasBuild(BlasId),
+    #[error("Top level acceleration structure {0:?} is built more than once by the same call")]
+    DuplicateTlasBuild(TlasId),
+    #[error("Build of {blas:?} provides {actual} geometries, but it was created with {expected}")]
+    GeometryCountMismatch {
+        blas: BlasId,
+        expected: usize,
+        actual: usize,
+    },
+    #[error("Geometry {index} of {blas:?} does not match the size descriptor it was created with: {reason}")]
+    IncompatibleGeometry {
+        blas: BlasId,
+        index: usize,
+        reason: &'static str,
+    },
+    #[error("Build of {tlas:?} contains {count} instances, but it was created for at most {max}")]
+    TooManyInstances { tlas: TlasId, count: u32, max: u32 },
+}
+
+/// A triangle geometry of a [`BlasBuildEntry`].
+///
+/// The counts and formats in `size` have to stay within the bounds of the
+/// corresponding geometry the [`Blas`] was created with.
+///
+/// [`Blas`]: crate::resource::Blas
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "trace", derive(serde::Serialize))]
+#[cfg_attr(feature = "replay", derive(serde::Deserialize))]
+pub struct BlasTriangleGeometry {
+    pub size: wgt::BlasTriangleGeometrySizeDescriptor,
+    pub vertex_buffer: BufferId,
+    /// Offset in the vertex buffer, as a number of vertices.
+    pub first_vertex: u32,
+    pub vertex_stride: BufferAddress,
+    pub index_buffer: Option<BufferId>,
+    /// Byte offset in the index buffer.
+    pub index_buffer_offset: Option<BufferAddress>,
+    /// Buffer containing a 3x4 row major transformation matrix of `f32`s.
+    pub transform_buffer: Option<BufferId>,
+    /// Byte offset in the transform buffer.
+    pub transform_buffer_offset: Option<BufferAddress>,
+}
+
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "trace", derive(serde::Serialize))]
+#[cfg_attr(feature = "replay", derive(serde::Deserialize))]
+pub enum BlasGeometries {
+    TriangleGeometries(Vec<BlasTriangleGeometry>),
+}
+
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "trace", derive(serde::Serialize))]
+#[cfg_attr(feature = "replay", derive(serde::Deserialize))]
+pub struct BlasBuildEntry {
+    pub blas_id: BlasId,
+    pub geometries: BlasGeometries,
+}
+
+/// Build of a top level acceleration structure from `instance_count`
+/// instances of [`wgt::TLAS_INSTANCE_SIZE`] bytes each, at the start of
+/// `instance_buffer_id`.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "trace", derive(serde::Serialize))]
+#[cfg_attr(feature = "replay", derive(serde::Deserialize))]
+pub struct TlasBuildEntry {
+    pub tlas_id: TlasId,
+    pub instance_buffer_id: BufferId,
+    pub instance_count: u32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum TlasActionKind {
+    Build,
+    Use,
+}
+
+/// A build or use of a [`Tlas`] recorded into a command buffer, checked and
+/// applied in order when the command buffer is submitted.
+///
+/// [`Tlas`]: crate::resource::Tlas
+#[derive(Clone, Debug)]
+pub(crate) struct TlasAction {
+    pub id: TlasId,
+    pub kind: TlasActionKind,
+}
+
+fn is_supported_vertex_format(format: VertexFormat) -> bool {
+    matches!(
+        format,
+        VertexFormat::Float32x3
+            | VertexFormat::Float32x2
+            | VertexFormat::Float16x4
+            | VertexFormat::Float16x2
+    )
+}
+
+/// Check the geometry bounds given at [`Blas`] creation.
+///
+/// [`Blas`]: crate::resource::Blas
+pub(crate) fn validate_blas_sizes(
+    sizes: &wgt::BlasGeometrySizeDescriptors,
+) -> Result<(), CreateBlasError> {
+    match *sizes {
+        wgt::BlasGeometrySizeDescriptors::Triangles { ref desc } => {
+            for (index, size) in desc.iter().enumerate() {
+                if !is_supported_vertex_format(size.vertex_format) {
+                    return Err(CreateBlasError::UnsupportedVertexFormat(size.vertex_format));
+                }
+                match (size.index_format, size.index_count) {
+                    (Some(_), Some(count)) if count % 3 != 0 => {
+                        return Err(CreateBlasError::UnalignedIndexCount { index, count });
+                    }
+                    (Some(_), Some(_)) | (None, None) => {}
+                    _ => return Err(CreateBlasError::MismatchedIndexData(index)),
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Check that a geometry provided to a build stays within the bounds the
+/// [`Blas`] was created with.
+///
+/// [`Blas`]: crate::resource::Blas
+pub(crate) fn validate_triangle_geometry(
+    blas: BlasId,
+    index: usize,
+    created: &wgt::BlasTriangleGeometrySizeDescriptor,
+    geometry: &BlasTriangleGeometry,
+) -> Result<(), BuildAccelerationStructureError> {
+    let incompatible = |reason| BuildAccelerationStructureError::IncompatibleGeometry {
+        blas,
+        index,
+        reason,
+    };
+    let size = &geometry.size;
+
+    if size.vertex_format != created.vertex_format {
+        return Err(incompatible("different vertex format"));
+    }
+    if size.vertex_count > created.vertex_count {
+        return Err(incompatible("more vertices"));
+    }
+    if size.index_format != created.index_format {
+        return Err(incompatible("different index format"));
+    }
+    if size.index_count.unwrap_or(0) > created.index_count.unwrap_or(0) {
+        return Err(incompatible("more indices"));
+    }
+    if size.index_count.map_or(false, |count| count % 3 != 0) {
+        return Err(incompatible("index count is not a multiple of 3"));
+    }
+    if size.index_format.is_some() != geometry.index_buffer.is_some() {
+        return Err(incompatible(
+            "index buffer presence differs from the index format",
+        ));
+    }
+    if size.flags != created.flags {
+        return Err(incompatible("different geometry flags"));
+    }
+    Ok(())
+}
+
+#[test]
+fn test_validate_blas_sizes() {
+    let triangles =
+        |vertex_format, index_format, index_count| wgt::BlasGeometrySizeDescriptors::Triangles {
+            desc: vec![wgt::BlasTriangleGeometrySizeDescriptor {
+                vertex_format,
+                vertex_count: 3,
+                index_format,
+                index_count,
+                flags: wgt::AccelerationStructureGeometryFlags::OPAQUE,
+            }],
+        };
+
+    assert!(validate_blas_sizes(&triangles(VertexFormat::Float32x3, None, None)).is_ok());
+    assert!(validate_blas_sizes(&triangles(
+        VertexFormat::Float16x2,
+        Some(wgt::IndexFormat::Uint16),
+        Some(6)
+    ))
+    .is_ok());
+    assert!(matches!(
+        validate_blas_sizes(&triangles(VertexFormat::Uint32x3, None, None)),
+        Err(CreateBlasError::UnsupportedVertexFormat(
+            VertexFormat::Uint32x3
+        ))
+    ));
+    assert!(matches!(
+        validate_blas_sizes(&triangles(
+            VertexFormat::Float32x3,
+            Some(wgt::IndexFormat::Uint32),
+            None
+        )),
+        Err(CreateBlasError::MismatchedIndexData(0))
+    ));
+    assert!(matches!(
+        validate_blas_sizes(&triangles(
+            VertexFormat::Float32x3,
+            Some(wgt::IndexFormat::Uint32),
+            Some(4)
+        )),
+        Err(CreateBlasError::UnalignedIndexCount { index: 0, count: 4 })
+    ));
+}
+
+#[test]
+fn test_validate_triangle_geometry() {
+    use crate::id::TypedId as _;
+
+    let blas = BlasId::zip(0, 1, wgt::Backend::Empty);
+    let created = wgt::BlasTriangleGeometrySizeDescriptor {
+        vertex_format: VertexFormat::Float32x3,
+        vertex_count: 6,
+        index_format: Some(wgt::IndexFormat::Uint16),
+        index_count: Some(6),
+        flags: wgt::AccelerationStructureGeometryFlags::empty(),
+    };
+    let geometry = |size| BlasTriangleGeometry {
+        size,
+        vertex_buffer: BufferId::zip(0, 1, wgt::Backend::Empty),
+        first_vertex: 0,
+        vertex_stride: 12,
+        index_buffer: Some(BufferId::zip(1, 1, wgt::Backend::Empty)),
+        index_buffer_offset: None,
+        transform_buffer: None,
+        transform_buffer_offset: None,
+    };
+
+    let smaller = wgt::BlasTriangleGeometrySizeDescriptor {
+        vertex_count: 3,
+        index_count: Some(3),
+        ..created
+    };
+    assert!(validate_triangle_geometry(blas, 0, &created, &geometry(smaller)).is_ok());
+
+    let more_vertices = wgt::BlasTriangleGeometrySizeDescriptor {
+        vertex_count: 9,
+        ..created
+    };
+    assert!(matches!(
+        validate_triangle_geometry(blas, 0, &created, &geometry(more_vertices)),
+        Err(BuildAccelerationStructureError::IncompatibleGeometry { index: 0, .. })
+    ));
+
+    let other_format = wgt::BlasTriangleGeometrySizeDescriptor {
+        vertex_format: VertexFormat::Float32x2,
+        ..created
+    };
+    assert!(validate_triangle_geometry(blas, 0, &created, &geometry(other_format)).is_err());
+
+    let mut unindexed = geometry(created.clone());
+    unindexed.index_buffer = None;
+    assert!(validate_triangle_geometry(blas, 0, &created, &unindexed).is_err());
+}
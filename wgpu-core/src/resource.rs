@@ -3,18 +3,20 @@ use crate::{
     global::Global,
     hal_api::HalApi,
     hub::Token,
-    id::{AdapterId, DeviceId, SurfaceId, TextureId, Valid},
+    id::{AdapterId, BufferId, DeviceId, ExternalSemaphoreId, HeapId, SurfaceId, TextureId, Valid},
     identity::GlobalIdentityHandlerFactory,
-    init_tracker::{BufferInitTracker, TextureInitTracker},
+    index_validation::IndexContents,
+    init_tracker::{BufferInitTracker, QuerySetInitTracker, TextureInitTracker},
     track::TextureSelector,
     validation::MissingBufferUsageError,
-    Label, LifeGuard, RefCount, Stored,
+    Label, LifeGuard, RefCount, Stored, SubmissionIndex,
 };
 
+use parking_lot::Mutex;
 use smallvec::SmallVec;
 use thiserror::Error;
 
-use std::{borrow::Borrow, ops::Range, ptr::NonNull};
+use std::{borrow::Borrow, iter, ops::Range, ptr::NonNull};
 
 pub trait Resource {
     const TYPE: &'static str;
@@ -281,6 +283,23 @@ pub struct Buffer<A: hal::Api> {
     pub(crate) sync_mapped_writes: Option<hal::MemoryRange>,
     pub(crate) life_guard: LifeGuard,
     pub(crate) map_state: BufferMapState<A>,
+    /// Copy of the contents of index buffers, if the device validates the
+    /// vertices fetched by indexed draws.
+    pub(crate) index_contents: Option<Mutex<IndexContents>>,
+    /// Where the buffer lives, if it was created in a [`Heap`].
+    pub(crate) heap: Option<HeapPlacement>,
+    /// The memory bound to the pages of a sparse buffer.
+    pub(crate) sparse: Option<SparsePages>,
+}
+
+impl<A: hal::Api> Buffer<A> {
+    /// Note that the GPU may write to this buffer, so that its contents
+    /// are no longer known to indexed draw validation.
+    pub(crate) fn invalidate_index_contents(&self) {
+        if let Some(ref contents) = self.index_contents {
+            contents.lock().invalidate();
+        }
+    }
 }
 
 #[derive(Clone, Debug, Error)]
@@ -300,6 +319,10 @@ pub enum CreateBufferError {
     MaxBufferSize { requested: u64, maximum: u64 },
     #[error(transparent)]
     MissingDownlevelFlags(#[from] MissingDownlevelFlags),
+    #[error(transparent)]
+    Placement(#[from] PlacementError),
+    #[error(transparent)]
+    Sparse(#[from] SparseResourceError),
 }
 
 impl<A: hal::Api> Resource for Buffer<A> {
@@ -407,9 +430,40 @@ pub struct Texture<A: hal::Api> {
     pub(crate) full_range: TextureSelector,
     pub(crate) life_guard: LifeGuard,
     pub(crate) clear_mode: TextureClearMode<A>,
+    /// Where the texture lives, if it was created in a [`Heap`].
+    pub(crate) heap: Option<HeapPlacement>,
+    /// The memory bound to the pages of a sparse texture.
+    pub(crate) sparse: Option<SparsePages>,
 }
 
 impl<A: hal::Api> Texture<A> {
+    /// The size of the texture's contents in bytes, without any padding the
+    /// backend may add.
+    pub(crate) fn content_size(&self) -> wgt::BufferAddress {
+        let format = self.desc.format;
+        // `Depth24Plus` has no fixed size, assume it's stored in 32 bits.
+        let texel_size = |aspect| format.block_size(aspect).unwrap_or(4) as wgt::BufferAddress;
+        let block_size = if format.is_combined_depth_stencil_format() {
+            texel_size(Some(wgt::TextureAspect::DepthOnly))
+                + texel_size(Some(wgt::TextureAspect::StencilOnly))
+        } else {
+            texel_size(None)
+        };
+        let (block_width, block_height) = format.block_dimensions();
+
+        let blocks = (0..self.desc.mip_level_count)
+            .map(|mip_level| {
+                let size = self.desc.mip_level_size(mip_level).unwrap();
+                let width = (size.width + block_width - 1) / block_width;
+                let height = (size.height + block_height - 1) / block_height;
+                width as wgt::BufferAddress
+                    * height as wgt::BufferAddress
+                    * size.depth_or_array_layers as wgt::BufferAddress
+            })
+            .sum::<wgt::BufferAddress>();
+        blocks * block_size * self.desc.sample_count as wgt::BufferAddress
+    }
+
     pub(crate) fn get_clear_view(&self, mip_level: u32, depth_or_layer: u32) -> &A::TextureView {
         match self.clear_mode {
             TextureClearMode::BufferCopy => {
@@ -454,6 +508,29 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
         hal_texture_callback(hal_texture);
     }
 
+    /// # Safety
+    ///
+    /// - The raw semaphore handle must not be manually destroyed
+    pub unsafe fn external_semaphore_as_hal<
+        A: HalApi,
+        F: FnOnce(Option<&A::ExternalSemaphore>) -> R,
+        R,
+    >(
+        &self,
+        id: ExternalSemaphoreId,
+        hal_external_semaphore_callback: F,
+    ) -> R {
+        profiling::scope!("ExternalSemaphore::as_hal");
+
+        let hub = A::hub(self);
+        let mut token = Token::root();
+        let (guard, _) = hub.external_semaphores.read(&mut token);
+        let semaphore = guard.try_get(id).ok().flatten();
+        let hal_semaphore = semaphore.map(|semaphore| &semaphore.raw);
+
+        hal_external_semaphore_callback(hal_semaphore)
+    }
+
     /// # Safety
     ///
     /// - The raw adapter handle must not be manually destroyed
@@ -587,6 +664,10 @@ pub enum CreateTextureError {
     MissingFeatures(wgt::TextureFormat, #[source] MissingFeatures),
     #[error(transparent)]
     MissingDownlevelFlags(#[from] MissingDownlevelFlags),
+    #[error(transparent)]
+    Placement(#[from] PlacementError),
+    #[error(transparent)]
+    Sparse(#[from] SparseResourceError),
 }
 
 impl<A: hal::Api> Resource for Texture<A> {
@@ -825,6 +906,203 @@ impl<A: hal::Api> Resource for Sampler<A> {
     }
 }
 
+pub type HeapDescriptor<'a> = wgt::HeapDescriptor<Label<'a>>;
+
+/// Memory that buffers and textures can be placed in, see
+/// [`wgt::Features::RESOURCE_HEAPS`].
+#[derive(Debug)]
+pub struct Heap<A: hal::Api> {
+    pub(crate) raw: A::Heap,
+    pub(crate) device_id: Stored<DeviceId>,
+    pub(crate) size: wgt::BufferAddress,
+    pub(crate) life_guard: LifeGuard,
+    /// The resource whose contents each range of the heap holds, as of the
+    /// last submission that used one of the resources placed in it.
+    pub(crate) residents: Vec<(Range<wgt::BufferAddress>, HeapResource)>,
+}
+
+impl<A: hal::Api> Heap<A> {
+    /// Make `resource`, placed at `range`, the owner of that range of the heap.
+    ///
+    /// Returns the resources that held an overlapping range until now: their
+    /// contents are undefined from this point on.
+    pub(crate) fn make_resident(
+        &mut self,
+        resource: HeapResource,
+        range: &Range<wgt::BufferAddress>,
+    ) -> Vec<HeapResource> {
+        if self
+            .residents
+            .iter()
+            .any(|&(ref r, res)| res == resource && r == range)
+        {
+            return Vec::new();
+        }
+
+        let mut evicted = Vec::new();
+        self.residents.retain(|&(ref r, res)| {
+            let overlaps = r.start < range.end && range.start < r.end;
+            if overlaps && res != resource {
+                evicted.push(res);
+            }
+            !overlaps
+        });
+        self.residents.push((range.clone(), resource));
+        evicted
+    }
+}
+
+impl<A: hal::Api> Resource for Heap<A> {
+    const TYPE: &'static str = "Heap";
+
+    fn life_guard(&self) -> &LifeGuard {
+        &self.life_guard
+    }
+}
+
+#[derive(Clone, Debug, Error)]
+#[non_exhaustive]
+pub enum CreateHeapError {
+    #[error(transparent)]
+    Device(#[from] DeviceError),
+    #[error("Heaps cannot be empty")]
+    ZeroSize,
+    #[error(transparent)]
+    MissingFeatures(#[from] MissingFeatures),
+}
+
+/// A buffer or texture placed in a [`Heap`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum HeapResource {
+    Buffer(BufferId),
+    Texture(TextureId),
+}
+
+/// The range of a [`Heap`] a buffer or texture is bound to.
+#[derive(Debug)]
+pub(crate) struct HeapPlacement {
+    pub(crate) heap_id: Stored<HeapId>,
+    pub(crate) range: Range<wgt::BufferAddress>,
+}
+
+impl HeapPlacement {
+    pub(crate) fn overlaps(&self, other: &Self) -> bool {
+        self.heap_id.value == other.heap_id.value
+            && self.range.start < other.range.end
+            && other.range.start < self.range.end
+    }
+}
+
+/// Error creating a buffer or a texture in a [`Heap`].
+#[derive(Clone, Debug, Error)]
+#[non_exhaustive]
+pub enum PlacementError {
+    #[error("Heap {0:?} is invalid")]
+    InvalidHeap(HeapId),
+    #[error(transparent)]
+    MissingFeatures(#[from] MissingFeatures),
+    #[error("Buffers placed in a heap can't be mapped")]
+    Mappable,
+    #[error("Offset {offset} must be a multiple of {alignment}")]
+    Misaligned {
+        offset: wgt::BufferAddress,
+        alignment: wgt::BufferAddress,
+    },
+    #[error("The resource needs {size} bytes at offset {offset}, which is past the end of the heap ({heap_size} bytes)")]
+    OutOfBounds {
+        offset: wgt::BufferAddress,
+        size: wgt::BufferAddress,
+        heap_size: wgt::BufferAddress,
+    },
+    #[error("The resource can't be placed in the memory of the heap")]
+    IncompatibleMemory,
+}
+
+/// The heaps the pages of a sparse buffer or texture are bound to, see
+/// [`wgt::Features::SPARSE_BINDING`].
+#[derive(Debug)]
+pub(crate) struct SparsePages {
+    /// The resource may be used while some of its pages are unbound.
+    pub(crate) residency: bool,
+    pub(crate) pages: Vec<Option<Stored<HeapId>>>,
+}
+
+impl SparsePages {
+    pub(crate) fn new(residency: bool, count: usize) -> Self {
+        Self {
+            residency,
+            pages: iter::repeat_with(|| None).take(count).collect(),
+        }
+    }
+
+    pub(crate) fn is_fully_bound(&self) -> bool {
+        self.pages.iter().all(Option::is_some)
+    }
+
+    /// The heaps bound to any of the pages, each one only once.
+    pub(crate) fn into_heaps(self) -> Vec<Stored<HeapId>> {
+        let mut heaps = Vec::<Stored<HeapId>>::new();
+        for heap in self.pages.into_iter().flatten() {
+            if heaps.iter().all(|other| other.value != heap.value) {
+                heaps.push(heap);
+            }
+        }
+        heaps
+    }
+}
+
+/// The number of sparse tiles of `mip_level` of a texture, in columns and rows.
+pub(crate) fn sparse_tiles<L, V>(
+    desc: &wgt::TextureDescriptor<L, V>,
+    mip_level: u32,
+) -> (u32, u32) {
+    let (tile_width, tile_height) = desc.format.sparse_tile_extent().unwrap();
+    let size = desc.size.mip_level_size(mip_level, desc.dimension);
+    (size.width / tile_width, size.height / tile_height)
+}
+
+/// The index of the first page of `mip_level` and `array_layer` of a sparse
+/// texture.
+///
+/// Pages go through the tiles in row-major order, one array layer after the
+/// other, and one mip level after the other.
+pub(crate) fn sparse_page_base<L, V>(
+    desc: &wgt::TextureDescriptor<L, V>,
+    mip_level: u32,
+    array_layer: u32,
+) -> usize {
+    let layer_pages = |mip_level| {
+        let (columns, rows) = sparse_tiles(desc, mip_level);
+        columns as usize * rows as usize
+    };
+    let mip_base = (0..mip_level)
+        .map(|mip_level| layer_pages(mip_level) * desc.array_layer_count() as usize)
+        .sum::<usize>();
+    mip_base + array_layer as usize * layer_pages(mip_level)
+}
+
+/// Error creating a sparse buffer or texture.
+#[derive(Clone, Debug, Error)]
+#[non_exhaustive]
+pub enum SparseResourceError {
+    #[error(transparent)]
+    MissingFeatures(#[from] MissingFeatures),
+    #[error("Sparse buffers can't be mapped")]
+    Mappable,
+    #[error("Sparse textures must be 2D and can't be multisampled")]
+    InvalidDimension,
+    #[error("Textures of format {0:?} can't be sparse")]
+    UnsupportedFormat(wgt::TextureFormat),
+    #[error("Mip level {mip_level} is {width}x{height}, which is not a multiple of the {tile_width}x{tile_height} sparse tiles")]
+    PartialTiles {
+        mip_level: u32,
+        width: u32,
+        height: u32,
+        tile_width: u32,
+        tile_height: u32,
+    },
+}
+
 #[derive(Clone, Debug, Error)]
 #[non_exhaustive]
 pub enum CreateQuerySetError {
@@ -846,6 +1124,9 @@ pub struct QuerySet<A: hal::Api> {
     pub(crate) device_id: Stored<DeviceId>,
     pub(crate) life_guard: LifeGuard,
     pub(crate) desc: wgt::QuerySetDescriptor<()>,
+    /// Queries that have been written by submitted commands, so that
+    /// resolving the others can produce zeros.
+    pub(crate) initialization_status: Mutex<QuerySetInitTracker>,
 }
 
 impl<A: hal::Api> Resource for QuerySet<A> {
@@ -856,6 +1137,80 @@ impl<A: hal::Api> Resource for QuerySet<A> {
     }
 }
 
+/// A bottom level acceleration structure, see [`crate::ray_tracing`].
+#[derive(Debug)]
+pub struct Blas<A: hal::Api> {
+    pub(crate) raw: A::AccelerationStructure,
+    pub(crate) device_id: Stored<DeviceId>,
+    pub(crate) life_guard: LifeGuard,
+    pub(crate) size_info: hal::AccelerationStructureBuildSizes,
+    pub(crate) sizes: wgt::BlasGeometrySizeDescriptors,
+    pub(crate) flags: wgt::AccelerationStructureFlags,
+    pub(crate) update_mode: wgt::AccelerationStructureUpdateMode,
+    /// The index of the last submission building this acceleration structure.
+    pub(crate) built_index: Option<SubmissionIndex>,
+    /// The device address referenced by instances in a [`Tlas`] build.
+    pub(crate) handle: u64,
+}
+
+impl<A: hal::Api> Resource for Blas<A> {
+    const TYPE: &'static str = "Blas";
+
+    fn life_guard(&self) -> &LifeGuard {
+        &self.life_guard
+    }
+}
+
+/// A top level acceleration structure, see [`crate::ray_tracing`].
+#[derive(Debug)]
+pub struct Tlas<A: hal::Api> {
+    pub(crate) raw: A::AccelerationStructure,
+    pub(crate) device_id: Stored<DeviceId>,
+    pub(crate) life_guard: LifeGuard,
+    pub(crate) size_info: hal::AccelerationStructureBuildSizes,
+    pub(crate) max_instance_count: u32,
+    pub(crate) flags: wgt::AccelerationStructureFlags,
+    pub(crate) update_mode: wgt::AccelerationStructureUpdateMode,
+    /// The index of the last submission building this acceleration structure.
+    pub(crate) built_index: Option<SubmissionIndex>,
+}
+
+impl<A: hal::Api> Resource for Tlas<A> {
+    const TYPE: &'static str = "Tlas";
+
+    fn life_guard(&self) -> &LifeGuard {
+        &self.life_guard
+    }
+}
+
+pub type ExternalSemaphoreDescriptor<'a> = wgt::ExternalSemaphoreDescriptor<Label<'a>>;
+
+/// A timeline semaphore shared with other APIs, see
+/// [`wgt::Features::EXTERNAL_SEMAPHORES`].
+#[derive(Debug)]
+pub struct ExternalSemaphore<A: hal::Api> {
+    pub(crate) raw: A::ExternalSemaphore,
+    pub(crate) device_id: Stored<DeviceId>,
+    pub(crate) life_guard: LifeGuard,
+}
+
+impl<A: hal::Api> Resource for ExternalSemaphore<A> {
+    const TYPE: &'static str = "ExternalSemaphore";
+
+    fn life_guard(&self) -> &LifeGuard {
+        &self.life_guard
+    }
+}
+
+#[derive(Clone, Debug, Error)]
+#[non_exhaustive]
+pub enum CreateExternalSemaphoreError {
+    #[error(transparent)]
+    Device(#[from] DeviceError),
+    #[error(transparent)]
+    MissingFeatures(#[from] MissingFeatures),
+}
+
 #[derive(Clone, Debug, Error)]
 #[non_exhaustive]
 pub enum DestroyError {
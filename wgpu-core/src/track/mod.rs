@@ -196,7 +196,7 @@ pub(crate) trait ResourceUses:
 
 /// Returns true if the given states violates the usage scope rule
 /// of any(inclusive) XOR one(exclusive)
-fn invalid_resource_state<T: ResourceUses>(state: T) -> bool {
+pub(crate) fn invalid_resource_state<T: ResourceUses>(state: T) -> bool {
     // Is power of two also means "is one bit set". We check for this as if
     // we're in any exclusive state, we must only be in a single state.
     state.any_exclusive() && !conv::is_power_of_two_u16(state.bits())
@@ -228,6 +228,11 @@ pub enum UsageConflict {
         array_layers: ops::Range<u32>,
         invalid_use: InvalidUse<hal::TextureUses>,
     },
+    #[error("Attempted to use {first:?} and {second:?}, which alias each other in the same heap")]
+    Aliased {
+        first: resource::HeapResource,
+        second: resource::HeapResource,
+    },
 }
 
 impl UsageConflict {
@@ -279,6 +284,14 @@ impl crate::error::PrettyError for UsageConflict {
             Self::Texture { id, .. } => {
                 fmt.texture_label(&id);
             }
+            Self::Aliased { first, second } => {
+                for resource in [first, second] {
+                    match resource {
+                        resource::HeapResource::Buffer(id) => fmt.buffer_label(&id),
+                        resource::HeapResource::Texture(id) => fmt.texture_label(&id),
+                    }
+                }
+            }
         }
     }
 }
@@ -320,6 +333,7 @@ pub(crate) struct BindGroupStates<A: HalApi> {
     pub textures: TextureBindGroupState<A>,
     pub views: StatelessBindGroupSate<resource::TextureView<A>, id::TextureViewId>,
     pub samplers: StatelessBindGroupSate<resource::Sampler<A>, id::SamplerId>,
+    pub acceleration_structures: StatelessBindGroupSate<resource::Tlas<A>, id::TlasId>,
 }
 
 impl<A: HalApi> BindGroupStates<A> {
@@ -329,6 +343,7 @@ impl<A: HalApi> BindGroupStates<A> {
             textures: TextureBindGroupState::new(),
             views: StatelessBindGroupSate::new(),
             samplers: StatelessBindGroupSate::new(),
+            acceleration_structures: StatelessBindGroupSate::new(),
         }
     }
 
@@ -341,6 +356,7 @@ impl<A: HalApi> BindGroupStates<A> {
         self.textures.optimize();
         self.views.optimize();
         self.samplers.optimize();
+        self.acceleration_structures.optimize();
     }
 }
 
@@ -406,6 +422,39 @@ impl<A: HalApi> RenderBundleScope<A> {
     }
 }
 
+/// This is a compute bundle specific scope. It owns the resources used by a
+/// compute bundle, but unlike [`RenderBundleScope`] it is never merged into a
+/// usage scope as a whole: every dispatch of a compute pass is its own usage
+/// scope, which is built from the bind groups of the dispatch when the bundle
+/// is executed.
+pub(crate) struct ComputeBundleScope<A: HalApi> {
+    /// Indirect buffers. Everything else is used through bind groups.
+    pub buffers: BufferUsageScope<A>,
+    pub bind_groups: StatelessTracker<A, binding_model::BindGroup<A>, id::BindGroupId>,
+    pub compute_pipelines: StatelessTracker<A, pipeline::ComputePipeline<A>, id::ComputePipelineId>,
+}
+
+impl<A: HalApi> ComputeBundleScope<A> {
+    /// Create the compute bundle scope and pull the maximum IDs from the hubs.
+    pub fn new(
+        buffers: &storage::Storage<resource::Buffer<A>, id::BufferId>,
+        bind_groups: &storage::Storage<binding_model::BindGroup<A>, id::BindGroupId>,
+        compute_pipelines: &storage::Storage<pipeline::ComputePipeline<A>, id::ComputePipelineId>,
+    ) -> Self {
+        let mut value = Self {
+            buffers: BufferUsageScope::new(),
+            bind_groups: StatelessTracker::new(),
+            compute_pipelines: StatelessTracker::new(),
+        };
+
+        value.buffers.set_size(buffers.len());
+        value.bind_groups.set_size(bind_groups.len());
+        value.compute_pipelines.set_size(compute_pipelines.len());
+
+        value
+    }
+}
+
 /// A usage scope tracker. Only needs to store stateful resources as stateless
 /// resources cannot possibly have a usage conflict.
 #[derive(Debug)]
@@ -474,6 +523,37 @@ impl<A: HalApi> UsageScope<A> {
 
         Ok(())
     }
+
+    /// Check that no two resources in the scope are placed at overlapping
+    /// ranges of the same heap.
+    pub fn check_aliasing(
+        &self,
+        buffers: &storage::Storage<resource::Buffer<A>, id::BufferId>,
+        textures: &storage::Storage<resource::Texture<A>, id::TextureId>,
+    ) -> Result<(), UsageConflict> {
+        let placed_buffers = self.buffers.used().filter_map(|id| {
+            let placement = buffers.get(id.0).ok()?.heap.as_ref()?;
+            Some((placement, resource::HeapResource::Buffer(id.0)))
+        });
+        let placed_textures = self.textures.used().filter_map(|id| {
+            let placement = textures.get(id.0).ok()?.heap.as_ref()?;
+            Some((placement, resource::HeapResource::Texture(id.0)))
+        });
+
+        let mut placed = Vec::new();
+        for (placement, resource) in placed_buffers.chain(placed_textures) {
+            if let Some(&(_, first)) = placed.iter().find(|&&(other, _)| placement.overlaps(other))
+            {
+                return Err(UsageConflict::Aliased {
+                    first,
+                    second: resource,
+                });
+            }
+            placed.push((placement, resource));
+        }
+
+        Ok(())
+    }
 }
 
 /// A full double sided tracker used by CommandBuffers and the Device.
@@ -486,7 +566,10 @@ pub(crate) struct Tracker<A: HalApi> {
     pub compute_pipelines: StatelessTracker<A, pipeline::ComputePipeline<A>, id::ComputePipelineId>,
     pub render_pipelines: StatelessTracker<A, pipeline::RenderPipeline<A>, id::RenderPipelineId>,
     pub bundles: StatelessTracker<A, command::RenderBundle<A>, id::RenderBundleId>,
+    pub compute_bundles: StatelessTracker<A, command::ComputeBundle<A>, id::ComputeBundleId>,
     pub query_sets: StatelessTracker<A, resource::QuerySet<A>, id::QuerySetId>,
+    pub blas_s: StatelessTracker<A, resource::Blas<A>, id::BlasId>,
+    pub tlas_s: StatelessTracker<A, resource::Tlas<A>, id::TlasId>,
 }
 
 impl<A: HalApi> Tracker<A> {
@@ -500,7 +583,10 @@ impl<A: HalApi> Tracker<A> {
             compute_pipelines: StatelessTracker::new(),
             render_pipelines: StatelessTracker::new(),
             bundles: StatelessTracker::new(),
+            compute_bundles: StatelessTracker::new(),
             query_sets: StatelessTracker::new(),
+            blas_s: StatelessTracker::new(),
+            tlas_s: StatelessTracker::new(),
         }
     }
 
@@ -519,6 +605,7 @@ impl<A: HalApi> Tracker<A> {
             &storage::Storage<pipeline::RenderPipeline<A>, id::RenderPipelineId>,
         >,
         bundles: Option<&storage::Storage<command::RenderBundle<A>, id::RenderBundleId>>,
+        compute_bundles: Option<&storage::Storage<command::ComputeBundle<A>, id::ComputeBundleId>>,
         query_sets: Option<&storage::Storage<resource::QuerySet<A>, id::QuerySetId>>,
     ) {
         if let Some(buffers) = buffers {
@@ -545,6 +632,9 @@ impl<A: HalApi> Tracker<A> {
         if let Some(bundles) = bundles {
             self.bundles.set_size(bundles.len());
         };
+        if let Some(compute_bundles) = compute_bundles {
+            self.compute_bundles.set_size(compute_bundles.len());
+        };
         if let Some(query_sets) = query_sets {
             self.query_sets.set_size(query_sets.len());
         };
@@ -612,4 +702,14 @@ impl<A: HalApi> Tracker<A> {
 
         Ok(())
     }
+
+    /// Tracks the stateless resources from the given compute bundle. The
+    /// stateful resources are merged into the usage scope of each dispatch
+    /// while the bundle is executed.
+    pub fn add_from_compute_bundle(&mut self, compute_bundle: &ComputeBundleScope<A>) {
+        self.bind_groups
+            .add_from_tracker(&compute_bundle.bind_groups);
+        self.compute_pipelines
+            .add_from_tracker(&compute_bundle.compute_pipelines);
+    }
 }
@@ -16,6 +16,7 @@ enum ResourceType {
     Sampler {
         comparison: bool,
     },
+    AccelerationStructure,
 }
 
 #[derive(Debug)]
@@ -422,6 +423,10 @@ impl Resource {
                 }
                 _ => return Err(BindingError::WrongType),
             },
+            ResourceType::AccelerationStructure => match entry.ty {
+                BindingType::AccelerationStructure => (),
+                _ => return Err(BindingError::WrongType),
+            },
             ResourceType::Texture {
                 dim,
                 arrayed,
@@ -534,6 +539,7 @@ impl Resource {
             } else {
                 wgt::SamplerBindingType::Filtering
             }),
+            ResourceType::AccelerationStructure => BindingType::AccelerationStructure,
             ResourceType::Texture {
                 dim,
                 arrayed,
@@ -867,6 +873,7 @@ impl Interface {
                     class,
                 },
                 naga::TypeInner::Sampler { comparison } => ResourceType::Sampler { comparison },
+                naga::TypeInner::AccelerationStructure => ResourceType::AccelerationStructure,
                 naga::TypeInner::Array { stride, .. } => ResourceType::Buffer {
                     size: wgt::BufferSize::new(stride as u64).unwrap(),
                 },
@@ -115,7 +115,11 @@ impl<A: hal::Api> Example<A> {
 
         let hal::OpenDevice { device, mut queue } = unsafe {
             adapter
-                .open(wgt::Features::empty(), &wgt::Limits::default())
+                .open(
+                    wgt::Features::empty(),
+                    &wgt::Limits::default(),
+                    &wgt::MemoryHints::MemoryUsage,
+                )
                 .unwrap()
         };
 
@@ -257,6 +261,7 @@ impl<A: hal::Api> Example<A> {
                 write_mask: wgt::ColorWrites::default(),
             })],
             multiview: None,
+            cache: None,
         };
         let pipeline = unsafe { device.create_render_pipeline(&pipeline_desc).unwrap() };
 
@@ -302,6 +307,7 @@ impl<A: hal::Api> Example<A> {
         let cmd_encoder_desc = hal::CommandEncoderDescriptor {
             label: None,
             queue: &queue,
+            reusable: false,
         };
         let mut cmd_encoder = unsafe { device.create_command_encoder(&cmd_encoder_desc).unwrap() };
         unsafe { cmd_encoder.begin_encoding(Some("init")).unwrap() };
@@ -429,6 +435,7 @@ impl<A: hal::Api> Example<A> {
                 buffers: &[global_buffer_binding],
                 samplers: &[&sampler],
                 textures: &[texture_binding],
+                acceleration_structures: &[],
                 entries: &[
                     hal::BindGroupEntry {
                         binding: 0,
@@ -462,6 +469,7 @@ impl<A: hal::Api> Example<A> {
                 buffers: &[local_buffer_binding],
                 samplers: &[],
                 textures: &[],
+                acceleration_structures: &[],
                 entries: &[hal::BindGroupEntry {
                     binding: 0,
                     resource_index: 0,
@@ -728,6 +736,7 @@ impl<A: hal::Api> Example<A> {
                 let hal_desc = hal::CommandEncoderDescriptor {
                     label: None,
                     queue: &self.queue,
+                    reusable: false,
                 };
                 self.contexts.push(unsafe {
                     ExecutionContext {
@@ -0,0 +1,121 @@
+use std::time::Instant;
+
+use super::Api;
+
+impl super::Adapter {
+    pub(super) fn expose() -> crate::ExposedAdapter<Api> {
+        let features = wgt::Features::DEPTH_CLIP_CONTROL
+            | wgt::Features::TIMESTAMP_QUERY
+            | wgt::Features::TIMESTAMP_QUERY_INSIDE_PASSES
+            | wgt::Features::INDIRECT_FIRST_INSTANCE
+            | wgt::Features::DEPTH32FLOAT_STENCIL8
+            | wgt::Features::RG11B10UFLOAT_RENDERABLE
+            | wgt::Features::BGRA8UNORM_STORAGE
+            | wgt::Features::TEXTURE_FORMAT_16BIT_NORM
+            | wgt::Features::MAPPABLE_PRIMARY_BUFFERS
+            | wgt::Features::MULTI_DRAW_INDIRECT
+            | wgt::Features::MULTI_DRAW_INDIRECT_COUNT
+            | wgt::Features::ADDRESS_MODE_CLAMP_TO_ZERO
+            | wgt::Features::ADDRESS_MODE_CLAMP_TO_BORDER
+            | wgt::Features::VERTEX_WRITABLE_STORAGE
+            | wgt::Features::CLEAR_TEXTURE
+            | wgt::Features::SHADER_F64
+            | wgt::Features::SHADER_PRIMITIVE_INDEX
+            | wgt::Features::SHADER_UNUSED_VERTEX_OUTPUT
+            | wgt::Features::DUAL_SOURCE_BLENDING;
+
+        let downlevel = wgt::DownlevelCapabilities {
+            flags: wgt::DownlevelFlags::compliant() - wgt::DownlevelFlags::MULTISAMPLED_SHADING,
+            limits: wgt::DownlevelLimits {},
+            shader_model: wgt::ShaderModel::Sm5,
+        };
+
+        crate::ExposedAdapter {
+            adapter: Self,
+            info: wgt::AdapterInfo {
+                name: String::from("CPU software rasterizer"),
+                vendor: 0,
+                device: 0,
+                device_type: wgt::DeviceType::Cpu,
+                driver: String::from("wgpu-hal"),
+                driver_info: String::new(),
+                backend: wgt::Backend::Cpu,
+            },
+            features,
+            capabilities: crate::Capabilities {
+                limits: wgt::Limits::default(),
+                alignments: crate::Alignments {
+                    buffer_copy_offset: wgt::BufferSize::new(4).unwrap(),
+                    buffer_copy_pitch: wgt::BufferSize::new(4).unwrap(),
+                },
+                downlevel,
+            },
+        }
+    }
+}
+
+impl crate::Adapter<Api> for super::Adapter {
+    unsafe fn open(
+        &self,
+        _features: wgt::Features,
+        _limits: &wgt::Limits,
+        _memory_hints: &wgt::MemoryHints,
+    ) -> Result<crate::OpenDevice<Api>, crate::DeviceError> {
+        Ok(crate::OpenDevice {
+            device: super::Device,
+            queue: super::Queue {
+                epoch: Instant::now(),
+            },
+        })
+    }
+
+    unsafe fn texture_format_capabilities(
+        &self,
+        format: wgt::TextureFormat,
+    ) -> crate::TextureFormatCapabilities {
+        use crate::TextureFormatCapabilities as Tfc;
+        use wgt::TextureFormat as Tf;
+
+        if format.is_compressed() {
+            return Tfc::empty();
+        }
+
+        let mut caps = Tfc::SAMPLED
+            | Tfc::SAMPLED_LINEAR
+            | Tfc::COPY_SRC
+            | Tfc::COPY_DST
+            | Tfc::MULTISAMPLE_X4;
+        if format.is_depth_stencil_format() {
+            return caps | Tfc::DEPTH_STENCIL_ATTACHMENT;
+        }
+
+        caps |= Tfc::COLOR_ATTACHMENT | Tfc::MULTISAMPLE_RESOLVE;
+        match format.sample_type(None) {
+            Some(wgt::TextureSampleType::Float { .. }) => caps |= Tfc::COLOR_ATTACHMENT_BLEND,
+            _ => caps -= Tfc::SAMPLED_LINEAR | Tfc::MULTISAMPLE_RESOLVE,
+        }
+        if format != Tf::Rgb9e5Ufloat {
+            caps |= Tfc::STORAGE | Tfc::STORAGE_READ_WRITE;
+        } else {
+            caps -= Tfc::COLOR_ATTACHMENT
+                | Tfc::COLOR_ATTACHMENT_BLEND
+                | Tfc::MULTISAMPLE_X4
+                | Tfc::MULTISAMPLE_RESOLVE;
+        }
+        if format.is_srgb() {
+            caps -= Tfc::STORAGE | Tfc::STORAGE_READ_WRITE;
+        }
+        caps
+    }
+
+    unsafe fn surface_capabilities(
+        &self,
+        surface: &super::Surface,
+    ) -> Option<crate::SurfaceCapabilities> {
+        match *surface {}
+    }
+
+    unsafe fn get_presentation_timestamp(&self) -> wgt::PresentationTimestamp {
+        wgt::PresentationTimestamp::INVALID_TIMESTAMP
+    }
+}
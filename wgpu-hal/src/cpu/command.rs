@@ -0,0 +1,519 @@
+use std::{mem, ops::Range, sync::Arc};
+
+use super::{raster, shader::BoundGroup, Api, Memory, TextureInner};
+
+/// A recorded command, holding on to the resources it uses.
+#[derive(Debug)]
+pub(super) enum Command {
+    ClearBuffer {
+        memory: Arc<Memory>,
+        range: crate::MemoryRange,
+    },
+    CopyBufferToBuffer {
+        src: Arc<Memory>,
+        dst: Arc<Memory>,
+        regions: Vec<crate::BufferCopy>,
+    },
+    CopyTextureToTexture {
+        src: Arc<TextureInner>,
+        dst: Arc<TextureInner>,
+        regions: Vec<crate::TextureCopy>,
+    },
+    CopyBufferToTexture {
+        src: Arc<Memory>,
+        dst: Arc<TextureInner>,
+        regions: Vec<crate::BufferTextureCopy>,
+    },
+    CopyTextureToBuffer {
+        src: Arc<TextureInner>,
+        dst: Arc<Memory>,
+        regions: Vec<crate::BufferTextureCopy>,
+    },
+    BeginQuery {
+        set: Arc<Memory>,
+        index: u32,
+    },
+    EndQuery,
+    WriteTimestamp {
+        set: Arc<Memory>,
+        index: u32,
+    },
+    ResetQueries {
+        set: Arc<Memory>,
+        range: Range<u32>,
+    },
+    CopyQueryResults {
+        set: Arc<Memory>,
+        range: Range<u32>,
+        dst: Arc<Memory>,
+        offset: wgt::BufferAddress,
+        stride: wgt::BufferAddress,
+    },
+    BeginRenderPass(raster::RenderPass),
+    EndRenderPass,
+    SetBindGroup {
+        index: u32,
+        group: BoundGroup,
+    },
+    SetRenderPipeline(super::RenderPipeline),
+    SetIndexBuffer {
+        binding: raster::BufferBinding,
+        format: wgt::IndexFormat,
+    },
+    SetVertexBuffer {
+        index: u32,
+        binding: raster::BufferBinding,
+    },
+    SetViewport {
+        rect: crate::Rect<f32>,
+        depth_range: Range<f32>,
+    },
+    SetScissorRect(crate::Rect<u32>),
+    SetStencilReference(u32),
+    SetBlendConstants([f32; 4]),
+    Draw(raster::Draw),
+    DrawIndirect {
+        buffer: Arc<Memory>,
+        offset: wgt::BufferAddress,
+        /// Buffer and offset of the draw count, if any.
+        count: Option<(Arc<Memory>, wgt::BufferAddress)>,
+        max_count: u32,
+        indexed: bool,
+    },
+    SetComputePipeline(super::ComputePipeline),
+    Dispatch([u32; 3]),
+    DispatchIndirect {
+        buffer: Arc<Memory>,
+        offset: wgt::BufferAddress,
+    },
+}
+
+impl super::CommandEncoder {
+    /// Records the timestamp write at the beginning of a pass, and remembers
+    /// the one at the end.
+    fn begin_pass_timestamps(
+        &mut self,
+        query_set: &super::QuerySet,
+        beginning_index: Option<u32>,
+        end_index: Option<u32>,
+    ) {
+        if let Some(index) = beginning_index {
+            self.commands.push(Command::WriteTimestamp {
+                set: Arc::clone(&query_set.memory),
+                index,
+            });
+        }
+        self.pass_end_timestamp = end_index.map(|index| (Arc::clone(&query_set.memory), index));
+    }
+
+    fn end_pass_timestamps(&mut self) {
+        if let Some((set, index)) = self.pass_end_timestamp.take() {
+            self.commands.push(Command::WriteTimestamp { set, index });
+        }
+    }
+
+    fn push_draw_indirect(
+        &mut self,
+        buffer: &super::Buffer,
+        offset: wgt::BufferAddress,
+        count: Option<(&super::Buffer, wgt::BufferAddress)>,
+        max_count: u32,
+        indexed: bool,
+    ) {
+        self.commands.push(Command::DrawIndirect {
+            buffer: Arc::clone(&buffer.memory),
+            offset,
+            count: count.map(|(buffer, offset)| (Arc::clone(&buffer.memory), offset)),
+            max_count,
+            indexed,
+        });
+    }
+}
+
+impl crate::CommandEncoder<Api> for super::CommandEncoder {
+    unsafe fn begin_encoding(&mut self, _label: crate::Label) -> Result<(), crate::DeviceError> {
+        self.commands.clear();
+        self.pass_end_timestamp = None;
+        Ok(())
+    }
+    unsafe fn discard_encoding(&mut self) {
+        self.commands.clear();
+    }
+    unsafe fn end_encoding(&mut self) -> Result<super::CommandBuffer, crate::DeviceError> {
+        Ok(super::CommandBuffer {
+            commands: mem::take(&mut self.commands),
+        })
+    }
+    unsafe fn reset_all<I>(&mut self, _command_buffers: I) {}
+
+    unsafe fn transition_buffers<'a, T>(&mut self, _barriers: T)
+    where
+        T: Iterator<Item = crate::BufferBarrier<'a, Api>>,
+    {
+    }
+
+    unsafe fn transition_textures<'a, T>(&mut self, _barriers: T)
+    where
+        T: Iterator<Item = crate::TextureBarrier<'a, Api>>,
+    {
+    }
+
+    unsafe fn clear_buffer(&mut self, buffer: &super::Buffer, range: crate::MemoryRange) {
+        self.commands.push(Command::ClearBuffer {
+            memory: Arc::clone(&buffer.memory),
+            range,
+        });
+    }
+
+    unsafe fn copy_buffer_to_buffer<T>(
+        &mut self,
+        src: &super::Buffer,
+        dst: &super::Buffer,
+        regions: T,
+    ) where
+        T: Iterator<Item = crate::BufferCopy>,
+    {
+        self.commands.push(Command::CopyBufferToBuffer {
+            src: Arc::clone(&src.memory),
+            dst: Arc::clone(&dst.memory),
+            regions: regions.collect(),
+        });
+    }
+
+    #[cfg(all(target_arch = "wasm32", not(target_os = "emscripten")))]
+    unsafe fn copy_external_image_to_texture<T>(
+        &mut self,
+        _src: &wgt::ImageCopyExternalImage,
+        _dst: &super::Texture,
+        _dst_premultiplication: bool,
+        _regions: T,
+    ) where
+        T: Iterator<Item = crate::TextureCopy>,
+    {
+        log::error!("external images are not supported by the CPU backend");
+    }
+
+    unsafe fn copy_texture_to_texture<T>(
+        &mut self,
+        src: &super::Texture,
+        _src_usage: crate::TextureUses,
+        dst: &super::Texture,
+        regions: T,
+    ) where
+        T: Iterator<Item = crate::TextureCopy>,
+    {
+        self.commands.push(Command::CopyTextureToTexture {
+            src: Arc::clone(&src.inner),
+            dst: Arc::clone(&dst.inner),
+            regions: regions.collect(),
+        });
+    }
+
+    unsafe fn copy_buffer_to_texture<T>(
+        &mut self,
+        src: &super::Buffer,
+        dst: &super::Texture,
+        regions: T,
+    ) where
+        T: Iterator<Item = crate::BufferTextureCopy>,
+    {
+        self.commands.push(Command::CopyBufferToTexture {
+            src: Arc::clone(&src.memory),
+            dst: Arc::clone(&dst.inner),
+            regions: regions.collect(),
+        });
+    }
+
+    unsafe fn copy_texture_to_buffer<T>(
+        &mut self,
+        src: &super::Texture,
+        _src_usage: crate::TextureUses,
+        dst: &super::Buffer,
+        regions: T,
+    ) where
+        T: Iterator<Item = crate::BufferTextureCopy>,
+    {
+        self.commands.push(Command::CopyTextureToBuffer {
+            src: Arc::clone(&src.inner),
+            dst: Arc::clone(&dst.memory),
+            regions: regions.collect(),
+        });
+    }
+
+    unsafe fn begin_query(&mut self, set: &super::QuerySet, index: u32) {
+        self.commands.push(Command::BeginQuery {
+            set: Arc::clone(&set.memory),
+            index,
+        });
+    }
+    unsafe fn end_query(&mut self, _set: &super::QuerySet, _index: u32) {
+        self.commands.push(Command::EndQuery);
+    }
+    unsafe fn write_timestamp(&mut self, set: &super::QuerySet, index: u32) {
+        self.commands.push(Command::WriteTimestamp {
+            set: Arc::clone(&set.memory),
+            index,
+        });
+    }
+    unsafe fn reset_queries(&mut self, set: &super::QuerySet, range: Range<u32>) {
+        self.commands.push(Command::ResetQueries {
+            set: Arc::clone(&set.memory),
+            range,
+        });
+    }
+    unsafe fn copy_query_results(
+        &mut self,
+        set: &super::QuerySet,
+        range: Range<u32>,
+        buffer: &super::Buffer,
+        offset: wgt::BufferAddress,
+        stride: wgt::BufferSize,
+    ) {
+        self.commands.push(Command::CopyQueryResults {
+            set: Arc::clone(&set.memory),
+            range,
+            dst: Arc::clone(&buffer.memory),
+            offset,
+            stride: stride.get(),
+        });
+    }
+
+    // render
+
+    unsafe fn begin_render_pass(&mut self, desc: &crate::RenderPassDescriptor<Api>) {
+        if let Some(ref timestamp_writes) = desc.timestamp_writes {
+            self.begin_pass_timestamps(
+                timestamp_writes.query_set,
+                timestamp_writes.beginning_of_pass_write_index,
+                timestamp_writes.end_of_pass_write_index,
+            );
+        }
+        let color_targets = desc
+            .color_attachments
+            .iter()
+            .map(|attachment| {
+                attachment.as_ref().map(|attachment| raster::ColorTarget {
+                    view: attachment.target.view.clone(),
+                    resolve_target: attachment
+                        .resolve_target
+                        .as_ref()
+                        .map(|resolve| resolve.view.clone()),
+                    ops: attachment.ops,
+                    clear_value: attachment.clear_value,
+                })
+            })
+            .collect();
+        let depth_stencil =
+            desc.depth_stencil_attachment
+                .as_ref()
+                .map(|attachment| raster::DepthStencilTarget {
+                    view: attachment.target.view.clone(),
+                    depth_ops: attachment.depth_ops,
+                    stencil_ops: attachment.stencil_ops,
+                    clear_value: attachment.clear_value,
+                });
+        self.commands
+            .push(Command::BeginRenderPass(raster::RenderPass {
+                extent: desc.extent,
+                color_targets,
+                depth_stencil,
+            }));
+    }
+    unsafe fn end_render_pass(&mut self) {
+        self.commands.push(Command::EndRenderPass);
+        self.end_pass_timestamps();
+    }
+
+    unsafe fn set_bind_group(
+        &mut self,
+        _layout: &super::PipelineLayout,
+        index: u32,
+        group: &super::BindGroup,
+        dynamic_offsets: &[wgt::DynamicOffset],
+    ) {
+        self.commands.push(Command::SetBindGroup {
+            index,
+            group: BoundGroup {
+                entries: Arc::clone(&group.entries),
+                dynamic_offsets: dynamic_offsets.to_vec(),
+            },
+        });
+    }
+    unsafe fn set_push_constants(
+        &mut self,
+        _layout: &super::PipelineLayout,
+        _stages: wgt::ShaderStages,
+        _offset: u32,
+        _data: &[u32],
+    ) {
+    }
+
+    unsafe fn insert_debug_marker(&mut self, _label: &str) {}
+    unsafe fn begin_debug_marker(&mut self, _group_label: &str) {}
+    unsafe fn end_debug_marker(&mut self) {}
+
+    unsafe fn set_render_pipeline(&mut self, pipeline: &super::RenderPipeline) {
+        self.commands
+            .push(Command::SetRenderPipeline(pipeline.clone()));
+    }
+
+    unsafe fn set_index_buffer<'a>(
+        &mut self,
+        binding: crate::BufferBinding<'a, Api>,
+        format: wgt::IndexFormat,
+    ) {
+        self.commands.push(Command::SetIndexBuffer {
+            binding: raster::BufferBinding::new(&binding),
+            format,
+        });
+    }
+    unsafe fn set_vertex_buffer<'a>(&mut self, index: u32, binding: crate::BufferBinding<'a, Api>) {
+        self.commands.push(Command::SetVertexBuffer {
+            index,
+            binding: raster::BufferBinding::new(&binding),
+        });
+    }
+    unsafe fn set_viewport(&mut self, rect: &crate::Rect<f32>, depth_range: Range<f32>) {
+        self.commands.push(Command::SetViewport {
+            rect: rect.clone(),
+            depth_range,
+        });
+    }
+    unsafe fn set_scissor_rect(&mut self, rect: &crate::Rect<u32>) {
+        self.commands.push(Command::SetScissorRect(rect.clone()));
+    }
+    unsafe fn set_stencil_reference(&mut self, value: u32) {
+        self.commands.push(Command::SetStencilReference(value));
+    }
+    unsafe fn set_blend_constants(&mut self, color: &[f32; 4]) {
+        self.commands.push(Command::SetBlendConstants(*color));
+    }
+
+    unsafe fn draw(
+        &mut self,
+        start_vertex: u32,
+        vertex_count: u32,
+        start_instance: u32,
+        instance_count: u32,
+    ) {
+        self.commands.push(Command::Draw(raster::Draw {
+            first: start_vertex,
+            count: vertex_count,
+            base_vertex: 0,
+            first_instance: start_instance,
+            instance_count,
+            indexed: false,
+        }));
+    }
+    unsafe fn draw_indexed(
+        &mut self,
+        start_index: u32,
+        index_count: u32,
+        base_vertex: i32,
+        start_instance: u32,
+        instance_count: u32,
+    ) {
+        self.commands.push(Command::Draw(raster::Draw {
+            first: start_index,
+            count: index_count,
+            base_vertex,
+            first_instance: start_instance,
+            instance_count,
+            indexed: true,
+        }));
+    }
+    unsafe fn draw_indirect(
+        &mut self,
+        buffer: &super::Buffer,
+        offset: wgt::BufferAddress,
+        draw_count: u32,
+    ) {
+        self.push_draw_indirect(buffer, offset, None, draw_count, false);
+    }
+    unsafe fn draw_indexed_indirect(
+        &mut self,
+        buffer: &super::Buffer,
+        offset: wgt::BufferAddress,
+        draw_count: u32,
+    ) {
+        self.push_draw_indirect(buffer, offset, None, draw_count, true);
+    }
+    unsafe fn draw_indirect_count(
+        &mut self,
+        buffer: &super::Buffer,
+        offset: wgt::BufferAddress,
+        count_buffer: &super::Buffer,
+        count_offset: wgt::BufferAddress,
+        max_count: u32,
+    ) {
+        self.push_draw_indirect(
+            buffer,
+            offset,
+            Some((count_buffer, count_offset)),
+            max_count,
+            false,
+        );
+    }
+    unsafe fn draw_indexed_indirect_count(
+        &mut self,
+        buffer: &super::Buffer,
+        offset: wgt::BufferAddress,
+        count_buffer: &super::Buffer,
+        count_offset: wgt::BufferAddress,
+        max_count: u32,
+    ) {
+        self.push_draw_indirect(
+            buffer,
+            offset,
+            Some((count_buffer, count_offset)),
+            max_count,
+            true,
+        );
+    }
+
+    // compute
+
+    unsafe fn begin_compute_pass(&mut self, desc: &crate::ComputePassDescriptor<Api>) {
+        if let Some(ref timestamp_writes) = desc.timestamp_writes {
+            self.begin_pass_timestamps(
+                timestamp_writes.query_set,
+                timestamp_writes.beginning_of_pass_write_index,
+                timestamp_writes.end_of_pass_write_index,
+            );
+        }
+    }
+    unsafe fn end_compute_pass(&mut self) {
+        self.end_pass_timestamps();
+    }
+
+    unsafe fn set_compute_pipeline(&mut self, pipeline: &super::ComputePipeline) {
+        self.commands
+            .push(Command::SetComputePipeline(pipeline.clone()));
+    }
+
+    unsafe fn dispatch(&mut self, count: [u32; 3]) {
+        self.commands.push(Command::Dispatch(count));
+    }
+    unsafe fn dispatch_indirect(&mut self, buffer: &super::Buffer, offset: wgt::BufferAddress) {
+        self.commands.push(Command::DispatchIndirect {
+            buffer: Arc::clone(&buffer.memory),
+            offset,
+        });
+    }
+
+    unsafe fn build_acceleration_structures<'a, T>(
+        &mut self,
+        _descriptor_count: u32,
+        _descriptors: T,
+    ) where
+        Api: 'a,
+        T: IntoIterator<Item = crate::BuildAccelerationStructureDescriptor<'a, Api>>,
+    {
+    }
+
+    unsafe fn place_acceleration_structure_barrier(
+        &mut self,
+        _barriers: crate::AccelerationStructureBarrier,
+    ) {
+    }
+}
@@ -0,0 +1,602 @@
+//! Conversions between stored texel/vertex data and shader values, and the
+//! fixed function math shared by the rasterizer and the sampler.
+
+/// Four channels of a texel or vertex attribute, in shader representation.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(super) enum Texel {
+    Float([f32; 4]),
+    Uint([u32; 4]),
+    Sint([i32; 4]),
+}
+
+impl Texel {
+    pub(super) const ZERO: Self = Self::Float([0.0; 4]);
+
+    pub(super) fn to_float(self) -> [f32; 4] {
+        match self {
+            Self::Float(v) => v,
+            Self::Uint(v) => v.map(|c| c as f32),
+            Self::Sint(v) => v.map(|c| c as f32),
+        }
+    }
+
+    /// Converts a clear color to the representation used by `format`.
+    pub(super) fn from_color(format: wgt::TextureFormat, color: wgt::Color) -> Self {
+        let values = [color.r, color.g, color.b, color.a];
+        match format.sample_type(None) {
+            Some(wgt::TextureSampleType::Uint) => Self::Uint(values.map(|c| c as u32)),
+            Some(wgt::TextureSampleType::Sint) => Self::Sint(values.map(|c| c as i32)),
+            _ => Self::Float(values.map(|c| c as f32)),
+        }
+    }
+}
+
+/// Fills the channels missing from `values` with `(0, 0, 0, 1)`.
+fn widen<T: Copy>(values: impl Iterator<Item = T>, zero: T, one: T) -> [T; 4] {
+    let mut out = [zero, zero, zero, one];
+    for (slot, value) in out.iter_mut().zip(values) {
+        *slot = value;
+    }
+    out
+}
+
+pub(super) fn f16_to_f32(bits: u16) -> f32 {
+    let sign = if bits & 0x8000 != 0 { -1.0 } else { 1.0 };
+    let exponent = (bits >> 10) & 0x1f;
+    let mantissa = (bits & 0x3ff) as f32;
+    sign * match exponent {
+        0 => mantissa * 2f32.powi(-24),
+        0x1f if mantissa == 0.0 => f32::INFINITY,
+        0x1f => f32::NAN,
+        _ => (1.0 + mantissa / 1024.0) * 2f32.powi(exponent as i32 - 15),
+    }
+}
+
+pub(super) fn f32_to_f16(value: f32) -> u16 {
+    let bits = value.to_bits();
+    let sign = ((bits >> 16) & 0x8000) as u16;
+    if value.is_nan() {
+        return sign | 0x7e00;
+    }
+    let abs = value.abs();
+    if abs >= 65520.0 {
+        return sign | 0x7c00;
+    }
+    if abs < 2f32.powi(-14) {
+        // Subnormal, in units of 2^-24.
+        return sign | (abs * 2f32.powi(24)).round() as u16;
+    }
+    let exponent = abs.log2().floor() as i32;
+    let mut mantissa = ((abs / 2f32.powi(exponent) - 1.0) * 1024.0).round() as u32;
+    let mut exponent = exponent + 15;
+    if mantissa == 1024 {
+        mantissa = 0;
+        exponent += 1;
+    }
+    sign | ((exponent as u16) << 10) | mantissa as u16
+}
+
+/// Decodes the unsigned 5 bit exponent floats of `Rg11b10Float`.
+fn small_float_to_f32(bits: u32, mantissa_bits: u32) -> f32 {
+    let exponent = bits >> mantissa_bits;
+    let mantissa = (bits & ((1 << mantissa_bits) - 1)) as f32;
+    let scale = (1 << mantissa_bits) as f32;
+    match exponent {
+        0 => mantissa / scale * 2f32.powi(-14),
+        0x1f if mantissa == 0.0 => f32::INFINITY,
+        0x1f => f32::NAN,
+        _ => (1.0 + mantissa / scale) * 2f32.powi(exponent as i32 - 15),
+    }
+}
+
+fn f32_to_small_float(value: f32, mantissa_bits: u32) -> u32 {
+    // Reuse the half float encoding, which has the same exponent and a
+    // wider mantissa, then drop the extra mantissa bits.
+    if value.is_nan() {
+        return (0x1f << mantissa_bits) | 1;
+    }
+    let half = f32_to_f16(value.max(0.0)) as u32;
+    half >> (10 - mantissa_bits)
+}
+
+fn rgb9e5_to_f32(bits: u32) -> [f32; 3] {
+    let scale = 2f32.powi((bits >> 27) as i32 - 15 - 9);
+    [0, 9, 18].map(|shift| ((bits >> shift) & 0x1ff) as f32 * scale)
+}
+
+fn f32_to_rgb9e5(rgb: [f32; 3]) -> u32 {
+    const MAX: f32 = 511.0 / 512.0 * 65536.0;
+    let rgb = rgb.map(|c| if c.is_nan() { 0.0 } else { c.clamp(0.0, MAX) });
+    let max = rgb[0].max(rgb[1]).max(rgb[2]);
+    let mut exponent = (max.log2().floor() as i32).max(-16) + 16;
+    if (max / 2f32.powi(exponent - 15 - 9)).round() >= 512.0 {
+        exponent += 1;
+    }
+    let scale = 2f32.powi(exponent - 15 - 9);
+    let [r, g, b] = rgb.map(|c| ((c / scale).round() as u32).min(0x1ff));
+    ((exponent as u32) << 27) | (b << 18) | (g << 9) | r
+}
+
+pub(super) fn srgb_to_linear(c: f32) -> f32 {
+    if c <= 0.04045 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+pub(super) fn linear_to_srgb(c: f32) -> f32 {
+    if c <= 0.0031308 {
+        c * 12.92
+    } else {
+        1.055 * c.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+fn chunks<const N: usize>(data: &[u8], count: usize) -> impl Iterator<Item = [u8; N]> + '_ {
+    data.chunks_exact(N)
+        .take(count)
+        .map(|chunk| chunk.try_into().unwrap())
+}
+
+/// Decodes one texel of a color format.
+///
+/// Compressed formats aren't supported and decode as zero.
+pub(super) fn decode_color(format: wgt::TextureFormat, data: &[u8]) -> Texel {
+    use wgt::TextureFormat as Tf;
+
+    let n = format.components() as usize;
+    let word = || u32::from_le_bytes(data[..4].try_into().unwrap());
+    match format {
+        Tf::R8Unorm
+        | Tf::Rg8Unorm
+        | Tf::Rgba8Unorm
+        | Tf::Rgba8UnormSrgb
+        | Tf::Bgra8Unorm
+        | Tf::Bgra8UnormSrgb => {
+            let mut v = widen(data[..n].iter().map(|&c| c as f32 / 255.0), 0.0, 1.0);
+            if let Tf::Bgra8Unorm | Tf::Bgra8UnormSrgb = format {
+                v.swap(0, 2);
+            }
+            if format.is_srgb() {
+                for c in v[..3].iter_mut() {
+                    *c = srgb_to_linear(*c);
+                }
+            }
+            Texel::Float(v)
+        }
+        Tf::R8Snorm | Tf::Rg8Snorm | Tf::Rgba8Snorm => Texel::Float(widen(
+            data[..n]
+                .iter()
+                .map(|&c| (c as i8 as f32 / 127.0).max(-1.0)),
+            0.0,
+            1.0,
+        )),
+        Tf::R8Uint | Tf::Rg8Uint | Tf::Rgba8Uint => {
+            Texel::Uint(widen(data[..n].iter().map(|&c| c as u32), 0, 1))
+        }
+        Tf::R8Sint | Tf::Rg8Sint | Tf::Rgba8Sint => {
+            Texel::Sint(widen(data[..n].iter().map(|&c| c as i8 as i32), 0, 1))
+        }
+        Tf::R16Unorm | Tf::Rg16Unorm | Tf::Rgba16Unorm => Texel::Float(widen(
+            chunks(data, n).map(|c| u16::from_le_bytes(c) as f32 / 65535.0),
+            0.0,
+            1.0,
+        )),
+        Tf::R16Snorm | Tf::Rg16Snorm | Tf::Rgba16Snorm => Texel::Float(widen(
+            chunks(data, n).map(|c| (i16::from_le_bytes(c) as f32 / 32767.0).max(-1.0)),
+            0.0,
+            1.0,
+        )),
+        Tf::R16Uint | Tf::Rg16Uint | Tf::Rgba16Uint => Texel::Uint(widen(
+            chunks(data, n).map(|c| u16::from_le_bytes(c) as u32),
+            0,
+            1,
+        )),
+        Tf::R16Sint | Tf::Rg16Sint | Tf::Rgba16Sint => Texel::Sint(widen(
+            chunks(data, n).map(|c| i16::from_le_bytes(c) as i32),
+            0,
+            1,
+        )),
+        Tf::R16Float | Tf::Rg16Float | Tf::Rgba16Float => Texel::Float(widen(
+            chunks(data, n).map(|c| f16_to_f32(u16::from_le_bytes(c))),
+            0.0,
+            1.0,
+        )),
+        Tf::R32Uint | Tf::Rg32Uint | Tf::Rgba32Uint => {
+            Texel::Uint(widen(chunks(data, n).map(u32::from_le_bytes), 0, 1))
+        }
+        Tf::R32Sint | Tf::Rg32Sint | Tf::Rgba32Sint => {
+            Texel::Sint(widen(chunks(data, n).map(i32::from_le_bytes), 0, 1))
+        }
+        Tf::R32Float | Tf::Rg32Float | Tf::Rgba32Float => {
+            Texel::Float(widen(chunks(data, n).map(f32::from_le_bytes), 0.0, 1.0))
+        }
+        Tf::Rgb10a2Unorm => {
+            let bits = word();
+            Texel::Float([0, 10, 20, 30].map(|shift| {
+                let max = if shift == 30 { 3 } else { 0x3ff };
+                ((bits >> shift) & max) as f32 / max as f32
+            }))
+        }
+        Tf::Rgb10a2Uint => {
+            let bits = word();
+            Texel::Uint([0, 10, 20, 30].map(|shift| (bits >> shift) & 0x3ff))
+        }
+        Tf::Rg11b10Float => {
+            let bits = word();
+            Texel::Float([
+                small_float_to_f32(bits & 0x7ff, 6),
+                small_float_to_f32((bits >> 11) & 0x7ff, 6),
+                small_float_to_f32(bits >> 22, 5),
+                1.0,
+            ])
+        }
+        Tf::Rgb9e5Ufloat => {
+            let [r, g, b] = rgb9e5_to_f32(word());
+            Texel::Float([r, g, b, 1.0])
+        }
+        _ => Texel::ZERO,
+    }
+}
+
+/// Encodes one texel of a color format into `data`.
+pub(super) fn encode_color(format: wgt::TextureFormat, texel: Texel, data: &mut [u8]) {
+    use wgt::TextureFormat as Tf;
+
+    let n = format.components() as usize;
+    let float = texel.to_float();
+    let uint = match texel {
+        Texel::Float(v) => v.map(|c| c as u32),
+        Texel::Uint(v) => v,
+        Texel::Sint(v) => v.map(|c| c as u32),
+    };
+    let mut put = |width: usize, values: &mut dyn Iterator<Item = u32>| {
+        for (chunk, value) in data.chunks_exact_mut(width).zip(values) {
+            chunk.copy_from_slice(&value.to_le_bytes()[..width]);
+        }
+    };
+    match format {
+        Tf::R8Unorm
+        | Tf::Rg8Unorm
+        | Tf::Rgba8Unorm
+        | Tf::Rgba8UnormSrgb
+        | Tf::Bgra8Unorm
+        | Tf::Bgra8UnormSrgb => {
+            let mut v = float;
+            if format.is_srgb() {
+                for c in v[..3].iter_mut() {
+                    *c = linear_to_srgb(*c);
+                }
+            }
+            if let Tf::Bgra8Unorm | Tf::Bgra8UnormSrgb = format {
+                v.swap(0, 2);
+            }
+            put(
+                1,
+                &mut v[..n]
+                    .iter()
+                    .map(|&c| (c.clamp(0.0, 1.0) * 255.0).round() as u32),
+            );
+        }
+        Tf::R8Snorm | Tf::Rg8Snorm | Tf::Rgba8Snorm => put(
+            1,
+            &mut float[..n]
+                .iter()
+                .map(|&c| (c.clamp(-1.0, 1.0) * 127.0).round() as i32 as u32),
+        ),
+        Tf::R8Uint | Tf::Rg8Uint | Tf::Rgba8Uint | Tf::R8Sint | Tf::Rg8Sint | Tf::Rgba8Sint => {
+            put(1, &mut uint[..n].iter().copied())
+        }
+        Tf::R16Unorm | Tf::Rg16Unorm | Tf::Rgba16Unorm => put(
+            2,
+            &mut float[..n]
+                .iter()
+                .map(|&c| (c.clamp(0.0, 1.0) * 65535.0).round() as u32),
+        ),
+        Tf::R16Snorm | Tf::Rg16Snorm | Tf::Rgba16Snorm => put(
+            2,
+            &mut float[..n]
+                .iter()
+                .map(|&c| (c.clamp(-1.0, 1.0) * 32767.0).round() as i32 as u32),
+        ),
+        Tf::R16Uint
+        | Tf::Rg16Uint
+        | Tf::Rgba16Uint
+        | Tf::R16Sint
+        | Tf::Rg16Sint
+        | Tf::Rgba16Sint => put(2, &mut uint[..n].iter().copied()),
+        Tf::R16Float | Tf::Rg16Float | Tf::Rgba16Float => {
+            put(2, &mut float[..n].iter().map(|&c| f32_to_f16(c) as u32))
+        }
+        Tf::R32Uint
+        | Tf::Rg32Uint
+        | Tf::Rgba32Uint
+        | Tf::R32Sint
+        | Tf::Rg32Sint
+        | Tf::Rgba32Sint => put(4, &mut uint[..n].iter().copied()),
+        Tf::R32Float | Tf::Rg32Float | Tf::Rgba32Float => {
+            put(4, &mut float[..n].iter().map(|&c| c.to_bits()))
+        }
+        Tf::Rgb10a2Unorm => {
+            let mut bits = 0;
+            for (i, &c) in float.iter().enumerate() {
+                let max = if i == 3 { 3.0 } else { 1023.0 };
+                bits |= ((c.clamp(0.0, 1.0) * max).round() as u32) << (i * 10);
+            }
+            put(4, &mut Some(bits).into_iter());
+        }
+        Tf::Rgb10a2Uint => {
+            let mut bits = 0;
+            for (i, &c) in uint.iter().enumerate() {
+                let max = if i == 3 { 3 } else { 0x3ff };
+                bits |= c.min(max) << (i * 10);
+            }
+            put(4, &mut Some(bits).into_iter());
+        }
+        Tf::Rg11b10Float => {
+            let bits = f32_to_small_float(float[0], 6)
+                | (f32_to_small_float(float[1], 6) << 11)
+                | (f32_to_small_float(float[2], 5) << 22);
+            put(4, &mut Some(bits).into_iter());
+        }
+        Tf::Rgb9e5Ufloat => {
+            let bits = f32_to_rgb9e5([float[0], float[1], float[2]]);
+            put(4, &mut Some(bits).into_iter());
+        }
+        _ => {}
+    }
+}
+
+/// Size in bytes of one texel of the depth plane of `format`.
+pub(super) fn depth_texel_size(format: wgt::TextureFormat) -> u32 {
+    match format {
+        wgt::TextureFormat::Depth16Unorm => 2,
+        _ => 4,
+    }
+}
+
+pub(super) fn decode_depth(format: wgt::TextureFormat, data: &[u8]) -> f32 {
+    match format {
+        wgt::TextureFormat::Depth16Unorm => u16::from_le_bytes([data[0], data[1]]) as f32 / 65535.0,
+        _ => f32::from_le_bytes(data[..4].try_into().unwrap()),
+    }
+}
+
+pub(super) fn encode_depth(format: wgt::TextureFormat, depth: f32, data: &mut [u8]) {
+    match format {
+        wgt::TextureFormat::Depth16Unorm => data[..2]
+            .copy_from_slice(&((depth.clamp(0.0, 1.0) * 65535.0).round() as u16).to_le_bytes()),
+        _ => data[..4].copy_from_slice(&depth.to_le_bytes()),
+    }
+}
+
+/// Decodes one vertex attribute.
+pub(super) fn decode_vertex(format: wgt::VertexFormat, data: &[u8]) -> Texel {
+    use wgt::VertexFormat as Vf;
+
+    match format {
+        Vf::Uint8x2 | Vf::Uint8x4 => {
+            let n = format.size() as usize;
+            Texel::Uint(widen(data[..n].iter().map(|&c| c as u32), 0, 1))
+        }
+        Vf::Sint8x2 | Vf::Sint8x4 => {
+            let n = format.size() as usize;
+            Texel::Sint(widen(data[..n].iter().map(|&c| c as i8 as i32), 0, 1))
+        }
+        Vf::Unorm8x2 | Vf::Unorm8x4 => {
+            let n = format.size() as usize;
+            Texel::Float(widen(data[..n].iter().map(|&c| c as f32 / 255.0), 0.0, 1.0))
+        }
+        Vf::Snorm8x2 | Vf::Snorm8x4 => {
+            let n = format.size() as usize;
+            Texel::Float(widen(
+                data[..n]
+                    .iter()
+                    .map(|&c| (c as i8 as f32 / 127.0).max(-1.0)),
+                0.0,
+                1.0,
+            ))
+        }
+        Vf::Uint16x2 | Vf::Uint16x4 => Texel::Uint(widen(
+            chunks(data, format.size() as usize / 2).map(|c| u16::from_le_bytes(c) as u32),
+            0,
+            1,
+        )),
+        Vf::Sint16x2 | Vf::Sint16x4 => Texel::Sint(widen(
+            chunks(data, format.size() as usize / 2).map(|c| i16::from_le_bytes(c) as i32),
+            0,
+            1,
+        )),
+        Vf::Unorm16x2 | Vf::Unorm16x4 => Texel::Float(widen(
+            chunks(data, format.size() as usize / 2)
+                .map(|c| u16::from_le_bytes(c) as f32 / 65535.0),
+            0.0,
+            1.0,
+        )),
+        Vf::Snorm16x2 | Vf::Snorm16x4 => Texel::Float(widen(
+            chunks(data, format.size() as usize / 2)
+                .map(|c| (i16::from_le_bytes(c) as f32 / 32767.0).max(-1.0)),
+            0.0,
+            1.0,
+        )),
+        Vf::Float16x2 | Vf::Float16x4 => Texel::Float(widen(
+            chunks(data, format.size() as usize / 2).map(|c| f16_to_f32(u16::from_le_bytes(c))),
+            0.0,
+            1.0,
+        )),
+        Vf::Float32 | Vf::Float32x2 | Vf::Float32x3 | Vf::Float32x4 => Texel::Float(widen(
+            chunks(data, format.size() as usize / 4).map(f32::from_le_bytes),
+            0.0,
+            1.0,
+        )),
+        Vf::Uint32 | Vf::Uint32x2 | Vf::Uint32x3 | Vf::Uint32x4 => Texel::Uint(widen(
+            chunks(data, format.size() as usize / 4).map(u32::from_le_bytes),
+            0,
+            1,
+        )),
+        Vf::Sint32 | Vf::Sint32x2 | Vf::Sint32x3 | Vf::Sint32x4 => Texel::Sint(widen(
+            chunks(data, format.size() as usize / 4).map(i32::from_le_bytes),
+            0,
+            1,
+        )),
+        Vf::Float64 | Vf::Float64x2 | Vf::Float64x3 | Vf::Float64x4 => Texel::Float(widen(
+            chunks(data, format.size() as usize / 8).map(|c| f64::from_le_bytes(c) as f32),
+            0.0,
+            1.0,
+        )),
+    }
+}
+
+/// Evaluates `left <function> right`.
+pub(super) fn compare(function: wgt::CompareFunction, left: f32, right: f32) -> bool {
+    use wgt::CompareFunction as Cf;
+
+    match function {
+        Cf::Never => false,
+        Cf::Less => left < right,
+        Cf::Equal => left == right,
+        Cf::LessEqual => left <= right,
+        Cf::Greater => left > right,
+        Cf::NotEqual => left != right,
+        Cf::GreaterEqual => left >= right,
+        Cf::Always => true,
+    }
+}
+
+pub(super) fn stencil_op(op: wgt::StencilOperation, value: u8, reference: u8) -> u8 {
+    use wgt::StencilOperation as So;
+
+    match op {
+        So::Keep => value,
+        So::Zero => 0,
+        So::Replace => reference,
+        So::Invert => !value,
+        So::IncrementClamp => value.saturating_add(1),
+        So::DecrementClamp => value.saturating_sub(1),
+        So::IncrementWrap => value.wrapping_add(1),
+        So::DecrementWrap => value.wrapping_sub(1),
+    }
+}
+
+/// Inputs of the blend equation.
+pub(super) struct BlendInputs {
+    pub src: [f32; 4],
+    pub src1: [f32; 4],
+    pub dst: [f32; 4],
+    pub constant: [f32; 4],
+}
+
+fn blend_factor(factor: wgt::BlendFactor, inputs: &BlendInputs, channel: usize) -> f32 {
+    use wgt::BlendFactor as Bf;
+
+    let BlendInputs {
+        src,
+        src1,
+        dst,
+        constant,
+    } = *inputs;
+    match factor {
+        Bf::Zero => 0.0,
+        Bf::One => 1.0,
+        Bf::Src => src[channel],
+        Bf::OneMinusSrc => 1.0 - src[channel],
+        Bf::SrcAlpha => src[3],
+        Bf::OneMinusSrcAlpha => 1.0 - src[3],
+        Bf::Dst => dst[channel],
+        Bf::OneMinusDst => 1.0 - dst[channel],
+        Bf::DstAlpha => dst[3],
+        Bf::OneMinusDstAlpha => 1.0 - dst[3],
+        Bf::SrcAlphaSaturated if channel == 3 => 1.0,
+        Bf::SrcAlphaSaturated => src[3].min(1.0 - dst[3]),
+        Bf::Constant => constant[channel],
+        Bf::OneMinusConstant => 1.0 - constant[channel],
+        Bf::Src1 => src1[channel],
+        Bf::OneMinusSrc1 => 1.0 - src1[channel],
+        Bf::Src1Alpha => src1[3],
+        Bf::OneMinusSrc1Alpha => 1.0 - src1[3],
+    }
+}
+
+fn blend_component(component: &wgt::BlendComponent, inputs: &BlendInputs, channel: usize) -> f32 {
+    use wgt::BlendOperation as Bo;
+
+    let src = inputs.src[channel];
+    let dst = inputs.dst[channel];
+    let src_factor = blend_factor(component.src_factor, inputs, channel);
+    let dst_factor = blend_factor(component.dst_factor, inputs, channel);
+    match component.operation {
+        Bo::Add => src * src_factor + dst * dst_factor,
+        Bo::Subtract => src * src_factor - dst * dst_factor,
+        Bo::ReverseSubtract => dst * dst_factor - src * src_factor,
+        Bo::Min => src.min(dst),
+        Bo::Max => src.max(dst),
+    }
+}
+
+pub(super) fn blend(state: &wgt::BlendState, inputs: &BlendInputs) -> [f32; 4] {
+    [
+        blend_component(&state.color, inputs, 0),
+        blend_component(&state.color, inputs, 1),
+        blend_component(&state.color, inputs, 2),
+        blend_component(&state.alpha, inputs, 3),
+    ]
+}
+
+/// Clamps a color to the range representable by `format`, as required
+/// before and after blending into normalized formats.
+pub(super) fn clamp_to_format(format: wgt::TextureFormat, color: [f32; 4]) -> [f32; 4] {
+    use wgt::TextureFormat as Tf;
+
+    match format {
+        Tf::R8Snorm
+        | Tf::Rg8Snorm
+        | Tf::Rgba8Snorm
+        | Tf::R16Snorm
+        | Tf::Rg16Snorm
+        | Tf::Rgba16Snorm => color.map(|c| c.clamp(-1.0, 1.0)),
+        Tf::R16Float
+        | Tf::Rg16Float
+        | Tf::Rgba16Float
+        | Tf::R32Float
+        | Tf::Rg32Float
+        | Tf::Rgba32Float
+        | Tf::Rg11b10Float => color,
+        _ => color.map(|c| c.clamp(0.0, 1.0)),
+    }
+}
+
+#[test]
+fn half_float_round_trip() {
+    for value in [0.0, 1.0, -2.5, 0.333, 65504.0, 6.1e-5, 1.0e-7] {
+        let bits = f32_to_f16(value);
+        let decoded = f16_to_f32(bits);
+        assert!((decoded - value).abs() <= value.abs() / 1024.0 + 6.0e-8);
+        assert_eq!(f32_to_f16(decoded), bits);
+    }
+    assert_eq!(f32_to_f16(1.0e6), 0x7c00);
+}
+
+#[test]
+fn color_round_trip() {
+    use wgt::TextureFormat as Tf;
+
+    let color = Texel::Float([0.25, 0.5, 0.75, 1.0]);
+    for format in [
+        Tf::Rgba8Unorm,
+        Tf::Rgba8UnormSrgb,
+        Tf::Bgra8Unorm,
+        Tf::Rgba16Float,
+        Tf::Rgba32Float,
+        Tf::Rgb10a2Unorm,
+        Tf::Rg11b10Float,
+        Tf::Rgb9e5Ufloat,
+    ] {
+        let mut data = [0; 16];
+        encode_color(format, color, &mut data);
+        let decoded = decode_color(format, &data).to_float();
+        for (&a, &b) in decoded.iter().zip(color.to_float().iter()) {
+            assert!((a - b).abs() < 0.01, "{format:?}: {decoded:?}");
+        }
+    }
+}
@@ -0,0 +1,362 @@
+use std::sync::Arc;
+
+use arrayvec::ArrayVec;
+
+use super::{conv, shader, Api, Memory};
+
+type DeviceResult<T> = Result<T, crate::DeviceError>;
+
+impl crate::Device<Api> for super::Device {
+    unsafe fn exit(self, _queue: super::Queue) {}
+
+    unsafe fn create_buffer(&self, desc: &crate::BufferDescriptor) -> DeviceResult<super::Buffer> {
+        Ok(super::Buffer {
+            memory: Arc::new(Memory::new(desc.size)?),
+        })
+    }
+    unsafe fn destroy_buffer(&self, _buffer: super::Buffer) {}
+
+    unsafe fn map_buffer(
+        &self,
+        buffer: &super::Buffer,
+        range: crate::MemoryRange,
+    ) -> DeviceResult<crate::BufferMapping> {
+        Ok(crate::BufferMapping {
+            ptr: buffer.memory.ptr_at(range.start),
+            is_coherent: true,
+        })
+    }
+    unsafe fn unmap_buffer(&self, _buffer: &super::Buffer) -> DeviceResult<()> {
+        Ok(())
+    }
+    unsafe fn flush_mapped_ranges<I>(&self, _buffer: &super::Buffer, _ranges: I) {}
+    unsafe fn invalidate_mapped_ranges<I>(&self, _buffer: &super::Buffer, _ranges: I) {}
+
+    unsafe fn create_texture(
+        &self,
+        desc: &crate::TextureDescriptor,
+    ) -> DeviceResult<super::Texture> {
+        let format = desc.format;
+        let mut plane_layouts = ArrayVec::<_, 2>::new();
+        if format.has_depth_aspect() {
+            plane_layouts.push((crate::FormatAspects::DEPTH, conv::depth_texel_size(format)));
+        }
+        if format.has_stencil_aspect() {
+            plane_layouts.push((crate::FormatAspects::STENCIL, 1));
+        }
+        if plane_layouts.is_empty() {
+            let texel_size = format.block_size(None).unwrap_or(4);
+            plane_layouts.push((crate::FormatAspects::COLOR, texel_size));
+        }
+
+        let mut planes = ArrayVec::new();
+        for (aspects, texel_size) in plane_layouts {
+            let mut mip_offsets = Vec::with_capacity(desc.mip_level_count as usize);
+            let mut size = 0u64;
+            for level in 0..desc.mip_level_count {
+                mip_offsets.push(size);
+                let extent = desc.size.mip_level_size(level, desc.dimension);
+                size += extent.width as u64
+                    * extent.height as u64
+                    * extent.depth_or_array_layers as u64
+                    * texel_size as u64;
+            }
+            planes.push((
+                aspects,
+                super::Plane {
+                    memory: Memory::new(size)?,
+                    texel_size,
+                    mip_offsets,
+                },
+            ));
+        }
+
+        Ok(super::Texture {
+            inner: Arc::new(super::TextureInner {
+                format,
+                dimension: desc.dimension,
+                size: desc.size,
+                mip_level_count: desc.mip_level_count,
+                sample_count: desc.sample_count,
+                planes,
+            }),
+        })
+    }
+    unsafe fn destroy_texture(&self, _texture: super::Texture) {}
+
+    unsafe fn create_texture_view(
+        &self,
+        texture: &super::Texture,
+        desc: &crate::TextureViewDescriptor,
+    ) -> DeviceResult<super::TextureView> {
+        let inner = &texture.inner;
+        let array_layer_count = match inner.dimension {
+            wgt::TextureDimension::D3 => 1,
+            _ => inner.size.depth_or_array_layers,
+        };
+        Ok(super::TextureView {
+            texture: Arc::clone(inner),
+            format: desc.format,
+            dimension: desc.dimension,
+            aspects: crate::FormatAspects::new(inner.format, desc.range.aspect),
+            mip_levels: desc.range.mip_range(inner.mip_level_count),
+            array_layers: desc.range.layer_range(array_layer_count),
+        })
+    }
+    unsafe fn destroy_texture_view(&self, _view: super::TextureView) {}
+
+    unsafe fn create_sampler(
+        &self,
+        desc: &crate::SamplerDescriptor,
+    ) -> DeviceResult<super::Sampler> {
+        Ok(super::Sampler {
+            address_modes: desc.address_modes,
+            mag_filter: desc.mag_filter,
+            min_filter: desc.min_filter,
+            mipmap_filter: desc.mipmap_filter,
+            lod_clamp: desc.lod_clamp.clone(),
+            compare: desc.compare,
+            border_color: desc.border_color,
+        })
+    }
+    unsafe fn destroy_sampler(&self, _sampler: super::Sampler) {}
+
+    // Heaps aren't exposed by this backend.
+    unsafe fn create_heap(&self, _desc: &crate::HeapDescriptor) -> Result<(), crate::DeviceError> {
+        Err(crate::DeviceError::ResourceCreationFailed)
+    }
+    unsafe fn destroy_heap(&self, (): ()) {}
+    unsafe fn create_buffer_in_heap(
+        &self,
+        _desc: &crate::BufferDescriptor,
+        _heap: &(),
+        _offset: wgt::BufferAddress,
+    ) -> Result<crate::PlacedResource<super::Buffer>, crate::HeapPlacementError> {
+        Err(crate::DeviceError::ResourceCreationFailed.into())
+    }
+    unsafe fn create_texture_in_heap(
+        &self,
+        _desc: &crate::TextureDescriptor,
+        _heap: &(),
+        _offset: wgt::BufferAddress,
+    ) -> Result<crate::PlacedResource<super::Texture>, crate::HeapPlacementError> {
+        Err(crate::DeviceError::ResourceCreationFailed.into())
+    }
+
+    // External semaphores aren't exposed by this backend.
+    unsafe fn create_external_semaphore(
+        &self,
+        _desc: &crate::ExternalSemaphoreDescriptor,
+    ) -> Result<(), crate::DeviceError> {
+        Err(crate::DeviceError::ResourceCreationFailed)
+    }
+    unsafe fn destroy_external_semaphore(&self, (): ()) {}
+
+    unsafe fn create_command_encoder(
+        &self,
+        _desc: &crate::CommandEncoderDescriptor<Api>,
+    ) -> DeviceResult<super::CommandEncoder> {
+        Ok(super::CommandEncoder {
+            commands: Vec::new(),
+            pass_end_timestamp: None,
+        })
+    }
+    unsafe fn destroy_command_encoder(&self, _encoder: super::CommandEncoder) {}
+
+    unsafe fn create_bind_group_layout(
+        &self,
+        desc: &crate::BindGroupLayoutDescriptor,
+    ) -> DeviceResult<super::BindGroupLayout> {
+        Ok(super::BindGroupLayout {
+            entries: desc.entries.to_vec(),
+        })
+    }
+    unsafe fn destroy_bind_group_layout(&self, _bg_layout: super::BindGroupLayout) {}
+
+    unsafe fn create_pipeline_layout(
+        &self,
+        _desc: &crate::PipelineLayoutDescriptor<Api>,
+    ) -> DeviceResult<super::PipelineLayout> {
+        Ok(super::PipelineLayout)
+    }
+    unsafe fn destroy_pipeline_layout(&self, _pipeline_layout: super::PipelineLayout) {}
+
+    unsafe fn create_bind_group(
+        &self,
+        desc: &crate::BindGroupDescriptor<Api>,
+    ) -> DeviceResult<super::BindGroup> {
+        let mut entries = Vec::with_capacity(desc.entries.len());
+        for entry in desc.entries {
+            let layout = match desc
+                .layout
+                .entries
+                .iter()
+                .find(|layout| layout.binding == entry.binding)
+            {
+                Some(layout) => layout,
+                None => continue,
+            };
+            let index = entry.resource_index as usize;
+            let (has_dynamic_offset, resource) = match layout.ty {
+                wgt::BindingType::Buffer {
+                    has_dynamic_offset, ..
+                } => {
+                    let binding = &desc.buffers[index];
+                    let memory = &binding.buffer.memory;
+                    let size = match binding.size {
+                        Some(size) => size.get(),
+                        None => memory.len().saturating_sub(binding.offset),
+                    };
+                    (
+                        has_dynamic_offset,
+                        super::Resource::Buffer {
+                            memory: Arc::clone(memory),
+                            offset: binding.offset,
+                            size,
+                        },
+                    )
+                }
+                wgt::BindingType::Sampler(_) => (
+                    false,
+                    super::Resource::Sampler(desc.samplers[index].clone()),
+                ),
+                wgt::BindingType::Texture { .. } | wgt::BindingType::StorageTexture { .. } => (
+                    false,
+                    super::Resource::Texture(Arc::new(desc.textures[index].view.clone())),
+                ),
+                wgt::BindingType::AccelerationStructure => continue,
+            };
+            entries.push((entry.binding, has_dynamic_offset, resource));
+        }
+        entries.sort_by_key(|&(binding, _, _)| binding);
+        Ok(super::BindGroup {
+            entries: Arc::new(entries),
+        })
+    }
+    unsafe fn destroy_bind_group(&self, _group: super::BindGroup) {}
+
+    unsafe fn create_shader_module(
+        &self,
+        _desc: &crate::ShaderModuleDescriptor,
+        shader: crate::ShaderInput,
+    ) -> Result<super::ShaderModule, crate::ShaderError> {
+        match shader {
+            crate::ShaderInput::Naga(naga) => Ok(super::ShaderModule {
+                module: Arc::new(naga.module.into_owned()),
+            }),
+            crate::ShaderInput::SpirV(_) => Err(crate::ShaderError::Compilation(String::from(
+                "the CPU backend only accepts naga IR",
+            ))),
+        }
+    }
+    unsafe fn destroy_shader_module(&self, _module: super::ShaderModule) {}
+
+    unsafe fn create_render_pipeline(
+        &self,
+        desc: &crate::RenderPipelineDescriptor<Api>,
+    ) -> Result<super::RenderPipeline, crate::PipelineError> {
+        let vertex_stage = super::Stage::new(&desc.vertex_stage, naga::ShaderStage::Vertex)?;
+        let fragment_stage = match desc.fragment_stage {
+            Some(ref stage) => Some(super::Stage::new(stage, naga::ShaderStage::Fragment)?),
+            None => None,
+        };
+        let vertex_buffers = desc
+            .vertex_buffers
+            .iter()
+            .map(|layout| super::VertexBufferLayout {
+                array_stride: layout.array_stride,
+                step_mode: layout.step_mode,
+                attributes: layout.attributes.to_vec(),
+            })
+            .collect();
+        Ok(super::RenderPipeline {
+            inner: Arc::new(super::RenderPipelineInner {
+                vertex_stage,
+                fragment_stage,
+                vertex_buffers,
+                primitive: desc.primitive,
+                depth_stencil: desc.depth_stencil.clone(),
+                color_targets: desc.color_targets.to_vec(),
+            }),
+        })
+    }
+    unsafe fn destroy_render_pipeline(&self, _pipeline: super::RenderPipeline) {}
+
+    unsafe fn create_compute_pipeline(
+        &self,
+        desc: &crate::ComputePipelineDescriptor<Api>,
+    ) -> Result<super::ComputePipeline, crate::PipelineError> {
+        let stage = super::Stage::new(&desc.stage, naga::ShaderStage::Compute)?;
+        let uses_barriers = shader::uses_barriers(&stage.module);
+        Ok(super::ComputePipeline {
+            stage: Arc::new(stage),
+            uses_barriers,
+        })
+    }
+    unsafe fn destroy_compute_pipeline(&self, _pipeline: super::ComputePipeline) {}
+
+    unsafe fn create_pipeline_cache(
+        &self,
+        _desc: &crate::PipelineCacheDescriptor<'_>,
+    ) -> Result<(), crate::PipelineCacheError> {
+        Ok(())
+    }
+    unsafe fn destroy_pipeline_cache(&self, (): ()) {}
+
+    unsafe fn create_query_set(
+        &self,
+        desc: &wgt::QuerySetDescriptor<crate::Label>,
+    ) -> DeviceResult<super::QuerySet> {
+        Ok(super::QuerySet {
+            memory: Arc::new(Memory::new(desc.count as u64 * 8)?),
+        })
+    }
+    unsafe fn destroy_query_set(&self, _set: super::QuerySet) {}
+
+    unsafe fn create_fence(&self) -> DeviceResult<super::Fence> {
+        Ok(super::Fence { value: 0 })
+    }
+    unsafe fn destroy_fence(&self, _fence: super::Fence) {}
+    unsafe fn get_fence_value(&self, fence: &super::Fence) -> DeviceResult<crate::FenceValue> {
+        Ok(fence.value)
+    }
+    unsafe fn wait(
+        &self,
+        fence: &super::Fence,
+        value: crate::FenceValue,
+        _timeout_ms: u32,
+    ) -> DeviceResult<bool> {
+        // Submissions complete before `submit` returns, so there is nothing
+        // to wait for.
+        Ok(fence.value >= value)
+    }
+
+    unsafe fn start_capture(&self) -> bool {
+        false
+    }
+    unsafe fn stop_capture(&self) {}
+
+    unsafe fn create_acceleration_structure(
+        &self,
+        _desc: &crate::AccelerationStructureDescriptor,
+    ) -> DeviceResult<super::AccelerationStructure> {
+        Ok(super::AccelerationStructure)
+    }
+    unsafe fn get_acceleration_structure_build_sizes<'a>(
+        &self,
+        _desc: &crate::GetAccelerationStructureBuildSizesDescriptor<'a, Api>,
+    ) -> crate::AccelerationStructureBuildSizes {
+        Default::default()
+    }
+    unsafe fn get_acceleration_structure_device_address(
+        &self,
+        _acceleration_structure: &super::AccelerationStructure,
+    ) -> wgt::BufferAddress {
+        0
+    }
+    unsafe fn destroy_acceleration_structure(
+        &self,
+        _acceleration_structure: super::AccelerationStructure,
+    ) {
+    }
+}
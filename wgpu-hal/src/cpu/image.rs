@@ -0,0 +1,380 @@
+//! Texel access and filtering for shader image operations.
+
+use wgt::TextureViewDimension as Tvd;
+
+use super::{
+    conv::{self, Texel},
+    shader::Vector,
+    Plane, Sampler, TextureView,
+};
+
+/// Level of detail of a sample.
+pub(super) enum Level {
+    Lod(f32),
+    /// Derivatives of the coordinate along the screen axes.
+    Gradient(Vec<f32>, Vec<f32>),
+}
+
+pub(super) struct SampleRequest<'a> {
+    pub coordinate: &'a [f32],
+    pub array_index: Option<i32>,
+    pub offset: [i32; 3],
+    pub level: Level,
+    /// Component to gather, if this is a gather operation.
+    pub gather: Option<usize>,
+    pub depth_ref: Option<f32>,
+}
+
+/// Splits an integer image coordinate into texel coordinates and an array
+/// layer.
+pub(super) fn split_coordinate(coordinate: &Vector, array_index: Option<i32>) -> ([i32; 3], i32) {
+    let mut texel = [0; 3];
+    for (slot, c) in texel.iter_mut().zip(coordinate.iter()) {
+        *slot = c.as_i32();
+    }
+    (texel, array_index.unwrap_or(0))
+}
+
+/// Maps a cube direction to a face index and 2D coordinates on that face.
+fn cube_face(direction: &[f32]) -> (u32, [f32; 2]) {
+    let (x, y, z) = (direction[0], direction[1], direction[2]);
+    let (ax, ay, az) = (x.abs(), y.abs(), z.abs());
+    let (face, sc, tc, ma) = if ax >= ay && ax >= az {
+        if x >= 0.0 {
+            (0, -z, -y, ax)
+        } else {
+            (1, z, -y, ax)
+        }
+    } else if ay >= az {
+        if y >= 0.0 {
+            (2, x, z, ay)
+        } else {
+            (3, x, -z, ay)
+        }
+    } else if z >= 0.0 {
+        (4, x, -y, az)
+    } else {
+        (5, -x, -y, az)
+    };
+    let ma = if ma == 0.0 { 1.0 } else { ma };
+    (face, [(sc / ma + 1.0) / 2.0, (tc / ma + 1.0) / 2.0])
+}
+
+/// Applies an address mode to a texel index, returning `None` for the
+/// border.
+fn address(mode: wgt::AddressMode, index: i32, size: u32) -> Option<u32> {
+    let size = size as i32;
+    let index = match mode {
+        wgt::AddressMode::ClampToEdge => index.clamp(0, size - 1),
+        wgt::AddressMode::Repeat => index.rem_euclid(size),
+        wgt::AddressMode::MirrorRepeat => {
+            let period = index.rem_euclid(2 * size);
+            if period < size {
+                period
+            } else {
+                2 * size - 1 - period
+            }
+        }
+        wgt::AddressMode::ClampToBorder => {
+            if index < 0 || index >= size {
+                return None;
+            }
+            index
+        }
+    };
+    Some(index as u32)
+}
+
+fn border_color(sampler: &Sampler) -> [f32; 4] {
+    match sampler.border_color {
+        Some(wgt::SamplerBorderColor::OpaqueBlack) => [0.0, 0.0, 0.0, 1.0],
+        Some(wgt::SamplerBorderColor::OpaqueWhite) => [1.0; 4],
+        Some(wgt::SamplerBorderColor::TransparentBlack | wgt::SamplerBorderColor::Zero) | None => {
+            [0.0; 4]
+        }
+    }
+}
+
+impl TextureView {
+    fn dimensions(&self) -> usize {
+        match self.dimension {
+            Tvd::D1 => 1,
+            Tvd::D2 | Tvd::D2Array | Tvd::Cube | Tvd::CubeArray => 2,
+            Tvd::D3 => 3,
+        }
+    }
+
+    fn is_arrayed(&self) -> bool {
+        matches!(self.dimension, Tvd::D2Array | Tvd::CubeArray)
+    }
+
+    /// Size of mip level `level` of the view, with the layer count of the
+    /// view for non-3D views.
+    pub(super) fn level_size(&self, level: u32) -> wgt::Extent3d {
+        let mut size = self.texture.mip_size(self.mip_levels.start + level);
+        if self.dimension != Tvd::D3 {
+            size.depth_or_array_layers = self.layer_count();
+        }
+        size
+    }
+
+    pub(super) fn layer_count(&self) -> u32 {
+        match self.dimension {
+            Tvd::Cube | Tvd::CubeArray => self.array_layers.len() as u32 / 6,
+            _ => self.array_layers.len() as u32,
+        }
+    }
+
+    fn plane(&self) -> &Plane {
+        let aspect = if self.aspects.contains(crate::FormatAspects::DEPTH) {
+            crate::FormatAspects::DEPTH
+        } else if self.aspects.contains(crate::FormatAspects::STENCIL) {
+            crate::FormatAspects::STENCIL
+        } else {
+            crate::FormatAspects::COLOR
+        };
+        self.texture.plane(aspect)
+    }
+
+    /// Reads a texel of the texture. `level` and `z` are absolute, and must
+    /// be in bounds.
+    fn read(&self, level: u32, x: u32, y: u32, z: u32) -> Texel {
+        let plane = self.plane();
+        let offset = self.texture.texel_offset(plane, level, x, y, z);
+        let mut data = [0u8; 16];
+        let data = &mut data[..plane.texel_size as usize];
+        plane.memory.read(offset, data);
+        if self.aspects.contains(crate::FormatAspects::DEPTH) {
+            let depth = conv::decode_depth(self.texture.format, data);
+            Texel::Float([depth, 0.0, 0.0, 1.0])
+        } else if self.aspects.contains(crate::FormatAspects::STENCIL) {
+            Texel::Uint([data[0] as u32, 0, 0, 1])
+        } else {
+            conv::decode_color(self.format, data)
+        }
+    }
+
+    fn write(&self, level: u32, x: u32, y: u32, z: u32, texel: Texel) {
+        let plane = self.plane();
+        let offset = self.texture.texel_offset(plane, level, x, y, z);
+        let mut data = [0u8; 16];
+        let data = &mut data[..plane.texel_size as usize];
+        conv::encode_color(self.format, texel, data);
+        plane.memory.write(offset, data);
+    }
+
+    /// Resolves view relative integer coordinates to absolute ones,
+    /// returning `None` if they are out of bounds.
+    fn locate(&self, coordinate: [i32; 3], layer: i32, level: i32) -> Option<(u32, [u32; 3])> {
+        let level = u32::try_from(level).ok()?;
+        if level >= self.mip_levels.len() as u32 {
+            return None;
+        }
+        let size = self.level_size(level);
+        let extent = [size.width, size.height, size.depth_or_array_layers];
+        let mut texel = [0; 3];
+        for d in 0..self.dimensions() {
+            texel[d] = u32::try_from(coordinate[d])
+                .ok()
+                .filter(|&c| c < extent[d])?;
+        }
+        if self.dimension != Tvd::D3 {
+            let layer = u32::try_from(layer)
+                .ok()
+                .filter(|&layer| layer < self.layer_count())?;
+            texel[2] = self.array_layers.start + layer;
+        }
+        Some((self.mip_levels.start + level, texel))
+    }
+
+    /// `textureLoad`, returning `None` out of bounds.
+    pub(super) fn load(&self, coordinate: [i32; 3], layer: i32, level: i32) -> Option<Texel> {
+        let (level, [x, y, z]) = self.locate(coordinate, layer, level)?;
+        Some(self.read(level, x, y, z))
+    }
+
+    /// `textureStore`, dropping out of bounds writes.
+    pub(super) fn store(&self, coordinate: [i32; 3], layer: i32, texel: Texel) {
+        if let Some((level, [x, y, z])) = self.locate(coordinate, layer, 0) {
+            self.write(level, x, y, z, texel);
+        }
+    }
+
+    /// Computes the level of detail from the sampled coordinate derivatives.
+    fn gradient_lod(&self, dx: &[f32], dy: &[f32]) -> f32 {
+        let size = self.level_size(0);
+        let extent = [size.width, size.height, size.depth_or_array_layers];
+        let length = |d: &[f32]| {
+            d.iter()
+                .zip(extent.iter())
+                .take(self.dimensions())
+                .map(|(&d, &e)| (d * e as f32).powi(2))
+                .sum::<f32>()
+                .sqrt()
+        };
+        length(dx).max(length(dy)).log2()
+    }
+
+    /// Samples the view, returning the texel, the depth comparison result,
+    /// or the gathered component.
+    pub(super) fn sample(&self, sampler: &Sampler, request: &SampleRequest) -> [f32; 4] {
+        let (coordinate, face) = if matches!(self.dimension, Tvd::Cube | Tvd::CubeArray) {
+            let (face, uv) = cube_face(request.coordinate);
+            ([uv[0], uv[1], 0.0], face)
+        } else {
+            let mut coordinate = [0.0; 3];
+            for (slot, &c) in coordinate.iter_mut().zip(request.coordinate) {
+                *slot = c;
+            }
+            (coordinate, 0)
+        };
+        let layer = if self.is_arrayed() {
+            let count = self.layer_count() as i32;
+            request.array_index.unwrap_or(0).clamp(0, count - 1) as u32
+        } else {
+            0
+        };
+        let layer = match self.dimension {
+            Tvd::Cube | Tvd::CubeArray => layer * 6 + face,
+            _ => layer,
+        };
+
+        let lod = match request.level {
+            Level::Lod(lod) => lod,
+            Level::Gradient(ref dx, ref dy) => self.gradient_lod(dx, dy),
+        };
+        let lod = lod.clamp(sampler.lod_clamp.start, sampler.lod_clamp.end);
+        let filter = if lod <= 0.0 {
+            sampler.mag_filter
+        } else {
+            sampler.min_filter
+        };
+        if request.gather.is_some() {
+            // Gathers always use the base level of the view.
+            return self.sample_level(sampler, request, 0, coordinate, layer, filter);
+        }
+
+        let max_level = self.mip_levels.len() as f32 - 1.0;
+        let lod = lod.clamp(0.0, max_level);
+        match sampler.mipmap_filter {
+            wgt::FilterMode::Nearest => {
+                let level = lod.round() as u32;
+                self.sample_level(sampler, request, level, coordinate, layer, filter)
+            }
+            wgt::FilterMode::Linear => {
+                let low = lod.floor();
+                let t = lod - low;
+                let a = self.sample_level(sampler, request, low as u32, coordinate, layer, filter);
+                if t == 0.0 {
+                    return a;
+                }
+                let b =
+                    self.sample_level(sampler, request, low as u32 + 1, coordinate, layer, filter);
+                [0, 1, 2, 3].map(|i| a[i] * (1.0 - t) + b[i] * t)
+            }
+        }
+    }
+
+    fn sample_level(
+        &self,
+        sampler: &Sampler,
+        request: &SampleRequest,
+        level: u32,
+        coordinate: [f32; 3],
+        layer: u32,
+        filter: wgt::FilterMode,
+    ) -> [f32; 4] {
+        let size = self.level_size(level);
+        let extent = [size.width, size.height, size.depth_or_array_layers];
+        let dimensions = self.dimensions();
+        let linear = filter == wgt::FilterMode::Linear || request.gather.is_some();
+
+        // Integer base texel and interpolation weight along every axis.
+        let mut base = [0i32; 3];
+        let mut weight = [0f32; 3];
+        for d in 0..dimensions {
+            let texel = coordinate[d] * extent[d] as f32;
+            if linear {
+                let texel = texel - 0.5;
+                base[d] = texel.floor() as i32 + request.offset[d];
+                weight[d] = texel - texel.floor();
+            } else {
+                base[d] = texel.floor() as i32 + request.offset[d];
+            }
+        }
+
+        let fetch = |corner: [i32; 3]| -> [f32; 4] {
+            let mut texel = [0, 0, 0];
+            for d in 0..dimensions {
+                match address(sampler.address_modes[d], base[d] + corner[d], extent[d]) {
+                    Some(index) => texel[d] = index,
+                    None => return border_color(sampler),
+                }
+            }
+            let z = if dimensions == 3 {
+                texel[2]
+            } else {
+                self.array_layers.start + layer
+            };
+            let value = self
+                .read(self.mip_levels.start + level, texel[0], texel[1], z)
+                .to_float();
+            match (request.depth_ref, sampler.compare) {
+                (Some(reference), Some(function)) => {
+                    let pass = conv::compare(function, reference, value[0]);
+                    let pass = if pass { 1.0 } else { 0.0 };
+                    [pass, pass, pass, pass]
+                }
+                _ => value,
+            }
+        };
+
+        if let Some(component) = request.gather {
+            let texels = [[0, 1, 0], [1, 1, 0], [1, 0, 0], [0, 0, 0]].map(fetch);
+            return texels.map(|texel| texel[component.min(3)]);
+        }
+        if !linear {
+            return fetch([0, 0, 0]);
+        }
+
+        let mut result = [0.0; 4];
+        for corner in 0..1 << dimensions {
+            let offset = [corner & 1, (corner >> 1) & 1, (corner >> 2) & 1];
+            let mut factor = 1.0;
+            for d in 0..dimensions {
+                factor *= if offset[d] == 1 {
+                    weight[d]
+                } else {
+                    1.0 - weight[d]
+                };
+            }
+            if factor == 0.0 {
+                continue;
+            }
+            let texel = fetch(offset);
+            for (sum, value) in result.iter_mut().zip(texel) {
+                *sum += value * factor;
+            }
+        }
+        result
+    }
+}
+
+#[test]
+fn address_modes() {
+    use wgt::AddressMode as Am;
+
+    assert_eq!(address(Am::ClampToEdge, -3, 4), Some(0));
+    assert_eq!(address(Am::ClampToEdge, 9, 4), Some(3));
+    assert_eq!(address(Am::Repeat, -1, 4), Some(3));
+    assert_eq!(address(Am::MirrorRepeat, 4, 4), Some(3));
+    assert_eq!(address(Am::MirrorRepeat, -1, 4), Some(0));
+    assert_eq!(address(Am::ClampToBorder, 4, 4), None);
+}
+
+#[test]
+fn cube_faces() {
+    assert_eq!(cube_face(&[1.0, 0.0, 0.0]), (0, [0.5, 0.5]));
+    assert_eq!(cube_face(&[0.0, -1.0, 0.0]), (3, [0.5, 0.5]));
+    assert_eq!(cube_face(&[0.0, 0.0, -2.0]).0, 5);
+}
@@ -0,0 +1,680 @@
+//! Operators and built-in functions of the shader interpreter.
+//!
+//! Floating point math is done in `f64` and rounded back to the width of the
+//! operands.
+
+use naga::{BinaryOperator as Bo, MathFunction as Mf};
+
+use super::{
+    conv,
+    shader::{Scalar, Value, Vector},
+};
+
+/// Applies `f` to every component of a scalar, vector or matrix.
+pub(super) fn map(value: Value, f: impl Fn(Scalar) -> Scalar) -> Value {
+    match value {
+        Value::Scalar(scalar) => Value::Scalar(f(scalar)),
+        Value::Vector(vector) => Value::Vector(vector.into_iter().map(f).collect()),
+        Value::Matrix(columns) => Value::Matrix(
+            columns
+                .into_iter()
+                .map(|column| column.into_iter().map(&f).collect())
+                .collect(),
+        ),
+        other => other,
+    }
+}
+
+/// Applies `f` component-wise, splatting scalars against vectors.
+fn zip(left: Value, right: Value, f: impl Fn(Scalar, Scalar) -> Scalar) -> Value {
+    match (left, right) {
+        (Value::Scalar(a), Value::Scalar(b)) => Value::Scalar(f(a, b)),
+        (Value::Vector(a), Value::Scalar(b)) => {
+            Value::Vector(a.into_iter().map(|a| f(a, b)).collect())
+        }
+        (Value::Scalar(a), Value::Vector(b)) => {
+            Value::Vector(b.into_iter().map(|b| f(a, b)).collect())
+        }
+        (Value::Vector(a), Value::Vector(b)) => {
+            Value::Vector(a.into_iter().zip(b).map(|(a, b)| f(a, b)).collect())
+        }
+        (Value::Matrix(a), Value::Matrix(b)) => Value::Matrix(
+            a.into_iter()
+                .zip(b)
+                .map(|(a, b)| a.into_iter().zip(b).map(|(a, b)| f(a, b)).collect())
+                .collect(),
+        ),
+        (left, _) => left,
+    }
+}
+
+fn zip3(a: Value, b: Value, c: Value, f: impl Fn(Scalar, Scalar, Scalar) -> Scalar) -> Value {
+    let len = a
+        .components()
+        .len()
+        .max(b.components().len())
+        .max(c.components().len());
+    let get = |value: &Value, i: usize| match *value {
+        Value::Vector(ref vector) => vector[i],
+        ref other => other.scalar(),
+    };
+    let results: Vector = (0..len)
+        .map(|i| f(get(&a, i), get(&b, i), get(&c, i)))
+        .collect();
+    match a {
+        Value::Vector(_) | Value::Scalar(_) if len > 1 => Value::Vector(results),
+        _ => Value::Scalar(results[0]),
+    }
+}
+
+fn float1(scalar: Scalar, f: impl Fn(f64) -> f64) -> Scalar {
+    scalar.with_f64(f(scalar.as_f64()))
+}
+
+fn float_map(value: Value, f: impl Fn(f64) -> f64) -> Value {
+    map(value, |scalar| float1(scalar, &f))
+}
+
+fn float_zip(left: Value, right: Value, f: impl Fn(f64, f64) -> f64) -> Value {
+    zip(left, right, |a, b| a.with_f64(f(a.as_f64(), b.as_f64())))
+}
+
+pub(super) fn binary_scalar(op: Bo, a: Scalar, b: Scalar) -> Scalar {
+    use Scalar as S;
+
+    match (a, b) {
+        (S::I32(a), S::I32(b)) => match op {
+            Bo::Add => S::I32(a.wrapping_add(b)),
+            Bo::Subtract => S::I32(a.wrapping_sub(b)),
+            Bo::Multiply => S::I32(a.wrapping_mul(b)),
+            Bo::Divide => S::I32(a.checked_div(b).unwrap_or(a)),
+            Bo::Modulo => S::I32(a.checked_rem(b).unwrap_or(0)),
+            Bo::Equal => S::Bool(a == b),
+            Bo::NotEqual => S::Bool(a != b),
+            Bo::Less => S::Bool(a < b),
+            Bo::LessEqual => S::Bool(a <= b),
+            Bo::Greater => S::Bool(a > b),
+            Bo::GreaterEqual => S::Bool(a >= b),
+            Bo::And => S::I32(a & b),
+            Bo::ExclusiveOr => S::I32(a ^ b),
+            Bo::InclusiveOr => S::I32(a | b),
+            Bo::ShiftLeft => S::I32(a.wrapping_shl(b as u32)),
+            Bo::ShiftRight => S::I32(a.wrapping_shr(b as u32)),
+            Bo::LogicalAnd | Bo::LogicalOr => S::I32(a),
+        },
+        (S::I32(a), S::U32(b)) => match op {
+            Bo::ShiftLeft => S::I32(a.wrapping_shl(b)),
+            _ => S::I32(a.wrapping_shr(b)),
+        },
+        (S::U32(a), S::U32(b)) => match op {
+            Bo::Add => S::U32(a.wrapping_add(b)),
+            Bo::Subtract => S::U32(a.wrapping_sub(b)),
+            Bo::Multiply => S::U32(a.wrapping_mul(b)),
+            Bo::Divide => S::U32(a.checked_div(b).unwrap_or(a)),
+            Bo::Modulo => S::U32(a.checked_rem(b).unwrap_or(0)),
+            Bo::Equal => S::Bool(a == b),
+            Bo::NotEqual => S::Bool(a != b),
+            Bo::Less => S::Bool(a < b),
+            Bo::LessEqual => S::Bool(a <= b),
+            Bo::Greater => S::Bool(a > b),
+            Bo::GreaterEqual => S::Bool(a >= b),
+            Bo::And => S::U32(a & b),
+            Bo::ExclusiveOr => S::U32(a ^ b),
+            Bo::InclusiveOr => S::U32(a | b),
+            Bo::ShiftLeft => S::U32(a.wrapping_shl(b)),
+            Bo::ShiftRight => S::U32(a.wrapping_shr(b)),
+            Bo::LogicalAnd | Bo::LogicalOr => S::U32(a),
+        },
+        (S::Bool(a), S::Bool(b)) => S::Bool(match op {
+            Bo::Equal => a == b,
+            Bo::NotEqual | Bo::ExclusiveOr => a != b,
+            Bo::And | Bo::LogicalAnd => a && b,
+            Bo::InclusiveOr | Bo::LogicalOr => a || b,
+            _ => a,
+        }),
+        (a, b) => {
+            let (x, y) = (a.as_f64(), b.as_f64());
+            match op {
+                Bo::Add => a.with_f64(x + y),
+                Bo::Subtract => a.with_f64(x - y),
+                Bo::Multiply => a.with_f64(x * y),
+                Bo::Divide => a.with_f64(x / y),
+                Bo::Modulo => a.with_f64(x % y),
+                Bo::Equal => S::Bool(x == y),
+                Bo::NotEqual => S::Bool(x != y),
+                Bo::Less => S::Bool(x < y),
+                Bo::LessEqual => S::Bool(x <= y),
+                Bo::Greater => S::Bool(x > y),
+                Bo::GreaterEqual => S::Bool(x >= y),
+                _ => a,
+            }
+        }
+    }
+}
+
+fn dot(a: &Vector, b: &Vector) -> Scalar {
+    a.iter()
+        .zip(b.iter())
+        .map(|(&a, &b)| binary_scalar(Bo::Multiply, a, b))
+        .reduce(|sum, x| binary_scalar(Bo::Add, sum, x))
+        .unwrap_or(Scalar::F32(0.0))
+}
+
+/// Multiplies a matrix by a column vector.
+fn matrix_vector(columns: &[Vector], vector: &Vector) -> Vector {
+    let rows = columns.first().map_or(0, |column| column.len());
+    (0..rows)
+        .map(|row| {
+            let row_vector: Vector = columns.iter().map(|column| column[row]).collect();
+            dot(&row_vector, vector)
+        })
+        .collect()
+}
+
+pub(super) fn binary(op: Bo, left: Value, right: Value) -> Value {
+    match (op, left, right) {
+        (Bo::Multiply, Value::Matrix(columns), Value::Vector(vector)) => {
+            Value::Vector(matrix_vector(&columns, &vector))
+        }
+        (Bo::Multiply, Value::Vector(vector), Value::Matrix(columns)) => {
+            Value::Vector(columns.iter().map(|column| dot(&vector, column)).collect())
+        }
+        (Bo::Multiply, Value::Matrix(a), Value::Matrix(b)) => {
+            Value::Matrix(b.iter().map(|column| matrix_vector(&a, column)).collect())
+        }
+        (Bo::Multiply, Value::Matrix(columns), Value::Scalar(s))
+        | (Bo::Multiply, Value::Scalar(s), Value::Matrix(columns)) => {
+            map(Value::Matrix(columns), |c| {
+                binary_scalar(Bo::Multiply, c, s)
+            })
+        }
+        (op, left, right) => zip(left, right, |a, b| binary_scalar(op, a, b)),
+    }
+}
+
+pub(super) fn unary(op: naga::UnaryOperator, value: Value) -> Value {
+    map(value, |scalar| match (op, scalar) {
+        (naga::UnaryOperator::Negate, Scalar::I32(v)) => Scalar::I32(v.wrapping_neg()),
+        (naga::UnaryOperator::Negate, s) => s.with_f64(-s.as_f64()),
+        (_, Scalar::Bool(v)) => Scalar::Bool(!v),
+        (_, Scalar::I32(v)) => Scalar::I32(!v),
+        (_, Scalar::U32(v)) => Scalar::U32(!v),
+        (_, s) => s,
+    })
+}
+
+pub(super) fn min(a: Scalar, b: Scalar) -> Scalar {
+    match (a, b) {
+        (Scalar::I32(a), Scalar::I32(b)) => Scalar::I32(a.min(b)),
+        (Scalar::U32(a), Scalar::U32(b)) => Scalar::U32(a.min(b)),
+        (a, b) => a.with_f64(a.as_f64().min(b.as_f64())),
+    }
+}
+
+pub(super) fn max(a: Scalar, b: Scalar) -> Scalar {
+    match (a, b) {
+        (Scalar::I32(a), Scalar::I32(b)) => Scalar::I32(a.max(b)),
+        (Scalar::U32(a), Scalar::U32(b)) => Scalar::U32(a.max(b)),
+        (a, b) => a.with_f64(a.as_f64().max(b.as_f64())),
+    }
+}
+
+pub(super) fn relational(fun: naga::RelationalFunction, value: Value) -> Value {
+    match fun {
+        naga::RelationalFunction::All => {
+            Value::Scalar(Scalar::Bool(value.components().iter().all(|c| c.as_bool())))
+        }
+        naga::RelationalFunction::Any => {
+            Value::Scalar(Scalar::Bool(value.components().iter().any(|c| c.as_bool())))
+        }
+        naga::RelationalFunction::IsNan => map(value, |c| Scalar::Bool(c.as_f64().is_nan())),
+        naga::RelationalFunction::IsInf => map(value, |c| Scalar::Bool(c.as_f64().is_infinite())),
+    }
+}
+
+/// Rounds half-way cases to the nearest even integer, like WGSL's `round`.
+fn round_even(x: f64) -> f64 {
+    let rounded = x.round();
+    if (x - x.trunc()).abs() == 0.5 {
+        2.0 * (x / 2.0).round()
+    } else {
+        rounded
+    }
+}
+
+fn length(value: &Value) -> f64 {
+    value
+        .components()
+        .iter()
+        .map(|c| c.as_f64() * c.as_f64())
+        .sum::<f64>()
+        .sqrt()
+}
+
+fn columns_to_rows(columns: &[Vector]) -> Vec<Vec<f64>> {
+    let rows = columns.first().map_or(0, |column| column.len());
+    (0..rows)
+        .map(|row| columns.iter().map(|column| column[row].as_f64()).collect())
+        .collect()
+}
+
+/// Determinant by Gaussian elimination.
+fn determinant(mut m: Vec<Vec<f64>>) -> f64 {
+    let n = m.len();
+    let mut det = 1.0;
+    for i in 0..n {
+        let pivot = (i..n)
+            .max_by(|&a, &b| m[a][i].abs().total_cmp(&m[b][i].abs()))
+            .unwrap();
+        if m[pivot][i] == 0.0 {
+            return 0.0;
+        }
+        if pivot != i {
+            m.swap(pivot, i);
+            det = -det;
+        }
+        det *= m[i][i];
+        for j in i + 1..n {
+            let factor = m[j][i] / m[i][i];
+            let (upper, lower) = m.split_at_mut(j);
+            for (target, &source) in lower[0][i..].iter_mut().zip(&upper[i][i..]) {
+                *target -= factor * source;
+            }
+        }
+    }
+    det
+}
+
+/// Inverse by Gauss-Jordan elimination, as rows.
+fn inverse(mut m: Vec<Vec<f64>>) -> Vec<Vec<f64>> {
+    let n = m.len();
+    let mut inv: Vec<Vec<f64>> = (0..n)
+        .map(|i| (0..n).map(|j| if i == j { 1.0 } else { 0.0 }).collect())
+        .collect();
+    for i in 0..n {
+        let pivot = (i..n)
+            .max_by(|&a, &b| m[a][i].abs().total_cmp(&m[b][i].abs()))
+            .unwrap();
+        m.swap(pivot, i);
+        inv.swap(pivot, i);
+        let scale = m[i][i];
+        for k in 0..n {
+            m[i][k] /= scale;
+            inv[i][k] /= scale;
+        }
+        for j in 0..n {
+            if j != i {
+                let factor = m[j][i];
+                for k in 0..n {
+                    m[j][k] -= factor * m[i][k];
+                    inv[j][k] -= factor * inv[i][k];
+                }
+            }
+        }
+    }
+    inv
+}
+
+fn extract_bits(e: Scalar, offset: u32, count: u32) -> Scalar {
+    let offset = offset.min(32);
+    let count = count.min(32 - offset);
+    if count == 0 {
+        return match e {
+            Scalar::I32(_) => Scalar::I32(0),
+            _ => Scalar::U32(0),
+        };
+    }
+    match e {
+        Scalar::I32(v) => Scalar::I32((v << (32 - offset - count)) >> (32 - count)),
+        other => {
+            let v = other.as_index();
+            Scalar::U32((v >> offset) & (u32::MAX >> (32 - count)))
+        }
+    }
+}
+
+fn insert_bits(e: Scalar, new_bits: Scalar, offset: u32, count: u32) -> Scalar {
+    let offset = offset.min(32);
+    let count = count.min(32 - offset);
+    let mask = if count == 0 {
+        0
+    } else {
+        (u32::MAX >> (32 - count)) << offset
+    };
+    let bits = |s: Scalar| match s {
+        Scalar::I32(v) => v as u32,
+        other => other.as_index(),
+    };
+    let result = (bits(e) & !mask) | (bits(new_bits).wrapping_shl(offset) & mask);
+    match e {
+        Scalar::I32(_) => Scalar::I32(result as i32),
+        _ => Scalar::U32(result),
+    }
+}
+
+fn integer_bits(scalar: Scalar, f: impl Fn(u32) -> u32) -> Scalar {
+    match scalar {
+        Scalar::I32(v) => Scalar::I32(f(v as u32) as i32),
+        other => Scalar::U32(f(other.as_index())),
+    }
+}
+
+fn pack(components: &Vector, bits: u32, f: impl Fn(f64) -> u32) -> Value {
+    let mask = u32::MAX >> (32 - bits);
+    let packed = components.iter().enumerate().fold(0, |packed, (i, c)| {
+        packed | ((f(c.as_f64()) & mask) << (i as u32 * bits))
+    });
+    Value::Scalar(Scalar::U32(packed))
+}
+
+fn unpack(value: u32, count: u32, f: impl Fn(u32) -> f64) -> Value {
+    let bits = 32 / count;
+    let mask = u32::MAX >> (32 - bits);
+    Value::Vector(
+        (0..count)
+            .map(|i| Scalar::F32(f((value >> (i * bits)) & mask) as f32))
+            .collect(),
+    )
+}
+
+pub(super) fn math(
+    fun: Mf,
+    arg: Value,
+    arg1: Option<Value>,
+    arg2: Option<Value>,
+    arg3: Option<Value>,
+) -> Value {
+    let arg1 = || arg1.clone().unwrap_or(Value::ZERO);
+    let arg2 = || arg2.clone().unwrap_or(Value::ZERO);
+    match fun {
+        Mf::Abs => map(arg, |c| match c {
+            Scalar::I32(v) => Scalar::I32(v.wrapping_abs()),
+            Scalar::U32(v) => Scalar::U32(v),
+            other => float1(other, f64::abs),
+        }),
+        Mf::Min => zip(arg, arg1(), min),
+        Mf::Max => zip(arg, arg1(), max),
+        Mf::Clamp => zip3(arg, arg1(), arg2(), |e, low, high| min(max(e, low), high)),
+        Mf::Saturate => float_map(arg, |x| x.clamp(0.0, 1.0)),
+        Mf::Cos => float_map(arg, f64::cos),
+        Mf::Cosh => float_map(arg, f64::cosh),
+        Mf::Sin => float_map(arg, f64::sin),
+        Mf::Sinh => float_map(arg, f64::sinh),
+        Mf::Tan => float_map(arg, f64::tan),
+        Mf::Tanh => float_map(arg, f64::tanh),
+        Mf::Acos => float_map(arg, f64::acos),
+        Mf::Asin => float_map(arg, f64::asin),
+        Mf::Atan => float_map(arg, f64::atan),
+        Mf::Atan2 => float_zip(arg, arg1(), f64::atan2),
+        Mf::Asinh => float_map(arg, f64::asinh),
+        Mf::Acosh => float_map(arg, f64::acosh),
+        Mf::Atanh => float_map(arg, f64::atanh),
+        Mf::Radians => float_map(arg, f64::to_radians),
+        Mf::Degrees => float_map(arg, f64::to_degrees),
+        Mf::Ceil => float_map(arg, f64::ceil),
+        Mf::Floor => float_map(arg, f64::floor),
+        Mf::Round => float_map(arg, round_even),
+        Mf::Fract => float_map(arg, |x| x - x.floor()),
+        Mf::Trunc => float_map(arg, f64::trunc),
+        Mf::Modf => Value::Composite(vec![
+            float_map(arg.clone(), f64::fract),
+            float_map(arg, f64::trunc),
+        ]),
+        Mf::Frexp => {
+            let split = |x: f64| {
+                if x == 0.0 || !x.is_finite() {
+                    (x, 0)
+                } else {
+                    let exponent = x.abs().log2().floor() as i32 + 1;
+                    let fraction = x / 2f64.powi(exponent);
+                    // Correct for rounding errors of `log2`.
+                    if fraction.abs() >= 1.0 {
+                        (fraction / 2.0, exponent + 1)
+                    } else if fraction.abs() < 0.5 {
+                        (fraction * 2.0, exponent - 1)
+                    } else {
+                        (fraction, exponent)
+                    }
+                }
+            };
+            let fraction = float_map(arg.clone(), |x| split(x).0);
+            let exponent = map(arg, |c| Scalar::I32(split(c.as_f64()).1));
+            Value::Composite(vec![fraction, exponent])
+        }
+        Mf::Ldexp => zip(arg, arg1(), |e, exponent| {
+            e.with_f64(e.as_f64() * 2f64.powi(exponent.as_i32()))
+        }),
+        Mf::Exp => float_map(arg, f64::exp),
+        Mf::Exp2 => float_map(arg, f64::exp2),
+        Mf::Log => float_map(arg, f64::ln),
+        Mf::Log2 => float_map(arg, f64::log2),
+        Mf::Pow => float_zip(arg, arg1(), f64::powf),
+        Mf::Dot => Value::Scalar(dot(&arg.components(), &arg1().components())),
+        Mf::Outer => {
+            let a = arg.components();
+            Value::Matrix(
+                arg1()
+                    .components()
+                    .iter()
+                    .map(|&b| {
+                        a.iter()
+                            .map(|&a| binary_scalar(Bo::Multiply, a, b))
+                            .collect()
+                    })
+                    .collect(),
+            )
+        }
+        Mf::Cross => {
+            let a: Vec<f64> = arg.components().iter().map(|c| c.as_f64()).collect();
+            let b: Vec<f64> = arg1().components().iter().map(|c| c.as_f64()).collect();
+            let first = arg.scalar();
+            Value::Vector(
+                [
+                    a[1] * b[2] - a[2] * b[1],
+                    a[2] * b[0] - a[0] * b[2],
+                    a[0] * b[1] - a[1] * b[0],
+                ]
+                .iter()
+                .map(|&c| first.with_f64(c))
+                .collect(),
+            )
+        }
+        Mf::Distance => {
+            let first = arg.scalar();
+            let difference = binary(Bo::Subtract, arg, arg1());
+            Value::Scalar(first.with_f64(length(&difference)))
+        }
+        Mf::Length => Value::Scalar(arg.scalar().with_f64(length(&arg))),
+        Mf::Normalize => {
+            let length = length(&arg);
+            float_map(arg, |x| x / length)
+        }
+        Mf::FaceForward => {
+            let negate = dot(&arg1().components(), &arg2().components()).as_f64() < 0.0;
+            if negate {
+                arg
+            } else {
+                float_map(arg, |x| -x)
+            }
+        }
+        Mf::Reflect => {
+            let normal = arg1();
+            let d = 2.0 * dot(&normal.components(), &arg.components()).as_f64();
+            let scaled = float_map(normal, |x| x * d);
+            binary(Bo::Subtract, arg, scaled)
+        }
+        Mf::Refract => {
+            let normal = arg1();
+            let eta = arg2().scalar().as_f64();
+            let n_dot_i = dot(&normal.components(), &arg.components()).as_f64();
+            let k = 1.0 - eta * eta * (1.0 - n_dot_i * n_dot_i);
+            if k < 0.0 {
+                float_map(arg, |_| 0.0)
+            } else {
+                let a = float_map(arg, |x| x * eta);
+                let b = float_map(normal, |x| x * (eta * n_dot_i + k.sqrt()));
+                binary(Bo::Subtract, a, b)
+            }
+        }
+        Mf::Sign => map(arg, |c| match c {
+            Scalar::I32(v) => Scalar::I32(v.signum()),
+            other => float1(other, |x| if x == 0.0 { 0.0 } else { x.signum() }),
+        }),
+        Mf::Fma => zip3(arg, arg1(), arg2(), |a, b, c| {
+            a.with_f64(a.as_f64().mul_add(b.as_f64(), c.as_f64()))
+        }),
+        Mf::Mix => zip3(arg, arg1(), arg2(), |a, b, t| {
+            let t = t.as_f64();
+            a.with_f64(a.as_f64() * (1.0 - t) + b.as_f64() * t)
+        }),
+        Mf::Step => zip(arg, arg1(), |edge, x| {
+            x.with_f64(if x.as_f64() >= edge.as_f64() {
+                1.0
+            } else {
+                0.0
+            })
+        }),
+        Mf::SmoothStep => zip3(arg, arg1(), arg2(), |low, high, x| {
+            let (low, high) = (low.as_f64(), high.as_f64());
+            let t = ((x.as_f64() - low) / (high - low)).clamp(0.0, 1.0);
+            x.with_f64(t * t * (3.0 - 2.0 * t))
+        }),
+        Mf::Sqrt => float_map(arg, f64::sqrt),
+        Mf::InverseSqrt => float_map(arg, |x| 1.0 / x.sqrt()),
+        Mf::Inverse | Mf::Transpose | Mf::Determinant => {
+            let columns = match arg {
+                Value::Matrix(columns) => columns,
+                other => return other,
+            };
+            let first = columns[0][0];
+            let rows = columns_to_rows(&columns);
+            match fun {
+                Mf::Determinant => Value::Scalar(first.with_f64(determinant(rows))),
+                Mf::Transpose => Value::Matrix(
+                    (0..columns[0].len())
+                        .map(|row| columns.iter().map(|column| column[row]).collect())
+                        .collect(),
+                ),
+                _ => {
+                    let inverse = inverse(rows);
+                    Value::Matrix(
+                        (0..inverse.len())
+                            .map(|column| {
+                                inverse
+                                    .iter()
+                                    .map(|row| first.with_f64(row[column]))
+                                    .collect()
+                            })
+                            .collect(),
+                    )
+                }
+            }
+        }
+        Mf::CountTrailingZeros => map(arg, |c| integer_bits(c, u32::trailing_zeros)),
+        Mf::CountLeadingZeros => map(arg, |c| integer_bits(c, u32::leading_zeros)),
+        Mf::CountOneBits => map(arg, |c| integer_bits(c, u32::count_ones)),
+        Mf::ReverseBits => map(arg, |c| integer_bits(c, u32::reverse_bits)),
+        Mf::ExtractBits => {
+            let offset = arg1().scalar().as_index();
+            let count = arg2().scalar().as_index();
+            map(arg, |c| extract_bits(c, offset, count))
+        }
+        Mf::InsertBits => {
+            let offset = arg2().scalar().as_index();
+            let count = arg3.map_or(0, |count| count.scalar().as_index());
+            zip(arg, arg1(), |e, new_bits| {
+                insert_bits(e, new_bits, offset, count)
+            })
+        }
+        Mf::FindLsb => map(arg, |c| {
+            integer_bits(c, |v| if v == 0 { u32::MAX } else { v.trailing_zeros() })
+        }),
+        Mf::FindMsb => map(arg, |c| match c {
+            Scalar::I32(v) => {
+                let v = if v < 0 { !v } else { v };
+                Scalar::I32(if v == 0 {
+                    -1
+                } else {
+                    31 - v.leading_zeros() as i32
+                })
+            }
+            other => integer_bits(other, |v| {
+                if v == 0 {
+                    u32::MAX
+                } else {
+                    31 - v.leading_zeros()
+                }
+            }),
+        }),
+        Mf::Pack4x8snorm => pack(&arg.components(), 8, |x| {
+            (x.clamp(-1.0, 1.0) * 127.0).round() as i32 as u32
+        }),
+        Mf::Pack4x8unorm => pack(&arg.components(), 8, |x| {
+            (x.clamp(0.0, 1.0) * 255.0).round() as u32
+        }),
+        Mf::Pack2x16snorm => pack(&arg.components(), 16, |x| {
+            (x.clamp(-1.0, 1.0) * 32767.0).round() as i32 as u32
+        }),
+        Mf::Pack2x16unorm => pack(&arg.components(), 16, |x| {
+            (x.clamp(0.0, 1.0) * 65535.0).round() as u32
+        }),
+        Mf::Pack2x16float => pack(&arg.components(), 16, |x| conv::f32_to_f16(x as f32) as u32),
+        Mf::Unpack4x8snorm => unpack(arg.scalar().as_index(), 4, |v| {
+            (v as u8 as i8 as f64 / 127.0).max(-1.0)
+        }),
+        Mf::Unpack4x8unorm => unpack(arg.scalar().as_index(), 4, |v| v as f64 / 255.0),
+        Mf::Unpack2x16snorm => unpack(arg.scalar().as_index(), 2, |v| {
+            (v as u16 as i16 as f64 / 32767.0).max(-1.0)
+        }),
+        Mf::Unpack2x16unorm => unpack(arg.scalar().as_index(), 2, |v| v as f64 / 65535.0),
+        Mf::Unpack2x16float => unpack(arg.scalar().as_index(), 2, |v| {
+            conv::f16_to_f32(v as u16) as f64
+        }),
+    }
+}
+
+#[test]
+fn integer_semantics() {
+    assert_eq!(
+        binary_scalar(Bo::Divide, Scalar::I32(i32::MIN), Scalar::I32(-1)),
+        Scalar::I32(i32::MIN)
+    );
+    assert_eq!(
+        binary_scalar(Bo::Modulo, Scalar::U32(7), Scalar::U32(0)),
+        Scalar::U32(0)
+    );
+    assert_eq!(extract_bits(Scalar::I32(0b1100), 2, 2), Scalar::I32(-1),);
+    assert_eq!(
+        insert_bits(Scalar::U32(0), Scalar::U32(0b11), 4, 2),
+        Scalar::U32(0b11_0000),
+    );
+    assert_eq!(round_even(2.5), 2.0);
+    assert_eq!(round_even(-3.5), -4.0);
+}
+
+#[test]
+fn matrix_math() {
+    let column = |x: f32, y: f32| Vector::from_iter([Scalar::F32(x), Scalar::F32(y)]);
+    // Rotation by 90 degrees: columns (0, 1) and (-1, 0).
+    let rotation = Value::Matrix([column(0.0, 1.0), column(-1.0, 0.0)].into_iter().collect());
+    let rotated = binary(
+        Bo::Multiply,
+        rotation.clone(),
+        Value::Vector(column(1.0, 0.0)),
+    );
+    assert_eq!(rotated.components(), column(0.0, 1.0));
+    let determinant = math(Mf::Determinant, rotation, None, None, None);
+    assert_eq!(determinant.scalar(), Scalar::F32(1.0));
+    assert_eq!(
+        naga::ScalarKind::Float,
+        math(
+            Mf::Length,
+            Value::Vector(column(3.0, 4.0)),
+            None,
+            None,
+            None
+        )
+        .scalar()
+        .kind()
+    );
+}
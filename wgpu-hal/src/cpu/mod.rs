@@ -0,0 +1,348 @@
+/*!
+# CPU software rasterizer.
+
+A backend that doesn't need any GPU, driver or window system: all resources
+live in host memory and all work is done on the calling thread when command
+buffers are submitted. It is meant for headless testing and trace replay on
+machines without a GPU, not for speed.
+
+## Resources
+
+Buffers, textures and query sets are plain zero-initialized allocations.
+Buffers are always mappable and coherent, so mapping only hands out a pointer
+into the allocation.
+
+Textures store every mip level of every array layer tightly packed. Depth and
+stencil are kept in separate planes so that copies of a single aspect are a
+plain memory copy: depth is stored as `f32` (or `u16` for `Depth16Unorm`) and
+stencil as `u8`. Multisampled textures keep a single sample per texel, which
+the rasterizer writes for all covered samples; resolving is a copy.
+
+## Commands
+
+Command encoders record a list of commands which keeps the referenced
+resources alive. Submitting replays the list in order. There is no
+concurrency, so barriers and transitions are no-ops, fences are signaled as
+soon as `submit` returns and timestamps come from a host clock.
+
+## Shaders
+
+Only naga IR is accepted. Entry points are executed by an interpreter that
+walks the IR directly, see `shader.rs`. Derivatives evaluate to zero, so
+implicit level of detail always samples the base level of a view. Compute
+workgroups that use barriers get one host thread per invocation, with the
+threads taking turns between barriers; other workgroups run their invocations
+one after another.
+
+## Rasterization
+
+Draws go through a simple rasterizer (`raster.rs`): primitives are clipped
+against the near and far planes, then pixel centers are tested against
+top-left edge functions, and varyings are interpolated with perspective
+correction. Points and lines are one pixel wide.
+*/
+
+mod adapter;
+mod command;
+mod conv;
+mod device;
+mod image;
+mod math;
+mod queue;
+mod raster;
+mod shader;
+
+use std::{ops::Range, sync::Arc, time::Instant};
+
+use arrayvec::ArrayVec;
+
+use crate::host_memory::Memory;
+
+#[derive(Clone, Debug)]
+pub struct Api;
+
+impl crate::Api for Api {
+    type Instance = Instance;
+    type Surface = Surface;
+    type Adapter = Adapter;
+    type Device = Device;
+
+    type Queue = Queue;
+    type CommandEncoder = CommandEncoder;
+    type CommandBuffer = CommandBuffer;
+
+    type Buffer = Buffer;
+    type Texture = Texture;
+    type SurfaceTexture = Texture;
+    type TextureView = TextureView;
+    type Sampler = Sampler;
+    type QuerySet = QuerySet;
+    type Fence = Fence;
+
+    type BindGroupLayout = BindGroupLayout;
+    type BindGroup = BindGroup;
+    type PipelineLayout = PipelineLayout;
+    type ShaderModule = ShaderModule;
+    type RenderPipeline = RenderPipeline;
+    type ComputePipeline = ComputePipeline;
+    type PipelineCache = ();
+    type Heap = ();
+    type ExternalSemaphore = ();
+
+    type AccelerationStructure = AccelerationStructure;
+}
+
+pub struct Instance;
+
+/// Presenting isn't supported, so surfaces can never be created.
+#[derive(Debug)]
+pub enum Surface {}
+
+pub struct Adapter;
+
+pub struct Device;
+
+pub struct Queue {
+    /// Origin of the timestamps written by timestamp queries.
+    epoch: Instant,
+}
+
+#[derive(Debug)]
+pub struct Buffer {
+    memory: Arc<Memory>,
+}
+
+/// One aspect of a texture.
+#[derive(Debug)]
+struct Plane {
+    memory: Memory,
+    texel_size: u32,
+    /// Byte offset of every mip level.
+    mip_offsets: Vec<u64>,
+}
+
+#[derive(Debug)]
+struct TextureInner {
+    format: wgt::TextureFormat,
+    dimension: wgt::TextureDimension,
+    size: wgt::Extent3d,
+    mip_level_count: u32,
+    sample_count: u32,
+    /// Color plane, or depth and/or stencil planes, in that order.
+    planes: ArrayVec<(crate::FormatAspects, Plane), 2>,
+}
+
+impl TextureInner {
+    fn mip_size(&self, level: u32) -> wgt::Extent3d {
+        self.size.mip_level_size(level, self.dimension)
+    }
+
+    /// Plane holding `aspect`, which must be a single aspect.
+    fn plane(&self, aspect: crate::FormatAspects) -> &Plane {
+        self.planes
+            .iter()
+            .find(|&&(aspects, _)| aspects.contains(aspect))
+            .map(|&(_, ref plane)| plane)
+            .unwrap_or(&self.planes[0].1)
+    }
+
+    /// Byte offset of a texel in a plane. `z` is the depth slice of 3D
+    /// textures and the array layer otherwise.
+    fn texel_offset(&self, plane: &Plane, level: u32, x: u32, y: u32, z: u32) -> u64 {
+        let size = self.mip_size(level);
+        let index = (z as u64 * size.height as u64 + y as u64) * size.width as u64 + x as u64;
+        plane.mip_offsets[level as usize] + index * plane.texel_size as u64
+    }
+}
+
+#[derive(Debug)]
+pub struct Texture {
+    inner: Arc<TextureInner>,
+}
+
+#[derive(Clone, Debug)]
+pub struct TextureView {
+    texture: Arc<TextureInner>,
+    format: wgt::TextureFormat,
+    dimension: wgt::TextureViewDimension,
+    aspects: crate::FormatAspects,
+    mip_levels: Range<u32>,
+    array_layers: Range<u32>,
+}
+
+#[derive(Clone, Debug)]
+pub struct Sampler {
+    address_modes: [wgt::AddressMode; 3],
+    mag_filter: wgt::FilterMode,
+    min_filter: wgt::FilterMode,
+    mipmap_filter: wgt::FilterMode,
+    lod_clamp: Range<f32>,
+    compare: Option<wgt::CompareFunction>,
+    border_color: Option<wgt::SamplerBorderColor>,
+}
+
+#[derive(Debug)]
+pub struct QuerySet {
+    /// One `u64` per query.
+    memory: Arc<Memory>,
+}
+
+#[derive(Debug)]
+pub struct Fence {
+    value: crate::FenceValue,
+}
+
+#[derive(Debug)]
+pub struct BindGroupLayout {
+    entries: Vec<wgt::BindGroupLayoutEntry>,
+}
+
+#[derive(Debug)]
+pub struct PipelineLayout;
+
+#[derive(Debug)]
+enum Resource {
+    Buffer {
+        memory: Arc<Memory>,
+        offset: wgt::BufferAddress,
+        size: wgt::BufferAddress,
+    },
+    Sampler(Sampler),
+    Texture(Arc<TextureView>),
+}
+
+#[derive(Debug)]
+pub struct BindGroup {
+    /// Resources sorted by binding, paired with whether the binding has a
+    /// dynamic offset.
+    entries: Arc<Vec<(u32, bool, Resource)>>,
+}
+
+#[derive(Debug)]
+pub struct ShaderModule {
+    module: Arc<naga::Module>,
+}
+
+/// An entry point with the module it lives in.
+#[derive(Debug)]
+struct Stage {
+    module: Arc<naga::Module>,
+    entry_point: usize,
+}
+
+impl Stage {
+    fn new(
+        stage: &crate::ProgrammableStage<Api>,
+        naga_stage: naga::ShaderStage,
+    ) -> Result<Self, crate::PipelineError> {
+        let module = &stage.module.module;
+        let entry_point = module
+            .entry_points
+            .iter()
+            .position(|ep| ep.stage == naga_stage && ep.name == stage.entry_point)
+            .ok_or(crate::PipelineError::EntryPoint(naga_stage))?;
+        Ok(Self {
+            module: Arc::clone(module),
+            entry_point,
+        })
+    }
+
+    fn entry_point(&self) -> &naga::EntryPoint {
+        &self.module.entry_points[self.entry_point]
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct ComputePipeline {
+    stage: Arc<Stage>,
+    /// Whether the invocations of a workgroup need to run concurrently.
+    uses_barriers: bool,
+}
+
+#[derive(Debug)]
+struct VertexBufferLayout {
+    array_stride: wgt::BufferAddress,
+    step_mode: wgt::VertexStepMode,
+    attributes: Vec<wgt::VertexAttribute>,
+}
+
+#[derive(Debug)]
+struct RenderPipelineInner {
+    vertex_stage: Stage,
+    fragment_stage: Option<Stage>,
+    vertex_buffers: Vec<VertexBufferLayout>,
+    primitive: wgt::PrimitiveState,
+    depth_stencil: Option<wgt::DepthStencilState>,
+    color_targets: Vec<Option<wgt::ColorTargetState>>,
+}
+
+#[derive(Clone, Debug)]
+pub struct RenderPipeline {
+    inner: Arc<RenderPipelineInner>,
+}
+
+/// Acceleration structures aren't supported, this only exists to satisfy
+/// the `Api` trait.
+#[derive(Debug)]
+pub struct AccelerationStructure;
+
+#[derive(Debug)]
+pub struct CommandEncoder {
+    commands: Vec<command::Command>,
+    /// Timestamp to write when the current pass ends.
+    pass_end_timestamp: Option<(Arc<Memory>, u32)>,
+}
+
+#[derive(Debug)]
+pub struct CommandBuffer {
+    commands: Vec<command::Command>,
+}
+
+impl crate::Instance<Api> for Instance {
+    unsafe fn init(_desc: &crate::InstanceDescriptor) -> Result<Self, crate::InstanceError> {
+        Ok(Self)
+    }
+
+    unsafe fn create_surface(
+        &self,
+        _display_handle: raw_window_handle::RawDisplayHandle,
+        _window_handle: raw_window_handle::RawWindowHandle,
+    ) -> Result<Surface, crate::InstanceError> {
+        Err(crate::InstanceError::new(String::from(
+            "the CPU backend can't present to surfaces",
+        )))
+    }
+
+    unsafe fn destroy_surface(&self, surface: Surface) {
+        match surface {}
+    }
+
+    unsafe fn enumerate_adapters(&self) -> Vec<crate::ExposedAdapter<Api>> {
+        vec![Adapter::expose()]
+    }
+}
+
+impl crate::Surface<Api> for Surface {
+    unsafe fn configure(
+        &mut self,
+        _device: &Device,
+        _config: &crate::SurfaceConfiguration,
+    ) -> Result<(), crate::SurfaceError> {
+        match *self {}
+    }
+
+    unsafe fn unconfigure(&mut self, _device: &Device) {
+        match *self {}
+    }
+
+    unsafe fn acquire_texture(
+        &mut self,
+        _timeout: Option<std::time::Duration>,
+    ) -> Result<Option<crate::AcquiredSurfaceTexture<Api>>, crate::SurfaceError> {
+        match *self {}
+    }
+
+    unsafe fn discard_texture(&mut self, _texture: Texture) {
+        match *self {}
+    }
+}
@@ -0,0 +1,427 @@
+use std::sync::{Arc, Barrier};
+
+use crate::host_memory::buffer_row_offset;
+
+use super::{
+    command::Command,
+    raster::{self, RenderPass, RenderState},
+    shader::{BoundGroup, ComputeIds, Invocation, Program, Workgroup},
+    Api, Memory, TextureInner,
+};
+
+/// State carried between the commands of a submission.
+#[derive(Default)]
+struct State<'c> {
+    bind_groups: Vec<Option<BoundGroup>>,
+    compute_pipeline: Option<super::ComputePipeline>,
+    render_pass: Option<(&'c RenderPass, RenderState)>,
+    /// Occlusion query being counted.
+    occlusion_query: Option<(Arc<Memory>, u32)>,
+}
+
+/// Reads little endian `u32`s from a buffer.
+fn read_u32s<const N: usize>(memory: &Memory, offset: u64) -> [u32; N] {
+    let mut words = [0; N];
+    for (i, word) in words.iter_mut().enumerate() {
+        let mut data = [0; 4];
+        memory.read(offset + i as u64 * 4, &mut data);
+        *word = u32::from_le_bytes(data);
+    }
+    words
+}
+
+fn write_query(set: &Memory, index: u32, value: u64) {
+    set.write(index as u64 * 8, &value.to_le_bytes());
+}
+
+/// Calls `row` with the plane offset of every row of texels in a copy
+/// region, and the index of that row within the region.
+fn for_each_row(
+    texture: &TextureInner,
+    base: &crate::TextureCopyBase,
+    size: &crate::CopyExtent,
+    mut row: impl FnMut(&Memory, u64, u64, u32, u32),
+) {
+    let plane = texture.plane(base.aspect);
+    let row_size = size.width as u64 * plane.texel_size as u64;
+    let is_3d = texture.dimension == wgt::TextureDimension::D3;
+    for z in 0..size.depth {
+        let slice = if is_3d {
+            base.origin.z + z
+        } else {
+            base.array_layer + z
+        };
+        for y in 0..size.height {
+            let offset = texture.texel_offset(
+                plane,
+                base.mip_level,
+                base.origin.x,
+                base.origin.y + y,
+                slice,
+            );
+            row(&plane.memory, offset, row_size, y, z);
+        }
+    }
+}
+
+impl super::Queue {
+    fn execute<'c>(&self, state: &mut State<'c>, command: &'c Command) {
+        match *command {
+            Command::ClearBuffer {
+                ref memory,
+                ref range,
+            } => memory.fill(range.clone(), 0),
+            Command::CopyBufferToBuffer {
+                ref src,
+                ref dst,
+                ref regions,
+            } => {
+                for region in regions {
+                    Memory::copy(
+                        src,
+                        region.src_offset,
+                        dst,
+                        region.dst_offset,
+                        region.size.get(),
+                    );
+                }
+            }
+            Command::CopyTextureToTexture {
+                ref src,
+                ref dst,
+                ref regions,
+            } => {
+                for region in regions {
+                    let dst_plane = dst.plane(region.dst_base.aspect);
+                    let is_3d = dst.dimension == wgt::TextureDimension::D3;
+                    for_each_row(
+                        src,
+                        &region.src_base,
+                        &region.size,
+                        |memory, offset, size, y, z| {
+                            let base = &region.dst_base;
+                            let slice = if is_3d {
+                                base.origin.z + z
+                            } else {
+                                base.array_layer + z
+                            };
+                            let dst_offset = dst.texel_offset(
+                                dst_plane,
+                                base.mip_level,
+                                base.origin.x,
+                                base.origin.y + y,
+                                slice,
+                            );
+                            Memory::copy(memory, offset, &dst_plane.memory, dst_offset, size);
+                        },
+                    );
+                }
+            }
+            Command::CopyBufferToTexture {
+                ref src,
+                ref dst,
+                ref regions,
+            } => {
+                for region in regions {
+                    for_each_row(
+                        dst,
+                        &region.texture_base,
+                        &region.size,
+                        |memory, offset, size, y, z| {
+                            let src_offset =
+                                buffer_row_offset(region, size, region.size.height, y, z);
+                            Memory::copy(src, src_offset, memory, offset, size);
+                        },
+                    );
+                }
+            }
+            Command::CopyTextureToBuffer {
+                ref src,
+                ref dst,
+                ref regions,
+            } => {
+                for region in regions {
+                    for_each_row(
+                        src,
+                        &region.texture_base,
+                        &region.size,
+                        |memory, offset, size, y, z| {
+                            let dst_offset =
+                                buffer_row_offset(region, size, region.size.height, y, z);
+                            Memory::copy(memory, offset, dst, dst_offset, size);
+                        },
+                    );
+                }
+            }
+            Command::BeginQuery { ref set, index } => {
+                if let Some((_, ref mut render_state)) = state.render_pass {
+                    render_state.samples_passed = 0;
+                }
+                state.occlusion_query = Some((Arc::clone(set), index));
+            }
+            Command::EndQuery => {
+                if let Some((set, index)) = state.occlusion_query.take() {
+                    let samples = state
+                        .render_pass
+                        .as_ref()
+                        .map_or(0, |&(_, ref render_state)| render_state.samples_passed);
+                    write_query(&set, index, samples);
+                }
+            }
+            Command::WriteTimestamp { ref set, index } => {
+                let nanoseconds = self.epoch.elapsed().as_nanos() as u64;
+                write_query(set, index, nanoseconds);
+            }
+            Command::ResetQueries { ref set, ref range } => {
+                set.fill(range.start as u64 * 8..range.end as u64 * 8, 0);
+            }
+            Command::CopyQueryResults {
+                ref set,
+                ref range,
+                ref dst,
+                offset,
+                stride,
+            } => {
+                for (i, index) in range.clone().enumerate() {
+                    Memory::copy(set, index as u64 * 8, dst, offset + i as u64 * stride, 8);
+                }
+            }
+            Command::BeginRenderPass(ref pass) => {
+                pass.begin();
+                state.render_pass = Some((pass, RenderState::new(pass)));
+            }
+            Command::EndRenderPass => {
+                if let Some((pass, _)) = state.render_pass.take() {
+                    pass.end();
+                }
+            }
+            Command::SetBindGroup { index, ref group } => {
+                let index = index as usize;
+                if state.bind_groups.len() <= index {
+                    state.bind_groups.resize_with(index + 1, || None);
+                }
+                state.bind_groups[index] = Some(BoundGroup {
+                    entries: Arc::clone(&group.entries),
+                    dynamic_offsets: group.dynamic_offsets.clone(),
+                });
+            }
+            Command::SetRenderPipeline(ref pipeline) => {
+                if let Some((_, ref mut render_state)) = state.render_pass {
+                    render_state.pipeline = Some(Arc::clone(&pipeline.inner));
+                }
+            }
+            Command::SetIndexBuffer {
+                ref binding,
+                format,
+            } => {
+                if let Some((_, ref mut render_state)) = state.render_pass {
+                    render_state.index_buffer = Some((binding.clone(), format));
+                }
+            }
+            Command::SetVertexBuffer { index, ref binding } => {
+                if let Some((_, ref mut render_state)) = state.render_pass {
+                    let index = index as usize;
+                    if render_state.vertex_buffers.len() <= index {
+                        render_state.vertex_buffers.resize(index + 1, None);
+                    }
+                    render_state.vertex_buffers[index] = Some(binding.clone());
+                }
+            }
+            Command::SetViewport {
+                ref rect,
+                ref depth_range,
+            } => {
+                if let Some((_, ref mut render_state)) = state.render_pass {
+                    render_state.viewport = rect.clone();
+                    render_state.depth_range = depth_range.clone();
+                }
+            }
+            Command::SetScissorRect(ref rect) => {
+                if let Some((_, ref mut render_state)) = state.render_pass {
+                    render_state.scissor = rect.clone();
+                }
+            }
+            Command::SetStencilReference(value) => {
+                if let Some((_, ref mut render_state)) = state.render_pass {
+                    render_state.stencil_reference = value;
+                }
+            }
+            Command::SetBlendConstants(color) => {
+                if let Some((_, ref mut render_state)) = state.render_pass {
+                    render_state.blend_constant = color;
+                }
+            }
+            Command::Draw(draw) => {
+                if let Some((pass, ref mut render_state)) = state.render_pass {
+                    pass.draw(render_state, &state.bind_groups, draw);
+                }
+            }
+            Command::DrawIndirect {
+                ref buffer,
+                offset,
+                ref count,
+                max_count,
+                indexed,
+            } => {
+                let draw_count = match *count {
+                    Some((ref memory, offset)) => read_u32s::<1>(memory, offset)[0].min(max_count),
+                    None => max_count,
+                };
+                let stride = if indexed { 20 } else { 16 };
+                for i in 0..draw_count {
+                    let offset = offset + i as u64 * stride;
+                    let draw = if indexed {
+                        let [count, instance_count, first, base_vertex, first_instance] =
+                            read_u32s(buffer, offset);
+                        raster::Draw {
+                            first,
+                            count,
+                            base_vertex: base_vertex as i32,
+                            first_instance,
+                            instance_count,
+                            indexed,
+                        }
+                    } else {
+                        let [count, instance_count, first, first_instance] =
+                            read_u32s(buffer, offset);
+                        raster::Draw {
+                            first,
+                            count,
+                            base_vertex: 0,
+                            first_instance,
+                            instance_count,
+                            indexed,
+                        }
+                    };
+                    if let Some((pass, ref mut render_state)) = state.render_pass {
+                        pass.draw(render_state, &state.bind_groups, draw);
+                    }
+                }
+            }
+            Command::SetComputePipeline(ref pipeline) => {
+                state.compute_pipeline = Some(pipeline.clone());
+            }
+            Command::Dispatch(count) => self.dispatch(state, count),
+            Command::DispatchIndirect { ref buffer, offset } => {
+                self.dispatch(state, read_u32s(buffer, offset));
+            }
+        }
+    }
+
+    fn dispatch(&self, state: &State, count: [u32; 3]) {
+        let pipeline = match state.compute_pipeline {
+            Some(ref pipeline) => pipeline,
+            None => return,
+        };
+        let module = &pipeline.stage.module;
+        let entry_point = pipeline.stage.entry_point();
+        let program = Program::new(module, &state.bind_groups);
+        let [size_x, size_y, size_z] = entry_point.workgroup_size;
+        let local_ids = (0..size_z)
+            .flat_map(|z| (0..size_y).flat_map(move |y| (0..size_x).map(move |x| [x, y, z])));
+
+        for group_z in 0..count[2] {
+            for group_y in 0..count[1] {
+                for group_x in 0..count[0] {
+                    let workgroup_id = [group_x, group_y, group_z];
+                    let ids = |local: [u32; 3]| ComputeIds {
+                        global_invocation_id: [0, 1, 2]
+                            .map(|i| workgroup_id[i] * entry_point.workgroup_size[i] + local[i]),
+                        local_invocation_id: local,
+                        local_invocation_index: (local[2] * size_y + local[1]) * size_x + local[0],
+                        workgroup_id,
+                        num_workgroups: count,
+                    };
+                    let workgroup = Workgroup::new(module);
+
+                    if pipeline.uses_barriers {
+                        // Every invocation needs its own thread so that
+                        // they can all wait on the barrier.
+                        let barrier = Barrier::new((size_x * size_y * size_z) as usize);
+                        std::thread::scope(|scope| {
+                            for local in local_ids.clone() {
+                                let (program, workgroup, barrier) =
+                                    (&program, &workgroup, &barrier);
+                                let ids = ids(local);
+                                scope.spawn(move || {
+                                    let mut invocation =
+                                        Invocation::new(program, workgroup, Some(barrier));
+                                    run_invocation(&mut invocation, module, entry_point, ids);
+                                });
+                            }
+                        });
+                    } else {
+                        let mut invocation = Invocation::new(&program, &workgroup, None);
+                        for local in local_ids.clone() {
+                            run_invocation(&mut invocation, module, entry_point, ids(local));
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn run_invocation<'a>(
+    invocation: &mut Invocation<'a, '_>,
+    module: &naga::Module,
+    entry_point: &'a naga::EntryPoint,
+    ids: ComputeIds,
+) {
+    invocation.run(entry_point, &mut |binding, ty| {
+        ids.get(binding)
+            .unwrap_or_else(|| super::shader::zero_value(module, ty))
+    });
+}
+
+impl crate::Queue<Api> for super::Queue {
+    unsafe fn submit(
+        &mut self,
+        command_buffers: &[&super::CommandBuffer],
+        signal_fence: Option<(&mut super::Fence, crate::FenceValue)>,
+    ) -> Result<(), crate::DeviceError> {
+        for command_buffer in command_buffers {
+            let mut state = State::default();
+            for command in command_buffer.commands.iter() {
+                self.execute(&mut state, command);
+            }
+        }
+        if let Some((fence, value)) = signal_fence {
+            fence.value = value;
+        }
+        Ok(())
+    }
+
+    // No external semaphore can be created, so there is nothing to wait on or signal.
+    unsafe fn submit_with_sync(
+        &mut self,
+        command_buffers: &[&super::CommandBuffer],
+        _waits: &[(&(), u64)],
+        _signals: &[(&(), u64)],
+        signal_fence: Option<(&mut super::Fence, crate::FenceValue)>,
+    ) -> Result<(), crate::DeviceError> {
+        unsafe { crate::Queue::submit(self, command_buffers, signal_fence) }
+    }
+
+    unsafe fn present(
+        &mut self,
+        surface: &mut super::Surface,
+        _texture: super::Texture,
+    ) -> Result<(), crate::SurfaceError> {
+        match *surface {}
+    }
+
+    // Sparse resources aren't exposed by this backend.
+    unsafe fn bind_sparse(
+        &mut self,
+        _buffer_bindings: &[crate::SparseBufferBinding<Api>],
+        _texture_bindings: &[crate::SparseTextureBinding<Api>],
+        _signal_fence: Option<(&mut super::Fence, crate::FenceValue)>,
+    ) -> Result<(), crate::DeviceError> {
+        Err(crate::DeviceError::ResourceCreationFailed)
+    }
+
+    unsafe fn get_timestamp_period(&self) -> f32 {
+        1.0
+    }
+}
@@ -0,0 +1,1094 @@
+//! Render passes: attachment load/store and the triangle rasterizer.
+
+use std::{collections::HashMap, ops::Range, sync::Arc};
+
+use arrayvec::ArrayVec;
+use naga::Handle;
+
+use super::{
+    conv::{self, Texel},
+    shader::{self, BoundGroup, Invocation, Outputs, Program, Scalar, Value, Workgroup},
+    Api, Memory, Plane, RenderPipelineInner, TextureView,
+};
+
+/// Clip space `w` below which vertices are clipped, to avoid dividing by
+/// zero.
+const MIN_W: f32 = 1e-6;
+
+#[derive(Clone, Debug)]
+pub(super) struct BufferBinding {
+    memory: Arc<Memory>,
+    offset: wgt::BufferAddress,
+    size: wgt::BufferAddress,
+}
+
+impl BufferBinding {
+    pub(super) fn new(binding: &crate::BufferBinding<Api>) -> Self {
+        let memory = &binding.buffer.memory;
+        let size = match binding.size {
+            Some(size) => size.get(),
+            None => memory.len().saturating_sub(binding.offset),
+        };
+        Self {
+            memory: Arc::clone(memory),
+            offset: binding.offset,
+            size,
+        }
+    }
+
+    /// Reads `dst.len()` bytes at `offset` into the binding. Reads that
+    /// aren't entirely in bounds return zeros.
+    fn read(&self, offset: u64, dst: &mut [u8]) {
+        if offset.saturating_add(dst.len() as u64) > self.size {
+            dst.fill(0);
+        } else {
+            self.memory.read(self.offset + offset, dst);
+        }
+    }
+}
+
+#[derive(Debug)]
+pub(super) struct ColorTarget {
+    pub view: TextureView,
+    pub resolve_target: Option<TextureView>,
+    pub ops: crate::AttachmentOps,
+    pub clear_value: wgt::Color,
+}
+
+#[derive(Debug)]
+pub(super) struct DepthStencilTarget {
+    pub view: TextureView,
+    pub depth_ops: crate::AttachmentOps,
+    pub stencil_ops: crate::AttachmentOps,
+    pub clear_value: (f32, u32),
+}
+
+#[derive(Debug)]
+pub(super) struct RenderPass {
+    pub extent: wgt::Extent3d,
+    pub color_targets: Vec<Option<ColorTarget>>,
+    pub depth_stencil: Option<DepthStencilTarget>,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub(super) struct Draw {
+    /// First vertex, or first index for indexed draws.
+    pub first: u32,
+    pub count: u32,
+    pub base_vertex: i32,
+    pub first_instance: u32,
+    pub instance_count: u32,
+    pub indexed: bool,
+}
+
+/// State set by commands inside a render pass.
+pub(super) struct RenderState {
+    pub pipeline: Option<Arc<RenderPipelineInner>>,
+    pub index_buffer: Option<(BufferBinding, wgt::IndexFormat)>,
+    pub vertex_buffers: Vec<Option<BufferBinding>>,
+    pub viewport: crate::Rect<f32>,
+    pub depth_range: Range<f32>,
+    pub scissor: crate::Rect<u32>,
+    pub stencil_reference: u32,
+    pub blend_constant: [f32; 4],
+    /// Samples that passed the depth and stencil tests, for occlusion
+    /// queries.
+    pub samples_passed: u64,
+}
+
+impl RenderState {
+    pub(super) fn new(pass: &RenderPass) -> Self {
+        Self {
+            pipeline: None,
+            index_buffer: None,
+            vertex_buffers: Vec::new(),
+            viewport: crate::Rect {
+                x: 0.0,
+                y: 0.0,
+                w: pass.extent.width as f32,
+                h: pass.extent.height as f32,
+            },
+            depth_range: 0.0..1.0,
+            scissor: crate::Rect {
+                x: 0,
+                y: 0,
+                w: pass.extent.width,
+                h: pass.extent.height,
+            },
+            stencil_reference: 0,
+            blend_constant: [0.0; 4],
+            samples_passed: 0,
+        }
+    }
+}
+
+/// Byte offset of texel `(x, y)` of an attachment view.
+fn attachment_offset(view: &TextureView, plane: &Plane, x: u32, y: u32) -> u64 {
+    view.texture
+        .texel_offset(plane, view.mip_levels.start, x, y, view.array_layers.start)
+}
+
+fn read_texel(view: &TextureView, plane: &Plane, x: u32, y: u32, data: &mut [u8]) {
+    let data = &mut data[..plane.texel_size as usize];
+    plane
+        .memory
+        .read(attachment_offset(view, plane, x, y), data);
+}
+
+fn write_texel(view: &TextureView, plane: &Plane, x: u32, y: u32, data: &[u8]) {
+    let data = &data[..plane.texel_size as usize];
+    plane
+        .memory
+        .write(attachment_offset(view, plane, x, y), data);
+}
+
+/// Fills an attachment with a single texel value.
+fn fill(view: &TextureView, aspect: crate::FormatAspects, data: &[u8]) {
+    let plane = view.texture.plane(aspect);
+    let size = view.texture.mip_size(view.mip_levels.start);
+    for y in 0..size.height {
+        for x in 0..size.width {
+            write_texel(view, plane, x, y, data);
+        }
+    }
+}
+
+impl RenderPass {
+    /// Clears the attachments that aren't loaded.
+    pub(super) fn begin(&self) {
+        let mut data = [0u8; 16];
+        for target in self.color_targets.iter().flatten() {
+            if !target.ops.contains(crate::AttachmentOps::LOAD) {
+                let texel = Texel::from_color(target.view.format, target.clear_value);
+                conv::encode_color(target.view.format, texel, &mut data);
+                fill(&target.view, crate::FormatAspects::COLOR, &data);
+            }
+        }
+        if let Some(ref target) = self.depth_stencil {
+            let format = target.view.texture.format;
+            if format.has_depth_aspect() && !target.depth_ops.contains(crate::AttachmentOps::LOAD) {
+                conv::encode_depth(format, target.clear_value.0, &mut data);
+                fill(&target.view, crate::FormatAspects::DEPTH, &data);
+            }
+            if format.has_stencil_aspect()
+                && !target.stencil_ops.contains(crate::AttachmentOps::LOAD)
+            {
+                fill(
+                    &target.view,
+                    crate::FormatAspects::STENCIL,
+                    &[target.clear_value.1 as u8],
+                );
+            }
+        }
+    }
+
+    /// Resolves multisampled attachments.
+    ///
+    /// Multisampled textures only store one sample per texel, so resolving
+    /// is a copy.
+    pub(super) fn end(&self) {
+        for target in self.color_targets.iter().flatten() {
+            let resolve = match target.resolve_target {
+                Some(ref resolve) => resolve,
+                None => continue,
+            };
+            let src_plane = target.view.texture.plane(crate::FormatAspects::COLOR);
+            let dst_plane = resolve.texture.plane(crate::FormatAspects::COLOR);
+            let size = target.view.texture.mip_size(target.view.mip_levels.start);
+            let row_size = size.width as u64 * src_plane.texel_size as u64;
+            for y in 0..size.height {
+                Memory::copy(
+                    &src_plane.memory,
+                    attachment_offset(&target.view, src_plane, 0, y),
+                    &dst_plane.memory,
+                    attachment_offset(resolve, dst_plane, 0, y),
+                    row_size,
+                );
+            }
+        }
+    }
+
+    pub(super) fn draw(&self, state: &mut RenderState, groups: &[Option<BoundGroup>], draw: Draw) {
+        let pipeline = match state.pipeline {
+            Some(ref pipeline) => Arc::clone(pipeline),
+            None => return,
+        };
+        let vertex_program = Program::new(&pipeline.vertex_stage.module, groups);
+        let vertex_workgroup = Workgroup::new(&pipeline.vertex_stage.module);
+        let fragment_program = pipeline
+            .fragment_stage
+            .as_ref()
+            .map(|stage| Program::new(&stage.module, groups));
+        let fragment_workgroup = pipeline
+            .fragment_stage
+            .as_ref()
+            .map(|stage| Workgroup::new(&stage.module));
+
+        let indices = self.indices(state, &pipeline, draw);
+        let vertex_buffers = state.vertex_buffers.clone();
+        let mut rasterizer = Rasterizer {
+            pass: self,
+            pipeline: &pipeline,
+            state,
+            fragment_invocation: match (&fragment_program, &fragment_workgroup) {
+                (&Some(ref program), &Some(ref workgroup)) => {
+                    Some(Invocation::new(program, workgroup, None))
+                }
+                _ => None,
+            },
+            bounds: [0.0; 4],
+        };
+        rasterizer.bounds = rasterizer.bounds();
+        let mut vertex_invocation = Invocation::new(&vertex_program, &vertex_workgroup, None);
+
+        for instance in draw.first_instance..draw.first_instance + draw.instance_count {
+            let mut vertices = HashMap::new();
+            let mut shade = |index: u32| -> Arc<Vertex> {
+                Arc::clone(vertices.entry(index).or_insert_with(|| {
+                    Arc::new(shade_vertex(
+                        &mut vertex_invocation,
+                        &pipeline,
+                        &vertex_buffers,
+                        index,
+                        instance,
+                    ))
+                }))
+            };
+            let primitives = assemble(pipeline.primitive.topology, &indices);
+            for (primitive_index, primitive) in (0..).zip(primitives) {
+                let vertices: ArrayVec<Arc<Vertex>, 3> = primitive
+                    .indices
+                    .iter()
+                    .map(|&index| shade(index))
+                    .collect();
+                match vertices.len() {
+                    1 => rasterizer.point(&vertices[0], primitive_index),
+                    2 => rasterizer.line([&vertices[0], &vertices[1]], primitive_index),
+                    _ => rasterizer.triangle(
+                        [&vertices[0], &vertices[1], &vertices[2]],
+                        primitive.odd,
+                        primitive_index,
+                    ),
+                }
+            }
+        }
+    }
+
+    /// Vertex indices of a draw, with `None` for primitive restarts.
+    fn indices(
+        &self,
+        state: &RenderState,
+        pipeline: &RenderPipelineInner,
+        draw: Draw,
+    ) -> Vec<Option<u32>> {
+        let range = draw.first..draw.first.saturating_add(draw.count);
+        if !draw.indexed {
+            return range.map(Some).collect();
+        }
+        let (buffer, format) = match state.index_buffer {
+            Some((ref buffer, format)) => (buffer, format),
+            None => return Vec::new(),
+        };
+        let restart = pipeline.primitive.strip_index_format.is_some();
+        range
+            .map(|i| {
+                let (index, restart_index) = match format {
+                    wgt::IndexFormat::Uint16 => {
+                        let mut data = [0; 2];
+                        buffer.read(i as u64 * 2, &mut data);
+                        (u16::from_le_bytes(data) as u32, u16::MAX as u32)
+                    }
+                    wgt::IndexFormat::Uint32 => {
+                        let mut data = [0; 4];
+                        buffer.read(i as u64 * 4, &mut data);
+                        (u32::from_le_bytes(data), u32::MAX)
+                    }
+                };
+                if restart && index == restart_index {
+                    None
+                } else {
+                    Some((index as i64 + draw.base_vertex as i64) as u32)
+                }
+            })
+            .collect()
+    }
+}
+
+/// A shaded vertex.
+struct Vertex {
+    position: [f32; 4],
+    outputs: Outputs,
+}
+
+/// Converts vertex data to a value of type `ty`.
+fn input_value(module: &naga::Module, ty: Handle<naga::Type>, texel: Texel) -> Value {
+    match module.types[ty].inner {
+        naga::TypeInner::Scalar { kind, width } => Value::from_texel(texel, kind, None, width),
+        naga::TypeInner::Vector { size, kind, width } => {
+            Value::from_texel(texel, kind, Some(size), width)
+        }
+        _ => shader::zero_value(module, ty),
+    }
+}
+
+fn shade_vertex<'a>(
+    invocation: &mut Invocation<'a, '_>,
+    pipeline: &'a RenderPipelineInner,
+    vertex_buffers: &[Option<BufferBinding>],
+    vertex_index: u32,
+    instance_index: u32,
+) -> Vertex {
+    let module = &pipeline.vertex_stage.module;
+    let mut inputs = |binding: &naga::Binding, ty: Handle<naga::Type>| match *binding {
+        naga::Binding::BuiltIn(naga::BuiltIn::VertexIndex) => {
+            Value::Scalar(Scalar::U32(vertex_index))
+        }
+        naga::Binding::BuiltIn(naga::BuiltIn::InstanceIndex) => {
+            Value::Scalar(Scalar::U32(instance_index))
+        }
+        naga::Binding::Location { location, .. } => {
+            for (slot, layout) in pipeline.vertex_buffers.iter().enumerate() {
+                let attribute = match layout
+                    .attributes
+                    .iter()
+                    .find(|attribute| attribute.shader_location == location)
+                {
+                    Some(attribute) => attribute,
+                    None => continue,
+                };
+                let index = match layout.step_mode {
+                    wgt::VertexStepMode::Vertex => vertex_index,
+                    wgt::VertexStepMode::Instance => instance_index,
+                };
+                let mut data = [0; 32];
+                let data = &mut data[..attribute.format.size() as usize];
+                if let Some(&Some(ref buffer)) = vertex_buffers.get(slot) {
+                    buffer.read(index as u64 * layout.array_stride + attribute.offset, data);
+                }
+                return input_value(module, ty, conv::decode_vertex(attribute.format, data));
+            }
+            shader::zero_value(module, ty)
+        }
+        _ => shader::zero_value(module, ty),
+    };
+    let outputs = invocation
+        .run(pipeline.vertex_stage.entry_point(), &mut inputs)
+        .unwrap_or_default();
+    let mut position = [0.0; 4];
+    for &(ref binding, ref value) in outputs.iter() {
+        if let naga::Binding::BuiltIn(naga::BuiltIn::Position { .. }) = *binding {
+            for (slot, c) in position.iter_mut().zip(value.components()) {
+                *slot = c.as_f32();
+            }
+        }
+    }
+    Vertex { position, outputs }
+}
+
+struct Primitive {
+    indices: ArrayVec<u32, 3>,
+    /// Odd triangles of a strip, whose winding is reversed.
+    odd: bool,
+}
+
+/// Splits a list of vertex indices into primitives.
+fn assemble(topology: wgt::PrimitiveTopology, indices: &[Option<u32>]) -> Vec<Primitive> {
+    use wgt::PrimitiveTopology as Pt;
+
+    let mut primitives = Vec::new();
+    let per_primitive = match topology {
+        Pt::PointList => 1,
+        Pt::LineList | Pt::LineStrip => 2,
+        Pt::TriangleList | Pt::TriangleStrip => 3,
+    };
+    let strip = matches!(topology, Pt::LineStrip | Pt::TriangleStrip);
+    for run in indices.split(|index| index.is_none()) {
+        let run: Vec<u32> = run.iter().flatten().copied().collect();
+        if run.len() < per_primitive {
+            continue;
+        }
+        if strip {
+            for (i, window) in run.windows(per_primitive).enumerate() {
+                primitives.push(Primitive {
+                    indices: window.iter().copied().collect(),
+                    odd: i % 2 == 1,
+                });
+            }
+        } else {
+            for chunk in run.chunks_exact(per_primitive) {
+                primitives.push(Primitive {
+                    indices: chunk.iter().copied().collect(),
+                    odd: false,
+                });
+            }
+        }
+    }
+    primitives
+}
+
+/// A vertex of a clipped primitive, as a combination of the original
+/// vertices.
+#[derive(Clone, Copy)]
+struct ClipVertex {
+    position: [f32; 4],
+    weights: [f32; 3],
+}
+
+impl ClipVertex {
+    fn lerp(&self, other: &Self, t: f32) -> Self {
+        Self {
+            position: [0, 1, 2, 3]
+                .map(|i| self.position[i] + (other.position[i] - self.position[i]) * t),
+            weights: [0, 1, 2].map(|i| self.weights[i] + (other.weights[i] - self.weights[i]) * t),
+        }
+    }
+}
+
+/// Clips a polygon against the plane where `distance` is positive.
+fn clip_polygon(polygon: Vec<ClipVertex>, distance: impl Fn(&[f32; 4]) -> f32) -> Vec<ClipVertex> {
+    let mut result = Vec::with_capacity(polygon.len() + 1);
+    for (i, current) in polygon.iter().enumerate() {
+        let next = &polygon[(i + 1) % polygon.len()];
+        let (d0, d1) = (distance(&current.position), distance(&next.position));
+        if d0 >= 0.0 {
+            result.push(*current);
+        }
+        if (d0 >= 0.0) != (d1 >= 0.0) {
+            result.push(current.lerp(next, d0 / (d0 - d1)));
+        }
+    }
+    result
+}
+
+/// A vertex in framebuffer space.
+#[derive(Clone, Copy)]
+struct ScreenVertex {
+    x: f32,
+    y: f32,
+    z: f32,
+    /// Reciprocal of the clip space `w`.
+    inv_w: f32,
+    weights: [f32; 3],
+}
+
+/// Edge function: twice the signed area of the triangle `a`, `b`, `p`.
+fn edge(a: &ScreenVertex, b: &ScreenVertex, px: f32, py: f32) -> f32 {
+    (b.x - a.x) * (py - a.y) - (b.y - a.y) * (px - a.x)
+}
+
+/// Tie breaking rule for pixels exactly on an edge: an edge shared by two
+/// triangles is walked in opposite directions by them, so exactly one of
+/// them owns the pixels on it.
+fn owns_edge(a: &ScreenVertex, b: &ScreenVertex) -> bool {
+    let (dx, dy) = (b.x - a.x, b.y - a.y);
+    dy > 0.0 || (dy == 0.0 && dx < 0.0)
+}
+
+/// A fragment to be shaded.
+struct Fragment {
+    x: u32,
+    y: u32,
+    z: f32,
+    inv_w: f32,
+    /// Perspective correct weights of the primitive vertices.
+    perspective: [f32; 3],
+    /// Screen space weights of the primitive vertices.
+    linear: [f32; 3],
+    front_facing: bool,
+    primitive_index: u32,
+}
+
+struct Rasterizer<'a, 'p> {
+    pass: &'a RenderPass,
+    pipeline: &'a RenderPipelineInner,
+    state: &'a mut RenderState,
+    fragment_invocation: Option<Invocation<'a, 'p>>,
+    /// Pixel bounds `[x0, y0, x1, y1]`: the viewport, scissor and render
+    /// area combined.
+    bounds: [f32; 4],
+}
+
+impl<'a, 'p> Rasterizer<'a, 'p> {
+    fn bounds(&self) -> [f32; 4] {
+        let viewport = &self.state.viewport;
+        let scissor = &self.state.scissor;
+        [
+            viewport.x.max(scissor.x as f32).max(0.0),
+            viewport.y.max(scissor.y as f32).max(0.0),
+            (viewport.x + viewport.w)
+                .min((scissor.x + scissor.w) as f32)
+                .min(self.pass.extent.width as f32),
+            (viewport.y + viewport.h)
+                .min((scissor.y + scissor.h) as f32)
+                .min(self.pass.extent.height as f32),
+        ]
+    }
+
+    fn clip_planes(&self) -> ArrayVec<fn(&[f32; 4]) -> f32, 3> {
+        let mut planes: ArrayVec<fn(&[f32; 4]) -> f32, 3> = ArrayVec::new();
+        planes.push(|p| p[3] - MIN_W);
+        if !self.pipeline.primitive.unclipped_depth {
+            planes.push(|p| p[2]);
+            planes.push(|p| p[3] - p[2]);
+        }
+        planes
+    }
+
+    fn to_screen(&self, vertex: &ClipVertex) -> ScreenVertex {
+        let viewport = &self.state.viewport;
+        let depth = &self.state.depth_range;
+        let [x, y, z, w] = vertex.position;
+        let inv_w = 1.0 / w;
+        ScreenVertex {
+            x: viewport.x + (x * inv_w + 1.0) * 0.5 * viewport.w,
+            y: viewport.y + (1.0 - y * inv_w) * 0.5 * viewport.h,
+            z: depth.start + z * inv_w * (depth.end - depth.start),
+            inv_w,
+            weights: vertex.weights,
+        }
+    }
+
+    fn point(&mut self, vertex: &Vertex, primitive_index: u32) {
+        let clip = ClipVertex {
+            position: vertex.position,
+            weights: [1.0, 0.0, 0.0],
+        };
+        if self
+            .clip_planes()
+            .iter()
+            .any(|plane| plane(&clip.position) < 0.0)
+        {
+            return;
+        }
+        let v = self.to_screen(&clip);
+        let (x, y) = (v.x.floor(), v.y.floor());
+        if x < self.bounds[0] || y < self.bounds[1] || x >= self.bounds[2] || y >= self.bounds[3] {
+            return;
+        }
+        self.fragment(
+            &[vertex],
+            Fragment {
+                x: x as u32,
+                y: y as u32,
+                z: v.z,
+                inv_w: v.inv_w,
+                perspective: [1.0, 0.0, 0.0],
+                linear: [1.0, 0.0, 0.0],
+                front_facing: true,
+                primitive_index,
+            },
+        );
+    }
+
+    fn line(&mut self, vertices: [&Vertex; 2], primitive_index: u32) {
+        let mut a = ClipVertex {
+            position: vertices[0].position,
+            weights: [1.0, 0.0, 0.0],
+        };
+        let mut b = ClipVertex {
+            position: vertices[1].position,
+            weights: [0.0, 1.0, 0.0],
+        };
+        for plane in self.clip_planes() {
+            let (da, db) = (plane(&a.position), plane(&b.position));
+            match (da >= 0.0, db >= 0.0) {
+                (true, true) => {}
+                (false, false) => return,
+                (true, false) => b = a.lerp(&b, da / (da - db)),
+                (false, true) => a = a.lerp(&b, da / (da - db)),
+            }
+        }
+        let (a, b) = (self.to_screen(&a), self.to_screen(&b));
+        let steps = (b.x - a.x).abs().max((b.y - a.y).abs()).ceil().max(1.0);
+        for step in 0..steps as u32 {
+            let t = (step as f32 + 0.5) / steps;
+            let x = (a.x + (b.x - a.x) * t).floor();
+            let y = (a.y + (b.y - a.y) * t).floor();
+            if x < self.bounds[0]
+                || y < self.bounds[1]
+                || x >= self.bounds[2]
+                || y >= self.bounds[3]
+            {
+                continue;
+            }
+            let linear = [1.0 - t, t];
+            let inv_w = linear[0] * a.inv_w + linear[1] * b.inv_w;
+            let perspective = [linear[0] * a.inv_w / inv_w, linear[1] * b.inv_w / inv_w];
+            let combine =
+                |w: [f32; 2]| [0, 1, 2].map(|i| w[0] * a.weights[i] + w[1] * b.weights[i]);
+            self.fragment(
+                &vertices,
+                Fragment {
+                    x: x as u32,
+                    y: y as u32,
+                    z: linear[0] * a.z + linear[1] * b.z,
+                    inv_w,
+                    perspective: combine(perspective),
+                    linear: combine(linear),
+                    front_facing: true,
+                    primitive_index,
+                },
+            );
+        }
+    }
+
+    fn triangle(&mut self, vertices: [&Vertex; 3], odd: bool, primitive_index: u32) {
+        let mut polygon = vec![
+            ClipVertex {
+                position: vertices[0].position,
+                weights: [1.0, 0.0, 0.0],
+            },
+            ClipVertex {
+                position: vertices[1].position,
+                weights: [0.0, 1.0, 0.0],
+            },
+            ClipVertex {
+                position: vertices[2].position,
+                weights: [0.0, 0.0, 1.0],
+            },
+        ];
+        for plane in self.clip_planes() {
+            polygon = clip_polygon(polygon, plane);
+            if polygon.len() < 3 {
+                return;
+            }
+        }
+        let screen: Vec<ScreenVertex> = polygon.iter().map(|v| self.to_screen(v)).collect();
+
+        // Framebuffer space has y pointing down, so a counter-clockwise
+        // polygon in normalized device coordinates has a negative area.
+        let area: f32 = (0..screen.len())
+            .map(|i| {
+                let (a, b) = (&screen[i], &screen[(i + 1) % screen.len()]);
+                a.x * b.y - b.x * a.y
+            })
+            .sum();
+        if area == 0.0 || !area.is_finite() {
+            return;
+        }
+        let ccw = (area < 0.0) != odd;
+        let front_facing = ccw == (self.pipeline.primitive.front_face == wgt::FrontFace::Ccw);
+        match self.pipeline.primitive.cull_mode {
+            Some(wgt::Face::Front) if front_facing => return,
+            Some(wgt::Face::Back) if !front_facing => return,
+            _ => {}
+        }
+
+        for i in 1..screen.len() - 1 {
+            self.fan_triangle(
+                &vertices,
+                [screen[0], screen[i], screen[i + 1]],
+                front_facing,
+                primitive_index,
+            );
+        }
+    }
+
+    fn depth_bias(&self, v: &[ScreenVertex; 3]) -> f32 {
+        let state = match self.pipeline.depth_stencil {
+            Some(ref state) if state.bias.is_enabled() => state,
+            _ => return 0.0,
+        };
+        // Slope of the depth plane.
+        let (e1, e2) = (
+            [v[1].x - v[0].x, v[1].y - v[0].y, v[1].z - v[0].z],
+            [v[2].x - v[0].x, v[2].y - v[0].y, v[2].z - v[0].z],
+        );
+        let normal_z = e1[0] * e2[1] - e1[1] * e2[0];
+        let (dzdx, dzdy) = if normal_z == 0.0 {
+            (0.0, 0.0)
+        } else {
+            (
+                -(e1[1] * e2[2] - e1[2] * e2[1]) / normal_z,
+                -(e1[2] * e2[0] - e1[0] * e2[2]) / normal_z,
+            )
+        };
+        let unit = match state.format {
+            wgt::TextureFormat::Depth16Unorm => 1.0 / 65536.0,
+            wgt::TextureFormat::Depth32Float | wgt::TextureFormat::Depth32FloatStencil8 => {
+                1.0 / (1 << 23) as f32
+            }
+            _ => 1.0 / (1 << 24) as f32,
+        };
+        let bias =
+            state.bias.constant as f32 * unit + state.bias.slope_scale * dzdx.abs().max(dzdy.abs());
+        if state.bias.clamp > 0.0 {
+            bias.min(state.bias.clamp)
+        } else if state.bias.clamp < 0.0 {
+            bias.max(state.bias.clamp)
+        } else {
+            bias
+        }
+    }
+
+    fn fan_triangle(
+        &mut self,
+        vertices: &[&Vertex; 3],
+        mut v: [ScreenVertex; 3],
+        front_facing: bool,
+        primitive_index: u32,
+    ) {
+        let mut area = edge(&v[0], &v[1], v[2].x, v[2].y);
+        if area < 0.0 {
+            v.swap(1, 2);
+            area = -area;
+        }
+        if area == 0.0 {
+            return;
+        }
+        let bias = self.depth_bias(&v);
+        let min_x = v.iter().map(|v| v.x).fold(f32::INFINITY, f32::min);
+        let min_y = v.iter().map(|v| v.y).fold(f32::INFINITY, f32::min);
+        let max_x = v.iter().map(|v| v.x).fold(f32::NEG_INFINITY, f32::max);
+        let max_y = v.iter().map(|v| v.y).fold(f32::NEG_INFINITY, f32::max);
+        let x0 = min_x.floor().max(self.bounds[0]) as u32;
+        let y0 = min_y.floor().max(self.bounds[1]) as u32;
+        let x1 = max_x.ceil().min(self.bounds[2]).max(0.0) as u32;
+        let y1 = max_y.ceil().min(self.bounds[3]).max(0.0) as u32;
+        let edges = [(1, 2), (2, 0), (0, 1)];
+        let owned = edges.map(|(a, b)| owns_edge(&v[a], &v[b]));
+
+        for y in y0..y1 {
+            for x in x0..x1 {
+                let (px, py) = (x as f32 + 0.5, y as f32 + 0.5);
+                let mut barycentric = [0.0; 3];
+                let mut inside = true;
+                for (i, &(a, b)) in edges.iter().enumerate() {
+                    let e = edge(&v[a], &v[b], px, py);
+                    if e < 0.0 || (e == 0.0 && !owned[i]) {
+                        inside = false;
+                        break;
+                    }
+                    barycentric[i] = e / area;
+                }
+                if !inside {
+                    continue;
+                }
+                let inv_w: f32 = (0..3).map(|i| barycentric[i] * v[i].inv_w).sum();
+                let perspective = [0, 1, 2].map(|i| barycentric[i] * v[i].inv_w / inv_w);
+                let combine = |w: [f32; 3]| {
+                    [0, 1, 2].map(|j| (0..3).map(|i| w[i] * v[i].weights[j]).sum::<f32>())
+                };
+                let z: f32 = (0..3).map(|i| barycentric[i] * v[i].z).sum();
+                self.fragment(
+                    vertices,
+                    Fragment {
+                        x,
+                        y,
+                        z: z + bias,
+                        inv_w,
+                        perspective: combine(perspective),
+                        linear: combine(barycentric),
+                        front_facing,
+                        primitive_index,
+                    },
+                );
+            }
+        }
+    }
+
+    /// Shades a fragment and writes it to the attachments.
+    fn fragment(&mut self, vertices: &[&Vertex], fragment: Fragment) {
+        let depth_range = &self.state.depth_range;
+        let (depth_min, depth_max) = (
+            depth_range.start.min(depth_range.end),
+            depth_range.start.max(depth_range.end),
+        );
+        let mut depth = fragment.z.clamp(depth_min, depth_max);
+
+        let pipeline = self.pipeline;
+        let outputs = match (
+            self.fragment_invocation.as_mut(),
+            pipeline.fragment_stage.as_ref(),
+        ) {
+            (Some(invocation), Some(stage)) => {
+                let module = &stage.module;
+                let mut inputs = |binding: &naga::Binding, ty: Handle<naga::Type>| match *binding {
+                    naga::Binding::BuiltIn(naga::BuiltIn::Position { .. }) => Value::Vector(
+                        [
+                            fragment.x as f32 + 0.5,
+                            fragment.y as f32 + 0.5,
+                            depth,
+                            fragment.inv_w,
+                        ]
+                        .iter()
+                        .map(|&c| Scalar::F32(c))
+                        .collect(),
+                    ),
+                    naga::Binding::BuiltIn(naga::BuiltIn::FrontFacing) => {
+                        Value::Scalar(Scalar::Bool(fragment.front_facing))
+                    }
+                    naga::Binding::BuiltIn(naga::BuiltIn::PrimitiveIndex) => {
+                        Value::Scalar(Scalar::U32(fragment.primitive_index))
+                    }
+                    naga::Binding::BuiltIn(naga::BuiltIn::SampleIndex) => {
+                        Value::Scalar(Scalar::U32(0))
+                    }
+                    naga::Binding::BuiltIn(naga::BuiltIn::SampleMask) => {
+                        Value::Scalar(Scalar::U32(!0))
+                    }
+                    naga::Binding::Location {
+                        location,
+                        interpolation,
+                        ..
+                    } => {
+                        let weights = match interpolation {
+                            Some(naga::Interpolation::Flat) => [1.0, 0.0, 0.0],
+                            Some(naga::Interpolation::Linear) => fragment.linear,
+                            _ => fragment.perspective,
+                        };
+                        interpolate(vertices, location, &weights)
+                            .unwrap_or_else(|| shader::zero_value(module, ty))
+                    }
+                    _ => shader::zero_value(module, ty),
+                };
+                match invocation.run(stage.entry_point(), &mut inputs) {
+                    Some(outputs) => outputs,
+                    None => return,
+                }
+            }
+            _ => Vec::new(),
+        };
+        for &(ref binding, ref value) in outputs.iter() {
+            if let naga::Binding::BuiltIn(naga::BuiltIn::FragDepth) = *binding {
+                depth = value.scalar().as_f32().clamp(depth_min, depth_max);
+            }
+        }
+
+        if !self.depth_stencil_test(fragment.x, fragment.y, depth, fragment.front_facing) {
+            return;
+        }
+        self.state.samples_passed += 1;
+
+        for (location, target) in self.pass.color_targets.iter().enumerate() {
+            let target = match *target {
+                Some(ref target) => target,
+                None => continue,
+            };
+            let state = match self.pipeline.color_targets.get(location) {
+                Some(&Some(ref state)) => state,
+                _ => continue,
+            };
+            let output = |second_blend_source: bool| {
+                outputs
+                    .iter()
+                    .find_map(|&(ref binding, ref value)| match *binding {
+                        naga::Binding::Location {
+                            location: l,
+                            second_blend_source: s,
+                            ..
+                        } if l as usize == location && s == second_blend_source => Some(value),
+                        _ => None,
+                    })
+            };
+            let value = match output(false) {
+                Some(value) => value,
+                None => continue,
+            };
+            let src1 = output(true).map_or([0.0; 4], |value| value.to_texel().to_float());
+            self.write_color(
+                target,
+                state,
+                fragment.x,
+                fragment.y,
+                value.to_texel(),
+                src1,
+            );
+        }
+    }
+
+    /// Runs the depth and stencil tests and updates the depth/stencil
+    /// attachment. Returns whether the fragment passed.
+    fn depth_stencil_test(&mut self, x: u32, y: u32, depth: f32, front_facing: bool) -> bool {
+        let (target, state) = match (&self.pass.depth_stencil, &self.pipeline.depth_stencil) {
+            (&Some(ref target), &Some(ref state)) => (target, state),
+            _ => return true,
+        };
+        let view = &target.view;
+        let format = view.texture.format;
+        let mut data = [0u8; 4];
+
+        let stencil = if format.has_stencil_aspect() && state.stencil.is_enabled() {
+            let plane = view.texture.plane(crate::FormatAspects::STENCIL);
+            read_texel(view, plane, x, y, &mut data);
+            let face = if front_facing {
+                &state.stencil.front
+            } else {
+                &state.stencil.back
+            };
+            Some((plane, face, data[0]))
+        } else {
+            None
+        };
+        let reference = self.state.stencil_reference as u8;
+        let update_stencil = |op: wgt::StencilOperation| {
+            if let Some((plane, _, value)) = stencil {
+                let write_mask = state.stencil.write_mask as u8;
+                let new = conv::stencil_op(op, value, reference);
+                let new = (value & !write_mask) | (new & write_mask);
+                write_texel(view, plane, x, y, &[new]);
+            }
+        };
+
+        if let Some((_, face, value)) = stencil {
+            let read_mask = state.stencil.read_mask as u8;
+            let pass = conv::compare(
+                face.compare,
+                (reference & read_mask) as f32,
+                (value & read_mask) as f32,
+            );
+            if !pass {
+                update_stencil(face.fail_op);
+                return false;
+            }
+        }
+
+        if format.has_depth_aspect() {
+            let plane = view.texture.plane(crate::FormatAspects::DEPTH);
+            read_texel(view, plane, x, y, &mut data);
+            let stored = conv::decode_depth(format, &data);
+            if !conv::compare(state.depth_compare, depth, stored) {
+                if let Some((_, face, _)) = stencil {
+                    update_stencil(face.depth_fail_op);
+                }
+                return false;
+            }
+            if state.depth_write_enabled {
+                conv::encode_depth(format, depth, &mut data);
+                write_texel(view, plane, x, y, &data);
+            }
+        }
+        if let Some((_, face, _)) = stencil {
+            update_stencil(face.pass_op);
+        }
+        true
+    }
+
+    fn write_color(
+        &self,
+        target: &ColorTarget,
+        state: &wgt::ColorTargetState,
+        x: u32,
+        y: u32,
+        texel: Texel,
+        src1: [f32; 4],
+    ) {
+        let view = &target.view;
+        let format = view.format;
+        let plane = view.texture.plane(crate::FormatAspects::COLOR);
+        let mut data = [0u8; 16];
+        read_texel(view, plane, x, y, &mut data);
+        let dst = conv::decode_color(format, &data);
+
+        let texel = match (texel, dst) {
+            (Texel::Float(src), Texel::Float(dst)) => {
+                let src = conv::clamp_to_format(format, src);
+                let color = match state.blend {
+                    Some(ref blend) => conv::clamp_to_format(
+                        format,
+                        conv::blend(
+                            blend,
+                            &conv::BlendInputs {
+                                src,
+                                src1,
+                                dst,
+                                constant: self.state.blend_constant,
+                            },
+                        ),
+                    ),
+                    None => src,
+                };
+                Texel::Float(mask(state.write_mask, color, dst))
+            }
+            (Texel::Uint(src), Texel::Uint(dst)) => Texel::Uint(mask(state.write_mask, src, dst)),
+            (Texel::Sint(src), Texel::Sint(dst)) => Texel::Sint(mask(state.write_mask, src, dst)),
+            (src, _) => src,
+        };
+        conv::encode_color(format, texel, &mut data);
+        write_texel(view, plane, x, y, &data);
+    }
+}
+
+/// Keeps the channels of `dst` that aren't written.
+fn mask<T: Copy>(write_mask: wgt::ColorWrites, src: [T; 4], dst: [T; 4]) -> [T; 4] {
+    let channels = [
+        wgt::ColorWrites::RED,
+        wgt::ColorWrites::GREEN,
+        wgt::ColorWrites::BLUE,
+        wgt::ColorWrites::ALPHA,
+    ];
+    [0, 1, 2, 3].map(|i| {
+        if write_mask.contains(channels[i]) {
+            src[i]
+        } else {
+            dst[i]
+        }
+    })
+}
+
+/// Interpolates the vertex output at `location`.
+fn interpolate(vertices: &[&Vertex], location: u32, weights: &[f32; 3]) -> Option<Value> {
+    let values = vertices
+        .iter()
+        .map(|vertex| {
+            vertex
+                .outputs
+                .iter()
+                .find_map(|&(ref binding, ref value)| match *binding {
+                    naga::Binding::Location { location: l, .. } if l == location => Some(value),
+                    _ => None,
+                })
+        })
+        .collect::<Option<ArrayVec<&Value, 3>>>()?;
+    let components: ArrayVec<shader::Vector, 3> =
+        values.iter().map(|value| value.components()).collect();
+    let interpolated: shader::Vector = components[0]
+        .iter()
+        .enumerate()
+        .map(|(c, &first)| match first {
+            Scalar::F32(_) | Scalar::F64(_) => first.with_f64(
+                components
+                    .iter()
+                    .zip(weights)
+                    .map(|(vertex, &weight)| vertex[c].as_f64() * weight as f64)
+                    .sum(),
+            ),
+            other => other,
+        })
+        .collect();
+    Some(match *values[0] {
+        Value::Scalar(_) => Value::Scalar(interpolated[0]),
+        _ => Value::Vector(interpolated),
+    })
+}
+
+#[test]
+fn assemble_strips() {
+    let indices = [
+        Some(0),
+        Some(1),
+        Some(2),
+        Some(3),
+        None,
+        Some(4),
+        Some(5),
+        Some(6),
+    ];
+    let triangles = assemble(wgt::PrimitiveTopology::TriangleStrip, &indices);
+    let triangles: Vec<(Vec<u32>, bool)> = triangles
+        .into_iter()
+        .map(|primitive| (primitive.indices.to_vec(), primitive.odd))
+        .collect();
+    assert_eq!(
+        triangles,
+        [
+            (vec![0, 1, 2], false),
+            (vec![1, 2, 3], true),
+            (vec![4, 5, 6], false),
+        ]
+    );
+    assert_eq!(
+        assemble(wgt::PrimitiveTopology::LineStrip, &indices).len(),
+        5
+    );
+}
@@ -0,0 +1,1582 @@
+//! An interpreter for naga IR.
+//!
+//! Values are kept in a tree shaped [`Value`] representation. Buffers are
+//! accessed through typed pointers carrying a byte offset into the binding,
+//! following the layout rules of the naga types. Everything else that
+//! lives in memory (function locals, private and workgroup variables) is a
+//! [`Value`] tree addressed by a path of indices.
+//!
+//! All accesses are bounds checked: reads outside of a binding return zero
+//! and writes outside of it are dropped.
+
+use std::sync::Arc;
+
+use arrayvec::ArrayVec;
+use naga::{Handle, ScalarKind, VectorSize};
+use parking_lot::{Mutex, MutexGuard};
+
+use super::{conv::Texel, image, math, Memory, Resource, Sampler, TextureView};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(super) enum Scalar {
+    Bool(bool),
+    I32(i32),
+    U32(u32),
+    F32(f32),
+    F64(f64),
+}
+
+impl Scalar {
+    pub(super) fn zero(kind: ScalarKind, width: naga::Bytes) -> Self {
+        match kind {
+            ScalarKind::Bool => Self::Bool(false),
+            ScalarKind::Sint => Self::I32(0),
+            ScalarKind::Uint => Self::U32(0),
+            ScalarKind::Float if width == 8 => Self::F64(0.0),
+            ScalarKind::Float => Self::F32(0.0),
+        }
+    }
+
+    pub(super) fn kind(self) -> ScalarKind {
+        match self {
+            Self::Bool(_) => ScalarKind::Bool,
+            Self::I32(_) => ScalarKind::Sint,
+            Self::U32(_) => ScalarKind::Uint,
+            Self::F32(_) | Self::F64(_) => ScalarKind::Float,
+        }
+    }
+
+    fn width(self) -> naga::Bytes {
+        match self {
+            Self::F64(_) => 8,
+            _ => 4,
+        }
+    }
+
+    pub(super) fn as_f64(self) -> f64 {
+        match self {
+            Self::Bool(value) => value as u8 as f64,
+            Self::I32(value) => value as f64,
+            Self::U32(value) => value as f64,
+            Self::F32(value) => value as f64,
+            Self::F64(value) => value,
+        }
+    }
+
+    pub(super) fn as_f32(self) -> f32 {
+        match self {
+            Self::F32(value) => value,
+            other => other.as_f64() as f32,
+        }
+    }
+
+    /// Interprets an integer as an index, mapping negative values past the
+    /// end of anything.
+    pub(super) fn as_index(self) -> u32 {
+        match self {
+            Self::I32(value) => u32::try_from(value).unwrap_or(u32::MAX),
+            Self::U32(value) => value,
+            other => other.as_f64() as u32,
+        }
+    }
+
+    pub(super) fn as_i32(self) -> i32 {
+        match self {
+            Self::I32(value) => value,
+            Self::U32(value) => value as i32,
+            other => other.as_f64() as i32,
+        }
+    }
+
+    pub(super) fn as_bool(self) -> bool {
+        match self {
+            Self::Bool(value) => value,
+            other => other.as_f64() != 0.0,
+        }
+    }
+
+    /// Replaces the value of a float, keeping its width.
+    pub(super) fn with_f64(self, value: f64) -> Self {
+        match self {
+            Self::F64(_) => Self::F64(value),
+            _ => Self::F32(value as f32),
+        }
+    }
+
+    /// Value conversion, as done by WGSL type constructors.
+    pub(super) fn convert(self, kind: ScalarKind, width: naga::Bytes) -> Self {
+        match kind {
+            ScalarKind::Bool => Self::Bool(self.as_bool()),
+            ScalarKind::Sint => Self::I32(self.as_i32()),
+            ScalarKind::Uint => Self::U32(match self {
+                Self::I32(value) => value as u32,
+                Self::U32(value) => value,
+                other => other.as_f64() as u32,
+            }),
+            ScalarKind::Float if width == 8 => Self::F64(self.as_f64()),
+            ScalarKind::Float => Self::F32(match self {
+                Self::I32(value) => value as f32,
+                Self::U32(value) => value as f32,
+                other => other.as_f32(),
+            }),
+        }
+    }
+
+    fn bits(self) -> u64 {
+        match self {
+            Self::Bool(value) => value as u64,
+            Self::I32(value) => value as u32 as u64,
+            Self::U32(value) => value as u64,
+            Self::F32(value) => value.to_bits() as u64,
+            Self::F64(value) => value.to_bits(),
+        }
+    }
+
+    fn from_bits(kind: ScalarKind, width: naga::Bytes, bits: u64) -> Self {
+        match kind {
+            ScalarKind::Bool => Self::Bool(bits != 0),
+            ScalarKind::Sint => Self::I32(bits as u32 as i32),
+            ScalarKind::Uint => Self::U32(bits as u32),
+            ScalarKind::Float if width == 8 => Self::F64(f64::from_bits(bits)),
+            ScalarKind::Float => Self::F32(f32::from_bits(bits as u32)),
+        }
+    }
+
+    /// Reinterprets the bits of a value, as done by `bitcast`.
+    pub(super) fn bitcast(self, kind: ScalarKind) -> Self {
+        Self::from_bits(kind, self.width(), self.bits())
+    }
+}
+
+pub(super) type Vector = ArrayVec<Scalar, 4>;
+
+#[derive(Clone, Debug)]
+pub(super) enum Value {
+    Scalar(Scalar),
+    Vector(Vector),
+    /// Columns of a matrix.
+    Matrix(Vec<Vector>),
+    /// Arrays and structures.
+    Composite(Vec<Value>),
+    Pointer(Pointer),
+    /// An image or sampler global variable.
+    Handle(Handle<naga::GlobalVariable>),
+}
+
+impl Value {
+    pub(super) const ZERO: Self = Self::Scalar(Scalar::U32(0));
+
+    /// The first component of a scalar or vector.
+    pub(super) fn scalar(&self) -> Scalar {
+        match *self {
+            Self::Scalar(scalar) => scalar,
+            Self::Vector(ref vector) => vector[0],
+            _ => Scalar::U32(0),
+        }
+    }
+
+    /// The components of a scalar or vector.
+    pub(super) fn components(&self) -> Vector {
+        match *self {
+            Self::Scalar(scalar) => [scalar].into_iter().collect(),
+            Self::Vector(ref vector) => vector.clone(),
+            _ => Vector::new(),
+        }
+    }
+
+    pub(super) fn from_texel(
+        texel: Texel,
+        kind: ScalarKind,
+        size: Option<VectorSize>,
+        width: naga::Bytes,
+    ) -> Self {
+        let components: Vector = match texel {
+            Texel::Float(v) => v.iter().map(|&c| Scalar::F32(c)).collect(),
+            Texel::Uint(v) => v.iter().map(|&c| Scalar::U32(c)).collect(),
+            Texel::Sint(v) => v.iter().map(|&c| Scalar::I32(c)).collect(),
+        };
+        let convert = |scalar: Scalar| match (scalar.kind(), kind) {
+            // Integer data can't be used as float data and vice versa, so
+            // mismatches only happen with the bits of packed formats.
+            (ScalarKind::Uint, ScalarKind::Sint) | (ScalarKind::Sint, ScalarKind::Uint) => {
+                scalar.bitcast(kind)
+            }
+            _ => scalar.convert(kind, width),
+        };
+        match size {
+            None => Self::Scalar(convert(components[0])),
+            Some(size) => Self::Vector(
+                components
+                    .into_iter()
+                    .take(size as usize)
+                    .map(convert)
+                    .collect(),
+            ),
+        }
+    }
+
+    pub(super) fn to_texel(&self) -> Texel {
+        let components = self.components();
+        let first = components.first().copied().unwrap_or(Scalar::F32(0.0));
+        let mut texel = match first.kind() {
+            ScalarKind::Uint => Texel::Uint([0, 0, 0, 1]),
+            ScalarKind::Sint => Texel::Sint([0, 0, 0, 1]),
+            _ => Texel::Float([0.0, 0.0, 0.0, 1.0]),
+        };
+        for (i, &scalar) in components.iter().enumerate() {
+            match texel {
+                Texel::Float(ref mut v) => v[i] = scalar.as_f32(),
+                Texel::Uint(ref mut v) => v[i] = scalar.as_index(),
+                Texel::Sint(ref mut v) => v[i] = scalar.as_i32(),
+            }
+        }
+        texel
+    }
+
+    /// Extracts the element `index` of a composite value, clamping the index.
+    fn element(&self, index: u32) -> Self {
+        let clamp = |len: usize| (index as usize).min(len.saturating_sub(1));
+        match *self {
+            Self::Vector(ref vector) => Self::Scalar(vector[clamp(vector.len())]),
+            Self::Matrix(ref columns) => Self::Vector(columns[clamp(columns.len())].clone()),
+            Self::Composite(ref elements) if !elements.is_empty() => {
+                elements[clamp(elements.len())].clone()
+            }
+            _ => Self::ZERO,
+        }
+    }
+
+    /// Replaces the element at `path`.
+    fn set(&mut self, path: &[u32], value: Self) {
+        let (&index, rest) = match path.split_first() {
+            Some(split) => split,
+            None => {
+                *self = value;
+                return;
+            }
+        };
+        let clamp = |len: usize| (index as usize).min(len.saturating_sub(1));
+        match *self {
+            Self::Vector(ref mut vector) => {
+                let i = clamp(vector.len());
+                vector[i] = value.scalar();
+            }
+            Self::Matrix(ref mut columns) => {
+                let i = clamp(columns.len());
+                match rest.first() {
+                    Some(&row) => {
+                        let row = (row as usize).min(columns[i].len() - 1);
+                        columns[i][row] = value.scalar();
+                    }
+                    None => columns[i] = value.components(),
+                }
+            }
+            Self::Composite(ref mut elements) if !elements.is_empty() => {
+                let i = clamp(elements.len());
+                elements[i].set(rest, value);
+            }
+            _ => {}
+        }
+    }
+
+    fn get(&self, path: &[u32]) -> Self {
+        match path.split_first() {
+            Some((&index, rest)) => self.element(index).get(rest),
+            None => self.clone(),
+        }
+    }
+}
+
+/// How to interpret the memory a buffer pointer points to.
+#[derive(Clone, Copy, Debug)]
+pub(super) enum MemoryType {
+    Type(Handle<naga::Type>),
+    Scalar(ScalarKind, naga::Bytes),
+    Vector(VectorSize, ScalarKind, naga::Bytes),
+}
+
+#[derive(Clone, Debug)]
+pub(super) enum Pointer {
+    Local {
+        frame: usize,
+        variable: Handle<naga::LocalVariable>,
+        path: Vec<u32>,
+    },
+    Private {
+        variable: Handle<naga::GlobalVariable>,
+        path: Vec<u32>,
+    },
+    WorkGroup {
+        variable: Handle<naga::GlobalVariable>,
+        path: Vec<u32>,
+    },
+    Buffer {
+        variable: Handle<naga::GlobalVariable>,
+        offset: u64,
+        ty: MemoryType,
+    },
+}
+
+/// A bind group set on an encoder, with its dynamic offsets.
+#[derive(Debug)]
+pub(super) struct BoundGroup {
+    pub entries: Arc<Vec<(u32, bool, Resource)>>,
+    pub dynamic_offsets: Vec<wgt::DynamicOffset>,
+}
+
+/// The resource a global variable is bound to.
+enum Bound<'a> {
+    None,
+    Buffer {
+        memory: &'a Memory,
+        offset: u64,
+        size: u64,
+    },
+    Texture(&'a TextureView),
+    Sampler(&'a Sampler),
+}
+
+/// A shader module with the resources of a draw or dispatch.
+pub(super) struct Program<'a> {
+    module: &'a naga::Module,
+    bound: Vec<Bound<'a>>,
+}
+
+impl<'a> Program<'a> {
+    pub(super) fn new(module: &'a naga::Module, groups: &'a [Option<BoundGroup>]) -> Self {
+        let bound = module
+            .global_variables
+            .iter()
+            .map(|(_, var)| {
+                let binding = match var.binding {
+                    Some(ref binding) => binding,
+                    None => return Bound::None,
+                };
+                let group = match groups.get(binding.group as usize) {
+                    Some(&Some(ref group)) => group,
+                    _ => return Bound::None,
+                };
+                let index = match group
+                    .entries
+                    .binary_search_by_key(&binding.binding, |&(binding, _, _)| binding)
+                {
+                    Ok(index) => index,
+                    Err(_) => return Bound::None,
+                };
+                match group.entries[index] {
+                    (
+                        _,
+                        has_dynamic_offset,
+                        Resource::Buffer {
+                            ref memory,
+                            offset,
+                            size,
+                        },
+                    ) => {
+                        let dynamic_offset = if has_dynamic_offset {
+                            let dynamic_index = group.entries[..index]
+                                .iter()
+                                .filter(|&&(_, dynamic, _)| dynamic)
+                                .count();
+                            group
+                                .dynamic_offsets
+                                .get(dynamic_index)
+                                .copied()
+                                .unwrap_or(0)
+                        } else {
+                            0
+                        };
+                        Bound::Buffer {
+                            memory,
+                            offset: offset + dynamic_offset as u64,
+                            size,
+                        }
+                    }
+                    (_, _, Resource::Sampler(ref sampler)) => Bound::Sampler(sampler),
+                    (_, _, Resource::Texture(ref view)) => Bound::Texture(view),
+                }
+            })
+            .collect();
+        Self { module, bound }
+    }
+
+    /// Reads a scalar from a buffer binding, or zero if out of bounds.
+    fn read_scalar(
+        &self,
+        variable: Handle<naga::GlobalVariable>,
+        offset: u64,
+        kind: ScalarKind,
+        width: naga::Bytes,
+    ) -> Scalar {
+        let mut bytes = [0; 8];
+        let bytes = &mut bytes[..width as usize];
+        if let Bound::Buffer {
+            memory,
+            offset: base,
+            size,
+        } = self.bound[variable.index()]
+        {
+            if offset + width as u64 <= size {
+                memory.read(base + offset, bytes);
+            }
+        }
+        let mut bits = [0; 8];
+        bits[..bytes.len()].copy_from_slice(bytes);
+        Scalar::from_bits(kind, width, u64::from_le_bytes(bits))
+    }
+
+    fn write_scalar(&self, variable: Handle<naga::GlobalVariable>, offset: u64, scalar: Scalar) {
+        if let Bound::Buffer {
+            memory,
+            offset: base,
+            size,
+        } = self.bound[variable.index()]
+        {
+            let width = scalar.width() as u64;
+            if offset + width <= size {
+                memory.write(
+                    base + offset,
+                    &scalar.bits().to_le_bytes()[..width as usize],
+                );
+            }
+        }
+    }
+
+    fn binding_size(&self, variable: Handle<naga::GlobalVariable>) -> u64 {
+        match self.bound[variable.index()] {
+            Bound::Buffer { size, .. } => size,
+            _ => 0,
+        }
+    }
+
+    fn read(&self, variable: Handle<naga::GlobalVariable>, offset: u64, ty: MemoryType) -> Value {
+        match ty {
+            MemoryType::Scalar(kind, width) => {
+                Value::Scalar(self.read_scalar(variable, offset, kind, width))
+            }
+            MemoryType::Vector(size, kind, width) => Value::Vector(
+                (0..size as u64)
+                    .map(|i| self.read_scalar(variable, offset + i * width as u64, kind, width))
+                    .collect(),
+            ),
+            MemoryType::Type(ty) => match self.module.types[ty].inner {
+                naga::TypeInner::Scalar { kind, width }
+                | naga::TypeInner::Atomic { kind, width } => {
+                    self.read(variable, offset, MemoryType::Scalar(kind, width))
+                }
+                naga::TypeInner::Vector { size, kind, width } => {
+                    self.read(variable, offset, MemoryType::Vector(size, kind, width))
+                }
+                naga::TypeInner::Matrix {
+                    columns,
+                    rows,
+                    width,
+                } => Value::Matrix(
+                    (0..columns as u64)
+                        .map(|i| {
+                            let column = offset + i * column_stride(rows, width);
+                            self.read(
+                                variable,
+                                column,
+                                MemoryType::Vector(rows, ScalarKind::Float, width),
+                            )
+                            .components()
+                        })
+                        .collect(),
+                ),
+                naga::TypeInner::Array { base, size, stride } => {
+                    let count = match size {
+                        naga::ArraySize::Constant(count) => count.get() as u64,
+                        naga::ArraySize::Dynamic => {
+                            self.binding_size(variable).saturating_sub(offset) / stride as u64
+                        }
+                    };
+                    Value::Composite(
+                        (0..count)
+                            .map(|i| {
+                                self.read(
+                                    variable,
+                                    offset + i * stride as u64,
+                                    MemoryType::Type(base),
+                                )
+                            })
+                            .collect(),
+                    )
+                }
+                naga::TypeInner::Struct { ref members, .. } => Value::Composite(
+                    members
+                        .iter()
+                        .map(|member| {
+                            self.read(
+                                variable,
+                                offset + member.offset as u64,
+                                MemoryType::Type(member.ty),
+                            )
+                        })
+                        .collect(),
+                ),
+                _ => Value::ZERO,
+            },
+        }
+    }
+
+    fn write(
+        &self,
+        variable: Handle<naga::GlobalVariable>,
+        offset: u64,
+        ty: MemoryType,
+        value: &Value,
+    ) {
+        match ty {
+            MemoryType::Scalar(..) => self.write_scalar(variable, offset, value.scalar()),
+            MemoryType::Vector(_, _, width) => {
+                for (i, &scalar) in value.components().iter().enumerate() {
+                    self.write_scalar(variable, offset + i as u64 * width as u64, scalar);
+                }
+            }
+            MemoryType::Type(ty) => match self.module.types[ty].inner {
+                naga::TypeInner::Scalar { kind, width }
+                | naga::TypeInner::Atomic { kind, width } => {
+                    self.write(variable, offset, MemoryType::Scalar(kind, width), value)
+                }
+                naga::TypeInner::Vector { size, kind, width } => self.write(
+                    variable,
+                    offset,
+                    MemoryType::Vector(size, kind, width),
+                    value,
+                ),
+                naga::TypeInner::Matrix { rows, width, .. } => {
+                    if let Value::Matrix(ref columns) = *value {
+                        for (i, column) in columns.iter().enumerate() {
+                            let vector = MemoryType::Vector(rows, ScalarKind::Float, width);
+                            self.write(
+                                variable,
+                                offset + i as u64 * column_stride(rows, width),
+                                vector,
+                                &Value::Vector(column.clone()),
+                            );
+                        }
+                    }
+                }
+                naga::TypeInner::Array { base, stride, .. } => {
+                    if let Value::Composite(ref elements) = *value {
+                        for (i, element) in elements.iter().enumerate() {
+                            let element_offset = offset + i as u64 * stride as u64;
+                            self.write(variable, element_offset, MemoryType::Type(base), element);
+                        }
+                    }
+                }
+                naga::TypeInner::Struct { ref members, .. } => {
+                    if let Value::Composite(ref elements) = *value {
+                        for (member, element) in members.iter().zip(elements) {
+                            let member_offset = offset + member.offset as u64;
+                            self.write(
+                                variable,
+                                member_offset,
+                                MemoryType::Type(member.ty),
+                                element,
+                            );
+                        }
+                    }
+                }
+                _ => {}
+            },
+        }
+    }
+
+    fn texture(&self, value: &Value) -> Option<&'a TextureView> {
+        match *value {
+            Value::Handle(variable) => match self.bound[variable.index()] {
+                Bound::Texture(view) => Some(view),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    fn sampler(&self, value: &Value) -> Option<&'a Sampler> {
+        match *value {
+            Value::Handle(variable) => match self.bound[variable.index()] {
+                Bound::Sampler(sampler) => Some(sampler),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+}
+
+/// Distance between the columns of a matrix in host-shareable memory.
+fn column_stride(rows: VectorSize, width: naga::Bytes) -> u64 {
+    match rows {
+        VectorSize::Bi => 2 * width as u64,
+        VectorSize::Tri | VectorSize::Quad => 4 * width as u64,
+    }
+}
+
+/// Builds the zero value of a type.
+pub(super) fn zero_value(module: &naga::Module, ty: Handle<naga::Type>) -> Value {
+    match module.types[ty].inner {
+        naga::TypeInner::Scalar { kind, width } | naga::TypeInner::Atomic { kind, width } => {
+            Value::Scalar(Scalar::zero(kind, width))
+        }
+        naga::TypeInner::Vector { size, kind, width } => Value::Vector(
+            (0..size as usize)
+                .map(|_| Scalar::zero(kind, width))
+                .collect(),
+        ),
+        naga::TypeInner::Matrix {
+            columns,
+            rows,
+            width,
+        } => Value::Matrix(
+            (0..columns as usize)
+                .map(|_| {
+                    (0..rows as usize)
+                        .map(|_| Scalar::zero(ScalarKind::Float, width))
+                        .collect()
+                })
+                .collect(),
+        ),
+        naga::TypeInner::Array {
+            base,
+            size: naga::ArraySize::Constant(count),
+            ..
+        } => Value::Composite(vec![zero_value(module, base); count.get() as usize]),
+        naga::TypeInner::Struct { ref members, .. } => Value::Composite(
+            members
+                .iter()
+                .map(|member| zero_value(module, member.ty))
+                .collect(),
+        ),
+        _ => Value::Composite(Vec::new()),
+    }
+}
+
+fn compose(module: &naga::Module, ty: Handle<naga::Type>, components: Vec<Value>) -> Value {
+    match module.types[ty].inner {
+        naga::TypeInner::Vector { .. } => Value::Vector(
+            components
+                .iter()
+                .flat_map(|component| component.components())
+                .collect(),
+        ),
+        naga::TypeInner::Matrix { rows, .. } => {
+            if components.iter().all(|c| matches!(*c, Value::Vector(_))) {
+                Value::Matrix(components.iter().map(Value::components).collect())
+            } else {
+                let scalars: Vec<_> = components.iter().map(Value::scalar).collect();
+                Value::Matrix(
+                    scalars
+                        .chunks(rows as usize)
+                        .map(|column| column.iter().copied().collect())
+                        .collect(),
+                )
+            }
+        }
+        _ => Value::Composite(components),
+    }
+}
+
+fn splat(size: VectorSize, value: &Value) -> Value {
+    Value::Vector((0..size as usize).map(|_| value.scalar()).collect())
+}
+
+fn literal(literal: naga::Literal) -> Scalar {
+    match literal {
+        naga::Literal::F64(value) => Scalar::F64(value),
+        naga::Literal::F32(value) => Scalar::F32(value),
+        naga::Literal::U32(value) => Scalar::U32(value),
+        naga::Literal::I32(value) => Scalar::I32(value),
+        naga::Literal::Bool(value) => Scalar::Bool(value),
+    }
+}
+
+/// Evaluates an expression of `module.const_expressions`.
+pub(super) fn const_value(module: &naga::Module, expr: Handle<naga::Expression>) -> Value {
+    match module.const_expressions[expr] {
+        naga::Expression::Literal(value) => Value::Scalar(literal(value)),
+        naga::Expression::Constant(constant) => {
+            const_value(module, module.constants[constant].init)
+        }
+        naga::Expression::ZeroValue(ty) => zero_value(module, ty),
+        naga::Expression::Compose { ty, ref components } => compose(
+            module,
+            ty,
+            components
+                .iter()
+                .map(|&component| const_value(module, component))
+                .collect(),
+        ),
+        naga::Expression::Splat { size, value } => splat(size, &const_value(module, value)),
+        _ => Value::ZERO,
+    }
+}
+
+/// Returns true if any function of the module synchronizes a workgroup.
+pub(super) fn uses_barriers(module: &naga::Module) -> bool {
+    fn block_uses_barriers(block: &naga::Block) -> bool {
+        block.iter().any(|statement| match *statement {
+            naga::Statement::Barrier(_) | naga::Statement::WorkGroupUniformLoad { .. } => true,
+            naga::Statement::Block(ref block) => block_uses_barriers(block),
+            naga::Statement::If {
+                ref accept,
+                ref reject,
+                ..
+            } => block_uses_barriers(accept) || block_uses_barriers(reject),
+            naga::Statement::Switch { ref cases, .. } => {
+                cases.iter().any(|case| block_uses_barriers(&case.body))
+            }
+            naga::Statement::Loop {
+                ref body,
+                ref continuing,
+                ..
+            } => block_uses_barriers(body) || block_uses_barriers(continuing),
+            _ => false,
+        })
+    }
+
+    module
+        .functions
+        .iter()
+        .map(|(_, function)| function)
+        .chain(module.entry_points.iter().map(|ep| &ep.function))
+        .any(|function| block_uses_barriers(&function.body))
+}
+
+/// Workgroup variables, shared by the invocations of a workgroup.
+pub(super) struct Workgroup {
+    variables: Vec<Option<Value>>,
+}
+
+impl Workgroup {
+    pub(super) fn new(module: &naga::Module) -> Mutex<Self> {
+        Mutex::new(Self {
+            variables: vec![None; module.global_variables.len()],
+        })
+    }
+}
+
+/// Built-in values only available to compute shaders.
+#[derive(Clone, Copy, Debug, Default)]
+pub(super) struct ComputeIds {
+    pub global_invocation_id: [u32; 3],
+    pub local_invocation_id: [u32; 3],
+    pub local_invocation_index: u32,
+    pub workgroup_id: [u32; 3],
+    pub num_workgroups: [u32; 3],
+}
+
+impl ComputeIds {
+    /// Value of a compute built-in, or `None` for any other binding.
+    pub(super) fn get(&self, binding: &naga::Binding) -> Option<Value> {
+        let vector = |v: [u32; 3]| Value::Vector(v.iter().map(|&c| Scalar::U32(c)).collect());
+        Some(match *binding {
+            naga::Binding::BuiltIn(naga::BuiltIn::GlobalInvocationId) => {
+                vector(self.global_invocation_id)
+            }
+            naga::Binding::BuiltIn(naga::BuiltIn::LocalInvocationId) => {
+                vector(self.local_invocation_id)
+            }
+            naga::Binding::BuiltIn(naga::BuiltIn::LocalInvocationIndex) => {
+                Value::Scalar(Scalar::U32(self.local_invocation_index))
+            }
+            naga::Binding::BuiltIn(naga::BuiltIn::WorkGroupId) => vector(self.workgroup_id),
+            naga::Binding::BuiltIn(naga::BuiltIn::NumWorkGroups) => vector(self.num_workgroups),
+            _ => return None,
+        })
+    }
+}
+
+/// Shader outputs, paired with their bindings.
+pub(super) type Outputs = Vec<(naga::Binding, Value)>;
+
+enum Flow {
+    Next,
+    Break,
+    Continue,
+    Return(Option<Value>),
+    Kill,
+}
+
+struct Frame<'a> {
+    function: &'a naga::Function,
+    arguments: Vec<Value>,
+    locals: Vec<Value>,
+    expressions: Vec<Option<Value>>,
+}
+
+/// State of one shader invocation.
+///
+/// An invocation can be reused to run the same program several times, for
+/// example for every fragment of a draw.
+pub(super) struct Invocation<'a, 'p> {
+    program: &'p Program<'a>,
+    module: &'a naga::Module,
+    privates: Vec<Option<Value>>,
+    workgroup: &'p Mutex<Workgroup>,
+    /// Held while the invocation runs, released while waiting on `barrier`.
+    guard: Option<MutexGuard<'p, Workgroup>>,
+    barrier: Option<&'p std::sync::Barrier>,
+    frames: Vec<Frame<'a>>,
+}
+
+impl<'a, 'p> Invocation<'a, 'p> {
+    pub(super) fn new(
+        program: &'p Program<'a>,
+        workgroup: &'p Mutex<Workgroup>,
+        barrier: Option<&'p std::sync::Barrier>,
+    ) -> Self {
+        Self {
+            program,
+            module: program.module,
+            privates: vec![None; program.module.global_variables.len()],
+            workgroup,
+            guard: None,
+            barrier,
+            frames: Vec::new(),
+        }
+    }
+
+    /// Runs an entry point. `inputs` provides the value of every input
+    /// binding.
+    ///
+    /// Returns `None` if the invocation was killed.
+    pub(super) fn run(
+        &mut self,
+        entry_point: &'a naga::EntryPoint,
+        inputs: &mut dyn FnMut(&naga::Binding, Handle<naga::Type>) -> Value,
+    ) -> Option<Outputs> {
+        let module = self.module;
+        for private in self.privates.iter_mut() {
+            *private = None;
+        }
+        let function = &entry_point.function;
+        let arguments = function
+            .arguments
+            .iter()
+            .map(|argument| match argument.binding {
+                Some(ref binding) => inputs(binding, argument.ty),
+                None => match module.types[argument.ty].inner {
+                    naga::TypeInner::Struct { ref members, .. } => Value::Composite(
+                        members
+                            .iter()
+                            .map(|member| match member.binding {
+                                Some(ref binding) => inputs(binding, member.ty),
+                                None => zero_value(module, member.ty),
+                            })
+                            .collect(),
+                    ),
+                    _ => zero_value(module, argument.ty),
+                },
+            })
+            .collect();
+
+        self.guard = Some(self.workgroup.lock());
+        let flow = self.call(function, arguments);
+        self.guard = None;
+
+        let value = match flow {
+            Flow::Kill => return None,
+            Flow::Return(value) => value,
+            _ => None,
+        };
+        let mut outputs = Vec::new();
+        if let (Some(result), Some(value)) = (function.result.as_ref(), value) {
+            match result.binding {
+                Some(ref binding) => outputs.push((binding.clone(), value)),
+                None => {
+                    if let (
+                        &naga::TypeInner::Struct { ref members, .. },
+                        Value::Composite(elements),
+                    ) = (&module.types[result.ty].inner, value)
+                    {
+                        for (member, element) in members.iter().zip(elements) {
+                            if let Some(ref binding) = member.binding {
+                                outputs.push((binding.clone(), element));
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        Some(outputs)
+    }
+
+    fn call(&mut self, function: &'a naga::Function, arguments: Vec<Value>) -> Flow {
+        self.frames.push(Frame {
+            function,
+            arguments,
+            locals: Vec::with_capacity(function.local_variables.len()),
+            expressions: vec![None; function.expressions.len()],
+        });
+        for (_, local) in function.local_variables.iter() {
+            let value = match local.init {
+                Some(init) => self.value(init),
+                None => zero_value(self.module, local.ty),
+            };
+            self.frame_mut().locals.push(value);
+        }
+        let flow = self.block(&function.body);
+        self.frames.pop();
+        flow
+    }
+
+    fn frame(&self) -> &Frame<'a> {
+        self.frames.last().unwrap()
+    }
+
+    fn frame_mut(&mut self) -> &mut Frame<'a> {
+        self.frames.last_mut().unwrap()
+    }
+
+    fn set_result(&mut self, expr: Handle<naga::Expression>, value: Value) {
+        self.frame_mut().expressions[expr.index()] = Some(value);
+    }
+
+    /// Waits for the other invocations of the workgroup.
+    fn barrier(&mut self) {
+        if let Some(barrier) = self.barrier {
+            self.guard = None;
+            barrier.wait();
+            self.guard = Some(self.workgroup.lock());
+        }
+    }
+
+    fn block(&mut self, block: &'a naga::Block) -> Flow {
+        for statement in block.iter() {
+            match self.statement(statement) {
+                Flow::Next => {}
+                flow => return flow,
+            }
+        }
+        Flow::Next
+    }
+
+    fn statement(&mut self, statement: &'a naga::Statement) -> Flow {
+        use naga::Statement as St;
+
+        match *statement {
+            St::Emit(ref range) => {
+                for expr in range.clone() {
+                    let value = self.evaluate(expr);
+                    self.set_result(expr, value);
+                }
+            }
+            St::Block(ref block) => return self.block(block),
+            St::If {
+                condition,
+                ref accept,
+                ref reject,
+            } => {
+                return if self.value(condition).scalar().as_bool() {
+                    self.block(accept)
+                } else {
+                    self.block(reject)
+                };
+            }
+            St::Switch {
+                selector,
+                ref cases,
+            } => {
+                let selector = self.value(selector).scalar();
+                let start = cases
+                    .iter()
+                    .position(|case| match case.value {
+                        naga::SwitchValue::I32(value) => selector == Scalar::I32(value),
+                        naga::SwitchValue::U32(value) => selector == Scalar::U32(value),
+                        naga::SwitchValue::Default => false,
+                    })
+                    .or_else(|| {
+                        cases
+                            .iter()
+                            .position(|case| case.value == naga::SwitchValue::Default)
+                    });
+                if let Some(start) = start {
+                    for case in cases[start..].iter() {
+                        match self.block(&case.body) {
+                            Flow::Next => {}
+                            Flow::Break => break,
+                            flow => return flow,
+                        }
+                        if !case.fall_through {
+                            break;
+                        }
+                    }
+                }
+            }
+            St::Loop {
+                ref body,
+                ref continuing,
+                break_if,
+            } => loop {
+                match self.block(body) {
+                    Flow::Next | Flow::Continue => {}
+                    Flow::Break => break,
+                    flow => return flow,
+                }
+                match self.block(continuing) {
+                    Flow::Next | Flow::Continue => {}
+                    Flow::Break => break,
+                    flow => return flow,
+                }
+                if let Some(condition) = break_if {
+                    if self.value(condition).scalar().as_bool() {
+                        break;
+                    }
+                }
+            },
+            St::Break => return Flow::Break,
+            St::Continue => return Flow::Continue,
+            St::Return { value } => return Flow::Return(value.map(|value| self.value(value))),
+            St::Kill => return Flow::Kill,
+            St::Barrier(_) => self.barrier(),
+            St::Store { pointer, value } => {
+                let value = self.value(value);
+                if let Value::Pointer(pointer) = self.value(pointer) {
+                    self.store(&pointer, value);
+                }
+            }
+            St::ImageStore {
+                image,
+                coordinate,
+                array_index,
+                value,
+            } => {
+                let view = self.program.texture(&self.value(image));
+                let coordinate = self.value(coordinate).components();
+                let array_index = array_index.map(|index| self.value(index).scalar().as_i32());
+                let texel = self.value(value).to_texel();
+                if let Some(view) = view {
+                    let (coordinate, layer) = image::split_coordinate(&coordinate, array_index);
+                    view.store(coordinate, layer, texel);
+                }
+            }
+            St::Atomic {
+                pointer,
+                ref fun,
+                value,
+                result,
+            } => {
+                let pointer = match self.value(pointer) {
+                    Value::Pointer(pointer) => pointer,
+                    _ => return Flow::Next,
+                };
+                let old = self.load(&pointer).scalar();
+                let operand = self.value(value).scalar();
+                let (new, result_value) = match *fun {
+                    naga::AtomicFunction::Add => (
+                        math::binary_scalar(naga::BinaryOperator::Add, old, operand),
+                        None,
+                    ),
+                    naga::AtomicFunction::Subtract => (
+                        math::binary_scalar(naga::BinaryOperator::Subtract, old, operand),
+                        None,
+                    ),
+                    naga::AtomicFunction::And => (
+                        math::binary_scalar(naga::BinaryOperator::And, old, operand),
+                        None,
+                    ),
+                    naga::AtomicFunction::ExclusiveOr => (
+                        math::binary_scalar(naga::BinaryOperator::ExclusiveOr, old, operand),
+                        None,
+                    ),
+                    naga::AtomicFunction::InclusiveOr => (
+                        math::binary_scalar(naga::BinaryOperator::InclusiveOr, old, operand),
+                        None,
+                    ),
+                    naga::AtomicFunction::Min => (math::min(old, operand), None),
+                    naga::AtomicFunction::Max => (math::max(old, operand), None),
+                    naga::AtomicFunction::Exchange { compare: None } => (operand, None),
+                    naga::AtomicFunction::Exchange {
+                        compare: Some(compare),
+                    } => {
+                        let exchanged = old == self.value(compare).scalar();
+                        let new = if exchanged { operand } else { old };
+                        let result = Value::Composite(vec![
+                            Value::Scalar(old),
+                            Value::Scalar(Scalar::Bool(exchanged)),
+                        ]);
+                        (new, Some(result))
+                    }
+                };
+                self.store(&pointer, Value::Scalar(new));
+                self.set_result(result, result_value.unwrap_or(Value::Scalar(old)));
+            }
+            St::WorkGroupUniformLoad { pointer, result } => {
+                self.barrier();
+                let value = match self.value(pointer) {
+                    Value::Pointer(pointer) => self.load(&pointer),
+                    _ => Value::ZERO,
+                };
+                self.barrier();
+                self.set_result(result, value);
+            }
+            St::Call {
+                function,
+                ref arguments,
+                result,
+            } => {
+                let arguments = arguments
+                    .iter()
+                    .map(|&argument| self.value(argument))
+                    .collect();
+                match self.call(&self.module.functions[function], arguments) {
+                    Flow::Kill => return Flow::Kill,
+                    Flow::Return(Some(value)) => {
+                        if let Some(result) = result {
+                            self.set_result(result, value);
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            St::RayQuery { .. } => {
+                log::error!("Ray queries are not supported by the CPU backend");
+            }
+        }
+        Flow::Next
+    }
+
+    fn load(&mut self, pointer: &Pointer) -> Value {
+        match *pointer {
+            Pointer::Local {
+                frame,
+                variable,
+                ref path,
+            } => self.frames[frame].locals[variable.index()].get(path),
+            Pointer::Private { variable, ref path } => self.private(variable).get(path),
+            Pointer::WorkGroup { variable, ref path } => self.shared(variable).get(path),
+            Pointer::Buffer {
+                variable,
+                offset,
+                ty,
+            } => self.program.read(variable, offset, ty),
+        }
+    }
+
+    fn store(&mut self, pointer: &Pointer, value: Value) {
+        match *pointer {
+            Pointer::Local {
+                frame,
+                variable,
+                ref path,
+            } => self.frames[frame].locals[variable.index()].set(path, value),
+            Pointer::Private { variable, ref path } => self.private(variable).set(path, value),
+            Pointer::WorkGroup { variable, ref path } => self.shared(variable).set(path, value),
+            Pointer::Buffer {
+                variable,
+                offset,
+                ty,
+            } => self.program.write(variable, offset, ty, &value),
+        }
+    }
+
+    fn private(&mut self, variable: Handle<naga::GlobalVariable>) -> &mut Value {
+        let module = self.module;
+        self.privates[variable.index()].get_or_insert_with(|| {
+            let var = &module.global_variables[variable];
+            match var.init {
+                Some(init) => const_value(module, init),
+                None => zero_value(module, var.ty),
+            }
+        })
+    }
+
+    fn shared(&mut self, variable: Handle<naga::GlobalVariable>) -> &mut Value {
+        let module = self.module;
+        let guard = self.guard.as_mut().unwrap();
+        guard.variables[variable.index()]
+            .get_or_insert_with(|| zero_value(module, module.global_variables[variable].ty))
+    }
+
+    /// Returns the value of an expression, evaluating it if it wasn't
+    /// emitted yet, which is only the case for constant expressions.
+    fn value(&mut self, expr: Handle<naga::Expression>) -> Value {
+        if let Some(ref value) = self.frame().expressions[expr.index()] {
+            return value.clone();
+        }
+        let value = self.evaluate(expr);
+        self.set_result(expr, value.clone());
+        value
+    }
+
+    /// Evaluates `Access` and `AccessIndex` on a pointer.
+    fn access_pointer(&self, pointer: Pointer, index: u32) -> Pointer {
+        match pointer {
+            Pointer::Local {
+                frame,
+                variable,
+                mut path,
+            } => {
+                path.push(index);
+                Pointer::Local {
+                    frame,
+                    variable,
+                    path,
+                }
+            }
+            Pointer::Private { variable, mut path } => {
+                path.push(index);
+                Pointer::Private { variable, path }
+            }
+            Pointer::WorkGroup { variable, mut path } => {
+                path.push(index);
+                Pointer::WorkGroup { variable, path }
+            }
+            Pointer::Buffer {
+                variable,
+                offset,
+                ty,
+            } => {
+                let (offset, ty) = match ty {
+                    MemoryType::Scalar(..) => (offset, ty),
+                    MemoryType::Vector(size, kind, width) => {
+                        let index = index.min(size as u32 - 1) as u64;
+                        (
+                            offset + index * width as u64,
+                            MemoryType::Scalar(kind, width),
+                        )
+                    }
+                    MemoryType::Type(ty) => match self.module.types[ty].inner {
+                        naga::TypeInner::Vector { size, kind, width } => {
+                            let index = index.min(size as u32 - 1) as u64;
+                            (
+                                offset + index * width as u64,
+                                MemoryType::Scalar(kind, width),
+                            )
+                        }
+                        naga::TypeInner::Matrix {
+                            columns,
+                            rows,
+                            width,
+                        } => {
+                            let index = index.min(columns as u32 - 1) as u64;
+                            (
+                                offset + index * column_stride(rows, width),
+                                MemoryType::Vector(rows, ScalarKind::Float, width),
+                            )
+                        }
+                        naga::TypeInner::Array { base, size, stride } => {
+                            let index = match size {
+                                naga::ArraySize::Constant(count) => index.min(count.get() - 1),
+                                naga::ArraySize::Dynamic => index,
+                            };
+                            (
+                                offset + index as u64 * stride as u64,
+                                MemoryType::Type(base),
+                            )
+                        }
+                        naga::TypeInner::Struct { ref members, .. } => {
+                            let member = &members[index as usize];
+                            (offset + member.offset as u64, MemoryType::Type(member.ty))
+                        }
+                        _ => (offset, MemoryType::Type(ty)),
+                    },
+                };
+                Pointer::Buffer {
+                    variable,
+                    offset,
+                    ty,
+                }
+            }
+        }
+    }
+
+    fn access(&mut self, base: Handle<naga::Expression>, index: u32) -> Value {
+        match self.value(base) {
+            Value::Pointer(pointer) => Value::Pointer(self.access_pointer(pointer, index)),
+            value => value.element(index),
+        }
+    }
+
+    fn evaluate(&mut self, expr: Handle<naga::Expression>) -> Value {
+        use naga::Expression as Ex;
+
+        let module = self.module;
+        let function = self.frame().function;
+        match function.expressions[expr] {
+            Ex::Literal(value) => Value::Scalar(literal(value)),
+            Ex::Constant(constant) => const_value(module, module.constants[constant].init),
+            Ex::ZeroValue(ty) => zero_value(module, ty),
+            Ex::Compose { ty, ref components } => {
+                let components = components
+                    .iter()
+                    .map(|&component| self.value(component))
+                    .collect();
+                compose(module, ty, components)
+            }
+            Ex::Access { base, index } => {
+                let index = self.value(index).scalar().as_index();
+                self.access(base, index)
+            }
+            Ex::AccessIndex { base, index } => self.access(base, index),
+            Ex::Splat { size, value } => splat(size, &self.value(value)),
+            Ex::Swizzle {
+                size,
+                vector,
+                pattern,
+            } => {
+                let vector = self.value(vector).components();
+                Value::Vector(
+                    pattern[..size as usize]
+                        .iter()
+                        .map(|&component| vector[component as usize])
+                        .collect(),
+                )
+            }
+            Ex::FunctionArgument(index) => self.frame().arguments[index as usize].clone(),
+            Ex::GlobalVariable(variable) => match module.global_variables[variable].space {
+                naga::AddressSpace::Private => Value::Pointer(Pointer::Private {
+                    variable,
+                    path: Vec::new(),
+                }),
+                naga::AddressSpace::WorkGroup => Value::Pointer(Pointer::WorkGroup {
+                    variable,
+                    path: Vec::new(),
+                }),
+                naga::AddressSpace::Handle => Value::Handle(variable),
+                _ => Value::Pointer(Pointer::Buffer {
+                    variable,
+                    offset: 0,
+                    ty: MemoryType::Type(module.global_variables[variable].ty),
+                }),
+            },
+            Ex::LocalVariable(variable) => Value::Pointer(Pointer::Local {
+                frame: self.frames.len() - 1,
+                variable,
+                path: Vec::new(),
+            }),
+            Ex::Load { pointer } => match self.value(pointer) {
+                Value::Pointer(pointer) => self.load(&pointer),
+                value => value,
+            },
+            Ex::ImageSample {
+                image,
+                sampler,
+                gather,
+                coordinate,
+                array_index,
+                offset,
+                ref level,
+                depth_ref,
+            } => {
+                let view = self.program.texture(&self.value(image));
+                let sampler = self.program.sampler(&self.value(sampler));
+                let coordinate: Vec<f32> = self
+                    .value(coordinate)
+                    .components()
+                    .iter()
+                    .map(|c| c.as_f32())
+                    .collect();
+                let array_index = array_index.map(|index| self.value(index).scalar().as_i32());
+                let offset = offset.map_or([0; 3], |offset| {
+                    let mut components = [0; 3];
+                    for (slot, c) in components
+                        .iter_mut()
+                        .zip(const_value(module, offset).components())
+                    {
+                        *slot = c.as_i32();
+                    }
+                    components
+                });
+                let level = match *level {
+                    naga::SampleLevel::Auto | naga::SampleLevel::Zero => image::Level::Lod(0.0),
+                    naga::SampleLevel::Exact(lod) | naga::SampleLevel::Bias(lod) => {
+                        image::Level::Lod(self.value(lod).scalar().as_f32())
+                    }
+                    naga::SampleLevel::Gradient { x, y } => {
+                        let x = self
+                            .value(x)
+                            .components()
+                            .iter()
+                            .map(|c| c.as_f32())
+                            .collect();
+                        let y = self
+                            .value(y)
+                            .components()
+                            .iter()
+                            .map(|c| c.as_f32())
+                            .collect();
+                        image::Level::Gradient(x, y)
+                    }
+                };
+                let depth_ref = depth_ref.map(|value| self.value(value).scalar().as_f32());
+                let result = match (view, sampler) {
+                    (Some(view), Some(sampler)) => view.sample(
+                        sampler,
+                        &image::SampleRequest {
+                            coordinate: &coordinate,
+                            array_index,
+                            offset,
+                            level,
+                            gather: gather.map(|component| component as usize),
+                            depth_ref,
+                        },
+                    ),
+                    _ => [0.0; 4],
+                };
+                if depth_ref.is_some() && gather.is_none() {
+                    Value::Scalar(Scalar::F32(result[0]))
+                } else {
+                    Value::Vector(result.iter().map(|&c| Scalar::F32(c)).collect())
+                }
+            }
+            Ex::ImageLoad {
+                image,
+                coordinate,
+                array_index,
+                sample: _,
+                level,
+            } => {
+                let image_value = self.value(image);
+                let view = self.program.texture(&image_value);
+                let coordinate = self.value(coordinate).components();
+                let array_index = array_index.map(|index| self.value(index).scalar().as_i32());
+                let level = level.map_or(0, |level| self.value(level).scalar().as_i32());
+                let texel = view
+                    .and_then(|view| {
+                        let (coordinate, layer) = image::split_coordinate(&coordinate, array_index);
+                        view.load(coordinate, layer, level)
+                    })
+                    .unwrap_or(Texel::ZERO);
+                let class = match image_value {
+                    Value::Handle(variable) => {
+                        match module.types[module.global_variables[variable].ty].inner {
+                            naga::TypeInner::Image { class, .. } => Some(class),
+                            _ => None,
+                        }
+                    }
+                    _ => None,
+                };
+                match class {
+                    Some(naga::ImageClass::Depth { .. }) => {
+                        Value::Scalar(Scalar::F32(texel.to_float()[0]))
+                    }
+                    Some(naga::ImageClass::Sampled { kind, .. }) => {
+                        Value::from_texel(texel, kind, Some(VectorSize::Quad), 4)
+                    }
+                    _ => {
+                        let kind = match texel {
+                            Texel::Float(_) => ScalarKind::Float,
+                            Texel::Uint(_) => ScalarKind::Uint,
+                            Texel::Sint(_) => ScalarKind::Sint,
+                        };
+                        Value::from_texel(texel, kind, Some(VectorSize::Quad), 4)
+                    }
+                }
+            }
+            Ex::ImageQuery { image, ref query } => {
+                let image_value = self.value(image);
+                let view = match self.program.texture(&image_value) {
+                    Some(view) => view,
+                    None => return Value::Scalar(Scalar::U32(0)),
+                };
+                match *query {
+                    naga::ImageQuery::Size { level } => {
+                        let level = level.map_or(0, |level| self.value(level).scalar().as_index());
+                        let size = view.level_size(level);
+                        let components: Vector = match view.dimension {
+                            wgt::TextureViewDimension::D1 => {
+                                return Value::Scalar(Scalar::U32(size.width))
+                            }
+                            wgt::TextureViewDimension::D3 => {
+                                [size.width, size.height, size.depth_or_array_layers]
+                                    .iter()
+                                    .map(|&c| Scalar::U32(c))
+                                    .collect()
+                            }
+                            _ => [size.width, size.height]
+                                .iter()
+                                .map(|&c| Scalar::U32(c))
+                                .collect(),
+                        };
+                        Value::Vector(components)
+                    }
+                    naga::ImageQuery::NumLevels => {
+                        Value::Scalar(Scalar::U32(view.mip_levels.len() as u32))
+                    }
+                    naga::ImageQuery::NumLayers => Value::Scalar(Scalar::U32(view.layer_count())),
+                    naga::ImageQuery::NumSamples => {
+                        Value::Scalar(Scalar::U32(view.texture.sample_count))
+                    }
+                }
+            }
+            Ex::Unary { op, expr } => math::unary(op, self.value(expr)),
+            Ex::Binary { op, left, right } => {
+                let left = self.value(left);
+                let right = self.value(right);
+                math::binary(op, left, right)
+            }
+            Ex::Select {
+                condition,
+                accept,
+                reject,
+            } => {
+                let condition = self.value(condition);
+                let accept = self.value(accept);
+                let reject = self.value(reject);
+                match condition {
+                    Value::Vector(condition) => Value::Vector(
+                        condition
+                            .iter()
+                            .zip(accept.components().iter().zip(reject.components().iter()))
+                            .map(|(c, (&a, &r))| if c.as_bool() { a } else { r })
+                            .collect(),
+                    ),
+                    condition if condition.scalar().as_bool() => accept,
+                    _ => reject,
+                }
+            }
+            // Invocations run one at a time, so neighboring fragments aren't
+            // available to compute derivatives.
+            Ex::Derivative { expr, .. } => {
+                math::map(self.value(expr), |scalar| scalar.with_f64(0.0))
+            }
+            Ex::Relational { fun, argument } => math::relational(fun, self.value(argument)),
+            Ex::Math {
+                fun,
+                arg,
+                arg1,
+                arg2,
+                arg3,
+            } => {
+                let arg = self.value(arg);
+                let arg1 = arg1.map(|arg| self.value(arg));
+                let arg2 = arg2.map(|arg| self.value(arg));
+                let arg3 = arg3.map(|arg| self.value(arg));
+                math::math(fun, arg, arg1, arg2, arg3)
+            }
+            Ex::As {
+                expr,
+                kind,
+                convert,
+            } => {
+                let value = self.value(expr);
+                match convert {
+                    Some(width) => math::map(value, |scalar| scalar.convert(kind, width)),
+                    None => math::map(value, |scalar| scalar.bitcast(kind)),
+                }
+            }
+            Ex::CallResult(_)
+            | Ex::AtomicResult { .. }
+            | Ex::WorkGroupUniformLoadResult { .. }
+            | Ex::RayQueryProceedResult
+            | Ex::RayQueryGetIntersection { .. } => Value::ZERO,
+            Ex::ArrayLength(pointer) => match self.value(pointer) {
+                Value::Pointer(Pointer::Buffer {
+                    variable,
+                    offset,
+                    ty: MemoryType::Type(ty),
+                }) => {
+                    let stride = match module.types[ty].inner {
+                        naga::TypeInner::Array { stride, .. } => stride as u64,
+                        _ => 1,
+                    };
+                    let size = self.program.binding_size(variable).saturating_sub(offset);
+                    Value::Scalar(Scalar::U32((size / stride) as u32))
+                }
+                _ => Value::Scalar(Scalar::U32(0)),
+            },
+        }
+    }
+}
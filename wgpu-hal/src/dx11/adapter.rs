@@ -7,6 +7,7 @@ impl crate::Adapter<super::Api> for super::Adapter {
         &self,
         features: wgt::Features,
         limits: &wgt::Limits,
+        _memory_hints: &wgt::MemoryHints,
     ) -> Result<crate::OpenDevice<super::Api>, crate::DeviceError> {
         todo!()
     }
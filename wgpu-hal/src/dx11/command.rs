@@ -268,4 +268,24 @@ impl crate::CommandEncoder<super::Api> for super::CommandEncoder {
     unsafe fn dispatch_indirect(&mut self, buffer: &super::Buffer, offset: wgt::BufferAddress) {
         todo!()
     }
+
+    // Acceleration structures aren't exposed by this backend, and wgpu-core
+    // requires `Features::RAY_TRACING_ACCELERATION_STRUCTURE` to build them.
+    unsafe fn build_acceleration_structures<'a, T>(
+        &mut self,
+        _descriptor_count: u32,
+        _descriptors: T,
+    ) where
+        super::Api: 'a,
+        T: IntoIterator<Item = crate::BuildAccelerationStructureDescriptor<'a, super::Api>>,
+    {
+        unreachable!("RAY_TRACING_ACCELERATION_STRUCTURE is not exposed on this backend")
+    }
+
+    unsafe fn place_acceleration_structure_barrier(
+        &mut self,
+        _barriers: crate::AccelerationStructureBarrier,
+    ) {
+        unreachable!("RAY_TRACING_ACCELERATION_STRUCTURE is not exposed on this backend")
+    }
 }
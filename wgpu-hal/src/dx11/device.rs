@@ -80,6 +80,41 @@ impl crate::Device<super::Api> for super::Device {
         todo!()
     }
 
+    // Resource heaps aren't exposed by this backend.
+    unsafe fn create_heap(&self, _desc: &crate::HeapDescriptor) -> Result<(), crate::DeviceError> {
+        Err(crate::DeviceError::ResourceCreationFailed)
+    }
+
+    unsafe fn destroy_heap(&self, (): ()) {}
+
+    unsafe fn create_buffer_in_heap(
+        &self,
+        _desc: &crate::BufferDescriptor,
+        _heap: &(),
+        _offset: wgt::BufferAddress,
+    ) -> Result<crate::PlacedResource<super::Buffer>, crate::HeapPlacementError> {
+        Err(crate::DeviceError::ResourceCreationFailed.into())
+    }
+
+    unsafe fn create_texture_in_heap(
+        &self,
+        _desc: &crate::TextureDescriptor,
+        _heap: &(),
+        _offset: wgt::BufferAddress,
+    ) -> Result<crate::PlacedResource<super::Texture>, crate::HeapPlacementError> {
+        Err(crate::DeviceError::ResourceCreationFailed.into())
+    }
+
+    // External semaphores aren't exposed by this backend.
+    unsafe fn create_external_semaphore(
+        &self,
+        _desc: &crate::ExternalSemaphoreDescriptor,
+    ) -> Result<(), crate::DeviceError> {
+        Err(crate::DeviceError::ResourceCreationFailed)
+    }
+
+    unsafe fn destroy_external_semaphore(&self, (): ()) {}
+
     unsafe fn create_command_encoder(
         &self,
         desc: &crate::CommandEncoderDescriptor<super::Api>,
@@ -158,6 +193,14 @@ impl crate::Device<super::Api> for super::Device {
         todo!()
     }
 
+    unsafe fn create_pipeline_cache(
+        &self,
+        _desc: &crate::PipelineCacheDescriptor<'_>,
+    ) -> Result<(), crate::PipelineCacheError> {
+        Ok(())
+    }
+    unsafe fn destroy_pipeline_cache(&self, (): ()) {}
+
     unsafe fn create_query_set(
         &self,
         desc: &wgt::QuerySetDescriptor<crate::Label>,
@@ -200,6 +243,29 @@ impl crate::Device<super::Api> for super::Device {
     unsafe fn stop_capture(&self) {
         todo!()
     }
+
+    // Acceleration structures aren't exposed by this backend. wgpu-core requires
+    // `Features::RAY_TRACING_ACCELERATION_STRUCTURE` before asking for build sizes,
+    // and only asks for the address of acceleration structures it has created.
+    unsafe fn create_acceleration_structure(
+        &self,
+        _desc: &crate::AccelerationStructureDescriptor,
+    ) -> Result<(), crate::DeviceError> {
+        Err(crate::DeviceError::ResourceCreationFailed)
+    }
+    unsafe fn get_acceleration_structure_build_sizes<'a>(
+        &self,
+        _desc: &crate::GetAccelerationStructureBuildSizesDescriptor<'a, super::Api>,
+    ) -> crate::AccelerationStructureBuildSizes {
+        unreachable!("RAY_TRACING_ACCELERATION_STRUCTURE is not exposed on this backend")
+    }
+    unsafe fn get_acceleration_structure_device_address(
+        &self,
+        _acceleration_structure: &(),
+    ) -> wgt::BufferAddress {
+        unreachable!("RAY_TRACING_ACCELERATION_STRUCTURE is not exposed on this backend")
+    }
+    unsafe fn destroy_acceleration_structure(&self, _acceleration_structure: ()) {}
 }
 
 impl crate::Queue<super::Api> for super::Queue {
@@ -211,6 +277,16 @@ impl crate::Queue<super::Api> for super::Queue {
         todo!()
     }
 
+    unsafe fn submit_with_sync(
+        &mut self,
+        command_buffers: &[&super::CommandBuffer],
+        _waits: &[(&(), u64)],
+        _signals: &[(&(), u64)],
+        signal_fence: Option<(&mut super::Fence, crate::FenceValue)>,
+    ) -> Result<(), crate::DeviceError> {
+        unsafe { crate::Queue::submit(self, command_buffers, signal_fence) }
+    }
+
     unsafe fn present(
         &mut self,
         surface: &mut super::Surface,
@@ -219,6 +295,16 @@ impl crate::Queue<super::Api> for super::Queue {
         todo!()
     }
 
+    // Sparse resources aren't exposed by this backend.
+    unsafe fn bind_sparse(
+        &mut self,
+        _buffer_bindings: &[crate::SparseBufferBinding<super::Api>],
+        _texture_bindings: &[crate::SparseTextureBinding<super::Api>],
+        _signal_fence: Option<(&mut super::Fence, crate::FenceValue)>,
+    ) -> Result<(), crate::DeviceError> {
+        Err(crate::DeviceError::ResourceCreationFailed)
+    }
+
     unsafe fn get_timestamp_period(&self) -> f32 {
         todo!()
     }
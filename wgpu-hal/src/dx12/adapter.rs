@@ -253,7 +253,8 @@ impl super::Adapter {
             | wgt::Features::PUSH_CONSTANTS
             | wgt::Features::SHADER_PRIMITIVE_INDEX
             | wgt::Features::RG11B10UFLOAT_RENDERABLE
-            | wgt::Features::DUAL_SOURCE_BLENDING;
+            | wgt::Features::DUAL_SOURCE_BLENDING
+            | wgt::Features::REUSABLE_COMMAND_BUFFERS;
 
         //TODO: in order to expose this, we need to run a compute shader
         // that extract the necessary statistics out of the D3D12 result.
@@ -407,6 +408,7 @@ impl crate::Adapter<super::Api> for super::Adapter {
         &self,
         _features: wgt::Features,
         limits: &wgt::Limits,
+        _memory_hints: &wgt::MemoryHints,
     ) -> Result<crate::OpenDevice<super::Api>, crate::DeviceError> {
         let queue = {
             profiling::scope!("ID3D12Device::CreateCommandQueue");
@@ -112,6 +112,9 @@ pub fn map_binding_type(ty: &wgt::BindingType) -> d3d12::DescriptorRangeType {
             ..
         }
         | Bt::StorageTexture { .. } => d3d12::DescriptorRangeType::UAV,
+        // wgpu-core requires `Features::RAY_QUERY` for this binding type, which
+        // this backend doesn't expose.
+        Bt::AccelerationStructure => unreachable!("RAY_QUERY is not exposed on this backend"),
     }
 }
 
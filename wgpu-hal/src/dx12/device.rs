@@ -625,6 +625,37 @@ impl crate::Device<super::Api> for super::Device {
         self.sampler_pool.lock().free_handle(sampler.handle);
     }
 
+    // Heaps aren't exposed by this backend.
+    unsafe fn create_heap(&self, _desc: &crate::HeapDescriptor) -> Result<(), crate::DeviceError> {
+        Err(crate::DeviceError::ResourceCreationFailed)
+    }
+    unsafe fn destroy_heap(&self, (): ()) {}
+    unsafe fn create_buffer_in_heap(
+        &self,
+        _desc: &crate::BufferDescriptor,
+        _heap: &(),
+        _offset: wgt::BufferAddress,
+    ) -> Result<crate::PlacedResource<super::Buffer>, crate::HeapPlacementError> {
+        Err(crate::DeviceError::ResourceCreationFailed.into())
+    }
+    unsafe fn create_texture_in_heap(
+        &self,
+        _desc: &crate::TextureDescriptor,
+        _heap: &(),
+        _offset: wgt::BufferAddress,
+    ) -> Result<crate::PlacedResource<super::Texture>, crate::HeapPlacementError> {
+        Err(crate::DeviceError::ResourceCreationFailed.into())
+    }
+
+    // External semaphores aren't exposed by this backend.
+    unsafe fn create_external_semaphore(
+        &self,
+        _desc: &crate::ExternalSemaphoreDescriptor,
+    ) -> Result<(), crate::DeviceError> {
+        Err(crate::DeviceError::ResourceCreationFailed)
+    }
+    unsafe fn destroy_external_semaphore(&self, (): ()) {}
+
     unsafe fn create_command_encoder(
         &self,
         desc: &crate::CommandEncoderDescriptor<super::Api>,
@@ -674,6 +705,11 @@ impl crate::Device<super::Api> for super::Device {
                     num_texture_views += count
                 }
                 wgt::BindingType::Sampler { .. } => num_samplers += count,
+                // wgpu-core requires `Features::RAY_QUERY` for this binding type, which
+                // this backend doesn't expose.
+                wgt::BindingType::AccelerationStructure => {
+                    unreachable!("RAY_QUERY is not exposed on this backend")
+                }
             }
         }
 
@@ -1188,6 +1224,11 @@ impl crate::Device<super::Api> for super::Device {
                         cpu_views.as_mut().unwrap().stage.push(handle.raw);
                     }
                 }
+                // wgpu-core requires `Features::RAY_QUERY` for this binding type, which
+                // this backend doesn't expose.
+                wgt::BindingType::AccelerationStructure => {
+                    unreachable!("RAY_QUERY is not exposed on this backend")
+                }
                 wgt::BindingType::Sampler { .. } => {
                     let start = entry.resource_index as usize;
                     let end = start + entry.count as usize;
@@ -1479,6 +1520,14 @@ impl crate::Device<super::Api> for super::Device {
     }
     unsafe fn destroy_compute_pipeline(&self, _pipeline: super::ComputePipeline) {}
 
+    unsafe fn create_pipeline_cache(
+        &self,
+        _desc: &crate::PipelineCacheDescriptor<'_>,
+    ) -> Result<(), crate::PipelineCacheError> {
+        Ok(())
+    }
+    unsafe fn destroy_pipeline_cache(&self, (): ()) {}
+
     unsafe fn create_query_set(
         &self,
         desc: &wgt::QuerySetDescriptor<crate::Label>,
@@ -1574,4 +1623,27 @@ impl crate::Device<super::Api> for super::Device {
                 .end_frame_capture(self.raw.as_mut_ptr() as *mut _, ptr::null_mut())
         }
     }
+
+    // Acceleration structures aren't exposed by this backend. wgpu-core requires
+    // `Features::RAY_TRACING_ACCELERATION_STRUCTURE` before asking for build sizes,
+    // and only asks for the address of acceleration structures it has created.
+    unsafe fn create_acceleration_structure(
+        &self,
+        _desc: &crate::AccelerationStructureDescriptor,
+    ) -> Result<(), crate::DeviceError> {
+        Err(crate::DeviceError::ResourceCreationFailed)
+    }
+    unsafe fn get_acceleration_structure_build_sizes<'a>(
+        &self,
+        _desc: &crate::GetAccelerationStructureBuildSizesDescriptor<'a, super::Api>,
+    ) -> crate::AccelerationStructureBuildSizes {
+        unreachable!("RAY_TRACING_ACCELERATION_STRUCTURE is not exposed on this backend")
+    }
+    unsafe fn get_acceleration_structure_device_address(
+        &self,
+        _acceleration_structure: &(),
+    ) -> wgt::BufferAddress {
+        unreachable!("RAY_TRACING_ACCELERATION_STRUCTURE is not exposed on this backend")
+    }
+    unsafe fn destroy_acceleration_structure(&self, _acceleration_structure: ()) {}
 }
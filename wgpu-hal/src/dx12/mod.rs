@@ -82,6 +82,11 @@ impl crate::Api for Api {
     type ShaderModule = ShaderModule;
     type RenderPipeline = RenderPipeline;
     type ComputePipeline = ComputePipeline;
+    type PipelineCache = ();
+    type Heap = ();
+    type ExternalSemaphore = ();
+
+    type AccelerationStructure = ();
 }
 
 // Limited by D3D12's root signature size of 64. Each element takes 1 or 2 entries.
@@ -884,6 +889,17 @@ impl crate::Queue<Api> for Queue {
 
         Ok(())
     }
+    // No external semaphore can be created, so there is nothing to wait on or signal.
+    unsafe fn submit_with_sync(
+        &mut self,
+        command_buffers: &[&CommandBuffer],
+        _waits: &[(&(), u64)],
+        _signals: &[(&(), u64)],
+        signal_fence: Option<(&mut Fence, crate::FenceValue)>,
+    ) -> Result<(), crate::DeviceError> {
+        unsafe { crate::Queue::submit(self, command_buffers, signal_fence) }
+    }
+
     unsafe fn present(
         &mut self,
         surface: &mut Surface,
@@ -906,6 +922,16 @@ impl crate::Queue<Api> for Queue {
         Ok(())
     }
 
+    // Sparse resources aren't exposed by this backend.
+    unsafe fn bind_sparse(
+        &mut self,
+        _buffer_bindings: &[crate::SparseBufferBinding<Api>],
+        _texture_bindings: &[crate::SparseTextureBinding<Api>],
+        _signal_fence: Option<(&mut Fence, crate::FenceValue)>,
+    ) -> Result<(), crate::DeviceError> {
+        Err(crate::DeviceError::ResourceCreationFailed)
+    }
+
     unsafe fn get_timestamp_period(&self) -> f32 {
         let mut frequency = 0u64;
         unsafe { self.raw.GetTimestampFrequency(&mut frequency) };
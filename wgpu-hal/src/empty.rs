@@ -1,13 +1,19 @@
 #![allow(unused_variables)]
 
-use std::ops::Range;
+//! Backend without a GPU.
+//!
+//! Buffers, textures and query sets live in host memory, and transfer and
+//! query commands are executed on submission. Everything else is a dummy
+//! resource, and draws and dispatches do nothing.
+
+use std::{ops::Range, sync::Arc, time::Instant};
+
+use crate::host_memory::{buffer_row_offset, Memory};
 
 #[derive(Clone, Debug)]
 pub struct Api;
 pub struct Context;
 #[derive(Debug)]
-pub struct Encoder;
-#[derive(Debug)]
 pub struct Resource;
 
 type DeviceResult<T> = Result<T, crate::DeviceError>;
@@ -18,17 +24,17 @@ impl crate::Api for Api {
     type Adapter = Context;
     type Device = Context;
 
-    type Queue = Context;
+    type Queue = Queue;
     type CommandEncoder = Encoder;
-    type CommandBuffer = Resource;
+    type CommandBuffer = CommandBuffer;
 
-    type Buffer = Resource;
-    type Texture = Resource;
-    type SurfaceTexture = Resource;
+    type Buffer = Buffer;
+    type Texture = Texture;
+    type SurfaceTexture = Texture;
     type TextureView = Resource;
     type Sampler = Resource;
-    type QuerySet = Resource;
-    type Fence = Resource;
+    type QuerySet = QuerySet;
+    type Fence = Fence;
 
     type BindGroupLayout = Resource;
     type BindGroup = Resource;
@@ -36,6 +42,271 @@ impl crate::Api for Api {
     type ShaderModule = Resource;
     type RenderPipeline = Resource;
     type ComputePipeline = Resource;
+    type PipelineCache = Resource;
+    type Heap = Resource;
+    type ExternalSemaphore = Resource;
+
+    type AccelerationStructure = Resource;
+}
+
+pub struct Queue {
+    /// Origin of timestamp queries.
+    epoch: Instant,
+}
+
+#[derive(Debug)]
+pub struct Buffer {
+    memory: Arc<Memory>,
+}
+
+/// The texels of one aspect of a texture, stored as tightly packed rows of
+/// blocks, mip level after mip level.
+#[derive(Debug)]
+struct Plane {
+    aspects: crate::FormatAspects,
+    memory: Memory,
+    block_size: u32,
+    mip_offsets: Vec<u64>,
+}
+
+#[derive(Debug)]
+struct TextureInner {
+    dimension: wgt::TextureDimension,
+    size: wgt::Extent3d,
+    block_dimensions: (u32, u32),
+    planes: Vec<Plane>,
+}
+
+impl TextureInner {
+    fn plane(&self, aspect: crate::FormatAspects) -> &Plane {
+        self.planes
+            .iter()
+            .find(|plane| plane.aspects.contains(aspect))
+            .unwrap_or(&self.planes[0])
+    }
+
+    /// Size of a mip level in blocks.
+    fn mip_blocks(&self, level: u32) -> (u64, u64) {
+        let size = self.size.mip_level_size(level, self.dimension);
+        let (block_width, block_height) = self.block_dimensions;
+        (
+            ((size.width + block_width - 1) / block_width) as u64,
+            ((size.height + block_height - 1) / block_height) as u64,
+        )
+    }
+
+    /// Size in bytes of a row of blocks of a copy region, and the number of
+    /// rows in every image.
+    fn row_layout(&self, plane: &Plane, size: &crate::CopyExtent) -> (u64, u32) {
+        let (block_width, block_height) = self.block_dimensions;
+        (
+            ((size.width + block_width - 1) / block_width) as u64 * plane.block_size as u64,
+            (size.height + block_height - 1) / block_height,
+        )
+    }
+
+    /// Byte offset in `plane` of row `y` of image `z` of a copy region.
+    fn row_offset(&self, plane: &Plane, base: &crate::TextureCopyBase, y: u32, z: u32) -> u64 {
+        let (block_width, block_height) = self.block_dimensions;
+        let (blocks_wide, blocks_high) = self.mip_blocks(base.mip_level);
+        let image = match self.dimension {
+            wgt::TextureDimension::D3 => base.origin.z,
+            _ => base.array_layer,
+        } + z;
+        let row = image as u64 * blocks_high + (base.origin.y / block_height + y) as u64;
+        plane.mip_offsets[base.mip_level as usize]
+            + (row * blocks_wide + (base.origin.x / block_width) as u64) * plane.block_size as u64
+    }
+}
+
+/// Rows of blocks of a copy region, as `(row, image)` pairs.
+fn rows(rows_per_image: u32, depth: u32) -> impl Iterator<Item = (u32, u32)> {
+    (0..depth).flat_map(move |z| (0..rows_per_image).map(move |y| (y, z)))
+}
+
+#[derive(Debug)]
+pub struct Texture {
+    inner: Arc<TextureInner>,
+}
+
+#[derive(Debug)]
+pub struct QuerySet {
+    memory: Arc<Memory>,
+    /// Size of the result of one query.
+    result_size: u64,
+}
+
+#[derive(Debug)]
+pub struct Fence {
+    value: crate::FenceValue,
+}
+
+#[derive(Debug)]
+enum Command {
+    ClearBuffer {
+        memory: Arc<Memory>,
+        range: crate::MemoryRange,
+    },
+    CopyBufferToBuffer {
+        src: Arc<Memory>,
+        dst: Arc<Memory>,
+        regions: Vec<crate::BufferCopy>,
+    },
+    CopyTextureToTexture {
+        src: Arc<TextureInner>,
+        dst: Arc<TextureInner>,
+        regions: Vec<crate::TextureCopy>,
+    },
+    CopyBufferToTexture {
+        src: Arc<Memory>,
+        dst: Arc<TextureInner>,
+        regions: Vec<crate::BufferTextureCopy>,
+    },
+    CopyTextureToBuffer {
+        src: Arc<TextureInner>,
+        dst: Arc<Memory>,
+        regions: Vec<crate::BufferTextureCopy>,
+    },
+    /// Writes the result of an occlusion or pipeline statistics query. Nothing
+    /// is ever drawn, so all the counters are zero.
+    EndQuery {
+        set: Arc<Memory>,
+        offset: u64,
+        size: u64,
+    },
+    WriteTimestamp {
+        set: Arc<Memory>,
+        offset: u64,
+    },
+    CopyQueryResults {
+        set: Arc<Memory>,
+        range: crate::MemoryRange,
+        result_size: u64,
+        dst: Arc<Memory>,
+        offset: wgt::BufferAddress,
+        stride: u64,
+    },
+}
+
+#[derive(Debug, Default)]
+pub struct Encoder {
+    commands: Vec<Command>,
+}
+
+#[derive(Debug)]
+pub struct CommandBuffer {
+    commands: Vec<Command>,
+}
+
+impl Queue {
+    fn execute(&self, command: &Command) {
+        match *command {
+            Command::ClearBuffer {
+                ref memory,
+                ref range,
+            } => memory.fill(range.clone(), 0),
+            Command::CopyBufferToBuffer {
+                ref src,
+                ref dst,
+                ref regions,
+            } => {
+                for region in regions {
+                    Memory::copy(
+                        src,
+                        region.src_offset,
+                        dst,
+                        region.dst_offset,
+                        region.size.get(),
+                    );
+                }
+            }
+            Command::CopyTextureToTexture {
+                ref src,
+                ref dst,
+                ref regions,
+            } => {
+                for region in regions {
+                    let src_plane = src.plane(region.src_base.aspect);
+                    let dst_plane = dst.plane(region.dst_base.aspect);
+                    let (row_size, rows_per_image) = src.row_layout(src_plane, &region.size);
+                    for (y, z) in rows(rows_per_image, region.size.depth) {
+                        Memory::copy(
+                            &src_plane.memory,
+                            src.row_offset(src_plane, &region.src_base, y, z),
+                            &dst_plane.memory,
+                            dst.row_offset(dst_plane, &region.dst_base, y, z),
+                            row_size,
+                        );
+                    }
+                }
+            }
+            Command::CopyBufferToTexture {
+                ref src,
+                ref dst,
+                ref regions,
+            } => {
+                for region in regions {
+                    let plane = dst.plane(region.texture_base.aspect);
+                    let (row_size, rows_per_image) = dst.row_layout(plane, &region.size);
+                    for (y, z) in rows(rows_per_image, region.size.depth) {
+                        Memory::copy(
+                            src,
+                            buffer_row_offset(region, row_size, rows_per_image, y, z),
+                            &plane.memory,
+                            dst.row_offset(plane, &region.texture_base, y, z),
+                            row_size,
+                        );
+                    }
+                }
+            }
+            Command::CopyTextureToBuffer {
+                ref src,
+                ref dst,
+                ref regions,
+            } => {
+                for region in regions {
+                    let plane = src.plane(region.texture_base.aspect);
+                    let (row_size, rows_per_image) = src.row_layout(plane, &region.size);
+                    for (y, z) in rows(rows_per_image, region.size.depth) {
+                        Memory::copy(
+                            &plane.memory,
+                            src.row_offset(plane, &region.texture_base, y, z),
+                            dst,
+                            buffer_row_offset(region, row_size, rows_per_image, y, z),
+                            row_size,
+                        );
+                    }
+                }
+            }
+            Command::EndQuery {
+                ref set,
+                offset,
+                size,
+            } => set.fill(offset..offset + size, 0),
+            Command::WriteTimestamp { ref set, offset } => {
+                let nanoseconds = self.epoch.elapsed().as_nanos() as u64;
+                set.write(offset, &nanoseconds.to_le_bytes());
+            }
+            Command::CopyQueryResults {
+                ref set,
+                ref range,
+                result_size,
+                ref dst,
+                offset,
+                stride,
+            } => {
+                for (i, query) in range.clone().enumerate() {
+                    Memory::copy(
+                        set,
+                        query * result_size,
+                        dst,
+                        offset + i as u64 * stride,
+                        result_size,
+                    );
+                }
+            }
+        }
+    }
 }
 
 impl crate::Instance<Api> for Context {
@@ -51,7 +322,37 @@ impl crate::Instance<Api> for Context {
     }
     unsafe fn destroy_surface(&self, surface: Context) {}
     unsafe fn enumerate_adapters(&self) -> Vec<crate::ExposedAdapter<Api>> {
-        Vec::new()
+        let features = wgt::Features::DEPTH32FLOAT_STENCIL8
+            | wgt::Features::TIMESTAMP_QUERY
+            | wgt::Features::TIMESTAMP_QUERY_INSIDE_PASSES
+            | wgt::Features::PIPELINE_STATISTICS_QUERY
+            | wgt::Features::TEXTURE_COMPRESSION_BC
+            | wgt::Features::TEXTURE_COMPRESSION_ETC2
+            | wgt::Features::TEXTURE_COMPRESSION_ASTC
+            | wgt::Features::TEXTURE_FORMAT_16BIT_NORM
+            | wgt::Features::MAPPABLE_PRIMARY_BUFFERS
+            | wgt::Features::CLEAR_TEXTURE;
+        vec![crate::ExposedAdapter {
+            adapter: Context,
+            info: wgt::AdapterInfo {
+                name: String::from("Empty"),
+                vendor: 0,
+                device: 0,
+                device_type: wgt::DeviceType::Other,
+                driver: String::new(),
+                driver_info: String::new(),
+                backend: wgt::Backend::Empty,
+            },
+            features,
+            capabilities: crate::Capabilities {
+                limits: wgt::Limits::default(),
+                alignments: crate::Alignments {
+                    buffer_copy_offset: wgt::BufferSize::new(4).unwrap(),
+                    buffer_copy_pitch: wgt::BufferSize::new(4).unwrap(),
+                },
+                downlevel: wgt::DownlevelCapabilities::default(),
+            },
+        }]
     }
 }
 
@@ -72,7 +373,7 @@ impl crate::Surface<Api> for Context {
     ) -> Result<Option<crate::AcquiredSurfaceTexture<Api>>, crate::SurfaceError> {
         Ok(None)
     }
-    unsafe fn discard_texture(&mut self, texture: Resource) {}
+    unsafe fn discard_texture(&mut self, texture: Texture) {}
 }
 
 impl crate::Adapter<Api> for Context {
@@ -80,14 +381,20 @@ impl crate::Adapter<Api> for Context {
         &self,
         features: wgt::Features,
         _limits: &wgt::Limits,
+        _memory_hints: &wgt::MemoryHints,
     ) -> DeviceResult<crate::OpenDevice<Api>> {
-        Err(crate::DeviceError::Lost)
+        Ok(crate::OpenDevice {
+            device: Context,
+            queue: Queue {
+                epoch: Instant::now(),
+            },
+        })
     }
     unsafe fn texture_format_capabilities(
         &self,
         format: wgt::TextureFormat,
     ) -> crate::TextureFormatCapabilities {
-        crate::TextureFormatCapabilities::empty()
+        crate::TextureFormatCapabilities::COPY_SRC | crate::TextureFormatCapabilities::COPY_DST
     }
 
     unsafe fn surface_capabilities(&self, surface: &Context) -> Option<crate::SurfaceCapabilities> {
@@ -99,53 +406,128 @@ impl crate::Adapter<Api> for Context {
     }
 }
 
-impl crate::Queue<Api> for Context {
+impl crate::Queue<Api> for Queue {
     unsafe fn submit(
         &mut self,
-        command_buffers: &[&Resource],
-        signal_fence: Option<(&mut Resource, crate::FenceValue)>,
+        command_buffers: &[&CommandBuffer],
+        signal_fence: Option<(&mut Fence, crate::FenceValue)>,
     ) -> DeviceResult<()> {
+        for cmd_buf in command_buffers {
+            for command in cmd_buf.commands.iter() {
+                self.execute(command);
+            }
+        }
+        if let Some((fence, value)) = signal_fence {
+            fence.value = value;
+        }
         Ok(())
     }
+    unsafe fn submit_with_sync(
+        &mut self,
+        command_buffers: &[&CommandBuffer],
+        waits: &[(&Resource, u64)],
+        signals: &[(&Resource, u64)],
+        signal_fence: Option<(&mut Fence, crate::FenceValue)>,
+    ) -> DeviceResult<()> {
+        unsafe { crate::Queue::submit(self, command_buffers, signal_fence) }
+    }
     unsafe fn present(
         &mut self,
         surface: &mut Context,
-        texture: Resource,
+        texture: Texture,
     ) -> Result<(), crate::SurfaceError> {
         Ok(())
     }
 
+    unsafe fn bind_sparse(
+        &mut self,
+        buffer_bindings: &[crate::SparseBufferBinding<Api>],
+        texture_bindings: &[crate::SparseTextureBinding<Api>],
+        signal_fence: Option<(&mut Fence, crate::FenceValue)>,
+    ) -> Result<(), crate::DeviceError> {
+        if let Some((fence, value)) = signal_fence {
+            fence.value = value;
+        }
+        Ok(())
+    }
+
     unsafe fn get_timestamp_period(&self) -> f32 {
         1.0
     }
 }
 
 impl crate::Device<Api> for Context {
-    unsafe fn exit(self, queue: Context) {}
-    unsafe fn create_buffer(&self, desc: &crate::BufferDescriptor) -> DeviceResult<Resource> {
-        Ok(Resource)
+    unsafe fn exit(self, queue: Queue) {}
+    unsafe fn create_buffer(&self, desc: &crate::BufferDescriptor) -> DeviceResult<Buffer> {
+        Ok(Buffer {
+            memory: Arc::new(Memory::new(desc.size)?),
+        })
     }
-    unsafe fn destroy_buffer(&self, buffer: Resource) {}
+    unsafe fn destroy_buffer(&self, buffer: Buffer) {}
     unsafe fn map_buffer(
         &self,
-        buffer: &Resource,
+        buffer: &Buffer,
         range: crate::MemoryRange,
     ) -> DeviceResult<crate::BufferMapping> {
-        Err(crate::DeviceError::Lost)
+        Ok(crate::BufferMapping {
+            ptr: buffer.memory.ptr_at(range.start),
+            is_coherent: true,
+        })
     }
-    unsafe fn unmap_buffer(&self, buffer: &Resource) -> DeviceResult<()> {
+    unsafe fn unmap_buffer(&self, buffer: &Buffer) -> DeviceResult<()> {
         Ok(())
     }
-    unsafe fn flush_mapped_ranges<I>(&self, buffer: &Resource, ranges: I) {}
-    unsafe fn invalidate_mapped_ranges<I>(&self, buffer: &Resource, ranges: I) {}
+    unsafe fn flush_mapped_ranges<I>(&self, buffer: &Buffer, ranges: I) {}
+    unsafe fn invalidate_mapped_ranges<I>(&self, buffer: &Buffer, ranges: I) {}
 
-    unsafe fn create_texture(&self, desc: &crate::TextureDescriptor) -> DeviceResult<Resource> {
-        Ok(Resource)
+    unsafe fn create_texture(&self, desc: &crate::TextureDescriptor) -> DeviceResult<Texture> {
+        let format = desc.format;
+        let (block_width, block_height) = format.block_dimensions();
+        let mut planes = Vec::new();
+        for aspects in [
+            crate::FormatAspects::COLOR,
+            crate::FormatAspects::DEPTH,
+            crate::FormatAspects::STENCIL,
+        ] {
+            if !crate::FormatAspects::from(format).contains(aspects) {
+                continue;
+            }
+            // Formats that can't be copied, like `Depth24Plus`, still get
+            // some storage.
+            let block_size = format.block_size(Some(aspects.map())).unwrap_or(4);
+            let mut mip_offsets = Vec::with_capacity(desc.mip_level_count as usize);
+            let mut size = 0;
+            for level in 0..desc.mip_level_count {
+                mip_offsets.push(size);
+                // Array layers are kept, only the depth of 3D textures shrinks.
+                let extent = desc.size.mip_level_size(level, desc.dimension);
+                let blocks_wide = (extent.width + block_width - 1) / block_width;
+                let blocks_high = (extent.height + block_height - 1) / block_height;
+                size += blocks_wide as u64
+                    * blocks_high as u64
+                    * extent.depth_or_array_layers as u64
+                    * block_size as u64;
+            }
+            planes.push(Plane {
+                aspects,
+                memory: Memory::new(size)?,
+                block_size,
+                mip_offsets,
+            });
+        }
+        Ok(Texture {
+            inner: Arc::new(TextureInner {
+                dimension: desc.dimension,
+                size: desc.size,
+                block_dimensions: (block_width, block_height),
+                planes,
+            }),
+        })
     }
-    unsafe fn destroy_texture(&self, texture: Resource) {}
+    unsafe fn destroy_texture(&self, texture: Texture) {}
     unsafe fn create_texture_view(
         &self,
-        texture: &Resource,
+        texture: &Texture,
         desc: &crate::TextureViewDescriptor,
     ) -> DeviceResult<Resource> {
         Ok(Resource)
@@ -156,11 +538,52 @@ impl crate::Device<Api> for Context {
     }
     unsafe fn destroy_sampler(&self, sampler: Resource) {}
 
+    // Placed resources get memory of their own, so they never alias.
+    unsafe fn create_heap(&self, desc: &crate::HeapDescriptor) -> DeviceResult<Resource> {
+        Ok(Resource)
+    }
+    unsafe fn destroy_heap(&self, heap: Resource) {}
+    unsafe fn create_buffer_in_heap(
+        &self,
+        desc: &crate::BufferDescriptor,
+        heap: &Resource,
+        offset: wgt::BufferAddress,
+    ) -> Result<crate::PlacedResource<Buffer>, crate::HeapPlacementError> {
+        Ok(crate::PlacedResource {
+            raw: unsafe { self.create_buffer(desc) }?,
+            size: desc.size,
+        })
+    }
+    unsafe fn create_texture_in_heap(
+        &self,
+        desc: &crate::TextureDescriptor,
+        heap: &Resource,
+        offset: wgt::BufferAddress,
+    ) -> Result<crate::PlacedResource<Texture>, crate::HeapPlacementError> {
+        let raw = unsafe { self.create_texture(desc) }?;
+        let size = raw
+            .inner
+            .planes
+            .iter()
+            .map(|plane| plane.memory.len())
+            .sum();
+        Ok(crate::PlacedResource { raw, size })
+    }
+
+    // Submissions complete right away, so waits and signals are no-ops.
+    unsafe fn create_external_semaphore(
+        &self,
+        desc: &crate::ExternalSemaphoreDescriptor,
+    ) -> DeviceResult<Resource> {
+        Ok(Resource)
+    }
+    unsafe fn destroy_external_semaphore(&self, semaphore: Resource) {}
+
     unsafe fn create_command_encoder(
         &self,
         desc: &crate::CommandEncoderDescriptor<Api>,
     ) -> DeviceResult<Encoder> {
-        Ok(Encoder)
+        Ok(Encoder::default())
     }
     unsafe fn destroy_command_encoder(&self, encoder: Encoder) {}
 
@@ -208,43 +631,82 @@ impl crate::Device<Api> for Context {
         Ok(Resource)
     }
     unsafe fn destroy_compute_pipeline(&self, pipeline: Resource) {}
+    unsafe fn create_pipeline_cache(
+        &self,
+        desc: &crate::PipelineCacheDescriptor<'_>,
+    ) -> Result<Resource, crate::PipelineCacheError> {
+        Ok(Resource)
+    }
+    unsafe fn destroy_pipeline_cache(&self, cache: Resource) {}
 
     unsafe fn create_query_set(
         &self,
         desc: &wgt::QuerySetDescriptor<crate::Label>,
-    ) -> DeviceResult<Resource> {
-        Ok(Resource)
+    ) -> DeviceResult<QuerySet> {
+        let result_size = match desc.ty {
+            wgt::QueryType::PipelineStatistics(types) => 8 * types.bits().count_ones() as u64,
+            _ => 8,
+        };
+        Ok(QuerySet {
+            memory: Arc::new(Memory::new(desc.count as u64 * result_size)?),
+            result_size,
+        })
     }
-    unsafe fn destroy_query_set(&self, set: Resource) {}
-    unsafe fn create_fence(&self) -> DeviceResult<Resource> {
-        Ok(Resource)
+    unsafe fn destroy_query_set(&self, set: QuerySet) {}
+    unsafe fn create_fence(&self) -> DeviceResult<Fence> {
+        Ok(Fence { value: 0 })
     }
-    unsafe fn destroy_fence(&self, fence: Resource) {}
-    unsafe fn get_fence_value(&self, fence: &Resource) -> DeviceResult<crate::FenceValue> {
-        Ok(0)
+    unsafe fn destroy_fence(&self, fence: Fence) {}
+    unsafe fn get_fence_value(&self, fence: &Fence) -> DeviceResult<crate::FenceValue> {
+        Ok(fence.value)
     }
     unsafe fn wait(
         &self,
-        fence: &Resource,
+        fence: &Fence,
         value: crate::FenceValue,
         timeout_ms: u32,
     ) -> DeviceResult<bool> {
-        Ok(true)
+        // Submissions are executed by `submit`, so there is nothing to wait for.
+        Ok(fence.value >= value)
     }
 
     unsafe fn start_capture(&self) -> bool {
         false
     }
     unsafe fn stop_capture(&self) {}
+
+    unsafe fn create_acceleration_structure(
+        &self,
+        desc: &crate::AccelerationStructureDescriptor,
+    ) -> DeviceResult<Resource> {
+        Ok(Resource)
+    }
+    unsafe fn get_acceleration_structure_build_sizes<'a>(
+        &self,
+        desc: &crate::GetAccelerationStructureBuildSizesDescriptor<'a, Api>,
+    ) -> crate::AccelerationStructureBuildSizes {
+        Default::default()
+    }
+    unsafe fn get_acceleration_structure_device_address(
+        &self,
+        acceleration_structure: &Resource,
+    ) -> wgt::BufferAddress {
+        Default::default()
+    }
+    unsafe fn destroy_acceleration_structure(&self, acceleration_structure: Resource) {}
 }
 
 impl crate::CommandEncoder<Api> for Encoder {
     unsafe fn begin_encoding(&mut self, label: crate::Label) -> DeviceResult<()> {
         Ok(())
     }
-    unsafe fn discard_encoding(&mut self) {}
-    unsafe fn end_encoding(&mut self) -> DeviceResult<Resource> {
-        Ok(Resource)
+    unsafe fn discard_encoding(&mut self) {
+        self.commands.clear();
+    }
+    unsafe fn end_encoding(&mut self) -> DeviceResult<CommandBuffer> {
+        Ok(CommandBuffer {
+            commands: std::mem::take(&mut self.commands),
+        })
     }
     unsafe fn reset_all<I>(&mut self, command_buffers: I) {}
 
@@ -260,15 +722,29 @@ impl crate::CommandEncoder<Api> for Encoder {
     {
     }
 
-    unsafe fn clear_buffer(&mut self, buffer: &Resource, range: crate::MemoryRange) {}
+    unsafe fn clear_buffer(&mut self, buffer: &Buffer, range: crate::MemoryRange) {
+        self.commands.push(Command::ClearBuffer {
+            memory: Arc::clone(&buffer.memory),
+            range,
+        });
+    }
 
-    unsafe fn copy_buffer_to_buffer<T>(&mut self, src: &Resource, dst: &Resource, regions: T) {}
+    unsafe fn copy_buffer_to_buffer<T>(&mut self, src: &Buffer, dst: &Buffer, regions: T)
+    where
+        T: Iterator<Item = crate::BufferCopy>,
+    {
+        self.commands.push(Command::CopyBufferToBuffer {
+            src: Arc::clone(&src.memory),
+            dst: Arc::clone(&dst.memory),
+            regions: regions.collect(),
+        });
+    }
 
     #[cfg(all(target_arch = "wasm32", not(target_os = "emscripten")))]
     unsafe fn copy_external_image_to_texture<T>(
         &mut self,
         src: &wgt::ImageCopyExternalImage,
-        dst: &Resource,
+        dst: &Texture,
         dst_premultiplication: bool,
         regions: T,
     ) where
@@ -278,36 +754,83 @@ impl crate::CommandEncoder<Api> for Encoder {
 
     unsafe fn copy_texture_to_texture<T>(
         &mut self,
-        src: &Resource,
+        src: &Texture,
         src_usage: crate::TextureUses,
-        dst: &Resource,
+        dst: &Texture,
         regions: T,
-    ) {
+    ) where
+        T: Iterator<Item = crate::TextureCopy>,
+    {
+        self.commands.push(Command::CopyTextureToTexture {
+            src: Arc::clone(&src.inner),
+            dst: Arc::clone(&dst.inner),
+            regions: regions.collect(),
+        });
     }
 
-    unsafe fn copy_buffer_to_texture<T>(&mut self, src: &Resource, dst: &Resource, regions: T) {}
+    unsafe fn copy_buffer_to_texture<T>(&mut self, src: &Buffer, dst: &Texture, regions: T)
+    where
+        T: Iterator<Item = crate::BufferTextureCopy>,
+    {
+        self.commands.push(Command::CopyBufferToTexture {
+            src: Arc::clone(&src.memory),
+            dst: Arc::clone(&dst.inner),
+            regions: regions.collect(),
+        });
+    }
 
     unsafe fn copy_texture_to_buffer<T>(
         &mut self,
-        src: &Resource,
+        src: &Texture,
         src_usage: crate::TextureUses,
-        dst: &Resource,
+        dst: &Buffer,
         regions: T,
-    ) {
+    ) where
+        T: Iterator<Item = crate::BufferTextureCopy>,
+    {
+        self.commands.push(Command::CopyTextureToBuffer {
+            src: Arc::clone(&src.inner),
+            dst: Arc::clone(&dst.memory),
+            regions: regions.collect(),
+        });
     }
 
-    unsafe fn begin_query(&mut self, set: &Resource, index: u32) {}
-    unsafe fn end_query(&mut self, set: &Resource, index: u32) {}
-    unsafe fn write_timestamp(&mut self, set: &Resource, index: u32) {}
-    unsafe fn reset_queries(&mut self, set: &Resource, range: Range<u32>) {}
+    unsafe fn begin_query(&mut self, set: &QuerySet, index: u32) {}
+    unsafe fn end_query(&mut self, set: &QuerySet, index: u32) {
+        self.commands.push(Command::EndQuery {
+            set: Arc::clone(&set.memory),
+            offset: index as u64 * set.result_size,
+            size: set.result_size,
+        });
+    }
+    unsafe fn write_timestamp(&mut self, set: &QuerySet, index: u32) {
+        self.commands.push(Command::WriteTimestamp {
+            set: Arc::clone(&set.memory),
+            offset: index as u64 * set.result_size,
+        });
+    }
+    unsafe fn reset_queries(&mut self, set: &QuerySet, range: Range<u32>) {
+        self.commands.push(Command::ClearBuffer {
+            memory: Arc::clone(&set.memory),
+            range: range.start as u64 * set.result_size..range.end as u64 * set.result_size,
+        });
+    }
     unsafe fn copy_query_results(
         &mut self,
-        set: &Resource,
+        set: &QuerySet,
         range: Range<u32>,
-        buffer: &Resource,
+        buffer: &Buffer,
         offset: wgt::BufferAddress,
         stride: wgt::BufferSize,
     ) {
+        self.commands.push(Command::CopyQueryResults {
+            set: Arc::clone(&set.memory),
+            range: range.start as u64..range.end as u64,
+            result_size: set.result_size,
+            dst: Arc::clone(&buffer.memory),
+            offset,
+            stride: stride.get(),
+        });
     }
 
     // render
@@ -370,32 +893,32 @@ impl crate::CommandEncoder<Api> for Encoder {
     }
     unsafe fn draw_indirect(
         &mut self,
-        buffer: &Resource,
+        buffer: &Buffer,
         offset: wgt::BufferAddress,
         draw_count: u32,
     ) {
     }
     unsafe fn draw_indexed_indirect(
         &mut self,
-        buffer: &Resource,
+        buffer: &Buffer,
         offset: wgt::BufferAddress,
         draw_count: u32,
     ) {
     }
     unsafe fn draw_indirect_count(
         &mut self,
-        buffer: &Resource,
+        buffer: &Buffer,
         offset: wgt::BufferAddress,
-        count_buffer: &Resource,
+        count_buffer: &Buffer,
         count_offset: wgt::BufferAddress,
         max_count: u32,
     ) {
     }
     unsafe fn draw_indexed_indirect_count(
         &mut self,
-        buffer: &Resource,
+        buffer: &Buffer,
         offset: wgt::BufferAddress,
-        count_buffer: &Resource,
+        count_buffer: &Buffer,
         count_offset: wgt::BufferAddress,
         max_count: u32,
     ) {
@@ -409,5 +932,158 @@ impl crate::CommandEncoder<Api> for Encoder {
     unsafe fn set_compute_pipeline(&mut self, pipeline: &Resource) {}
 
     unsafe fn dispatch(&mut self, count: [u32; 3]) {}
-    unsafe fn dispatch_indirect(&mut self, buffer: &Resource, offset: wgt::BufferAddress) {}
+    unsafe fn dispatch_indirect(&mut self, buffer: &Buffer, offset: wgt::BufferAddress) {}
+
+    unsafe fn build_acceleration_structures<'a, T>(&mut self, descriptor_count: u32, descriptors: T)
+    where
+        Api: 'a,
+        T: IntoIterator<Item = crate::BuildAccelerationStructureDescriptor<'a, Api>>,
+    {
+    }
+
+    unsafe fn place_acceleration_structure_barrier(
+        &mut self,
+        barriers: crate::AccelerationStructureBarrier,
+    ) {
+    }
+}
+
+#[test]
+fn transfers() {
+    use crate::{CommandEncoder as _, Device as _, Instance as _, Queue as _};
+
+    let instance = unsafe {
+        Context::init(&crate::InstanceDescriptor {
+            name: "empty",
+            flags: wgt::InstanceFlags::empty(),
+            dx12_shader_compiler: wgt::Dx12Compiler::Fxc,
+            gles_minor_version: wgt::Gles3MinorVersion::Automatic,
+        })
+    }
+    .unwrap();
+    let exposed = unsafe { instance.enumerate_adapters() }.remove(0);
+    let crate::OpenDevice { device, mut queue } = unsafe {
+        crate::Adapter::open(
+            &exposed.adapter,
+            exposed.features,
+            &wgt::Limits::default(),
+            &wgt::MemoryHints::default(),
+        )
+    }
+    .unwrap();
+
+    let buffer_desc = |size| crate::BufferDescriptor {
+        label: None,
+        size,
+        usage: crate::BufferUses::COPY_SRC | crate::BufferUses::COPY_DST,
+        memory_flags: crate::MemoryFlags::empty(),
+    };
+    let src = unsafe { device.create_buffer(&buffer_desc(64)) }.unwrap();
+    let dst = unsafe { device.create_buffer(&buffer_desc(64)) }.unwrap();
+    let texture = unsafe {
+        device.create_texture(&crate::TextureDescriptor {
+            label: None,
+            size: wgt::Extent3d {
+                width: 4,
+                height: 2,
+                depth_or_array_layers: 2,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgt::TextureDimension::D2,
+            format: wgt::TextureFormat::R8Unorm,
+            usage: crate::TextureUses::COPY_SRC | crate::TextureUses::COPY_DST,
+            memory_flags: crate::MemoryFlags::empty(),
+            view_formats: Vec::new(),
+        })
+    }
+    .unwrap();
+    let query_set = unsafe {
+        device.create_query_set(&wgt::QuerySetDescriptor {
+            label: None,
+            ty: wgt::QueryType::Occlusion,
+            count: 2,
+        })
+    }
+    .unwrap();
+
+    let mapping = unsafe { device.map_buffer(&src, 0..64) }.unwrap();
+    let data: Vec<u8> = (0..64).collect();
+    unsafe { std::ptr::copy_nonoverlapping(data.as_ptr(), mapping.ptr.as_ptr(), 64) };
+
+    let base = crate::TextureCopyBase {
+        mip_level: 0,
+        array_layer: 0,
+        origin: wgt::Origin3d::ZERO,
+        aspect: crate::FormatAspects::COLOR,
+    };
+    let size = crate::CopyExtent {
+        width: 4,
+        height: 2,
+        depth: 2,
+    };
+    // Upload with padded rows, then read back tightly packed.
+    let upload = crate::BufferTextureCopy {
+        buffer_layout: wgt::ImageDataLayout {
+            offset: 0,
+            bytes_per_row: Some(8),
+            rows_per_image: Some(2),
+        },
+        texture_base: base.clone(),
+        size,
+    };
+    let download = crate::BufferTextureCopy {
+        buffer_layout: wgt::ImageDataLayout {
+            offset: 0,
+            bytes_per_row: None,
+            rows_per_image: None,
+        },
+        texture_base: base,
+        size,
+    };
+    let mut encoder = unsafe {
+        device.create_command_encoder(&crate::CommandEncoderDescriptor {
+            label: None,
+            queue: &queue,
+            reusable: false,
+        })
+    }
+    .unwrap();
+    let mut fence = unsafe { device.create_fence() }.unwrap();
+    let cmd_buf = unsafe {
+        encoder.begin_encoding(None).unwrap();
+        encoder.clear_buffer(&dst, 0..64);
+        encoder.copy_buffer_to_buffer(
+            &src,
+            &dst,
+            std::iter::once(crate::BufferCopy {
+                src_offset: 48,
+                dst_offset: 48,
+                size: wgt::BufferSize::new(16).unwrap(),
+            }),
+        );
+        encoder.copy_buffer_to_texture(&src, &texture, std::iter::once(upload));
+        encoder.copy_texture_to_buffer(
+            &texture,
+            crate::TextureUses::COPY_SRC,
+            &dst,
+            std::iter::once(download),
+        );
+        encoder.begin_query(&query_set, 1);
+        encoder.end_query(&query_set, 1);
+        encoder.copy_query_results(&query_set, 1..2, &dst, 48, wgt::BufferSize::new(8).unwrap());
+        encoder.end_encoding().unwrap()
+    };
+    unsafe { queue.submit(&[&cmd_buf], Some((&mut fence, 1))) }.unwrap();
+    assert!(unsafe { device.wait(&fence, 1, 0) }.unwrap());
+
+    let mapping = unsafe { device.map_buffer(&dst, 0..64) }.unwrap();
+    let result = unsafe { std::slice::from_raw_parts(mapping.ptr.as_ptr(), 64) };
+    let texels: Vec<u8> = [0..4, 8..12, 16..20, 24..28]
+        .into_iter()
+        .flatten()
+        .collect();
+    assert_eq!(result[..16], texels[..]);
+    assert_eq!(result[16..56], [0; 40]);
+    assert_eq!(result[56..], data[56..]);
 }
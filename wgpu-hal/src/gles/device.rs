@@ -912,6 +912,37 @@ impl crate::Device<super::Api> for super::Device {
         unsafe { gl.delete_sampler(sampler.raw) };
     }
 
+    // Heaps aren't exposed by this backend.
+    unsafe fn create_heap(&self, _desc: &crate::HeapDescriptor) -> Result<(), crate::DeviceError> {
+        Err(crate::DeviceError::ResourceCreationFailed)
+    }
+    unsafe fn destroy_heap(&self, (): ()) {}
+    unsafe fn create_buffer_in_heap(
+        &self,
+        _desc: &crate::BufferDescriptor,
+        _heap: &(),
+        _offset: wgt::BufferAddress,
+    ) -> Result<crate::PlacedResource<super::Buffer>, crate::HeapPlacementError> {
+        Err(crate::DeviceError::ResourceCreationFailed.into())
+    }
+    unsafe fn create_texture_in_heap(
+        &self,
+        _desc: &crate::TextureDescriptor,
+        _heap: &(),
+        _offset: wgt::BufferAddress,
+    ) -> Result<crate::PlacedResource<super::Texture>, crate::HeapPlacementError> {
+        Err(crate::DeviceError::ResourceCreationFailed.into())
+    }
+
+    // External semaphores aren't exposed by this backend.
+    unsafe fn create_external_semaphore(
+        &self,
+        _desc: &crate::ExternalSemaphoreDescriptor,
+    ) -> Result<(), crate::DeviceError> {
+        Err(crate::DeviceError::ResourceCreationFailed)
+    }
+    unsafe fn destroy_external_semaphore(&self, (): ()) {}
+
     unsafe fn create_command_encoder(
         &self,
         _desc: &crate::CommandEncoderDescriptor<super::Api>,
@@ -975,6 +1006,11 @@ impl crate::Device<super::Api> for super::Device {
                     wgt::BindingType::Sampler { .. } => &mut num_samplers,
                     wgt::BindingType::Texture { .. } => &mut num_textures,
                     wgt::BindingType::StorageTexture { .. } => &mut num_images,
+                    // wgpu-core requires `Features::RAY_QUERY` for this binding type, which
+                    // this backend doesn't expose.
+                    wgt::BindingType::AccelerationStructure => {
+                        unreachable!("RAY_QUERY is not exposed on this backend")
+                    }
                     wgt::BindingType::Buffer {
                         ty: wgt::BufferBindingType::Uniform,
                         ..
@@ -1048,6 +1084,11 @@ impl crate::Device<super::Api> for super::Device {
                         aspects: view.aspects,
                     }
                 }
+                // wgpu-core requires `Features::RAY_QUERY` for this binding type, which
+                // this backend doesn't expose.
+                wgt::BindingType::AccelerationStructure => {
+                    unreachable!("RAY_QUERY is not exposed on this backend")
+                }
                 wgt::BindingType::StorageTexture {
                     access,
                     format,
@@ -1208,6 +1249,14 @@ impl crate::Device<super::Api> for super::Device {
         }
     }
 
+    unsafe fn create_pipeline_cache(
+        &self,
+        _desc: &crate::PipelineCacheDescriptor<'_>,
+    ) -> Result<(), crate::PipelineCacheError> {
+        Ok(())
+    }
+    unsafe fn destroy_pipeline_cache(&self, (): ()) {}
+
     #[cfg_attr(target_arch = "wasm32", allow(unused))]
     unsafe fn create_query_set(
         &self,
@@ -1319,6 +1368,29 @@ impl crate::Device<super::Api> for super::Device {
                 .end_frame_capture(ptr::null_mut(), ptr::null_mut())
         }
     }
+
+    // Acceleration structures aren't exposed by this backend. wgpu-core requires
+    // `Features::RAY_TRACING_ACCELERATION_STRUCTURE` before asking for build sizes,
+    // and only asks for the address of acceleration structures it has created.
+    unsafe fn create_acceleration_structure(
+        &self,
+        _desc: &crate::AccelerationStructureDescriptor,
+    ) -> Result<(), crate::DeviceError> {
+        Err(crate::DeviceError::ResourceCreationFailed)
+    }
+    unsafe fn get_acceleration_structure_build_sizes<'a>(
+        &self,
+        _desc: &crate::GetAccelerationStructureBuildSizesDescriptor<'a, super::Api>,
+    ) -> crate::AccelerationStructureBuildSizes {
+        unreachable!("RAY_TRACING_ACCELERATION_STRUCTURE is not exposed on this backend")
+    }
+    unsafe fn get_acceleration_structure_device_address(
+        &self,
+        _acceleration_structure: &(),
+    ) -> wgt::BufferAddress {
+        unreachable!("RAY_TRACING_ACCELERATION_STRUCTURE is not exposed on this backend")
+    }
+    unsafe fn destroy_acceleration_structure(&self, _acceleration_structure: ()) {}
 }
 
 #[cfg(all(
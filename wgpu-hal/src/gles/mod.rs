@@ -126,6 +126,11 @@ impl crate::Api for Api {
     type ShaderModule = ShaderModule;
     type RenderPipeline = RenderPipeline;
     type ComputePipeline = ComputePipeline;
+    type PipelineCache = ();
+    type Heap = ();
+    type ExternalSemaphore = ();
+
+    type AccelerationStructure = ();
 }
 
 bitflags::bitflags! {
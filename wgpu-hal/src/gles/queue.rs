@@ -1438,6 +1438,17 @@ impl crate::Queue<super::Api> for super::Queue {
         Ok(())
     }
 
+    // No external semaphore can be created, so there is nothing to wait on or signal.
+    unsafe fn submit_with_sync(
+        &mut self,
+        command_buffers: &[&super::CommandBuffer],
+        _waits: &[(&(), u64)],
+        _signals: &[(&(), u64)],
+        signal_fence: Option<(&mut super::Fence, crate::FenceValue)>,
+    ) -> Result<(), crate::DeviceError> {
+        unsafe { crate::Queue::submit(self, command_buffers, signal_fence) }
+    }
+
     unsafe fn present(
         &mut self,
         surface: &mut super::Surface,
@@ -1452,6 +1463,16 @@ impl crate::Queue<super::Api> for super::Queue {
         unsafe { surface.present(texture, gl) }
     }
 
+    // Sparse resources aren't exposed by this backend.
+    unsafe fn bind_sparse(
+        &mut self,
+        _buffer_bindings: &[crate::SparseBufferBinding<super::Api>],
+        _texture_bindings: &[crate::SparseTextureBinding<super::Api>],
+        _signal_fence: Option<(&mut super::Fence, crate::FenceValue)>,
+    ) -> Result<(), crate::DeviceError> {
+        Err(crate::DeviceError::ResourceCreationFailed)
+    }
+
     unsafe fn get_timestamp_period(&self) -> f32 {
         1.0
     }
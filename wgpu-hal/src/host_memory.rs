@@ -0,0 +1,122 @@
+//! Host memory shared by the backends that keep resources in system memory.
+
+use std::{ops::Range, ptr::NonNull};
+
+/// Zero-initialized host allocation.
+///
+/// The contents are only ever accessed through raw pointers, so mapped
+/// pointers handed out to the user don't alias any Rust reference.
+pub(crate) struct Memory {
+    ptr: NonNull<u8>,
+    size: usize,
+}
+
+unsafe impl Send for Memory {}
+unsafe impl Sync for Memory {}
+
+impl Memory {
+    pub fn new(size: u64) -> Result<Self, crate::DeviceError> {
+        let size = usize::try_from(size).map_err(|_| crate::DeviceError::OutOfMemory)?;
+        let mut data = Vec::new();
+        data.try_reserve_exact(size)
+            .map_err(|_| crate::DeviceError::OutOfMemory)?;
+        data.resize(size, 0u8);
+        let data = Box::into_raw(data.into_boxed_slice());
+        Ok(Self {
+            ptr: NonNull::new(data.cast::<u8>()).unwrap(),
+            size,
+        })
+    }
+
+    pub fn len(&self) -> u64 {
+        self.size as u64
+    }
+
+    /// Pointer to the byte at `offset`, clamped to the end of the allocation.
+    pub fn ptr_at(&self, offset: u64) -> NonNull<u8> {
+        let offset = self.clamp(offset, 0).start;
+        unsafe { NonNull::new_unchecked(self.ptr.as_ptr().add(offset)) }
+    }
+
+    /// Clamps `offset..offset + size` to the allocation.
+    fn clamp(&self, offset: u64, size: u64) -> Range<usize> {
+        let start = offset.min(self.len());
+        let end = start.saturating_add(size).min(self.len());
+        start as usize..end as usize
+    }
+
+    /// Reads `dst.len()` bytes at `offset`. Bytes past the end read as zero.
+    #[cfg_attr(not(feature = "cpu"), allow(dead_code))]
+    pub fn read(&self, offset: u64, dst: &mut [u8]) {
+        let range = self.clamp(offset, dst.len() as u64);
+        let count = range.len();
+        unsafe {
+            std::ptr::copy_nonoverlapping(
+                self.ptr.as_ptr().add(range.start),
+                dst.as_mut_ptr(),
+                count,
+            )
+        };
+        dst[count..].fill(0);
+    }
+
+    /// Writes `src` at `offset`. Bytes past the end are dropped.
+    pub fn write(&self, offset: u64, src: &[u8]) {
+        let range = self.clamp(offset, src.len() as u64);
+        unsafe {
+            std::ptr::copy_nonoverlapping(
+                src.as_ptr(),
+                self.ptr.as_ptr().add(range.start),
+                range.len(),
+            )
+        };
+    }
+
+    pub fn fill(&self, range: crate::MemoryRange, value: u8) {
+        let range = self.clamp(range.start, range.end.saturating_sub(range.start));
+        unsafe { std::ptr::write_bytes(self.ptr.as_ptr().add(range.start), value, range.len()) };
+    }
+
+    /// Copies `size` bytes between two (possibly identical) allocations.
+    pub fn copy(src: &Self, src_offset: u64, dst: &Self, dst_offset: u64, size: u64) {
+        let src_range = src.clamp(src_offset, size);
+        let dst_range = dst.clamp(dst_offset, src_range.len() as u64);
+        unsafe {
+            std::ptr::copy(
+                src.ptr.as_ptr().add(src_range.start),
+                dst.ptr.as_ptr().add(dst_range.start),
+                dst_range.len(),
+            )
+        };
+    }
+}
+
+impl Drop for Memory {
+    fn drop(&mut self) {
+        let data = std::ptr::slice_from_raw_parts_mut(self.ptr.as_ptr(), self.size);
+        drop(unsafe { Box::from_raw(data) });
+    }
+}
+
+impl std::fmt::Debug for Memory {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Memory").field("size", &self.size).finish()
+    }
+}
+
+/// Byte offset of row `y` of image `z` in a buffer used by a texture copy.
+///
+/// `row_size` and `rows_per_image` are used when the buffer layout leaves
+/// them out, so they describe the tightly packed copy.
+pub(crate) fn buffer_row_offset(
+    copy: &crate::BufferTextureCopy,
+    row_size: u64,
+    rows_per_image: u32,
+    y: u32,
+    z: u32,
+) -> u64 {
+    let layout = &copy.buffer_layout;
+    let bytes_per_row = layout.bytes_per_row.map_or(row_size, u64::from);
+    let rows_per_image = layout.rows_per_image.unwrap_or(rows_per_image);
+    layout.offset + (z as u64 * rows_per_image as u64 + y as u64) * bytes_per_row
+}
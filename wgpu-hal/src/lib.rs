@@ -51,6 +51,9 @@
     clippy::pattern_type_mismatch,
 )]
 
+/// CPU software rasterizer.
+#[cfg(feature = "cpu")]
+pub mod cpu;
 /// DirectX11 API internals.
 #[cfg(all(feature = "dx11", windows))]
 pub mod dx11;
@@ -70,7 +73,10 @@ pub mod metal;
 pub mod vulkan;
 
 pub mod auxil;
+mod host_memory;
 pub mod api {
+    #[cfg(feature = "cpu")]
+    pub use super::cpu::Api as Cpu;
     #[cfg(all(feature = "dx11", windows))]
     pub use super::dx11::Api as Dx11;
     #[cfg(all(feature = "dx12", windows))]
@@ -140,6 +146,24 @@ pub enum PipelineError {
     Device(#[from] DeviceError),
 }
 
+#[derive(Clone, Debug, Eq, PartialEq, Error)]
+pub enum PipelineCacheError {
+    #[error(transparent)]
+    Device(#[from] DeviceError),
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Error)]
+pub enum HeapPlacementError {
+    #[error("Offset must be a multiple of {0}")]
+    Misaligned(wgt::BufferAddress),
+    #[error("The resource needs {0} bytes, which don't fit in the heap at the given offset")]
+    OutOfBounds(wgt::BufferAddress),
+    #[error("The resource can't be placed in the memory of the heap")]
+    IncompatibleMemory,
+    #[error(transparent)]
+    Device(#[from] DeviceError),
+}
+
 #[derive(Clone, Debug, Eq, PartialEq, Error)]
 pub enum SurfaceError {
     #[error("Surface is lost")]
@@ -213,6 +237,11 @@ pub trait Api: Clone + fmt::Debug + Sized {
     type ShaderModule: fmt::Debug + WasmNotSend + WasmNotSync;
     type RenderPipeline: WasmNotSend + WasmNotSync;
     type ComputePipeline: WasmNotSend + WasmNotSync;
+    type PipelineCache: fmt::Debug + WasmNotSend + WasmNotSync;
+    type Heap: fmt::Debug + WasmNotSend + WasmNotSync;
+    type ExternalSemaphore: fmt::Debug + WasmNotSend + WasmNotSync;
+
+    type AccelerationStructure: fmt::Debug + WasmNotSend + WasmNotSync + 'static;
 }
 
 pub trait Instance<A: Api>: Sized + WasmNotSend + WasmNotSync {
@@ -273,6 +302,7 @@ pub trait Adapter<A: Api>: WasmNotSend + WasmNotSync {
         &self,
         features: wgt::Features,
         limits: &wgt::Limits,
+        memory_hints: &wgt::MemoryHints,
     ) -> Result<OpenDevice<A>, DeviceError>;
 
     /// Return the set of supported capabilities for a texture format.
@@ -328,6 +358,42 @@ pub trait Device<A: Api>: WasmNotSend + WasmNotSync {
     unsafe fn create_sampler(&self, desc: &SamplerDescriptor) -> Result<A::Sampler, DeviceError>;
     unsafe fn destroy_sampler(&self, sampler: A::Sampler);
 
+    /// Allocates device local memory that buffers and textures can be placed in.
+    unsafe fn create_heap(&self, desc: &HeapDescriptor) -> Result<A::Heap, DeviceError>;
+    /// Frees the memory of `heap`.
+    ///
+    /// All the resources placed in it must have been destroyed already.
+    unsafe fn destroy_heap(&self, heap: A::Heap);
+    /// Creates a buffer bound to the memory of `heap` at `offset`.
+    ///
+    /// The buffer can't be mapped. Resources bound to overlapping ranges
+    /// alias each other, the caller is responsible for only using one of
+    /// them at a time.
+    unsafe fn create_buffer_in_heap(
+        &self,
+        desc: &BufferDescriptor,
+        heap: &A::Heap,
+        offset: wgt::BufferAddress,
+    ) -> Result<PlacedResource<A::Buffer>, HeapPlacementError>;
+    /// Creates a texture bound to the memory of `heap` at `offset`.
+    ///
+    /// See [`Device::create_buffer_in_heap`] for how aliasing resources behave.
+    unsafe fn create_texture_in_heap(
+        &self,
+        desc: &TextureDescriptor,
+        heap: &A::Heap,
+        offset: wgt::BufferAddress,
+    ) -> Result<PlacedResource<A::Texture>, HeapPlacementError>;
+
+    /// Creates a timeline semaphore that can be shared with other APIs.
+    ///
+    /// Its value starts at zero.
+    unsafe fn create_external_semaphore(
+        &self,
+        desc: &ExternalSemaphoreDescriptor,
+    ) -> Result<A::ExternalSemaphore, DeviceError>;
+    unsafe fn destroy_external_semaphore(&self, semaphore: A::ExternalSemaphore);
+
     unsafe fn create_command_encoder(
         &self,
         desc: &CommandEncoderDescriptor<A>,
@@ -368,6 +434,35 @@ pub trait Device<A: Api>: WasmNotSend + WasmNotSync {
     ) -> Result<A::ComputePipeline, PipelineError>;
     unsafe fn destroy_compute_pipeline(&self, pipeline: A::ComputePipeline);
 
+    /// Create a driver-level pipeline cache, optionally seeded with `desc.data`.
+    ///
+    /// The data is expected to have been produced by [`Device::pipeline_cache_get_data`]
+    /// on a device with the same [`Device::pipeline_cache_validation_key`]. Callers are
+    /// responsible for checking that key before handing the data over, as drivers are
+    /// not required to reject mismatched blobs gracefully.
+    unsafe fn create_pipeline_cache(
+        &self,
+        desc: &PipelineCacheDescriptor<'_>,
+    ) -> Result<A::PipelineCache, PipelineCacheError>;
+    unsafe fn destroy_pipeline_cache(&self, cache: A::PipelineCache);
+    /// Serialize the current contents of `cache`, if the backend supports it.
+    unsafe fn pipeline_cache_get_data(&self, cache: &A::PipelineCache) -> Option<Vec<u8>> {
+        let _ = cache;
+        None
+    }
+    /// A key identifying which pipeline cache blobs this device can consume.
+    ///
+    /// Returns `None` if the backend does not support pipeline caches.
+    fn pipeline_cache_validation_key(&self) -> Option<[u8; 16]> {
+        None
+    }
+    /// Reports the device memory allocated by this device, and how much of it is in use.
+    ///
+    /// Returns an empty report if the backend does not track its allocations.
+    fn memory_report(&self) -> MemoryReport {
+        MemoryReport::default()
+    }
+
     unsafe fn create_query_set(
         &self,
         desc: &wgt::QuerySetDescriptor<Label>,
@@ -386,6 +481,29 @@ pub trait Device<A: Api>: WasmNotSend + WasmNotSync {
 
     unsafe fn start_capture(&self) -> bool;
     unsafe fn stop_capture(&self);
+
+    unsafe fn create_acceleration_structure(
+        &self,
+        desc: &AccelerationStructureDescriptor,
+    ) -> Result<A::AccelerationStructure, DeviceError>;
+    /// Compute the sizes needed to build an acceleration structure from `desc`.
+    ///
+    /// Only the counts, formats and flags of the entries are considered, their
+    /// buffers may be `None`.
+    unsafe fn get_acceleration_structure_build_sizes(
+        &self,
+        desc: &GetAccelerationStructureBuildSizesDescriptor<A>,
+    ) -> AccelerationStructureBuildSizes;
+    /// The address that instances of a top level acceleration structure use to
+    /// reference this (bottom level) acceleration structure.
+    unsafe fn get_acceleration_structure_device_address(
+        &self,
+        acceleration_structure: &A::AccelerationStructure,
+    ) -> wgt::BufferAddress;
+    unsafe fn destroy_acceleration_structure(
+        &self,
+        acceleration_structure: A::AccelerationStructure,
+    );
 }
 
 pub trait Queue<A: Api>: WasmNotSend + WasmNotSync {
@@ -400,11 +518,39 @@ pub trait Queue<A: Api>: WasmNotSend + WasmNotSync {
         command_buffers: &[&A::CommandBuffer],
         signal_fence: Option<(&mut A::Fence, FenceValue)>,
     ) -> Result<(), DeviceError>;
+    /// Submits the command buffers like [`Queue::submit`], synchronized with
+    /// external semaphores.
+    ///
+    /// The command buffers execute once each semaphore in `waits` reaches at
+    /// least the value it's paired with, and each semaphore in `signals` is set
+    /// to its value once they complete.
+    unsafe fn submit_with_sync(
+        &mut self,
+        command_buffers: &[&A::CommandBuffer],
+        waits: &[(&A::ExternalSemaphore, u64)],
+        signals: &[(&A::ExternalSemaphore, u64)],
+        signal_fence: Option<(&mut A::Fence, FenceValue)>,
+    ) -> Result<(), DeviceError>;
     unsafe fn present(
         &mut self,
         surface: &mut A::Surface,
         texture: A::SurfaceTexture,
     ) -> Result<(), SurfaceError>;
+    /// Binds heap memory to pages of sparse buffers and textures, or unbinds it.
+    ///
+    /// The bindings are updated after all the previous submissions are done,
+    /// and before any of the later ones start. `signal_fence` is signaled
+    /// like in [`Queue::submit`] once they are.
+    ///
+    /// Valid usage:
+    /// - the resources were created with [`MemoryFlags::SPARSE_BINDING`].
+    /// - the ranges and regions are multiples of the page or tile size.
+    unsafe fn bind_sparse(
+        &mut self,
+        buffer_bindings: &[SparseBufferBinding<A>],
+        texture_bindings: &[SparseTextureBinding<A>],
+        signal_fence: Option<(&mut A::Fence, FenceValue)>,
+    ) -> Result<(), DeviceError>;
     unsafe fn get_timestamp_period(&self) -> f32;
 }
 
@@ -606,6 +752,33 @@ pub trait CommandEncoder<A: Api>: WasmNotSend + WasmNotSync + fmt::Debug {
 
     unsafe fn dispatch(&mut self, count: [u32; 3]);
     unsafe fn dispatch_indirect(&mut self, buffer: &A::Buffer, offset: wgt::BufferAddress);
+
+    // acceleration structures
+
+    /// Build or update acceleration structures.
+    ///
+    /// Valid usage:
+    /// - the entries of every descriptor must have all of their buffers set.
+    /// - `descriptor_count` is the number of items yielded by `descriptors`.
+    /// - no destination acceleration structure may be used as the source or
+    ///   as the destination of another descriptor in the same call.
+    /// - the scratch buffer ranges must not overlap, and be at least as large
+    ///   as the relevant size returned by
+    ///   [`Device::get_acceleration_structure_build_sizes`].
+    unsafe fn build_acceleration_structures<'a, T>(
+        &mut self,
+        descriptor_count: u32,
+        descriptors: T,
+    ) where
+        A: 'a,
+        T: IntoIterator<Item = BuildAccelerationStructureDescriptor<'a, A>>;
+
+    /// Make the results of previous acceleration structure builds visible to
+    /// subsequent builds and shader reads.
+    unsafe fn place_acceleration_structure_barrier(
+        &mut self,
+        barrier: AccelerationStructureBarrier,
+    );
 }
 
 bitflags!(
@@ -727,6 +900,13 @@ bitflags!(
     pub struct MemoryFlags: u32 {
         const TRANSIENT = 1 << 0;
         const PREFER_COHERENT = 1 << 1;
+        /// The resource is created without memory, its pages are bound with
+        /// [`Queue::bind_sparse`].
+        const SPARSE_BINDING = 1 << 2;
+        /// The resource may be used while only some of its pages are bound.
+        ///
+        /// Requires `SPARSE_BINDING`.
+        const SPARSE_RESIDENCY = 1 << 3;
     }
 );
 
@@ -766,12 +946,21 @@ bitflags::bitflags! {
         const INDIRECT = 1 << 9;
         /// A buffer used to store query results.
         const QUERY_RESOLVE = 1 << 10;
+        /// The scratch buffer of an acceleration structure build.
+        const ACCELERATION_STRUCTURE_SCRATCH = 1 << 11;
+        /// The vertex, index or transform buffer of a bottom level acceleration structure build.
+        const BOTTOM_LEVEL_ACCELERATION_STRUCTURE_INPUT = 1 << 12;
+        /// The instance buffer of a top level acceleration structure build.
+        const TOP_LEVEL_ACCELERATION_STRUCTURE_INPUT = 1 << 13;
         /// The combination of states that a buffer may be in _at the same time_.
         const INCLUSIVE = Self::MAP_READ.bits() | Self::COPY_SRC.bits() |
             Self::INDEX.bits() | Self::VERTEX.bits() | Self::UNIFORM.bits() |
-            Self::STORAGE_READ.bits() | Self::INDIRECT.bits();
+            Self::STORAGE_READ.bits() | Self::INDIRECT.bits() |
+            Self::BOTTOM_LEVEL_ACCELERATION_STRUCTURE_INPUT.bits() |
+            Self::TOP_LEVEL_ACCELERATION_STRUCTURE_INPUT.bits();
         /// The combination of states that a buffer must exclusively be in.
-        const EXCLUSIVE = Self::MAP_WRITE.bits() | Self::COPY_DST.bits() | Self::STORAGE_READ_WRITE.bits();
+        const EXCLUSIVE = Self::MAP_WRITE.bits() | Self::COPY_DST.bits() |
+            Self::STORAGE_READ_WRITE.bits() | Self::ACCELERATION_STRUCTURE_SCRATCH.bits();
         /// The combination of all usages that the are guaranteed to be be ordered by the hardware.
         /// If a usage is ordered, then if the buffer state doesn't change between draw calls, there
         /// are no barriers needed for synchronization.
@@ -906,6 +1095,13 @@ pub struct OpenDevice<A: Api> {
     pub queue: A::Queue,
 }
 
+/// Memory usage of a device, see [`Device::memory_report`].
+#[derive(Clone, Debug, Default)]
+pub struct MemoryReport {
+    pub heaps: Vec<wgt::MemoryHeapReport>,
+    pub memory_types: Vec<wgt::MemoryTypeReport>,
+}
+
 #[derive(Clone, Debug)]
 pub struct BufferMapping {
     pub ptr: NonNull<u8>,
@@ -920,6 +1116,25 @@ pub struct BufferDescriptor<'a> {
     pub memory_flags: MemoryFlags,
 }
 
+#[derive(Clone, Debug)]
+pub struct HeapDescriptor<'a> {
+    pub label: Label<'a>,
+    pub size: wgt::BufferAddress,
+}
+
+#[derive(Clone, Debug)]
+pub struct ExternalSemaphoreDescriptor<'a> {
+    pub label: Label<'a>,
+}
+
+/// A resource bound to a range of a heap.
+#[derive(Debug)]
+pub struct PlacedResource<R> {
+    pub raw: R,
+    /// Number of bytes of the heap the resource occupies, starting at its offset.
+    pub size: wgt::BufferAddress,
+}
+
 #[derive(Clone, Debug)]
 pub struct TextureDescriptor<'a> {
     pub label: Label<'a>,
@@ -1083,12 +1298,16 @@ pub struct BindGroupDescriptor<'a, A: Api> {
     pub samplers: &'a [&'a A::Sampler],
     pub textures: &'a [TextureBinding<'a, A>],
     pub entries: &'a [BindGroupEntry],
+    pub acceleration_structures: &'a [&'a A::AccelerationStructure],
 }
 
 #[derive(Clone, Debug)]
 pub struct CommandEncoderDescriptor<'a, A: Api> {
     pub label: Label<'a>,
     pub queue: &'a A::Queue,
+    /// Command buffers produced by this encoder may be submitted more than
+    /// once, including while a previous submission is still executing.
+    pub reusable: bool,
 }
 
 /// Naga shader module.
@@ -1147,6 +1366,17 @@ pub struct ComputePipelineDescriptor<'a, A: Api> {
     pub layout: &'a A::PipelineLayout,
     /// The compiled compute stage and its entry point.
     pub stage: ProgrammableStage<'a, A>,
+    /// The cache which will be used and filled when compiling this pipeline.
+    pub cache: Option<&'a A::PipelineCache>,
+}
+
+/// Describes a pipeline cache.
+#[derive(Clone, Debug)]
+pub struct PipelineCacheDescriptor<'a> {
+    pub label: Label<'a>,
+    /// Previously serialized cache contents, as returned by
+    /// [`Device::pipeline_cache_get_data`].
+    pub data: Option<&'a [u8]>,
 }
 
 /// Describes how the vertex buffer is interpreted.
@@ -1183,6 +1413,8 @@ pub struct RenderPipelineDescriptor<'a, A: Api> {
     /// If the pipeline will be used with a multiview render pass, this indicates how many array
     /// layers the attachments will have.
     pub multiview: Option<NonZeroU32>,
+    /// The cache which will be used and filled when compiling this pipeline.
+    pub cache: Option<&'a A::PipelineCache>,
 }
 
 #[derive(Debug, Clone)]
@@ -1227,6 +1459,71 @@ pub struct TextureBarrier<'a, A: Api> {
     pub usage: Range<TextureUses>,
 }
 
+/// Memory of a heap bound to pages of a sparse resource.
+#[derive(Debug)]
+pub struct SparseMemory<'a, A: Api> {
+    pub heap: &'a A::Heap,
+    /// Offset of the first page, in multiples of [`wgt::SPARSE_PAGE_SIZE`].
+    pub offset: wgt::BufferAddress,
+}
+
+// Rust gets confused about the impl requirements for `A`
+impl<A: Api> Clone for SparseMemory<'_, A> {
+    fn clone(&self) -> Self {
+        Self {
+            heap: self.heap,
+            offset: self.offset,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct SparseBufferBinding<'a, A: Api> {
+    pub buffer: &'a A::Buffer,
+    /// The pages of the buffer, in multiples of [`wgt::SPARSE_PAGE_SIZE`].
+    pub range: MemoryRange,
+    /// `None` unbinds the pages.
+    pub memory: Option<SparseMemory<'a, A>>,
+}
+
+impl<A: Api> Clone for SparseBufferBinding<'_, A> {
+    fn clone(&self) -> Self {
+        Self {
+            buffer: self.buffer,
+            range: self.range.clone(),
+            memory: self.memory.clone(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct SparseTextureBinding<'a, A: Api> {
+    pub texture: &'a A::Texture,
+    pub mip_level: u32,
+    pub array_layer: u32,
+    /// The tiles of the texture, in multiples of
+    /// [`wgt::TextureFormat::sparse_tile_extent`]. `z` is always 0.
+    pub origin: wgt::Origin3d,
+    /// `depth` is always 1.
+    pub size: CopyExtent,
+    /// `None` unbinds the tiles. Otherwise, they are bound to consecutive
+    /// pages in row-major order.
+    pub memory: Option<SparseMemory<'a, A>>,
+}
+
+impl<A: Api> Clone for SparseTextureBinding<'_, A> {
+    fn clone(&self) -> Self {
+        Self {
+            texture: self.texture,
+            mip_level: self.mip_level,
+            array_layer: self.array_layer,
+            origin: self.origin,
+            size: self.size,
+            memory: self.memory.clone(),
+        }
+    }
+}
+
 #[derive(Clone, Copy, Debug)]
 pub struct BufferCopy {
     pub src_offset: wgt::BufferAddress,
@@ -1365,6 +1662,128 @@ pub struct ComputePassDescriptor<'a, A: Api> {
     pub timestamp_writes: Option<ComputePassTimestampWrites<'a, A>>,
 }
 
+#[derive(Clone, Debug)]
+pub struct AccelerationStructureDescriptor<'a> {
+    pub label: Label<'a>,
+    pub size: wgt::BufferAddress,
+    pub format: AccelerationStructureFormat,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum AccelerationStructureFormat {
+    TopLevel,
+    BottomLevel,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum AccelerationStructureBuildMode {
+    Build,
+    Update,
+}
+
+/// Information of the required size for a corresponding entries struct (+ flags)
+#[derive(Copy, Clone, Debug, Default)]
+pub struct AccelerationStructureBuildSizes {
+    pub acceleration_structure_size: wgt::BufferAddress,
+    pub update_scratch_size: wgt::BufferAddress,
+    pub build_scratch_size: wgt::BufferAddress,
+}
+
+/// Updates use `source_acceleration_structure` if present, else the update
+/// is performed in place. For updates only the data is allowed to change,
+/// not the meta data or sizes.
+#[derive(Debug)]
+pub struct BuildAccelerationStructureDescriptor<'a, A: Api> {
+    pub entries: &'a AccelerationStructureEntries<'a, A>,
+    pub mode: AccelerationStructureBuildMode,
+    pub flags: AccelerationStructureBuildFlags,
+    pub source_acceleration_structure: Option<&'a A::AccelerationStructure>,
+    pub destination_acceleration_structure: &'a A::AccelerationStructure,
+    pub scratch_buffer: &'a A::Buffer,
+    pub scratch_buffer_offset: wgt::BufferAddress,
+}
+
+/// - All buffers, buffer addresses and offsets will be ignored.
+/// - The build mode will be ignored.
+/// - Reducing the amount of Instances, Triangle groups or AABB groups (or the
+///   number of Triangles/AABBs in corresponding groups) may result in reduced
+///   size requirements.
+/// - Any other change may result in a bigger or smaller size requirement.
+#[derive(Debug)]
+pub struct GetAccelerationStructureBuildSizesDescriptor<'a, A: Api> {
+    pub entries: &'a AccelerationStructureEntries<'a, A>,
+    pub flags: AccelerationStructureBuildFlags,
+}
+
+/// Entries for a single descriptor
+/// * `Instances` - Multiple instances for a top level acceleration structure
+/// * `Triangles` - Multiple triangle meshes for a bottom level acceleration structure
+#[derive(Debug)]
+pub enum AccelerationStructureEntries<'a, A: Api> {
+    Instances(AccelerationStructureInstances<'a, A>),
+    Triangles(Vec<AccelerationStructureTriangles<'a, A>>),
+}
+
+/// * `first_vertex` - offset in the vertex buffer (as number of vertices)
+/// * `indices` - optional index buffer with attributes
+/// * `transform` - optional transform
+#[derive(Debug)]
+pub struct AccelerationStructureTriangles<'a, A: Api> {
+    pub vertex_buffer: Option<&'a A::Buffer>,
+    pub vertex_format: wgt::VertexFormat,
+    pub first_vertex: u32,
+    pub vertex_count: u32,
+    pub vertex_stride: wgt::BufferAddress,
+    pub indices: Option<AccelerationStructureTriangleIndices<'a, A>>,
+    pub transform: Option<AccelerationStructureTriangleTransform<'a, A>>,
+    pub flags: AccelerationStructureGeometryFlags,
+}
+
+/// * `offset` - offset in bytes
+#[derive(Debug)]
+pub struct AccelerationStructureInstances<'a, A: Api> {
+    pub buffer: Option<&'a A::Buffer>,
+    pub offset: u32,
+    pub count: u32,
+}
+
+/// * `offset` - offset in bytes
+#[derive(Debug)]
+pub struct AccelerationStructureTriangleIndices<'a, A: Api> {
+    pub format: wgt::IndexFormat,
+    pub buffer: Option<&'a A::Buffer>,
+    pub offset: u32,
+    pub count: u32,
+}
+
+/// * `offset` - offset in bytes
+#[derive(Debug)]
+pub struct AccelerationStructureTriangleTransform<'a, A: Api> {
+    pub buffer: &'a A::Buffer,
+    pub offset: u32,
+}
+
+pub type AccelerationStructureBuildFlags = wgt::AccelerationStructureFlags;
+pub type AccelerationStructureGeometryFlags = wgt::AccelerationStructureGeometryFlags;
+
+bitflags::bitflags! {
+    #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+    pub struct AccelerationStructureUses: u8 {
+        /// The acceleration structure is read by a build, either as the BLAS
+        /// referenced by a TLAS build or as the source of an update.
+        const BUILD_INPUT = 1 << 0;
+        /// The acceleration structure is written by a build.
+        const BUILD_OUTPUT = 1 << 1;
+        /// The acceleration structure is read by ray queries in shaders.
+        const SHADER_INPUT = 1 << 2;
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct AccelerationStructureBarrier {
+    pub usage: Range<AccelerationStructureUses>,
+}
+
 /// Stores if any API validation error has occurred in this process
 /// since it was last reset.
 ///
@@ -507,6 +507,37 @@ impl crate::Device<super::Api> for super::Device {
     }
     unsafe fn destroy_sampler(&self, _sampler: super::Sampler) {}
 
+    // Heaps aren't exposed by this backend.
+    unsafe fn create_heap(&self, _desc: &crate::HeapDescriptor) -> Result<(), crate::DeviceError> {
+        Err(crate::DeviceError::ResourceCreationFailed)
+    }
+    unsafe fn destroy_heap(&self, (): ()) {}
+    unsafe fn create_buffer_in_heap(
+        &self,
+        _desc: &crate::BufferDescriptor,
+        _heap: &(),
+        _offset: wgt::BufferAddress,
+    ) -> Result<crate::PlacedResource<super::Buffer>, crate::HeapPlacementError> {
+        Err(crate::DeviceError::ResourceCreationFailed.into())
+    }
+    unsafe fn create_texture_in_heap(
+        &self,
+        _desc: &crate::TextureDescriptor,
+        _heap: &(),
+        _offset: wgt::BufferAddress,
+    ) -> Result<crate::PlacedResource<super::Texture>, crate::HeapPlacementError> {
+        Err(crate::DeviceError::ResourceCreationFailed.into())
+    }
+
+    // External semaphores aren't exposed by this backend.
+    unsafe fn create_external_semaphore(
+        &self,
+        _desc: &crate::ExternalSemaphoreDescriptor,
+    ) -> Result<(), crate::DeviceError> {
+        Err(crate::DeviceError::ResourceCreationFailed)
+    }
+    unsafe fn destroy_external_semaphore(&self, (): ()) {}
+
     unsafe fn create_command_encoder(
         &self,
         desc: &crate::CommandEncoderDescriptor<super::Api>,
@@ -628,6 +659,11 @@ impl crate::Device<super::Api> for super::Device {
                             target.texture = Some(info.counters.textures as _);
                             info.counters.textures += count;
                         }
+                        // wgpu-core requires `Features::RAY_QUERY` for this binding type, which
+                        // this backend doesn't expose.
+                        wgt::BindingType::AccelerationStructure => {
+                            unreachable!("RAY_QUERY is not exposed on this backend")
+                        }
                         wgt::BindingType::StorageTexture { access, .. } => {
                             target.texture = Some(info.counters.textures as _);
                             info.counters.textures += count;
@@ -758,6 +794,11 @@ impl crate::Device<super::Api> for super::Device {
                             .extend(desc.samplers[start..end].iter().map(|samp| samp.as_raw()));
                         counter.samplers += size;
                     }
+                    // wgpu-core requires `Features::RAY_QUERY` for this binding type, which
+                    // this backend doesn't expose.
+                    wgt::BindingType::AccelerationStructure => {
+                        unreachable!("RAY_QUERY is not exposed on this backend")
+                    }
                     wgt::BindingType::Texture { .. } | wgt::BindingType::StorageTexture { .. } => {
                         let start = entry.resource_index as usize;
                         let end = start + size as usize;
@@ -1082,6 +1123,14 @@ impl crate::Device<super::Api> for super::Device {
     }
     unsafe fn destroy_compute_pipeline(&self, _pipeline: super::ComputePipeline) {}
 
+    unsafe fn create_pipeline_cache(
+        &self,
+        _desc: &crate::PipelineCacheDescriptor<'_>,
+    ) -> Result<(), crate::PipelineCacheError> {
+        Ok(())
+    }
+    unsafe fn destroy_pipeline_cache(&self, (): ()) {}
+
     unsafe fn create_query_set(
         &self,
         desc: &wgt::QuerySetDescriptor<crate::Label>,
@@ -1218,4 +1267,27 @@ impl crate::Device<super::Api> for super::Device {
         }
         shared_capture_manager.stop_capture();
     }
+
+    // Acceleration structures aren't exposed by this backend. wgpu-core requires
+    // `Features::RAY_TRACING_ACCELERATION_STRUCTURE` before asking for build sizes,
+    // and only asks for the address of acceleration structures it has created.
+    unsafe fn create_acceleration_structure(
+        &self,
+        _desc: &crate::AccelerationStructureDescriptor,
+    ) -> Result<(), crate::DeviceError> {
+        Err(crate::DeviceError::ResourceCreationFailed)
+    }
+    unsafe fn get_acceleration_structure_build_sizes<'a>(
+        &self,
+        _desc: &crate::GetAccelerationStructureBuildSizesDescriptor<'a, super::Api>,
+    ) -> crate::AccelerationStructureBuildSizes {
+        unreachable!("RAY_TRACING_ACCELERATION_STRUCTURE is not exposed on this backend")
+    }
+    unsafe fn get_acceleration_structure_device_address(
+        &self,
+        _acceleration_structure: &(),
+    ) -> wgt::BufferAddress {
+        unreachable!("RAY_TRACING_ACCELERATION_STRUCTURE is not exposed on this backend")
+    }
+    unsafe fn destroy_acceleration_structure(&self, _acceleration_structure: ()) {}
 }
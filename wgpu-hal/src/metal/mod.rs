@@ -66,6 +66,11 @@ impl crate::Api for Api {
     type ShaderModule = ShaderModule;
     type RenderPipeline = RenderPipeline;
     type ComputePipeline = ComputePipeline;
+    type PipelineCache = ();
+    type Heap = ();
+    type ExternalSemaphore = ();
+
+    type AccelerationStructure = ();
 }
 
 pub struct Instance {
@@ -404,6 +409,17 @@ impl crate::Queue<Api> for Queue {
         });
         Ok(())
     }
+    // No external semaphore can be created, so there is nothing to wait on or signal.
+    unsafe fn submit_with_sync(
+        &mut self,
+        command_buffers: &[&CommandBuffer],
+        _waits: &[(&(), u64)],
+        _signals: &[(&(), u64)],
+        signal_fence: Option<(&mut Fence, crate::FenceValue)>,
+    ) -> Result<(), crate::DeviceError> {
+        unsafe { crate::Queue::submit(self, command_buffers, signal_fence) }
+    }
+
     unsafe fn present(
         &mut self,
         _surface: &mut Surface,
@@ -429,6 +445,16 @@ impl crate::Queue<Api> for Queue {
         Ok(())
     }
 
+    // Sparse resources aren't exposed by this backend.
+    unsafe fn bind_sparse(
+        &mut self,
+        _buffer_bindings: &[crate::SparseBufferBinding<Api>],
+        _texture_bindings: &[crate::SparseTextureBinding<Api>],
+        _signal_fence: Option<(&mut Fence, crate::FenceValue)>,
+    ) -> Result<(), crate::DeviceError> {
+        Err(crate::DeviceError::ResourceCreationFailed)
+    }
+
     unsafe fn get_timestamp_period(&self) -> f32 {
         self.timestamp_period
     }
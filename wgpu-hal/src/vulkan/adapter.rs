@@ -33,6 +33,9 @@ pub struct PhysicalDeviceFeatures {
     )>,
     zero_initialize_workgroup_memory:
         Option<vk::PhysicalDeviceZeroInitializeWorkgroupMemoryFeatures>,
+    buffer_device_address: Option<vk::PhysicalDeviceBufferDeviceAddressFeaturesKHR>,
+    acceleration_structure: Option<vk::PhysicalDeviceAccelerationStructureFeaturesKHR>,
+    ray_query: Option<vk::PhysicalDeviceRayQueryFeaturesKHR>,
 }
 
 // This is safe because the structs have `p_next: *mut c_void`, which we null out/never read.
@@ -71,6 +74,15 @@ impl PhysicalDeviceFeatures {
         if let Some(ref mut feature) = self.zero_initialize_workgroup_memory {
             info = info.push_next(feature);
         }
+        if let Some(ref mut feature) = self.buffer_device_address {
+            info = info.push_next(feature);
+        }
+        if let Some(ref mut feature) = self.acceleration_structure {
+            info = info.push_next(feature);
+        }
+        if let Some(ref mut feature) = self.ray_query {
+            info = info.push_next(feature);
+        }
         info
     }
 
@@ -178,6 +190,13 @@ impl PhysicalDeviceFeatures {
                 .geometry_shader(requested_features.contains(wgt::Features::SHADER_PRIMITIVE_INDEX))
                 .depth_clamp(requested_features.contains(wgt::Features::DEPTH_CLIP_CONTROL))
                 .dual_src_blend(requested_features.contains(wgt::Features::DUAL_SOURCE_BLENDING))
+                .sparse_binding(requested_features.contains(wgt::Features::SPARSE_BINDING))
+                .sparse_residency_buffer(
+                    requested_features.contains(wgt::Features::SPARSE_RESIDENCY),
+                )
+                .sparse_residency_image2_d(
+                    requested_features.contains(wgt::Features::SPARSE_RESIDENCY),
+                )
                 .build(),
             descriptor_indexing: if requested_features.intersects(indexing_features()) {
                 Some(
@@ -292,6 +311,37 @@ impl PhysicalDeviceFeatures {
             } else {
                 None
             },
+            buffer_device_address: if requested_features
+                .contains(wgt::Features::RAY_TRACING_ACCELERATION_STRUCTURE)
+            {
+                Some(
+                    vk::PhysicalDeviceBufferDeviceAddressFeaturesKHR::builder()
+                        .buffer_device_address(true)
+                        .build(),
+                )
+            } else {
+                None
+            },
+            acceleration_structure: if enabled_extensions
+                .contains(&vk::KhrAccelerationStructureFn::name())
+            {
+                Some(
+                    vk::PhysicalDeviceAccelerationStructureFeaturesKHR::builder()
+                        .acceleration_structure(true)
+                        .build(),
+                )
+            } else {
+                None
+            },
+            ray_query: if enabled_extensions.contains(&vk::KhrRayQueryFn::name()) {
+                Some(
+                    vk::PhysicalDeviceRayQueryFeaturesKHR::builder()
+                        .ray_query(true)
+                        .build(),
+                )
+            } else {
+                None
+            },
         }
     }
 
@@ -312,7 +362,10 @@ impl PhysicalDeviceFeatures {
             | F::TIMESTAMP_QUERY
             | F::TIMESTAMP_QUERY_INSIDE_PASSES
             | F::TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES
-            | F::CLEAR_TEXTURE;
+            | F::CLEAR_TEXTURE
+            | F::PIPELINE_CACHE
+            | F::REUSABLE_COMMAND_BUFFERS
+            | F::RESOURCE_HEAPS;
 
         let mut dl_flags = Df::COMPUTE_SHADERS
             | Df::BASE_VERTEX
@@ -421,6 +474,49 @@ impl PhysicalDeviceFeatures {
             caps.supports_extension(vk::ExtConservativeRasterizationFn::name()),
         );
 
+        // Acceleration structures are only exposed on Vulkan 1.2+, where buffer device
+        // addresses, descriptor indexing and SPIR-V 1.4 are all part of the core API.
+        let supports_acceleration_structures = caps.device_api_version >= vk::API_VERSION_1_2
+            && caps.supports_extension(vk::KhrDeferredHostOperationsFn::name())
+            && self
+                .acceleration_structure
+                .as_ref()
+                .map_or(false, |f| f.acceleration_structure != 0)
+            && self
+                .buffer_device_address
+                .as_ref()
+                .map_or(false, |f| f.buffer_device_address != 0);
+        features.set(
+            F::RAY_TRACING_ACCELERATION_STRUCTURE,
+            supports_acceleration_structures,
+        );
+        features.set(
+            F::RAY_QUERY,
+            supports_acceleration_structures
+                && self.ray_query.as_ref().map_or(false, |f| f.ray_query != 0),
+        );
+
+        // Sparse binds are executed on the queue, which is always created from
+        // the first family.
+        let sparse_queue = unsafe { instance.get_physical_device_queue_family_properties(phd) }
+            .first()
+            .map_or(false, |family| {
+                family.queue_flags.contains(vk::QueueFlags::SPARSE_BINDING)
+            });
+        let sparse_properties = caps.properties.sparse_properties;
+        features.set(
+            F::SPARSE_BINDING,
+            sparse_queue && self.core.sparse_binding != 0,
+        );
+        features.set(
+            F::SPARSE_RESIDENCY,
+            features.contains(F::SPARSE_BINDING)
+                && self.core.sparse_residency_buffer != 0
+                && self.core.sparse_residency_image2_d != 0
+                && sparse_properties.residency_standard2_d_block_shape != 0
+                && sparse_properties.residency_non_resident_strict != 0,
+        );
+
         let intel_windows = caps.properties.vendor_id == db::intel::VENDOR && cfg!(windows);
 
         if let Some(ref descriptor_indexing) = self.descriptor_indexing {
@@ -529,6 +625,16 @@ impl PhysicalDeviceFeatures {
             supports_bgra8unorm_storage(instance, phd, caps.device_api_version),
         );
 
+        features.set(
+            F::EXTERNAL_SEMAPHORES,
+            cfg!(unix)
+                && caps.supports_extension(vk::KhrExternalSemaphoreFdFn::name())
+                && self
+                    .timeline_semaphore
+                    .map_or(false, |f| f.timeline_semaphore != 0)
+                && supports_opaque_fd_timeline_semaphores(instance, phd, caps.device_api_version),
+        );
+
         (features, dl_flags)
     }
 
@@ -672,6 +778,11 @@ impl PhysicalDeviceCapabilities {
             extensions.push(vk::ExtRobustness2Fn::name());
         }
 
+        // Optional `VK_EXT_memory_budget`
+        if self.supports_extension(vk::ExtMemoryBudgetFn::name()) {
+            extensions.push(vk::ExtMemoryBudgetFn::name());
+        }
+
         // Require `VK_KHR_draw_indirect_count` if the associated feature was requested
         // Even though Vulkan 1.2 has promoted the extension to core, we must require the extension to avoid
         // large amounts of spaghetti involved with using PhysicalDeviceVulkan12Features.
@@ -693,6 +804,24 @@ impl PhysicalDeviceCapabilities {
             extensions.push(vk::ExtTextureCompressionAstcHdrFn::name());
         }
 
+        // Require `VK_KHR_deferred_host_operations` and `VK_KHR_acceleration_structure` if the associated feature was requested.
+        // `VK_KHR_buffer_device_address` is core in Vulkan 1.2, which the feature requires.
+        if requested_features.contains(wgt::Features::RAY_TRACING_ACCELERATION_STRUCTURE) {
+            extensions.push(vk::KhrDeferredHostOperationsFn::name());
+            extensions.push(vk::KhrAccelerationStructureFn::name());
+        }
+
+        // Require `VK_KHR_ray_query` if the associated feature was requested
+        if requested_features.contains(wgt::Features::RAY_QUERY) {
+            extensions.push(vk::KhrRayQueryFn::name());
+        }
+
+        // Require `VK_KHR_external_semaphore_fd` if the associated feature was requested.
+        // `VK_KHR_external_semaphore` is core in Vulkan 1.1, and the feature requires 1.2.
+        if requested_features.contains(wgt::Features::EXTERNAL_SEMAPHORES) {
+            extensions.push(vk::KhrExternalSemaphoreFdFn::name());
+        }
+
         extensions
     }
 
@@ -901,6 +1030,26 @@ impl super::InstanceShared {
                 builder = builder.push_next(next);
             }
 
+            // `VK_KHR_buffer_device_address` is promoted to 1.2
+            if capabilities.device_api_version >= vk::API_VERSION_1_2 {
+                let next = features
+                    .buffer_device_address
+                    .insert(vk::PhysicalDeviceBufferDeviceAddressFeaturesKHR::default());
+                builder = builder.push_next(next);
+            }
+            if capabilities.supports_extension(vk::KhrAccelerationStructureFn::name()) {
+                let next = features
+                    .acceleration_structure
+                    .insert(vk::PhysicalDeviceAccelerationStructureFeaturesKHR::default());
+                builder = builder.push_next(next);
+            }
+            if capabilities.supports_extension(vk::KhrRayQueryFn::name()) {
+                let next = features
+                    .ray_query
+                    .insert(vk::PhysicalDeviceRayQueryFeaturesKHR::default());
+                builder = builder.push_next(next);
+            }
+
             let mut features2 = builder.build();
             unsafe {
                 get_device_properties.get_physical_device_features2(phd, &mut features2);
@@ -1174,6 +1323,7 @@ impl super::Adapter {
         handle_is_owned: bool,
         enabled_extensions: &[&'static CStr],
         features: wgt::Features,
+        memory_hints: &wgt::MemoryHints,
         family_index: u32,
         queue_index: u32,
     ) -> Result<crate::OpenDevice<super::Api>, crate::DeviceError> {
@@ -1213,6 +1363,25 @@ impl super::Adapter {
         } else {
             None
         };
+        let acceleration_structure_fn =
+            if enabled_extensions.contains(&khr::AccelerationStructure::name()) {
+                Some(khr::AccelerationStructure::new(
+                    &self.instance.raw,
+                    &raw_device,
+                ))
+            } else {
+                None
+            };
+
+        let external_semaphore_fd_fn =
+            if enabled_extensions.contains(&khr::ExternalSemaphoreFd::name()) {
+                Some(khr::ExternalSemaphoreFd::new(
+                    &self.instance.raw,
+                    &raw_device,
+                ))
+            } else {
+                None
+            };
 
         let naga_options = {
             use naga::back::spv;
@@ -1261,6 +1430,10 @@ impl super::Adapter {
                 capabilities.push(spv::Capability::StorageImageWriteWithoutFormat);
             }
 
+            if features.contains(wgt::Features::RAY_QUERY) {
+                capabilities.push(spv::Capability::RayQueryKHR);
+            }
+
             let mut flags = spv::WriterFlags::empty();
             flags.set(
                 spv::WriterFlags::DEBUG,
@@ -1278,7 +1451,12 @@ impl super::Adapter {
                 true, // could check `super::Workarounds::SEPARATE_ENTRY_POINTS`
             );
             spv::Options {
-                lang_version: (1, 0),
+                // `SPV_KHR_ray_query` requires SPIR-V 1.4.
+                lang_version: if features.contains(wgt::Features::RAY_QUERY) {
+                    (1, 4)
+                } else {
+                    (1, 0)
+                },
                 flags,
                 capabilities: Some(capabilities.iter().cloned().collect()),
                 bounds_check_policies: naga::proc::BoundsCheckPolicies {
@@ -1328,13 +1506,17 @@ impl super::Adapter {
             extension_fns: super::DeviceExtensionFunctions {
                 draw_indirect_count: indirect_count_fn,
                 timeline_semaphore: timeline_semaphore_fn,
+                acceleration_structure: acceleration_structure_fn,
+                external_semaphore_fd: external_semaphore_fd_fn,
             },
             vendor_id: self.phd_capabilities.properties.vendor_id,
+            pipeline_cache_validation_key: self.phd_capabilities.properties.pipeline_cache_uuid,
             timestamp_period: self.phd_capabilities.properties.limits.timestamp_period,
             private_caps: self.private_caps.clone(),
             workarounds: self.workarounds,
             render_passes: Mutex::new(Default::default()),
             framebuffers: Mutex::new(Default::default()),
+            memory_tracker: Mutex::new(super::MemoryTracker::new(&mem_properties)),
         });
         let mut relay_semaphores = [vk::Semaphore::null(); 2];
         for sem in relay_semaphores.iter_mut() {
@@ -1355,7 +1537,41 @@ impl super::Adapter {
 
         let mem_allocator = {
             let limits = self.phd_capabilities.properties.limits;
-            let config = gpu_alloc::Config::i_am_prototyping(); //TODO
+            // The block sizes are picked so that `Performance` starts out with
+            // large blocks, like other allocators do, while `MemoryUsage` grows
+            // from small blocks and keeps fewer resources in dedicated memory.
+            let mb = 1024 * 1024;
+            let performance_config = gpu_alloc::Config {
+                dedicated_threshold: 32 * mb,
+                preferred_dedicated_threshold: mb,
+                transient_dedicated_threshold: 128 * mb,
+                starting_free_list_chunk: 128 * mb,
+                final_free_list_chunk: 512 * mb,
+                minimal_buddy_size: 1,
+                initial_buddy_dedicated_size: 8 * mb,
+            };
+            let config = match *memory_hints {
+                wgt::MemoryHints::Performance => performance_config,
+                wgt::MemoryHints::MemoryUsage => gpu_alloc::Config {
+                    dedicated_threshold: 8 * mb,
+                    preferred_dedicated_threshold: mb,
+                    transient_dedicated_threshold: 16 * mb,
+                    starting_free_list_chunk: 8 * mb,
+                    final_free_list_chunk: 64 * mb,
+                    minimal_buddy_size: 1,
+                    initial_buddy_dedicated_size: 8 * mb,
+                },
+                wgt::MemoryHints::Manual {
+                    ref suballocated_device_memory_block_size,
+                } => gpu_alloc::Config {
+                    starting_free_list_chunk: suballocated_device_memory_block_size.start,
+                    final_free_list_chunk: suballocated_device_memory_block_size
+                        .end
+                        .max(suballocated_device_memory_block_size.start),
+                    initial_buddy_dedicated_size: suballocated_device_memory_block_size.start,
+                    ..performance_config
+                },
+            };
             let max_memory_allocation_size =
                 if let Some(maintenance_3) = self.phd_capabilities.maintenance_3 {
                     maintenance_3.max_memory_allocation_size
@@ -1382,7 +1598,8 @@ impl super::Adapter {
                         size: memory_heap.size,
                     })
                     .collect(),
-                buffer_device_address: false,
+                buffer_device_address: features
+                    .contains(wgt::Features::RAY_TRACING_ACCELERATION_STRUCTURE),
             };
             gpu_alloc::GpuAllocator::new(config, properties)
         };
@@ -1413,6 +1630,7 @@ impl crate::Adapter<super::Api> for super::Adapter {
         &self,
         features: wgt::Features,
         _limits: &wgt::Limits,
+        memory_hints: &wgt::MemoryHints,
     ) -> Result<crate::OpenDevice<super::Api>, crate::DeviceError> {
         let enabled_extensions = self.required_device_extensions(features);
         let mut enabled_phd_features = self.physical_device_features(&enabled_extensions, features);
@@ -1449,6 +1667,7 @@ impl crate::Adapter<super::Api> for super::Adapter {
                 true,
                 &enabled_extensions,
                 features,
+                memory_hints,
                 family_info.queue_family_index,
                 0,
             )
@@ -1777,3 +1996,30 @@ fn supports_bgra8unorm_storage(
             && features3.contains(vk::FormatFeatureFlags2::STORAGE_WRITE_WITHOUT_FORMAT)
     }
 }
+
+fn supports_opaque_fd_timeline_semaphores(
+    instance: &ash::Instance,
+    phd: vk::PhysicalDevice,
+    device_api_version: u32,
+) -> bool {
+    // The function call and structures used below are all core in VK1.2.
+    if device_api_version < vk::API_VERSION_1_2 {
+        return false;
+    }
+
+    unsafe {
+        let mut type_info =
+            vk::SemaphoreTypeCreateInfo::builder().semaphore_type(vk::SemaphoreType::TIMELINE);
+        let info = vk::PhysicalDeviceExternalSemaphoreInfo::builder()
+            .handle_type(vk::ExternalSemaphoreHandleTypeFlags::OPAQUE_FD)
+            .push_next(&mut type_info);
+        let mut properties = vk::ExternalSemaphoreProperties::default();
+
+        instance.get_physical_device_external_semaphore_properties(phd, &info, &mut properties);
+
+        properties.external_semaphore_features.contains(
+            vk::ExternalSemaphoreFeatureFlags::EXPORTABLE
+                | vk::ExternalSemaphoreFeatureFlags::IMPORTABLE,
+        )
+    }
+}
@@ -85,9 +85,12 @@ impl crate::CommandEncoder<super::Api> for super::CommandEncoder {
         // Reset this in case the last renderpass was never ended.
         self.rpass_debug_marker_active = false;
 
-        let vk_info = vk::CommandBufferBeginInfo::builder()
-            .flags(vk::CommandBufferUsageFlags::ONE_TIME_SUBMIT)
-            .build();
+        let usage = if self.reusable {
+            vk::CommandBufferUsageFlags::SIMULTANEOUS_USE
+        } else {
+            vk::CommandBufferUsageFlags::ONE_TIME_SUBMIT
+        };
+        let vk_info = vk::CommandBufferBeginInfo::builder().flags(usage).build();
         unsafe { self.device.raw.begin_command_buffer(raw, &vk_info) }?;
         self.active = raw;
 
@@ -894,6 +897,97 @@ impl crate::CommandEncoder<super::Api> for super::CommandEncoder {
                 .cmd_dispatch_indirect(self.active, buffer.raw, offset)
         }
     }
+
+    unsafe fn build_acceleration_structures<'a, T>(&mut self, descriptor_count: u32, descriptors: T)
+    where
+        super::Api: 'a,
+        T: IntoIterator<Item = crate::BuildAccelerationStructureDescriptor<'a, super::Api>>,
+    {
+        let ray_tracing_functions = self.device.acceleration_structure_fn();
+
+        let capacity = descriptor_count as usize;
+        // The geometry infos point into these, so they have to outlive the build command.
+        let mut geometries_storage = Vec::with_capacity(capacity);
+        let mut ranges_storage = Vec::with_capacity(capacity);
+        let mut geometry_infos = Vec::with_capacity(capacity);
+
+        for desc in descriptors {
+            let (geometries, ranges) =
+                unsafe { self.device.map_acceleration_structure_entries(desc.entries) };
+
+            let ty = match *desc.entries {
+                crate::AccelerationStructureEntries::Instances(_) => {
+                    vk::AccelerationStructureTypeKHR::TOP_LEVEL
+                }
+                crate::AccelerationStructureEntries::Triangles(_) => {
+                    vk::AccelerationStructureTypeKHR::BOTTOM_LEVEL
+                }
+            };
+            let scratch_info =
+                vk::BufferDeviceAddressInfo::builder().buffer(desc.scratch_buffer.raw);
+            let scratch_address =
+                unsafe { self.device.raw.get_buffer_device_address(&scratch_info) };
+
+            let mut geometry_info = vk::AccelerationStructureBuildGeometryInfoKHR::builder()
+                .ty(ty)
+                .mode(conv::map_acceleration_structure_build_mode(desc.mode))
+                .flags(conv::map_acceleration_structure_flags(desc.flags))
+                .dst_acceleration_structure(desc.destination_acceleration_structure.raw)
+                .scratch_data(vk::DeviceOrHostAddressKHR {
+                    device_address: scratch_address + desc.scratch_buffer_offset,
+                })
+                .geometries(&geometries);
+            if desc.mode == crate::AccelerationStructureBuildMode::Update {
+                geometry_info.src_acceleration_structure = desc
+                    .source_acceleration_structure
+                    .unwrap_or(desc.destination_acceleration_structure)
+                    .raw;
+            }
+
+            geometry_infos.push(*geometry_info);
+            geometries_storage.push(geometries);
+            ranges_storage.push(ranges);
+        }
+
+        let range_refs = ranges_storage
+            .iter()
+            .map(|ranges| ranges.as_slice())
+            .collect::<Vec<_>>();
+
+        unsafe {
+            ray_tracing_functions.cmd_build_acceleration_structures(
+                self.active,
+                &geometry_infos,
+                &range_refs,
+            )
+        };
+    }
+
+    unsafe fn place_acceleration_structure_barrier(
+        &mut self,
+        barrier: crate::AccelerationStructureBarrier,
+    ) {
+        //Note: this is done so that we never end up with empty stage flags
+        let (src_stage, src_access) =
+            conv::map_acceleration_structure_usage_to_barrier(barrier.usage.start);
+        let (dst_stage, dst_access) =
+            conv::map_acceleration_structure_usage_to_barrier(barrier.usage.end);
+
+        unsafe {
+            self.device.raw.cmd_pipeline_barrier(
+                self.active,
+                src_stage | vk::PipelineStageFlags::TOP_OF_PIPE,
+                dst_stage | vk::PipelineStageFlags::BOTTOM_OF_PIPE,
+                vk::DependencyFlags::empty(),
+                &[vk::MemoryBarrier::builder()
+                    .src_access_mask(src_access)
+                    .dst_access_mask(dst_access)
+                    .build()],
+                &[],
+                &[],
+            )
+        };
+    }
 }
 
 #[test]
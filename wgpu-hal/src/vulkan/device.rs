@@ -62,6 +62,103 @@ impl super::DeviceShared {
         };
     }
 
+    pub(super) fn acceleration_structure_fn(&self) -> &khr::AccelerationStructure {
+        self.extension_fns
+            .acceleration_structure
+            .as_ref()
+            .expect("Feature `RAY_TRACING_ACCELERATION_STRUCTURE` not enabled")
+    }
+
+    unsafe fn buffer_device_address(
+        &self,
+        buffer: Option<&super::Buffer>,
+    ) -> vk::DeviceOrHostAddressConstKHR {
+        match buffer {
+            Some(buffer) => {
+                let info = vk::BufferDeviceAddressInfo::builder().buffer(buffer.raw);
+                vk::DeviceOrHostAddressConstKHR {
+                    device_address: unsafe { self.raw.get_buffer_device_address(&info) },
+                }
+            }
+            None => vk::DeviceOrHostAddressConstKHR::default(),
+        }
+    }
+
+    /// Translate acceleration structure entries into Vulkan geometries and the
+    /// matching build ranges. Buffers that are `None` get a null address.
+    pub(super) unsafe fn map_acceleration_structure_entries(
+        &self,
+        entries: &crate::AccelerationStructureEntries<super::Api>,
+    ) -> (
+        Vec<vk::AccelerationStructureGeometryKHR>,
+        Vec<vk::AccelerationStructureBuildRangeInfoKHR>,
+    ) {
+        match *entries {
+            crate::AccelerationStructureEntries::Instances(ref instances) => {
+                let instance_data = vk::AccelerationStructureGeometryInstancesDataKHR::builder()
+                    .data(unsafe { self.buffer_device_address(instances.buffer) });
+                let geometry = vk::AccelerationStructureGeometryKHR::builder()
+                    .geometry_type(vk::GeometryTypeKHR::INSTANCES)
+                    .geometry(vk::AccelerationStructureGeometryDataKHR {
+                        instances: *instance_data,
+                    });
+                let range = vk::AccelerationStructureBuildRangeInfoKHR::builder()
+                    .primitive_count(instances.count)
+                    .primitive_offset(instances.offset);
+                (vec![*geometry], vec![*range])
+            }
+            crate::AccelerationStructureEntries::Triangles(ref in_geometries) => {
+                let mut geometries = Vec::with_capacity(in_geometries.len());
+                let mut ranges = Vec::with_capacity(in_geometries.len());
+                for triangles in in_geometries {
+                    let mut triangle_data =
+                        vk::AccelerationStructureGeometryTrianglesDataKHR::builder()
+                            .vertex_format(conv::map_vertex_format(triangles.vertex_format))
+                            .vertex_data(unsafe {
+                                self.buffer_device_address(triangles.vertex_buffer)
+                            })
+                            .vertex_stride(triangles.vertex_stride)
+                            .max_vertex(
+                                triangles.first_vertex + triangles.vertex_count.saturating_sub(1),
+                            );
+                    let mut range = vk::AccelerationStructureBuildRangeInfoKHR::builder()
+                        .first_vertex(triangles.first_vertex);
+
+                    if let Some(ref indices) = triangles.indices {
+                        triangle_data = triangle_data
+                            .index_type(conv::map_index_format(indices.format))
+                            .index_data(unsafe { self.buffer_device_address(indices.buffer) });
+                        range = range
+                            .primitive_count(indices.count / 3)
+                            .primitive_offset(indices.offset);
+                    } else {
+                        triangle_data = triangle_data.index_type(vk::IndexType::NONE_KHR);
+                        range = range.primitive_count(triangles.vertex_count / 3);
+                    }
+
+                    if let Some(ref transform) = triangles.transform {
+                        triangle_data = triangle_data.transform_data(unsafe {
+                            self.buffer_device_address(Some(transform.buffer))
+                        });
+                        range = range.transform_offset(transform.offset);
+                    }
+
+                    let geometry = vk::AccelerationStructureGeometryKHR::builder()
+                        .geometry_type(vk::GeometryTypeKHR::TRIANGLES)
+                        .geometry(vk::AccelerationStructureGeometryDataKHR {
+                            triangles: *triangle_data,
+                        })
+                        .flags(conv::map_acceleration_structure_geometry_flags(
+                            triangles.flags,
+                        ));
+                    geometries.push(*geometry);
+                    ranges.push(*range);
+                }
+                (geometries, ranges)
+            }
+        }
+    }
+
     pub fn make_render_pass(
         &self,
         key: super::RenderPassKey,
@@ -325,7 +422,12 @@ impl gpu_alloc::MemoryDevice<vk::DeviceMemory> for super::DeviceShared {
         }
 
         match unsafe { self.raw.allocate_memory(&info, None) } {
-            Ok(memory) => Ok(memory),
+            Ok(memory) => {
+                self.memory_tracker
+                    .lock()
+                    .allocate(memory, memory_type, size);
+                Ok(memory)
+            }
             Err(vk::Result::ERROR_OUT_OF_DEVICE_MEMORY) => {
                 Err(gpu_alloc::OutOfMemory::OutOfDeviceMemory)
             }
@@ -338,6 +440,7 @@ impl gpu_alloc::MemoryDevice<vk::DeviceMemory> for super::DeviceShared {
     }
 
     unsafe fn deallocate_memory(&self, memory: vk::DeviceMemory) {
+        self.memory_tracker.lock().deallocate(memory);
         unsafe { self.raw.free_memory(memory, None) };
     }
 
@@ -385,6 +488,119 @@ impl gpu_alloc::MemoryDevice<vk::DeviceMemory> for super::DeviceShared {
     }
 }
 
+impl super::Heap {
+    /// Checks that a resource with the memory requirements `req` can be bound at `offset`.
+    fn check_placement(
+        &self,
+        req: &vk::MemoryRequirements,
+        offset: u64,
+    ) -> Result<(), crate::HeapPlacementError> {
+        if req.memory_type_bits & (1 << self.memory_type) == 0 {
+            return Err(crate::HeapPlacementError::IncompatibleMemory);
+        }
+        if offset % req.alignment != 0 {
+            return Err(crate::HeapPlacementError::Misaligned(req.alignment));
+        }
+        if offset
+            .checked_add(req.size)
+            .map_or(true, |end| end > self.size)
+        {
+            return Err(crate::HeapPlacementError::OutOfBounds(req.size));
+        }
+        Ok(())
+    }
+}
+
+impl super::MemoryTracker {
+    pub(super) fn new(properties: &vk::PhysicalDeviceMemoryProperties) -> Self {
+        Self {
+            memory_type_heaps: properties.memory_types[..properties.memory_type_count as usize]
+                .iter()
+                .map(|memory_type| memory_type.heap_index)
+                .collect(),
+            heap_sizes: properties.memory_heaps[..properties.memory_heap_count as usize]
+                .iter()
+                .map(|heap| heap.size)
+                .collect(),
+            blocks: Default::default(),
+        }
+    }
+
+    fn allocate(&mut self, memory: vk::DeviceMemory, memory_type: u32, size: u64) {
+        self.blocks.insert(
+            memory,
+            super::MemoryBlockInfo {
+                memory_type,
+                size,
+                used_ranges: Default::default(),
+            },
+        );
+    }
+
+    fn deallocate(&mut self, memory: vk::DeviceMemory) {
+        self.blocks.remove(&memory);
+    }
+
+    fn bind(&mut self, memory: vk::DeviceMemory, offset: u64, size: u64) {
+        if let Some(block) = self.blocks.get_mut(&memory) {
+            block.used_ranges.insert(offset, size);
+        }
+    }
+
+    fn unbind(&mut self, memory: vk::DeviceMemory, offset: u64) {
+        if let Some(block) = self.blocks.get_mut(&memory) {
+            block.used_ranges.remove(&offset);
+        }
+    }
+
+    fn report(&self) -> crate::MemoryReport {
+        let mut memory_types = self
+            .memory_type_heaps
+            .iter()
+            .map(|&heap_index| wgt::MemoryTypeReport {
+                heap_index,
+                ..Default::default()
+            })
+            .collect::<Vec<_>>();
+        for block in self.blocks.values() {
+            let used = block.used_ranges.values().sum::<u64>();
+            // The gaps between the used ranges, and after the last one.
+            let mut largest_free_range = 0;
+            let mut free_start = 0;
+            for (&offset, &size) in block.used_ranges.iter() {
+                largest_free_range = largest_free_range.max(offset.saturating_sub(free_start));
+                free_start = offset + size;
+            }
+            largest_free_range = largest_free_range.max(block.size.saturating_sub(free_start));
+
+            let report = &mut memory_types[block.memory_type as usize];
+            report.allocated += block.size;
+            report.used += used;
+            report.block_count += 1;
+            report.largest_free_range = report.largest_free_range.max(largest_free_range);
+        }
+
+        let mut heaps = self
+            .heap_sizes
+            .iter()
+            .map(|&size| wgt::MemoryHeapReport {
+                size,
+                ..Default::default()
+            })
+            .collect::<Vec<_>>();
+        for memory_type in memory_types.iter() {
+            let heap = &mut heaps[memory_type.heap_index as usize];
+            heap.allocated += memory_type.allocated;
+            heap.used += memory_type.used;
+        }
+
+        crate::MemoryReport {
+            heaps,
+            memory_types,
+        }
+    }
+}
+
 impl
     gpu_descriptor::DescriptorDevice<vk::DescriptorSetLayout, vk::DescriptorPool, vk::DescriptorSet>
     for super::DeviceShared
@@ -537,6 +753,145 @@ struct CompiledStage {
 }
 
 impl super::Device {
+    /// Returns the memory type heaps are allocated from.
+    fn heap_memory_type(&self) -> Option<u32> {
+        let mem_properties = unsafe {
+            self.shared
+                .instance
+                .raw
+                .get_physical_device_memory_properties(self.shared.physical_device)
+        };
+        // Prefer memory that isn't visible to the host, like `gpu_alloc`
+        // does for `FAST_DEVICE_ACCESS`.
+        mem_properties.memory_types[..mem_properties.memory_type_count as usize]
+            .iter()
+            .enumerate()
+            .filter(|&(index, memory_type)| {
+                self.valid_ash_memory_types & (1 << index) != 0
+                    && memory_type
+                        .property_flags
+                        .contains(vk::MemoryPropertyFlags::DEVICE_LOCAL)
+            })
+            .min_by_key(|&(_, memory_type)| {
+                memory_type
+                    .property_flags
+                    .contains(vk::MemoryPropertyFlags::HOST_VISIBLE)
+            })
+            .map(|(index, _)| index as u32)
+    }
+
+    /// Checks that the pages of a sparse resource with the memory requirements
+    /// `req` can be bound to the memory of any heap.
+    fn check_sparse_requirements(
+        &self,
+        req: &vk::MemoryRequirements,
+    ) -> Result<(), crate::DeviceError> {
+        let compatible = self.heap_memory_type().map_or(false, |memory_type| {
+            req.memory_type_bits & (1 << memory_type) != 0
+        });
+        if !compatible || wgt::SPARSE_PAGE_SIZE % req.alignment != 0 {
+            log::error!("Sparse resource memory requirements are not supported: {req:?}");
+            return Err(crate::DeviceError::ResourceCreationFailed);
+        }
+        Ok(())
+    }
+
+    /// Creates an image without any memory bound to it.
+    ///
+    /// Returns the image, its create flags and its view formats.
+    unsafe fn create_image(
+        &self,
+        desc: &crate::TextureDescriptor,
+    ) -> Result<(vk::Image, vk::ImageCreateFlags, Vec<wgt::TextureFormat>), crate::DeviceError>
+    {
+        let copy_size = desc.copy_extent();
+
+        let mut raw_flags = vk::ImageCreateFlags::empty();
+        if desc.is_cube_compatible() {
+            raw_flags |= vk::ImageCreateFlags::CUBE_COMPATIBLE;
+        }
+        if desc
+            .memory_flags
+            .contains(crate::MemoryFlags::SPARSE_BINDING)
+        {
+            raw_flags |= vk::ImageCreateFlags::SPARSE_BINDING;
+        }
+        if desc
+            .memory_flags
+            .contains(crate::MemoryFlags::SPARSE_RESIDENCY)
+        {
+            raw_flags |= vk::ImageCreateFlags::SPARSE_RESIDENCY;
+        }
+
+        let original_format = self.shared.private_caps.map_texture_format(desc.format);
+        let mut vk_view_formats = vec![];
+        let mut wgt_view_formats = vec![];
+        if !desc.view_formats.is_empty() {
+            raw_flags |= vk::ImageCreateFlags::MUTABLE_FORMAT;
+            wgt_view_formats = desc.view_formats.clone();
+            wgt_view_formats.push(desc.format);
+
+            if self.shared.private_caps.image_format_list {
+                vk_view_formats = desc
+                    .view_formats
+                    .iter()
+                    .map(|f| self.shared.private_caps.map_texture_format(*f))
+                    .collect();
+                vk_view_formats.push(original_format)
+            }
+        }
+
+        let mut vk_info = vk::ImageCreateInfo::builder()
+            .flags(raw_flags)
+            .image_type(conv::map_texture_dimension(desc.dimension))
+            .format(original_format)
+            .extent(conv::map_copy_extent(&copy_size))
+            .mip_levels(desc.mip_level_count)
+            .array_layers(desc.array_layer_count())
+            .samples(vk::SampleCountFlags::from_raw(desc.sample_count))
+            .tiling(vk::ImageTiling::OPTIMAL)
+            .usage(conv::map_texture_usage(desc.usage))
+            .sharing_mode(vk::SharingMode::EXCLUSIVE)
+            .initial_layout(vk::ImageLayout::UNDEFINED);
+
+        let mut format_list_info = vk::ImageFormatListCreateInfo::builder();
+        if !vk_view_formats.is_empty() {
+            format_list_info = format_list_info.view_formats(&vk_view_formats);
+            vk_info = vk_info.push_next(&mut format_list_info);
+        }
+
+        let raw = unsafe { self.shared.raw.create_image(&vk_info, None)? };
+
+        if let Some(label) = desc.label {
+            unsafe {
+                self.shared
+                    .set_object_name(vk::ObjectType::IMAGE, raw, label)
+            };
+        }
+
+        Ok((raw, raw_flags, wgt_view_formats))
+    }
+
+    unsafe fn alloc_memory(
+        &self,
+        request: gpu_alloc::Request,
+    ) -> Result<gpu_alloc::MemoryBlock<vk::DeviceMemory>, gpu_alloc::AllocationError> {
+        let block = unsafe { self.mem_allocator.lock().alloc(&*self.shared, request)? };
+        self.shared
+            .memory_tracker
+            .lock()
+            .bind(*block.memory(), block.offset(), block.size());
+        Ok(block)
+    }
+
+    unsafe fn dealloc_memory(&self, block: gpu_alloc::MemoryBlock<vk::DeviceMemory>) {
+        self.shared
+            .memory_tracker
+            .lock()
+            .unbind(*block.memory(), block.offset());
+        unsafe { self.mem_allocator.lock().dealloc(&*self.shared, block) };
+    }
+
     pub(super) unsafe fn create_swapchain(
         &self,
         surface: &mut super::Surface,
@@ -691,6 +1046,73 @@ impl super::Device {
         }
     }
 
+    fn external_semaphore_fd(&self) -> Result<&khr::ExternalSemaphoreFd, crate::DeviceError> {
+        self.shared
+            .extension_fns
+            .external_semaphore_fd
+            .as_ref()
+            .ok_or(crate::DeviceError::ResourceCreationFailed)
+    }
+
+    /// Creates a timeline semaphore that can be exported as an opaque file descriptor.
+    fn create_external_timeline_semaphore(&self) -> Result<vk::Semaphore, crate::DeviceError> {
+        let mut type_info =
+            vk::SemaphoreTypeCreateInfo::builder().semaphore_type(vk::SemaphoreType::TIMELINE);
+        let mut export_info = vk::ExportSemaphoreCreateInfo::builder()
+            .handle_types(vk::ExternalSemaphoreHandleTypeFlags::OPAQUE_FD);
+        let vk_info = vk::SemaphoreCreateInfo::builder()
+            .push_next(&mut type_info)
+            .push_next(&mut export_info);
+        Ok(unsafe { self.shared.raw.create_semaphore(&vk_info, None) }?)
+    }
+
+    /// Imports a timeline semaphore from an opaque file descriptor.
+    ///
+    /// On success, the file descriptor is owned by the Vulkan implementation,
+    /// and must not be used or closed by the caller anymore.
+    ///
+    /// # Safety
+    ///
+    /// - `fd` must have been exported from a timeline semaphore, by a device
+    ///   with the same `deviceUUID` and `driverUUID` as this one
+    /// - [`wgt::Features::EXTERNAL_SEMAPHORES`] must be enabled
+    #[cfg(unix)]
+    pub unsafe fn import_semaphore_fd(
+        &self,
+        fd: std::os::unix::io::RawFd,
+    ) -> Result<super::ExternalSemaphore, crate::DeviceError> {
+        let external_semaphore_fd = self.external_semaphore_fd()?;
+        let raw = self.create_external_timeline_semaphore()?;
+        let vk_info = vk::ImportSemaphoreFdInfoKHR::builder()
+            .semaphore(raw)
+            .handle_type(vk::ExternalSemaphoreHandleTypeFlags::OPAQUE_FD)
+            .fd(fd);
+        if let Err(error) = unsafe { external_semaphore_fd.import_semaphore_fd(&vk_info) } {
+            unsafe { self.shared.raw.destroy_semaphore(raw, None) };
+            return Err(error.into());
+        }
+        Ok(super::ExternalSemaphore { raw })
+    }
+
+    /// Exports an opaque file descriptor referencing `semaphore`.
+    ///
+    /// The caller owns the returned file descriptor.
+    ///
+    /// # Safety
+    ///
+    /// - `semaphore` must have been created by this device
+    #[cfg(unix)]
+    pub unsafe fn export_semaphore_fd(
+        &self,
+        semaphore: &super::ExternalSemaphore,
+    ) -> Result<std::os::unix::io::RawFd, crate::DeviceError> {
+        let external_semaphore_fd = self.external_semaphore_fd()?;
+        let vk_info = vk::SemaphoreGetFdInfoKHR::builder()
+            .semaphore(semaphore.raw)
+            .handle_type(vk::ExternalSemaphoreHandleTypeFlags::OPAQUE_FD);
+        Ok(unsafe { external_semaphore_fd.get_semaphore_fd(&vk_info) }?)
+    }
+
     fn create_shader_module_impl(
         &self,
         spv: &[u32],
@@ -822,7 +1244,21 @@ impl crate::Device<super::Api> for super::Device {
         &self,
         desc: &crate::BufferDescriptor,
     ) -> Result<super::Buffer, crate::DeviceError> {
+        let mut raw_flags = vk::BufferCreateFlags::empty();
+        if desc
+            .memory_flags
+            .contains(crate::MemoryFlags::SPARSE_BINDING)
+        {
+            raw_flags |= vk::BufferCreateFlags::SPARSE_BINDING;
+        }
+        if desc
+            .memory_flags
+            .contains(crate::MemoryFlags::SPARSE_RESIDENCY)
+        {
+            raw_flags |= vk::BufferCreateFlags::SPARSE_RESIDENCY;
+        }
         let vk_info = vk::BufferCreateInfo::builder()
+            .flags(raw_flags)
             .size(desc.size)
             .usage(conv::map_buffer_usage(desc.usage))
             .sharing_mode(vk::SharingMode::EXCLUSIVE);
@@ -830,6 +1266,21 @@ impl crate::Device<super::Api> for super::Device {
         let raw = unsafe { self.shared.raw.create_buffer(&vk_info, None)? };
         let req = unsafe { self.shared.raw.get_buffer_memory_requirements(raw) };
 
+        if let Some(label) = desc.label {
+            unsafe {
+                self.shared
+                    .set_object_name(vk::ObjectType::BUFFER, raw, label)
+            };
+        }
+
+        if raw_flags.contains(vk::BufferCreateFlags::SPARSE_BINDING) {
+            if let Err(err) = self.check_sparse_requirements(&req) {
+                unsafe { self.shared.raw.destroy_buffer(raw, None) };
+                return Err(err);
+            }
+            return Ok(super::Buffer { raw, block: None });
+        }
+
         let mut alloc_usage = if desc
             .usage
             .intersects(crate::BufferUses::MAP_READ | crate::BufferUses::MAP_WRITE)
@@ -852,17 +1303,22 @@ impl crate::Device<super::Api> for super::Device {
             gpu_alloc::UsageFlags::TRANSIENT,
             desc.memory_flags.contains(crate::MemoryFlags::TRANSIENT),
         );
+        alloc_usage.set(
+            gpu_alloc::UsageFlags::DEVICE_ADDRESS,
+            desc.usage.intersects(
+                crate::BufferUses::ACCELERATION_STRUCTURE_SCRATCH
+                    | crate::BufferUses::BOTTOM_LEVEL_ACCELERATION_STRUCTURE_INPUT
+                    | crate::BufferUses::TOP_LEVEL_ACCELERATION_STRUCTURE_INPUT,
+            ),
+        );
 
         let block = unsafe {
-            self.mem_allocator.lock().alloc(
-                &*self.shared,
-                gpu_alloc::Request {
-                    size: req.size,
-                    align_mask: req.alignment - 1,
-                    usage: alloc_usage,
-                    memory_types: req.memory_type_bits & self.valid_ash_memory_types,
-                },
-            )?
+            self.alloc_memory(gpu_alloc::Request {
+                size: req.size,
+                align_mask: req.alignment - 1,
+                usage: alloc_usage,
+                memory_types: req.memory_type_bits & self.valid_ash_memory_types,
+            })?
         };
 
         unsafe {
@@ -871,13 +1327,6 @@ impl crate::Device<super::Api> for super::Device {
                 .bind_buffer_memory(raw, *block.memory(), block.offset())?
         };
 
-        if let Some(label) = desc.label {
-            unsafe {
-                self.shared
-                    .set_object_name(vk::ObjectType::BUFFER, raw, label)
-            };
-        }
-
         Ok(super::Buffer {
             raw,
             block: Some(Mutex::new(block)),
@@ -886,11 +1335,7 @@ impl crate::Device<super::Api> for super::Device {
     unsafe fn destroy_buffer(&self, buffer: super::Buffer) {
         unsafe { self.shared.raw.destroy_buffer(buffer.raw, None) };
         if let Some(block) = buffer.block {
-            unsafe {
-                self.mem_allocator
-                    .lock()
-                    .dealloc(&*self.shared, block.into_inner())
-            };
+            unsafe { self.dealloc_memory(block.into_inner()) };
         }
     }
 
@@ -955,63 +1400,33 @@ impl crate::Device<super::Api> for super::Device {
         &self,
         desc: &crate::TextureDescriptor,
     ) -> Result<super::Texture, crate::DeviceError> {
-        let copy_size = desc.copy_extent();
-
-        let mut raw_flags = vk::ImageCreateFlags::empty();
-        if desc.is_cube_compatible() {
-            raw_flags |= vk::ImageCreateFlags::CUBE_COMPATIBLE;
-        }
-
-        let original_format = self.shared.private_caps.map_texture_format(desc.format);
-        let mut vk_view_formats = vec![];
-        let mut wgt_view_formats = vec![];
-        if !desc.view_formats.is_empty() {
-            raw_flags |= vk::ImageCreateFlags::MUTABLE_FORMAT;
-            wgt_view_formats = desc.view_formats.clone();
-            wgt_view_formats.push(desc.format);
+        let (raw, raw_flags, view_formats) = unsafe { self.create_image(desc)? };
+        let req = unsafe { self.shared.raw.get_image_memory_requirements(raw) };
 
-            if self.shared.private_caps.image_format_list {
-                vk_view_formats = desc
-                    .view_formats
-                    .iter()
-                    .map(|f| self.shared.private_caps.map_texture_format(*f))
-                    .collect();
-                vk_view_formats.push(original_format)
+        if raw_flags.contains(vk::ImageCreateFlags::SPARSE_BINDING) {
+            if let Err(err) = self.check_sparse_requirements(&req) {
+                unsafe { self.shared.raw.destroy_image(raw, None) };
+                return Err(err);
             }
+            return Ok(super::Texture {
+                raw,
+                drop_guard: None,
+                block: None,
+                usage: desc.usage,
+                format: desc.format,
+                raw_flags,
+                copy_size: desc.copy_extent(),
+                view_formats,
+            });
         }
 
-        let mut vk_info = vk::ImageCreateInfo::builder()
-            .flags(raw_flags)
-            .image_type(conv::map_texture_dimension(desc.dimension))
-            .format(original_format)
-            .extent(conv::map_copy_extent(&copy_size))
-            .mip_levels(desc.mip_level_count)
-            .array_layers(desc.array_layer_count())
-            .samples(vk::SampleCountFlags::from_raw(desc.sample_count))
-            .tiling(vk::ImageTiling::OPTIMAL)
-            .usage(conv::map_texture_usage(desc.usage))
-            .sharing_mode(vk::SharingMode::EXCLUSIVE)
-            .initial_layout(vk::ImageLayout::UNDEFINED);
-
-        let mut format_list_info = vk::ImageFormatListCreateInfo::builder();
-        if !vk_view_formats.is_empty() {
-            format_list_info = format_list_info.view_formats(&vk_view_formats);
-            vk_info = vk_info.push_next(&mut format_list_info);
-        }
-
-        let raw = unsafe { self.shared.raw.create_image(&vk_info, None)? };
-        let req = unsafe { self.shared.raw.get_image_memory_requirements(raw) };
-
         let block = unsafe {
-            self.mem_allocator.lock().alloc(
-                &*self.shared,
-                gpu_alloc::Request {
-                    size: req.size,
-                    align_mask: req.alignment - 1,
-                    usage: gpu_alloc::UsageFlags::FAST_DEVICE_ACCESS,
-                    memory_types: req.memory_type_bits & self.valid_ash_memory_types,
-                },
-            )?
+            self.alloc_memory(gpu_alloc::Request {
+                size: req.size,
+                align_mask: req.alignment - 1,
+                usage: gpu_alloc::UsageFlags::FAST_DEVICE_ACCESS,
+                memory_types: req.memory_type_bits & self.valid_ash_memory_types,
+            })?
         };
 
         unsafe {
@@ -1020,13 +1435,6 @@ impl crate::Device<super::Api> for super::Device {
                 .bind_image_memory(raw, *block.memory(), block.offset())?
         };
 
-        if let Some(label) = desc.label {
-            unsafe {
-                self.shared
-                    .set_object_name(vk::ObjectType::IMAGE, raw, label)
-            };
-        }
-
         Ok(super::Texture {
             raw,
             drop_guard: None,
@@ -1034,8 +1442,8 @@ impl crate::Device<super::Api> for super::Device {
             usage: desc.usage,
             format: desc.format,
             raw_flags,
-            copy_size,
-            view_formats: wgt_view_formats,
+            copy_size: desc.copy_extent(),
+            view_formats,
         })
     }
     unsafe fn destroy_texture(&self, texture: super::Texture) {
@@ -1043,7 +1451,7 @@ impl crate::Device<super::Api> for super::Device {
             unsafe { self.shared.raw.destroy_image(texture.raw, None) };
         }
         if let Some(block) = texture.block {
-            unsafe { self.mem_allocator.lock().dealloc(&*self.shared, block) };
+            unsafe { self.dealloc_memory(block) };
         }
     }
 
@@ -1165,13 +1573,146 @@ impl crate::Device<super::Api> for super::Device {
         unsafe { self.shared.raw.destroy_sampler(sampler.raw, None) };
     }
 
+    unsafe fn create_heap(
+        &self,
+        desc: &crate::HeapDescriptor,
+    ) -> Result<super::Heap, crate::DeviceError> {
+        let memory_type = self
+            .heap_memory_type()
+            .ok_or(crate::DeviceError::OutOfMemory)?;
+
+        let vk_info = vk::MemoryAllocateInfo::builder()
+            .allocation_size(desc.size)
+            .memory_type_index(memory_type);
+        let raw = unsafe { self.shared.raw.allocate_memory(&vk_info, None)? };
+
+        // The resources placed in the heap aren't tracked, it counts as used in full.
+        let mut memory_tracker = self.shared.memory_tracker.lock();
+        memory_tracker.allocate(raw, memory_type, desc.size);
+        memory_tracker.bind(raw, 0, desc.size);
+        drop(memory_tracker);
+
+        if let Some(label) = desc.label {
+            unsafe {
+                self.shared
+                    .set_object_name(vk::ObjectType::DEVICE_MEMORY, raw, label)
+            };
+        }
+
+        Ok(super::Heap {
+            raw,
+            memory_type,
+            size: desc.size,
+        })
+    }
+    unsafe fn destroy_heap(&self, heap: super::Heap) {
+        self.shared.memory_tracker.lock().deallocate(heap.raw);
+        unsafe { self.shared.raw.free_memory(heap.raw, None) };
+    }
+    unsafe fn create_buffer_in_heap(
+        &self,
+        desc: &crate::BufferDescriptor,
+        heap: &super::Heap,
+        offset: wgt::BufferAddress,
+    ) -> Result<crate::PlacedResource<super::Buffer>, crate::HeapPlacementError> {
+        let vk_info = vk::BufferCreateInfo::builder()
+            .size(desc.size)
+            .usage(conv::map_buffer_usage(desc.usage))
+            .sharing_mode(vk::SharingMode::EXCLUSIVE);
+
+        let raw = unsafe {
+            self.shared
+                .raw
+                .create_buffer(&vk_info, None)
+                .map_err(crate::DeviceError::from)?
+        };
+        let req = unsafe { self.shared.raw.get_buffer_memory_requirements(raw) };
+        let result = heap.check_placement(&req, offset).and_then(|()| {
+            unsafe { self.shared.raw.bind_buffer_memory(raw, heap.raw, offset) }
+                .map_err(|err| crate::DeviceError::from(err).into())
+        });
+        if let Err(err) = result {
+            unsafe { self.shared.raw.destroy_buffer(raw, None) };
+            return Err(err);
+        }
+
+        if let Some(label) = desc.label {
+            unsafe {
+                self.shared
+                    .set_object_name(vk::ObjectType::BUFFER, raw, label)
+            };
+        }
+
+        Ok(crate::PlacedResource {
+            raw: super::Buffer { raw, block: None },
+            size: req.size,
+        })
+    }
+    unsafe fn create_texture_in_heap(
+        &self,
+        desc: &crate::TextureDescriptor,
+        heap: &super::Heap,
+        offset: wgt::BufferAddress,
+    ) -> Result<crate::PlacedResource<super::Texture>, crate::HeapPlacementError> {
+        let (raw, raw_flags, view_formats) = unsafe { self.create_image(desc)? };
+        let req = unsafe { self.shared.raw.get_image_memory_requirements(raw) };
+        let result = heap.check_placement(&req, offset).and_then(|()| {
+            unsafe { self.shared.raw.bind_image_memory(raw, heap.raw, offset) }
+                .map_err(|err| crate::DeviceError::from(err).into())
+        });
+        if let Err(err) = result {
+            unsafe { self.shared.raw.destroy_image(raw, None) };
+            return Err(err);
+        }
+
+        Ok(crate::PlacedResource {
+            raw: super::Texture {
+                raw,
+                drop_guard: None,
+                block: None,
+                usage: desc.usage,
+                format: desc.format,
+                raw_flags,
+                copy_size: desc.copy_extent(),
+                view_formats,
+            },
+            size: req.size,
+        })
+    }
+
+    unsafe fn create_external_semaphore(
+        &self,
+        desc: &crate::ExternalSemaphoreDescriptor,
+    ) -> Result<super::ExternalSemaphore, crate::DeviceError> {
+        // The semaphore is of no use to other APIs if it can't be exported.
+        self.external_semaphore_fd()?;
+        let raw = self.create_external_timeline_semaphore()?;
+
+        if let Some(label) = desc.label {
+            unsafe {
+                self.shared
+                    .set_object_name(vk::ObjectType::SEMAPHORE, raw, label)
+            };
+        }
+
+        Ok(super::ExternalSemaphore { raw })
+    }
+    unsafe fn destroy_external_semaphore(&self, semaphore: super::ExternalSemaphore) {
+        unsafe { self.shared.raw.destroy_semaphore(semaphore.raw, None) };
+    }
+
     unsafe fn create_command_encoder(
         &self,
         desc: &crate::CommandEncoderDescriptor<super::Api>,
     ) -> Result<super::CommandEncoder, crate::DeviceError> {
+        let flags = if desc.reusable {
+            vk::CommandPoolCreateFlags::empty()
+        } else {
+            vk::CommandPoolCreateFlags::TRANSIENT
+        };
         let vk_info = vk::CommandPoolCreateInfo::builder()
             .queue_family_index(desc.queue.family_index)
-            .flags(vk::CommandPoolCreateFlags::TRANSIENT)
+            .flags(flags)
             .build();
         let raw = unsafe { self.shared.raw.create_command_pool(&vk_info, None)? };
 
@@ -1185,6 +1726,7 @@ impl crate::Device<super::Api> for super::Device {
             discarded: Vec::new(),
             rpass_debug_marker_active: false,
             end_of_pass_timer_query: None,
+            reusable: desc.reusable,
         })
     }
     unsafe fn destroy_command_encoder(&self, cmd_encoder: super::CommandEncoder) {
@@ -1246,6 +1788,9 @@ impl crate::Device<super::Api> for super::Device {
                 wgt::BindingType::StorageTexture { .. } => {
                     desc_count.storage_image += count;
                 }
+                wgt::BindingType::AccelerationStructure => {
+                    desc_count.acceleration_structure += count;
+                }
             }
         }
 
@@ -1420,6 +1965,9 @@ impl crate::Device<super::Api> for super::Device {
         let mut buffer_infos = Vec::with_capacity(desc.buffers.len());
         let mut sampler_infos = Vec::with_capacity(desc.samplers.len());
         let mut image_infos = Vec::with_capacity(desc.textures.len());
+        let mut raw_acceleration_structures =
+            Vec::with_capacity(desc.acceleration_structures.len());
+        let mut acceleration_structure_infos = Vec::with_capacity(desc.entries.len());
         for entry in desc.entries {
             let (ty, size) = desc.layout.types[entry.binding as usize];
             if size == 0 {
@@ -1479,6 +2027,26 @@ impl crate::Device<super::Api> for super::Device {
                     ));
                     write.buffer_info(&buffer_infos[index..])
                 }
+                vk::DescriptorType::ACCELERATION_STRUCTURE_KHR => {
+                    let index = raw_acceleration_structures.len();
+                    let start = entry.resource_index;
+                    let end = start + entry.count;
+                    raw_acceleration_structures.extend(
+                        desc.acceleration_structures[start as usize..end as usize]
+                            .iter()
+                            .map(|acceleration_structure| acceleration_structure.raw),
+                    );
+                    let info_index = acceleration_structure_infos.len();
+                    acceleration_structure_infos.push(
+                        vk::WriteDescriptorSetAccelerationStructureKHR::builder()
+                            .acceleration_structures(&raw_acceleration_structures[index..])
+                            .build(),
+                    );
+                    // The descriptor count can't be inferred from the extension struct.
+                    let mut write = write.push_next(&mut acceleration_structure_infos[info_index]);
+                    write.descriptor_count = entry.count;
+                    write
+                }
                 _ => unreachable!(),
             };
             writes.push(write.build());
@@ -1758,12 +2326,17 @@ impl crate::Device<super::Api> for super::Device {
                 .build()
         }];
 
+        let pipeline_cache = desc
+            .cache
+            .map(|it| it.raw)
+            .unwrap_or(vk::PipelineCache::null());
+
         let mut raw_vec = {
             profiling::scope!("vkCreateGraphicsPipelines");
             unsafe {
                 self.shared
                     .raw
-                    .create_graphics_pipelines(vk::PipelineCache::null(), &vk_infos, None)
+                    .create_graphics_pipelines(pipeline_cache, &vk_infos, None)
                     .map_err(|(_, e)| crate::DeviceError::from(e))
             }?
         };
@@ -1810,12 +2383,17 @@ impl crate::Device<super::Api> for super::Device {
                 .build()
         }];
 
+        let pipeline_cache = desc
+            .cache
+            .map(|it| it.raw)
+            .unwrap_or(vk::PipelineCache::null());
+
         let mut raw_vec = {
             profiling::scope!("vkCreateComputePipelines");
             unsafe {
                 self.shared
                     .raw
-                    .create_compute_pipelines(vk::PipelineCache::null(), &vk_infos, None)
+                    .create_compute_pipelines(pipeline_cache, &vk_infos, None)
                     .map_err(|(_, e)| crate::DeviceError::from(e))
             }?
         };
@@ -1838,6 +2416,69 @@ impl crate::Device<super::Api> for super::Device {
         unsafe { self.shared.raw.destroy_pipeline(pipeline.raw, None) };
     }
 
+    unsafe fn create_pipeline_cache(
+        &self,
+        desc: &crate::PipelineCacheDescriptor<'_>,
+    ) -> Result<super::PipelineCache, crate::PipelineCacheError> {
+        let mut info = vk::PipelineCacheCreateInfo::builder();
+        if let Some(data) = desc.data {
+            info = info.initial_data(data)
+        }
+        profiling::scope!("vkCreatePipelineCache");
+        let raw = unsafe { self.shared.raw.create_pipeline_cache(&info, None) }
+            .map_err(crate::DeviceError::from)?;
+
+        if let Some(label) = desc.label {
+            unsafe {
+                self.shared
+                    .set_object_name(vk::ObjectType::PIPELINE_CACHE, raw, label)
+            };
+        }
+
+        Ok(super::PipelineCache { raw })
+    }
+    unsafe fn destroy_pipeline_cache(&self, cache: super::PipelineCache) {
+        unsafe { self.shared.raw.destroy_pipeline_cache(cache.raw, None) }
+    }
+    unsafe fn pipeline_cache_get_data(&self, cache: &super::PipelineCache) -> Option<Vec<u8>> {
+        profiling::scope!("vkGetPipelineCacheData");
+        unsafe { self.shared.raw.get_pipeline_cache_data(cache.raw) }.ok()
+    }
+    fn pipeline_cache_validation_key(&self) -> Option<[u8; 16]> {
+        Some(self.shared.pipeline_cache_validation_key)
+    }
+
+    fn memory_report(&self) -> crate::MemoryReport {
+        let mut report = self.shared.memory_tracker.lock().report();
+
+        if self
+            .shared
+            .enabled_extensions
+            .contains(&vk::ExtMemoryBudgetFn::name())
+        {
+            if let Some(ref get_device_properties) =
+                self.shared.instance.get_physical_device_properties
+            {
+                let mut budget = vk::PhysicalDeviceMemoryBudgetPropertiesEXT::default();
+                let mut properties2 = vk::PhysicalDeviceMemoryProperties2KHR::builder()
+                    .push_next(&mut budget)
+                    .build();
+                unsafe {
+                    get_device_properties.get_physical_device_memory_properties2(
+                        self.shared.physical_device,
+                        &mut properties2,
+                    )
+                };
+                for (i, heap) in report.heaps.iter_mut().enumerate() {
+                    heap.budget = Some(budget.heap_budget[i]);
+                    heap.process_usage = Some(budget.heap_usage[i]);
+                }
+            }
+        }
+
+        report
+    }
+
     unsafe fn create_query_set(
         &self,
         desc: &wgt::QuerySetDescriptor<crate::Label>,
@@ -2006,6 +2647,125 @@ impl crate::Device<super::Api> for super::Device {
             }
         }
     }
+
+    unsafe fn create_acceleration_structure(
+        &self,
+        desc: &crate::AccelerationStructureDescriptor,
+    ) -> Result<super::AccelerationStructure, crate::DeviceError> {
+        let ray_tracing_functions = self.shared.acceleration_structure_fn();
+
+        let vk_buffer_info = vk::BufferCreateInfo::builder()
+            .size(desc.size)
+            .usage(
+                vk::BufferUsageFlags::ACCELERATION_STRUCTURE_STORAGE_KHR
+                    | vk::BufferUsageFlags::SHADER_DEVICE_ADDRESS,
+            )
+            .sharing_mode(vk::SharingMode::EXCLUSIVE);
+
+        let raw_buffer = unsafe { self.shared.raw.create_buffer(&vk_buffer_info, None)? };
+        let req = unsafe { self.shared.raw.get_buffer_memory_requirements(raw_buffer) };
+
+        let block = unsafe {
+            self.alloc_memory(gpu_alloc::Request {
+                size: req.size,
+                align_mask: req.alignment - 1,
+                usage: gpu_alloc::UsageFlags::FAST_DEVICE_ACCESS
+                    | gpu_alloc::UsageFlags::DEVICE_ADDRESS,
+                memory_types: req.memory_type_bits & self.valid_ash_memory_types,
+            })?
+        };
+
+        unsafe {
+            self.shared
+                .raw
+                .bind_buffer_memory(raw_buffer, *block.memory(), block.offset())?
+        };
+
+        let vk_info = vk::AccelerationStructureCreateInfoKHR::builder()
+            .buffer(raw_buffer)
+            .offset(0)
+            .size(desc.size)
+            .ty(conv::map_acceleration_structure_format(desc.format));
+
+        let raw = unsafe { ray_tracing_functions.create_acceleration_structure(&vk_info, None)? };
+
+        if let Some(label) = desc.label {
+            unsafe {
+                self.shared
+                    .set_object_name(vk::ObjectType::BUFFER, raw_buffer, label);
+                self.shared
+                    .set_object_name(vk::ObjectType::ACCELERATION_STRUCTURE_KHR, raw, label);
+            }
+        }
+
+        Ok(super::AccelerationStructure {
+            raw,
+            buffer: raw_buffer,
+            block: Mutex::new(block),
+        })
+    }
+    unsafe fn get_acceleration_structure_build_sizes(
+        &self,
+        desc: &crate::GetAccelerationStructureBuildSizesDescriptor<super::Api>,
+    ) -> crate::AccelerationStructureBuildSizes {
+        let ray_tracing_functions = self.shared.acceleration_structure_fn();
+
+        let (geometries, ranges) =
+            unsafe { self.shared.map_acceleration_structure_entries(desc.entries) };
+        let max_primitive_counts = ranges
+            .iter()
+            .map(|range| range.primitive_count)
+            .collect::<Vec<_>>();
+        let ty = match *desc.entries {
+            crate::AccelerationStructureEntries::Instances(_) => {
+                vk::AccelerationStructureTypeKHR::TOP_LEVEL
+            }
+            crate::AccelerationStructureEntries::Triangles(_) => {
+                vk::AccelerationStructureTypeKHR::BOTTOM_LEVEL
+            }
+        };
+
+        let geometry_info = vk::AccelerationStructureBuildGeometryInfoKHR::builder()
+            .ty(ty)
+            .flags(conv::map_acceleration_structure_flags(desc.flags))
+            .geometries(&geometries);
+
+        let raw = unsafe {
+            ray_tracing_functions.get_acceleration_structure_build_sizes(
+                vk::AccelerationStructureBuildTypeKHR::DEVICE,
+                &geometry_info,
+                &max_primitive_counts,
+            )
+        };
+
+        crate::AccelerationStructureBuildSizes {
+            acceleration_structure_size: raw.acceleration_structure_size,
+            update_scratch_size: raw.update_scratch_size,
+            build_scratch_size: raw.build_scratch_size,
+        }
+    }
+    unsafe fn get_acceleration_structure_device_address(
+        &self,
+        acceleration_structure: &super::AccelerationStructure,
+    ) -> wgt::BufferAddress {
+        let ray_tracing_functions = self.shared.acceleration_structure_fn();
+        let info = vk::AccelerationStructureDeviceAddressInfoKHR::builder()
+            .acceleration_structure(acceleration_structure.raw);
+        unsafe { ray_tracing_functions.get_acceleration_structure_device_address(&info) }
+    }
+    unsafe fn destroy_acceleration_structure(
+        &self,
+        acceleration_structure: super::AccelerationStructure,
+    ) {
+        let ray_tracing_functions = self.shared.acceleration_structure_fn();
+        unsafe {
+            ray_tracing_functions.destroy_acceleration_structure(acceleration_structure.raw, None);
+            self.shared
+                .raw
+                .destroy_buffer(acceleration_structure.buffer, None);
+            self.dealloc_memory(acceleration_structure.block.into_inner());
+        }
+    }
 }
 
 impl From<gpu_alloc::AllocationError> for crate::DeviceError {
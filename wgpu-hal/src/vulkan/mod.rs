@@ -31,7 +31,7 @@ mod conv;
 mod device;
 mod instance;
 
-use std::{borrow::Borrow, ffi::CStr, fmt, num::NonZeroU32, sync::Arc};
+use std::{borrow::Borrow, collections::BTreeMap, ffi::CStr, fmt, num::NonZeroU32, sync::Arc};
 
 use arrayvec::ArrayVec;
 use ash::{
@@ -70,6 +70,11 @@ impl crate::Api for Api {
     type ShaderModule = ShaderModule;
     type RenderPipeline = RenderPipeline;
     type ComputePipeline = ComputePipeline;
+    type PipelineCache = PipelineCache;
+    type Heap = Heap;
+    type ExternalSemaphore = ExternalSemaphore;
+
+    type AccelerationStructure = AccelerationStructure;
 }
 
 struct DebugUtils {
@@ -178,6 +183,8 @@ enum ExtensionFn<T> {
 struct DeviceExtensionFunctions {
     draw_indirect_count: Option<khr::DrawIndirectCount>,
     timeline_semaphore: Option<ExtensionFn<khr::TimelineSemaphore>>,
+    acceleration_structure: Option<khr::AccelerationStructure>,
+    external_semaphore_fd: Option<khr::ExternalSemaphoreFd>,
 }
 
 /// Set of internal capabilities, which don't show up in the exposed
@@ -305,11 +312,33 @@ struct DeviceShared {
     enabled_extensions: Vec<&'static CStr>,
     extension_fns: DeviceExtensionFunctions,
     vendor_id: u32,
+    pipeline_cache_validation_key: [u8; 16],
     timestamp_period: f32,
     private_caps: PrivateCapabilities,
     workarounds: Workarounds,
     render_passes: Mutex<rustc_hash::FxHashMap<RenderPassKey, vk::RenderPass>>,
     framebuffers: Mutex<rustc_hash::FxHashMap<FramebufferKey, vk::Framebuffer>>,
+    memory_tracker: Mutex<MemoryTracker>,
+}
+
+/// The device memory allocated by `gpu_alloc`, and the ranges of it bound to resources.
+///
+/// `gpu_alloc` doesn't expose any statistics, so we keep our own for
+/// `Device::memory_report`.
+#[derive(Debug)]
+struct MemoryTracker {
+    /// The heap each memory type allocates from.
+    memory_type_heaps: Vec<u32>,
+    heap_sizes: Vec<u64>,
+    blocks: rustc_hash::FxHashMap<vk::DeviceMemory, MemoryBlockInfo>,
+}
+
+#[derive(Debug)]
+struct MemoryBlockInfo {
+    memory_type: u32,
+    size: u64,
+    /// Sizes of the ranges bound to resources, keyed by their offset.
+    used_ranges: BTreeMap<u64, u64>,
 }
 
 pub struct Device {
@@ -343,6 +372,13 @@ pub struct Buffer {
     block: Option<Mutex<gpu_alloc::MemoryBlock<vk::DeviceMemory>>>,
 }
 
+#[derive(Debug)]
+pub struct AccelerationStructure {
+    raw: vk::AccelerationStructureKHR,
+    buffer: vk::Buffer,
+    block: Mutex<gpu_alloc::MemoryBlock<vk::DeviceMemory>>,
+}
+
 #[derive(Debug)]
 pub struct Texture {
     raw: vk::Image,
@@ -437,6 +473,10 @@ pub struct CommandEncoder {
     /// If set, the end of the next render/compute pass will write a timestamp at
     /// the given pool & location.
     end_of_pass_timer_query: Option<(vk::QueryPool, u32)>,
+
+    /// Command buffers are begun with `SIMULTANEOUS_USE` rather than
+    /// `ONE_TIME_SUBMIT`, so that they can be submitted again.
+    reusable: bool,
 }
 
 impl fmt::Debug for CommandEncoder {
@@ -472,6 +512,36 @@ pub struct ComputePipeline {
     raw: vk::Pipeline,
 }
 
+#[derive(Debug)]
+pub struct PipelineCache {
+    raw: vk::PipelineCache,
+}
+
+/// A single allocation of device memory, that resources are bound to directly
+/// instead of going through `gpu_alloc`.
+#[derive(Debug)]
+pub struct Heap {
+    raw: vk::DeviceMemory,
+    memory_type: u32,
+    size: u64,
+}
+
+/// A timeline semaphore that can be exported to, or imported from, an opaque
+/// file descriptor.
+#[derive(Debug)]
+pub struct ExternalSemaphore {
+    raw: vk::Semaphore,
+}
+
+impl ExternalSemaphore {
+    /// # Safety
+    ///
+    /// - The semaphore handle must not be manually destroyed
+    pub unsafe fn raw_handle(&self) -> vk::Semaphore {
+        self.raw
+    }
+}
+
 #[derive(Debug)]
 pub struct QuerySet {
     raw: vk::QueryPool,
@@ -552,11 +622,58 @@ impl Fence {
     }
 }
 
+/// How a queue operation signals a [`Fence`].
+enum SignalTarget {
+    /// The timeline semaphore to signal along with the other semaphores.
+    Semaphore(vk::Semaphore),
+    /// The fence to pass to the operation.
+    Fence(vk::Fence),
+}
+
+impl Queue {
+    fn prepare_signal(
+        &self,
+        fence: &mut Fence,
+        value: crate::FenceValue,
+    ) -> Result<SignalTarget, crate::DeviceError> {
+        fence.maintain(&self.device.raw)?;
+        Ok(match *fence {
+            Fence::TimelineSemaphore(raw) => SignalTarget::Semaphore(raw),
+            Fence::FencePool {
+                ref mut active,
+                ref mut free,
+                ..
+            } => {
+                let raw = match free.pop() {
+                    Some(raw) => raw,
+                    None => unsafe {
+                        self.device
+                            .raw
+                            .create_fence(&vk::FenceCreateInfo::builder(), None)?
+                    },
+                };
+                active.push((value, raw));
+                SignalTarget::Fence(raw)
+            }
+        })
+    }
+}
+
 impl crate::Queue<Api> for Queue {
     unsafe fn submit(
         &mut self,
         command_buffers: &[&CommandBuffer],
         signal_fence: Option<(&mut Fence, crate::FenceValue)>,
+    ) -> Result<(), crate::DeviceError> {
+        unsafe { crate::Queue::submit_with_sync(self, command_buffers, &[], &[], signal_fence) }
+    }
+
+    unsafe fn submit_with_sync(
+        &mut self,
+        command_buffers: &[&CommandBuffer],
+        waits: &[(&ExternalSemaphore, u64)],
+        signals: &[(&ExternalSemaphore, u64)],
+        signal_fence: Option<(&mut Fence, crate::FenceValue)>,
     ) -> Result<(), crate::DeviceError> {
         let vk_cmd_buffers = command_buffers
             .iter()
@@ -565,58 +682,61 @@ impl crate::Queue<Api> for Queue {
 
         let mut vk_info = vk::SubmitInfo::builder().command_buffers(&vk_cmd_buffers);
 
-        let mut fence_raw = vk::Fence::null();
-        let mut vk_timeline_info;
-        let mut signal_semaphores = [vk::Semaphore::null(), vk::Semaphore::null()];
-        let signal_values;
+        // The values of binary semaphores are ignored.
+        let mut wait_semaphores = Vec::with_capacity(waits.len() + 1);
+        let mut wait_values = Vec::with_capacity(waits.len() + 1);
+        let mut wait_stage_masks = Vec::with_capacity(waits.len() + 1);
+        let mut signal_semaphores = Vec::with_capacity(signals.len() + 2);
+        let mut signal_values = Vec::with_capacity(signals.len() + 2);
 
+        let mut fence_raw = vk::Fence::null();
+        let mut fence_semaphore = None;
         if let Some((fence, value)) = signal_fence {
-            fence.maintain(&self.device.raw)?;
-            match *fence {
-                Fence::TimelineSemaphore(raw) => {
-                    signal_values = [!0, value];
-                    signal_semaphores[1] = raw;
-                    vk_timeline_info = vk::TimelineSemaphoreSubmitInfo::builder()
-                        .signal_semaphore_values(&signal_values);
-                    vk_info = vk_info.push_next(&mut vk_timeline_info);
-                }
-                Fence::FencePool {
-                    ref mut active,
-                    ref mut free,
-                    ..
-                } => {
-                    fence_raw = match free.pop() {
-                        Some(raw) => raw,
-                        None => unsafe {
-                            self.device
-                                .raw
-                                .create_fence(&vk::FenceCreateInfo::builder(), None)?
-                        },
-                    };
-                    active.push((value, fence_raw));
-                }
+            match self.prepare_signal(fence, value)? {
+                SignalTarget::Semaphore(raw) => fence_semaphore = Some((raw, value)),
+                SignalTarget::Fence(raw) => fence_raw = raw,
             }
         }
 
-        let wait_stage_mask = [vk::PipelineStageFlags::TOP_OF_PIPE];
         let sem_index = match self.relay_index {
             Some(old_index) => {
-                vk_info = vk_info
-                    .wait_semaphores(&self.relay_semaphores[old_index..old_index + 1])
-                    .wait_dst_stage_mask(&wait_stage_mask);
+                wait_semaphores.push(self.relay_semaphores[old_index]);
+                wait_values.push(!0);
+                wait_stage_masks.push(vk::PipelineStageFlags::TOP_OF_PIPE);
                 (old_index + 1) % self.relay_semaphores.len()
             }
             None => 0,
         };
         self.relay_index = Some(sem_index);
-        signal_semaphores[0] = self.relay_semaphores[sem_index];
+        signal_semaphores.push(self.relay_semaphores[sem_index]);
+        signal_values.push(!0);
 
-        let signal_count = if signal_semaphores[1] == vk::Semaphore::null() {
-            1
-        } else {
-            2
-        };
-        vk_info = vk_info.signal_semaphores(&signal_semaphores[..signal_count]);
+        for &(semaphore, value) in waits {
+            wait_semaphores.push(semaphore.raw);
+            wait_values.push(value);
+            wait_stage_masks.push(vk::PipelineStageFlags::ALL_COMMANDS);
+        }
+        for &(semaphore, value) in signals {
+            signal_semaphores.push(semaphore.raw);
+            signal_values.push(value);
+        }
+        if let Some((raw, value)) = fence_semaphore {
+            signal_semaphores.push(raw);
+            signal_values.push(value);
+        }
+
+        vk_info = vk_info
+            .wait_semaphores(&wait_semaphores)
+            .wait_dst_stage_mask(&wait_stage_masks)
+            .signal_semaphores(&signal_semaphores);
+
+        let mut vk_timeline_info;
+        if !waits.is_empty() || signal_semaphores.len() > 1 {
+            vk_timeline_info = vk::TimelineSemaphoreSubmitInfo::builder()
+                .wait_semaphore_values(&wait_values)
+                .signal_semaphore_values(&signal_values);
+            vk_info = vk_info.push_next(&mut vk_timeline_info);
+        }
 
         profiling::scope!("vkQueueSubmit");
         unsafe {
@@ -665,6 +785,148 @@ impl crate::Queue<Api> for Queue {
         Ok(())
     }
 
+    unsafe fn bind_sparse(
+        &mut self,
+        buffer_bindings: &[crate::SparseBufferBinding<Api>],
+        texture_bindings: &[crate::SparseTextureBinding<Api>],
+        signal_fence: Option<(&mut Fence, crate::FenceValue)>,
+    ) -> Result<(), crate::DeviceError> {
+        fn map_memory(memory: &Option<crate::SparseMemory<Api>>) -> (vk::DeviceMemory, u64) {
+            memory
+                .as_ref()
+                .map_or((vk::DeviceMemory::null(), 0), |memory| {
+                    (memory.heap.raw, memory.offset)
+                })
+        }
+
+        let buffer_binds = buffer_bindings
+            .iter()
+            .map(|binding| {
+                let (memory, memory_offset) = map_memory(&binding.memory);
+                vk::SparseMemoryBind {
+                    resource_offset: binding.range.start,
+                    size: binding.range.end - binding.range.start,
+                    memory,
+                    memory_offset,
+                    flags: vk::SparseMemoryBindFlags::empty(),
+                }
+            })
+            .collect::<Vec<_>>();
+        let buffer_infos = buffer_bindings
+            .iter()
+            .zip(buffer_binds.iter())
+            .map(|(binding, bind)| {
+                vk::SparseBufferMemoryBindInfo::builder()
+                    .buffer(binding.buffer.raw)
+                    .binds(std::slice::from_ref(bind))
+                    .build()
+            })
+            .collect::<Vec<_>>();
+
+        // Bind every tile on its own, so that they end up in row-major order
+        // in the memory.
+        let image_binds = texture_bindings
+            .iter()
+            .map(|binding| {
+                let texture = binding.texture;
+                let (tile_width, tile_height) = texture.format.sparse_tile_extent().unwrap();
+                let subresource = vk::ImageSubresource {
+                    aspect_mask: conv::map_aspects(crate::FormatAspects::from(texture.format)),
+                    mip_level: binding.mip_level,
+                    array_layer: binding.array_layer,
+                };
+                let (memory, memory_offset) = map_memory(&binding.memory);
+                let columns = binding.size.width / tile_width;
+                let rows = binding.size.height / tile_height;
+                (0..rows)
+                    .flat_map(|row| (0..columns).map(move |column| (row, column)))
+                    .map(|(row, column)| {
+                        let page = u64::from(row * columns + column);
+                        vk::SparseImageMemoryBind {
+                            subresource,
+                            offset: vk::Offset3D {
+                                x: (binding.origin.x + column * tile_width) as i32,
+                                y: (binding.origin.y + row * tile_height) as i32,
+                                z: 0,
+                            },
+                            extent: vk::Extent3D {
+                                width: tile_width,
+                                height: tile_height,
+                                depth: 1,
+                            },
+                            memory,
+                            memory_offset: if memory == vk::DeviceMemory::null() {
+                                0
+                            } else {
+                                memory_offset + page * wgt::SPARSE_PAGE_SIZE
+                            },
+                            flags: vk::SparseMemoryBindFlags::empty(),
+                        }
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let image_infos = texture_bindings
+            .iter()
+            .zip(image_binds.iter())
+            .map(|(binding, binds)| {
+                vk::SparseImageMemoryBindInfo::builder()
+                    .image(binding.texture.raw)
+                    .binds(binds)
+                    .build()
+            })
+            .collect::<Vec<_>>();
+
+        let mut vk_info = vk::BindSparseInfo::builder()
+            .buffer_binds(&buffer_infos)
+            .image_binds(&image_infos);
+
+        let mut fence_raw = vk::Fence::null();
+        let mut vk_timeline_info;
+        let mut signal_semaphores = [vk::Semaphore::null(), vk::Semaphore::null()];
+        let signal_values;
+
+        if let Some((fence, value)) = signal_fence {
+            match self.prepare_signal(fence, value)? {
+                SignalTarget::Semaphore(raw) => {
+                    signal_values = [!0, value];
+                    signal_semaphores[1] = raw;
+                    vk_timeline_info = vk::TimelineSemaphoreSubmitInfo::builder()
+                        .signal_semaphore_values(&signal_values);
+                    vk_info = vk_info.push_next(&mut vk_timeline_info);
+                }
+                SignalTarget::Fence(raw) => fence_raw = raw,
+            }
+        }
+
+        // Take part in the chain of relay semaphores, which orders the binds
+        // after the previous submissions and before the next ones.
+        let sem_index = match self.relay_index {
+            Some(old_index) => {
+                vk_info = vk_info.wait_semaphores(&self.relay_semaphores[old_index..old_index + 1]);
+                (old_index + 1) % self.relay_semaphores.len()
+            }
+            None => 0,
+        };
+        self.relay_index = Some(sem_index);
+        signal_semaphores[0] = self.relay_semaphores[sem_index];
+
+        let signal_count = if signal_semaphores[1] == vk::Semaphore::null() {
+            1
+        } else {
+            2
+        };
+        vk_info = vk_info.signal_semaphores(&signal_semaphores[..signal_count]);
+
+        profiling::scope!("vkQueueBindSparse");
+        unsafe {
+            self.device
+                .raw
+                .queue_bind_sparse(self.raw, &[vk_info.build()], fence_raw)?
+        };
+        Ok(())
+    }
+
     unsafe fn get_timestamp_period(&self) -> f32 {
         self.device.timestamp_period
     }
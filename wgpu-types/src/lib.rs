@@ -88,6 +88,9 @@ pub const PUSH_CONSTANT_ALIGNMENT: u32 = 4;
 pub const QUERY_SET_MAX_QUERIES: u32 = 8192;
 /// Size of a single piece of query data.
 pub const QUERY_SIZE: u32 = 8;
+/// Size of a page of sparse buffers and textures, and the alignment of the heap memory bound
+/// to them.
+pub const SPARSE_PAGE_SIZE: BufferAddress = 1 << 16;
 
 /// Backends supported by wgpu.
 #[repr(u8)]
@@ -108,6 +111,8 @@ pub enum Backend {
     Gl = 5,
     /// WebGPU in the browser
     BrowserWebGpu = 6,
+    /// Software rasterizer running on the CPU
+    Cpu = 7,
 }
 
 impl Backend {
@@ -121,6 +126,7 @@ impl Backend {
             Backend::Dx11 => "dx11",
             Backend::Gl => "gl",
             Backend::BrowserWebGpu => "webgpu",
+            Backend::Cpu => "cpu",
         }
     }
 }
@@ -162,6 +168,9 @@ bitflags::bitflags! {
         const DX11 = 1 << Backend::Dx11 as u32;
         /// Supported when targeting the web through webassembly
         const BROWSER_WEBGPU = 1 << Backend::BrowserWebGpu as u32;
+        /// Supported everywhere, with the `cpu` feature enabled. Much slower than
+        /// any of the GPU backends, intended for testing and headless use.
+        const CPU = 1 << Backend::Cpu as u32;
         /// All the apis that wgpu offers first tier of support for.
         ///
         /// Vulkan + Metal + DX12 + Browser WebGPU
@@ -231,7 +240,7 @@ bitflags::bitflags! {
     #[repr(transparent)]
     #[derive(Default)]
     #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
-    pub struct Features: u64 {
+    pub struct Features: u128 {
         //
         // ---- Start numbering at 1 << 0 ----
         //
@@ -760,8 +769,49 @@ bitflags::bitflags! {
         /// - Metal
         /// - OpenGL
         const SHADER_UNUSED_VERTEX_OUTPUT = 1 << 54;
-
-        // 54..59 available
+        /// Allows the creation of pipeline caches, which can be used to speed up pipeline
+        /// creation by reusing compiled shader code across runs of the application.
+        ///
+        /// Call [`Device::create_pipeline_cache`] to create a cache, and
+        /// [`PipelineCache::get_data`] to retrieve its contents for persisting to disk.
+        ///
+        /// Supported platforms:
+        /// - Vulkan
+        ///
+        /// This is a native only feature.
+        const PIPELINE_CACHE = 1 << 55;
+        /// Allows for the creation of ray-tracing acceleration structures.
+        ///
+        /// Bottom level acceleration structures (BLAS) are built from triangle
+        /// geometry stored in buffers with [`BufferUsages::BLAS_INPUT`], top level
+        /// acceleration structures (TLAS) from instance buffers with
+        /// [`BufferUsages::TLAS_INPUT`].
+        ///
+        /// Supported platforms:
+        /// - Vulkan
+        ///
+        /// This is a native-only feature.
+        const RAY_TRACING_ACCELERATION_STRUCTURE = 1 << 56;
+        /// Allows for the usage of ray queries in shaders, and binding top level
+        /// acceleration structures with [`BindingType::AccelerationStructure`].
+        ///
+        /// Requires [`Features::RAY_TRACING_ACCELERATION_STRUCTURE`].
+        ///
+        /// Supported platforms:
+        /// - Vulkan
+        ///
+        /// This is a native-only feature.
+        const RAY_QUERY = 1 << 57;
+        /// Allows command buffers to be submitted more than once, by creating their
+        /// encoder with [`CommandEncoderDescriptor::reusable`] set.
+        ///
+        /// Supported platforms:
+        /// - Vulkan
+        /// - DX12
+        /// - OpenGL
+        ///
+        /// This is a native only feature.
+        const REUSABLE_COMMAND_BUFFERS = 1 << 58;
 
         // Shader:
 
@@ -816,6 +866,74 @@ bitflags::bitflags! {
         /// - Vulkan (with dualSrcBlend)
         /// - DX12
         const DUAL_SOURCE_BLENDING = 1 << 63;
+
+        // Resources:
+
+        /// Allows memory to be allocated up front as a [`Heap`](../wgpu/struct.Heap.html),
+        /// and buffers and textures to be placed at an offset in it. Resources placed
+        /// at overlapping ranges alias each other.
+        ///
+        /// Only one of the resources that overlap may be used in a usage scope. Using
+        /// a resource makes the contents of the resources it overlaps undefined, and
+        /// they are zero initialized again when they are next read.
+        ///
+        /// Supported platforms:
+        /// - Vulkan
+        ///
+        /// This is a native only feature.
+        const RESOURCE_HEAPS = 1 << 64;
+        /// Allows buffers and textures to be created without any memory of their own,
+        /// with [`Device::create_sparse_buffer`] and [`Device::create_sparse_texture`].
+        /// Pages of [`SPARSE_PAGE_SIZE`] bytes of them are bound to the memory of heaps,
+        /// and unbound again, with [`Queue::update_sparse_bindings`].
+        ///
+        /// Without [`Features::SPARSE_RESIDENCY`], every page of a sparse buffer has to
+        /// be bound when it's used, and sparse textures can't be created.
+        ///
+        /// Heaps are created with [`Features::RESOURCE_HEAPS`], which has to be enabled
+        /// as well to bind any memory.
+        ///
+        /// Supported platforms:
+        /// - Vulkan (with sparseBinding)
+        ///
+        /// This is a native only feature.
+        ///
+        /// [`Device::create_sparse_buffer`]: ../wgpu/struct.Device.html#method.create_sparse_buffer
+        /// [`Device::create_sparse_texture`]: ../wgpu/struct.Device.html#method.create_sparse_texture
+        /// [`Queue::update_sparse_bindings`]: ../wgpu/struct.Queue.html#method.update_sparse_bindings
+        const SPARSE_BINDING = 1 << 65;
+        /// Allows sparse buffers and textures to be used while only some of their pages
+        /// are bound. Reading from a page that isn't bound returns zero, and writes to it
+        /// are discarded.
+        ///
+        /// Also allows sparse textures to be created. They are bound in tiles of
+        /// [`TextureFormat::sparse_tile_extent`] texels.
+        ///
+        /// Requires [`Features::SPARSE_BINDING`].
+        ///
+        /// Supported platforms:
+        /// - Vulkan (with sparseResidencyBuffer, sparseResidencyImage2D,
+        ///   residencyStandard2DBlockShape and residencyNonResidentStrict)
+        ///
+        /// This is a native only feature.
+        const SPARSE_RESIDENCY = 1 << 66;
+        /// Allows the creation of external semaphores with
+        /// [`Device::create_external_semaphore`], timeline semaphores that are shared
+        /// with other APIs and processes.
+        ///
+        /// A submission made with [`Queue::submit_with_sync`] waits for any of them to
+        /// reach a given value before it executes, and sets others to a given value once
+        /// it completes.
+        ///
+        /// Supported platforms:
+        /// - Vulkan 1.2+ on Unix (with VK_KHR_external_semaphore_fd, exported and
+        ///   imported as opaque file descriptors through wgpu-hal)
+        ///
+        /// This is a native only feature.
+        ///
+        /// [`Device::create_external_semaphore`]: ../wgpu/struct.Device.html#method.create_external_semaphore
+        /// [`Queue::submit_with_sync`]: ../wgpu/struct.Queue.html#method.submit_with_sync
+        const EXTERNAL_SEMAPHORES = 1 << 67;
     }
 }
 
@@ -824,12 +942,12 @@ impl_bitflags!(Features);
 impl Features {
     /// Mask of all features which are part of the upstream WebGPU standard.
     pub const fn all_webgpu_mask() -> Self {
-        Self::from_bits_truncate(0x0000_0000_0000_FFFF)
+        Self::from_bits_truncate(0xFFFF)
     }
 
     /// Mask of all features that are only available when targeting native (not web).
     pub const fn all_native_mask() -> Self {
-        Self::from_bits_truncate(0xFFFF_FFFF_FFFF_0000)
+        Self::from_bits_truncate(!0xFFFF)
     }
 }
 
@@ -846,6 +964,24 @@ bitflags::bitflags! {
         const DEBUG = 1 << 0;
         /// Enable validation, if possible.
         const VALIDATION = 1 << 1;
+        /// Validate that indexed draws only refer to vertices within the bound vertex buffers.
+        ///
+        /// The contents of index buffers are shadowed on the CPU to validate `draw_indexed`
+        /// calls, and indexed indirect draws of render passes are checked by a compute pass
+        /// that turns invalid draws into empty ones. This costs memory and time, so it is
+        /// meant to track down out-of-bounds vertex fetches rather than to be always enabled.
+        const INDEX_RANGE_VALIDATION = 1 << 2;
+        /// Validate the arguments of indirect draws and dispatches on the GPU.
+        ///
+        /// Before the indirect calls of render and compute passes are executed, internal
+        /// compute passes copy their arguments to a scratch buffer, turning the calls that
+        /// would be invalid for a direct call into empty ones: vertex, index or instance
+        /// ranges beyond the bound buffers, a non-zero first instance without
+        /// [`Features::INDIRECT_FIRST_INSTANCE`], and workgroup counts over
+        /// [`Limits::max_compute_workgroups_per_dimension`].
+        ///
+        /// Indirect draws recorded in render bundles are not validated.
+        const VALIDATION_INDIRECT_CALL = 1 << 3;
     }
 }
 
@@ -884,6 +1020,8 @@ impl InstanceFlags {
     /// The environment variables are named after the flags prefixed with "WGPU_". For example:
     /// - WGPU_DEBUG
     /// - WGPU_VALIDATION
+    /// - WGPU_INDEX_RANGE_VALIDATION
+    /// - WGPU_VALIDATION_INDIRECT_CALL
     pub fn with_env(mut self) -> Self {
         fn env(key: &str) -> Option<bool> {
             std::env::var(key).ok().map(|s| match s.as_str() {
@@ -898,6 +1036,12 @@ impl InstanceFlags {
         if let Some(bit) = env("WGPU_DEBUG") {
             self.set(Self::DEBUG, bit);
         }
+        if let Some(bit) = env("WGPU_INDEX_RANGE_VALIDATION") {
+            self.set(Self::INDEX_RANGE_VALIDATION, bit);
+        }
+        if let Some(bit) = env("WGPU_VALIDATION_INDIRECT_CALL") {
+            self.set(Self::VALIDATION_INDIRECT_CALL, bit);
+        }
 
         self
     }
@@ -1566,6 +1710,8 @@ pub struct DeviceDescriptor<L> {
     /// Limits that the device should support. If any limit is "better" than the limit exposed by
     /// the adapter, creating a device will panic.
     pub limits: Limits,
+    /// Hints for the memory allocator of the device.
+    pub memory_hints: MemoryHints,
 }
 
 impl<L> DeviceDescriptor<L> {
@@ -1575,10 +1721,111 @@ impl<L> DeviceDescriptor<L> {
             label: fun(&self.label),
             features: self.features,
             limits: self.limits.clone(),
+            memory_hints: self.memory_hints.clone(),
         }
     }
 }
 
+/// Hints to the device about the memory allocation strategy.
+///
+/// Some backends may ignore these hints.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "trace", derive(Serialize))]
+#[cfg_attr(feature = "replay", derive(Deserialize))]
+pub enum MemoryHints {
+    /// Favor performance over memory usage (the default value).
+    #[default]
+    Performance,
+    /// Favor memory usage over performance.
+    ///
+    /// Smaller memory blocks are allocated up front, which suits applications
+    /// that only create a handful of resources.
+    MemoryUsage,
+    /// Applications that have control over the content that is rendered
+    /// (typically games) may find an optimal compromise between memory
+    /// usage and performance by specifying the allocation configuration.
+    Manual {
+        /// Defines the range of allowed memory block sizes for sub-allocated
+        /// resources.
+        ///
+        /// The backend may attempt to group multiple resources into fewer
+        /// device memory blocks (sub-allocation) for performance reasons.
+        /// The start of the provided range specifies the initial memory
+        /// block size for sub-allocated resources. After running out of
+        /// space in existing memory blocks, the backend may chose to
+        /// progressively increase the block size of subsequent allocations
+        /// up to a limit specified by the end of the range.
+        ///
+        /// This does not limit resource sizes. If a resource does not fit
+        /// in the specified range, it will typically be placed in a dedicated
+        /// memory block.
+        suballocated_device_memory_block_size: Range<u64>,
+    },
+}
+
+/// GPU memory usage of a [`Device`](../wgpu/struct.Device.html).
+///
+/// Backends that don't track their allocations leave `heaps` and `memory_types` empty.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MemoryReport {
+    /// Usage of every memory heap of the device, indexed like the heaps of the backend.
+    pub heaps: Vec<MemoryHeapReport>,
+    /// Usage of every memory type of the device, indexed like the memory types of the backend.
+    pub memory_types: Vec<MemoryTypeReport>,
+    /// Totals of the buffers alive on the device.
+    pub buffers: ResourceMemoryReport,
+    /// Totals of the textures alive on the device.
+    pub textures: ResourceMemoryReport,
+}
+
+/// Memory usage of a single memory heap, see [`MemoryReport`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MemoryHeapReport {
+    /// Size of the heap, in bytes.
+    pub size: u64,
+    /// Bytes of device memory allocated from the heap.
+    pub allocated: u64,
+    /// Bytes of the allocated memory that are bound to resources.
+    pub used: u64,
+    /// Bytes the process can allocate from the heap before allocations may fail or
+    /// degrade performance, as reported by the driver.
+    ///
+    /// `None` if the driver does not report a budget.
+    pub budget: Option<u64>,
+    /// Bytes of the heap used by the process, as reported by the driver.
+    ///
+    /// This includes allocations made outside of wgpu. `None` if the driver does not
+    /// report it.
+    pub process_usage: Option<u64>,
+}
+
+/// Memory usage of a single memory type, see [`MemoryReport`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MemoryTypeReport {
+    /// Index of the heap this memory type allocates from.
+    pub heap_index: u32,
+    /// Bytes of device memory allocated with this memory type.
+    pub allocated: u64,
+    /// Bytes of the allocated memory that are bound to resources.
+    pub used: u64,
+    /// Number of device memory blocks allocated with this memory type.
+    pub block_count: u32,
+    /// Size of the largest range of an allocated block that is not bound to any resource.
+    pub largest_free_range: u64,
+}
+
+/// Totals of a type of resource, see [`MemoryReport`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ResourceMemoryReport {
+    /// Number of resources.
+    pub count: u32,
+    /// Bytes of the resources' contents.
+    ///
+    /// This is computed from the resource descriptors, and doesn't include any padding
+    /// or alignment the backend adds.
+    pub bytes: u64,
+}
+
 bitflags::bitflags! {
     /// Describes the shader stages that a binding will be visible from.
     ///
@@ -3279,6 +3526,27 @@ impl TextureFormat {
         }
     }
 
+    /// Returns the extent of a tile of a sparse texture with this format, in texels.
+    ///
+    /// A tile takes up [`SPARSE_PAGE_SIZE`] bytes of memory. Returns `None` for depth
+    /// and stencil formats, which can't be used with sparse textures.
+    pub fn sparse_tile_extent(&self) -> Option<(u32, u32)> {
+        if self.is_depth_stencil_format() {
+            return None;
+        }
+        let (block_width, block_height) = self.block_dimensions();
+        // The standard sparse image block shapes of Vulkan, in texel blocks.
+        let (width, height) = match self.block_size(None)? {
+            1 => (256, 256),
+            2 => (256, 128),
+            4 => (128, 128),
+            8 => (128, 64),
+            16 => (64, 64),
+            _ => return None,
+        };
+        Some((width * block_width, height * block_height))
+    }
+
     /// Returns the number of components this format has.
     pub fn components(&self) -> u8 {
         self.components_with_aspect(TextureAspect::All)
@@ -4077,6 +4345,22 @@ impl<T> Maintain<T> {
     }
 }
 
+/// Reason a device was lost, passed to the device lost callback.
+///
+/// Corresponds to [WebGPU `GPUDeviceLostReason`](
+/// https://gpuweb.github.io/gpuweb/#enumdef-gpudevicelostreason).
+#[repr(u8)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "trace", derive(Serialize))]
+#[cfg_attr(feature = "replay", derive(Deserialize))]
+pub enum DeviceLostReason {
+    /// The device was lost for a reason other than an explicit call to
+    /// `destroy`, for example a driver reset or the device being dropped.
+    Unknown = 0,
+    /// The device was lost because `destroy` was called on it.
+    Destroyed = 1,
+}
+
 /// State of the stencil operation (fixed-pipeline stage).
 ///
 /// For use in [`DepthStencilState`].
@@ -4608,6 +4892,16 @@ bitflags::bitflags! {
         const INDIRECT = 1 << 8;
         /// Allow a buffer to be the destination buffer for a [`CommandEncoder::resolve_query_set`] operation.
         const QUERY_RESOLVE = 1 << 9;
+        /// Allow a buffer to be the vertex, index or transform buffer of a bottom level
+        /// acceleration structure build.
+        ///
+        /// Requires [`Features::RAY_TRACING_ACCELERATION_STRUCTURE`].
+        const BLAS_INPUT = 1 << 10;
+        /// Allow a buffer to be the instance buffer of a top level acceleration
+        /// structure build.
+        ///
+        /// Requires [`Features::RAY_TRACING_ACCELERATION_STRUCTURE`].
+        const TLAS_INPUT = 1 << 11;
     }
 }
 
@@ -4649,6 +4943,52 @@ impl<L> BufferDescriptor<L> {
     }
 }
 
+/// Describes a [`Heap`](../wgpu/struct.Heap.html).
+///
+/// Requires [`Features::RESOURCE_HEAPS`].
+#[repr(C)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "trace", derive(Serialize))]
+#[cfg_attr(feature = "replay", derive(Deserialize))]
+pub struct HeapDescriptor<L> {
+    /// Debug label of a heap. This will show up in graphics debuggers for easy identification.
+    pub label: L,
+    /// Size of the heap, in bytes.
+    pub size: BufferAddress,
+}
+
+impl<L> HeapDescriptor<L> {
+    /// Takes a closure and maps the label of the heap descriptor into another.
+    pub fn map_label<K>(&self, fun: impl FnOnce(&L) -> K) -> HeapDescriptor<K> {
+        HeapDescriptor {
+            label: fun(&self.label),
+            size: self.size,
+        }
+    }
+}
+
+/// Describes an [`ExternalSemaphore`](../wgpu/struct.ExternalSemaphore.html).
+///
+/// Requires [`Features::EXTERNAL_SEMAPHORES`].
+#[repr(C)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "trace", derive(Serialize))]
+#[cfg_attr(feature = "replay", derive(Deserialize))]
+pub struct ExternalSemaphoreDescriptor<L> {
+    /// Debug label of an external semaphore. This will show up in graphics debuggers for easy
+    /// identification.
+    pub label: L,
+}
+
+impl<L> ExternalSemaphoreDescriptor<L> {
+    /// Takes a closure and maps the label of the external semaphore descriptor into another.
+    pub fn map_label<K>(&self, fun: impl FnOnce(&L) -> K) -> ExternalSemaphoreDescriptor<K> {
+        ExternalSemaphoreDescriptor {
+            label: fun(&self.label),
+        }
+    }
+}
+
 /// Describes a [`CommandEncoder`](../wgpu/struct.CommandEncoder.html).
 ///
 /// Corresponds to [WebGPU `GPUCommandEncoderDescriptor`](
@@ -4660,6 +5000,14 @@ impl<L> BufferDescriptor<L> {
 pub struct CommandEncoderDescriptor<L> {
     /// Debug label for the command encoder. This will show up in graphics debuggers for easy identification.
     pub label: L,
+    /// Whether the finished command buffer can be submitted more than once.
+    ///
+    /// Memory initialization and resource transitions are worked out again for
+    /// every submission. Submitting fails if a resource used by the command buffer
+    /// has been destroyed since it was recorded.
+    ///
+    /// Requires [`Features::REUSABLE_COMMAND_BUFFERS`].
+    pub reusable: bool,
 }
 
 impl<L> CommandEncoderDescriptor<L> {
@@ -4667,13 +5015,17 @@ impl<L> CommandEncoderDescriptor<L> {
     pub fn map_label<K>(&self, fun: impl FnOnce(&L) -> K) -> CommandEncoderDescriptor<K> {
         CommandEncoderDescriptor {
             label: fun(&self.label),
+            reusable: self.reusable,
         }
     }
 }
 
 impl<T> Default for CommandEncoderDescriptor<Option<T>> {
     fn default() -> Self {
-        Self { label: None }
+        Self {
+            label: None,
+            reusable: false,
+        }
     }
 }
 
@@ -5590,6 +5942,33 @@ impl<T> Default for RenderBundleDescriptor<Option<T>> {
     }
 }
 
+/// Describes a [`ComputeBundle`](../wgpu/struct.ComputeBundle.html).
+///
+/// Compute bundles have no WebGPU equivalent.
+#[repr(C)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "trace", derive(Serialize))]
+#[cfg_attr(feature = "replay", derive(Deserialize))]
+pub struct ComputeBundleDescriptor<L> {
+    /// Debug label of the compute bundle. This will show up in graphics debuggers for easy identification.
+    pub label: L,
+}
+
+impl<L> ComputeBundleDescriptor<L> {
+    /// Takes a closure and maps the label of the compute bundle descriptor into another.
+    pub fn map_label<K>(&self, fun: impl FnOnce(&L) -> K) -> ComputeBundleDescriptor<K> {
+        ComputeBundleDescriptor {
+            label: fun(&self.label),
+        }
+    }
+}
+
+impl<T> Default for ComputeBundleDescriptor<Option<T>> {
+    fn default() -> Self {
+        Self { label: None }
+    }
+}
+
 /// Layout of a texture in a buffer's memory.
 ///
 /// The bytes per row and rows per image can be hard to figure out so here are some examples:
@@ -5995,6 +6374,22 @@ pub enum BindingType {
         /// Dimension of the texture view that is going to be sampled.
         view_dimension: TextureViewDimension,
     },
+    /// A ray-tracing top level acceleration structure.
+    ///
+    /// Example WGSL syntax:
+    /// ```rust,ignore
+    /// @group(0) @binding(0)
+    /// var as: acceleration_structure;
+    /// ```
+    ///
+    /// Example GLSL syntax:
+    /// ```cpp,ignore
+    /// layout(binding = 0)
+    /// uniform accelerationStructureEXT as;
+    /// ```
+    ///
+    /// Requires [`Features::RAY_QUERY`].
+    AccelerationStructure,
 }
 
 impl BindingType {
@@ -6334,6 +6729,101 @@ impl ImageSubresourceRange {
     }
 }
 
+/// A hint that a buffer is about to be used in a particular way.
+///
+/// Used with `CommandEncoder::transition_resources` to move the buffer into
+/// that usage ahead of the commands that need it.
+#[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "trace", derive(serde::Serialize))]
+#[cfg_attr(feature = "replay", derive(serde::Deserialize))]
+pub struct BufferTransition<B> {
+    /// The buffer to transition.
+    pub buffer: B,
+    /// The usage the buffer will be used with next.
+    ///
+    /// Must be a subset of the usages the buffer was created with. Only read-only usages
+    /// may be combined with each other.
+    pub state: BufferUsages,
+}
+
+/// A hint that a range of texture subresources is about to be used in a particular way.
+///
+/// Used with `CommandEncoder::transition_resources` to move the subresources into
+/// that usage ahead of the commands that need it.
+#[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "trace", derive(serde::Serialize))]
+#[cfg_attr(feature = "replay", derive(serde::Deserialize))]
+pub struct TextureTransition<T> {
+    /// The texture to transition.
+    pub texture: T,
+    /// The subresources to transition.
+    pub subresource_range: ImageSubresourceRange,
+    /// The usage the subresources will be used with next.
+    ///
+    /// Must be a subset of the usages the texture was created with. Only read-only usages
+    /// may be combined with each other.
+    pub state: TextureUsages,
+}
+
+/// Heap memory bound to the pages of a sparse buffer or texture.
+#[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "trace", derive(serde::Serialize))]
+#[cfg_attr(feature = "replay", derive(serde::Deserialize))]
+pub struct SparseMemory<H> {
+    /// The heap the memory is taken from.
+    pub heap: H,
+    /// Offset of the memory in the heap, in bytes.
+    ///
+    /// Must be a multiple of [`SPARSE_PAGE_SIZE`]. The pages are bound to consecutive
+    /// ranges of the heap from this offset on.
+    pub offset: BufferAddress,
+}
+
+/// Binds memory to, or unbinds it from, a range of a sparse buffer.
+///
+/// Used with `Queue::update_sparse_bindings`.
+#[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "trace", derive(serde::Serialize))]
+#[cfg_attr(feature = "replay", derive(serde::Deserialize))]
+pub struct SparseBufferBinding<B, H> {
+    /// The sparse buffer to update.
+    pub buffer: B,
+    /// Start of the range, in bytes. Must be a multiple of [`SPARSE_PAGE_SIZE`].
+    pub offset: BufferAddress,
+    /// Size of the range, in bytes. Must be a multiple of [`SPARSE_PAGE_SIZE`], unless the
+    /// range ends at the end of the buffer.
+    pub size: BufferAddress,
+    /// The memory to bind the range to, or `None` to unbind it.
+    pub memory: Option<SparseMemory<H>>,
+}
+
+/// Binds memory to, or unbinds it from, a region of a sparse texture.
+///
+/// Used with `Queue::update_sparse_bindings`.
+#[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "trace", derive(serde::Serialize))]
+#[cfg_attr(feature = "replay", derive(serde::Deserialize))]
+pub struct SparseTextureBinding<T, H> {
+    /// The sparse texture to update.
+    pub texture: T,
+    /// The mip level of the region.
+    pub mip_level: u32,
+    /// The first texel of the region. `z` is the first array layer.
+    ///
+    /// `x` and `y` must be multiples of the [tile extent](TextureFormat::sparse_tile_extent).
+    pub origin: Origin3d,
+    /// The size of the region. `depth_or_array_layers` is the number of array layers.
+    ///
+    /// `width` and `height` must be multiples of the
+    /// [tile extent](TextureFormat::sparse_tile_extent).
+    pub size: Extent3d,
+    /// The memory to bind the region to, or `None` to unbind it.
+    ///
+    /// Every tile takes up a page of memory. Tiles are bound in row-major order, one
+    /// array layer after the other.
+    pub memory: Option<SparseMemory<H>>,
+}
+
 /// Color variation to use when sampler addressing mode is [`AddressMode::ClampToBorder`]
 #[repr(C)]
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
@@ -6532,6 +7022,208 @@ impl Default for ShaderBoundChecks {
     }
 }
 
+/// Messages produced while compiling a shader module.
+///
+/// Corresponds to [WebGPU `GPUCompilationInfo`](
+/// https://gpuweb.github.io/gpuweb/#gpucompilationinfo).
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CompilationInfo {
+    /// The messages, in the order they were produced.
+    pub messages: Vec<CompilationMessage>,
+}
+
+/// A single message produced while compiling a shader module.
+///
+/// Corresponds to [WebGPU `GPUCompilationMessage`](
+/// https://gpuweb.github.io/gpuweb/#gpucompilationmessage).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CompilationMessage {
+    /// Human readable description of the message.
+    pub message: String,
+    /// Severity of the message.
+    pub message_type: CompilationMessageType,
+    /// Location in the shader source the message refers to, if any.
+    pub location: Option<SourceLocation>,
+}
+
+/// Severity of a [`CompilationMessage`].
+///
+/// Corresponds to [WebGPU `GPUCompilationMessageType`](
+/// https://gpuweb.github.io/gpuweb/#enumdef-gpucompilationmessagetype).
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum CompilationMessageType {
+    /// The shader could not be compiled.
+    Error,
+    /// The shader compiled, but something in it is likely a mistake.
+    Warning,
+    /// Additional information, such as the context of a previous error.
+    Info,
+}
+
+/// Span of shader source text a [`CompilationMessage`] refers to.
+///
+/// Unlike WebGPU, `offset` and `length` count bytes (UTF-8 code units) rather
+/// than UTF-16 code units.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct SourceLocation {
+    /// 1-based line number.
+    pub line_number: u32,
+    /// 1-based column of the start of the span.
+    pub line_position: u32,
+    /// 0-based offset in bytes of the start of the span.
+    pub offset: u32,
+    /// Length in bytes of the span.
+    pub length: u32,
+}
+
+/// Size in bytes of a single instance in the instance buffer of a top level
+/// acceleration structure build.
+///
+/// The layout of an instance matches `VkAccelerationStructureInstanceKHR`.
+pub const TLAS_INSTANCE_SIZE: BufferAddress = 64;
+
+bitflags::bitflags! {
+    /// Flags for the creation of an acceleration structure.
+    ///
+    /// The `PREFER_FAST_TRACE` and `PREFER_FAST_BUILD` flags are mutually exclusive.
+    #[repr(transparent)]
+    #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+    pub struct AccelerationStructureFlags: u8 {
+        /// Allow the acceleration structure to be updated in place after it has been built,
+        /// see [`AccelerationStructureUpdateMode::PreferUpdate`].
+        const ALLOW_UPDATE = 1 << 0;
+        /// Prioritize traversal performance over build time.
+        const PREFER_FAST_TRACE = 1 << 1;
+        /// Prioritize build time over traversal performance.
+        const PREFER_FAST_BUILD = 1 << 2;
+        /// Minimize the memory used by the acceleration structure, possibly at the
+        /// cost of build time and traversal performance.
+        const LOW_MEMORY = 1 << 3;
+    }
+}
+
+impl_bitflags!(AccelerationStructureFlags);
+
+bitflags::bitflags! {
+    /// Flags for a geometry inside a bottom level acceleration structure.
+    #[repr(transparent)]
+    #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+    pub struct AccelerationStructureGeometryFlags: u8 {
+        /// The geometry is opaque: ray queries will report intersections with it as
+        /// committed candidates without requiring confirmation.
+        const OPAQUE = 1 << 0;
+        /// The implementation may not invoke any-hit processing more than once for
+        /// a single primitive of the geometry.
+        const NO_DUPLICATE_ANY_HIT_INVOCATION = 1 << 1;
+    }
+}
+
+impl_bitflags!(AccelerationStructureGeometryFlags);
+
+/// Selects how an acceleration structure is built.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "trace", derive(serde::Serialize))]
+#[cfg_attr(feature = "replay", derive(serde::Deserialize))]
+pub enum AccelerationStructureUpdateMode {
+    /// Always build the acceleration structure from scratch.
+    #[default]
+    Build,
+    /// Update the acceleration structure in place if it was built before and was
+    /// created with [`AccelerationStructureFlags::ALLOW_UPDATE`], otherwise build it
+    /// from scratch.
+    ///
+    /// Updating is only valid if the geometry layout did not change since the
+    /// last build; only the vertex positions and instance transforms may differ.
+    PreferUpdate,
+}
+
+/// Describes how to create a bottom level acceleration structure.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "trace", derive(serde::Serialize))]
+#[cfg_attr(feature = "replay", derive(serde::Deserialize))]
+pub struct CreateBlasDescriptor<L> {
+    /// Debug label for the acceleration structure.
+    pub label: L,
+    /// Flags for the acceleration structure.
+    pub flags: AccelerationStructureFlags,
+    /// How subsequent builds of the acceleration structure are performed.
+    pub update_mode: AccelerationStructureUpdateMode,
+}
+
+impl<L> CreateBlasDescriptor<L> {
+    /// Takes a closure and maps the label of the descriptor into another.
+    pub fn map_label<K>(&self, fun: impl FnOnce(&L) -> K) -> CreateBlasDescriptor<K> {
+        CreateBlasDescriptor {
+            label: fun(&self.label),
+            flags: self.flags,
+            update_mode: self.update_mode,
+        }
+    }
+}
+
+/// Describes how to create a top level acceleration structure.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "trace", derive(serde::Serialize))]
+#[cfg_attr(feature = "replay", derive(serde::Deserialize))]
+pub struct CreateTlasDescriptor<L> {
+    /// Debug label for the acceleration structure.
+    pub label: L,
+    /// Maximum number of instances a build of the acceleration structure may contain.
+    pub max_instances: u32,
+    /// Flags for the acceleration structure.
+    pub flags: AccelerationStructureFlags,
+    /// How subsequent builds of the acceleration structure are performed.
+    pub update_mode: AccelerationStructureUpdateMode,
+}
+
+impl<L> CreateTlasDescriptor<L> {
+    /// Takes a closure and maps the label of the descriptor into another.
+    pub fn map_label<K>(&self, fun: impl FnOnce(&L) -> K) -> CreateTlasDescriptor<K> {
+        CreateTlasDescriptor {
+            label: fun(&self.label),
+            max_instances: self.max_instances,
+            flags: self.flags,
+            update_mode: self.update_mode,
+        }
+    }
+}
+
+/// Describes the upper bounds of a triangle geometry inside a bottom level
+/// acceleration structure.
+///
+/// The acceleration structure is sized for these bounds at creation, and every
+/// build must provide geometry which stays within them.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "trace", derive(serde::Serialize))]
+#[cfg_attr(feature = "replay", derive(serde::Deserialize))]
+pub struct BlasTriangleGeometrySizeDescriptor {
+    /// Format of the vertex positions. Must be [`VertexFormat::Float32x3`],
+    /// [`VertexFormat::Float32x2`], [`VertexFormat::Float16x4`] or
+    /// [`VertexFormat::Float16x2`].
+    pub vertex_format: VertexFormat,
+    /// Maximum number of vertices.
+    pub vertex_count: u32,
+    /// Format of the indices, if the geometry is indexed.
+    pub index_format: Option<IndexFormat>,
+    /// Maximum number of indices. Must be `Some` exactly when `index_format` is,
+    /// and a multiple of 3.
+    pub index_count: Option<u32>,
+    /// Flags for the geometry.
+    pub flags: AccelerationStructureGeometryFlags,
+}
+
+/// Describes the geometries of a bottom level acceleration structure.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "trace", derive(serde::Serialize))]
+#[cfg_attr(feature = "replay", derive(serde::Deserialize))]
+pub enum BlasGeometrySizeDescriptors {
+    /// Triangle geometries.
+    Triangles {
+        /// The descriptors of the individual geometries.
+        desc: Vec<BlasTriangleGeometrySizeDescriptor>,
+    },
+}
+
 /// Selects which DX12 shader compiler to use.
 ///
 /// If the `wgpu-hal/dx12-shader-compiler` feature isn't enabled then this will fall back
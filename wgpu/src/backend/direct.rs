@@ -1,11 +1,12 @@
 use crate::{
     context::{ObjectId, Unused},
     AdapterInfo, BindGroupDescriptor, BindGroupLayoutDescriptor, BindingResource, BufferBinding,
-    BufferDescriptor, CommandEncoderDescriptor, ComputePassDescriptor, ComputePipelineDescriptor,
-    DownlevelCapabilities, Features, Label, Limits, LoadOp, MapMode, Operations,
-    PipelineLayoutDescriptor, RenderBundleEncoderDescriptor, RenderPipelineDescriptor,
-    SamplerDescriptor, ShaderModuleDescriptor, ShaderModuleDescriptorSpirV, ShaderSource, StoreOp,
-    SurfaceStatus, TextureDescriptor, TextureViewDescriptor, UncapturedErrorHandler,
+    BufferDescriptor, CommandEncoderDescriptor, CompilationInfo, ComputeBundleEncoderDescriptor,
+    ComputePassDescriptor, ComputePipelineDescriptor, DownlevelCapabilities, Features, Label,
+    Limits, LoadOp, MapMode, Operations, PipelineLayoutDescriptor, RenderBundleEncoderDescriptor,
+    RenderPipelineDescriptor, SamplerDescriptor, ShaderModuleDescriptor,
+    ShaderModuleDescriptorSpirV, ShaderSource, StoreOp, SurfaceStatus, TextureDescriptor,
+    TextureViewDescriptor, UncapturedErrorHandler,
 };
 
 use arrayvec::ArrayVec;
@@ -21,7 +22,7 @@ use std::{
     slice,
     sync::Arc,
 };
-use wgc::command::{bundle_ffi::*, compute_ffi::*, render_ffi::*};
+use wgc::command::{bundle_ffi::*, compute_bundle_ffi::*, compute_ffi::*, render_ffi::*};
 use wgc::id::TypedId;
 use wgt::{WasmNotSend, WasmNotSync};
 
@@ -186,6 +187,33 @@ impl Context {
         )
     }
 
+    pub unsafe fn create_external_semaphore_from_hal<A: wgc::hal_api::HalApi>(
+        &self,
+        hal_semaphore: A::ExternalSemaphore,
+        device: &Device,
+        desc: &crate::ExternalSemaphoreDescriptor,
+    ) -> wgc::id::ExternalSemaphoreId {
+        let global = &self.0;
+        let (id, error) = unsafe {
+            global.create_external_semaphore_from_hal::<A>(
+                hal_semaphore,
+                device.id,
+                &desc.map_label(|l| l.map(Borrowed)),
+                (),
+            )
+        };
+        if let Some(cause) = error {
+            self.handle_error(
+                &device.error_sink,
+                cause,
+                LABEL,
+                desc.label,
+                "Device::create_external_semaphore_from_hal",
+            );
+        }
+        id
+    }
+
     pub unsafe fn device_as_hal<A: wgc::hal_api::HalApi, F: FnOnce(Option<&A::Device>) -> R, R>(
         &self,
         device: &Device,
@@ -223,6 +251,23 @@ impl Context {
         }
     }
 
+    pub unsafe fn external_semaphore_as_hal<
+        A: wgc::hal_api::HalApi,
+        F: FnOnce(Option<&A::ExternalSemaphore>) -> R,
+        R,
+    >(
+        &self,
+        external_semaphore: &wgc::id::ExternalSemaphoreId,
+        hal_external_semaphore_callback: F,
+    ) -> R {
+        unsafe {
+            self.0.external_semaphore_as_hal::<A, F, R>(
+                *external_semaphore,
+                hal_external_semaphore_callback,
+            )
+        }
+    }
+
     pub fn generate_report(&self) -> wgc::global::GlobalReport {
         self.0.generate_report()
     }
@@ -481,6 +526,11 @@ pub struct Buffer {
     error_sink: ErrorSink,
 }
 
+#[derive(Debug)]
+pub struct ShaderModule {
+    compilation_info: CompilationInfo,
+}
+
 #[derive(Debug)]
 pub struct Texture {
     id: wgc::id::TextureId,
@@ -523,7 +573,7 @@ impl crate::Context for Context {
     type QueueId = wgc::id::QueueId;
     type QueueData = Queue;
     type ShaderModuleId = wgc::id::ShaderModuleId;
-    type ShaderModuleData = ();
+    type ShaderModuleData = ShaderModule;
     type BindGroupLayoutId = wgc::id::BindGroupLayoutId;
     type BindGroupLayoutData = ();
     type BindGroupId = wgc::id::BindGroupId;
@@ -544,6 +594,16 @@ impl crate::Context for Context {
     type RenderPipelineData = ();
     type ComputePipelineId = wgc::id::ComputePipelineId;
     type ComputePipelineData = ();
+    type PipelineCacheId = wgc::id::PipelineCacheId;
+    type PipelineCacheData = ();
+    type HeapId = wgc::id::HeapId;
+    type HeapData = ();
+    type ExternalSemaphoreId = wgc::id::ExternalSemaphoreId;
+    type ExternalSemaphoreData = ();
+    type BlasId = wgc::id::BlasId;
+    type BlasData = ();
+    type TlasId = wgc::id::TlasId;
+    type TlasData = ();
     type CommandEncoderId = wgc::id::CommandEncoderId;
     type CommandEncoderData = CommandEncoder;
     type ComputePassId = Unused;
@@ -556,6 +616,10 @@ impl crate::Context for Context {
     type RenderBundleEncoderData = wgc::command::RenderBundleEncoder;
     type RenderBundleId = wgc::id::RenderBundleId;
     type RenderBundleData = ();
+    type ComputeBundleEncoderId = Unused;
+    type ComputeBundleEncoderData = wgc::command::ComputeBundleEncoder;
+    type ComputeBundleId = wgc::id::ComputeBundleId;
+    type ComputeBundleData = ();
 
     type SurfaceId = wgc::id::SurfaceId;
     type SurfaceData = Surface;
@@ -579,6 +643,7 @@ impl crate::Context for Context {
     >;
 
     type PopErrorScopeFuture = Ready<Option<crate::Error>>;
+    type CompilationInfoFuture = Ready<CompilationInfo>;
 
     fn init(instance_desc: wgt::InstanceDescriptor) -> Self {
         Self(wgc::global::Global::new(
@@ -863,6 +928,18 @@ impl crate::Context for Context {
         }
     }
 
+    fn device_memory_report(
+        &self,
+        device: &Self::DeviceId,
+        _device_data: &Self::DeviceData,
+    ) -> wgt::MemoryReport {
+        let global = &self.0;
+        match wgc::gfx_select!(device => global.device_memory_report(*device)) {
+            Ok(report) => report,
+            Err(err) => self.handle_error_fatal(err, "Device::memory_report"),
+        }
+    }
+
     fn device_downlevel_properties(
         &self,
         device: &Self::DeviceId,
@@ -934,16 +1011,21 @@ impl crate::Context for Context {
         let (id, error) = wgc::gfx_select!(
             device => global.device_create_shader_module(*device, &descriptor, source, ())
         );
-        if let Some(cause) = error {
-            self.handle_error(
-                &device_data.error_sink,
-                cause,
-                LABEL,
-                desc.label,
-                "Device::create_shader_module",
-            );
-        }
-        (id, ())
+        let compilation_info = match error {
+            Some(cause) => {
+                let compilation_info = cause.compilation_info();
+                self.handle_error(
+                    &device_data.error_sink,
+                    cause,
+                    LABEL,
+                    desc.label,
+                    "Device::create_shader_module",
+                );
+                compilation_info
+            }
+            None => CompilationInfo::default(),
+        };
+        (id, ShaderModule { compilation_info })
     }
 
     unsafe fn device_create_shader_module_spirv(
@@ -962,16 +1044,21 @@ impl crate::Context for Context {
         let (id, error) = wgc::gfx_select!(
             device => global.device_create_shader_module_spirv(*device, &descriptor, Borrowed(&desc.source), ())
         );
-        if let Some(cause) = error {
-            self.handle_error(
-                &device_data.error_sink,
-                cause,
-                LABEL,
-                desc.label,
-                "Device::create_shader_module_spirv",
-            );
-        }
-        (id, ())
+        let compilation_info = match error {
+            Some(cause) => {
+                let compilation_info = cause.compilation_info();
+                self.handle_error(
+                    &device_data.error_sink,
+                    cause,
+                    LABEL,
+                    desc.label,
+                    "Device::create_shader_module_spirv",
+                );
+                compilation_info
+            }
+            None => CompilationInfo::default(),
+        };
+        (id, ShaderModule { compilation_info })
     }
 
     fn device_create_bind_group_layout(
@@ -1088,6 +1175,9 @@ impl crate::Context for Context {
                             &remaining_arrayed_texture_views[array.len()..];
                         bm::BindingResource::TextureViewArray(Owned(views))
                     }
+                    BindingResource::AccelerationStructure(tlas) => {
+                        bm::BindingResource::AccelerationStructure(tlas.id.into())
+                    }
                 },
             })
             .collect::<Vec<_>>();
@@ -1204,6 +1294,7 @@ impl crate::Context for Context {
                 targets: Borrowed(frag.targets),
             }),
             multiview: desc.multiview,
+            cache: desc.cache.map(|c| c.id.into()),
         };
 
         let global = &self.0;
@@ -1250,6 +1341,7 @@ impl crate::Context for Context {
                 module: desc.module.id.into(),
                 entry_point: Borrowed(desc.entry_point),
             },
+            cache: desc.cache.map(|c| c.id.into()),
         };
 
         let global = &self.0;
@@ -1278,6 +1370,34 @@ impl crate::Context for Context {
         }
         (id, ())
     }
+    unsafe fn device_create_pipeline_cache(
+        &self,
+        device: &Self::DeviceId,
+        device_data: &Self::DeviceData,
+        desc: &crate::PipelineCacheDescriptor<'_>,
+    ) -> (Self::PipelineCacheId, Self::PipelineCacheData) {
+        use wgc::pipeline as pipe;
+
+        let descriptor = pipe::PipelineCacheDescriptor {
+            label: desc.label.map(Borrowed),
+            data: desc.data.map(Borrowed),
+            fallback: desc.fallback,
+        };
+        let global = &self.0;
+        let (id, error) = unsafe {
+            wgc::gfx_select!(device => global.device_create_pipeline_cache(*device, &descriptor, ()))
+        };
+        if let Some(cause) = error {
+            self.handle_error(
+                &device_data.error_sink,
+                cause,
+                LABEL,
+                desc.label,
+                "Device::create_pipeline_cache",
+            );
+        }
+        (id, ())
+    }
     fn device_create_buffer(
         &self,
         device: &Self::DeviceId,
@@ -1393,14 +1513,14 @@ impl crate::Context for Context {
         }
         (id, ())
     }
-    fn device_create_command_encoder(
+    fn device_create_heap(
         &self,
         device: &Self::DeviceId,
         device_data: &Self::DeviceData,
-        desc: &CommandEncoderDescriptor,
-    ) -> (Self::CommandEncoderId, Self::CommandEncoderData) {
+        desc: &crate::HeapDescriptor<'_>,
+    ) -> (Self::HeapId, Self::HeapData) {
         let global = &self.0;
-        let (id, error) = wgc::gfx_select!(device => global.device_create_command_encoder(
+        let (id, error) = wgc::gfx_select!(device => global.device_create_heap(
             *device,
             &desc.map_label(|l| l.map(Borrowed)),
             ()
@@ -1411,91 +1531,335 @@ impl crate::Context for Context {
                 cause,
                 LABEL,
                 desc.label,
-                "Device::create_command_encoder",
+                "Device::create_heap",
+            );
+        }
+        (id, ())
+    }
+    fn device_create_external_semaphore(
+        &self,
+        device: &Self::DeviceId,
+        device_data: &Self::DeviceData,
+        desc: &crate::ExternalSemaphoreDescriptor<'_>,
+    ) -> (Self::ExternalSemaphoreId, Self::ExternalSemaphoreData) {
+        let global = &self.0;
+        let (id, error) = wgc::gfx_select!(device => global.device_create_external_semaphore(
+            *device,
+            &desc.map_label(|l| l.map(Borrowed)),
+            ()
+        ));
+        if let Some(cause) = error {
+            self.handle_error(
+                &device_data.error_sink,
+                cause,
+                LABEL,
+                desc.label,
+                "Device::create_external_semaphore",
+            );
+        }
+        (id, ())
+    }
+    fn device_create_buffer_in_heap(
+        &self,
+        device: &Self::DeviceId,
+        device_data: &Self::DeviceData,
+        desc: &crate::BufferDescriptor<'_>,
+        heap: &Self::HeapId,
+        _heap_data: &Self::HeapData,
+        offset: wgt::BufferAddress,
+    ) -> (Self::BufferId, Self::BufferData) {
+        let global = &self.0;
+        let (id, error) = wgc::gfx_select!(device => global.device_create_buffer_in_heap(
+            *device,
+            &desc.map_label(|l| l.map(Borrowed)),
+            *heap,
+            offset,
+            ()
+        ));
+        if let Some(cause) = error {
+            self.handle_error(
+                &device_data.error_sink,
+                cause,
+                LABEL,
+                desc.label,
+                "Device::create_buffer_in_heap",
             );
         }
         (
             id,
-            CommandEncoder {
+            Buffer {
                 error_sink: Arc::clone(&device_data.error_sink),
-                open: true,
             },
         )
     }
-    fn device_create_render_bundle_encoder(
+    fn device_create_texture_in_heap(
         &self,
         device: &Self::DeviceId,
-        _device_data: &Self::DeviceData,
-        desc: &RenderBundleEncoderDescriptor,
-    ) -> (Self::RenderBundleEncoderId, Self::RenderBundleEncoderData) {
-        let descriptor = wgc::command::RenderBundleEncoderDescriptor {
-            label: desc.label.map(Borrowed),
-            color_formats: Borrowed(desc.color_formats),
-            depth_stencil: desc.depth_stencil,
-            sample_count: desc.sample_count,
-            multiview: desc.multiview,
-        };
-        match wgc::command::RenderBundleEncoder::new(&descriptor, *device, None) {
-            Ok(encoder) => (Unused, encoder),
-            Err(e) => panic!("Error in Device::create_render_bundle_encoder: {e}"),
-        }
-    }
-    #[cfg_attr(target_arch = "wasm32", allow(unused))]
-    fn device_drop(&self, device: &Self::DeviceId, _device_data: &Self::DeviceData) {
+        device_data: &Self::DeviceData,
+        desc: &TextureDescriptor,
+        heap: &Self::HeapId,
+        _heap_data: &Self::HeapData,
+        offset: wgt::BufferAddress,
+    ) -> (Self::TextureId, Self::TextureData) {
+        let wgt_desc = desc.map_label_and_view_formats(|l| l.map(Borrowed), |v| v.to_vec());
         let global = &self.0;
-
-        #[cfg(any(not(target_arch = "wasm32"), target_os = "emscripten"))]
-        {
-            match wgc::gfx_select!(device => global.device_poll(*device, wgt::Maintain::Wait)) {
-                Ok(_) => (),
-                Err(err) => self.handle_error_fatal(err, "Device::drop"),
-            }
+        let (id, error) = wgc::gfx_select!(device => global.device_create_texture_in_heap(
+            *device,
+            &wgt_desc,
+            *heap,
+            offset,
+            ()
+        ));
+        if let Some(cause) = error {
+            self.handle_error(
+                &device_data.error_sink,
+                cause,
+                LABEL,
+                desc.label,
+                "Device::create_texture_in_heap",
+            );
         }
-
-        wgc::gfx_select!(device => global.device_drop(*device));
-    }
-    fn device_destroy(&self, device: &Self::DeviceId, _device_data: &Self::DeviceData) {
-        let global = &self.0;
-        wgc::gfx_select!(device => global.device_destroy(*device));
-    }
-    fn device_lose(&self, device: &Self::DeviceId, _device_data: &Self::DeviceData) {
-        // TODO: accept a reason, and pass it to device_lose.
-        let global = &self.0;
-        wgc::gfx_select!(device => global.device_lose(*device, None));
+        (
+            id,
+            Texture {
+                id,
+                error_sink: Arc::clone(&device_data.error_sink),
+            },
+        )
     }
-    fn device_poll(
+    fn device_create_sparse_buffer(
         &self,
         device: &Self::DeviceId,
-        _device_data: &Self::DeviceData,
-        maintain: crate::Maintain,
-    ) -> bool {
+        device_data: &Self::DeviceData,
+        desc: &crate::BufferDescriptor<'_>,
+    ) -> (Self::BufferId, Self::BufferData) {
         let global = &self.0;
-        let maintain_inner = maintain.map_index(|i| *i.1.as_ref().downcast_ref().unwrap());
-        match wgc::gfx_select!(device => global.device_poll(
+        let (id, error) = wgc::gfx_select!(device => global.device_create_sparse_buffer(
             *device,
-            maintain_inner
-        )) {
-            Ok(queue_empty) => queue_empty,
-            Err(err) => self.handle_error_fatal(err, "Device::poll"),
+            &desc.map_label(|l| l.map(Borrowed)),
+            ()
+        ));
+        if let Some(cause) = error {
+            self.handle_error(
+                &device_data.error_sink,
+                cause,
+                LABEL,
+                desc.label,
+                "Device::create_sparse_buffer",
+            );
         }
+        (
+            id,
+            Buffer {
+                error_sink: Arc::clone(&device_data.error_sink),
+            },
+        )
     }
-    fn device_on_uncaptured_error(
+    fn device_create_sparse_texture(
         &self,
-        _device: &Self::DeviceId,
+        device: &Self::DeviceId,
         device_data: &Self::DeviceData,
-        handler: Box<dyn UncapturedErrorHandler>,
-    ) {
-        let mut error_sink = device_data.error_sink.lock();
-        error_sink.uncaptured_handler = handler;
+        desc: &TextureDescriptor,
+    ) -> (Self::TextureId, Self::TextureData) {
+        let wgt_desc = desc.map_label_and_view_formats(|l| l.map(Borrowed), |v| v.to_vec());
+        let global = &self.0;
+        let (id, error) = wgc::gfx_select!(device => global.device_create_sparse_texture(
+            *device,
+            &wgt_desc,
+            ()
+        ));
+        if let Some(cause) = error {
+            self.handle_error(
+                &device_data.error_sink,
+                cause,
+                LABEL,
+                desc.label,
+                "Device::create_sparse_texture",
+            );
+        }
+        (
+            id,
+            Texture {
+                id,
+                error_sink: Arc::clone(&device_data.error_sink),
+            },
+        )
     }
-    fn device_push_error_scope(
+    fn device_create_blas(
         &self,
-        _device: &Self::DeviceId,
+        device: &Self::DeviceId,
         device_data: &Self::DeviceData,
-        filter: crate::ErrorFilter,
-    ) {
-        let mut error_sink = device_data.error_sink.lock();
-        error_sink.scopes.push(ErrorScope {
+        desc: &crate::CreateBlasDescriptor<'_>,
+        sizes: wgt::BlasGeometrySizeDescriptors,
+    ) -> (Self::BlasId, Option<u64>, Self::BlasData) {
+        let global = &self.0;
+        let (id, handle, error) = wgc::gfx_select!(device => global.device_create_blas(
+            *device,
+            &desc.map_label(|l| l.map(Borrowed)),
+            sizes,
+            ()
+        ));
+        if let Some(cause) = error {
+            self.handle_error(
+                &device_data.error_sink,
+                cause,
+                LABEL,
+                desc.label,
+                "Device::create_blas",
+            );
+        }
+        (id, handle, ())
+    }
+    fn device_create_tlas(
+        &self,
+        device: &Self::DeviceId,
+        device_data: &Self::DeviceData,
+        desc: &crate::CreateTlasDescriptor<'_>,
+    ) -> (Self::TlasId, Self::TlasData) {
+        let global = &self.0;
+        let (id, error) = wgc::gfx_select!(device => global.device_create_tlas(
+            *device,
+            &desc.map_label(|l| l.map(Borrowed)),
+            ()
+        ));
+        if let Some(cause) = error {
+            self.handle_error(
+                &device_data.error_sink,
+                cause,
+                LABEL,
+                desc.label,
+                "Device::create_tlas",
+            );
+        }
+        (id, ())
+    }
+    fn device_create_command_encoder(
+        &self,
+        device: &Self::DeviceId,
+        device_data: &Self::DeviceData,
+        desc: &CommandEncoderDescriptor,
+    ) -> (Self::CommandEncoderId, Self::CommandEncoderData) {
+        let global = &self.0;
+        let (id, error) = wgc::gfx_select!(device => global.device_create_command_encoder(
+            *device,
+            &desc.map_label(|l| l.map(Borrowed)),
+            ()
+        ));
+        if let Some(cause) = error {
+            self.handle_error(
+                &device_data.error_sink,
+                cause,
+                LABEL,
+                desc.label,
+                "Device::create_command_encoder",
+            );
+        }
+        (
+            id,
+            CommandEncoder {
+                error_sink: Arc::clone(&device_data.error_sink),
+                open: true,
+            },
+        )
+    }
+    fn device_create_render_bundle_encoder(
+        &self,
+        device: &Self::DeviceId,
+        _device_data: &Self::DeviceData,
+        desc: &RenderBundleEncoderDescriptor,
+    ) -> (Self::RenderBundleEncoderId, Self::RenderBundleEncoderData) {
+        let descriptor = wgc::command::RenderBundleEncoderDescriptor {
+            label: desc.label.map(Borrowed),
+            color_formats: Borrowed(desc.color_formats),
+            depth_stencil: desc.depth_stencil,
+            sample_count: desc.sample_count,
+            multiview: desc.multiview,
+        };
+        match wgc::command::RenderBundleEncoder::new(&descriptor, *device, None) {
+            Ok(encoder) => (Unused, encoder),
+            Err(e) => panic!("Error in Device::create_render_bundle_encoder: {e}"),
+        }
+    }
+    fn device_create_compute_bundle_encoder(
+        &self,
+        device: &Self::DeviceId,
+        _device_data: &Self::DeviceData,
+        desc: &ComputeBundleEncoderDescriptor,
+    ) -> (Self::ComputeBundleEncoderId, Self::ComputeBundleEncoderData) {
+        let descriptor = wgc::command::ComputeBundleEncoderDescriptor {
+            label: desc.label.map(Borrowed),
+        };
+        (
+            Unused,
+            wgc::command::ComputeBundleEncoder::new(&descriptor, *device, None),
+        )
+    }
+    #[cfg_attr(target_arch = "wasm32", allow(unused))]
+    fn device_drop(&self, device: &Self::DeviceId, _device_data: &Self::DeviceData) {
+        let global = &self.0;
+
+        #[cfg(any(not(target_arch = "wasm32"), target_os = "emscripten"))]
+        {
+            match wgc::gfx_select!(device => global.device_poll(*device, wgt::Maintain::Wait)) {
+                Ok(_) => (),
+                Err(err) => self.handle_error_fatal(err, "Device::drop"),
+            }
+        }
+
+        wgc::gfx_select!(device => global.device_drop(*device));
+    }
+    fn device_destroy(&self, device: &Self::DeviceId, _device_data: &Self::DeviceData) {
+        let global = &self.0;
+        wgc::gfx_select!(device => global.device_destroy(*device));
+    }
+    fn device_lose(&self, device: &Self::DeviceId, _device_data: &Self::DeviceData) {
+        // TODO: accept a reason, and pass it to device_lose.
+        let global = &self.0;
+        wgc::gfx_select!(device => global.device_lose(*device, None));
+    }
+    fn device_set_device_lost_callback(
+        &self,
+        device: &Self::DeviceId,
+        _device_data: &Self::DeviceData,
+        device_lost_callback: crate::context::DeviceLostCallback,
+    ) {
+        let device_lost_closure = wgc::device::DeviceLostClosure::from_rust(device_lost_callback);
+        let global = &self.0;
+        wgc::gfx_select!(device => global.device_set_device_lost_closure(*device, device_lost_closure));
+    }
+    fn device_poll(
+        &self,
+        device: &Self::DeviceId,
+        _device_data: &Self::DeviceData,
+        maintain: crate::Maintain,
+    ) -> bool {
+        let global = &self.0;
+        let maintain_inner = maintain.map_index(|i| *i.1.as_ref().downcast_ref().unwrap());
+        match wgc::gfx_select!(device => global.device_poll(
+            *device,
+            maintain_inner
+        )) {
+            Ok(queue_empty) => queue_empty,
+            Err(err) => self.handle_error_fatal(err, "Device::poll"),
+        }
+    }
+    fn device_on_uncaptured_error(
+        &self,
+        _device: &Self::DeviceId,
+        device_data: &Self::DeviceData,
+        handler: Box<dyn UncapturedErrorHandler>,
+    ) {
+        let mut error_sink = device_data.error_sink.lock();
+        error_sink.uncaptured_handler = handler;
+    }
+    fn device_push_error_scope(
+        &self,
+        _device: &Self::DeviceId,
+        device_data: &Self::DeviceData,
+        filter: crate::ErrorFilter,
+    ) {
+        let mut error_sink = device_data.error_sink.lock();
+        error_sink.scopes.push(ErrorScope {
             error: None,
             filter,
         });
@@ -1510,6 +1874,14 @@ impl crate::Context for Context {
         ready(scope.error)
     }
 
+    fn shader_get_compilation_info(
+        &self,
+        _shader: &Self::ShaderModuleId,
+        shader_data: &Self::ShaderModuleData,
+    ) -> Self::CompilationInfoFuture {
+        ready(shader_data.compilation_info.clone())
+    }
+
     fn buffer_map_async(
         &self,
         buffer: &Self::BufferId,
@@ -1652,6 +2024,16 @@ impl crate::Context for Context {
         wgc::gfx_select!(*query_set => global.query_set_drop(*query_set))
     }
 
+    fn blas_drop(&self, blas: &Self::BlasId, _blas_data: &Self::BlasData) {
+        let global = &self.0;
+        wgc::gfx_select!(*blas => global.blas_drop(*blas))
+    }
+
+    fn tlas_drop(&self, tlas: &Self::TlasId, _tlas_data: &Self::TlasData) {
+        let global = &self.0;
+        wgc::gfx_select!(*tlas => global.tlas_drop(*tlas))
+    }
+
     fn bind_group_drop(
         &self,
         bind_group: &Self::BindGroupId,
@@ -1715,6 +2097,15 @@ impl crate::Context for Context {
         wgc::gfx_select!(*render_bundle => global.render_bundle_drop(*render_bundle))
     }
 
+    fn compute_bundle_drop(
+        &self,
+        compute_bundle: &Self::ComputeBundleId,
+        _compute_bundle_data: &Self::ComputeBundleData,
+    ) {
+        let global = &self.0;
+        wgc::gfx_select!(*compute_bundle => global.compute_bundle_drop(*compute_bundle))
+    }
+
     fn compute_pipeline_drop(
         &self,
         pipeline: &Self::ComputePipelineId,
@@ -1733,6 +2124,29 @@ impl crate::Context for Context {
         wgc::gfx_select!(*pipeline => global.render_pipeline_drop(*pipeline))
     }
 
+    fn pipeline_cache_drop(
+        &self,
+        cache: &Self::PipelineCacheId,
+        _cache_data: &Self::PipelineCacheData,
+    ) {
+        let global = &self.0;
+        wgc::gfx_select!(*cache => global.pipeline_cache_drop(*cache))
+    }
+
+    fn heap_drop(&self, heap: &Self::HeapId, _heap_data: &Self::HeapData) {
+        let global = &self.0;
+        wgc::gfx_select!(*heap => global.heap_drop(*heap))
+    }
+
+    fn external_semaphore_drop(
+        &self,
+        external_semaphore: &Self::ExternalSemaphoreId,
+        _external_semaphore_data: &Self::ExternalSemaphoreData,
+    ) {
+        let global = &self.0;
+        wgc::gfx_select!(*external_semaphore => global.external_semaphore_drop(*external_semaphore))
+    }
+
     fn compute_pipeline_get_bind_group_layout(
         &self,
         pipeline: &Self::ComputePipelineId,
@@ -1761,6 +2175,15 @@ impl crate::Context for Context {
         (id, ())
     }
 
+    fn pipeline_cache_get_data(
+        &self,
+        cache: &Self::PipelineCacheId,
+        _cache_data: &Self::PipelineCacheData,
+    ) -> Option<Vec<u8>> {
+        let global = &self.0;
+        wgc::gfx_select!(*cache => global.pipeline_cache_get_data(*cache))
+    }
+
     fn command_encoder_copy_buffer_to_buffer(
         &self,
         encoder: &Self::CommandEncoderId,
@@ -2050,6 +2473,43 @@ impl crate::Context for Context {
         }
     }
 
+    fn command_encoder_transition_resources(
+        &self,
+        encoder: &Self::CommandEncoderId,
+        encoder_data: &Self::CommandEncoderData,
+        buffer_transitions: &[crate::BufferTransition],
+        texture_transitions: &[crate::TextureTransition],
+    ) {
+        let buffer_transitions = buffer_transitions
+            .iter()
+            .map(|transition| wgt::BufferTransition {
+                buffer: transition.buffer.id.into(),
+                state: transition.state,
+            })
+            .collect::<SmallVec<[_; 4]>>();
+        let texture_transitions = texture_transitions
+            .iter()
+            .map(|transition| wgt::TextureTransition {
+                texture: transition.texture.id.into(),
+                subresource_range: transition.subresource_range,
+                state: transition.state,
+            })
+            .collect::<SmallVec<[_; 4]>>();
+
+        let global = &self.0;
+        if let Err(cause) = wgc::gfx_select!(encoder => global.command_encoder_transition_resources(
+            *encoder,
+            &buffer_transitions,
+            &texture_transitions
+        )) {
+            self.handle_error_nolabel(
+                &encoder_data.error_sink,
+                cause,
+                "CommandEncoder::transition_resources",
+            );
+        }
+    }
+
     fn command_encoder_insert_debug_marker(
         &self,
         encoder: &Self::CommandEncoderId,
@@ -2154,6 +2614,65 @@ impl crate::Context for Context {
         }
     }
 
+    fn command_encoder_build_acceleration_structures_unsafe_tlas(
+        &self,
+        encoder: &Self::CommandEncoderId,
+        encoder_data: &Self::CommandEncoderData,
+        blas: &[crate::BlasBuildEntry<'_>],
+        tlas: &[crate::TlasBuildEntry<'_>],
+    ) {
+        use wgc::ray_tracing as rt;
+
+        let blas = blas
+            .iter()
+            .map(|entry| rt::BlasBuildEntry {
+                blas_id: entry.blas.id.into(),
+                geometries: match entry.geometry {
+                    crate::BlasGeometries::TriangleGeometries(ref triangles) => {
+                        rt::BlasGeometries::TriangleGeometries(
+                            triangles
+                                .iter()
+                                .map(|triangle| rt::BlasTriangleGeometry {
+                                    size: triangle.size.clone(),
+                                    vertex_buffer: triangle.vertex_buffer.id.into(),
+                                    first_vertex: triangle.first_vertex,
+                                    vertex_stride: triangle.vertex_stride,
+                                    index_buffer: triangle.index_buffer.map(|b| b.id.into()),
+                                    index_buffer_offset: triangle.index_buffer_offset,
+                                    transform_buffer: triangle
+                                        .transform_buffer
+                                        .map(|b| b.id.into()),
+                                    transform_buffer_offset: triangle.transform_buffer_offset,
+                                })
+                                .collect(),
+                        )
+                    }
+                },
+            })
+            .collect::<Vec<_>>();
+        let tlas = tlas
+            .iter()
+            .map(|entry| rt::TlasBuildEntry {
+                tlas_id: entry.tlas.id.into(),
+                instance_buffer_id: entry.instance_buffer.id.into(),
+                instance_count: entry.instance_count,
+            })
+            .collect::<Vec<_>>();
+
+        let global = &self.0;
+        if let Err(cause) = wgc::gfx_select!(encoder => global.command_encoder_build_acceleration_structures_unsafe_tlas(
+            *encoder,
+            &blas,
+            &tlas
+        )) {
+            self.handle_error_nolabel(
+                &encoder_data.error_sink,
+                cause,
+                "CommandEncoder::build_acceleration_structures_unsafe_tlas",
+            );
+        }
+    }
+
     fn render_bundle_encoder_finish(
         &self,
         _encoder: Self::RenderBundleEncoderId,
@@ -2172,6 +2691,24 @@ impl crate::Context for Context {
         (id, ())
     }
 
+    fn compute_bundle_encoder_finish(
+        &self,
+        _encoder: Self::ComputeBundleEncoderId,
+        encoder_data: Self::ComputeBundleEncoderData,
+        desc: &crate::ComputeBundleDescriptor,
+    ) -> (Self::ComputeBundleId, Self::ComputeBundleData) {
+        let global = &self.0;
+        let (id, error) = wgc::gfx_select!(encoder_data.parent() => global.compute_bundle_encoder_finish(
+            encoder_data,
+            &desc.map_label(|l| l.map(Borrowed)),
+            ()
+        ));
+        if let Some(err) = error {
+            self.handle_error_fatal(err, "ComputeBundleEncoder::finish");
+        }
+        (id, ())
+    }
+
     fn queue_write_buffer(
         &self,
         queue: &Self::QueueId,
@@ -2285,6 +2822,49 @@ impl crate::Context for Context {
         }
     }
 
+    fn queue_update_sparse_bindings(
+        &self,
+        queue: &Self::QueueId,
+        queue_data: &Self::QueueData,
+        buffer_bindings: &[crate::SparseBufferBinding],
+        texture_bindings: &[crate::SparseTextureBinding],
+    ) {
+        let map_memory = |memory: &Option<crate::SparseMemory>| {
+            memory.map(|memory| wgt::SparseMemory {
+                heap: memory.heap.id.into(),
+                offset: memory.offset,
+            })
+        };
+        let buffer_bindings = buffer_bindings
+            .iter()
+            .map(|binding| wgt::SparseBufferBinding {
+                buffer: binding.buffer.id.into(),
+                offset: binding.offset,
+                size: binding.size,
+                memory: map_memory(&binding.memory),
+            })
+            .collect::<Vec<_>>();
+        let texture_bindings = texture_bindings
+            .iter()
+            .map(|binding| wgt::SparseTextureBinding {
+                texture: binding.texture.id.into(),
+                mip_level: binding.mip_level,
+                origin: binding.origin,
+                size: binding.size,
+                memory: map_memory(&binding.memory),
+            })
+            .collect::<Vec<_>>();
+
+        let global = &self.0;
+        if let Err(err) = wgc::gfx_select!(*queue => global.queue_update_sparse_bindings(
+            *queue,
+            &buffer_bindings,
+            &texture_bindings
+        )) {
+            self.handle_error_nolabel(&queue_data.error_sink, err, "Queue::update_sparse_bindings");
+        }
+    }
+
     #[cfg(all(target_arch = "wasm32", not(target_os = "emscripten")))]
     fn queue_copy_external_image_to_texture(
         &self,
@@ -2310,14 +2890,17 @@ impl crate::Context for Context {
         }
     }
 
-    fn queue_submit<I: Iterator<Item = (Self::CommandBufferId, Self::CommandBufferData)>>(
+    fn queue_submit<
+        'a,
+        I: Iterator<Item = (&'a Self::CommandBufferId, &'a Self::CommandBufferData)>,
+    >(
         &self,
         queue: &Self::QueueId,
         _queue_data: &Self::QueueData,
         command_buffers: I,
     ) -> (Self::SubmissionIndex, Self::SubmissionIndexData) {
         let temp_command_buffers = command_buffers
-            .map(|(i, _)| i)
+            .map(|(&i, _)| i)
             .collect::<SmallVec<[_; 4]>>();
 
         let global = &self.0;
@@ -2329,6 +2912,42 @@ impl crate::Context for Context {
         (Unused, index)
     }
 
+    fn queue_submit_with_sync<
+        'a,
+        I: Iterator<Item = (&'a Self::CommandBufferId, &'a Self::CommandBufferData)>,
+    >(
+        &self,
+        queue: &Self::QueueId,
+        _queue_data: &Self::QueueData,
+        command_buffers: I,
+        waits: &[(&crate::ExternalSemaphore, u64)],
+        signals: &[(&crate::ExternalSemaphore, u64)],
+    ) -> (Self::SubmissionIndex, Self::SubmissionIndexData) {
+        let temp_command_buffers = command_buffers
+            .map(|(&i, _)| i)
+            .collect::<SmallVec<[_; 4]>>();
+        let map_semaphores = |semaphores: &[(&crate::ExternalSemaphore, u64)]| {
+            semaphores
+                .iter()
+                .map(|&(semaphore, value)| (semaphore.id.into(), value))
+                .collect::<SmallVec<[_; 4]>>()
+        };
+        let waits = map_semaphores(waits);
+        let signals = map_semaphores(signals);
+
+        let global = &self.0;
+        let index = match wgc::gfx_select!(*queue => global.queue_submit_with_sync(
+            *queue,
+            &temp_command_buffers,
+            &waits,
+            &signals
+        )) {
+            Ok(index) => index,
+            Err(err) => self.handle_error_fatal(err, "Queue::submit"),
+        };
+        (Unused, index)
+    }
+
     fn queue_get_timestamp_period(
         &self,
         queue: &Self::QueueId,
@@ -2502,6 +3121,26 @@ impl crate::Context for Context {
         wgpu_compute_pass_dispatch_workgroups_indirect(pass_data, *indirect_buffer, indirect_offset)
     }
 
+    fn compute_pass_execute_bundles<'a>(
+        &self,
+        _pass: &mut Self::ComputePassId,
+        pass_data: &mut Self::ComputePassData,
+        compute_bundles: Box<
+            dyn Iterator<Item = (Self::ComputeBundleId, &'a Self::ComputeBundleData)> + 'a,
+        >,
+    ) {
+        let temp_compute_bundles = compute_bundles
+            .map(|(i, _)| i)
+            .collect::<SmallVec<[_; 4]>>();
+        unsafe {
+            wgpu_compute_pass_execute_bundles(
+                pass_data,
+                temp_compute_bundles.as_ptr(),
+                temp_compute_bundles.len(),
+            )
+        }
+    }
+
     fn render_bundle_encoder_set_pipeline(
         &self,
         _encoder: &mut Self::RenderBundleEncoderId,
@@ -2636,55 +3275,197 @@ impl crate::Context for Context {
     fn render_bundle_encoder_multi_draw_indirect(
         &self,
         _encoder: &mut Self::RenderBundleEncoderId,
-        _encoder_data: &mut Self::RenderBundleEncoderData,
-        _indirect_buffer: &Self::BufferId,
+        encoder_data: &mut Self::RenderBundleEncoderData,
+        indirect_buffer: &Self::BufferId,
         _indirect_buffer_data: &Self::BufferData,
-        _indirect_offset: wgt::BufferAddress,
-        _count: u32,
+        indirect_offset: wgt::BufferAddress,
+        count: u32,
     ) {
-        unimplemented!()
+        wgpu_render_bundle_multi_draw_indirect(
+            encoder_data,
+            *indirect_buffer,
+            indirect_offset,
+            count,
+        )
     }
 
     fn render_bundle_encoder_multi_draw_indexed_indirect(
         &self,
         _encoder: &mut Self::RenderBundleEncoderId,
-        _encoder_data: &mut Self::RenderBundleEncoderData,
-        _indirect_buffer: &Self::BufferId,
+        encoder_data: &mut Self::RenderBundleEncoderData,
+        indirect_buffer: &Self::BufferId,
         _indirect_buffer_data: &Self::BufferData,
-        _indirect_offset: wgt::BufferAddress,
-        _count: u32,
+        indirect_offset: wgt::BufferAddress,
+        count: u32,
     ) {
-        unimplemented!()
+        wgpu_render_bundle_multi_draw_indexed_indirect(
+            encoder_data,
+            *indirect_buffer,
+            indirect_offset,
+            count,
+        )
     }
 
     fn render_bundle_encoder_multi_draw_indirect_count(
         &self,
         _encoder: &mut Self::RenderBundleEncoderId,
-        _encoder_data: &mut Self::RenderBundleEncoderData,
-        _indirect_buffer: &Self::BufferId,
+        encoder_data: &mut Self::RenderBundleEncoderData,
+        indirect_buffer: &Self::BufferId,
         _indirect_buffer_data: &Self::BufferData,
-        _indirect_offset: wgt::BufferAddress,
-        _count_buffer: &Self::BufferId,
+        indirect_offset: wgt::BufferAddress,
+        count_buffer: &Self::BufferId,
         _count_buffer_data: &Self::BufferData,
-        _count_buffer_offset: wgt::BufferAddress,
-        _max_count: u32,
+        count_buffer_offset: wgt::BufferAddress,
+        max_count: u32,
     ) {
-        unimplemented!()
+        wgpu_render_bundle_multi_draw_indirect_count(
+            encoder_data,
+            *indirect_buffer,
+            indirect_offset,
+            *count_buffer,
+            count_buffer_offset,
+            max_count,
+        )
     }
 
     fn render_bundle_encoder_multi_draw_indexed_indirect_count(
         &self,
         _encoder: &mut Self::RenderBundleEncoderId,
-        _encoder_data: &mut Self::RenderBundleEncoderData,
-        _indirect_buffer: &Self::BufferId,
+        encoder_data: &mut Self::RenderBundleEncoderData,
+        indirect_buffer: &Self::BufferId,
         _indirect_buffer_data: &Self::BufferData,
-        _indirect_offset: wgt::BufferAddress,
-        _count_buffer: &Self::BufferId,
+        indirect_offset: wgt::BufferAddress,
+        count_buffer: &Self::BufferId,
         _count_buffer_data: &Self::BufferData,
-        _count_buffer_offset: wgt::BufferAddress,
-        _max_count: u32,
+        count_buffer_offset: wgt::BufferAddress,
+        max_count: u32,
+    ) {
+        wgpu_render_bundle_multi_draw_indexed_indirect_count(
+            encoder_data,
+            *indirect_buffer,
+            indirect_offset,
+            *count_buffer,
+            count_buffer_offset,
+            max_count,
+        )
+    }
+
+    fn render_bundle_encoder_insert_debug_marker(
+        &self,
+        _encoder: &mut Self::RenderBundleEncoderId,
+        encoder_data: &mut Self::RenderBundleEncoderData,
+        label: &str,
+    ) {
+        unsafe {
+            let label = std::ffi::CString::new(label).unwrap();
+            wgpu_render_bundle_insert_debug_marker(encoder_data, label.as_ptr());
+        }
+    }
+
+    fn render_bundle_encoder_push_debug_group(
+        &self,
+        _encoder: &mut Self::RenderBundleEncoderId,
+        encoder_data: &mut Self::RenderBundleEncoderData,
+        group_label: &str,
+    ) {
+        unsafe {
+            let label = std::ffi::CString::new(group_label).unwrap();
+            wgpu_render_bundle_push_debug_group(encoder_data, label.as_ptr());
+        }
+    }
+
+    fn render_bundle_encoder_pop_debug_group(
+        &self,
+        _encoder: &mut Self::RenderBundleEncoderId,
+        encoder_data: &mut Self::RenderBundleEncoderData,
+    ) {
+        wgpu_render_bundle_pop_debug_group(encoder_data);
+    }
+
+    fn render_bundle_encoder_write_timestamp(
+        &self,
+        _encoder: &mut Self::RenderBundleEncoderId,
+        encoder_data: &mut Self::RenderBundleEncoderData,
+        query_set: &Self::QuerySetId,
+        _query_set_data: &Self::QuerySetData,
+        query_index: u32,
+    ) {
+        wgpu_render_bundle_write_timestamp(encoder_data, *query_set, query_index)
+    }
+
+    fn render_bundle_encoder_begin_pipeline_statistics_query(
+        &self,
+        _encoder: &mut Self::RenderBundleEncoderId,
+        encoder_data: &mut Self::RenderBundleEncoderData,
+        query_set: &Self::QuerySetId,
+        _query_set_data: &Self::QuerySetData,
+        query_index: u32,
+    ) {
+        wgpu_render_bundle_begin_pipeline_statistics_query(encoder_data, *query_set, query_index)
+    }
+
+    fn render_bundle_encoder_end_pipeline_statistics_query(
+        &self,
+        _encoder: &mut Self::RenderBundleEncoderId,
+        encoder_data: &mut Self::RenderBundleEncoderData,
+    ) {
+        wgpu_render_bundle_end_pipeline_statistics_query(encoder_data)
+    }
+
+    fn compute_bundle_encoder_set_pipeline(
+        &self,
+        _encoder: &mut Self::ComputeBundleEncoderId,
+        encoder_data: &mut Self::ComputeBundleEncoderData,
+        pipeline: &Self::ComputePipelineId,
+        _pipeline_data: &Self::ComputePipelineData,
+    ) {
+        wgpu_compute_bundle_set_pipeline(encoder_data, *pipeline)
+    }
+
+    fn compute_bundle_encoder_set_bind_group(
+        &self,
+        _encoder: &mut Self::ComputeBundleEncoderId,
+        encoder_data: &mut Self::ComputeBundleEncoderData,
+        index: u32,
+        bind_group: &Self::BindGroupId,
+        _bind_group_data: &Self::BindGroupData,
+        offsets: &[wgt::DynamicOffset],
     ) {
-        unimplemented!()
+        unsafe {
+            wgpu_compute_bundle_set_bind_group(
+                encoder_data,
+                index,
+                *bind_group,
+                offsets.as_ptr(),
+                offsets.len(),
+            )
+        }
+    }
+
+    fn compute_bundle_encoder_dispatch_workgroups(
+        &self,
+        _encoder: &mut Self::ComputeBundleEncoderId,
+        encoder_data: &mut Self::ComputeBundleEncoderData,
+        x: u32,
+        y: u32,
+        z: u32,
+    ) {
+        wgpu_compute_bundle_dispatch_workgroups(encoder_data, x, y, z)
+    }
+
+    fn compute_bundle_encoder_dispatch_workgroups_indirect(
+        &self,
+        _encoder: &mut Self::ComputeBundleEncoderId,
+        encoder_data: &mut Self::ComputeBundleEncoderData,
+        indirect_buffer: &Self::BufferId,
+        _indirect_buffer_data: &Self::BufferData,
+        indirect_offset: wgt::BufferAddress,
+    ) {
+        wgpu_compute_bundle_dispatch_workgroups_indirect(
+            encoder_data,
+            *indirect_buffer,
+            indirect_offset,
+        )
     }
 
     fn render_pass_set_pipeline(
@@ -854,6 +854,41 @@ fn future_pop_error_scope(result: JsFutureResult) -> Option<crate::Error> {
     }
 }
 
+fn future_compilation_info(result: JsFutureResult) -> crate::CompilationInfo {
+    let info = match result.and_then(wasm_bindgen::JsCast::dyn_into::<web_sys::GpuCompilationInfo>)
+    {
+        Ok(info) => info,
+        Err(_) => return crate::CompilationInfo::default(),
+    };
+    let messages = info
+        .messages()
+        .iter()
+        .map(|message| {
+            let message: web_sys::GpuCompilationMessage = message.unchecked_into();
+            let location = (message.line_num() != 0.0).then(|| crate::SourceLocation {
+                line_number: message.line_num() as u32,
+                line_position: message.line_pos() as u32,
+                offset: message.offset() as u32,
+                length: message.length() as u32,
+            });
+            crate::CompilationMessage {
+                message: message.message(),
+                message_type: match message.type_() {
+                    web_sys::GpuCompilationMessageType::Error => {
+                        crate::CompilationMessageType::Error
+                    }
+                    web_sys::GpuCompilationMessageType::Warning => {
+                        crate::CompilationMessageType::Warning
+                    }
+                    _ => crate::CompilationMessageType::Info,
+                },
+                location,
+            }
+        })
+        .collect();
+    crate::CompilationInfo { messages }
+}
+
 /// Calls `callback(success_value)` when the promise completes successfully, calls `callback(failure_value)`
 /// when the promise completes unsuccessfully.
 fn register_then_closures<F, T>(promise: &Promise, callback: F, success_value: T, failure_value: T)
@@ -1028,6 +1063,16 @@ impl crate::context::Context for Context {
     type RenderPipelineData = Sendable<web_sys::GpuRenderPipeline>;
     type ComputePipelineId = Identified<web_sys::GpuComputePipeline>;
     type ComputePipelineData = Sendable<web_sys::GpuComputePipeline>;
+    type PipelineCacheId = Unused;
+    type PipelineCacheData = ();
+    type HeapId = Unused;
+    type HeapData = ();
+    type ExternalSemaphoreId = Unused;
+    type ExternalSemaphoreData = ();
+    type BlasId = Unused;
+    type BlasData = ();
+    type TlasId = Unused;
+    type TlasData = ();
     type CommandEncoderId = Identified<web_sys::GpuCommandEncoder>;
     type CommandEncoderData = Sendable<web_sys::GpuCommandEncoder>;
     type ComputePassId = Identified<web_sys::GpuComputePassEncoder>;
@@ -1040,6 +1085,10 @@ impl crate::context::Context for Context {
     type RenderBundleEncoderData = Sendable<web_sys::GpuRenderBundleEncoder>;
     type RenderBundleId = Identified<web_sys::GpuRenderBundle>;
     type RenderBundleData = Sendable<web_sys::GpuRenderBundle>;
+    type ComputeBundleEncoderId = Unused;
+    type ComputeBundleEncoderData = ();
+    type ComputeBundleId = Unused;
+    type ComputeBundleData = ();
     type SurfaceId = Identified<(Canvas, web_sys::GpuCanvasContext)>;
     type SurfaceData = Sendable<(Canvas, web_sys::GpuCanvasContext)>;
 
@@ -1067,6 +1116,10 @@ impl crate::context::Context for Context {
     >;
     type PopErrorScopeFuture =
         MakeSendFuture<wasm_bindgen_futures::JsFuture, fn(JsFutureResult) -> Option<crate::Error>>;
+    type CompilationInfoFuture = MakeSendFuture<
+        wasm_bindgen_futures::JsFuture,
+        fn(JsFutureResult) -> crate::CompilationInfo,
+    >;
 
     fn init(_instance_desc: wgt::InstanceDescriptor) -> Self {
         let global: Global = js_sys::global().unchecked_into();
@@ -1379,6 +1432,15 @@ impl crate::context::Context for Context {
         map_wgt_limits(device_data.0.limits())
     }
 
+    fn device_memory_report(
+        &self,
+        _device: &Self::DeviceId,
+        _device_data: &Self::DeviceData,
+    ) -> wgt::MemoryReport {
+        // The browser doesn't expose memory usage.
+        wgt::MemoryReport::default()
+    }
+
     fn device_downlevel_properties(
         &self,
         _device: &Self::DeviceId,
@@ -1578,6 +1640,9 @@ impl crate::context::Context for Context {
                         storage_texture.view_dimension(map_texture_view_dimension(view_dimension));
                         mapped_entry.storage_texture(&storage_texture);
                     }
+                    wgt::BindingType::AccelerationStructure => {
+                        panic!("Web backend does not support acceleration structures")
+                    }
                 }
 
                 mapped_entry
@@ -1635,6 +1700,9 @@ impl crate::context::Context for Context {
                     crate::BindingResource::TextureViewArray(..) => {
                         panic!("Web backend does not support BINDING_INDEXING extension")
                     }
+                    crate::BindingResource::AccelerationStructure(..) => {
+                        panic!("Web backend does not support acceleration structures")
+                    }
                 };
 
                 web_sys::GpuBindGroupEntry::new(binding.binding, &mapped_resource)
@@ -1800,6 +1868,15 @@ impl crate::context::Context for Context {
         create_identified(device_data.0.create_compute_pipeline(&mapped_desc))
     }
 
+    unsafe fn device_create_pipeline_cache(
+        &self,
+        _: &Self::DeviceId,
+        _: &Self::DeviceData,
+        _: &crate::PipelineCacheDescriptor<'_>,
+    ) -> (Self::PipelineCacheId, Self::PipelineCacheData) {
+        (Unused, ())
+    }
+
     fn device_create_buffer(
         &self,
         _device: &Self::DeviceId,
@@ -1885,6 +1962,85 @@ impl crate::context::Context for Context {
         create_identified(device_data.0.create_query_set(&mapped_desc))
     }
 
+    fn device_create_heap(
+        &self,
+        _device: &Self::DeviceId,
+        _device_data: &Self::DeviceData,
+        _desc: &crate::HeapDescriptor<'_>,
+    ) -> (Self::HeapId, Self::HeapData) {
+        panic!("Web backend does not support resource heaps")
+    }
+
+    fn device_create_external_semaphore(
+        &self,
+        _device: &Self::DeviceId,
+        _device_data: &Self::DeviceData,
+        _desc: &crate::ExternalSemaphoreDescriptor<'_>,
+    ) -> (Self::ExternalSemaphoreId, Self::ExternalSemaphoreData) {
+        panic!("Web backend does not support external semaphores")
+    }
+
+    fn device_create_buffer_in_heap(
+        &self,
+        _device: &Self::DeviceId,
+        _device_data: &Self::DeviceData,
+        _desc: &crate::BufferDescriptor<'_>,
+        _heap: &Self::HeapId,
+        _heap_data: &Self::HeapData,
+        _offset: wgt::BufferAddress,
+    ) -> (Self::BufferId, Self::BufferData) {
+        panic!("Web backend does not support resource heaps")
+    }
+
+    fn device_create_texture_in_heap(
+        &self,
+        _device: &Self::DeviceId,
+        _device_data: &Self::DeviceData,
+        _desc: &crate::TextureDescriptor<'_>,
+        _heap: &Self::HeapId,
+        _heap_data: &Self::HeapData,
+        _offset: wgt::BufferAddress,
+    ) -> (Self::TextureId, Self::TextureData) {
+        panic!("Web backend does not support resource heaps")
+    }
+
+    fn device_create_sparse_buffer(
+        &self,
+        _device: &Self::DeviceId,
+        _device_data: &Self::DeviceData,
+        _desc: &crate::BufferDescriptor<'_>,
+    ) -> (Self::BufferId, Self::BufferData) {
+        panic!("Web backend does not support sparse resources")
+    }
+
+    fn device_create_sparse_texture(
+        &self,
+        _device: &Self::DeviceId,
+        _device_data: &Self::DeviceData,
+        _desc: &crate::TextureDescriptor<'_>,
+    ) -> (Self::TextureId, Self::TextureData) {
+        panic!("Web backend does not support sparse resources")
+    }
+
+    fn device_create_blas(
+        &self,
+        _device: &Self::DeviceId,
+        _device_data: &Self::DeviceData,
+        _desc: &crate::CreateBlasDescriptor<'_>,
+        _sizes: wgt::BlasGeometrySizeDescriptors,
+    ) -> (Self::BlasId, Option<u64>, Self::BlasData) {
+        panic!("Web backend does not support acceleration structures")
+    }
+
+    fn device_create_tlas(
+        &self,
+        _device: &Self::DeviceId,
+        _device_data: &Self::DeviceData,
+        _desc: &crate::CreateTlasDescriptor<'_>,
+    ) -> (Self::TlasId, Self::TlasData) {
+        panic!("Web backend does not support acceleration structures")
+    }
+
     fn device_create_command_encoder(
         &self,
         _device: &Self::DeviceId,
@@ -1929,6 +2085,15 @@ impl crate::context::Context for Context {
         create_identified(device_data.0.create_render_bundle_encoder(&mapped_desc))
     }
 
+    fn device_create_compute_bundle_encoder(
+        &self,
+        _device: &Self::DeviceId,
+        _device_data: &Self::DeviceData,
+        _desc: &crate::ComputeBundleEncoderDescriptor,
+    ) -> (Self::ComputeBundleEncoderId, Self::ComputeBundleEncoderData) {
+        panic!("Web backend does not support compute bundles")
+    }
+
     fn device_drop(&self, _device: &Self::DeviceId, _device_data: &Self::DeviceData) {
         // Device is dropped automatically
     }
@@ -1943,6 +2108,28 @@ impl crate::context::Context for Context {
         // with a callback.
     }
 
+    fn device_set_device_lost_callback(
+        &self,
+        _device: &Self::DeviceId,
+        device_data: &Self::DeviceData,
+        device_lost_callback: crate::context::DeviceLostCallback,
+    ) {
+        use web_sys::{GpuDeviceLostInfo, GpuDeviceLostReason};
+
+        let closure = wasm_bindgen::closure::Closure::once(move |info: JsValue| {
+            let info = info.dyn_into::<GpuDeviceLostInfo>().unwrap();
+            let reason = match GpuDeviceLostReason::from_js_value(&info.reason()) {
+                Some(GpuDeviceLostReason::Destroyed) => crate::DeviceLostReason::Destroyed,
+                _ => crate::DeviceLostReason::Unknown,
+            };
+            device_lost_callback(reason, info.message());
+        });
+        let _ = device_data.0.lost().then(&closure);
+        // The promise resolves at most once, so the closure is only ever
+        // called once; keep it alive until then.
+        closure.forget();
+    }
+
     fn device_poll(
         &self,
         _device: &Self::DeviceId,
@@ -1994,6 +2181,18 @@ impl crate::context::Context for Context {
         )
     }
 
+    fn shader_get_compilation_info(
+        &self,
+        _shader: &Self::ShaderModuleId,
+        shader_data: &Self::ShaderModuleData,
+    ) -> Self::CompilationInfoFuture {
+        let compilation_info_promise = shader_data.0.compilation_info();
+        MakeSendFuture::new(
+            wasm_bindgen_futures::JsFuture::from(compilation_info_promise),
+            future_compilation_info,
+        )
+    }
+
     fn buffer_map_async(
         &self,
         _buffer: &Self::BufferId,
@@ -2111,6 +2310,10 @@ impl crate::context::Context for Context {
         // Dropped automatically
     }
 
+    fn blas_drop(&self, _blas: &Self::BlasId, _blas_data: &Self::BlasData) {}
+
+    fn tlas_drop(&self, _tlas: &Self::TlasId, _tlas_data: &Self::TlasData) {}
+
     fn bind_group_drop(
         &self,
         _bind_group: &Self::BindGroupId,
@@ -2167,6 +2370,13 @@ impl crate::context::Context for Context {
         // Dropped automatically
     }
 
+    fn compute_bundle_drop(
+        &self,
+        _compute_bundle: &Self::ComputeBundleId,
+        _compute_bundle_data: &Self::ComputeBundleData,
+    ) {
+    }
+
     fn compute_pipeline_drop(
         &self,
         _pipeline: &Self::ComputePipelineId,
@@ -2183,6 +2393,17 @@ impl crate::context::Context for Context {
         // Dropped automatically
     }
 
+    fn pipeline_cache_drop(&self, _: &Self::PipelineCacheId, _: &Self::PipelineCacheData) {}
+
+    fn heap_drop(&self, _heap: &Self::HeapId, _heap_data: &Self::HeapData) {}
+
+    fn external_semaphore_drop(
+        &self,
+        _external_semaphore: &Self::ExternalSemaphoreId,
+        _external_semaphore_data: &Self::ExternalSemaphoreData,
+    ) {
+    }
+
     fn compute_pipeline_get_bind_group_layout(
         &self,
         _pipeline: &Self::ComputePipelineId,
@@ -2201,6 +2422,14 @@ impl crate::context::Context for Context {
         create_identified(pipeline_data.0.get_bind_group_layout(index))
     }
 
+    fn pipeline_cache_get_data(
+        &self,
+        _: &Self::PipelineCacheId,
+        _: &Self::PipelineCacheData,
+    ) -> Option<Vec<u8>> {
+        None
+    }
+
     fn command_encoder_copy_buffer_to_buffer(
         &self,
         _encoder: &Self::CommandEncoderId,
@@ -2443,6 +2672,16 @@ impl crate::context::Context for Context {
         }
     }
 
+    fn command_encoder_transition_resources(
+        &self,
+        _encoder: &Self::CommandEncoderId,
+        _encoder_data: &Self::CommandEncoderData,
+        _buffer_transitions: &[crate::BufferTransition],
+        _texture_transitions: &[crate::TextureTransition],
+    ) {
+        // The browser places all barriers itself.
+    }
+
     fn command_encoder_insert_debug_marker(
         &self,
         _encoder: &Self::CommandEncoderId,
@@ -2506,6 +2745,16 @@ impl crate::context::Context for Context {
         );
     }
 
+    fn command_encoder_build_acceleration_structures_unsafe_tlas(
+        &self,
+        _encoder: &Self::CommandEncoderId,
+        _encoder_data: &Self::CommandEncoderData,
+        _blas: &[crate::BlasBuildEntry<'_>],
+        _tlas: &[crate::TlasBuildEntry<'_>],
+    ) {
+        panic!("Web backend does not support acceleration structures")
+    }
+
     fn render_bundle_encoder_finish(
         &self,
         _encoder: Self::RenderBundleEncoderId,
@@ -2522,6 +2771,15 @@ impl crate::context::Context for Context {
         })
     }
 
+    fn compute_bundle_encoder_finish(
+        &self,
+        _encoder: Self::ComputeBundleEncoderId,
+        _encoder_data: Self::ComputeBundleEncoderData,
+        _desc: &crate::ComputeBundleDescriptor,
+    ) -> (Self::ComputeBundleId, Self::ComputeBundleData) {
+        panic!("Web backend does not support compute bundles")
+    }
+
     fn queue_write_buffer(
         &self,
         _queue: &Self::QueueId,
@@ -2659,6 +2917,16 @@ impl crate::context::Context for Context {
             );
     }
 
+    fn queue_update_sparse_bindings(
+        &self,
+        _queue: &Self::QueueId,
+        _queue_data: &Self::QueueData,
+        _buffer_bindings: &[crate::SparseBufferBinding],
+        _texture_bindings: &[crate::SparseTextureBinding],
+    ) {
+        panic!("Web backend does not support sparse resources")
+    }
+
     fn queue_copy_external_image_to_texture(
         &self,
         _queue: &Self::QueueId,
@@ -2676,14 +2944,17 @@ impl crate::context::Context for Context {
             );
     }
 
-    fn queue_submit<I: Iterator<Item = (Self::CommandBufferId, Self::CommandBufferData)>>(
+    fn queue_submit<
+        'a,
+        I: Iterator<Item = (&'a Self::CommandBufferId, &'a Self::CommandBufferData)>,
+    >(
         &self,
         _queue: &Self::QueueId,
         queue_data: &Self::QueueData,
         command_buffers: I,
     ) -> (Self::SubmissionIndex, Self::SubmissionIndexData) {
         let temp_command_buffers = command_buffers
-            .map(|(_, data)| data.0)
+            .map(|(_, data)| data.0.clone())
             .collect::<js_sys::Array>();
 
         queue_data.0.submit(&temp_command_buffers);
@@ -2691,6 +2962,20 @@ impl crate::context::Context for Context {
         (Unused, ())
     }
 
+    fn queue_submit_with_sync<
+        'a,
+        I: Iterator<Item = (&'a Self::CommandBufferId, &'a Self::CommandBufferData)>,
+    >(
+        &self,
+        _queue: &Self::QueueId,
+        _queue_data: &Self::QueueData,
+        _command_buffers: I,
+        _waits: &[(&crate::ExternalSemaphore, u64)],
+        _signals: &[(&crate::ExternalSemaphore, u64)],
+    ) -> (Self::SubmissionIndex, Self::SubmissionIndexData) {
+        panic!("Web backend does not support external semaphores")
+    }
+
     fn queue_get_timestamp_period(
         &self,
         _queue: &Self::QueueId,
@@ -2841,6 +3126,17 @@ impl crate::context::Context for Context {
             .dispatch_workgroups_indirect_with_f64(&indirect_buffer_data.0, indirect_offset as f64);
     }
 
+    fn compute_pass_execute_bundles<'a>(
+        &self,
+        _pass: &mut Self::ComputePassId,
+        _pass_data: &mut Self::ComputePassData,
+        _compute_bundles: Box<
+            dyn Iterator<Item = (Self::ComputeBundleId, &'a Self::ComputeBundleData)> + 'a,
+        >,
+    ) {
+        panic!("Web backend does not support compute bundles")
+    }
+
     fn render_bundle_encoder_set_pipeline(
         &self,
         _encoder: &mut Self::RenderBundleEncoderId,
@@ -3060,6 +3356,109 @@ impl crate::context::Context for Context {
         panic!("MULTI_DRAW_INDIRECT_COUNT feature must be enabled to call multi_draw_indexed_indirect_count")
     }
 
+    fn render_bundle_encoder_insert_debug_marker(
+        &self,
+        _encoder: &mut Self::RenderBundleEncoderId,
+        _encoder_data: &mut Self::RenderBundleEncoderData,
+        _label: &str,
+    ) {
+        // Not available in gecko yet
+        // self.0.insert_debug_marker(label);
+    }
+
+    fn render_bundle_encoder_push_debug_group(
+        &self,
+        _encoder: &mut Self::RenderBundleEncoderId,
+        _encoder_data: &mut Self::RenderBundleEncoderData,
+        _group_label: &str,
+    ) {
+        // Not available in gecko yet
+        // self.0.push_debug_group(group_label);
+    }
+
+    fn render_bundle_encoder_pop_debug_group(
+        &self,
+        _encoder: &mut Self::RenderBundleEncoderId,
+        _encoder_data: &mut Self::RenderBundleEncoderData,
+    ) {
+        // Not available in gecko yet
+        // self.0.pop_debug_group();
+    }
+
+    fn render_bundle_encoder_write_timestamp(
+        &self,
+        _encoder: &mut Self::RenderBundleEncoderId,
+        _encoder_data: &mut Self::RenderBundleEncoderData,
+        _query_set: &Self::QuerySetId,
+        _query_set_data: &Self::QuerySetData,
+        _query_index: u32,
+    ) {
+        panic!("TIMESTAMP_QUERY_INSIDE_PASSES feature must be enabled to call write_timestamp in a render bundle")
+    }
+
+    fn render_bundle_encoder_begin_pipeline_statistics_query(
+        &self,
+        _encoder: &mut Self::RenderBundleEncoderId,
+        _encoder_data: &mut Self::RenderBundleEncoderData,
+        _query_set: &Self::QuerySetId,
+        _query_set_data: &Self::QuerySetData,
+        _query_index: u32,
+    ) {
+        // Not available in gecko yet
+    }
+
+    fn render_bundle_encoder_end_pipeline_statistics_query(
+        &self,
+        _encoder: &mut Self::RenderBundleEncoderId,
+        _encoder_data: &mut Self::RenderBundleEncoderData,
+    ) {
+        // Not available in gecko yet
+    }
+
+    fn compute_bundle_encoder_set_pipeline(
+        &self,
+        _encoder: &mut Self::ComputeBundleEncoderId,
+        _encoder_data: &mut Self::ComputeBundleEncoderData,
+        _pipeline: &Self::ComputePipelineId,
+        _pipeline_data: &Self::ComputePipelineData,
+    ) {
+        panic!("Web backend does not support compute bundles")
+    }
+
+    fn compute_bundle_encoder_set_bind_group(
+        &self,
+        _encoder: &mut Self::ComputeBundleEncoderId,
+        _encoder_data: &mut Self::ComputeBundleEncoderData,
+        _index: u32,
+        _bind_group: &Self::BindGroupId,
+        _bind_group_data: &Self::BindGroupData,
+        _offsets: &[wgt::DynamicOffset],
+    ) {
+        panic!("Web backend does not support compute bundles")
+    }
+
+    fn compute_bundle_encoder_dispatch_workgroups(
+        &self,
+        _encoder: &mut Self::ComputeBundleEncoderId,
+        _encoder_data: &mut Self::ComputeBundleEncoderData,
+        _x: u32,
+        _y: u32,
+        _z: u32,
+    ) {
+        panic!("Web backend does not support compute bundles")
+    }
+
+    fn compute_bundle_encoder_dispatch_workgroups_indirect(
+        &self,
+        _encoder: &mut Self::ComputeBundleEncoderId,
+        _encoder_data: &mut Self::ComputeBundleEncoderData,
+        _indirect_buffer: &Self::BufferId,
+        _indirect_buffer_data: &Self::BufferData,
+        _indirect_offset: wgt::BufferAddress,
+    ) {
+        panic!("Web backend does not support compute bundles")
+    }
+
     fn render_pass_set_pipeline(
         &self,
         _pass: &mut Self::RenderPassId,
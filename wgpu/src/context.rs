@@ -2,20 +2,23 @@ use std::{any::Any, fmt::Debug, future::Future, num::NonZeroU64, ops::Range, pin
 
 use wgt::{
     strict_assert, strict_assert_eq, AdapterInfo, BufferAddress, BufferSize, Color,
-    DownlevelCapabilities, DynamicOffset, Extent3d, Features, ImageDataLayout,
-    ImageSubresourceRange, IndexFormat, Limits, ShaderStages, SurfaceStatus, TextureFormat,
-    TextureFormatFeatures, WasmNotSend, WasmNotSync,
+    CompilationInfo, DeviceLostReason, DownlevelCapabilities, DynamicOffset, Extent3d, Features,
+    ImageDataLayout, ImageSubresourceRange, IndexFormat, Limits, MemoryReport, ShaderStages,
+    SurfaceStatus, TextureFormat, TextureFormatFeatures, WasmNotSend, WasmNotSync,
 };
 
 use crate::{
-    AnyWasmNotSendSync, BindGroupDescriptor, BindGroupLayoutDescriptor, Buffer, BufferAsyncError,
-    BufferDescriptor, CommandEncoderDescriptor, ComputePassDescriptor, ComputePipelineDescriptor,
-    DeviceDescriptor, Error, ErrorFilter, ImageCopyBuffer, ImageCopyTexture, Maintain, MapMode,
-    PipelineLayoutDescriptor, QuerySetDescriptor, RenderBundleDescriptor,
-    RenderBundleEncoderDescriptor, RenderPassDescriptor, RenderPipelineDescriptor,
-    RequestAdapterOptions, RequestDeviceError, SamplerDescriptor, ShaderModuleDescriptor,
-    ShaderModuleDescriptorSpirV, Texture, TextureDescriptor, TextureViewDescriptor,
-    UncapturedErrorHandler,
+    AnyWasmNotSendSync, BindGroupDescriptor, BindGroupLayoutDescriptor, BlasBuildEntry, Buffer,
+    BufferAsyncError, BufferDescriptor, BufferTransition, CommandEncoderDescriptor,
+    ComputeBundleDescriptor, ComputeBundleEncoderDescriptor, ComputePassDescriptor,
+    ComputePipelineDescriptor, CreateBlasDescriptor, CreateTlasDescriptor, DeviceDescriptor, Error,
+    ErrorFilter, ExternalSemaphore, ExternalSemaphoreDescriptor, HeapDescriptor, ImageCopyBuffer,
+    ImageCopyTexture, Maintain, MapMode, PipelineCacheDescriptor, PipelineLayoutDescriptor,
+    QuerySetDescriptor, RenderBundleDescriptor, RenderBundleEncoderDescriptor,
+    RenderPassDescriptor, RenderPipelineDescriptor, RequestAdapterOptions, RequestDeviceError,
+    SamplerDescriptor, ShaderModuleDescriptor, ShaderModuleDescriptorSpirV, SparseBufferBinding,
+    SparseTextureBinding, Texture, TextureDescriptor, TextureTransition, TextureViewDescriptor,
+    TlasBuildEntry, UncapturedErrorHandler,
 };
 
 /// Meta trait for an id tracked by a context.
@@ -59,6 +62,16 @@ pub trait Context: Debug + WasmNotSend + WasmNotSync + Sized {
     type RenderPipelineData: ContextData;
     type ComputePipelineId: ContextId + WasmNotSend + WasmNotSync;
     type ComputePipelineData: ContextData;
+    type PipelineCacheId: ContextId + WasmNotSend + WasmNotSync;
+    type PipelineCacheData: ContextData;
+    type HeapId: ContextId + WasmNotSend + WasmNotSync;
+    type HeapData: ContextData;
+    type ExternalSemaphoreId: ContextId + WasmNotSend + WasmNotSync;
+    type ExternalSemaphoreData: ContextData;
+    type BlasId: ContextId + WasmNotSend + WasmNotSync;
+    type BlasData: ContextData;
+    type TlasId: ContextId + WasmNotSend + WasmNotSync;
+    type TlasData: ContextData;
     type CommandEncoderId: ContextId + WasmNotSend + WasmNotSync;
     type CommandEncoderData: ContextData;
     type ComputePassId: ContextId;
@@ -71,6 +84,10 @@ pub trait Context: Debug + WasmNotSend + WasmNotSync + Sized {
     type RenderBundleEncoderData: ContextData;
     type RenderBundleId: ContextId + WasmNotSend + WasmNotSync;
     type RenderBundleData: ContextData;
+    type ComputeBundleEncoderId: ContextId;
+    type ComputeBundleEncoderData: ContextData;
+    type ComputeBundleId: ContextId + WasmNotSend + WasmNotSync;
+    type ComputeBundleData: ContextData;
     type SurfaceId: ContextId + WasmNotSend + WasmNotSync;
     type SurfaceData: ContextData;
 
@@ -94,6 +111,7 @@ pub trait Context: Debug + WasmNotSend + WasmNotSync + Sized {
         > + WasmNotSend
         + 'static;
     type PopErrorScopeFuture: Future<Output = Option<Error>> + WasmNotSend + 'static;
+    type CompilationInfoFuture: Future<Output = CompilationInfo> + WasmNotSend + 'static;
 
     fn init(instance_desc: wgt::InstanceDescriptor) -> Self;
     fn instance_create_surface(
@@ -189,6 +207,11 @@ pub trait Context: Debug + WasmNotSend + WasmNotSync + Sized {
         device: &Self::DeviceId,
         device_data: &Self::DeviceData,
     ) -> DownlevelCapabilities;
+    fn device_memory_report(
+        &self,
+        device: &Self::DeviceId,
+        device_data: &Self::DeviceData,
+    ) -> MemoryReport;
     fn device_create_shader_module(
         &self,
         device: &Self::DeviceId,
@@ -232,6 +255,12 @@ pub trait Context: Debug + WasmNotSend + WasmNotSync + Sized {
         device_data: &Self::DeviceData,
         desc: &ComputePipelineDescriptor,
     ) -> (Self::ComputePipelineId, Self::ComputePipelineData);
+    unsafe fn device_create_pipeline_cache(
+        &self,
+        device: &Self::DeviceId,
+        device_data: &Self::DeviceData,
+        desc: &PipelineCacheDescriptor,
+    ) -> (Self::PipelineCacheId, Self::PipelineCacheData);
     fn device_create_buffer(
         &self,
         device: &Self::DeviceId,
@@ -256,6 +285,61 @@ pub trait Context: Debug + WasmNotSend + WasmNotSync + Sized {
         device_data: &Self::DeviceData,
         desc: &QuerySetDescriptor,
     ) -> (Self::QuerySetId, Self::QuerySetData);
+    fn device_create_heap(
+        &self,
+        device: &Self::DeviceId,
+        device_data: &Self::DeviceData,
+        desc: &HeapDescriptor,
+    ) -> (Self::HeapId, Self::HeapData);
+    fn device_create_external_semaphore(
+        &self,
+        device: &Self::DeviceId,
+        device_data: &Self::DeviceData,
+        desc: &ExternalSemaphoreDescriptor,
+    ) -> (Self::ExternalSemaphoreId, Self::ExternalSemaphoreData);
+    fn device_create_buffer_in_heap(
+        &self,
+        device: &Self::DeviceId,
+        device_data: &Self::DeviceData,
+        desc: &BufferDescriptor,
+        heap: &Self::HeapId,
+        heap_data: &Self::HeapData,
+        offset: BufferAddress,
+    ) -> (Self::BufferId, Self::BufferData);
+    fn device_create_texture_in_heap(
+        &self,
+        device: &Self::DeviceId,
+        device_data: &Self::DeviceData,
+        desc: &TextureDescriptor,
+        heap: &Self::HeapId,
+        heap_data: &Self::HeapData,
+        offset: BufferAddress,
+    ) -> (Self::TextureId, Self::TextureData);
+    fn device_create_sparse_buffer(
+        &self,
+        device: &Self::DeviceId,
+        device_data: &Self::DeviceData,
+        desc: &BufferDescriptor,
+    ) -> (Self::BufferId, Self::BufferData);
+    fn device_create_sparse_texture(
+        &self,
+        device: &Self::DeviceId,
+        device_data: &Self::DeviceData,
+        desc: &TextureDescriptor,
+    ) -> (Self::TextureId, Self::TextureData);
+    fn device_create_blas(
+        &self,
+        device: &Self::DeviceId,
+        device_data: &Self::DeviceData,
+        desc: &CreateBlasDescriptor,
+        sizes: wgt::BlasGeometrySizeDescriptors,
+    ) -> (Self::BlasId, Option<u64>, Self::BlasData);
+    fn device_create_tlas(
+        &self,
+        device: &Self::DeviceId,
+        device_data: &Self::DeviceData,
+        desc: &CreateTlasDescriptor,
+    ) -> (Self::TlasId, Self::TlasData);
     fn device_create_command_encoder(
         &self,
         device: &Self::DeviceId,
@@ -268,9 +352,21 @@ pub trait Context: Debug + WasmNotSend + WasmNotSync + Sized {
         device_data: &Self::DeviceData,
         desc: &RenderBundleEncoderDescriptor,
     ) -> (Self::RenderBundleEncoderId, Self::RenderBundleEncoderData);
+    fn device_create_compute_bundle_encoder(
+        &self,
+        device: &Self::DeviceId,
+        device_data: &Self::DeviceData,
+        desc: &ComputeBundleEncoderDescriptor,
+    ) -> (Self::ComputeBundleEncoderId, Self::ComputeBundleEncoderData);
     fn device_drop(&self, device: &Self::DeviceId, device_data: &Self::DeviceData);
     fn device_destroy(&self, device: &Self::DeviceId, device_data: &Self::DeviceData);
     fn device_lose(&self, device: &Self::DeviceId, device_data: &Self::DeviceData);
+    fn device_set_device_lost_callback(
+        &self,
+        device: &Self::DeviceId,
+        device_data: &Self::DeviceData,
+        device_lost_callback: DeviceLostCallback,
+    );
     fn device_poll(
         &self,
         device: &Self::DeviceId,
@@ -295,6 +391,12 @@ pub trait Context: Debug + WasmNotSend + WasmNotSync + Sized {
         device_data: &Self::DeviceData,
     ) -> Self::PopErrorScopeFuture;
 
+    fn shader_get_compilation_info(
+        &self,
+        shader: &Self::ShaderModuleId,
+        shader_data: &Self::ShaderModuleData,
+    ) -> Self::CompilationInfoFuture;
+
     fn buffer_map_async(
         &self,
         buffer: &Self::BufferId,
@@ -340,6 +442,14 @@ pub trait Context: Debug + WasmNotSend + WasmNotSync + Sized {
     );
     fn sampler_drop(&self, sampler: &Self::SamplerId, sampler_data: &Self::SamplerData);
     fn query_set_drop(&self, query_set: &Self::QuerySetId, query_set_data: &Self::QuerySetData);
+    fn heap_drop(&self, heap: &Self::HeapId, heap_data: &Self::HeapData);
+    fn external_semaphore_drop(
+        &self,
+        external_semaphore: &Self::ExternalSemaphoreId,
+        external_semaphore_data: &Self::ExternalSemaphoreData,
+    );
+    fn blas_drop(&self, blas: &Self::BlasId, blas_data: &Self::BlasData);
+    fn tlas_drop(&self, tlas: &Self::TlasId, tlas_data: &Self::TlasData);
     fn bind_group_drop(
         &self,
         bind_group: &Self::BindGroupId,
@@ -375,6 +485,11 @@ pub trait Context: Debug + WasmNotSend + WasmNotSync + Sized {
         render_bundle: &Self::RenderBundleId,
         render_bundle_data: &Self::RenderBundleData,
     );
+    fn compute_bundle_drop(
+        &self,
+        compute_bundle: &Self::ComputeBundleId,
+        compute_bundle_data: &Self::ComputeBundleData,
+    );
     fn compute_pipeline_drop(
         &self,
         pipeline: &Self::ComputePipelineId,
@@ -385,6 +500,11 @@ pub trait Context: Debug + WasmNotSend + WasmNotSync + Sized {
         pipeline: &Self::RenderPipelineId,
         pipeline_data: &Self::RenderPipelineData,
     );
+    fn pipeline_cache_drop(
+        &self,
+        cache: &Self::PipelineCacheId,
+        cache_data: &Self::PipelineCacheData,
+    );
 
     fn compute_pipeline_get_bind_group_layout(
         &self,
@@ -398,6 +518,11 @@ pub trait Context: Debug + WasmNotSend + WasmNotSync + Sized {
         pipeline_data: &Self::RenderPipelineData,
         index: u32,
     ) -> (Self::BindGroupLayoutId, Self::BindGroupLayoutData);
+    fn pipeline_cache_get_data(
+        &self,
+        cache: &Self::PipelineCacheId,
+        cache_data: &Self::PipelineCacheData,
+    ) -> Option<Vec<u8>>;
 
     #[allow(clippy::too_many_arguments)]
     fn command_encoder_copy_buffer_to_buffer(
@@ -484,6 +609,13 @@ pub trait Context: Debug + WasmNotSend + WasmNotSync + Sized {
         offset: BufferAddress,
         size: Option<BufferSize>,
     );
+    fn command_encoder_transition_resources(
+        &self,
+        encoder: &Self::CommandEncoderId,
+        encoder_data: &Self::CommandEncoderData,
+        buffer_transitions: &[BufferTransition],
+        texture_transitions: &[TextureTransition],
+    );
 
     fn command_encoder_insert_debug_marker(
         &self,
@@ -524,6 +656,13 @@ pub trait Context: Debug + WasmNotSend + WasmNotSync + Sized {
         destination_data: &Self::BufferData,
         destination_offset: BufferAddress,
     );
+    fn command_encoder_build_acceleration_structures_unsafe_tlas(
+        &self,
+        encoder: &Self::CommandEncoderId,
+        encoder_data: &Self::CommandEncoderData,
+        blas: &[BlasBuildEntry<'_>],
+        tlas: &[TlasBuildEntry<'_>],
+    );
 
     fn render_bundle_encoder_finish(
         &self,
@@ -531,6 +670,12 @@ pub trait Context: Debug + WasmNotSend + WasmNotSync + Sized {
         encoder_data: Self::RenderBundleEncoderData,
         desc: &RenderBundleDescriptor,
     ) -> (Self::RenderBundleId, Self::RenderBundleData);
+    fn compute_bundle_encoder_finish(
+        &self,
+        encoder: Self::ComputeBundleEncoderId,
+        encoder_data: Self::ComputeBundleEncoderData,
+        desc: &ComputeBundleDescriptor,
+    ) -> (Self::ComputeBundleId, Self::ComputeBundleData);
     fn queue_write_buffer(
         &self,
         queue: &Self::QueueId,
@@ -573,6 +718,13 @@ pub trait Context: Debug + WasmNotSend + WasmNotSync + Sized {
         data_layout: ImageDataLayout,
         size: Extent3d,
     );
+    fn queue_update_sparse_bindings(
+        &self,
+        queue: &Self::QueueId,
+        queue_data: &Self::QueueData,
+        buffer_bindings: &[SparseBufferBinding],
+        texture_bindings: &[SparseTextureBinding],
+    );
     #[cfg(all(target_arch = "wasm32", not(target_os = "emscripten")))]
     fn queue_copy_external_image_to_texture(
         &self,
@@ -582,11 +734,25 @@ pub trait Context: Debug + WasmNotSend + WasmNotSync + Sized {
         dest: crate::ImageCopyTextureTagged,
         size: wgt::Extent3d,
     );
-    fn queue_submit<I: Iterator<Item = (Self::CommandBufferId, Self::CommandBufferData)>>(
+    fn queue_submit<
+        'a,
+        I: Iterator<Item = (&'a Self::CommandBufferId, &'a Self::CommandBufferData)>,
+    >(
+        &self,
+        queue: &Self::QueueId,
+        queue_data: &Self::QueueData,
+        command_buffers: I,
+    ) -> (Self::SubmissionIndex, Self::SubmissionIndexData);
+    fn queue_submit_with_sync<
+        'a,
+        I: Iterator<Item = (&'a Self::CommandBufferId, &'a Self::CommandBufferData)>,
+    >(
         &self,
         queue: &Self::QueueId,
         queue_data: &Self::QueueData,
         command_buffers: I,
+        waits: &[(&ExternalSemaphore, u64)],
+        signals: &[(&ExternalSemaphore, u64)],
     ) -> (Self::SubmissionIndex, Self::SubmissionIndexData);
     fn queue_get_timestamp_period(
         &self,
@@ -680,6 +846,14 @@ pub trait Context: Debug + WasmNotSend + WasmNotSync + Sized {
         indirect_buffer_data: &Self::BufferData,
         indirect_offset: BufferAddress,
     );
+    fn compute_pass_execute_bundles<'a>(
+        &self,
+        pass: &mut Self::ComputePassId,
+        pass_data: &mut Self::ComputePassData,
+        compute_bundles: Box<
+            dyn Iterator<Item = (Self::ComputeBundleId, &'a Self::ComputeBundleData)> + 'a,
+        >,
+    );
 
     fn render_bundle_encoder_set_pipeline(
         &self,
@@ -803,6 +977,78 @@ pub trait Context: Debug + WasmNotSend + WasmNotSync + Sized {
         max_count: u32,
     );
 
+    fn render_bundle_encoder_insert_debug_marker(
+        &self,
+        encoder: &mut Self::RenderBundleEncoderId,
+        encoder_data: &mut Self::RenderBundleEncoderData,
+        label: &str,
+    );
+    fn render_bundle_encoder_push_debug_group(
+        &self,
+        encoder: &mut Self::RenderBundleEncoderId,
+        encoder_data: &mut Self::RenderBundleEncoderData,
+        group_label: &str,
+    );
+    fn render_bundle_encoder_pop_debug_group(
+        &self,
+        encoder: &mut Self::RenderBundleEncoderId,
+        encoder_data: &mut Self::RenderBundleEncoderData,
+    );
+    fn render_bundle_encoder_write_timestamp(
+        &self,
+        encoder: &mut Self::RenderBundleEncoderId,
+        encoder_data: &mut Self::RenderBundleEncoderData,
+        query_set: &Self::QuerySetId,
+        query_set_data: &Self::QuerySetData,
+        query_index: u32,
+    );
+    fn render_bundle_encoder_begin_pipeline_statistics_query(
+        &self,
+        encoder: &mut Self::RenderBundleEncoderId,
+        encoder_data: &mut Self::RenderBundleEncoderData,
+        query_set: &Self::QuerySetId,
+        query_set_data: &Self::QuerySetData,
+        query_index: u32,
+    );
+    fn render_bundle_encoder_end_pipeline_statistics_query(
+        &self,
+        encoder: &mut Self::RenderBundleEncoderId,
+        encoder_data: &mut Self::RenderBundleEncoderData,
+    );
+
+    fn compute_bundle_encoder_set_pipeline(
+        &self,
+        encoder: &mut Self::ComputeBundleEncoderId,
+        encoder_data: &mut Self::ComputeBundleEncoderData,
+        pipeline: &Self::ComputePipelineId,
+        pipeline_data: &Self::ComputePipelineData,
+    );
+    fn compute_bundle_encoder_set_bind_group(
+        &self,
+        encoder: &mut Self::ComputeBundleEncoderId,
+        encoder_data: &mut Self::ComputeBundleEncoderData,
+        index: u32,
+        bind_group: &Self::BindGroupId,
+        bind_group_data: &Self::BindGroupData,
+        offsets: &[DynamicOffset],
+    );
+    fn compute_bundle_encoder_dispatch_workgroups(
+        &self,
+        encoder: &mut Self::ComputeBundleEncoderId,
+        encoder_data: &mut Self::ComputeBundleEncoderData,
+        x: u32,
+        y: u32,
+        z: u32,
+    );
+    fn compute_bundle_encoder_dispatch_workgroups_indirect(
+        &self,
+        encoder: &mut Self::ComputeBundleEncoderId,
+        encoder_data: &mut Self::ComputeBundleEncoderData,
+        indirect_buffer: &Self::BufferId,
+        indirect_buffer_data: &Self::BufferData,
+        indirect_offset: BufferAddress,
+    );
+
     fn render_pass_set_pipeline(
         &self,
         pass: &mut Self::RenderPassId,
@@ -1183,6 +1429,23 @@ pub type DevicePopErrorFuture = Box<dyn Future<Output = Option<Error>> + Send>;
 )))]
 pub type DevicePopErrorFuture = Box<dyn Future<Output = Option<Error>>>;
 
+#[cfg(any(
+    not(target_arch = "wasm32"),
+    all(
+        feature = "fragile-send-sync-non-atomic-wasm",
+        not(target_feature = "atomics")
+    )
+))]
+pub type ShaderCompilationInfoFuture = Box<dyn Future<Output = CompilationInfo> + Send>;
+#[cfg(not(any(
+    not(target_arch = "wasm32"),
+    all(
+        feature = "fragile-send-sync-non-atomic-wasm",
+        not(target_feature = "atomics")
+    )
+)))]
+pub type ShaderCompilationInfoFuture = Box<dyn Future<Output = CompilationInfo>>;
+
 #[cfg(any(
     not(target_arch = "wasm32"),
     all(
@@ -1199,6 +1462,22 @@ pub type SubmittedWorkDoneCallback = Box<dyn FnOnce() + Send + 'static>;
     )
 )))]
 pub type SubmittedWorkDoneCallback = Box<dyn FnOnce() + 'static>;
+#[cfg(any(
+    not(target_arch = "wasm32"),
+    all(
+        feature = "fragile-send-sync-non-atomic-wasm",
+        not(target_feature = "atomics")
+    )
+))]
+pub type DeviceLostCallback = Box<dyn FnOnce(DeviceLostReason, String) + Send + 'static>;
+#[cfg(not(any(
+    not(target_arch = "wasm32"),
+    all(
+        feature = "fragile-send-sync-non-atomic-wasm",
+        not(target_feature = "atomics")
+    )
+)))]
+pub type DeviceLostCallback = Box<dyn FnOnce(DeviceLostReason, String) + 'static>;
 
 /// An object safe variant of [`Context`] implemented by all types that implement [`Context`].
 pub(crate) trait DynContext: Debug + WasmNotSend + WasmNotSync {
@@ -1285,6 +1564,7 @@ pub(crate) trait DynContext: Debug + WasmNotSend + WasmNotSync {
         device: &ObjectId,
         device_data: &crate::Data,
     ) -> DownlevelCapabilities;
+    fn device_memory_report(&self, device: &ObjectId, device_data: &crate::Data) -> MemoryReport;
     fn device_create_shader_module(
         &self,
         device: &ObjectId,
@@ -1328,6 +1608,12 @@ pub(crate) trait DynContext: Debug + WasmNotSend + WasmNotSync {
         device_data: &crate::Data,
         desc: &ComputePipelineDescriptor,
     ) -> (ObjectId, Box<crate::Data>);
+    unsafe fn device_create_pipeline_cache(
+        &self,
+        device: &ObjectId,
+        device_data: &crate::Data,
+        desc: &PipelineCacheDescriptor,
+    ) -> (ObjectId, Box<crate::Data>);
     fn device_create_buffer(
         &self,
         device: &ObjectId,
@@ -1352,6 +1638,61 @@ pub(crate) trait DynContext: Debug + WasmNotSend + WasmNotSync {
         device_data: &crate::Data,
         desc: &QuerySetDescriptor,
     ) -> (ObjectId, Box<crate::Data>);
+    fn device_create_heap(
+        &self,
+        device: &ObjectId,
+        device_data: &crate::Data,
+        desc: &HeapDescriptor,
+    ) -> (ObjectId, Box<crate::Data>);
+    fn device_create_external_semaphore(
+        &self,
+        device: &ObjectId,
+        device_data: &crate::Data,
+        desc: &ExternalSemaphoreDescriptor,
+    ) -> (ObjectId, Box<crate::Data>);
+    fn device_create_buffer_in_heap(
+        &self,
+        device: &ObjectId,
+        device_data: &crate::Data,
+        desc: &BufferDescriptor,
+        heap: &ObjectId,
+        heap_data: &crate::Data,
+        offset: BufferAddress,
+    ) -> (ObjectId, Box<crate::Data>);
+    fn device_create_texture_in_heap(
+        &self,
+        device: &ObjectId,
+        device_data: &crate::Data,
+        desc: &TextureDescriptor,
+        heap: &ObjectId,
+        heap_data: &crate::Data,
+        offset: BufferAddress,
+    ) -> (ObjectId, Box<crate::Data>);
+    fn device_create_sparse_buffer(
+        &self,
+        device: &ObjectId,
+        device_data: &crate::Data,
+        desc: &BufferDescriptor,
+    ) -> (ObjectId, Box<crate::Data>);
+    fn device_create_sparse_texture(
+        &self,
+        device: &ObjectId,
+        device_data: &crate::Data,
+        desc: &TextureDescriptor,
+    ) -> (ObjectId, Box<crate::Data>);
+    fn device_create_blas(
+        &self,
+        device: &ObjectId,
+        device_data: &crate::Data,
+        desc: &CreateBlasDescriptor,
+        sizes: wgt::BlasGeometrySizeDescriptors,
+    ) -> (ObjectId, Option<u64>, Box<crate::Data>);
+    fn device_create_tlas(
+        &self,
+        device: &ObjectId,
+        device_data: &crate::Data,
+        desc: &CreateTlasDescriptor,
+    ) -> (ObjectId, Box<crate::Data>);
     fn device_create_command_encoder(
         &self,
         device: &ObjectId,
@@ -1364,9 +1705,21 @@ pub(crate) trait DynContext: Debug + WasmNotSend + WasmNotSync {
         device_data: &crate::Data,
         desc: &RenderBundleEncoderDescriptor,
     ) -> (ObjectId, Box<crate::Data>);
+    fn device_create_compute_bundle_encoder(
+        &self,
+        device: &ObjectId,
+        device_data: &crate::Data,
+        desc: &ComputeBundleEncoderDescriptor,
+    ) -> (ObjectId, Box<crate::Data>);
     fn device_drop(&self, device: &ObjectId, device_data: &crate::Data);
     fn device_destroy(&self, device: &ObjectId, device_data: &crate::Data);
     fn device_lose(&self, device: &ObjectId, device_data: &crate::Data);
+    fn device_set_device_lost_callback(
+        &self,
+        device: &ObjectId,
+        device_data: &crate::Data,
+        device_lost_callback: DeviceLostCallback,
+    );
     fn device_poll(&self, device: &ObjectId, device_data: &crate::Data, maintain: Maintain)
         -> bool;
     fn device_on_uncaptured_error(
@@ -1386,6 +1739,12 @@ pub(crate) trait DynContext: Debug + WasmNotSend + WasmNotSync {
         device: &ObjectId,
         device_data: &crate::Data,
     ) -> Pin<DevicePopErrorFuture>;
+
+    fn shader_get_compilation_info(
+        &self,
+        shader: &ObjectId,
+        shader_data: &crate::Data,
+    ) -> Pin<ShaderCompilationInfoFuture>;
     fn buffer_map_async(
         &self,
         buffer: &ObjectId,
@@ -1427,6 +1786,14 @@ pub(crate) trait DynContext: Debug + WasmNotSend + WasmNotSync {
     fn texture_view_drop(&self, texture_view: &ObjectId, texture_view_data: &crate::Data);
     fn sampler_drop(&self, sampler: &ObjectId, sampler_data: &crate::Data);
     fn query_set_drop(&self, query_set: &ObjectId, query_set_data: &crate::Data);
+    fn heap_drop(&self, heap: &ObjectId, heap_data: &crate::Data);
+    fn external_semaphore_drop(
+        &self,
+        external_semaphore: &ObjectId,
+        external_semaphore_data: &crate::Data,
+    );
+    fn blas_drop(&self, blas: &ObjectId, blas_data: &crate::Data);
+    fn tlas_drop(&self, tlas: &ObjectId, tlas_data: &crate::Data);
     fn bind_group_drop(&self, bind_group: &ObjectId, bind_group_data: &crate::Data);
     fn bind_group_layout_drop(
         &self,
@@ -1438,8 +1805,10 @@ pub(crate) trait DynContext: Debug + WasmNotSend + WasmNotSync {
     fn command_encoder_drop(&self, command_encoder: &ObjectId, command_encoder_data: &crate::Data);
     fn command_buffer_drop(&self, command_buffer: &ObjectId, command_buffer_data: &crate::Data);
     fn render_bundle_drop(&self, render_bundle: &ObjectId, render_bundle_data: &crate::Data);
+    fn compute_bundle_drop(&self, compute_bundle: &ObjectId, compute_bundle_data: &crate::Data);
     fn compute_pipeline_drop(&self, pipeline: &ObjectId, pipeline_data: &crate::Data);
     fn render_pipeline_drop(&self, pipeline: &ObjectId, pipeline_data: &crate::Data);
+    fn pipeline_cache_drop(&self, cache: &ObjectId, cache_data: &crate::Data);
 
     fn compute_pipeline_get_bind_group_layout(
         &self,
@@ -1453,6 +1822,11 @@ pub(crate) trait DynContext: Debug + WasmNotSend + WasmNotSync {
         pipeline_data: &crate::Data,
         index: u32,
     ) -> (ObjectId, Box<crate::Data>);
+    fn pipeline_cache_get_data(
+        &self,
+        cache: &ObjectId,
+        cache_data: &crate::Data,
+    ) -> Option<Vec<u8>>;
 
     #[allow(clippy::too_many_arguments)]
     fn command_encoder_copy_buffer_to_buffer(
@@ -1539,6 +1913,13 @@ pub(crate) trait DynContext: Debug + WasmNotSend + WasmNotSync {
         offset: BufferAddress,
         size: Option<BufferSize>,
     );
+    fn command_encoder_transition_resources(
+        &self,
+        encoder: &ObjectId,
+        encoder_data: &crate::Data,
+        buffer_transitions: &[BufferTransition],
+        texture_transitions: &[TextureTransition],
+    );
 
     fn command_encoder_insert_debug_marker(
         &self,
@@ -1575,6 +1956,13 @@ pub(crate) trait DynContext: Debug + WasmNotSend + WasmNotSync {
         destination_data: &crate::Data,
         destination_offset: BufferAddress,
     );
+    fn command_encoder_build_acceleration_structures_unsafe_tlas(
+        &self,
+        encoder: &ObjectId,
+        encoder_data: &crate::Data,
+        blas: &[BlasBuildEntry<'_>],
+        tlas: &[TlasBuildEntry<'_>],
+    );
 
     fn render_bundle_encoder_finish(
         &self,
@@ -1582,6 +1970,12 @@ pub(crate) trait DynContext: Debug + WasmNotSend + WasmNotSync {
         encoder_data: Box<crate::Data>,
         desc: &RenderBundleDescriptor,
     ) -> (ObjectId, Box<crate::Data>);
+    fn compute_bundle_encoder_finish(
+        &self,
+        encoder: ObjectId,
+        encoder_data: Box<crate::Data>,
+        desc: &ComputeBundleDescriptor,
+    ) -> (ObjectId, Box<crate::Data>);
     fn queue_write_buffer(
         &self,
         queue: &ObjectId,
@@ -1624,6 +2018,13 @@ pub(crate) trait DynContext: Debug + WasmNotSend + WasmNotSync {
         data_layout: ImageDataLayout,
         size: Extent3d,
     );
+    fn queue_update_sparse_bindings(
+        &self,
+        queue: &ObjectId,
+        queue_data: &crate::Data,
+        buffer_bindings: &[SparseBufferBinding],
+        texture_bindings: &[SparseTextureBinding],
+    );
     #[cfg(all(target_arch = "wasm32", not(target_os = "emscripten")))]
     fn queue_copy_external_image_to_texture(
         &self,
@@ -1637,7 +2038,15 @@ pub(crate) trait DynContext: Debug + WasmNotSend + WasmNotSync {
         &self,
         queue: &ObjectId,
         queue_data: &crate::Data,
-        command_buffers: Box<dyn Iterator<Item = (ObjectId, Box<crate::Data>)> + 'a>,
+        command_buffers: Box<dyn Iterator<Item = (&'a ObjectId, &'a crate::Data)> + 'a>,
+    ) -> (ObjectId, Arc<crate::Data>);
+    fn queue_submit_with_sync<'a>(
+        &self,
+        queue: &ObjectId,
+        queue_data: &crate::Data,
+        command_buffers: Box<dyn Iterator<Item = (&'a ObjectId, &'a crate::Data)> + 'a>,
+        waits: &[(&ExternalSemaphore, u64)],
+        signals: &[(&ExternalSemaphore, u64)],
     ) -> (ObjectId, Arc<crate::Data>);
     fn queue_get_timestamp_period(&self, queue: &ObjectId, queue_data: &crate::Data) -> f32;
     fn queue_on_submitted_work_done(
@@ -1723,6 +2132,12 @@ pub(crate) trait DynContext: Debug + WasmNotSend + WasmNotSync {
         indirect_buffer_data: &crate::Data,
         indirect_offset: BufferAddress,
     );
+    fn compute_pass_execute_bundles<'a>(
+        &self,
+        pass: &mut ObjectId,
+        pass_data: &mut crate::Data,
+        compute_bundles: Box<dyn Iterator<Item = (&'a ObjectId, &'a crate::Data)> + 'a>,
+    );
 
     fn render_bundle_encoder_set_pipeline(
         &self,
@@ -1846,22 +2261,94 @@ pub(crate) trait DynContext: Debug + WasmNotSend + WasmNotSync {
         max_count: u32,
     );
 
-    fn render_pass_set_pipeline(
+    fn render_bundle_encoder_insert_debug_marker(
         &self,
-        pass: &mut ObjectId,
-        pass_data: &mut crate::Data,
-        pipeline: &ObjectId,
-        pipeline_data: &crate::Data,
+        encoder: &mut ObjectId,
+        encoder_data: &mut crate::Data,
+        label: &str,
     );
-    fn render_pass_set_bind_group(
+    fn render_bundle_encoder_push_debug_group(
         &self,
-        pass: &mut ObjectId,
-        pass_data: &mut crate::Data,
-        index: u32,
-        bind_group: &ObjectId,
-        bind_group_data: &crate::Data,
-        offsets: &[DynamicOffset],
-    );
+        encoder: &mut ObjectId,
+        encoder_data: &mut crate::Data,
+        group_label: &str,
+    );
+    fn render_bundle_encoder_pop_debug_group(
+        &self,
+        encoder: &mut ObjectId,
+        encoder_data: &mut crate::Data,
+    );
+    fn render_bundle_encoder_write_timestamp(
+        &self,
+        encoder: &mut ObjectId,
+        encoder_data: &mut crate::Data,
+        query_set: &ObjectId,
+        query_set_data: &crate::Data,
+        query_index: u32,
+    );
+    fn render_bundle_encoder_begin_pipeline_statistics_query(
+        &self,
+        encoder: &mut ObjectId,
+        encoder_data: &mut crate::Data,
+        query_set: &ObjectId,
+        query_set_data: &crate::Data,
+        query_index: u32,
+    );
+    fn render_bundle_encoder_end_pipeline_statistics_query(
+        &self,
+        encoder: &mut ObjectId,
+        encoder_data: &mut crate::Data,
+    );
+
+    fn compute_bundle_encoder_set_pipeline(
+        &self,
+        encoder: &mut ObjectId,
+        encoder_data: &mut crate::Data,
+        pipeline: &ObjectId,
+        pipeline_data: &crate::Data,
+    );
+    fn compute_bundle_encoder_set_bind_group(
+        &self,
+        encoder: &mut ObjectId,
+        encoder_data: &mut crate::Data,
+        index: u32,
+        bind_group: &ObjectId,
+        bind_group_data: &crate::Data,
+        offsets: &[DynamicOffset],
+    );
+    fn compute_bundle_encoder_dispatch_workgroups(
+        &self,
+        encoder: &mut ObjectId,
+        encoder_data: &mut crate::Data,
+        x: u32,
+        y: u32,
+        z: u32,
+    );
+    fn compute_bundle_encoder_dispatch_workgroups_indirect(
+        &self,
+        encoder: &mut ObjectId,
+        encoder_data: &mut crate::Data,
+        indirect_buffer: &ObjectId,
+        indirect_buffer_data: &crate::Data,
+        indirect_offset: BufferAddress,
+    );
+
+    fn render_pass_set_pipeline(
+        &self,
+        pass: &mut ObjectId,
+        pass_data: &mut crate::Data,
+        pipeline: &ObjectId,
+        pipeline_data: &crate::Data,
+    );
+    fn render_pass_set_bind_group(
+        &self,
+        pass: &mut ObjectId,
+        pass_data: &mut crate::Data,
+        index: u32,
+        bind_group: &ObjectId,
+        bind_group_data: &crate::Data,
+        offsets: &[DynamicOffset],
+    );
     #[allow(clippy::too_many_arguments)]
     fn render_pass_set_index_buffer(
         &self,
@@ -2251,6 +2738,12 @@ where
         Context::device_downlevel_properties(self, &device, device_data)
     }
 
+    fn device_memory_report(&self, device: &ObjectId, device_data: &crate::Data) -> MemoryReport {
+        let device = <T::DeviceId>::from(*device);
+        let device_data = downcast_ref(device_data);
+        Context::device_memory_report(self, &device, device_data)
+    }
+
     fn device_create_shader_module(
         &self,
         device: &ObjectId,
@@ -2348,6 +2841,19 @@ where
         (compute_pipeline.into(), Box::new(data) as _)
     }
 
+    unsafe fn device_create_pipeline_cache(
+        &self,
+        device: &ObjectId,
+        device_data: &crate::Data,
+        desc: &PipelineCacheDescriptor,
+    ) -> (ObjectId, Box<crate::Data>) {
+        let device = <T::DeviceId>::from(*device);
+        let device_data = downcast_ref(device_data);
+        let (pipeline_cache, data) =
+            unsafe { Context::device_create_pipeline_cache(self, &device, device_data, desc) };
+        (pipeline_cache.into(), Box::new(data) as _)
+    }
+
     fn device_create_buffer(
         &self,
         device: &ObjectId,
@@ -2396,6 +2902,132 @@ where
         (query_set.into(), Box::new(data) as _)
     }
 
+    fn device_create_heap(
+        &self,
+        device: &ObjectId,
+        device_data: &crate::Data,
+        desc: &HeapDescriptor,
+    ) -> (ObjectId, Box<crate::Data>) {
+        let device = <T::DeviceId>::from(*device);
+        let device_data = downcast_ref(device_data);
+        let (heap, data) = Context::device_create_heap(self, &device, device_data, desc);
+        (heap.into(), Box::new(data) as _)
+    }
+
+    fn device_create_external_semaphore(
+        &self,
+        device: &ObjectId,
+        device_data: &crate::Data,
+        desc: &ExternalSemaphoreDescriptor,
+    ) -> (ObjectId, Box<crate::Data>) {
+        let device = <T::DeviceId>::from(*device);
+        let device_data = downcast_ref(device_data);
+        let (external_semaphore, data) =
+            Context::device_create_external_semaphore(self, &device, device_data, desc);
+        (external_semaphore.into(), Box::new(data) as _)
+    }
+
+    fn device_create_buffer_in_heap(
+        &self,
+        device: &ObjectId,
+        device_data: &crate::Data,
+        desc: &BufferDescriptor,
+        heap: &ObjectId,
+        heap_data: &crate::Data,
+        offset: BufferAddress,
+    ) -> (ObjectId, Box<crate::Data>) {
+        let device = <T::DeviceId>::from(*device);
+        let device_data = downcast_ref(device_data);
+        let heap = <T::HeapId>::from(*heap);
+        let heap_data = downcast_ref(heap_data);
+        let (buffer, data) = Context::device_create_buffer_in_heap(
+            self,
+            &device,
+            device_data,
+            desc,
+            &heap,
+            heap_data,
+            offset,
+        );
+        (buffer.into(), Box::new(data) as _)
+    }
+
+    fn device_create_texture_in_heap(
+        &self,
+        device: &ObjectId,
+        device_data: &crate::Data,
+        desc: &TextureDescriptor,
+        heap: &ObjectId,
+        heap_data: &crate::Data,
+        offset: BufferAddress,
+    ) -> (ObjectId, Box<crate::Data>) {
+        let device = <T::DeviceId>::from(*device);
+        let device_data = downcast_ref(device_data);
+        let heap = <T::HeapId>::from(*heap);
+        let heap_data = downcast_ref(heap_data);
+        let (texture, data) = Context::device_create_texture_in_heap(
+            self,
+            &device,
+            device_data,
+            desc,
+            &heap,
+            heap_data,
+            offset,
+        );
+        (texture.into(), Box::new(data) as _)
+    }
+
+    fn device_create_sparse_buffer(
+        &self,
+        device: &ObjectId,
+        device_data: &crate::Data,
+        desc: &BufferDescriptor,
+    ) -> (ObjectId, Box<crate::Data>) {
+        let device = <T::DeviceId>::from(*device);
+        let device_data = downcast_ref(device_data);
+        let (buffer, data) = Context::device_create_sparse_buffer(self, &device, device_data, desc);
+        (buffer.into(), Box::new(data) as _)
+    }
+
+    fn device_create_sparse_texture(
+        &self,
+        device: &ObjectId,
+        device_data: &crate::Data,
+        desc: &TextureDescriptor,
+    ) -> (ObjectId, Box<crate::Data>) {
+        let device = <T::DeviceId>::from(*device);
+        let device_data = downcast_ref(device_data);
+        let (texture, data) =
+            Context::device_create_sparse_texture(self, &device, device_data, desc);
+        (texture.into(), Box::new(data) as _)
+    }
+
+    fn device_create_blas(
+        &self,
+        device: &ObjectId,
+        device_data: &crate::Data,
+        desc: &CreateBlasDescriptor,
+        sizes: wgt::BlasGeometrySizeDescriptors,
+    ) -> (ObjectId, Option<u64>, Box<crate::Data>) {
+        let device = <T::DeviceId>::from(*device);
+        let device_data = downcast_ref(device_data);
+        let (blas, handle, data) =
+            Context::device_create_blas(self, &device, device_data, desc, sizes);
+        (blas.into(), handle, Box::new(data) as _)
+    }
+
+    fn device_create_tlas(
+        &self,
+        device: &ObjectId,
+        device_data: &crate::Data,
+        desc: &CreateTlasDescriptor,
+    ) -> (ObjectId, Box<crate::Data>) {
+        let device = <T::DeviceId>::from(*device);
+        let device_data = downcast_ref(device_data);
+        let (tlas, data) = Context::device_create_tlas(self, &device, device_data, desc);
+        (tlas.into(), Box::new(data) as _)
+    }
+
     fn device_create_command_encoder(
         &self,
         device: &ObjectId,
@@ -2422,6 +3054,19 @@ where
         (render_bundle_encoder.into(), Box::new(data) as _)
     }
 
+    fn device_create_compute_bundle_encoder(
+        &self,
+        device: &ObjectId,
+        device_data: &crate::Data,
+        desc: &ComputeBundleEncoderDescriptor,
+    ) -> (ObjectId, Box<crate::Data>) {
+        let device = <T::DeviceId>::from(*device);
+        let device_data = downcast_ref(device_data);
+        let (compute_bundle_encoder, data) =
+            Context::device_create_compute_bundle_encoder(self, &device, device_data, desc);
+        (compute_bundle_encoder.into(), Box::new(data) as _)
+    }
+
     fn device_drop(&self, device: &ObjectId, device_data: &crate::Data) {
         let device = <T::DeviceId>::from(*device);
         let device_data = downcast_ref(device_data);
@@ -2440,6 +3085,17 @@ where
         Context::device_lose(self, &device, device_data)
     }
 
+    fn device_set_device_lost_callback(
+        &self,
+        device: &ObjectId,
+        device_data: &crate::Data,
+        device_lost_callback: DeviceLostCallback,
+    ) {
+        let device = <T::DeviceId>::from(*device);
+        let device_data = downcast_ref(device_data);
+        Context::device_set_device_lost_callback(self, &device, device_data, device_lost_callback)
+    }
+
     fn device_poll(
         &self,
         device: &ObjectId,
@@ -2483,6 +3139,17 @@ where
         Box::pin(Context::device_pop_error_scope(self, &device, device_data))
     }
 
+    fn shader_get_compilation_info(
+        &self,
+        shader: &ObjectId,
+        shader_data: &crate::Data,
+    ) -> Pin<ShaderCompilationInfoFuture> {
+        let shader = <T::ShaderModuleId>::from(*shader);
+        let shader_data = downcast_ref(shader_data);
+        let future = Context::shader_get_compilation_info(self, &shader, shader_data);
+        Box::pin(future)
+    }
+
     fn buffer_map_async(
         &self,
         buffer: &ObjectId,
@@ -2594,6 +3261,34 @@ where
         Context::query_set_drop(self, &query_set, query_set_data)
     }
 
+    fn heap_drop(&self, heap: &ObjectId, heap_data: &crate::Data) {
+        let heap = <T::HeapId>::from(*heap);
+        let heap_data = downcast_ref(heap_data);
+        Context::heap_drop(self, &heap, heap_data)
+    }
+
+    fn external_semaphore_drop(
+        &self,
+        external_semaphore: &ObjectId,
+        external_semaphore_data: &crate::Data,
+    ) {
+        let external_semaphore = <T::ExternalSemaphoreId>::from(*external_semaphore);
+        let external_semaphore_data = downcast_ref(external_semaphore_data);
+        Context::external_semaphore_drop(self, &external_semaphore, external_semaphore_data)
+    }
+
+    fn blas_drop(&self, blas: &ObjectId, blas_data: &crate::Data) {
+        let blas = <T::BlasId>::from(*blas);
+        let blas_data = downcast_ref(blas_data);
+        Context::blas_drop(self, &blas, blas_data)
+    }
+
+    fn tlas_drop(&self, tlas: &ObjectId, tlas_data: &crate::Data) {
+        let tlas = <T::TlasId>::from(*tlas);
+        let tlas_data = downcast_ref(tlas_data);
+        Context::tlas_drop(self, &tlas, tlas_data)
+    }
+
     fn bind_group_drop(&self, bind_group: &ObjectId, bind_group_data: &crate::Data) {
         let bind_group = <T::BindGroupId>::from(*bind_group);
         let bind_group_data = downcast_ref(bind_group_data);
@@ -2640,6 +3335,12 @@ where
         Context::render_bundle_drop(self, &render_bundle, render_bundle_data)
     }
 
+    fn compute_bundle_drop(&self, compute_bundle: &ObjectId, compute_bundle_data: &crate::Data) {
+        let compute_bundle = <T::ComputeBundleId>::from(*compute_bundle);
+        let compute_bundle_data = downcast_ref(compute_bundle_data);
+        Context::compute_bundle_drop(self, &compute_bundle, compute_bundle_data)
+    }
+
     fn compute_pipeline_drop(&self, pipeline: &ObjectId, pipeline_data: &crate::Data) {
         let pipeline = <T::ComputePipelineId>::from(*pipeline);
         let pipeline_data = downcast_ref(pipeline_data);
@@ -2652,6 +3353,12 @@ where
         Context::render_pipeline_drop(self, &pipeline, pipeline_data)
     }
 
+    fn pipeline_cache_drop(&self, cache: &ObjectId, cache_data: &crate::Data) {
+        let cache = <T::PipelineCacheId>::from(*cache);
+        let cache_data = downcast_ref(cache_data);
+        Context::pipeline_cache_drop(self, &cache, cache_data)
+    }
+
     fn compute_pipeline_get_bind_group_layout(
         &self,
         pipeline: &ObjectId,
@@ -2678,6 +3385,16 @@ where
         (bind_group_layout.into(), Box::new(data) as _)
     }
 
+    fn pipeline_cache_get_data(
+        &self,
+        cache: &ObjectId,
+        cache_data: &crate::Data,
+    ) -> Option<Vec<u8>> {
+        let cache = <T::PipelineCacheId>::from(*cache);
+        let cache_data = downcast_ref(cache_data);
+        Context::pipeline_cache_get_data(self, &cache, cache_data)
+    }
+
     fn command_encoder_copy_buffer_to_buffer(
         &self,
         encoder: &ObjectId,
@@ -2871,6 +3588,24 @@ where
         Context::command_encoder_clear_buffer(self, &encoder, encoder_data, buffer, offset, size)
     }
 
+    fn command_encoder_transition_resources(
+        &self,
+        encoder: &ObjectId,
+        encoder_data: &crate::Data,
+        buffer_transitions: &[BufferTransition],
+        texture_transitions: &[TextureTransition],
+    ) {
+        let encoder = <T::CommandEncoderId>::from(*encoder);
+        let encoder_data = downcast_ref(encoder_data);
+        Context::command_encoder_transition_resources(
+            self,
+            &encoder,
+            encoder_data,
+            buffer_transitions,
+            texture_transitions,
+        )
+    }
+
     fn command_encoder_insert_debug_marker(
         &self,
         encoder: &ObjectId,
@@ -2953,6 +3688,24 @@ where
         )
     }
 
+    fn command_encoder_build_acceleration_structures_unsafe_tlas(
+        &self,
+        encoder: &ObjectId,
+        encoder_data: &crate::Data,
+        blas: &[BlasBuildEntry<'_>],
+        tlas: &[TlasBuildEntry<'_>],
+    ) {
+        let encoder = <T::CommandEncoderId>::from(*encoder);
+        let encoder_data = downcast_ref(encoder_data);
+        Context::command_encoder_build_acceleration_structures_unsafe_tlas(
+            self,
+            &encoder,
+            encoder_data,
+            blas,
+            tlas,
+        )
+    }
+
     fn render_bundle_encoder_finish(
         &self,
         encoder: ObjectId,
@@ -2965,6 +3718,18 @@ where
         (render_bundle.into(), Box::new(data) as _)
     }
 
+    fn compute_bundle_encoder_finish(
+        &self,
+        encoder: ObjectId,
+        encoder_data: Box<crate::Data>,
+        desc: &ComputeBundleDescriptor,
+    ) -> (ObjectId, Box<crate::Data>) {
+        let encoder_data = *encoder_data.downcast().unwrap();
+        let (compute_bundle, data) =
+            Context::compute_bundle_encoder_finish(self, encoder.into(), encoder_data, desc);
+        (compute_bundle.into(), Box::new(data) as _)
+    }
+
     fn queue_write_buffer(
         &self,
         queue: &ObjectId,
@@ -3054,6 +3819,24 @@ where
         Context::queue_write_texture(self, &queue, queue_data, texture, data, data_layout, size)
     }
 
+    fn queue_update_sparse_bindings(
+        &self,
+        queue: &ObjectId,
+        queue_data: &crate::Data,
+        buffer_bindings: &[SparseBufferBinding],
+        texture_bindings: &[SparseTextureBinding],
+    ) {
+        let queue = <T::QueueId>::from(*queue);
+        let queue_data = downcast_ref(queue_data);
+        Context::queue_update_sparse_bindings(
+            self,
+            &queue,
+            queue_data,
+            buffer_bindings,
+            texture_bindings,
+        )
+    }
+
     #[cfg(all(target_arch = "wasm32", not(target_os = "emscripten")))]
     fn queue_copy_external_image_to_texture(
         &self,
@@ -3072,16 +3855,49 @@ where
         &self,
         queue: &ObjectId,
         queue_data: &crate::Data,
-        command_buffers: Box<dyn Iterator<Item = (ObjectId, Box<crate::Data>)> + 'a>,
+        command_buffers: Box<dyn Iterator<Item = (&'a ObjectId, &'a crate::Data)> + 'a>,
+    ) -> (ObjectId, Arc<crate::Data>) {
+        let queue = <T::QueueId>::from(*queue);
+        let queue_data = downcast_ref(queue_data);
+        let command_buffers = command_buffers
+            .map(|(id, data)| {
+                let data: &<T as Context>::CommandBufferData = downcast_ref(data);
+                (<T::CommandBufferId>::from(*id), data)
+            })
+            .collect::<Vec<_>>();
+        let (submission_index, data) = Context::queue_submit(
+            self,
+            &queue,
+            queue_data,
+            command_buffers.iter().map(|(id, data)| (id, *data)),
+        );
+        (submission_index.into(), Arc::new(data) as _)
+    }
+
+    fn queue_submit_with_sync<'a>(
+        &self,
+        queue: &ObjectId,
+        queue_data: &crate::Data,
+        command_buffers: Box<dyn Iterator<Item = (&'a ObjectId, &'a crate::Data)> + 'a>,
+        waits: &[(&ExternalSemaphore, u64)],
+        signals: &[(&ExternalSemaphore, u64)],
     ) -> (ObjectId, Arc<crate::Data>) {
         let queue = <T::QueueId>::from(*queue);
         let queue_data = downcast_ref(queue_data);
-        let command_buffers = command_buffers.into_iter().map(|(id, data)| {
-            let command_buffer_data: <T as Context>::CommandBufferData = *data.downcast().unwrap();
-            (<T::CommandBufferId>::from(id), command_buffer_data)
-        });
-        let (submission_index, data) =
-            Context::queue_submit(self, &queue, queue_data, command_buffers);
+        let command_buffers = command_buffers
+            .map(|(id, data)| {
+                let data: &<T as Context>::CommandBufferData = downcast_ref(data);
+                (<T::CommandBufferId>::from(*id), data)
+            })
+            .collect::<Vec<_>>();
+        let (submission_index, data) = Context::queue_submit_with_sync(
+            self,
+            &queue,
+            queue_data,
+            command_buffers.iter().map(|(id, data)| (id, *data)),
+            waits,
+            signals,
+        );
         (submission_index.into(), Arc::new(data) as _)
     }
 
@@ -3281,6 +4097,21 @@ where
         )
     }
 
+    fn compute_pass_execute_bundles<'a>(
+        &self,
+        pass: &mut ObjectId,
+        pass_data: &mut crate::Data,
+        compute_bundles: Box<dyn Iterator<Item = (&'a ObjectId, &'a crate::Data)> + 'a>,
+    ) {
+        let mut pass = <T::ComputePassId>::from(*pass);
+        let pass_data = downcast_mut::<T::ComputePassData>(pass_data);
+        let compute_bundles = Box::new(compute_bundles.into_iter().map(|(id, data)| {
+            let compute_bundle_data: &<T as Context>::ComputeBundleData = downcast_ref(data);
+            (<T::ComputeBundleId>::from(*id), compute_bundle_data)
+        }));
+        Context::compute_pass_execute_bundles(self, &mut pass, pass_data, compute_bundles)
+    }
+
     fn render_bundle_encoder_set_pipeline(
         &self,
         encoder: &mut ObjectId,
@@ -3585,6 +4416,187 @@ where
         )
     }
 
+    fn render_bundle_encoder_insert_debug_marker(
+        &self,
+        encoder: &mut ObjectId,
+        encoder_data: &mut crate::Data,
+        label: &str,
+    ) {
+        let mut encoder = <T::RenderBundleEncoderId>::from(*encoder);
+        let encoder_data = downcast_mut::<T::RenderBundleEncoderData>(encoder_data);
+        Context::render_bundle_encoder_insert_debug_marker(self, &mut encoder, encoder_data, label)
+    }
+
+    fn render_bundle_encoder_push_debug_group(
+        &self,
+        encoder: &mut ObjectId,
+        encoder_data: &mut crate::Data,
+        group_label: &str,
+    ) {
+        let mut encoder = <T::RenderBundleEncoderId>::from(*encoder);
+        let encoder_data = downcast_mut::<T::RenderBundleEncoderData>(encoder_data);
+        Context::render_bundle_encoder_push_debug_group(
+            self,
+            &mut encoder,
+            encoder_data,
+            group_label,
+        )
+    }
+
+    fn render_bundle_encoder_pop_debug_group(
+        &self,
+        encoder: &mut ObjectId,
+        encoder_data: &mut crate::Data,
+    ) {
+        let mut encoder = <T::RenderBundleEncoderId>::from(*encoder);
+        let encoder_data = downcast_mut::<T::RenderBundleEncoderData>(encoder_data);
+        Context::render_bundle_encoder_pop_debug_group(self, &mut encoder, encoder_data)
+    }
+
+    fn render_bundle_encoder_write_timestamp(
+        &self,
+        encoder: &mut ObjectId,
+        encoder_data: &mut crate::Data,
+        query_set: &ObjectId,
+        query_set_data: &crate::Data,
+        query_index: u32,
+    ) {
+        let mut encoder = <T::RenderBundleEncoderId>::from(*encoder);
+        let encoder_data = downcast_mut::<T::RenderBundleEncoderData>(encoder_data);
+        let query_set = <T::QuerySetId>::from(*query_set);
+        let query_set_data = downcast_ref(query_set_data);
+        Context::render_bundle_encoder_write_timestamp(
+            self,
+            &mut encoder,
+            encoder_data,
+            &query_set,
+            query_set_data,
+            query_index,
+        )
+    }
+
+    fn render_bundle_encoder_begin_pipeline_statistics_query(
+        &self,
+        encoder: &mut ObjectId,
+        encoder_data: &mut crate::Data,
+        query_set: &ObjectId,
+        query_set_data: &crate::Data,
+        query_index: u32,
+    ) {
+        let mut encoder = <T::RenderBundleEncoderId>::from(*encoder);
+        let encoder_data = downcast_mut::<T::RenderBundleEncoderData>(encoder_data);
+        let query_set = <T::QuerySetId>::from(*query_set);
+        let query_set_data = downcast_ref(query_set_data);
+        Context::render_bundle_encoder_begin_pipeline_statistics_query(
+            self,
+            &mut encoder,
+            encoder_data,
+            &query_set,
+            query_set_data,
+            query_index,
+        )
+    }
+
+    fn render_bundle_encoder_end_pipeline_statistics_query(
+        &self,
+        encoder: &mut ObjectId,
+        encoder_data: &mut crate::Data,
+    ) {
+        let mut encoder = <T::RenderBundleEncoderId>::from(*encoder);
+        let encoder_data = downcast_mut::<T::RenderBundleEncoderData>(encoder_data);
+        Context::render_bundle_encoder_end_pipeline_statistics_query(
+            self,
+            &mut encoder,
+            encoder_data,
+        )
+    }
+
+    fn compute_bundle_encoder_set_pipeline(
+        &self,
+        encoder: &mut ObjectId,
+        encoder_data: &mut crate::Data,
+        pipeline: &ObjectId,
+        pipeline_data: &crate::Data,
+    ) {
+        let mut encoder = <T::ComputeBundleEncoderId>::from(*encoder);
+        let encoder_data = downcast_mut::<T::ComputeBundleEncoderData>(encoder_data);
+        let pipeline = <T::ComputePipelineId>::from(*pipeline);
+        let pipeline_data = downcast_ref(pipeline_data);
+        Context::compute_bundle_encoder_set_pipeline(
+            self,
+            &mut encoder,
+            encoder_data,
+            &pipeline,
+            pipeline_data,
+        )
+    }
+
+    fn compute_bundle_encoder_set_bind_group(
+        &self,
+        encoder: &mut ObjectId,
+        encoder_data: &mut crate::Data,
+        index: u32,
+        bind_group: &ObjectId,
+        bind_group_data: &crate::Data,
+        offsets: &[DynamicOffset],
+    ) {
+        let mut encoder = <T::ComputeBundleEncoderId>::from(*encoder);
+        let encoder_data = downcast_mut::<T::ComputeBundleEncoderData>(encoder_data);
+        let bind_group = <T::BindGroupId>::from(*bind_group);
+        let bind_group_data = downcast_ref(bind_group_data);
+        Context::compute_bundle_encoder_set_bind_group(
+            self,
+            &mut encoder,
+            encoder_data,
+            index,
+            &bind_group,
+            bind_group_data,
+            offsets,
+        )
+    }
+
+    fn compute_bundle_encoder_dispatch_workgroups(
+        &self,
+        encoder: &mut ObjectId,
+        encoder_data: &mut crate::Data,
+        x: u32,
+        y: u32,
+        z: u32,
+    ) {
+        let mut encoder = <T::ComputeBundleEncoderId>::from(*encoder);
+        let encoder_data = downcast_mut::<T::ComputeBundleEncoderData>(encoder_data);
+        Context::compute_bundle_encoder_dispatch_workgroups(
+            self,
+            &mut encoder,
+            encoder_data,
+            x,
+            y,
+            z,
+        )
+    }
+
+    fn compute_bundle_encoder_dispatch_workgroups_indirect(
+        &self,
+        encoder: &mut ObjectId,
+        encoder_data: &mut crate::Data,
+        indirect_buffer: &ObjectId,
+        indirect_buffer_data: &crate::Data,
+        indirect_offset: BufferAddress,
+    ) {
+        let mut encoder = <T::ComputeBundleEncoderId>::from(*encoder);
+        let encoder_data = downcast_mut::<T::ComputeBundleEncoderData>(encoder_data);
+        let indirect_buffer = <T::BufferId>::from(*indirect_buffer);
+        let indirect_buffer_data = downcast_ref(indirect_buffer_data);
+        Context::compute_bundle_encoder_dispatch_workgroups_indirect(
+            self,
+            &mut encoder,
+            encoder_data,
+            &indirect_buffer,
+            indirect_buffer_data,
+            indirect_offset,
+        )
+    }
+
     fn render_pass_set_pipeline(
         &self,
         pass: &mut ObjectId,
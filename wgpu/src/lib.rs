@@ -8,6 +8,7 @@
 
 mod backend;
 mod context;
+mod recorded_pass;
 pub mod util;
 #[macro_use]
 mod macros;
@@ -26,25 +27,31 @@ use std::{
 
 use context::{Context, DeviceRequest, DynContext, ObjectId};
 use parking_lot::Mutex;
+pub use recorded_pass::{RecordedComputePass, RecordedRenderPass};
 
 pub use wgt::{
-    AdapterInfo, AddressMode, AstcBlock, AstcChannel, Backend, Backends, BindGroupLayoutEntry,
-    BindingType, BlendComponent, BlendFactor, BlendOperation, BlendState, BufferAddress,
-    BufferBindingType, BufferSize, BufferUsages, Color, ColorTargetState, ColorWrites,
-    CommandBufferDescriptor, CompareFunction, CompositeAlphaMode, DepthBiasState,
-    DepthStencilState, DeviceType, DownlevelCapabilities, DownlevelFlags, Dx12Compiler,
+    AccelerationStructureFlags, AccelerationStructureGeometryFlags,
+    AccelerationStructureUpdateMode, AdapterInfo, AddressMode, AstcBlock, AstcChannel, Backend,
+    Backends, BindGroupLayoutEntry, BindingType, BlasGeometrySizeDescriptors,
+    BlasTriangleGeometrySizeDescriptor, BlendComponent, BlendFactor, BlendOperation, BlendState,
+    BufferAddress, BufferBindingType, BufferSize, BufferUsages, Color, ColorTargetState,
+    ColorWrites, CommandBufferDescriptor, CompareFunction, CompilationInfo, CompilationMessage,
+    CompilationMessageType, CompositeAlphaMode, DepthBiasState, DepthStencilState,
+    DeviceLostReason, DeviceType, DownlevelCapabilities, DownlevelFlags, Dx12Compiler,
     DynamicOffset, Extent3d, Face, Features, FilterMode, FrontFace, Gles3MinorVersion,
     ImageDataLayout, ImageSubresourceRange, IndexFormat, InstanceDescriptor, InstanceFlags, Limits,
-    MultisampleState, Origin2d, Origin3d, PipelineStatisticsTypes, PolygonMode, PowerPreference,
-    PredefinedColorSpace, PresentMode, PresentationTimestamp, PrimitiveState, PrimitiveTopology,
-    PushConstantRange, QueryType, RenderBundleDepthStencil, SamplerBindingType, SamplerBorderColor,
-    ShaderLocation, ShaderModel, ShaderStages, StencilFaceState, StencilOperation, StencilState,
-    StorageTextureAccess, SurfaceCapabilities, SurfaceStatus, TextureAspect, TextureDimension,
-    TextureFormat, TextureFormatFeatureFlags, TextureFormatFeatures, TextureSampleType,
-    TextureUsages, TextureViewDimension, VertexAttribute, VertexFormat, VertexStepMode,
-    WasmNotSend, WasmNotSync, COPY_BUFFER_ALIGNMENT, COPY_BYTES_PER_ROW_ALIGNMENT, MAP_ALIGNMENT,
-    PUSH_CONSTANT_ALIGNMENT, QUERY_RESOLVE_BUFFER_ALIGNMENT, QUERY_SET_MAX_QUERIES, QUERY_SIZE,
-    VERTEX_STRIDE_ALIGNMENT,
+    MemoryHeapReport, MemoryHints, MemoryReport, MemoryTypeReport, MultisampleState, Origin2d,
+    Origin3d, PipelineStatisticsTypes, PolygonMode, PowerPreference, PredefinedColorSpace,
+    PresentMode, PresentationTimestamp, PrimitiveState, PrimitiveTopology, PushConstantRange,
+    QueryType, RenderBundleDepthStencil, ResourceMemoryReport, SamplerBindingType,
+    SamplerBorderColor, ShaderLocation, ShaderModel, ShaderStages, SourceLocation,
+    StencilFaceState, StencilOperation, StencilState, StorageTextureAccess, SurfaceCapabilities,
+    SurfaceStatus, TextureAspect, TextureDimension, TextureFormat, TextureFormatFeatureFlags,
+    TextureFormatFeatures, TextureSampleType, TextureUsages, TextureViewDimension, VertexAttribute,
+    VertexFormat, VertexStepMode, WasmNotSend, WasmNotSync, COPY_BUFFER_ALIGNMENT,
+    COPY_BYTES_PER_ROW_ALIGNMENT, MAP_ALIGNMENT, PUSH_CONSTANT_ALIGNMENT,
+    QUERY_RESOLVE_BUFFER_ALIGNMENT, QUERY_SET_MAX_QUERIES, QUERY_SIZE, SPARSE_PAGE_SIZE,
+    TLAS_INSTANCE_SIZE, VERTEX_STRIDE_ALIGNMENT,
 };
 
 #[cfg(any(
@@ -515,6 +522,18 @@ impl Drop for ShaderModule {
     }
 }
 
+impl ShaderModule {
+    /// Get the compilation info for the shader.
+    ///
+    /// This contains the errors and warnings produced while creating the
+    /// shader module, along with the location in the source they refer to.
+    /// A module that compiled without any diagnostics reports no messages.
+    pub fn get_compilation_info(&self) -> impl Future<Output = CompilationInfo> + WasmNotSend {
+        self.context
+            .shader_get_compilation_info(&self.id, self.data.as_ref())
+    }
+}
+
 /// Source of a shader module.
 ///
 /// The source will be parsed and validated.
@@ -699,6 +718,100 @@ impl ComputePipeline {
     }
 }
 
+/// Handle to a pipeline cache, which is used to accelerate
+/// creating [`RenderPipeline`]s and [`ComputePipeline`]s
+/// in subsequent executions
+///
+/// This reuse is only applicable for the same or similar devices.
+/// See [`util::pipeline_cache_key`] for some details.
+///
+/// # Background
+///
+/// In most GPU drivers, shader code must be converted into a machine code
+/// which can be executed on the GPU.
+/// Generating this machine code can require a lot of computation.
+/// Pipeline caches allow this computation to be reused between executions
+/// of the program.
+/// This can be very useful for reducing program startup time.
+///
+/// Note that most desktop GPU drivers will manage their own caches,
+/// meaning that little advantage can be gained from this on those platforms.
+/// However, on some platforms, especially Android, drivers leave this to the
+/// application to implement.
+///
+/// # Usage
+///
+/// It is valid to use this resource when creating multiple pipelines, in
+/// which case it will likely cache each of those pipelines.
+/// It is also valid to create a new cache for each pipeline.
+///
+/// This resource is most useful when the data produced from it (using
+/// [`PipelineCache::get_data`]) is persisted.
+/// Care should be taken that pipeline caches are only used for the same device,
+/// as pipeline caches from incompatible devices are unlikely to provide any advantage.
+/// `util::pipeline_cache_key` can be used as a file/directory name to help ensure that.
+///
+/// It is recommended to store pipeline caches atomically. If persisting to disk,
+/// this can usually be achieved by creating a temporary file, then moving/[renaming]
+/// the temporary file over the existing cache
+///
+/// [renaming]: std::fs::rename
+///
+/// # Storage Usage
+///
+/// There is not currently an API available to reduce the size of a cache.
+/// This is due to limitations in the underlying graphics APIs used.
+/// This is especially impactful if your application is being updated, so
+/// previous caches are no longer being used.
+///
+/// One option to work around this is to regenerate the cache.
+/// That is, creating the pipelines which your program runs using
+/// with the stored cached data, then recreating the *same* pipelines
+/// using a new cache, which your application then store.
+///
+/// # Implementations
+///
+/// This resource currently only works on the following backends:
+///  - Vulkan
+///
+/// This type is unique to the Rust API of `wgpu`.
+#[derive(Debug)]
+pub struct PipelineCache {
+    context: Arc<C>,
+    id: ObjectId,
+    data: Box<Data>,
+}
+#[cfg(any(
+    not(target_arch = "wasm32"),
+    all(
+        feature = "fragile-send-sync-non-atomic-wasm",
+        not(target_feature = "atomics")
+    )
+))]
+static_assertions::assert_impl_all!(PipelineCache: Send, Sync);
+
+impl PipelineCache {
+    /// Get the data associated with this pipeline cache.
+    /// The data format is an implementation detail of `wgpu`.
+    /// The only defined operation on this data setting it as the `data` field
+    /// on [`PipelineCacheDescriptor`], then to [`Device::create_pipeline_cache`].
+    ///
+    /// This function is unique to the Rust API of `wgpu`.
+    pub fn get_data(&self) -> Option<Vec<u8>> {
+        self.context
+            .pipeline_cache_get_data(&self.id, self.data.as_ref())
+    }
+}
+
+impl Drop for PipelineCache {
+    fn drop(&mut self) {
+        if !thread::panicking() {
+            self.context
+                .pipeline_cache_drop(&self.id, self.data.as_ref());
+        }
+    }
+}
+
 /// Handle to a command buffer on the GPU.
 ///
 /// A `CommandBuffer` represents a complete sequence of commands that may be submitted to a command
@@ -711,6 +824,7 @@ pub struct CommandBuffer {
     context: Arc<C>,
     id: Option<ObjectId>,
     data: Option<Box<Data>>,
+    reusable: bool,
 }
 #[cfg(any(
     not(target_arch = "wasm32"),
@@ -746,6 +860,7 @@ pub struct CommandEncoder {
     context: Arc<C>,
     id: Option<ObjectId>,
     data: Box<Data>,
+    reusable: bool,
 }
 #[cfg(any(
     not(target_arch = "wasm32"),
@@ -860,6 +975,56 @@ impl Drop for RenderBundle {
     }
 }
 
+/// Encodes a series of dispatches into a reusable "compute bundle".
+///
+/// Only pipeline changes, bind group changes and dispatches can be recorded.
+/// It can be created with [`Device::create_compute_bundle_encoder`].
+/// It can be executed in a compute pass using [`ComputePass::execute_bundles`].
+///
+/// Compute bundles have no WebGPU equivalent, and are not available on the web.
+#[derive(Debug)]
+pub struct ComputeBundleEncoder<'a> {
+    context: Arc<C>,
+    id: ObjectId,
+    data: Box<Data>,
+    parent: &'a Device,
+    /// This type should be !Send !Sync, because it represents an allocation on this thread's
+    /// command buffer.
+    _p: PhantomData<*const u8>,
+}
+static_assertions::assert_not_impl_any!(ComputeBundleEncoder<'_>: Send, Sync);
+
+/// Pre-prepared reusable bundle of dispatches.
+///
+/// The commands are validated when the bundle is finished, which makes executing a
+/// [`ComputeBundle`] cheaper than issuing the underlying commands manually.
+///
+/// It can be created by use of a [`ComputeBundleEncoder`], and executed in a compute pass
+/// using [`ComputePass::execute_bundles`].
+#[derive(Debug)]
+pub struct ComputeBundle {
+    context: Arc<C>,
+    id: ObjectId,
+    data: Box<Data>,
+}
+#[cfg(any(
+    not(target_arch = "wasm32"),
+    all(
+        feature = "fragile-send-sync-non-atomic-wasm",
+        not(target_feature = "atomics")
+    )
+))]
+static_assertions::assert_impl_all!(ComputeBundle: Send, Sync);
+
+impl Drop for ComputeBundle {
+    fn drop(&mut self) {
+        if !thread::panicking() {
+            self.context
+                .compute_bundle_drop(&self.id, self.data.as_ref());
+        }
+    }
+}
+
 /// Handle to a query set.
 ///
 /// It can be created with [`Device::create_query_set`].
@@ -895,6 +1060,169 @@ impl Drop for QuerySet {
     }
 }
 
+/// Handle to a resource heap.
+///
+/// A `Heap` is a block of device memory that buffers and textures can be
+/// placed into at explicit offsets with [`Device::create_buffer_in_heap`] and
+/// [`Device::create_texture_in_heap`]. Resources placed in overlapping ranges
+/// alias the same memory; only the most recently used one holds valid
+/// contents. It can be created with [`Device::create_heap`].
+#[derive(Debug)]
+pub struct Heap {
+    context: Arc<C>,
+    id: ObjectId,
+    data: Box<Data>,
+}
+#[cfg(any(
+    not(target_arch = "wasm32"),
+    all(
+        feature = "fragile-send-sync-non-atomic-wasm",
+        not(target_feature = "atomics")
+    )
+))]
+static_assertions::assert_impl_all!(Heap: Send, Sync);
+
+impl Drop for Heap {
+    fn drop(&mut self) {
+        if !thread::panicking() {
+            self.context.heap_drop(&self.id, self.data.as_ref());
+        }
+    }
+}
+
+/// Handle to an external timeline semaphore.
+///
+/// An `ExternalSemaphore` synchronizes queue submissions with work outside of
+/// wgpu, such as another API sharing the same device. Submissions made with
+/// [`Queue::submit_with_sync`] can wait for it to reach a value before they
+/// start and signal a value once they complete. It can be created with
+/// [`Device::create_external_semaphore`], or imported from a native handle
+/// with [`Device::create_external_semaphore_from_hal`].
+#[derive(Debug)]
+pub struct ExternalSemaphore {
+    context: Arc<C>,
+    id: ObjectId,
+    data: Box<Data>,
+}
+#[cfg(any(
+    not(target_arch = "wasm32"),
+    all(
+        feature = "fragile-send-sync-non-atomic-wasm",
+        not(target_feature = "atomics")
+    )
+))]
+static_assertions::assert_impl_all!(ExternalSemaphore: Send, Sync);
+
+impl Drop for ExternalSemaphore {
+    fn drop(&mut self) {
+        if !thread::panicking() {
+            self.context
+                .external_semaphore_drop(&self.id, self.data.as_ref());
+        }
+    }
+}
+
+impl ExternalSemaphore {
+    /// Returns the inner hal ExternalSemaphore using a callback. The hal semaphore will be `None`
+    /// if the backend type argument does not match with this wgpu ExternalSemaphore
+    ///
+    /// This is how the semaphore is exported to another API, for example with
+    /// `wgpu_hal::vulkan::Device::export_semaphore_fd`.
+    ///
+    /// # Safety
+    ///
+    /// - The raw handle obtained from the hal ExternalSemaphore must not be manually destroyed
+    #[cfg(any(
+        not(target_arch = "wasm32"),
+        target_os = "emscripten",
+        feature = "webgl"
+    ))]
+    pub unsafe fn as_hal<
+        A: wgc::hal_api::HalApi,
+        F: FnOnce(Option<&A::ExternalSemaphore>) -> R,
+        R,
+    >(
+        &self,
+        hal_external_semaphore_callback: F,
+    ) -> R {
+        let id = self.id.into();
+        unsafe {
+            self.context
+                .as_any()
+                .downcast_ref::<crate::backend::Context>()
+                .unwrap()
+                .external_semaphore_as_hal::<A, F, R>(&id, hal_external_semaphore_callback)
+        }
+    }
+}
+
+/// Handle to a bottom level acceleration structure.
+///
+/// A `Blas` holds triangle geometry, and is referenced by the instances of a
+/// [`Tlas`]. It can be created with [`Device::create_blas`] and is built with
+/// [`CommandEncoder::build_acceleration_structures_unsafe_tlas`].
+#[derive(Debug)]
+pub struct Blas {
+    context: Arc<C>,
+    id: ObjectId,
+    data: Box<Data>,
+    handle: Option<u64>,
+}
+#[cfg(any(
+    not(target_arch = "wasm32"),
+    all(
+        feature = "fragile-send-sync-non-atomic-wasm",
+        not(target_feature = "atomics")
+    )
+))]
+static_assertions::assert_impl_all!(Blas: Send, Sync);
+
+impl Blas {
+    /// The value an instance in a [`Tlas`] instance buffer uses to reference
+    /// this acceleration structure.
+    ///
+    /// Returns `None` if the acceleration structure is invalid.
+    pub fn handle(&self) -> Option<u64> {
+        self.handle
+    }
+}
+
+impl Drop for Blas {
+    fn drop(&mut self) {
+        if !thread::panicking() {
+            self.context.blas_drop(&self.id, self.data.as_ref());
+        }
+    }
+}
+
+/// Handle to a top level acceleration structure.
+///
+/// A `Tlas` holds instances of [`Blas`]es, and can be bound to shaders using
+/// ray queries with [`BindingResource::AccelerationStructure`]. It can be
+/// created with [`Device::create_tlas`].
+#[derive(Debug)]
+pub struct Tlas {
+    context: Arc<C>,
+    id: ObjectId,
+    data: Box<Data>,
+}
+#[cfg(any(
+    not(target_arch = "wasm32"),
+    all(
+        feature = "fragile-send-sync-non-atomic-wasm",
+        not(target_feature = "atomics")
+    )
+))]
+static_assertions::assert_impl_all!(Tlas: Send, Sync);
+
+impl Drop for Tlas {
+    fn drop(&mut self) {
+        if !thread::panicking() {
+            self.context.tlas_drop(&self.id, self.data.as_ref());
+        }
+    }
+}
+
 /// Handle to a command queue on a device.
 ///
 /// A `Queue` executes recorded [`CommandBuffer`] objects and provides convenience methods
@@ -959,6 +1287,12 @@ pub enum BindingResource<'a> {
     /// Corresponds to [`wgt::BindingType::Texture`] and [`wgt::BindingType::StorageTexture`] with
     /// [`BindGroupLayoutEntry::count`] set to Some.
     TextureViewArray(&'a [&'a TextureView]),
+    /// Binding is a top level acceleration structure.
+    ///
+    /// [`Features::RAY_QUERY`] must be supported to use this feature.
+    ///
+    /// Corresponds to [`wgt::BindingType::AccelerationStructure`].
+    AccelerationStructure(&'a Tlas),
 }
 #[cfg(any(
     not(target_arch = "wasm32"),
@@ -1212,6 +1546,11 @@ static_assertions::assert_impl_all!(CommandEncoderDescriptor: Send, Sync);
 /// https://gpuweb.github.io/gpuweb/#dictdef-gpurenderbundledescriptor).
 pub type RenderBundleDescriptor<'a> = wgt::RenderBundleDescriptor<Label<'a>>;
 static_assertions::assert_impl_all!(RenderBundleDescriptor: Send, Sync);
+/// Describes a [`ComputeBundle`].
+///
+/// For use with [`ComputeBundleEncoder::finish`].
+pub type ComputeBundleDescriptor<'a> = wgt::ComputeBundleDescriptor<Label<'a>>;
+static_assertions::assert_impl_all!(ComputeBundleDescriptor: Send, Sync);
 /// Describes a [`Texture`].
 ///
 /// For use with [`Device::create_texture`].
@@ -1228,6 +1567,97 @@ static_assertions::assert_impl_all!(TextureDescriptor: Send, Sync);
 /// https://gpuweb.github.io/gpuweb/#dictdef-gpuquerysetdescriptor).
 pub type QuerySetDescriptor<'a> = wgt::QuerySetDescriptor<Label<'a>>;
 static_assertions::assert_impl_all!(QuerySetDescriptor: Send, Sync);
+/// Describes a [`Heap`].
+///
+/// For use with [`Device::create_heap`].
+pub type HeapDescriptor<'a> = wgt::HeapDescriptor<Label<'a>>;
+static_assertions::assert_impl_all!(HeapDescriptor: Send, Sync);
+/// Describes an [`ExternalSemaphore`].
+///
+/// For use with [`Device::create_external_semaphore`].
+pub type ExternalSemaphoreDescriptor<'a> = wgt::ExternalSemaphoreDescriptor<Label<'a>>;
+static_assertions::assert_impl_all!(ExternalSemaphoreDescriptor: Send, Sync);
+/// Describes a [`Blas`].
+///
+/// For use with [`Device::create_blas`].
+pub type CreateBlasDescriptor<'a> = wgt::CreateBlasDescriptor<Label<'a>>;
+static_assertions::assert_impl_all!(CreateBlasDescriptor: Send, Sync);
+/// Describes a [`Tlas`].
+///
+/// For use with [`Device::create_tlas`].
+pub type CreateTlasDescriptor<'a> = wgt::CreateTlasDescriptor<Label<'a>>;
+static_assertions::assert_impl_all!(CreateTlasDescriptor: Send, Sync);
+
+/// Triangle geometry used to build a [`Blas`].
+///
+/// `size` has to stay within the bounds of the corresponding geometry the
+/// [`Blas`] was created with.
+#[derive(Clone, Debug)]
+pub struct BlasTriangleGeometry<'a> {
+    /// Formats and counts of the vertices and indices.
+    pub size: &'a BlasTriangleGeometrySizeDescriptor,
+    /// Buffer containing the vertices, with [`BufferUsages::BLAS_INPUT`].
+    pub vertex_buffer: &'a Buffer,
+    /// Offset in the vertex buffer, as a number of vertices.
+    pub first_vertex: u32,
+    /// Distance in bytes between the starts of consecutive vertices.
+    pub vertex_stride: BufferAddress,
+    /// Buffer containing the indices, with [`BufferUsages::BLAS_INPUT`].
+    ///
+    /// Must be set if and only if `size` has an index format.
+    pub index_buffer: Option<&'a Buffer>,
+    /// Byte offset in the index buffer, a multiple of the index size.
+    pub index_buffer_offset: Option<BufferAddress>,
+    /// Buffer containing a 3x4 row major transformation matrix of `f32`s,
+    /// with [`BufferUsages::BLAS_INPUT`].
+    pub transform_buffer: Option<&'a Buffer>,
+    /// Byte offset in the transform buffer, a multiple of 16.
+    pub transform_buffer_offset: Option<BufferAddress>,
+}
+#[cfg(any(
+    not(target_arch = "wasm32"),
+    all(
+        feature = "fragile-send-sync-non-atomic-wasm",
+        not(target_feature = "atomics")
+    )
+))]
+static_assertions::assert_impl_all!(BlasTriangleGeometry: Send, Sync);
+
+/// Geometries used to build a [`Blas`], one for each geometry it was created
+/// with.
+#[derive(Clone, Debug)]
+pub enum BlasGeometries<'a> {
+    /// Triangle geometries.
+    TriangleGeometries(Vec<BlasTriangleGeometry<'a>>),
+}
+
+/// Build of a [`Blas`].
+///
+/// For use with [`CommandEncoder::build_acceleration_structures_unsafe_tlas`].
+#[derive(Clone, Debug)]
+pub struct BlasBuildEntry<'a> {
+    /// The acceleration structure to build.
+    pub blas: &'a Blas,
+    /// The geometries to build it from.
+    pub geometry: BlasGeometries<'a>,
+}
+
+/// Build of a [`Tlas`].
+///
+/// For use with [`CommandEncoder::build_acceleration_structures_unsafe_tlas`].
+#[derive(Clone, Debug)]
+pub struct TlasBuildEntry<'a> {
+    /// The acceleration structure to build.
+    pub tlas: &'a Tlas,
+    /// Buffer containing the instances, with [`BufferUsages::TLAS_INPUT`].
+    ///
+    /// Every instance takes [`TLAS_INSTANCE_SIZE`] bytes, see
+    /// [`util::TlasInstance`] for the layout.
+    pub instance_buffer: &'a Buffer,
+    /// Number of instances, at most the `max_instances` the [`Tlas`] was
+    /// created with.
+    pub instance_count: u32,
+}
 pub use wgt::Maintain as MaintainBase;
 /// Passed to [`Device::poll`] to control how and if it should block.
 pub type Maintain = wgt::Maintain<SubmissionIndex>;
@@ -1527,6 +1957,8 @@ pub struct RenderPipelineDescriptor<'a> {
     /// If the pipeline will be used with a multiview render pass, this indicates how many array
     /// layers the attachments will have.
     pub multiview: Option<NonZeroU32>,
+    /// The pipeline cache to use when creating this pipeline.
+    pub cache: Option<&'a PipelineCache>,
 }
 #[cfg(any(
     not(target_arch = "wasm32"),
@@ -1603,6 +2035,8 @@ pub struct ComputePipelineDescriptor<'a> {
     /// The name of the entry point in the compiled shader. There must be a function with this name
     /// and no return value in the shader.
     pub entry_point: &'a str,
+    /// The pipeline cache to use when creating this pipeline.
+    pub cache: Option<&'a PipelineCache>,
 }
 #[cfg(any(
     not(target_arch = "wasm32"),
@@ -1613,6 +2047,36 @@ pub struct ComputePipelineDescriptor<'a> {
 ))]
 static_assertions::assert_impl_all!(ComputePipelineDescriptor: Send, Sync);
 
+/// Describes a pipeline cache, which allows reusing compilation work
+/// between program runs.
+///
+/// For use with [`Device::create_pipeline_cache`]
+///
+/// This type is unique to the Rust API of `wgpu`.
+#[derive(Clone, Debug)]
+pub struct PipelineCacheDescriptor<'a> {
+    /// Debug label of the pipeline cache. This will show up in graphics debuggers for easy identification.
+    pub label: Label<'a>,
+    /// The data used to initialize the cache.
+    ///
+    /// If not `None`, this data must have been returned by a previous call to
+    /// [`PipelineCache::get_data`].
+    pub data: Option<&'a [u8]>,
+    /// Whether to create an empty cache instead of failing when `data` can't be used,
+    /// for example because it was created on a different adapter or driver version.
+    ///
+    /// Setting this to `true` is recommended.
+    pub fallback: bool,
+}
+#[cfg(any(
+    not(target_arch = "wasm32"),
+    all(
+        feature = "fragile-send-sync-non-atomic-wasm",
+        not(target_feature = "atomics")
+    )
+))]
+static_assertions::assert_impl_all!(PipelineCacheDescriptor: Send, Sync);
+
 pub use wgt::ImageCopyBuffer as ImageCopyBufferBase;
 /// View of a buffer which can be used to copy to/from a texture.
 ///
@@ -1628,12 +2092,10 @@ pub type ImageCopyBuffer<'a> = ImageCopyBufferBase<&'a Buffer>;
 ))]
 static_assertions::assert_impl_all!(ImageCopyBuffer: Send, Sync);
 
-pub use wgt::ImageCopyTexture as ImageCopyTextureBase;
-/// View of a texture which can be used to copy to/from a buffer/texture.
-///
-/// Corresponds to [WebGPU `GPUImageCopyTexture`](
-/// https://gpuweb.github.io/gpuweb/#dictdef-gpuimagecopytexture).
-pub type ImageCopyTexture<'a> = ImageCopyTextureBase<&'a Texture>;
+pub use wgt::BufferTransition as BufferTransitionBase;
+/// Hint that a buffer is about to be used in a particular way, for use with
+/// [`CommandEncoder::transition_resources`].
+pub type BufferTransition<'a> = BufferTransitionBase<&'a Buffer>;
 #[cfg(any(
     not(target_arch = "wasm32"),
     all(
@@ -1641,15 +2103,12 @@ pub type ImageCopyTexture<'a> = ImageCopyTextureBase<&'a Texture>;
         not(target_feature = "atomics")
     )
 ))]
-static_assertions::assert_impl_all!(ImageCopyTexture: Send, Sync);
+static_assertions::assert_impl_all!(BufferTransition: Send, Sync);
 
-pub use wgt::ImageCopyTextureTagged as ImageCopyTextureTaggedBase;
-/// View of a texture which can be used to copy to a texture, including
-/// color space and alpha premultiplication information.
-///
-/// Corresponds to [WebGPU `GPUImageCopyTextureTagged`](
-/// https://gpuweb.github.io/gpuweb/#dictdef-gpuimagecopytexturetagged).
-pub type ImageCopyTextureTagged<'a> = ImageCopyTextureTaggedBase<&'a Texture>;
+pub use wgt::TextureTransition as TextureTransitionBase;
+/// Hint that texture subresources are about to be used in a particular way, for use with
+/// [`CommandEncoder::transition_resources`].
+pub type TextureTransition<'a> = TextureTransitionBase<&'a Texture>;
 #[cfg(any(
     not(target_arch = "wasm32"),
     all(
@@ -1657,15 +2116,85 @@ pub type ImageCopyTextureTagged<'a> = ImageCopyTextureTaggedBase<&'a Texture>;
         not(target_feature = "atomics")
     )
 ))]
-static_assertions::assert_impl_all!(ImageCopyTexture: Send, Sync);
+static_assertions::assert_impl_all!(TextureTransition: Send, Sync);
 
-/// Describes a [`BindGroupLayout`].
-///
-/// For use with [`Device::create_bind_group_layout`].
-///
-/// Corresponds to [WebGPU `GPUBindGroupLayoutDescriptor`](
-/// https://gpuweb.github.io/gpuweb/#dictdef-gpubindgrouplayoutdescriptor).
-#[derive(Clone, Debug)]
+pub use wgt::SparseMemory as SparseMemoryBase;
+/// Memory of a [`Heap`] bound to pages of a sparse buffer or texture, for use with
+/// [`Queue::update_sparse_bindings`].
+pub type SparseMemory<'a> = SparseMemoryBase<&'a Heap>;
+#[cfg(any(
+    not(target_arch = "wasm32"),
+    all(
+        feature = "fragile-send-sync-non-atomic-wasm",
+        not(target_feature = "atomics")
+    )
+))]
+static_assertions::assert_impl_all!(SparseMemory: Send, Sync);
+
+pub use wgt::SparseBufferBinding as SparseBufferBindingBase;
+/// Binds memory to, or unbinds it from, a range of a sparse buffer, for use with
+/// [`Queue::update_sparse_bindings`].
+pub type SparseBufferBinding<'a> = SparseBufferBindingBase<&'a Buffer, &'a Heap>;
+#[cfg(any(
+    not(target_arch = "wasm32"),
+    all(
+        feature = "fragile-send-sync-non-atomic-wasm",
+        not(target_feature = "atomics")
+    )
+))]
+static_assertions::assert_impl_all!(SparseBufferBinding: Send, Sync);
+
+pub use wgt::SparseTextureBinding as SparseTextureBindingBase;
+/// Binds memory to, or unbinds it from, a region of a sparse texture, for use with
+/// [`Queue::update_sparse_bindings`].
+pub type SparseTextureBinding<'a> = SparseTextureBindingBase<&'a Texture, &'a Heap>;
+#[cfg(any(
+    not(target_arch = "wasm32"),
+    all(
+        feature = "fragile-send-sync-non-atomic-wasm",
+        not(target_feature = "atomics")
+    )
+))]
+static_assertions::assert_impl_all!(SparseTextureBinding: Send, Sync);
+
+pub use wgt::ImageCopyTexture as ImageCopyTextureBase;
+/// View of a texture which can be used to copy to/from a buffer/texture.
+///
+/// Corresponds to [WebGPU `GPUImageCopyTexture`](
+/// https://gpuweb.github.io/gpuweb/#dictdef-gpuimagecopytexture).
+pub type ImageCopyTexture<'a> = ImageCopyTextureBase<&'a Texture>;
+#[cfg(any(
+    not(target_arch = "wasm32"),
+    all(
+        feature = "fragile-send-sync-non-atomic-wasm",
+        not(target_feature = "atomics")
+    )
+))]
+static_assertions::assert_impl_all!(ImageCopyTexture: Send, Sync);
+
+pub use wgt::ImageCopyTextureTagged as ImageCopyTextureTaggedBase;
+/// View of a texture which can be used to copy to a texture, including
+/// color space and alpha premultiplication information.
+///
+/// Corresponds to [WebGPU `GPUImageCopyTextureTagged`](
+/// https://gpuweb.github.io/gpuweb/#dictdef-gpuimagecopytexturetagged).
+pub type ImageCopyTextureTagged<'a> = ImageCopyTextureTaggedBase<&'a Texture>;
+#[cfg(any(
+    not(target_arch = "wasm32"),
+    all(
+        feature = "fragile-send-sync-non-atomic-wasm",
+        not(target_feature = "atomics")
+    )
+))]
+static_assertions::assert_impl_all!(ImageCopyTexture: Send, Sync);
+
+/// Describes a [`BindGroupLayout`].
+///
+/// For use with [`Device::create_bind_group_layout`].
+///
+/// Corresponds to [WebGPU `GPUBindGroupLayoutDescriptor`](
+/// https://gpuweb.github.io/gpuweb/#dictdef-gpubindgrouplayoutdescriptor).
+#[derive(Clone, Debug)]
 pub struct BindGroupLayoutDescriptor<'a> {
     /// Debug label of the bind group layout. This will show up in graphics debuggers for easy identification.
     pub label: Label<'a>,
@@ -1699,6 +2228,16 @@ pub struct RenderBundleEncoderDescriptor<'a> {
 }
 static_assertions::assert_impl_all!(RenderBundleEncoderDescriptor: Send, Sync);
 
+/// Describes a [`ComputeBundleEncoder`].
+///
+/// For use with [`Device::create_compute_bundle_encoder`].
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct ComputeBundleEncoderDescriptor<'a> {
+    /// Debug label of the compute bundle encoder. This will show up in graphics debuggers for easy identification.
+    pub label: Label<'a>,
+}
+static_assertions::assert_impl_all!(ComputeBundleEncoderDescriptor: Send, Sync);
+
 /// Surface texture that can be rendered to.
 /// Result of a successful call to [`Surface::get_current_texture`].
 ///
@@ -2168,7 +2707,9 @@ impl Adapter {
     ///
     /// - `desc` - Description of the features and limits requested from the given device.
     /// - `trace_path` - Can be used for API call tracing, if that feature is
-    ///   enabled in `wgpu-core`.
+    ///   enabled in `wgpu-core`. A path ending in `.wgputrace` records a binary
+    ///   trace file (`.wgputrace.gz` to compress it), and any other path is a
+    ///   directory to record a RON trace into.
     ///
     /// # Panics
     ///
@@ -2396,6 +2937,15 @@ impl Device {
         DynContext::device_limits(&*self.context, &self.id, self.data.as_ref())
     }
 
+    /// Reports how much GPU memory this device has allocated and how it is used.
+    ///
+    /// Per heap and memory type statistics are only available on Vulkan. Heap budgets
+    /// additionally require `VK_EXT_memory_budget`. On other backends the report only
+    /// contains the buffer and texture totals, and on WebGPU it is empty.
+    pub fn memory_report(&self) -> MemoryReport {
+        DynContext::device_memory_report(&*self.context, &self.id, self.data.as_ref())
+    }
+
     /// Creates a shader module from either SPIR-V or WGSL source code.
     pub fn create_shader_module(&self, desc: ShaderModuleDescriptor) -> ShaderModule {
         let (id, data) = DynContext::device_create_shader_module(
@@ -2479,6 +3029,7 @@ impl Device {
             context: Arc::clone(&self.context),
             id: Some(id),
             data,
+            reusable: desc.reusable,
         }
     }
 
@@ -2502,6 +3053,26 @@ impl Device {
         }
     }
 
+    /// Creates an empty [`ComputeBundleEncoder`].
+    pub fn create_compute_bundle_encoder(
+        &self,
+        desc: &ComputeBundleEncoderDescriptor,
+    ) -> ComputeBundleEncoder {
+        let (id, data) = DynContext::device_create_compute_bundle_encoder(
+            &*self.context,
+            &self.id,
+            self.data.as_ref(),
+            desc,
+        );
+        ComputeBundleEncoder {
+            context: Arc::clone(&self.context),
+            id,
+            data,
+            parent: self,
+            _p: Default::default(),
+        }
+    }
+
     /// Creates a new [`BindGroup`].
     pub fn create_bind_group(&self, desc: &BindGroupDescriptor) -> BindGroup {
         let (id, data) = DynContext::device_create_bind_group(
@@ -2577,6 +3148,40 @@ impl Device {
         }
     }
 
+    /// Creates a [`PipelineCache`], optionally seeded with data from a previous run.
+    ///
+    /// The cache can be passed to [`Device::create_compute_pipeline`] and
+    /// [`Device::create_render_pipeline`], which will both use and fill it.
+    ///
+    /// Requires [`Features::PIPELINE_CACHE`].
+    ///
+    /// # Safety
+    ///
+    /// If the `data` field of `desc` is set, it must have previously been returned from a call
+    /// to [`PipelineCache::get_data`]. The data is checked against the adapter and driver it
+    /// was produced on and discarded (or rejected, if `fallback` is false) on mismatch, but its
+    /// contents are otherwise handed to the driver as-is.
+    ///
+    /// Data produced by direct use of the backend APIs is not supported.
+    pub unsafe fn create_pipeline_cache(
+        &self,
+        desc: &PipelineCacheDescriptor<'_>,
+    ) -> PipelineCache {
+        let (id, data) = unsafe {
+            DynContext::device_create_pipeline_cache(
+                &*self.context,
+                &self.id,
+                self.data.as_ref(),
+                desc,
+            )
+        };
+        PipelineCache {
+            context: Arc::clone(&self.context),
+            id,
+            data,
+        }
+    }
+
     /// Creates a [`Buffer`].
     pub fn create_buffer(&self, desc: &BufferDescriptor) -> Buffer {
         let mut map_context = MapContext::new(desc.size);
@@ -2657,6 +3262,46 @@ impl Device {
         }
     }
 
+    /// Creates an [`ExternalSemaphore`] from a wgpu-hal ExternalSemaphore.
+    ///
+    /// This is how a semaphore shared by another API is imported, for example
+    /// with `wgpu_hal::vulkan::Device::import_semaphore_fd`.
+    ///
+    /// [`Features::EXTERNAL_SEMAPHORES`] must be enabled.
+    ///
+    /// # Safety
+    ///
+    /// - `hal_semaphore` must be created from this device internal handle
+    /// - `hal_semaphore` must be a timeline semaphore
+    #[cfg(any(
+        not(target_arch = "wasm32"),
+        target_os = "emscripten",
+        feature = "webgl"
+    ))]
+    pub unsafe fn create_external_semaphore_from_hal<A: wgc::hal_api::HalApi>(
+        &self,
+        hal_semaphore: A::ExternalSemaphore,
+        desc: &ExternalSemaphoreDescriptor,
+    ) -> ExternalSemaphore {
+        let id = unsafe {
+            self.context
+                .as_any()
+                .downcast_ref::<crate::backend::Context>()
+                .unwrap()
+                .create_external_semaphore_from_hal::<A>(
+                    hal_semaphore,
+                    self.data.as_ref().downcast_ref().unwrap(),
+                    desc,
+                )
+        };
+
+        ExternalSemaphore {
+            context: Arc::clone(&self.context),
+            id: ObjectId::from(id),
+            data: Box::new(()),
+        }
+    }
+
     /// Creates a [`Buffer`] from a wgpu-hal Buffer.
     ///
     /// # Safety
@@ -2725,53 +3370,244 @@ impl Device {
         }
     }
 
-    /// Set a callback for errors that are not handled in error scopes.
-    pub fn on_uncaptured_error(&self, handler: Box<dyn UncapturedErrorHandler>) {
-        self.context
-            .device_on_uncaptured_error(&self.id, self.data.as_ref(), handler);
+    /// Creates a new [`Heap`].
+    ///
+    /// [`Features::RESOURCE_HEAPS`] must be enabled.
+    pub fn create_heap(&self, desc: &HeapDescriptor) -> Heap {
+        let (id, data) =
+            DynContext::device_create_heap(&*self.context, &self.id, self.data.as_ref(), desc);
+        Heap {
+            context: Arc::clone(&self.context),
+            id,
+            data,
+        }
     }
 
-    /// Push an error scope.
-    pub fn push_error_scope(&self, filter: ErrorFilter) {
-        self.context
-            .device_push_error_scope(&self.id, self.data.as_ref(), filter);
+    /// Creates a new [`ExternalSemaphore`].
+    ///
+    /// The semaphore starts at a value of zero.
+    ///
+    /// [`Features::EXTERNAL_SEMAPHORES`] must be enabled.
+    pub fn create_external_semaphore(
+        &self,
+        desc: &ExternalSemaphoreDescriptor,
+    ) -> ExternalSemaphore {
+        let (id, data) = DynContext::device_create_external_semaphore(
+            &*self.context,
+            &self.id,
+            self.data.as_ref(),
+            desc,
+        );
+        ExternalSemaphore {
+            context: Arc::clone(&self.context),
+            id,
+            data,
+        }
     }
 
-    /// Pop an error scope.
-    pub fn pop_error_scope(&self) -> impl Future<Output = Option<Error>> + WasmNotSend {
-        self.context
-            .device_pop_error_scope(&self.id, self.data.as_ref())
-    }
+    /// Creates a new [`Buffer`] placed in `heap` at `offset`.
+    ///
+    /// The buffer may not be mappable. Its contents are undefined after
+    /// another resource overlapping the same range of the heap is used.
+    ///
+    /// [`Features::RESOURCE_HEAPS`] must be enabled.
+    pub fn create_buffer_in_heap(
+        &self,
+        desc: &BufferDescriptor,
+        heap: &Heap,
+        offset: BufferAddress,
+    ) -> Buffer {
+        let (id, data) = DynContext::device_create_buffer_in_heap(
+            &*self.context,
+            &self.id,
+            self.data.as_ref(),
+            desc,
+            &heap.id,
+            heap.data.as_ref(),
+            offset,
+        );
 
-    /// Starts frame capture.
-    pub fn start_capture(&self) {
-        DynContext::device_start_capture(&*self.context, &self.id, self.data.as_ref())
+        Buffer {
+            context: Arc::clone(&self.context),
+            id,
+            data,
+            map_context: Mutex::new(MapContext::new(desc.size)),
+            size: desc.size,
+            usage: desc.usage,
+        }
     }
 
-    /// Stops frame capture.
-    pub fn stop_capture(&self) {
-        DynContext::device_stop_capture(&*self.context, &self.id, self.data.as_ref())
+    /// Creates a new [`Texture`] placed in `heap` at `offset`.
+    ///
+    /// Its contents are undefined after another resource overlapping the same
+    /// range of the heap is used.
+    ///
+    /// [`Features::RESOURCE_HEAPS`] must be enabled.
+    pub fn create_texture_in_heap(
+        &self,
+        desc: &TextureDescriptor,
+        heap: &Heap,
+        offset: BufferAddress,
+    ) -> Texture {
+        let (id, data) = DynContext::device_create_texture_in_heap(
+            &*self.context,
+            &self.id,
+            self.data.as_ref(),
+            desc,
+            &heap.id,
+            heap.data.as_ref(),
+            offset,
+        );
+        Texture {
+            context: Arc::clone(&self.context),
+            id,
+            data,
+            owned: true,
+            descriptor: TextureDescriptor {
+                label: None,
+                view_formats: &[],
+                ..desc.clone()
+            },
+        }
     }
 
-    /// Apply a callback to this `Device`'s underlying backend device.
+    /// Creates a new sparse [`Buffer`], without any memory.
     ///
-    /// If this `Device` is implemented by the backend API given by `A` (Vulkan,
-    /// Dx12, etc.), then apply `hal_device_callback` to `Some(&device)`, where
-    /// `device` is the underlying backend device type, [`A::Device`].
+    /// Memory is bound to its pages with [`Queue::update_sparse_bindings`]. Without
+    /// [`Features::SPARSE_RESIDENCY`], all of its pages must be bound when it is used.
     ///
-    /// If this `Device` uses a different backend, apply `hal_device_callback`
-    /// to `None`.
+    /// [`Features::SPARSE_BINDING`] must be enabled.
+    pub fn create_sparse_buffer(&self, desc: &BufferDescriptor) -> Buffer {
+        let (id, data) = DynContext::device_create_sparse_buffer(
+            &*self.context,
+            &self.id,
+            self.data.as_ref(),
+            desc,
+        );
+
+        Buffer {
+            context: Arc::clone(&self.context),
+            id,
+            data,
+            map_context: Mutex::new(MapContext::new(desc.size)),
+            size: desc.size,
+            usage: desc.usage,
+        }
+    }
+
+    /// Creates a new sparse [`Texture`], without any memory.
     ///
-    /// The device is locked for reading while `hal_device_callback` runs. If
-    /// the callback attempts to perform any `wgpu` operations that require
-    /// write access to the device (destroying a buffer, say), deadlock will
-    /// occur. The locks are automatically released when the callback returns.
+    /// Memory is bound to its tiles with [`Queue::update_sparse_bindings`]. Reading
+    /// tiles without memory returns zero, and writes to them are discarded.
     ///
-    /// # Safety
+    /// The texture must be 2D and single-sampled, and the size of all of its mip levels
+    /// must be a multiple of [`TextureFormat::sparse_tile_extent`].
     ///
-    /// - The raw handle passed to the callback must not be manually destroyed.
+    /// [`Features::SPARSE_RESIDENCY`] must be enabled.
+    pub fn create_sparse_texture(&self, desc: &TextureDescriptor) -> Texture {
+        let (id, data) = DynContext::device_create_sparse_texture(
+            &*self.context,
+            &self.id,
+            self.data.as_ref(),
+            desc,
+        );
+        Texture {
+            context: Arc::clone(&self.context),
+            id,
+            data,
+            owned: true,
+            descriptor: TextureDescriptor {
+                label: None,
+                view_formats: &[],
+                ..desc.clone()
+            },
+        }
+    }
+
+    /// Creates a new [`Blas`] able to hold the geometries described by
+    /// `sizes`.
     ///
-    /// [`A::Device`]: hal::Api::Device
+    /// [`Features::RAY_TRACING_ACCELERATION_STRUCTURE`] must be enabled.
+    pub fn create_blas(
+        &self,
+        desc: &CreateBlasDescriptor,
+        sizes: BlasGeometrySizeDescriptors,
+    ) -> Blas {
+        let (id, handle, data) = DynContext::device_create_blas(
+            &*self.context,
+            &self.id,
+            self.data.as_ref(),
+            desc,
+            sizes,
+        );
+        Blas {
+            context: Arc::clone(&self.context),
+            id,
+            data,
+            handle,
+        }
+    }
+
+    /// Creates a new [`Tlas`].
+    ///
+    /// [`Features::RAY_TRACING_ACCELERATION_STRUCTURE`] must be enabled.
+    pub fn create_tlas(&self, desc: &CreateTlasDescriptor) -> Tlas {
+        let (id, data) =
+            DynContext::device_create_tlas(&*self.context, &self.id, self.data.as_ref(), desc);
+        Tlas {
+            context: Arc::clone(&self.context),
+            id,
+            data,
+        }
+    }
+
+    /// Set a callback for errors that are not handled in error scopes.
+    pub fn on_uncaptured_error(&self, handler: Box<dyn UncapturedErrorHandler>) {
+        self.context
+            .device_on_uncaptured_error(&self.id, self.data.as_ref(), handler);
+    }
+
+    /// Push an error scope.
+    pub fn push_error_scope(&self, filter: ErrorFilter) {
+        self.context
+            .device_push_error_scope(&self.id, self.data.as_ref(), filter);
+    }
+
+    /// Pop an error scope.
+    pub fn pop_error_scope(&self) -> impl Future<Output = Option<Error>> + WasmNotSend {
+        self.context
+            .device_pop_error_scope(&self.id, self.data.as_ref())
+    }
+
+    /// Starts frame capture.
+    pub fn start_capture(&self) {
+        DynContext::device_start_capture(&*self.context, &self.id, self.data.as_ref())
+    }
+
+    /// Stops frame capture.
+    pub fn stop_capture(&self) {
+        DynContext::device_stop_capture(&*self.context, &self.id, self.data.as_ref())
+    }
+
+    /// Apply a callback to this `Device`'s underlying backend device.
+    ///
+    /// If this `Device` is implemented by the backend API given by `A` (Vulkan,
+    /// Dx12, etc.), then apply `hal_device_callback` to `Some(&device)`, where
+    /// `device` is the underlying backend device type, [`A::Device`].
+    ///
+    /// If this `Device` uses a different backend, apply `hal_device_callback`
+    /// to `None`.
+    ///
+    /// The device is locked for reading while `hal_device_callback` runs. If
+    /// the callback attempts to perform any `wgpu` operations that require
+    /// write access to the device (destroying a buffer, say), deadlock will
+    /// occur. The locks are automatically released when the callback returns.
+    ///
+    /// # Safety
+    ///
+    /// - The raw handle passed to the callback must not be manually destroyed.
+    ///
+    /// [`A::Device`]: hal::Api::Device
     #[cfg(any(
         not(target_arch = "wasm32"),
         target_os = "emscripten",
@@ -2797,6 +3633,28 @@ impl Device {
     pub fn destroy(&self) {
         DynContext::device_destroy(&*self.context, &self.id, self.data.as_ref())
     }
+
+    /// Set a callback to be invoked when the device is lost.
+    ///
+    /// The callback is called at most once, with the reason the device was lost
+    /// and a message describing it. This happens when the device is destroyed,
+    /// when the backend reports the device as lost while polling, or when the
+    /// device is dropped. If the device has already been lost, the callback is
+    /// invoked right away.
+    ///
+    /// Setting a new callback replaces the previous one, which will then never
+    /// be called.
+    pub fn set_device_lost_callback(
+        &self,
+        callback: impl FnOnce(DeviceLostReason, String) + Send + 'static,
+    ) {
+        DynContext::device_set_device_lost_callback(
+            &*self.context,
+            &self.id,
+            self.data.as_ref(),
+            Box::new(callback),
+        )
+    }
 }
 
 impl Drop for Device {
@@ -3382,6 +4240,7 @@ impl CommandEncoder {
             context: Arc::clone(&self.context),
             id: Some(id),
             data: Some(data),
+            reusable: self.reusable,
         }
     }
 
@@ -3555,6 +4414,32 @@ impl CommandEncoder {
         );
     }
 
+    /// Moves resources into the usages they are going to be used with next.
+    ///
+    /// wgpu places the barriers a command needs right before that command. Transitioning
+    /// resources ahead of time instead lets the backend overlap the transitions, such as
+    /// Vulkan image layout changes, with unrelated work recorded in between. This is only
+    /// a hint, later commands still transition resources as they need to.
+    ///
+    /// # Panics
+    ///
+    /// - A usage was not specified when the resource was created.
+    /// - A usage that can be written to is combined with any other usage.
+    /// - A texture subresource range is out of bounds.
+    pub fn transition_resources(
+        &mut self,
+        buffer_transitions: &[BufferTransition<'_>],
+        texture_transitions: &[TextureTransition<'_>],
+    ) {
+        DynContext::command_encoder_transition_resources(
+            &*self.context,
+            self.id.as_ref().unwrap(),
+            self.data.as_ref(),
+            buffer_transitions,
+            texture_transitions,
+        );
+    }
+
     /// Inserts debug marker.
     pub fn insert_debug_marker(&mut self, label: &str) {
         let id = self.id.as_ref().unwrap();
@@ -3627,6 +4512,35 @@ impl CommandEncoder {
     }
 }
 
+/// [`Features::RAY_TRACING_ACCELERATION_STRUCTURE`] must be enabled on the device in order to call these functions.
+impl CommandEncoder {
+    /// Build bottom level acceleration structures, then top level ones.
+    ///
+    /// A [`Tlas`] has to be built before being used in a bind group, by
+    /// this command encoder or by one submitted earlier.
+    ///
+    /// # Safety of the instance buffers
+    ///
+    /// The contents of the instance buffers are not validated. Every
+    /// instance has to reference, by its [`Blas::handle`], a [`Blas`] that is
+    /// kept alive and built when the commands execute. Otherwise ray
+    /// queries against the [`Tlas`] may return wrong results, or cause the
+    /// device to be lost.
+    pub fn build_acceleration_structures_unsafe_tlas(
+        &mut self,
+        blas: &[BlasBuildEntry<'_>],
+        tlas: &[TlasBuildEntry<'_>],
+    ) {
+        DynContext::command_encoder_build_acceleration_structures_unsafe_tlas(
+            &*self.context,
+            self.id.as_ref().unwrap(),
+            self.data.as_ref(),
+            blas,
+            tlas,
+        )
+    }
+}
+
 impl<'a> RenderPass<'a> {
     /// Sets the active bind group for a given bind group index. The bind group layout
     /// in the active pipeline when any `draw_*()` method is called must match the layout of
@@ -4321,6 +5235,28 @@ impl<'a> ComputePass<'a> {
             indirect_offset,
         );
     }
+
+    /// Execute a [compute bundle][ComputeBundle], which is a set of pre-recorded dispatches
+    /// that can be run together.
+    ///
+    /// Dispatches in the bundle do not inherit this compute pass's current state, and after the
+    /// bundle has executed, the pipeline, bind groups and push constants are **cleared** (reset
+    /// to defaults, not the previous state).
+    pub fn execute_bundles<I: IntoIterator<Item = &'a ComputeBundle> + 'a>(
+        &mut self,
+        compute_bundles: I,
+    ) {
+        DynContext::compute_pass_execute_bundles(
+            &*self.parent.context,
+            &mut self.id,
+            self.data.as_mut(),
+            Box::new(
+                compute_bundles
+                    .into_iter()
+                    .map(|cb| (&cb.id, cb.data.as_ref())),
+            ),
+        )
+    }
 }
 
 /// [`Features::PUSH_CONSTANTS`] must be enabled on the device in order to call these functions.
@@ -4404,6 +5340,85 @@ impl<'a> Drop for ComputePass<'a> {
     }
 }
 
+impl<'a> ComputeBundleEncoder<'a> {
+    /// Finishes recording and returns a [`ComputeBundle`] that can be executed in compute passes.
+    pub fn finish(self, desc: &ComputeBundleDescriptor) -> ComputeBundle {
+        let (id, data) =
+            DynContext::compute_bundle_encoder_finish(&*self.context, self.id, self.data, desc);
+        ComputeBundle {
+            context: Arc::clone(&self.context),
+            id,
+            data,
+        }
+    }
+
+    /// Sets the active bind group for a given bind group index. The bind group layout
+    /// in the active pipeline when a `dispatch()` function is called must match the layout of this bind group.
+    ///
+    /// If the bind group have dynamic offsets, provide them in the binding order.
+    pub fn set_bind_group(
+        &mut self,
+        index: u32,
+        bind_group: &'a BindGroup,
+        offsets: &[DynamicOffset],
+    ) {
+        DynContext::compute_bundle_encoder_set_bind_group(
+            &*self.parent.context,
+            &mut self.id,
+            self.data.as_mut(),
+            index,
+            &bind_group.id,
+            bind_group.data.as_ref(),
+            offsets,
+        )
+    }
+
+    /// Sets the active compute pipeline.
+    ///
+    /// Push constants used by the pipeline are initialized to zero.
+    pub fn set_pipeline(&mut self, pipeline: &'a ComputePipeline) {
+        DynContext::compute_bundle_encoder_set_pipeline(
+            &*self.parent.context,
+            &mut self.id,
+            self.data.as_mut(),
+            &pipeline.id,
+            pipeline.data.as_ref(),
+        )
+    }
+
+    /// Dispatches compute work operations.
+    ///
+    /// `x`, `y` and `z` denote the number of work groups to dispatch in each dimension.
+    pub fn dispatch_workgroups(&mut self, x: u32, y: u32, z: u32) {
+        DynContext::compute_bundle_encoder_dispatch_workgroups(
+            &*self.parent.context,
+            &mut self.id,
+            self.data.as_mut(),
+            x,
+            y,
+            z,
+        )
+    }
+
+    /// Dispatches compute work operations, based on the contents of the `indirect_buffer`.
+    ///
+    /// The structure expected in `indirect_buffer` must conform to [`DispatchIndirect`](crate::util::DispatchIndirect).
+    pub fn dispatch_workgroups_indirect(
+        &mut self,
+        indirect_buffer: &'a Buffer,
+        indirect_offset: BufferAddress,
+    ) {
+        DynContext::compute_bundle_encoder_dispatch_workgroups_indirect(
+            &*self.parent.context,
+            &mut self.id,
+            self.data.as_mut(),
+            &indirect_buffer.id,
+            indirect_buffer.data.as_ref(),
+            indirect_offset,
+        )
+    }
+}
+
 impl<'a> RenderBundleEncoder<'a> {
     /// Finishes recording and returns a [`RenderBundle`] that can be executed in other render passes.
     pub fn finish(self, desc: &RenderBundleDescriptor) -> RenderBundle {
@@ -4587,6 +5602,37 @@ impl<'a> RenderBundleEncoder<'a> {
             indirect_offset,
         );
     }
+
+    /// Inserts debug marker.
+    pub fn insert_debug_marker(&mut self, label: &str) {
+        DynContext::render_bundle_encoder_insert_debug_marker(
+            &*self.parent.context,
+            &mut self.id,
+            self.data.as_mut(),
+            label,
+        );
+    }
+
+    /// Start record commands and group it into debug marker group.
+    ///
+    /// Debug groups must be balanced within the bundle.
+    pub fn push_debug_group(&mut self, label: &str) {
+        DynContext::render_bundle_encoder_push_debug_group(
+            &*self.parent.context,
+            &mut self.id,
+            self.data.as_mut(),
+            label,
+        );
+    }
+
+    /// Stops command recording and creates debug group.
+    pub fn pop_debug_group(&mut self) {
+        DynContext::render_bundle_encoder_pop_debug_group(
+            &*self.parent.context,
+            &mut self.id,
+            self.data.as_mut(),
+        );
+    }
 }
 
 /// [`Features::PUSH_CONSTANTS`] must be enabled on the device in order to call these functions.
@@ -4631,6 +5677,166 @@ impl<'a> RenderBundleEncoder<'a> {
     }
 }
 
+/// [`Features::MULTI_DRAW_INDIRECT`] must be enabled on the device in order to call these functions.
+impl<'a> RenderBundleEncoder<'a> {
+    /// Dispatches multiple draw calls from the active vertex buffer(s) based on the contents of the `indirect_buffer`.
+    /// `count` draw calls are issued.
+    ///
+    /// The active vertex buffers can be set with [`RenderBundleEncoder::set_vertex_buffer`].
+    ///
+    /// The structure expected in `indirect_buffer` must conform to [`DrawIndirect`](crate::util::DrawIndirect).
+    /// These draw structures are expected to be tightly packed.
+    pub fn multi_draw_indirect(
+        &mut self,
+        indirect_buffer: &'a Buffer,
+        indirect_offset: BufferAddress,
+        count: u32,
+    ) {
+        DynContext::render_bundle_encoder_multi_draw_indirect(
+            &*self.parent.context,
+            &mut self.id,
+            self.data.as_mut(),
+            &indirect_buffer.id,
+            indirect_buffer.data.as_ref(),
+            indirect_offset,
+            count,
+        );
+    }
+
+    /// Dispatches multiple draw calls from the active index buffer and the active vertex buffers,
+    /// based on the contents of the `indirect_buffer`. `count` draw calls are issued.
+    ///
+    /// The active index buffer can be set with [`RenderBundleEncoder::set_index_buffer`], while the active
+    /// vertex buffers can be set with [`RenderBundleEncoder::set_vertex_buffer`].
+    ///
+    /// The structure expected in `indirect_buffer` must conform to [`DrawIndexedIndirect`](crate::util::DrawIndexedIndirect).
+    /// These draw structures are expected to be tightly packed.
+    pub fn multi_draw_indexed_indirect(
+        &mut self,
+        indirect_buffer: &'a Buffer,
+        indirect_offset: BufferAddress,
+        count: u32,
+    ) {
+        DynContext::render_bundle_encoder_multi_draw_indexed_indirect(
+            &*self.parent.context,
+            &mut self.id,
+            self.data.as_mut(),
+            &indirect_buffer.id,
+            indirect_buffer.data.as_ref(),
+            indirect_offset,
+            count,
+        );
+    }
+}
+
+/// [`Features::MULTI_DRAW_INDIRECT_COUNT`] must be enabled on the device in order to call these functions.
+impl<'a> RenderBundleEncoder<'a> {
+    /// Dispatches multiple draw calls from the active vertex buffer(s) based on the contents of the `indirect_buffer`.
+    /// The count buffer is read to determine how many draws to issue.
+    ///
+    /// The indirect buffer must be long enough to account for `max_count` draws, however only `count`
+    /// draws will be read. If `count` is greater than `max_count`, `max_count` will be used.
+    ///
+    /// See [`RenderPass::multi_draw_indirect_count`] for the layout of `count_buffer`.
+    pub fn multi_draw_indirect_count(
+        &mut self,
+        indirect_buffer: &'a Buffer,
+        indirect_offset: BufferAddress,
+        count_buffer: &'a Buffer,
+        count_offset: BufferAddress,
+        max_count: u32,
+    ) {
+        DynContext::render_bundle_encoder_multi_draw_indirect_count(
+            &*self.parent.context,
+            &mut self.id,
+            self.data.as_mut(),
+            &indirect_buffer.id,
+            indirect_buffer.data.as_ref(),
+            indirect_offset,
+            &count_buffer.id,
+            count_buffer.data.as_ref(),
+            count_offset,
+            max_count,
+        );
+    }
+
+    /// Dispatches multiple draw calls from the active index buffer and the active vertex buffers,
+    /// based on the contents of the `indirect_buffer`. The count buffer is read to determine how many draws to issue.
+    ///
+    /// The indirect buffer must be long enough to account for `max_count` draws, however only `count`
+    /// draws will be read. If `count` is greater than `max_count`, `max_count` will be used.
+    ///
+    /// See [`RenderPass::multi_draw_indexed_indirect_count`] for the layout of `count_buffer`.
+    pub fn multi_draw_indexed_indirect_count(
+        &mut self,
+        indirect_buffer: &'a Buffer,
+        indirect_offset: BufferAddress,
+        count_buffer: &'a Buffer,
+        count_offset: BufferAddress,
+        max_count: u32,
+    ) {
+        DynContext::render_bundle_encoder_multi_draw_indexed_indirect_count(
+            &*self.parent.context,
+            &mut self.id,
+            self.data.as_mut(),
+            &indirect_buffer.id,
+            indirect_buffer.data.as_ref(),
+            indirect_offset,
+            &count_buffer.id,
+            count_buffer.data.as_ref(),
+            count_offset,
+            max_count,
+        );
+    }
+}
+
+/// [`Features::TIMESTAMP_QUERY_INSIDE_PASSES`] must be enabled on the device in order to call these functions.
+impl<'a> RenderBundleEncoder<'a> {
+    /// Issue a timestamp command at this point in the bundle. The
+    /// timestamp will be written to the specified query set, at the specified index,
+    /// each time the bundle is executed.
+    ///
+    /// A query may only be written once per render pass, so a bundle writing
+    /// timestamps can only be executed once in any given pass.
+    pub fn write_timestamp(&mut self, query_set: &QuerySet, query_index: u32) {
+        DynContext::render_bundle_encoder_write_timestamp(
+            &*self.parent.context,
+            &mut self.id,
+            self.data.as_mut(),
+            &query_set.id,
+            query_set.data.as_ref(),
+            query_index,
+        )
+    }
+}
+
+/// [`Features::PIPELINE_STATISTICS_QUERY`] must be enabled on the device in order to call these functions.
+impl<'a> RenderBundleEncoder<'a> {
+    /// Start a pipeline statistics query in this bundle. It must be ended with
+    /// `end_pipeline_statistics_query` before the bundle is finished, and no other
+    /// statistics query may be active in the render pass executing the bundle.
+    pub fn begin_pipeline_statistics_query(&mut self, query_set: &QuerySet, query_index: u32) {
+        DynContext::render_bundle_encoder_begin_pipeline_statistics_query(
+            &*self.parent.context,
+            &mut self.id,
+            self.data.as_mut(),
+            &query_set.id,
+            query_set.data.as_ref(),
+            query_index,
+        );
+    }
+
+    /// End the pipeline statistics query in this bundle. It can be started with
+    /// `begin_pipeline_statistics_query`. Pipeline statistics queries may not be nested.
+    pub fn end_pipeline_statistics_query(&mut self) {
+        DynContext::render_bundle_encoder_end_pipeline_statistics_query(
+            &*self.parent.context,
+            &mut self.id,
+            self.data.as_mut(),
+        );
+    }
+}
+
 /// A read-only view into a staging buffer.
 ///
 /// Reading into this buffer won't yield the contents of the buffer from the
@@ -4782,6 +5988,28 @@ impl Queue {
         )
     }
 
+    /// Binds memory of heaps to pages of sparse buffers and tiles of sparse textures, or
+    /// unbinds it.
+    ///
+    /// The bindings change after all the work submitted so far, including the writes
+    /// queued with [`Queue::write_buffer`] and [`Queue::write_texture`], and before any
+    /// work submitted later. Newly bound memory reads as zero.
+    ///
+    /// [`Features::SPARSE_BINDING`] must be enabled.
+    pub fn update_sparse_bindings(
+        &self,
+        buffer_bindings: &[SparseBufferBinding<'_>],
+        texture_bindings: &[SparseTextureBinding<'_>],
+    ) {
+        DynContext::queue_update_sparse_bindings(
+            &*self.context,
+            &self.id,
+            self.data.as_ref(),
+            buffer_bindings,
+            texture_bindings,
+        )
+    }
+
     /// Schedule a copy of data from `image` into `texture`.
     #[cfg(all(target_arch = "wasm32", not(target_os = "emscripten")))]
     pub fn copy_external_image_to_texture(
@@ -4801,19 +6029,91 @@ impl Queue {
     }
 
     /// Submits a series of finished command buffers for execution.
+    ///
+    /// Command buffers that are not reusable are consumed by the submission.
     pub fn submit<I: IntoIterator<Item = CommandBuffer>>(
         &self,
         command_buffers: I,
+    ) -> SubmissionIndex {
+        let mut command_buffers = command_buffers.into_iter().collect::<Vec<_>>();
+        let index = self.submit_raw(command_buffers.iter());
+        for comb in command_buffers.iter_mut().filter(|comb| !comb.reusable) {
+            // Ownership passed to the queue, nothing is left to drop.
+            comb.id.take();
+            comb.data.take();
+        }
+        index
+    }
+
+    /// Submits a series of reusable command buffers for execution, keeping
+    /// them around so they can be submitted again.
+    ///
+    /// # Panics
+    ///
+    /// - A command buffer was not created with [`CommandEncoderDescriptor::reusable`] set.
+    pub fn submit_reusable<'a, I: IntoIterator<Item = &'a CommandBuffer>>(
+        &self,
+        command_buffers: I,
+    ) -> SubmissionIndex {
+        self.submit_raw(command_buffers.into_iter().inspect(|comb| {
+            assert!(
+                comb.reusable,
+                "Command buffer passed to `submit_reusable` is not reusable"
+            )
+        }))
+    }
+
+    /// Submits a series of finished command buffers for execution, synchronized
+    /// with external timeline semaphores.
+    ///
+    /// Execution doesn't start before each semaphore in `waits` has reached its
+    /// value, and each semaphore in `signals` is set to its value once the
+    /// command buffers complete. Command buffers that are not reusable are
+    /// consumed by the submission, as with [`Queue::submit`].
+    ///
+    /// [`Features::EXTERNAL_SEMAPHORES`] must be enabled.
+    pub fn submit_with_sync<I: IntoIterator<Item = CommandBuffer>>(
+        &self,
+        command_buffers: I,
+        waits: &[(&ExternalSemaphore, u64)],
+        signals: &[(&ExternalSemaphore, u64)],
+    ) -> SubmissionIndex {
+        let mut command_buffers = command_buffers.into_iter().collect::<Vec<_>>();
+        let (raw, data) = DynContext::queue_submit_with_sync(
+            &*self.context,
+            &self.id,
+            self.data.as_ref(),
+            Box::new(command_buffers.iter().map(|comb| {
+                (
+                    comb.id.as_ref().unwrap(),
+                    comb.data.as_ref().unwrap().as_ref(),
+                )
+            })),
+            waits,
+            signals,
+        );
+        for comb in command_buffers.iter_mut().filter(|comb| !comb.reusable) {
+            // Ownership passed to the queue, nothing is left to drop.
+            comb.id.take();
+            comb.data.take();
+        }
+        SubmissionIndex(raw, data)
+    }
+
+    fn submit_raw<'a, I: Iterator<Item = &'a CommandBuffer>>(
+        &self,
+        command_buffers: I,
     ) -> SubmissionIndex {
         let (raw, data) = DynContext::queue_submit(
             &*self.context,
             &self.id,
             self.data.as_ref(),
-            Box::new(
-                command_buffers
-                    .into_iter()
-                    .map(|mut comb| (comb.id.take().unwrap(), comb.data.take().unwrap())),
-            ),
+            Box::new(command_buffers.map(|comb| {
+                (
+                    comb.id.as_ref().unwrap(),
+                    comb.data.as_ref().unwrap().as_ref(),
+                )
+            })),
         );
 
         SubmissionIndex(raw, data)
@@ -5216,6 +6516,32 @@ impl QuerySet {
     }
 }
 
+#[cfg(feature = "expose-ids")]
+impl Heap {
+    /// Returns a globally-unique identifier for this `Heap`.
+    ///
+    /// Calling this method multiple times on the same object will always return the same value.
+    /// The returned value is guaranteed to be unique among all `Heap`s created from the same
+    /// `Instance`.
+    #[cfg_attr(docsrs, doc(cfg(feature = "expose-ids")))]
+    pub fn global_id(&self) -> Id<Heap> {
+        Id(self.id.global_id(), std::marker::PhantomData)
+    }
+}
+
+#[cfg(feature = "expose-ids")]
+impl ExternalSemaphore {
+    /// Returns a globally-unique identifier for this `ExternalSemaphore`.
+    ///
+    /// Calling this method multiple times on the same object will always return the same value.
+    /// The returned value is guaranteed to be unique among all `ExternalSemaphore`s created from
+    /// the same `Instance`.
+    #[cfg_attr(docsrs, doc(cfg(feature = "expose-ids")))]
+    pub fn global_id(&self) -> Id<ExternalSemaphore> {
+        Id(self.id.global_id(), std::marker::PhantomData)
+    }
+}
+
 #[cfg(feature = "expose-ids")]
 impl PipelineLayout {
     /// Returns a globally-unique identifier for this `PipelineLayout`.
@@ -5255,6 +6581,19 @@ impl ComputePipeline {
     }
 }
 
+#[cfg(feature = "expose-ids")]
+impl PipelineCache {
+    /// Returns a globally-unique identifier for this `PipelineCache`.
+    ///
+    /// Calling this method multiple times on the same object will always return the same value.
+    /// The returned value is guaranteed to be unique among all `PipelineCache`s created from the same
+    /// `Instance`.
+    #[cfg_attr(docsrs, doc(cfg(feature = "expose-ids")))]
+    pub fn global_id(&self) -> Id<PipelineCache> {
+        Id(self.id.global_id(), std::marker::PhantomData)
+    }
+}
+
 #[cfg(feature = "expose-ids")]
 impl RenderBundle {
     /// Returns a globally-unique identifier for this `RenderBundle`.
@@ -5268,6 +6607,19 @@ impl RenderBundle {
     }
 }
 
+#[cfg(feature = "expose-ids")]
+impl ComputeBundle {
+    /// Returns a globally-unique identifier for this `ComputeBundle`.
+    ///
+    /// Calling this method multiple times on the same object will always return the same value.
+    /// The returned value is guaranteed to be unique among all `ComputeBundle`s created from the same
+    /// `Instance`.
+    #[cfg_attr(docsrs, doc(cfg(feature = "expose-ids")))]
+    pub fn global_id(&self) -> Id<ComputeBundle> {
+        Id(self.id.global_id(), std::marker::PhantomData)
+    }
+}
+
 #[cfg(feature = "expose-ids")]
 impl Surface {
     /// Returns a globally-unique identifier for this `Surface`.
@@ -0,0 +1,756 @@
+use std::{
+    ops::{Range, RangeBounds},
+    sync::Arc,
+};
+
+use wgt::{BufferAddress, BufferSize, Color, DynamicOffset, IndexFormat, ShaderStages};
+
+use crate::{
+    range_to_offset_size, BindGroup, Buffer, BufferSlice, CommandEncoder, ComputeBundle,
+    ComputePass, ComputePassDescriptor, ComputePipeline, QuerySet, RenderBundle, RenderPass,
+    RenderPassDescriptor, RenderPipeline,
+};
+
+#[derive(Debug)]
+enum RenderCommand {
+    SetBindGroup {
+        index: u32,
+        bind_group: Arc<BindGroup>,
+        offsets: Vec<DynamicOffset>,
+    },
+    SetPipeline(Arc<RenderPipeline>),
+    SetBlendConstant(Color),
+    SetIndexBuffer {
+        buffer: Arc<Buffer>,
+        index_format: IndexFormat,
+        offset: BufferAddress,
+        size: Option<BufferSize>,
+    },
+    SetVertexBuffer {
+        slot: u32,
+        buffer: Arc<Buffer>,
+        offset: BufferAddress,
+        size: Option<BufferSize>,
+    },
+    SetScissorRect {
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+    },
+    SetViewport {
+        x: f32,
+        y: f32,
+        w: f32,
+        h: f32,
+        min_depth: f32,
+        max_depth: f32,
+    },
+    SetStencilReference(u32),
+    SetPushConstants {
+        stages: ShaderStages,
+        offset: u32,
+        data: Vec<u8>,
+    },
+    Draw {
+        vertices: Range<u32>,
+        instances: Range<u32>,
+    },
+    DrawIndexed {
+        indices: Range<u32>,
+        base_vertex: i32,
+        instances: Range<u32>,
+    },
+    DrawIndirect {
+        buffer: Arc<Buffer>,
+        offset: BufferAddress,
+    },
+    DrawIndexedIndirect {
+        buffer: Arc<Buffer>,
+        offset: BufferAddress,
+    },
+    MultiDrawIndirect {
+        buffer: Arc<Buffer>,
+        offset: BufferAddress,
+        count: u32,
+    },
+    MultiDrawIndexedIndirect {
+        buffer: Arc<Buffer>,
+        offset: BufferAddress,
+        count: u32,
+    },
+    MultiDrawIndirectCount {
+        buffer: Arc<Buffer>,
+        offset: BufferAddress,
+        count_buffer: Arc<Buffer>,
+        count_offset: BufferAddress,
+        max_count: u32,
+    },
+    MultiDrawIndexedIndirectCount {
+        buffer: Arc<Buffer>,
+        offset: BufferAddress,
+        count_buffer: Arc<Buffer>,
+        count_offset: BufferAddress,
+        max_count: u32,
+    },
+    ExecuteBundles(Vec<Arc<RenderBundle>>),
+    InsertDebugMarker(String),
+    PushDebugGroup(String),
+    PopDebugGroup,
+    WriteTimestamp {
+        query_set: Arc<QuerySet>,
+        query_index: u32,
+    },
+    BeginOcclusionQuery(u32),
+    EndOcclusionQuery,
+    BeginPipelineStatisticsQuery {
+        query_set: Arc<QuerySet>,
+        query_index: u32,
+    },
+    EndPipelineStatisticsQuery,
+}
+
+/// Render pass commands recorded ahead of time, independently of any [`CommandEncoder`].
+///
+/// Unlike [`RenderPass`], a recorded pass owns shared handles to the resources it uses
+/// instead of borrowing them, so it can be built on any thread and kept around for later.
+/// It is replayed with [`CommandEncoder::replay_render_pass`], at which point every command
+/// is validated exactly as if it had been recorded into a [`RenderPass`] directly.
+///
+/// In contrast to a [`RenderBundle`], every render pass command is supported, and no
+/// validation or state tracking happens until the pass is replayed.
+#[derive(Debug, Default)]
+pub struct RecordedRenderPass {
+    commands: Vec<RenderCommand>,
+}
+#[cfg(any(
+    not(target_arch = "wasm32"),
+    all(
+        feature = "fragile-send-sync-non-atomic-wasm",
+        not(target_feature = "atomics")
+    )
+))]
+static_assertions::assert_impl_all!(RecordedRenderPass: Send, Sync);
+
+impl RecordedRenderPass {
+    /// Creates an empty recorded render pass.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns `true` if no commands have been recorded.
+    pub fn is_empty(&self) -> bool {
+        self.commands.is_empty()
+    }
+
+    /// Records [`RenderPass::set_bind_group`].
+    pub fn set_bind_group(
+        &mut self,
+        index: u32,
+        bind_group: &Arc<BindGroup>,
+        offsets: &[DynamicOffset],
+    ) {
+        self.commands.push(RenderCommand::SetBindGroup {
+            index,
+            bind_group: Arc::clone(bind_group),
+            offsets: offsets.to_vec(),
+        });
+    }
+
+    /// Records [`RenderPass::set_pipeline`].
+    pub fn set_pipeline(&mut self, pipeline: &Arc<RenderPipeline>) {
+        self.commands
+            .push(RenderCommand::SetPipeline(Arc::clone(pipeline)));
+    }
+
+    /// Records [`RenderPass::set_blend_constant`].
+    pub fn set_blend_constant(&mut self, color: Color) {
+        self.commands.push(RenderCommand::SetBlendConstant(color));
+    }
+
+    /// Records [`RenderPass::set_index_buffer`] for the `bounds` of `buffer`.
+    pub fn set_index_buffer<S: RangeBounds<BufferAddress>>(
+        &mut self,
+        buffer: &Arc<Buffer>,
+        bounds: S,
+        index_format: IndexFormat,
+    ) {
+        let (offset, size) = range_to_offset_size(bounds);
+        self.commands.push(RenderCommand::SetIndexBuffer {
+            buffer: Arc::clone(buffer),
+            index_format,
+            offset,
+            size,
+        });
+    }
+
+    /// Records [`RenderPass::set_vertex_buffer`] for the `bounds` of `buffer`.
+    pub fn set_vertex_buffer<S: RangeBounds<BufferAddress>>(
+        &mut self,
+        slot: u32,
+        buffer: &Arc<Buffer>,
+        bounds: S,
+    ) {
+        let (offset, size) = range_to_offset_size(bounds);
+        self.commands.push(RenderCommand::SetVertexBuffer {
+            slot,
+            buffer: Arc::clone(buffer),
+            offset,
+            size,
+        });
+    }
+
+    /// Records [`RenderPass::set_scissor_rect`].
+    pub fn set_scissor_rect(&mut self, x: u32, y: u32, width: u32, height: u32) {
+        self.commands.push(RenderCommand::SetScissorRect {
+            x,
+            y,
+            width,
+            height,
+        });
+    }
+
+    /// Records [`RenderPass::set_viewport`].
+    pub fn set_viewport(&mut self, x: f32, y: f32, w: f32, h: f32, min_depth: f32, max_depth: f32) {
+        self.commands.push(RenderCommand::SetViewport {
+            x,
+            y,
+            w,
+            h,
+            min_depth,
+            max_depth,
+        });
+    }
+
+    /// Records [`RenderPass::set_stencil_reference`].
+    pub fn set_stencil_reference(&mut self, reference: u32) {
+        self.commands
+            .push(RenderCommand::SetStencilReference(reference));
+    }
+
+    /// Records [`RenderPass::set_push_constants`].
+    pub fn set_push_constants(&mut self, stages: ShaderStages, offset: u32, data: &[u8]) {
+        self.commands.push(RenderCommand::SetPushConstants {
+            stages,
+            offset,
+            data: data.to_vec(),
+        });
+    }
+
+    /// Records [`RenderPass::draw`].
+    pub fn draw(&mut self, vertices: Range<u32>, instances: Range<u32>) {
+        self.commands.push(RenderCommand::Draw {
+            vertices,
+            instances,
+        });
+    }
+
+    /// Records [`RenderPass::draw_indexed`].
+    pub fn draw_indexed(&mut self, indices: Range<u32>, base_vertex: i32, instances: Range<u32>) {
+        self.commands.push(RenderCommand::DrawIndexed {
+            indices,
+            base_vertex,
+            instances,
+        });
+    }
+
+    /// Records [`RenderPass::draw_indirect`].
+    pub fn draw_indirect(&mut self, indirect_buffer: &Arc<Buffer>, indirect_offset: BufferAddress) {
+        self.commands.push(RenderCommand::DrawIndirect {
+            buffer: Arc::clone(indirect_buffer),
+            offset: indirect_offset,
+        });
+    }
+
+    /// Records [`RenderPass::draw_indexed_indirect`].
+    pub fn draw_indexed_indirect(
+        &mut self,
+        indirect_buffer: &Arc<Buffer>,
+        indirect_offset: BufferAddress,
+    ) {
+        self.commands.push(RenderCommand::DrawIndexedIndirect {
+            buffer: Arc::clone(indirect_buffer),
+            offset: indirect_offset,
+        });
+    }
+
+    /// Records [`RenderPass::multi_draw_indirect`].
+    pub fn multi_draw_indirect(
+        &mut self,
+        indirect_buffer: &Arc<Buffer>,
+        indirect_offset: BufferAddress,
+        count: u32,
+    ) {
+        self.commands.push(RenderCommand::MultiDrawIndirect {
+            buffer: Arc::clone(indirect_buffer),
+            offset: indirect_offset,
+            count,
+        });
+    }
+
+    /// Records [`RenderPass::multi_draw_indexed_indirect`].
+    pub fn multi_draw_indexed_indirect(
+        &mut self,
+        indirect_buffer: &Arc<Buffer>,
+        indirect_offset: BufferAddress,
+        count: u32,
+    ) {
+        self.commands.push(RenderCommand::MultiDrawIndexedIndirect {
+            buffer: Arc::clone(indirect_buffer),
+            offset: indirect_offset,
+            count,
+        });
+    }
+
+    /// Records [`RenderPass::multi_draw_indirect_count`].
+    pub fn multi_draw_indirect_count(
+        &mut self,
+        indirect_buffer: &Arc<Buffer>,
+        indirect_offset: BufferAddress,
+        count_buffer: &Arc<Buffer>,
+        count_offset: BufferAddress,
+        max_count: u32,
+    ) {
+        self.commands.push(RenderCommand::MultiDrawIndirectCount {
+            buffer: Arc::clone(indirect_buffer),
+            offset: indirect_offset,
+            count_buffer: Arc::clone(count_buffer),
+            count_offset,
+            max_count,
+        });
+    }
+
+    /// Records [`RenderPass::multi_draw_indexed_indirect_count`].
+    pub fn multi_draw_indexed_indirect_count(
+        &mut self,
+        indirect_buffer: &Arc<Buffer>,
+        indirect_offset: BufferAddress,
+        count_buffer: &Arc<Buffer>,
+        count_offset: BufferAddress,
+        max_count: u32,
+    ) {
+        self.commands
+            .push(RenderCommand::MultiDrawIndexedIndirectCount {
+                buffer: Arc::clone(indirect_buffer),
+                offset: indirect_offset,
+                count_buffer: Arc::clone(count_buffer),
+                count_offset,
+                max_count,
+            });
+    }
+
+    /// Records [`RenderPass::execute_bundles`].
+    pub fn execute_bundles<'a, I: IntoIterator<Item = &'a Arc<RenderBundle>>>(
+        &mut self,
+        render_bundles: I,
+    ) {
+        self.commands.push(RenderCommand::ExecuteBundles(
+            render_bundles.into_iter().cloned().collect(),
+        ));
+    }
+
+    /// Records [`RenderPass::insert_debug_marker`].
+    pub fn insert_debug_marker(&mut self, label: &str) {
+        self.commands
+            .push(RenderCommand::InsertDebugMarker(label.to_owned()));
+    }
+
+    /// Records [`RenderPass::push_debug_group`].
+    pub fn push_debug_group(&mut self, label: &str) {
+        self.commands
+            .push(RenderCommand::PushDebugGroup(label.to_owned()));
+    }
+
+    /// Records [`RenderPass::pop_debug_group`].
+    pub fn pop_debug_group(&mut self) {
+        self.commands.push(RenderCommand::PopDebugGroup);
+    }
+
+    /// Records [`RenderPass::write_timestamp`].
+    pub fn write_timestamp(&mut self, query_set: &Arc<QuerySet>, query_index: u32) {
+        self.commands.push(RenderCommand::WriteTimestamp {
+            query_set: Arc::clone(query_set),
+            query_index,
+        });
+    }
+
+    /// Records [`RenderPass::begin_occlusion_query`].
+    pub fn begin_occlusion_query(&mut self, query_index: u32) {
+        self.commands
+            .push(RenderCommand::BeginOcclusionQuery(query_index));
+    }
+
+    /// Records [`RenderPass::end_occlusion_query`].
+    pub fn end_occlusion_query(&mut self) {
+        self.commands.push(RenderCommand::EndOcclusionQuery);
+    }
+
+    /// Records [`RenderPass::begin_pipeline_statistics_query`].
+    pub fn begin_pipeline_statistics_query(&mut self, query_set: &Arc<QuerySet>, query_index: u32) {
+        self.commands
+            .push(RenderCommand::BeginPipelineStatisticsQuery {
+                query_set: Arc::clone(query_set),
+                query_index,
+            });
+    }
+
+    /// Records [`RenderPass::end_pipeline_statistics_query`].
+    pub fn end_pipeline_statistics_query(&mut self) {
+        self.commands
+            .push(RenderCommand::EndPipelineStatisticsQuery);
+    }
+
+    /// Replays all recorded commands into `pass`, in order.
+    pub fn replay<'a>(&'a self, pass: &mut RenderPass<'a>) {
+        for command in self.commands.iter() {
+            match *command {
+                RenderCommand::SetBindGroup {
+                    index,
+                    ref bind_group,
+                    ref offsets,
+                } => pass.set_bind_group(index, bind_group, offsets),
+                RenderCommand::SetPipeline(ref pipeline) => pass.set_pipeline(pipeline),
+                RenderCommand::SetBlendConstant(color) => pass.set_blend_constant(color),
+                RenderCommand::SetIndexBuffer {
+                    ref buffer,
+                    index_format,
+                    offset,
+                    size,
+                } => pass.set_index_buffer(
+                    BufferSlice {
+                        buffer,
+                        offset,
+                        size,
+                    },
+                    index_format,
+                ),
+                RenderCommand::SetVertexBuffer {
+                    slot,
+                    ref buffer,
+                    offset,
+                    size,
+                } => pass.set_vertex_buffer(
+                    slot,
+                    BufferSlice {
+                        buffer,
+                        offset,
+                        size,
+                    },
+                ),
+                RenderCommand::SetScissorRect {
+                    x,
+                    y,
+                    width,
+                    height,
+                } => pass.set_scissor_rect(x, y, width, height),
+                RenderCommand::SetViewport {
+                    x,
+                    y,
+                    w,
+                    h,
+                    min_depth,
+                    max_depth,
+                } => pass.set_viewport(x, y, w, h, min_depth, max_depth),
+                RenderCommand::SetStencilReference(reference) => {
+                    pass.set_stencil_reference(reference)
+                }
+                RenderCommand::SetPushConstants {
+                    stages,
+                    offset,
+                    ref data,
+                } => pass.set_push_constants(stages, offset, data),
+                RenderCommand::Draw {
+                    ref vertices,
+                    ref instances,
+                } => pass.draw(vertices.clone(), instances.clone()),
+                RenderCommand::DrawIndexed {
+                    ref indices,
+                    base_vertex,
+                    ref instances,
+                } => pass.draw_indexed(indices.clone(), base_vertex, instances.clone()),
+                RenderCommand::DrawIndirect { ref buffer, offset } => {
+                    pass.draw_indirect(buffer, offset)
+                }
+                RenderCommand::DrawIndexedIndirect { ref buffer, offset } => {
+                    pass.draw_indexed_indirect(buffer, offset)
+                }
+                RenderCommand::MultiDrawIndirect {
+                    ref buffer,
+                    offset,
+                    count,
+                } => pass.multi_draw_indirect(buffer, offset, count),
+                RenderCommand::MultiDrawIndexedIndirect {
+                    ref buffer,
+                    offset,
+                    count,
+                } => pass.multi_draw_indexed_indirect(buffer, offset, count),
+                RenderCommand::MultiDrawIndirectCount {
+                    ref buffer,
+                    offset,
+                    ref count_buffer,
+                    count_offset,
+                    max_count,
+                } => pass.multi_draw_indirect_count(
+                    buffer,
+                    offset,
+                    count_buffer,
+                    count_offset,
+                    max_count,
+                ),
+                RenderCommand::MultiDrawIndexedIndirectCount {
+                    ref buffer,
+                    offset,
+                    ref count_buffer,
+                    count_offset,
+                    max_count,
+                } => pass.multi_draw_indexed_indirect_count(
+                    buffer,
+                    offset,
+                    count_buffer,
+                    count_offset,
+                    max_count,
+                ),
+                RenderCommand::ExecuteBundles(ref bundles) => {
+                    pass.execute_bundles(bundles.iter().map(|bundle| &**bundle))
+                }
+                RenderCommand::InsertDebugMarker(ref label) => pass.insert_debug_marker(label),
+                RenderCommand::PushDebugGroup(ref label) => pass.push_debug_group(label),
+                RenderCommand::PopDebugGroup => pass.pop_debug_group(),
+                RenderCommand::WriteTimestamp {
+                    ref query_set,
+                    query_index,
+                } => pass.write_timestamp(query_set, query_index),
+                RenderCommand::BeginOcclusionQuery(query_index) => {
+                    pass.begin_occlusion_query(query_index)
+                }
+                RenderCommand::EndOcclusionQuery => pass.end_occlusion_query(),
+                RenderCommand::BeginPipelineStatisticsQuery {
+                    ref query_set,
+                    query_index,
+                } => pass.begin_pipeline_statistics_query(query_set, query_index),
+                RenderCommand::EndPipelineStatisticsQuery => pass.end_pipeline_statistics_query(),
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+enum ComputeCommand {
+    SetBindGroup {
+        index: u32,
+        bind_group: Arc<BindGroup>,
+        offsets: Vec<DynamicOffset>,
+    },
+    SetPipeline(Arc<ComputePipeline>),
+    SetPushConstants {
+        offset: u32,
+        data: Vec<u8>,
+    },
+    Dispatch([u32; 3]),
+    DispatchIndirect {
+        buffer: Arc<Buffer>,
+        offset: BufferAddress,
+    },
+    ExecuteBundles(Vec<Arc<ComputeBundle>>),
+    InsertDebugMarker(String),
+    PushDebugGroup(String),
+    PopDebugGroup,
+    WriteTimestamp {
+        query_set: Arc<QuerySet>,
+        query_index: u32,
+    },
+    BeginPipelineStatisticsQuery {
+        query_set: Arc<QuerySet>,
+        query_index: u32,
+    },
+    EndPipelineStatisticsQuery,
+}
+
+/// Compute pass commands recorded ahead of time, independently of any [`CommandEncoder`].
+///
+/// This is the compute counterpart of [`RecordedRenderPass`]. It is replayed with
+/// [`CommandEncoder::replay_compute_pass`], at which point every command is validated
+/// exactly as if it had been recorded into a [`ComputePass`] directly.
+#[derive(Debug, Default)]
+pub struct RecordedComputePass {
+    commands: Vec<ComputeCommand>,
+}
+#[cfg(any(
+    not(target_arch = "wasm32"),
+    all(
+        feature = "fragile-send-sync-non-atomic-wasm",
+        not(target_feature = "atomics")
+    )
+))]
+static_assertions::assert_impl_all!(RecordedComputePass: Send, Sync);
+
+impl RecordedComputePass {
+    /// Creates an empty recorded compute pass.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns `true` if no commands have been recorded.
+    pub fn is_empty(&self) -> bool {
+        self.commands.is_empty()
+    }
+
+    /// Records [`ComputePass::set_bind_group`].
+    pub fn set_bind_group(
+        &mut self,
+        index: u32,
+        bind_group: &Arc<BindGroup>,
+        offsets: &[DynamicOffset],
+    ) {
+        self.commands.push(ComputeCommand::SetBindGroup {
+            index,
+            bind_group: Arc::clone(bind_group),
+            offsets: offsets.to_vec(),
+        });
+    }
+
+    /// Records [`ComputePass::set_pipeline`].
+    pub fn set_pipeline(&mut self, pipeline: &Arc<ComputePipeline>) {
+        self.commands
+            .push(ComputeCommand::SetPipeline(Arc::clone(pipeline)));
+    }
+
+    /// Records [`ComputePass::set_push_constants`].
+    pub fn set_push_constants(&mut self, offset: u32, data: &[u8]) {
+        self.commands.push(ComputeCommand::SetPushConstants {
+            offset,
+            data: data.to_vec(),
+        });
+    }
+
+    /// Records [`ComputePass::dispatch_workgroups`].
+    pub fn dispatch_workgroups(&mut self, x: u32, y: u32, z: u32) {
+        self.commands.push(ComputeCommand::Dispatch([x, y, z]));
+    }
+
+    /// Records [`ComputePass::dispatch_workgroups_indirect`].
+    pub fn dispatch_workgroups_indirect(
+        &mut self,
+        indirect_buffer: &Arc<Buffer>,
+        indirect_offset: BufferAddress,
+    ) {
+        self.commands.push(ComputeCommand::DispatchIndirect {
+            buffer: Arc::clone(indirect_buffer),
+            offset: indirect_offset,
+        });
+    }
+
+    /// Records [`ComputePass::execute_bundles`].
+    pub fn execute_bundles<'a, I: IntoIterator<Item = &'a Arc<ComputeBundle>>>(
+        &mut self,
+        compute_bundles: I,
+    ) {
+        self.commands.push(ComputeCommand::ExecuteBundles(
+            compute_bundles.into_iter().cloned().collect(),
+        ));
+    }
+
+    /// Records [`ComputePass::insert_debug_marker`].
+    pub fn insert_debug_marker(&mut self, label: &str) {
+        self.commands
+            .push(ComputeCommand::InsertDebugMarker(label.to_owned()));
+    }
+
+    /// Records [`ComputePass::push_debug_group`].
+    pub fn push_debug_group(&mut self, label: &str) {
+        self.commands
+            .push(ComputeCommand::PushDebugGroup(label.to_owned()));
+    }
+
+    /// Records [`ComputePass::pop_debug_group`].
+    pub fn pop_debug_group(&mut self) {
+        self.commands.push(ComputeCommand::PopDebugGroup);
+    }
+
+    /// Records [`ComputePass::write_timestamp`].
+    pub fn write_timestamp(&mut self, query_set: &Arc<QuerySet>, query_index: u32) {
+        self.commands.push(ComputeCommand::WriteTimestamp {
+            query_set: Arc::clone(query_set),
+            query_index,
+        });
+    }
+
+    /// Records [`ComputePass::begin_pipeline_statistics_query`].
+    pub fn begin_pipeline_statistics_query(&mut self, query_set: &Arc<QuerySet>, query_index: u32) {
+        self.commands
+            .push(ComputeCommand::BeginPipelineStatisticsQuery {
+                query_set: Arc::clone(query_set),
+                query_index,
+            });
+    }
+
+    /// Records [`ComputePass::end_pipeline_statistics_query`].
+    pub fn end_pipeline_statistics_query(&mut self) {
+        self.commands
+            .push(ComputeCommand::EndPipelineStatisticsQuery);
+    }
+
+    /// Replays all recorded commands into `pass`, in order.
+    pub fn replay<'a>(&'a self, pass: &mut ComputePass<'a>) {
+        for command in self.commands.iter() {
+            match *command {
+                ComputeCommand::SetBindGroup {
+                    index,
+                    ref bind_group,
+                    ref offsets,
+                } => pass.set_bind_group(index, bind_group, offsets),
+                ComputeCommand::SetPipeline(ref pipeline) => pass.set_pipeline(pipeline),
+                ComputeCommand::SetPushConstants { offset, ref data } => {
+                    pass.set_push_constants(offset, data)
+                }
+                ComputeCommand::Dispatch([x, y, z]) => pass.dispatch_workgroups(x, y, z),
+                ComputeCommand::DispatchIndirect { ref buffer, offset } => {
+                    pass.dispatch_workgroups_indirect(buffer, offset)
+                }
+                ComputeCommand::ExecuteBundles(ref bundles) => {
+                    pass.execute_bundles(bundles.iter().map(|bundle| &**bundle))
+                }
+                ComputeCommand::InsertDebugMarker(ref label) => pass.insert_debug_marker(label),
+                ComputeCommand::PushDebugGroup(ref label) => pass.push_debug_group(label),
+                ComputeCommand::PopDebugGroup => pass.pop_debug_group(),
+                ComputeCommand::WriteTimestamp {
+                    ref query_set,
+                    query_index,
+                } => pass.write_timestamp(query_set, query_index),
+                ComputeCommand::BeginPipelineStatisticsQuery {
+                    ref query_set,
+                    query_index,
+                } => pass.begin_pipeline_statistics_query(query_set, query_index),
+                ComputeCommand::EndPipelineStatisticsQuery => pass.end_pipeline_statistics_query(),
+            }
+        }
+    }
+}
+
+impl CommandEncoder {
+    /// Begins a render pass described by `desc` and replays `recorded` into it.
+    ///
+    /// This is equivalent to calling [`CommandEncoder::begin_render_pass`] followed by
+    /// [`RecordedRenderPass::replay`] and ending the pass.
+    pub fn replay_render_pass<'pass>(
+        &'pass mut self,
+        desc: &RenderPassDescriptor<'pass, '_>,
+        recorded: &'pass RecordedRenderPass,
+    ) {
+        let mut pass = self.begin_render_pass(desc);
+        recorded.replay(&mut pass);
+    }
+
+    /// Begins a compute pass described by `desc` and replays `recorded` into it.
+    ///
+    /// This is equivalent to calling [`CommandEncoder::begin_compute_pass`] followed by
+    /// [`RecordedComputePass::replay`] and ending the pass.
+    pub fn replay_compute_pass(
+        &mut self,
+        desc: &ComputePassDescriptor,
+        recorded: &RecordedComputePass,
+    ) {
+        let mut pass = self.begin_compute_pass(desc);
+        recorded.replay(&mut pass);
+    }
+}
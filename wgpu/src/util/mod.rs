@@ -8,6 +8,7 @@ mod device;
 mod encoder;
 mod indirect;
 mod init;
+mod ray_tracing;
 
 use std::sync::Arc;
 use std::{
@@ -21,6 +22,7 @@ pub use device::{BufferInitDescriptor, DeviceExt};
 pub use encoder::RenderEncoder;
 pub use indirect::*;
 pub use init::*;
+pub use ray_tracing::TlasInstance;
 pub use wgt::math::*;
 
 /// Treat the given byte slice as a SPIR-V module.
@@ -109,8 +111,10 @@ impl DownloadBuffer {
             label: None,
         }));
 
-        let mut encoder =
-            device.create_command_encoder(&super::CommandEncoderDescriptor { label: None });
+        let mut encoder = device.create_command_encoder(&super::CommandEncoderDescriptor {
+            label: None,
+            reusable: false,
+        });
         encoder.copy_buffer_to_buffer(buffer.buffer, buffer.offset, &download, 0, size);
         let command_buffer: super::CommandBuffer = encoder.finish();
         queue.submit(Some(command_buffer));
@@ -141,3 +145,52 @@ impl std::ops::Deref for DownloadBuffer {
         self.1.slice()
     }
 }
+
+/// A recommended key for storing [`PipelineCache`]s for the adapter
+/// associated with the given [`AdapterInfo`](wgt::AdapterInfo)
+/// This key will define a class of adapters for which the same cache
+/// might be valid.
+///
+/// If this returns `None`, the adapter doesn't support [`PipelineCache`].
+/// This may be because the API doesn't support application managed caches
+/// (such as browser WebGPU), or that `wgpu` hasn't implemented it for
+/// that API yet.
+///
+/// This key could be used as a filename, as seen in the example below.
+///
+/// # Examples
+///
+/// ``` no_run
+/// # use std::path::PathBuf;
+/// # let adapter_info = todo!();
+/// let cache_dir: PathBuf = PathBuf::new();
+/// let filename = wgpu::util::pipeline_cache_key(&adapter_info);
+/// if let Some(filename) = filename {
+///     let cache_file = cache_dir.join(&filename);
+///     let cache_data = std::fs::read(&cache_file);
+///     let pipeline_cache: wgpu::PipelineCache = todo!("Use data (if present) to create a pipeline cache");
+///
+///     let data = pipeline_cache.get_data();
+///     if let Some(data) = data {
+///         let temp_file = cache_file.with_extension("temp");
+///         std::fs::write(&temp_file, &data)?;
+///         std::fs::rename(&temp_file, &cache_file)?;
+///     }
+/// }
+/// # Ok::<(), std::io::Error>(())
+/// ```
+///
+/// [`PipelineCache`]: super::PipelineCache
+pub fn pipeline_cache_key(adapter_info: &wgt::AdapterInfo) -> Option<String> {
+    match adapter_info.backend {
+        wgt::Backend::Vulkan => Some(format!(
+            // The vendor/device should uniquely define a driver
+            // We/the driver will also later validate that the vendor/device and driver
+            // version match, which may lead to clearing an outdated
+            // cache for the same device.
+            "wgpu_pipeline_cache_vulkan_{}_{}",
+            adapter_info.vendor, adapter_info.device
+        )),
+        _ => None,
+    }
+}
@@ -0,0 +1,51 @@
+/// The structure expected for every instance in the `instance_buffer` of a
+/// [`TlasBuildEntry`](crate::TlasBuildEntry).
+///
+/// This matches `VkAccelerationStructureInstanceKHR`, and is
+/// [`TLAS_INSTANCE_SIZE`](crate::TLAS_INSTANCE_SIZE) bytes large.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Default)]
+pub struct TlasInstance {
+    /// Row major 3x4 matrix transforming the [`Blas`](crate::Blas) into the
+    /// space of the top level acceleration structure.
+    pub transform: [f32; 12],
+    /// The low 24 bits are the custom index returned to shaders, the high 8
+    /// bits the mask tested against the cull mask of ray queries.
+    pub custom_index_and_mask: u32,
+    /// The low 24 bits are the shader binding table offset, the high 8 bits
+    /// are instance flags. Ray queries do not use these.
+    pub shader_binding_table_offset_and_flags: u32,
+    /// The [`Blas::handle`](crate::Blas::handle) of the instanced acceleration
+    /// structure.
+    pub blas_handle: u64,
+}
+
+impl TlasInstance {
+    /// Create an instance of the acceleration structure with the given
+    /// `blas_handle`.
+    ///
+    /// Only the low 24 bits of `custom_index` are kept.
+    pub fn new(blas_handle: u64, transform: [f32; 12], custom_index: u32, mask: u8) -> Self {
+        Self {
+            transform,
+            custom_index_and_mask: (custom_index & 0x00ff_ffff) | (u32::from(mask) << 24),
+            shader_binding_table_offset_and_flags: 0,
+            blas_handle,
+        }
+    }
+
+    /// Returns the bytes representation of the struct, ready to be written in a [`Buffer`](crate::Buffer).
+    pub fn as_bytes(&self) -> &[u8] {
+        unsafe {
+            std::mem::transmute(std::slice::from_raw_parts(
+                self as *const _ as *const u8,
+                std::mem::size_of::<Self>(),
+            ))
+        }
+    }
+}
+
+static_assertions::const_assert_eq!(
+    std::mem::size_of::<TlasInstance>() as u64,
+    wgt::TLAS_INSTANCE_SIZE
+);